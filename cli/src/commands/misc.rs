@@ -1,6 +1,23 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Context;
+use ledger::proofs::provers::BlockProver;
+use ledger::AccountIndex;
 use libp2p_identity::PeerId;
-use node::account::AccountSecretKey;
+use mina_p2p_messages::v2::EpochSeed;
+use node::account::{AccountPublicKey, AccountSecretKey};
+use node::block_producer::vrf_evaluator::SLOTS_PER_EPOCH;
 use node::p2p::identity::SecretKey;
+use node::rpc::RpcBlockProducerScheduleGetResponse;
+use node::snark::{BlockVerifier, TransactionVerifier};
+use node::transition_frontier::genesis::GenesisConfig;
+use openmina_node_native::signer::LocalSigner;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use reqwest::Url;
+use time::format_description::well_known::Rfc3339;
+use vrf::{VrfEvaluationInput, VrfEvaluationOutput};
 
 #[derive(Debug, clap::Args)]
 pub struct Misc {
@@ -13,6 +30,11 @@ impl Misc {
         match self.command {
             MiscCommand::P2PKeyPair(command) => command.run(),
             MiscCommand::MinaKeyPair(command) => command.run(),
+            MiscCommand::NodeStatus(command) => command.run(),
+            MiscCommand::BlockProducerSchedule(command) => command.run(),
+            MiscCommand::ImportPrecomputedBlocks(command) => command.run(),
+            MiscCommand::VrfSchedule(command) => command.run(),
+            MiscCommand::Signer(command) => command.run(),
         }
     }
 }
@@ -21,6 +43,21 @@ impl Misc {
 pub enum MiscCommand {
     P2PKeyPair(P2PKeyPair),
     MinaKeyPair(MinaKeyPair),
+    /// Query the node status of a peer connected to the running node.
+    NodeStatus(NodeStatus),
+    /// List the won slots of the running node's block producer keys in
+    /// the current and next epoch.
+    BlockProducerSchedule(BlockProducerSchedule),
+    /// Apply a directory of precomputed blocks to the node's persisted
+    /// transition frontier, without connecting to any peers.
+    ImportPrecomputedBlocks(ImportPrecomputedBlocks),
+    /// Compute offline the slots won in an epoch by a block producer key
+    /// and its delegators, from the epoch's staking ledger.
+    VrfSchedule(VrfSchedule),
+    /// Hold the block producer keys in a separate process, evaluating the
+    /// VRF and proving blocks for the node started with `--producer-signer`
+    /// over a Unix socket.
+    Signer(Signer),
 }
 
 #[derive(Debug, Clone, clap::Args)]
@@ -60,3 +97,406 @@ impl MinaKeyPair {
         Ok(())
     }
 }
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct NodeStatus {
+    /// HTTP address of the running node.
+    #[arg(long, default_value = "http://127.0.0.1:3000")]
+    node: Url,
+
+    /// Peer to query, must be connected to the node over libp2p.
+    /// Accepts both openmina and libp2p peer id encodings.
+    peer_id: String,
+}
+
+impl NodeStatus {
+    pub fn run(self) -> anyhow::Result<()> {
+        let peer_id = match self.peer_id.parse::<node::p2p::PeerId>() {
+            Ok(peer_id) => peer_id,
+            Err(_) => self.peer_id.parse::<PeerId>()?.try_into()?,
+        };
+        let url = self
+            .node
+            .join(&format!("state/peers/{peer_id}/node-status"))?;
+        let response = reqwest::blocking::get(url)?;
+        let status = response.status();
+        let body = response.text()?;
+        if !status.is_success() {
+            anyhow::bail!("node status query failed ({status}): {body}");
+        }
+        let body: serde_json::Value = serde_json::from_str(&body)?;
+        println!("{}", serde_json::to_string_pretty(&body)?);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct BlockProducerSchedule {
+    /// HTTP address of the running node.
+    #[arg(long, default_value = "http://127.0.0.1:3000")]
+    node: Url,
+
+    /// Print the schedule as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+impl BlockProducerSchedule {
+    pub fn run(self) -> anyhow::Result<()> {
+        let url = self.node.join("block-producer/schedule")?;
+        let response = reqwest::blocking::get(url)?;
+        let status = response.status();
+        let body = response.text()?;
+        if !status.is_success() {
+            anyhow::bail!("block producer schedule query failed ({status}): {body}");
+        }
+        let schedule: RpcBlockProducerScheduleGetResponse = serde_json::from_str(&body)?;
+        let Some(schedule) = schedule else {
+            anyhow::bail!("node isn't producing blocks, or isn't synced yet");
+        };
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&schedule)?);
+            return Ok(());
+        }
+
+        if let (Some(epoch), Some(slot)) = (schedule.current_epoch, schedule.current_global_slot) {
+            println!("current epoch: {epoch}, global slot: {slot}");
+        }
+        println!(
+            "evaluated up to global slot: {}",
+            schedule.latest_evaluated_global_slot
+        );
+        println!("won slots: {}", schedule.won_slots.len());
+        for slot in &schedule.won_slots {
+            let slot_time =
+                time::OffsetDateTime::from_unix_timestamp_nanos(u64::from(slot.slot_time).into())?
+                    .format(&time::format_description::well_known::Rfc3339)?;
+            println!();
+            println!(
+                "epoch {} global slot {} at {slot_time}",
+                slot.epoch, slot.global_slot
+            );
+            println!("  producer:   {}", slot.producer);
+            println!(
+                "  delegator:  {} (index {})",
+                slot.delegator, slot.delegator_index.0
+            );
+            println!("  vrf output: {}", slot.vrf_output);
+            if let Some((value, threshold)) = slot.value_with_threshold {
+                println!("  vrf value:  {value} (threshold {threshold})");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct VrfSchedule {
+    /// Staking ledger JSON file of the epoch. Either a list of accounts,
+    /// as exported by the mina daemon, or a daemon.json ledger object.
+    /// Account indexes are taken from the order of the accounts.
+    #[arg(long)]
+    ledger: PathBuf,
+
+    /// Seed of the epoch.
+    #[arg(long)]
+    epoch_seed: EpochSeed,
+
+    /// Epoch to compute the won slots for.
+    #[arg(long)]
+    epoch: u32,
+
+    /// Block producer key file.
+    #[arg(long)]
+    key: PathBuf,
+
+    /// Password used to decrypt the producer key file.
+    #[arg(env = "MINA_PRIVKEY_PASS", default_value = "")]
+    key_password: String,
+
+    /// Total currency of the epoch, in nanomina. Defaults to the sum of
+    /// the balances in the staking ledger.
+    #[arg(long)]
+    total_currency: Option<u64>,
+
+    /// Genesis timestamp (RFC 3339), to print the times of the won slots.
+    #[arg(long)]
+    genesis_timestamp: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum StakingLedgerFile {
+    Accounts(Vec<node::daemon_json::Account>),
+    Ledger(node::daemon_json::Ledger),
+}
+
+impl VrfSchedule {
+    pub fn run(self) -> anyhow::Result<()> {
+        let key = AccountSecretKey::from_encrypted_file(&self.key, &self.key_password)
+            .context("failed to decrypt secret key file")?;
+        let producer = key.public_key();
+        let keypair: mina_signer::Keypair = key.into();
+
+        let reader =
+            File::open(&self.ledger).context(anyhow::anyhow!("ledger file {:?}", self.ledger))?;
+        let accounts = match serde_json::from_reader(std::io::BufReader::new(reader))
+            .context(anyhow::anyhow!("ledger file {:?}", self.ledger))?
+        {
+            StakingLedgerFile::Accounts(accounts) => accounts,
+            StakingLedgerFile::Ledger(ledger) => ledger.accounts_with_genesis_winner(),
+        };
+
+        let (delegator_table, ledger_currency) = delegator_table(&accounts, &producer)?;
+        let total_currency = self.total_currency.unwrap_or(ledger_currency);
+        let delegated_stake = delegator_table
+            .iter()
+            .fold(0u64, |sum, (_, _, balance)| sum.saturating_add(*balance));
+
+        println!("producer:        {producer}");
+        println!("delegators:      {}", delegator_table.len());
+        println!("delegated stake: {delegated_stake}");
+        println!("total currency:  {total_currency}");
+
+        let genesis_timestamp = self
+            .genesis_timestamp
+            .map(|timestamp| time::OffsetDateTime::parse(&timestamp, &Rfc3339))
+            .transpose()
+            .context("invalid genesis timestamp")?;
+        let slot_duration = time::Duration::milliseconds(
+            openmina_core::constants::constraint_constants().block_window_duration_ms as i64,
+        );
+
+        let epoch_start = self
+            .epoch
+            .checked_mul(SLOTS_PER_EPOCH)
+            .context("epoch is too big")?;
+        let epoch_end = epoch_start
+            .checked_add(SLOTS_PER_EPOCH)
+            .context("epoch is too big")?;
+        let won_slots = (epoch_start..epoch_end)
+            .into_par_iter()
+            .map(|global_slot| {
+                for (index, delegator, balance) in &delegator_table {
+                    let vrf_input = VrfEvaluationInput {
+                        producer_key: keypair.clone(),
+                        global_slot,
+                        epoch_seed: self.epoch_seed.clone(),
+                        account_pub_key: delegator.clone(),
+                        delegator_index: *index,
+                        delegated_stake: (*balance).into(),
+                        total_currency: total_currency.into(),
+                    };
+                    // the first delegate that won the slot
+                    if let VrfEvaluationOutput::SlotWon(won_slot) = vrf::evaluate_vrf(vrf_input)? {
+                        return Ok(Some(won_slot));
+                    }
+                }
+                Ok(None)
+            })
+            .collect::<Result<Vec<_>, vrf::VrfError>>()?;
+
+        let won_slots = won_slots.into_iter().flatten().collect::<Vec<_>>();
+        println!("won slots:       {}", won_slots.len());
+        for won_slot in won_slots {
+            println!();
+            let slot = won_slot.global_slot;
+            match genesis_timestamp {
+                Some(genesis_timestamp) => {
+                    let slot_time = genesis_timestamp + slot_duration * slot;
+                    println!("global slot {slot} at {}", slot_time.format(&Rfc3339)?);
+                }
+                None => println!("global slot {slot}"),
+            }
+            println!(
+                "  delegator:  {} (index {})",
+                won_slot.winner_account, won_slot.account_index.0
+            );
+            println!("  vrf output: {}", won_slot.vrf_output);
+            if let Some((value, threshold)) = won_slot.value_with_threshold {
+                println!("  vrf value:  {value} (threshold {threshold})");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Accounts delegating to `producer`, with their index in the staking
+/// ledger and their balance, along with the total balance of the ledger.
+fn delegator_table(
+    accounts: &[node::daemon_json::Account],
+    producer: &AccountPublicKey,
+) -> anyhow::Result<(Vec<(AccountIndex, AccountPublicKey, u64)>, u64)> {
+    let mut delegator_table = Vec::new();
+    let mut ledger_currency = 0u64;
+    for (index, account) in accounts.iter().enumerate() {
+        if !account.token_id()?.is_default() {
+            continue;
+        }
+        let balance = account.balance().as_u64();
+        ledger_currency = ledger_currency.saturating_add(balance);
+        if account.delegate()?.as_ref() == Some(producer) {
+            delegator_table.push((AccountIndex(index as u64), account.public_key()?, balance));
+        }
+    }
+    Ok((delegator_table, ledger_currency))
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct ImportPrecomputedBlocks {
+    #[arg(
+        long,
+        short = 'd',
+        default_value = "~/.openmina",
+        env = "OPENMINA_HOME"
+    )]
+    work_dir: String,
+
+    /// Config JSON file of the node.
+    #[arg(short = 'c', long, env)]
+    config: Option<PathBuf>,
+
+    /// Skip the verification of the blocks' transactions and snark works,
+    /// for dumps coming from a trusted source.
+    #[arg(long)]
+    skip_verification: bool,
+
+    /// Directory with the precomputed block JSON files.
+    blocks_dir: PathBuf,
+}
+
+impl ImportPrecomputedBlocks {
+    pub fn run(self) -> anyhow::Result<()> {
+        openmina_node_native::tracing::initialize(tracing::Level::INFO);
+
+        let work_dir = shellexpand::full(&self.work_dir)?.into_owned();
+        let genesis_config = match self.config {
+            Some(config) => {
+                let reader =
+                    File::open(&config).context(anyhow::anyhow!("config file {config:?}"))?;
+                let config: node::daemon_json::DaemonJson = serde_json::from_reader(reader)
+                    .context(anyhow::anyhow!("config file {config:?}"))?;
+                Arc::new(GenesisConfig::DaemonJson(Box::new(config)))
+            }
+            None => node::config::DEVNET_CONFIG.clone(),
+        };
+
+        let best_tip = openmina_node_native::precomputed_blocks::import_precomputed_blocks(
+            &genesis_config,
+            &self.blocks_dir,
+            &PathBuf::from(work_dir).join("frontier"),
+            self.skip_verification,
+        )?;
+        match best_tip {
+            Some(best_tip) => println!(
+                "imported blocks up to {} ({})",
+                best_tip.height(),
+                best_tip.hash()
+            ),
+            None => println!("no new blocks to import"),
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, clap::Args)]
+pub struct Signer {
+    /// Unix socket to listen on.
+    #[arg(long)]
+    socket: PathBuf,
+
+    /// Block producer key file. Can be passed multiple times.
+    #[arg(long, env, value_delimiter = ',', required = true)]
+    producer_key: Vec<PathBuf>,
+
+    /// Password used to decrypt the producer key files.
+    #[arg(env = "MINA_PRIVKEY_PASS", default_value = "")]
+    producer_key_password: String,
+}
+
+impl Signer {
+    pub fn run(self) -> anyhow::Result<()> {
+        openmina_node_native::tracing::initialize(tracing::Level::INFO);
+
+        let keys = self
+            .producer_key
+            .iter()
+            .map(|path| {
+                AccountSecretKey::from_encrypted_file(path, &self.producer_key_password).context(
+                    anyhow::anyhow!("failed to decrypt secret key file {path:?}"),
+                )
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        for key in &keys {
+            println!("serving key: {}", key.public_key());
+        }
+
+        // Block proofs wait for the provers made here.
+        openmina_core::thread::spawn(|| {
+            node::core::info!(node::core::log::system_time(); summary = "loading provers index");
+            BlockProver::make(
+                Some(BlockVerifier::make()),
+                Some(TransactionVerifier::make()),
+            );
+            node::core::info!(node::core::log::system_time(); summary = "loaded provers index");
+        });
+
+        openmina_node_native::signer::serve(&self.socket, LocalSigner::new(keys))
+            .context(anyhow::anyhow!("signer socket {:?}", self.socket))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use node::daemon_json::Account;
+
+    use super::*;
+
+    fn key(i: u64) -> AccountPublicKey {
+        AccountSecretKey::deterministic(i).public_key()
+    }
+
+    fn account(i: u64, balance: &str, delegate: Option<u64>) -> Account {
+        Account::new(
+            key(i).to_string(),
+            balance.to_owned(),
+            delegate.map(|i| key(i).to_string()),
+        )
+    }
+
+    #[test]
+    fn delegator_table_from_staking_ledger() {
+        let token_account: Account = serde_json::from_value(serde_json::json!({
+            "pk": key(3).to_string(),
+            "balance": "7",
+            "token_id": "2",
+        }))
+        .unwrap();
+        let accounts = vec![
+            // not delegated, so it delegates to itself
+            account(0, "100", None),
+            account(1, "50", Some(0)),
+            account(2, "20", Some(1)),
+            token_account,
+            account(4, "30.5", Some(0)),
+        ];
+
+        let (delegator_table, ledger_currency) = delegator_table(&accounts, &key(0)).unwrap();
+        assert_eq!(
+            delegator_table,
+            vec![
+                (AccountIndex(0), key(0), 100_000_000_000),
+                (AccountIndex(1), key(1), 50_000_000_000),
+                (AccountIndex(4), key(4), 30_500_000_000),
+            ]
+        );
+        assert_eq!(ledger_currency, 200_500_000_000);
+
+        let (other_table, _) = super::delegator_table(&accounts, &key(5)).unwrap();
+        assert!(other_table.is_empty());
+    }
+}
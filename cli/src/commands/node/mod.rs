@@ -4,6 +4,7 @@ use anyhow::Context;
 use ledger::proofs::provers::BlockProver;
 use node::{
     account::AccountSecretKey,
+    block_producer::TransactionSelectionPolicyConfig,
     snark::{BlockVerifier, TransactionVerifier},
     transition_frontier::genesis::GenesisConfig,
 };
@@ -14,6 +15,7 @@ use reqwest::Url;
 use node::core::log::inner::Level;
 use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
 use node::p2p::identity::SecretKey;
+use node::p2p::service_impl::bandwidth::P2pBandwidthLimits;
 use node::service::Recorder;
 use node::SnarkerStrategy;
 
@@ -56,6 +58,10 @@ pub struct Node {
     #[arg(long, env, default_value = "8302")]
     pub libp2p_port: u16,
 
+    /// LibP2P UDP port to listen on for QUIC connections. QUIC is disabled if not set
+    #[arg(long, env)]
+    pub libp2p_quic_port: Option<u16>,
+
     /// Verbosity level (options: trace, debug, info, warn, error)
     #[arg(long, short, env, default_value = "info")]
     pub verbosity: Level,
@@ -90,6 +96,22 @@ pub struct Node {
     #[arg(long, default_value = "100")]
     pub max_peers: usize,
 
+    /// Maximum bytes per second received from a single peer.
+    #[arg(long, env)]
+    pub peer_inbound_bandwidth: Option<u64>,
+
+    /// Maximum bytes per second sent to a single peer.
+    #[arg(long, env)]
+    pub peer_outbound_bandwidth: Option<u64>,
+
+    /// Maximum bytes per second received from all peers.
+    #[arg(long, env)]
+    pub inbound_bandwidth: Option<u64>,
+
+    /// Maximum bytes per second sent to all peers.
+    #[arg(long, env)]
+    pub outbound_bandwidth: Option<u64>,
+
     /// Run the node in seed mode. No default peers will be added.
     #[arg(long, env)]
     pub seed: bool,
@@ -107,11 +129,19 @@ pub struct Node {
     #[arg(long, env, default_value = "seq", requires = "snarker")]
     pub snarker_strategy: SnarkerStrategy,
 
-    /// Enable block producer with this key file
+    /// Enable block producer with this key file. Can be passed multiple
+    /// times to produce blocks with multiple keys.
     ///
-    /// MINA_PRIVKEY_PASS must be set to decrypt the keyfile if it is password-protected
-    #[arg(long, env, group = "producer")]
-    pub producer_key: Option<PathBuf>,
+    /// MINA_PRIVKEY_PASS must be set to decrypt the keyfiles if they are password-protected
+    #[arg(long, env, value_delimiter = ',', group = "producer")]
+    pub producer_key: Vec<PathBuf>,
+
+    /// Unix socket of the external signer holding the block producer keys.
+    ///
+    /// The signer evaluates the VRF and proves the blocks, so the keys
+    /// never enter the node process.
+    #[arg(long, env, conflicts_with = "producer_key")]
+    pub producer_signer: Option<PathBuf>,
 
     /// Password used to decrypt the producer key file.
     #[arg(env = "MINA_PRIVKEY_PASS", default_value = "")]
@@ -121,10 +151,21 @@ pub struct Node {
     /// If not provided, coinbase rewards will be sent to the producer
     /// of a block.
     ///
+    /// With multiple `--producer-key`, the n-th receiver is used for
+    /// the n-th key.
+    ///
     /// Warning: If the key is from a zkApp account, the account's
     /// receive permission must be None.
     #[arg(long, requires = "producer")]
-    pub coinbase_receiver: Option<AccountPublicKey>,
+    pub coinbase_receiver: Vec<AccountPublicKey>,
+
+    /// Policy for picking the transactions of the produced blocks. Can be
+    /// passed multiple times, the policies are applied in order.
+    ///
+    /// One of: `max-fee` (default), `fifo`, `allow=<PK>[,<PK>..]`,
+    /// `deny=<PK>[,<PK>..]`, `max-zkapp-commands=<N>`, `min-fee=<NANOMINA>`.
+    #[arg(long, env, requires = "producer")]
+    pub transaction_selection: Vec<TransactionSelectionPolicyConfig>,
 
     #[arg(long, default_value = "none", env)]
     pub record: String,
@@ -133,6 +174,44 @@ pub struct Node {
     #[arg(long)]
     pub no_peers_discovery: bool,
 
+    /// Do not persist the transition frontier and ledgers, so that the
+    /// node syncs from scratch on every start.
+    #[arg(long, env)]
+    pub no_frontier_persistence: bool,
+
+    /// Store the accounts of snarked ledgers on disk, in the `ledgers`
+    /// directory of the work dir, instead of memory. Uses less memory at
+    /// the cost of slower ledger access.
+    #[arg(long, env)]
+    pub ledger_accounts_on_disk: bool,
+
+    /// Write every applied block into the archive database at this url,
+    /// using the Mina archive schema.
+    ///
+    /// Example: sqlite://archive.db?mode=rwc or postgres://localhost/archive
+    #[arg(long, env)]
+    pub archive_url: Option<String>,
+
+    /// Write the blocks added to the best chain into this directory, as
+    /// precomputed block JSON files.
+    #[arg(long, env)]
+    pub precomputed_blocks_dir: Option<PathBuf>,
+
+    /// Append node events (applied blocks, best tip changes, included
+    /// transactions, added snark work, produced blocks and missed slots)
+    /// to this file, one JSON object per line.
+    #[arg(long, env)]
+    pub event_log: Option<PathBuf>,
+
+    /// POST node events as JSON to this url.
+    #[arg(long, env)]
+    pub event_webhook: Option<String>,
+
+    /// Stream node events from the `/events` Server-Sent Events endpoint
+    /// of the http server.
+    #[arg(long, env)]
+    pub event_sse: bool,
+
     /// Config JSON file to load at startup.
     // TODO: make this argument required.
     #[arg(short = 'c', long, env)]
@@ -226,6 +305,9 @@ impl Node {
         }
 
         node_builder.p2p_libp2p_port(self.libp2p_port);
+        if let Some(port) = self.libp2p_quic_port {
+            node_builder.p2p_libp2p_quic_port(port);
+        }
 
         node_builder.external_addrs(
             self.libp2p_external_ip
@@ -234,6 +316,12 @@ impl Node {
         );
 
         node_builder.p2p_max_peers(self.max_peers);
+        node_builder.p2p_bandwidth_limits(P2pBandwidthLimits {
+            peer_inbound: self.peer_inbound_bandwidth,
+            peer_outbound: self.peer_outbound_bandwidth,
+            global_inbound: self.inbound_bandwidth,
+            global_outbound: self.outbound_bandwidth,
+        });
         self.seed.then(|| node_builder.p2p_seed_node());
         self.no_peers_discovery
             .then(|| node_builder.p2p_no_discovery());
@@ -252,28 +340,76 @@ impl Node {
             .block_verifier_index(block_verifier_index.clone())
             .work_verifier_index(work_verifier_index.clone());
 
-        if let Some(producer_key_path) = self.producer_key {
-            let password = &self.producer_key_password;
+        if self.coinbase_receiver.len() > self.producer_key.len() {
+            anyhow::bail!(
+                "more `--coinbase-receiver` ({}) than `--producer-key` ({}) given",
+                self.coinbase_receiver.len(),
+                self.producer_key.len()
+            );
+        }
+
+        if !self.producer_key.is_empty() {
             openmina_core::thread::spawn(|| {
                 node::core::info!(node::core::log::system_time(); summary = "loading provers index");
                 BlockProver::make(Some(block_verifier_index), Some(work_verifier_index));
                 node::core::info!(node::core::log::system_time(); summary = "loaded provers index");
             });
+        }
+
+        if let Some(path) = self.producer_signer {
+            node_builder.block_producer_signer(path)?;
+        }
+
+        let password = &self.producer_key_password;
+        let mut coinbase_receivers = self.coinbase_receiver.into_iter();
+        for producer_key_path in self.producer_key {
             node_builder.block_producer_from_file(producer_key_path, password, None)?;
 
-            if let Some(pub_key) = self.coinbase_receiver {
+            if let Some(pub_key) = coinbase_receivers.next() {
                 node_builder
                     .custom_coinbase_receiver(pub_key.into())
                     .unwrap();
             }
         }
 
+        if !self.transaction_selection.is_empty() {
+            node_builder.block_producer_transaction_selection(self.transaction_selection)?;
+        }
+
         if let Some(sec_key) = self.run_snarker {
             node_builder.snarker(sec_key, self.snarker_fee, self.snarker_strategy);
         }
 
         openmina_core::set_work_dir(work_dir.clone().into());
 
+        std::fs::create_dir_all(&work_dir)
+            .context(anyhow::anyhow!("creating work dir {work_dir}"))?;
+        node_builder.p2p_ban_list_file(PathBuf::from(&work_dir).join("p2p_bans.json"))?;
+        node_builder.p2p_peer_store_file(PathBuf::from(&work_dir).join("p2p_peers.json"))?;
+        if !self.no_frontier_persistence {
+            node_builder
+                .transition_frontier_storage_dir(PathBuf::from(&work_dir).join("frontier"))?;
+        }
+        if self.ledger_accounts_on_disk {
+            node_builder.ledger_accounts_dir(PathBuf::from(&work_dir).join("ledgers"))?;
+        }
+
+        if let Some(url) = &self.archive_url {
+            node_builder.archive(url)?;
+        }
+        if let Some(dir) = &self.precomputed_blocks_dir {
+            node_builder.precomputed_blocks_dir(dir);
+        }
+        if let Some(path) = &self.event_log {
+            node_builder.event_log(path)?;
+        }
+        if let Some(url) = &self.event_webhook {
+            node_builder.event_webhook(url.clone());
+        }
+        if self.event_sse {
+            node_builder.event_sse();
+        }
+
         node_builder
             .http_server(self.port)
             .gather_stats()
@@ -18,7 +18,7 @@ use crate::{
 
 use crate::HashesMatrix;
 
-use super::database_impl::DatabaseImpl;
+use super::{database_impl::DatabaseImpl, database_ondisk::OnDiskConfig};
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum DatabaseError {
@@ -61,6 +61,21 @@ impl Database<V2> {
         Self::create_with_dir(depth, None)
     }
 
+    /// Creates a database storing its accounts on disk, in `directory`,
+    /// to use less memory at the cost of slower access.
+    /// If the directory contains accounts already, they are loaded.
+    pub fn create_ondisk(
+        depth: u8,
+        directory: PathBuf,
+        config: &OnDiskConfig,
+    ) -> std::io::Result<Self> {
+        let db = DatabaseImpl::<V2>::create_ondisk(depth, directory, config)?;
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(db)),
+        })
+    }
+
     pub fn root_hash(&mut self) -> Fp {
         self.with(|this| this.root_hash())
     }
@@ -78,11 +93,18 @@ impl Database<V2> {
         self.with(|this| this.make_checkpoint(directory_name))
     }
 
-    pub fn clone_db(&self, directory_name: PathBuf) -> Self {
-        let db = self.with(|this| this.clone_db(directory_name));
-        Self {
+    pub fn clone_db(&self, directory_name: PathBuf) -> std::io::Result<Self> {
+        let db = self.with(|this| this.clone_db(directory_name))?;
+        Ok(Self {
             inner: Arc::new(Mutex::new(db)),
-        }
+        })
+    }
+
+    /// Writes the accounts modified since the last write to disk, then
+    /// returns the first error reading them since the last call, if any.
+    /// No-op for a database in memory.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.with(|this| this.flush())
     }
 
     pub fn get_cached_hash(&self, addr: &Address) -> Option<Fp> {
@@ -280,7 +302,42 @@ impl BaseLedger for Database<V2> {
     }
 
     fn commit(&mut self) {
-        // no-op
+        self.with(|this| this.commit())
+    }
+}
+
+/// Backends of [`Database`], tests run against each of them.
+#[cfg(test)]
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum TestBackend {
+    InMemory,
+    #[cfg(not(target_family = "wasm"))]
+    OnDisk,
+}
+
+#[cfg(test)]
+impl TestBackend {
+    #[cfg(not(target_family = "wasm"))]
+    pub const ALL: [Self; 2] = [Self::InMemory, Self::OnDisk];
+    #[cfg(target_family = "wasm")]
+    pub const ALL: [Self; 1] = [Self::InMemory];
+
+    pub fn create(self, depth: u8) -> Database<V2> {
+        match self {
+            Self::InMemory => Database::create(depth),
+            #[cfg(not(target_family = "wasm"))]
+            Self::OnDisk => {
+                let directory = std::env::temp_dir().join(format!("minadb-{}", crate::next_uuid()));
+                // small values, to go through the write buffer and the
+                // recomputed hashes
+                let config = OnDiskConfig {
+                    hashes_min_height: 2,
+                    write_buffer_len: 16,
+                    temporary: true,
+                };
+                Database::create_ondisk(depth, directory, &config).unwrap()
+            }
+        }
     }
 }
 
@@ -356,28 +413,30 @@ mod tests {
 
     #[test]
     fn test_db_v2() {
-        let two: usize = 2;
+        for backend in TestBackend::ALL {
+            let two: usize = 2;
 
-        for depth in 2..15 {
-            let mut db = Database::<V2>::create(depth);
+            for depth in 2..15 {
+                let mut db = backend.create(depth);
 
-            for _ in 0..two.pow(depth as u32) {
-                let account = Account::rand();
-                let id = account.id();
-                db.get_or_create_account(id, account).unwrap();
-            }
+                for _ in 0..two.pow(depth as u32) {
+                    let account = Account::rand();
+                    let id = account.id();
+                    db.get_or_create_account(id, account).unwrap();
+                }
 
-            let naccounts = db.naccounts();
-            assert_eq!(naccounts, two.pow(depth as u32));
+                let naccounts = db.naccounts();
+                assert_eq!(naccounts, two.pow(depth as u32));
 
-            let account = Account::create();
-            let id = account.id();
-            assert_eq!(
-                db.get_or_create_account(id, account).unwrap_err(),
-                DatabaseError::OutOfLeaves
-            );
+                let account = Account::create();
+                let id = account.id();
+                assert_eq!(
+                    db.get_or_create_account(id, account).unwrap_err(),
+                    DatabaseError::OutOfLeaves
+                );
 
-            elog!("depth={:?} naccounts={:?}", depth, naccounts);
+                elog!("depth={:?} naccounts={:?}", depth, naccounts);
+            }
         }
     }
 
@@ -496,27 +555,29 @@ export function performance_now() {
     #[cfg(not(target_family = "wasm"))]
     #[test]
     fn test_hashing_tree() {
-        const NACCOUNTS: u64 = 1_000;
+        for backend in TestBackend::ALL {
+            const NACCOUNTS: u64 = 1_000;
 
-        let now = redux::Instant::now();
-        let mut db = Database::<V2>::create(20);
+            let now = redux::Instant::now();
+            let mut db = backend.create(20);
 
-        elog!("{:?} accounts natively", NACCOUNTS);
+            elog!("{:?} accounts natively", NACCOUNTS);
 
-        let accounts = (0..NACCOUNTS).map(|_| Account::rand()).collect::<Vec<_>>();
+            let accounts = (0..NACCOUNTS).map(|_| Account::rand()).collect::<Vec<_>>();
 
-        for (index, mut account) in accounts.into_iter().enumerate() {
-            account.token_id = TokenId::from(index as u64);
-            let id = account.id();
-            db.get_or_create_account(id, account).unwrap();
-        }
+            for (index, mut account) in accounts.into_iter().enumerate() {
+                account.token_id = TokenId::from(index as u64);
+                let id = account.id();
+                db.get_or_create_account(id, account).unwrap();
+            }
 
-        elog!("generate random accounts {:?}", now.elapsed());
-        assert_eq!(db.naccounts(), NACCOUNTS as usize);
+            elog!("generate random accounts {:?}", now.elapsed());
+            assert_eq!(db.naccounts(), NACCOUNTS as usize);
 
-        let now = redux::Instant::now();
-        db.merkle_root();
-        elog!("compute merkle root {:?}", now.elapsed());
+            let now = redux::Instant::now();
+            db.merkle_root();
+            elog!("compute merkle root {:?}", now.elapsed());
+        }
     }
 
     #[test]
@@ -619,34 +680,36 @@ export function performance_now() {
     /// Accounts inserted in a different order produce different root hash
     #[test]
     fn test_root_hash_different_orders() {
-        let mut db = Database::<V2>::create(4);
+        for backend in TestBackend::ALL {
+            let mut db = backend.create(4);
 
-        let accounts = (0..16).map(|_| Account::rand()).collect::<Vec<_>>();
+            let accounts = (0..16).map(|_| Account::rand()).collect::<Vec<_>>();
 
-        for account in &accounts {
-            db.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
-        let root_hash_1 = db.merkle_root();
+            for account in &accounts {
+                db.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
+            let root_hash_1 = db.merkle_root();
 
-        let mut db = Database::<V2>::create(4);
-        for account in accounts.iter().rev() {
-            db.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
-        let root_hash_2 = db.merkle_root();
+            let mut db = backend.create(4);
+            for account in accounts.iter().rev() {
+                db.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
+            let root_hash_2 = db.merkle_root();
 
-        // Different orders, different root hash
-        assert_ne!(root_hash_1, root_hash_2);
+            // Different orders, different root hash
+            assert_ne!(root_hash_1, root_hash_2);
 
-        let mut db = Database::<V2>::create(4);
-        for account in accounts {
-            db.get_or_create_account(account.id(), account).unwrap();
-        }
-        let root_hash_3 = db.merkle_root();
+            let mut db = backend.create(4);
+            for account in accounts {
+                db.get_or_create_account(account.id(), account).unwrap();
+            }
+            let root_hash_3 = db.merkle_root();
 
-        // Same orders, same root hash
-        assert_eq!(root_hash_1, root_hash_3);
+            // Same orders, same root hash
+            assert_eq!(root_hash_1, root_hash_3);
+        }
     }
 
     // /// An empty tree produces the same hash than a tree full of empty accounts
@@ -702,100 +765,110 @@ mod tests_ocaml {
     // "add and retrieve an account"
     #[test]
     fn test_add_retrieve_account() {
-        let mut db = Database::<V2>::create(4);
+        for backend in TestBackend::ALL {
+            let mut db = backend.create(4);
 
-        let account = Account::rand();
-        let location = db
-            .get_or_create_account(account.id(), account.clone())
-            .unwrap();
-        let get_account = db.get(location.addr()).unwrap();
+            let account = Account::rand();
+            let location = db
+                .get_or_create_account(account.id(), account.clone())
+                .unwrap();
+            let get_account = db.get(location.addr()).unwrap();
 
-        assert_eq!(account, *get_account);
+            assert_eq!(account, *get_account);
+        }
     }
 
     // "accounts are atomic"
     #[test]
     fn test_accounts_are_atomic() {
-        let mut db = Database::<V2>::create(4);
+        for backend in TestBackend::ALL {
+            let mut db = backend.create(4);
 
-        let account = Box::new(Account::rand());
-        let location: Address = db
-            .get_or_create_account(account.id(), *account.clone())
-            .unwrap()
-            .addr();
+            let account = Box::new(Account::rand());
+            let location: Address = db
+                .get_or_create_account(account.id(), *account.clone())
+                .unwrap()
+                .addr();
 
-        db.set(location.clone(), account.clone());
-        let loc = db.location_of_account(&account.id()).unwrap();
+            db.set(location.clone(), account.clone());
+            let loc = db.location_of_account(&account.id()).unwrap();
 
-        assert_eq!(location, loc);
-        assert_eq!(db.get(location), db.get(loc));
+            assert_eq!(location, loc);
+            assert_eq!(db.get(location), db.get(loc));
+        }
     }
 
     // "length"
     #[test]
     fn test_lengths() {
-        for naccounts in 50..100 {
-            let mut db = Database::<V2>::create(10);
-            let mut unique = HashSet::with_capacity(naccounts);
-
-            for _ in 0..naccounts {
-                let account = loop {
-                    let account = Account::rand();
-                    if unique.insert(account.id()) {
-                        break account;
-                    }
-                };
-
-                db.get_or_create_account(account.id(), account).unwrap();
+        for backend in TestBackend::ALL {
+            for naccounts in 50..100 {
+                let mut db = backend.create(10);
+                let mut unique = HashSet::with_capacity(naccounts);
+
+                for _ in 0..naccounts {
+                    let account = loop {
+                        let account = Account::rand();
+                        if unique.insert(account.id()) {
+                            break account;
+                        }
+                    };
+
+                    db.get_or_create_account(account.id(), account).unwrap();
+                }
+
+                assert_eq!(db.num_accounts(), naccounts);
             }
-
-            assert_eq!(db.num_accounts(), naccounts);
         }
     }
 
     // "get_or_create_acount does not update an account if key already""
     #[test]
     fn test_no_update_if_exist() {
-        let mut db = Database::<V2>::create(10);
+        for backend in TestBackend::ALL {
+            let mut db = backend.create(10);
 
-        let mut account1 = Account::rand();
-        account1.balance = Balance::from_u64(100);
+            let mut account1 = Account::rand();
+            account1.balance = Balance::from_u64(100);
 
-        let location1 = db
-            .get_or_create_account(account1.id(), account1.clone())
-            .unwrap();
+            let location1 = db
+                .get_or_create_account(account1.id(), account1.clone())
+                .unwrap();
 
-        let mut account2 = account1;
-        account2.balance = Balance::from_u64(200);
+            let mut account2 = account1;
+            account2.balance = Balance::from_u64(200);
 
-        let location2 = db
-            .get_or_create_account(account2.id(), account2.clone())
-            .unwrap();
+            let location2 = db
+                .get_or_create_account(account2.id(), account2.clone())
+                .unwrap();
 
-        let addr1: Address = location1.clone().addr();
-        let addr2: Address = location2.clone().addr();
+            let addr1: Address = location1.clone().addr();
+            let addr2: Address = location2.clone().addr();
 
-        assert_eq!(addr1, addr2);
-        assert!(matches!(location2, GetOrCreated::Existed(_)));
-        assert_ne!(*db.get(location1.addr()).unwrap(), account2);
+            assert_eq!(addr1, addr2);
+            assert!(matches!(location2, GetOrCreated::Existed(_)));
+            assert_ne!(*db.get(location1.addr()).unwrap(), account2);
+        }
     }
 
     // "get_or_create_account t account = location_of_account account.key"
     #[test]
     fn test_location_of_account() {
-        for naccounts in 50..100 {
-            let mut db = Database::<V2>::create(10);
+        for backend in TestBackend::ALL {
+            for naccounts in 50..100 {
+                let mut db = backend.create(10);
 
-            for _ in 0..naccounts {
-                let account = Account::rand();
+                for _ in 0..naccounts {
+                    let account = Account::rand();
 
-                let account_id = account.id();
-                let location = db
-                    .get_or_create_account(account_id.clone(), account)
-                    .unwrap();
-                let addr: Address = location.addr();
+                    let account_id = account.id();
+                    let location = db
+                        .get_or_create_account(account_id.clone(), account)
+                        .unwrap();
+                    let addr: Address = location.addr();
 
-                assert_eq!(addr, db.location_of_account(&account_id).unwrap());
+                    assert_eq!(addr, db.location_of_account(&account_id).unwrap());
+                }
             }
         }
     }
@@ -807,8 +880,8 @@ mod tests_ocaml {
         // TODO
     }
 
-    fn create_full_db(depth: usize) -> Database<V2> {
-        let mut db = Database::<V2>::create(depth as u8);
+    fn create_full_db(backend: TestBackend, depth: usize) -> Database<V2> {
+        let mut db = backend.create(depth as u8);
 
         for _ in 0..2u64.pow(depth as u32) {
             let account = Account::rand();
@@ -822,41 +895,45 @@ mod tests_ocaml {
     //  get_inner_hash_at_addr_exn(address) = hash"
     #[test]
     fn test_get_set_all_same_root_hash() {
-        let mut db = create_full_db(7);
+        for backend in TestBackend::ALL {
+            let mut db = create_full_db(backend, 7);
 
-        let merkle_root1 = db.merkle_root();
-        let root = Address::root();
+            let merkle_root1 = db.merkle_root();
+            let root = Address::root();
 
-        let accounts = db.get_all_accounts_rooted_at(root.clone()).unwrap();
-        let accounts = accounts.into_iter().map(|acc| acc.1).collect::<Vec<_>>();
-        db.set_all_accounts_rooted_at(root, &accounts).unwrap();
+            let accounts = db.get_all_accounts_rooted_at(root.clone()).unwrap();
+            let accounts = accounts.into_iter().map(|acc| acc.1).collect::<Vec<_>>();
+            db.set_all_accounts_rooted_at(root, &accounts).unwrap();
 
-        let merkle_root2 = db.merkle_root();
+            let merkle_root2 = db.merkle_root();
 
-        assert_eq!(merkle_root1, merkle_root2);
+            assert_eq!(merkle_root1, merkle_root2);
+        }
     }
 
     // "set_inner_hash_at_addr_exn(address,hash);
     //  get_inner_hash_at_addr_exn(address) = hash"
     #[test]
     fn test_set_batch_accounts_change_root_hash() {
-        const DEPTH: usize = 7;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 7;
 
-        for _ in 0..5 {
-            let mut db = create_full_db(DEPTH);
+            for _ in 0..5 {
+                let mut db = create_full_db(backend, DEPTH);
 
-            let addr = Address::rand_nonleaf(DEPTH);
-            let children = addr.iter_children(DEPTH);
-            let accounts = children
-                .map(|addr| (addr, Box::new(Account::rand())))
-                .collect::<Vec<_>>();
+                let addr = Address::rand_nonleaf(DEPTH);
+                let children = addr.iter_children(DEPTH);
+                let accounts = children
+                    .map(|addr| (addr, Box::new(Account::rand())))
+                    .collect::<Vec<_>>();
 
-            let merkle_root1 = db.merkle_root();
-            elog!("naccounts={:?}", accounts.len());
-            db.set_batch_accounts(&accounts);
-            let merkle_root2 = db.merkle_root();
+                let merkle_root1 = db.merkle_root();
+                elog!("naccounts={:?}", accounts.len());
+                db.set_batch_accounts(&accounts);
+                let merkle_root2 = db.merkle_root();
 
-            assert_ne!(merkle_root1, merkle_root2);
+                assert_ne!(merkle_root1, merkle_root2);
+            }
         }
     }
 
@@ -864,44 +941,46 @@ mod tests_ocaml {
     //  set_batch_accounts""
     #[test]
     fn test_retrieve_account_after_set_batch() {
-        const DEPTH: usize = 7;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 7;
 
-        let mut db = Database::<V2>::create(DEPTH as u8);
+            let mut db = backend.create(DEPTH as u8);
 
-        let mut addr = Address::root();
-        for _ in 0..63 {
-            let account = Account::rand();
-            addr = db
-                .get_or_create_account(account.id(), account)
-                .unwrap()
-                .addr();
-        }
+            let mut addr = Address::root();
+            for _ in 0..63 {
+                let account = Account::rand();
+                addr = db
+                    .get_or_create_account(account.id(), account)
+                    .unwrap()
+                    .addr();
+            }
 
-        let last_location = db.last_filled().unwrap();
-        assert_eq!(addr, last_location);
+            let last_location = db.last_filled().unwrap();
+            assert_eq!(addr, last_location);
 
-        let mut accounts = Vec::with_capacity(2u64.pow(DEPTH as u32) as usize);
+            let mut accounts = Vec::with_capacity(2u64.pow(DEPTH as u32) as usize);
 
-        while let Some(next_addr) = addr.next() {
-            accounts.push((next_addr.clone(), Box::new(Account::rand())));
-            addr = next_addr;
-        }
+            while let Some(next_addr) = addr.next() {
+                accounts.push((next_addr.clone(), Box::new(Account::rand())));
+                addr = next_addr;
+            }
 
-        db.set_batch_accounts(&accounts);
+            db.set_batch_accounts(&accounts);
 
-        for (addr, account) in &accounts {
-            let account_id = account.id();
-            let location = db.location_of_account(&account_id).unwrap();
-            let queried_account = db.get(location.clone()).unwrap();
+            for (addr, account) in &accounts {
+                let account_id = account.id();
+                let location = db.location_of_account(&account_id).unwrap();
+                let queried_account = db.get(location.clone()).unwrap();
 
-            assert_eq!(*addr, location);
-            assert_eq!(account, &queried_account);
-        }
+                assert_eq!(*addr, location);
+                assert_eq!(account, &queried_account);
+            }
 
-        let expected_last_location = last_location.to_index().0 + accounts.len() as u64;
-        let actual_last_location = db.last_filled().unwrap().to_index().0;
+            let expected_last_location = last_location.to_index().0 + accounts.len() as u64;
+            let actual_last_location = db.last_filled().unwrap().to_index().0;
 
-        assert_eq!(expected_last_location, actual_last_location);
+            assert_eq!(expected_last_location, actual_last_location);
+        }
     }
 
     // "If the entire database is full,
@@ -909,70 +988,76 @@ mod tests_ocaml {
     //  = accounts"
     #[test]
     fn test_set_accounts_rooted_equal_get_accounts_rooted() {
-        const DEPTH: usize = 7;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 7;
 
-        let mut db = create_full_db(DEPTH);
+            let mut db = create_full_db(backend, DEPTH);
 
-        for _ in 0..5 {
-            let addr = Address::rand_nonleaf(DEPTH);
-            let children = addr.iter_children(DEPTH);
-            let accounts = children
-                .map(|_| Box::new(Account::rand()))
-                .collect::<Vec<_>>();
-
-            db.set_all_accounts_rooted_at(addr.clone(), &accounts)
-                .unwrap();
-            let list = db
-                .get_all_accounts_rooted_at(addr)
-                .unwrap()
-                .into_iter()
-                .map(|(_, acc)| acc)
-                .collect::<Vec<_>>();
+            for _ in 0..5 {
+                let addr = Address::rand_nonleaf(DEPTH);
+                let children = addr.iter_children(DEPTH);
+                let accounts = children
+                    .map(|_| Box::new(Account::rand()))
+                    .collect::<Vec<_>>();
 
-            assert!(!accounts.is_empty());
-            assert_eq!(accounts, list);
+                db.set_all_accounts_rooted_at(addr.clone(), &accounts)
+                    .unwrap();
+                let list = db
+                    .get_all_accounts_rooted_at(addr)
+                    .unwrap()
+                    .into_iter()
+                    .map(|(_, acc)| acc)
+                    .collect::<Vec<_>>();
+
+                assert!(!accounts.is_empty());
+                assert_eq!(accounts, list);
+            }
         }
     }
 
     // "create_empty doesn't modify the hash"
     #[test]
     fn test_create_empty_doesnt_modify_hash() {
-        const DEPTH: usize = 7;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 7;
 
-        let mut db = Database::<V2>::create(DEPTH as u8);
+            let mut db = backend.create(DEPTH as u8);
 
-        let start_hash = db.merkle_root();
+            let start_hash = db.merkle_root();
 
-        let account = Account::empty();
-        assert!(matches!(
-            db.get_or_create_account(account.id(), account).unwrap(),
-            GetOrCreated::Added(_)
-        ));
+            let account = Account::empty();
+            assert!(matches!(
+                db.get_or_create_account(account.id(), account).unwrap(),
+                GetOrCreated::Added(_)
+            ));
 
-        assert_eq!(start_hash, db.merkle_root());
+            assert_eq!(start_hash, db.merkle_root());
+        }
     }
 
     // "get_at_index_exn t (index_of_account_exn t public_key) =
     // account"
     #[test]
     fn test_get_indexed() {
-        const DEPTH: usize = 7;
-        const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 7;
+            const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
 
-        let mut db = Database::<V2>::create(DEPTH as u8);
-        let mut accounts = Vec::with_capacity(NACCOUNTS);
+            let mut db = backend.create(DEPTH as u8);
+            let mut accounts = Vec::with_capacity(NACCOUNTS);
 
-        for _ in 0..NACCOUNTS {
-            let account = Account::rand();
-            accounts.push(account.clone());
-            db.get_or_create_account(account.id(), account).unwrap();
-        }
+            for _ in 0..NACCOUNTS {
+                let account = Account::rand();
+                accounts.push(account.clone());
+                db.get_or_create_account(account.id(), account).unwrap();
+            }
 
-        for account in accounts {
-            let account_id = account.id();
-            let index_of_account = db.index_of_account(account_id).unwrap();
-            let indexed_account = db.get_at_index(index_of_account).unwrap();
-            assert_eq!(account, *indexed_account);
+            for account in accounts {
+                let account_id = account.id();
+                let index_of_account = db.index_of_account(account_id).unwrap();
+                let indexed_account = db.get_at_index(index_of_account).unwrap();
+                assert_eq!(account, *indexed_account);
+            }
         }
     }
 
@@ -980,282 +1065,298 @@ mod tests_ocaml {
     // index = account"
     #[test]
     fn test_set_get_indexed_equal() {
-        const DEPTH: usize = 7;
-        const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 7;
+            const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
 
-        let mut db = create_full_db(DEPTH);
+            let mut db = create_full_db(backend, DEPTH);
 
-        for _ in 0..50 {
-            let account = Box::new(Account::rand());
-            let index = rand::thread_rng().gen_range(0..NACCOUNTS);
-            let index = AccountIndex(index as u64);
+            for _ in 0..50 {
+                let account = Box::new(Account::rand());
+                let index = rand::thread_rng().gen_range(0..NACCOUNTS);
+                let index = AccountIndex(index as u64);
 
-            db.set_at_index(index, account.clone()).unwrap();
-            let at_index = db.get_at_index(index).unwrap();
-            assert_eq!(account, at_index);
+                db.set_at_index(index, account.clone()).unwrap();
+                let at_index = db.get_at_index(index).unwrap();
+                assert_eq!(account, at_index);
+            }
         }
     }
 
     // "iter"
     #[test]
     fn test_iter() {
-        const DEPTH: usize = 7;
-        const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 7;
+            const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
 
-        let mut db = Database::<V2>::create(DEPTH as u8);
-        let mut accounts = Vec::with_capacity(NACCOUNTS);
+            let mut db = backend.create(DEPTH as u8);
+            let mut accounts = Vec::with_capacity(NACCOUNTS);
 
-        for _ in 0..NACCOUNTS {
-            let account = Account::rand();
-            accounts.push(account.clone());
-            db.get_or_create_account(account.id(), account).unwrap();
-        }
+            for _ in 0..NACCOUNTS {
+                let account = Account::rand();
+                accounts.push(account.clone());
+                db.get_or_create_account(account.id(), account).unwrap();
+            }
 
-        assert_eq!(accounts, db.to_list(),)
+            assert_eq!(accounts, db.to_list(),)
+        }
     }
 
     // "Add 2^d accounts (for testing, d is small)"
     #[test]
     fn test_retrieve() {
-        const DEPTH: usize = 7;
-        const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 7;
+            const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
 
-        let mut db = Database::<V2>::create(DEPTH as u8);
-        let mut accounts = Vec::with_capacity(NACCOUNTS);
+            let mut db = backend.create(DEPTH as u8);
+            let mut accounts = Vec::with_capacity(NACCOUNTS);
 
-        for _ in 0..NACCOUNTS {
-            let account = Box::new(Account::rand());
-            accounts.push(account.clone());
-            db.get_or_create_account(account.id(), *account).unwrap();
-        }
+            for _ in 0..NACCOUNTS {
+                let account = Box::new(Account::rand());
+                accounts.push(account.clone());
+                db.get_or_create_account(account.id(), *account).unwrap();
+            }
 
-        let retrieved = db
-            .get_all_accounts_rooted_at(Address::root())
-            .unwrap()
-            .into_iter()
-            .map(|(_, acc)| acc)
-            .collect::<Vec<_>>();
+            let retrieved = db
+                .get_all_accounts_rooted_at(Address::root())
+                .unwrap()
+                .into_iter()
+                .map(|(_, acc)| acc)
+                .collect::<Vec<_>>();
 
-        assert_eq!(accounts, retrieved);
+            assert_eq!(accounts, retrieved);
+        }
     }
 
     // "removing accounts restores Merkle root"
     #[test]
     fn test_remove_restore_root_hash() {
-        const DEPTH: usize = 7;
-        const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 7;
+            const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
 
-        let mut db = Database::<V2>::create(DEPTH as u8);
+            let mut db = backend.create(DEPTH as u8);
 
-        let root_hash = db.merkle_root();
+            let root_hash = db.merkle_root();
 
-        let mut accounts = Vec::with_capacity(NACCOUNTS);
+            let mut accounts = Vec::with_capacity(NACCOUNTS);
 
-        for _ in 0..NACCOUNTS {
-            let account = Account::rand();
-            accounts.push(account.id());
-            db.get_or_create_account(account.id(), account).unwrap();
-        }
-        assert_ne!(root_hash, db.merkle_root());
+            for _ in 0..NACCOUNTS {
+                let account = Account::rand();
+                accounts.push(account.id());
+                db.get_or_create_account(account.id(), account).unwrap();
+            }
+            assert_ne!(root_hash, db.merkle_root());
 
-        db.remove_accounts(&accounts);
-        assert_eq!(root_hash, db.merkle_root());
+            db.remove_accounts(&accounts);
+            assert_eq!(root_hash, db.merkle_root());
+        }
     }
 
     // "fold over account balances"
     #[test]
     fn test_fold_over_account_balance() {
-        const DEPTH: usize = 7;
-        const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 7;
+            const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
 
-        let mut db = Database::<V2>::create(DEPTH as u8);
-        let mut total_balance: u128 = 0;
+            let mut db = backend.create(DEPTH as u8);
+            let mut total_balance: u128 = 0;
 
-        for _ in 0..NACCOUNTS {
-            let account = Account::rand();
-            total_balance += account.balance.as_u64() as u128;
-            db.get_or_create_account(account.id(), account).unwrap();
-        }
+            for _ in 0..NACCOUNTS {
+                let account = Account::rand();
+                total_balance += account.balance.as_u64() as u128;
+                db.get_or_create_account(account.id(), account).unwrap();
+            }
 
-        let retrieved = db.fold(0u128, |acc, account| acc + account.balance.as_u64() as u128);
-        assert_eq!(total_balance, retrieved);
+            let retrieved = db.fold(0u128, |acc, account| acc + account.balance.as_u64() as u128);
+            assert_eq!(total_balance, retrieved);
+        }
     }
 
     // "fold_until over account balances"
     #[test]
     fn test_fold_until_over_account_balance() {
-        const DEPTH: usize = 7;
-        const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 7;
+            const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
 
-        let mut db = Database::<V2>::create(DEPTH as u8);
-        let mut total_balance: u128 = 0;
-        let mut last_id: AccountId = Account::empty().id();
+            let mut db = backend.create(DEPTH as u8);
+            let mut total_balance: u128 = 0;
+            let mut last_id: AccountId = Account::empty().id();
 
-        for i in 0..NACCOUNTS {
-            let account = Account::rand();
-            if i <= 30 {
-                total_balance += account.balance.as_u64() as u128;
-                last_id = account.id();
+            for i in 0..NACCOUNTS {
+                let account = Account::rand();
+                if i <= 30 {
+                    total_balance += account.balance.as_u64() as u128;
+                    last_id = account.id();
+                }
+                db.get_or_create_account(account.id(), account).unwrap();
             }
-            db.get_or_create_account(account.id(), account).unwrap();
-        }
 
-        let retrieved = db.fold_until(0u128, |mut acc, account| {
-            acc += account.balance.as_u64() as u128;
+            let retrieved = db.fold_until(0u128, |mut acc, account| {
+                acc += account.balance.as_u64() as u128;
 
-            if account.id() != last_id {
-                ControlFlow::Continue(acc)
-            } else {
-                ControlFlow::Break(acc)
-            }
-        });
+                if account.id() != last_id {
+                    ControlFlow::Continue(acc)
+                } else {
+                    ControlFlow::Break(acc)
+                }
+            });
 
-        assert_eq!(total_balance, retrieved);
+            assert_eq!(total_balance, retrieved);
+        }
     }
 
     #[test]
     fn test_merkle_path_long() {
-        const DEPTH: usize = 4;
-        const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 4;
+            const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
 
-        let mut db = Database::<V2>::create(DEPTH as u8);
+            let mut db = backend.create(DEPTH as u8);
 
-        for index in 0..NACCOUNTS / 2 {
-            let mut account = Account::empty();
-            account.token_id = TokenId::from(index as u64);
-
-            // elog!("account{}={}", index, account.hash().to_hex());
+            for index in 0..NACCOUNTS / 2 {
+                let mut account = Account::empty();
+                account.token_id = TokenId::from(index as u64);
 
-            let res = db.get_or_create_account(account.id(), account).unwrap();
-            assert!(matches!(res, GetOrCreated::Added(_)));
-        }
+                // elog!("account{}={}", index, account.hash().to_hex());
 
-        elog!("naccounts={:?}", db.last_filled());
-
-        let expected = [
-            &[
-                "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
-                "19c428e06065374dcdbb9c2773d59c14a4bc5322b821b616f5ad8b95e3fce83c",
-                "277bb79d5e2b48e92cf9e3cf169eccf199e66b1b611765bdd190ad914d50f138",
-                "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
-            ][..],
-            &[
-                "ea3278ff7f5c0472163846755a94643f9dea2babe2d25848294fd727d83a6630",
-                "19c428e06065374dcdbb9c2773d59c14a4bc5322b821b616f5ad8b95e3fce83c",
-                "277bb79d5e2b48e92cf9e3cf169eccf199e66b1b611765bdd190ad914d50f138",
-                "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
-            ][..],
-            &[
-                "9c461cf909421302a96f3794f198bae68d14eb542cfdae3c5942d61211cdbc3c",
-                "c9c043aa20b69f061a06bf27e19e6c7a7c2cb94a5022a614ebfb9209cda11527",
-                "277bb79d5e2b48e92cf9e3cf169eccf199e66b1b611765bdd190ad914d50f138",
-                "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
-            ][..],
-            &[
-                "225381a665a6c436f0750ce907b8077ae3670b17af0acee4a3eb98569692dd10",
-                "c9c043aa20b69f061a06bf27e19e6c7a7c2cb94a5022a614ebfb9209cda11527",
-                "277bb79d5e2b48e92cf9e3cf169eccf199e66b1b611765bdd190ad914d50f138",
-                "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
-            ][..],
-            &[
-                "6224d72dc65c9b89d5717cb43b9de5a31763192dbe316f46bb0ed2486c46d50a",
-                "16b9cac5d2aa4e87c46520d88df96ddb6da7e2f52e23ec406fce9a06d3654f10",
-                "a4c190a90cddf828af5c87b2b9278da29fdf46bab27642c553f281c4cb25ca1f",
-                "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
-            ][..],
-            &[
-                "1b1edf8a2bc43639f2dd7fbffc953a5887ca8e2f57afe2474b293e00a0c8fd13",
-                "16b9cac5d2aa4e87c46520d88df96ddb6da7e2f52e23ec406fce9a06d3654f10",
-                "a4c190a90cddf828af5c87b2b9278da29fdf46bab27642c553f281c4cb25ca1f",
-                "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
-            ][..],
-            &[
-                "bd649e2c20b743b9ecc6235f06016b4aa1263ee7cd6af77afb5358c60ca9143e",
-                "2580091fe2ab125a78a5d8df8c017d1a0f588b871f50a2a34cd383df3d647503",
-                "a4c190a90cddf828af5c87b2b9278da29fdf46bab27642c553f281c4cb25ca1f",
-                "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
-            ][..],
-            &[
-                "2834b7e93942f095625b96ce9b6b709934bbdd35bc5762f6493db73dd84b242c",
-                "2580091fe2ab125a78a5d8df8c017d1a0f588b871f50a2a34cd383df3d647503",
-                "a4c190a90cddf828af5c87b2b9278da29fdf46bab27642c553f281c4cb25ca1f",
-                "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
-            ][..],
-            &[
-                "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
-                "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
-                "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
-                "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
-            ][..],
-            &[
-                "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
-                "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
-                "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
-                "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
-            ][..],
-            &[
-                "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
-                "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
-                "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
-                "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
-            ][..],
-            &[
-                "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
-                "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
-                "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
-                "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
-            ][..],
-            &[
-                "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
-                "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
-                "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
-                "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
-            ][..],
-            &[
-                "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
-                "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
-                "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
-                "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
-            ][..],
-            &[
-                "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
-                "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
-                "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
-                "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
-            ][..],
-            &[
-                "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
-                "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
-                "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
-                "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
-            ][..],
-        ];
+                let res = db.get_or_create_account(account.id(), account).unwrap();
+                assert!(matches!(res, GetOrCreated::Added(_)));
+            }
 
-        let mut hashes = Vec::with_capacity(100);
+            elog!("naccounts={:?}", db.last_filled());
+
+            let expected = [
+                &[
+                    "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
+                    "19c428e06065374dcdbb9c2773d59c14a4bc5322b821b616f5ad8b95e3fce83c",
+                    "277bb79d5e2b48e92cf9e3cf169eccf199e66b1b611765bdd190ad914d50f138",
+                    "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
+                ][..],
+                &[
+                    "ea3278ff7f5c0472163846755a94643f9dea2babe2d25848294fd727d83a6630",
+                    "19c428e06065374dcdbb9c2773d59c14a4bc5322b821b616f5ad8b95e3fce83c",
+                    "277bb79d5e2b48e92cf9e3cf169eccf199e66b1b611765bdd190ad914d50f138",
+                    "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
+                ][..],
+                &[
+                    "9c461cf909421302a96f3794f198bae68d14eb542cfdae3c5942d61211cdbc3c",
+                    "c9c043aa20b69f061a06bf27e19e6c7a7c2cb94a5022a614ebfb9209cda11527",
+                    "277bb79d5e2b48e92cf9e3cf169eccf199e66b1b611765bdd190ad914d50f138",
+                    "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
+                ][..],
+                &[
+                    "225381a665a6c436f0750ce907b8077ae3670b17af0acee4a3eb98569692dd10",
+                    "c9c043aa20b69f061a06bf27e19e6c7a7c2cb94a5022a614ebfb9209cda11527",
+                    "277bb79d5e2b48e92cf9e3cf169eccf199e66b1b611765bdd190ad914d50f138",
+                    "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
+                ][..],
+                &[
+                    "6224d72dc65c9b89d5717cb43b9de5a31763192dbe316f46bb0ed2486c46d50a",
+                    "16b9cac5d2aa4e87c46520d88df96ddb6da7e2f52e23ec406fce9a06d3654f10",
+                    "a4c190a90cddf828af5c87b2b9278da29fdf46bab27642c553f281c4cb25ca1f",
+                    "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
+                ][..],
+                &[
+                    "1b1edf8a2bc43639f2dd7fbffc953a5887ca8e2f57afe2474b293e00a0c8fd13",
+                    "16b9cac5d2aa4e87c46520d88df96ddb6da7e2f52e23ec406fce9a06d3654f10",
+                    "a4c190a90cddf828af5c87b2b9278da29fdf46bab27642c553f281c4cb25ca1f",
+                    "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
+                ][..],
+                &[
+                    "bd649e2c20b743b9ecc6235f06016b4aa1263ee7cd6af77afb5358c60ca9143e",
+                    "2580091fe2ab125a78a5d8df8c017d1a0f588b871f50a2a34cd383df3d647503",
+                    "a4c190a90cddf828af5c87b2b9278da29fdf46bab27642c553f281c4cb25ca1f",
+                    "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
+                ][..],
+                &[
+                    "2834b7e93942f095625b96ce9b6b709934bbdd35bc5762f6493db73dd84b242c",
+                    "2580091fe2ab125a78a5d8df8c017d1a0f588b871f50a2a34cd383df3d647503",
+                    "a4c190a90cddf828af5c87b2b9278da29fdf46bab27642c553f281c4cb25ca1f",
+                    "63f92c64075ae8ef4d076fc7d6743e758513e7b958a4d7a6cb6744eda019d019",
+                ][..],
+                &[
+                    "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
+                    "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
+                    "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
+                    "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
+                ][..],
+                &[
+                    "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
+                    "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
+                    "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
+                    "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
+                ][..],
+                &[
+                    "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
+                    "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
+                    "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
+                    "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
+                ][..],
+                &[
+                    "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
+                    "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
+                    "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
+                    "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
+                ][..],
+                &[
+                    "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
+                    "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
+                    "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
+                    "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
+                ][..],
+                &[
+                    "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
+                    "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
+                    "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
+                    "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
+                ][..],
+                &[
+                    "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
+                    "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
+                    "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
+                    "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
+                ][..],
+                &[
+                    "f3ee39f42a7b2cac196c8eb1c9fe00f853678c920c0c9ce3724c0b7fe911c731",
+                    "64ea581ca7a7aef2b78d04e4a64d0df1da1aa5c2d32a2a34c3c8d50a2c932f07",
+                    "358495ff9f624ba2ea37d0890b8c14079fb16e3626bed5ff0447ee10ecf5f30f",
+                    "c1da0c74de92bf4c1ae893b0f70524a9c6ff4a7ad754e0fc64f5e00548b4cd3d",
+                ][..],
+            ];
+
+            let mut hashes = Vec::with_capacity(100);
+
+            let root = Address::root();
+            let nchild = root.iter_children(DEPTH);
+
+            for child in nchild {
+                let path = db.merkle_path(child);
+                let path = path.iter().map(|p| p.hash().to_hex()).collect::<Vec<_>>();
+                hashes.push(path);
+            }
 
-        let root = Address::root();
-        let nchild = root.iter_children(DEPTH);
+            // elog!("expected={:#?}", expected);
+            // elog!("computed={:#?}", hashes);
 
-        for child in nchild {
-            let path = db.merkle_path(child);
-            let path = path.iter().map(|p| p.hash().to_hex()).collect::<Vec<_>>();
-            hashes.push(path);
+            assert_eq!(&expected[..], hashes.as_slice());
         }
-
-        // elog!("expected={:#?}", expected);
-        // elog!("computed={:#?}", hashes);
-
-        assert_eq!(&expected[..], hashes.as_slice());
     }
 
     // "fold_until over account balances"
     #[test]
     fn test_merkle_path_test2() {
-        const DEPTH: usize = 20;
-        const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 20;
+            const NACCOUNTS: usize = 2u64.pow(DEPTH as u32) as usize;
 
-        let mut db = Database::<V2>::create(DEPTH as u8);
-        db.merkle_path(Address::first(20));
+            let mut db = backend.create(DEPTH as u8);
+            db.merkle_path(Address::first(20));
+        }
     }
 
     // "fold_until over account balances"
@@ -1,4 +1,6 @@
 use std::{
+    borrow::Cow,
+    cell::RefCell,
     collections::{HashMap, HashSet},
     ops::ControlFlow,
     path::PathBuf,
@@ -13,18 +15,24 @@ use crate::{
     V2,
 };
 
-use super::DatabaseError;
+use super::{
+    database_ondisk::{OnDiskAccounts, OnDiskConfig},
+    DatabaseError,
+};
 
-#[derive(Clone)]
 pub struct DatabaseImpl<T: TreeVersion> {
-    accounts: Vec<Option<T::Account>>,
+    accounts: Accounts<T::Account>,
     pub hashes_matrix: HashesMatrix,
+    /// Inner hashes below this height are not cached, see [`OnDiskConfig`]
+    hashes_min_height: usize,
     id_to_addr: HashMap<AccountId, Address>,
     depth: u8,
     last_location: Option<Address>,
     naccounts: usize,
     uuid: Uuid,
     directory: PathBuf,
+    /// First error reading the accounts from disk, see [`Self::flush`]
+    read_error: RefCell<Option<std::io::Error>>,
 }
 
 impl<T: TreeVersion> std::fmt::Debug for DatabaseImpl<T> {
@@ -48,31 +56,123 @@ impl<T: TreeVersion> std::fmt::Debug for DatabaseImpl<T> {
 //     OutOfLeaves,
 // }
 
+/// Storage of the accounts, indexed by their position in the tree
+enum Accounts<A> {
+    InMemory(Vec<Option<A>>),
+    OnDisk(OnDiskAccounts),
+}
+
+impl Accounts<Account> {
+    fn len(&self) -> usize {
+        match self {
+            Self::InMemory(accounts) => accounts.len(),
+            Self::OnDisk(accounts) => accounts.len(),
+        }
+    }
+
+    fn get(&self, index: usize) -> std::io::Result<Option<Cow<'_, Account>>> {
+        match self {
+            Self::InMemory(accounts) => Ok(accounts
+                .get(index)
+                .and_then(Option::as_ref)
+                .map(Cow::Borrowed)),
+            Self::OnDisk(accounts) => accounts.get(index),
+        }
+    }
+
+    fn set(&mut self, index: usize, account: Account) {
+        match self {
+            Self::InMemory(accounts) => {
+                if accounts.len() <= index {
+                    accounts.resize(index + 1, None);
+                }
+                accounts[index] = Some(account);
+            }
+            Self::OnDisk(accounts) => accounts.set(index, Some(account)),
+        }
+    }
+
+    fn take(&mut self, index: usize) -> std::io::Result<Option<Account>> {
+        match self {
+            Self::InMemory(accounts) => Ok(accounts.get_mut(index).and_then(Option::take)),
+            Self::OnDisk(accounts) => accounts.take(index),
+        }
+    }
+
+    fn for_each<F>(&self, mut fun: F) -> std::io::Result<()>
+    where
+        F: FnMut(usize, &Account) -> ControlFlow<()>,
+    {
+        match self {
+            Self::InMemory(accounts) => {
+                for (index, account) in accounts.iter().enumerate() {
+                    if let Some(account) = account {
+                        if fun(index, account).is_break() {
+                            break;
+                        }
+                    }
+                }
+                Ok(())
+            }
+            Self::OnDisk(accounts) => accounts.for_each(fun),
+        }
+    }
+}
+
 impl DatabaseImpl<V2> {
-    pub fn clone_db(&self, new_directory: PathBuf) -> Self {
-        Self {
+    /// Accounts are read through [`BaseLedger`] methods, which can't return
+    /// errors. A failed read from disk is handled like a missing account,
+    /// and the error is kept to be returned by [`Self::flush`].
+    fn read_or_default<T: Default>(&self, result: std::io::Result<T>) -> T {
+        result.unwrap_or_else(|e| {
+            openmina_core::error!(
+                openmina_core::log::system_time();
+                message = "Failed to read accounts from disk",
+                directory = format!("{:?}", self.directory),
+                error = format!("{e:?}")
+            );
+            self.read_error.borrow_mut().get_or_insert(e);
+            T::default()
+        })
+    }
+
+    /// Copies the database. Accounts stored on disk are copied into
+    /// `new_directory`, or into a new directory next to it when it's the
+    /// directory of this database.
+    pub fn clone_db(&self, mut new_directory: PathBuf) -> std::io::Result<Self> {
+        let uuid = next_uuid();
+        let accounts = match &self.accounts {
+            Accounts::InMemory(accounts) => Accounts::InMemory(accounts.clone()),
+            Accounts::OnDisk(accounts) => {
+                if new_directory == self.directory {
+                    new_directory.as_mut_os_string().push(format!("-{uuid}"));
+                }
+                Accounts::OnDisk(accounts.copy_to(&new_directory)?)
+            }
+        };
+
+        Ok(Self {
             // root: self.root.clone(),
-            accounts: self.accounts.clone(),
+            accounts,
             id_to_addr: self.id_to_addr.clone(),
             depth: self.depth,
             last_location: self.last_location.clone(),
             naccounts: self.naccounts,
-            uuid: next_uuid(),
+            uuid,
             directory: new_directory,
             hashes_matrix: self.hashes_matrix.clone(),
+            hashes_min_height: self.hashes_min_height,
+            read_error: RefCell::new(None),
             // root_hash: RefCell::new(*self.root_hash.borrow()),
-        }
+        })
     }
 
     fn remove(&mut self, addr: Address) -> Option<Account> {
         let index = addr.to_index();
         let index: usize = index.0 as usize;
 
-        if let Some(account) = self.accounts.get_mut(index) {
-            return account.take();
-        }
-
-        None
+        let result = self.accounts.take(index);
+        self.read_or_default(result)
     }
 
     fn create_account(
@@ -93,8 +193,9 @@ impl DatabaseImpl<V2> {
             None => Address::first(self.depth as usize),
         };
 
-        assert_eq!(location.to_index(), self.accounts.len());
-        self.accounts.push(Some(account));
+        let index = location.to_index();
+        assert_eq!(index, self.accounts.len());
+        self.accounts.set(index.0 as usize, account);
 
         // let root = self.root.as_mut().unwrap();
         // root.add_account_on_path(account, location.iter());
@@ -115,15 +216,12 @@ impl DatabaseImpl<V2> {
     {
         let depth = self.depth as usize;
 
-        for (index, account) in self.accounts.iter().enumerate() {
-            let account = match account {
-                Some(account) => account,
-                None => continue,
-            };
-
+        let result = self.accounts.for_each(|index, account| {
             let addr = Address::from_index(index.into(), depth);
             fun(addr, account);
-        }
+            ControlFlow::Continue(())
+        });
+        self.read_or_default(result)
     }
 
     fn emulate_tree_to_get_hash_at(&mut self, addr: Address) -> Fp {
@@ -175,7 +273,7 @@ impl DatabaseImpl<V2> {
             Some(hash) => *hash,
             None => {
                 let hash = V2::hash_node(current_depth - 1, left_hash, right_hash);
-                self.hashes_matrix.set(&addr, hash);
+                self.cache_hash(&addr, hash);
                 hash
             }
         }
@@ -237,7 +335,7 @@ impl DatabaseImpl<V2> {
             Some(hash) => *hash,
             None => {
                 let hash = V2::hash_node(depth_in_tree - 1, left, right);
-                self.hashes_matrix.set(&addr, hash);
+                self.cache_hash(&addr, hash);
                 hash
             }
         }
@@ -256,7 +354,14 @@ impl DatabaseImpl<V2> {
     }
 
     pub fn set_cached_hash(&mut self, addr: &Address, hash: Fp) {
-        self.hashes_matrix.set(addr, hash);
+        self.cache_hash(addr, hash);
+    }
+
+    /// Caches the hash, unless the node is below `hashes_min_height`
+    fn cache_hash(&mut self, addr: &Address, hash: Fp) {
+        if self.depth as usize - addr.length() >= self.hashes_min_height {
+            self.hashes_matrix.set(addr, hash);
+        }
     }
 
     pub fn empty_hash_at_height(&mut self, height: usize) -> Fp {
@@ -267,7 +372,8 @@ impl DatabaseImpl<V2> {
         self.hashes_matrix.invalidate_hashes(account_index)
     }
 
-    pub fn transfert_hashes(&mut self, hashes: HashesMatrix) {
+    pub fn transfert_hashes(&mut self, mut hashes: HashesMatrix) {
+        hashes.remove_below_height(self.hashes_min_height);
         self.hashes_matrix.transfert_hashes(hashes)
     }
 }
@@ -287,8 +393,11 @@ impl DatabaseImpl<V1> {
             None => Address::first(self.depth as usize),
         };
 
-        assert_eq!(location.to_index(), self.accounts.len());
-        self.accounts.push(Some(account));
+        let Accounts::InMemory(accounts) = &mut self.accounts else {
+            unreachable!("legacy accounts are stored in memory");
+        };
+        assert_eq!(location.to_index(), accounts.len());
+        accounts.push(Some(account));
 
         // let root = self.root.as_mut().unwrap();
         // let path_iter = location.clone().into_iter();
@@ -332,28 +441,81 @@ impl DatabaseImpl<V2> {
 
         Self {
             depth,
-            accounts: Vec::with_capacity(NACCOUNTS),
+            accounts: Accounts::InMemory(Vec::with_capacity(NACCOUNTS)),
             last_location: None,
             naccounts: 0,
             id_to_addr: HashMap::with_capacity(NACCOUNTS),
             uuid,
             directory: path,
             hashes_matrix: HashesMatrix::new(depth as usize),
+            hashes_min_height: 0,
+            read_error: RefCell::new(None),
             // root_hash: Default::default(),
         }
     }
 
+    /// Creates a database storing its accounts on disk, in `directory`.
+    /// If the directory contains accounts already, they are loaded.
+    pub fn create_ondisk(
+        depth: u8,
+        directory: PathBuf,
+        config: &OnDiskConfig,
+    ) -> std::io::Result<Self> {
+        assert!((1..0xfe).contains(&depth));
+
+        let mut db = Self {
+            depth,
+            accounts: Accounts::OnDisk(OnDiskAccounts::open(&directory, config)?),
+            last_location: None,
+            naccounts: 0,
+            id_to_addr: HashMap::new(),
+            uuid: next_uuid(),
+            directory,
+            hashes_matrix: HashesMatrix::new(depth as usize),
+            hashes_min_height: config.hashes_min_height.min(depth as usize),
+            read_error: RefCell::new(None),
+        };
+
+        let mut id_to_addr = HashMap::new();
+        let mut last_location = None;
+        db.accounts.for_each(|index, account| {
+            let addr = Address::from_index(index.into(), depth as usize);
+            id_to_addr.insert(account.id(), addr.clone());
+            last_location = Some(addr);
+            ControlFlow::Continue(())
+        })?;
+        db.naccounts = id_to_addr.len();
+        db.id_to_addr = id_to_addr;
+        db.last_location = last_location;
+
+        Ok(db)
+    }
+
     pub fn create(depth: u8) -> Self {
         Self::create_with_dir(depth, None)
     }
 
+    /// Writes the accounts modified since the last write to disk, then
+    /// returns the first error reading them since the last call, if any.
+    /// No-op for a database in memory.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.accounts {
+            Accounts::InMemory(_) => {}
+            Accounts::OnDisk(accounts) => accounts.flush()?,
+        }
+        match self.read_error.get_mut().take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
     pub fn root_hash(&mut self) -> Fp {
         self.emulate_tree_to_get_hash_at(Address::root())
     }
 
     // Do not use
     pub fn naccounts(&self) -> usize {
-        self.accounts.iter().filter_map(Option::as_ref).count()
+        self.fold(0, |naccounts, _| naccounts + 1)
     }
 
     // fn naccounts_recursive(&self, elem: &NodeOrLeaf<T>, naccounts: &mut usize) {
@@ -370,21 +532,19 @@ impl DatabaseImpl<V2> {
     //     }
     // }
 
-    fn get_account_ref(&self, addr: Address) -> Option<&Account> {
+    fn get_account_ref(&self, addr: Address) -> Option<Cow<'_, Account>> {
         let index = addr.to_index();
         let index: usize = index.0 as usize;
 
-        self.accounts.get(index)?.as_ref()
+        self.read_or_default(self.accounts.get(index))
     }
 }
 
 impl BaseLedger for DatabaseImpl<V2> {
     fn to_list(&self) -> Vec<Account> {
-        self.accounts
-            .iter()
-            .filter_map(Option::as_ref)
-            .cloned()
-            .collect()
+        let mut accounts = Vec::with_capacity(self.naccounts);
+        self.iter(|account| accounts.push(account.clone()));
+        accounts
         // let root = match self.root.as_ref() {
         //     Some(root) => root,
         //     None => return Vec::new(),
@@ -400,14 +560,15 @@ impl BaseLedger for DatabaseImpl<V2> {
         // accounts
     }
 
-    fn iter<F>(&self, fun: F)
+    fn iter<F>(&self, mut fun: F)
     where
         F: FnMut(&Account),
     {
-        self.accounts
-            .iter()
-            .filter_map(Option::as_ref)
-            .for_each(fun);
+        let result = self.accounts.for_each(|_, account| {
+            fun(account);
+            ControlFlow::Continue(())
+        });
+        self.read_or_default(result);
 
         // let root = match self.root.as_ref() {
         //     Some(root) => root,
@@ -424,11 +585,12 @@ impl BaseLedger for DatabaseImpl<V2> {
     where
         F: FnMut(B, &Account) -> B,
     {
-        let mut accum = init;
-        for account in self.accounts.iter().filter_map(Option::as_ref) {
-            accum = fun(accum, account);
-        }
-        accum
+        let mut accum = Some(init);
+        self.iter(|account| {
+            let res = fun(accum.take().unwrap(), account);
+            accum = Some(res);
+        });
+        accum.unwrap()
 
         // let root = match self.root.as_ref() {
         //     Some(root) => root,
@@ -454,15 +616,16 @@ impl BaseLedger for DatabaseImpl<V2> {
     where
         F: FnMut(B, &Account) -> B,
     {
-        let mut accum = init;
-        for account in self.accounts.iter().filter_map(Option::as_ref) {
+        let mut accum = Some(init);
+        self.iter(|account| {
             let account_id = account.id();
 
             if !ignoreds.contains(&account_id) {
-                accum = fun(accum, account);
+                let res = fun(accum.take().unwrap(), account);
+                accum = Some(res);
             }
-        }
-        accum
+        });
+        accum.unwrap()
         // self.fold(init, |accum, account| {
         //     let account_id = account.id();
 
@@ -478,19 +641,21 @@ impl BaseLedger for DatabaseImpl<V2> {
     where
         F: FnMut(B, &Account) -> ControlFlow<B, B>,
     {
-        let mut accum = init;
-        for account in self.accounts.iter().filter_map(Option::as_ref) {
-            match fun(accum, account) {
-                ControlFlow::Continue(v) => {
-                    accum = v;
-                }
-                ControlFlow::Break(v) => {
-                    accum = v;
-                    break;
-                }
-            }
-        }
-        accum
+        let mut accum = Some(init);
+        let result =
+            self.accounts
+                .for_each(|_, account| match fun(accum.take().unwrap(), account) {
+                    ControlFlow::Continue(v) => {
+                        accum = Some(v);
+                        ControlFlow::Continue(())
+                    }
+                    ControlFlow::Break(v) => {
+                        accum = Some(v);
+                        ControlFlow::Break(())
+                    }
+                });
+        self.read_or_default(result);
+        accum.unwrap()
 
         // let root = match self.root.as_ref() {
         //     Some(root) => root,
@@ -519,11 +684,11 @@ impl BaseLedger for DatabaseImpl<V2> {
     fn tokens(&self, public_key: CompressedPubKey) -> HashSet<TokenId> {
         let mut set = HashSet::with_capacity(100);
 
-        for account in self.accounts.iter().filter_map(Option::as_ref) {
+        self.iter(|account| {
             if account.public_key == public_key {
                 set.insert(account.token_id.clone());
             }
-        }
+        });
 
         // let root = match self.root.as_ref() {
         //     Some(root) => root,
@@ -620,14 +785,15 @@ impl BaseLedger for DatabaseImpl<V2> {
         let account = self.get_account_ref(addr.clone())?;
         let hash = account.hash();
 
-        self.hashes_matrix.set(&addr, hash);
+        self.cache_hash(&addr, hash);
 
         Some(hash)
     }
 
     #[inline(never)]
     fn get(&self, addr: Address) -> Option<Box<Account>> {
-        self.get_account_ref(addr).cloned().map(Box::new)
+        self.get_account_ref(addr)
+            .map(|account| Box::new(account.into_owned()))
     }
 
     fn get_batch(&self, addr: &[Address]) -> Vec<(Address, Option<Box<Account>>)> {
@@ -658,10 +824,6 @@ impl BaseLedger for DatabaseImpl<V2> {
 
         let index: usize = index.0 as usize;
 
-        if self.accounts.len() <= index {
-            self.accounts.resize(index + 1, None);
-        }
-
         // if self.root.is_none() {
         //     self.root = Some(NodeOrLeaf::Node(Node::default()));
         // }
@@ -678,7 +840,7 @@ impl BaseLedger for DatabaseImpl<V2> {
         }
 
         self.id_to_addr.insert(id, addr.clone());
-        self.accounts[index] = Some(*account);
+        self.accounts.set(index, *account);
         // root.add_account_on_path(account, addr.iter());
 
         if self
@@ -903,6 +1065,13 @@ impl BaseLedger for DatabaseImpl<V2> {
     }
 
     fn commit(&mut self) {
-        // no-op
+        if let Err(e) = self.flush() {
+            openmina_core::warn!(
+                openmina_core::log::system_time();
+                message = "Failed to write accounts to disk, keeping them in memory",
+                directory = format!("{:?}", self.directory),
+                error = format!("{e:?}")
+            );
+        }
     }
 }
@@ -0,0 +1,201 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::BTreeMap,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+};
+
+use crate::{ondisk, Account};
+
+/// Configuration of a [`super::Database`] storing its accounts on disk.
+#[derive(Debug, Clone)]
+pub struct OnDiskConfig {
+    /// Inner hashes of the nodes below this height aren't kept in memory,
+    /// they are recomputed from the accounts when needed.
+    ///
+    /// Every increment halves the memory used by the hashes, but doubles
+    /// the number of accounts read to recompute a hash after a change.
+    /// `0` keeps all of them, like the in-memory database.
+    pub hashes_min_height: usize,
+    /// Number of modified accounts kept in memory before they are written
+    /// to disk.
+    pub write_buffer_len: usize,
+    /// The directory is removed once the database is dropped, for
+    /// ledgers that aren't meant to outlive the process.
+    pub temporary: bool,
+}
+
+impl Default for OnDiskConfig {
+    fn default() -> Self {
+        Self {
+            hashes_min_height: 4,
+            write_buffer_len: 1024,
+            temporary: false,
+        }
+    }
+}
+
+/// Accounts of a database, stored in [`ondisk::Database`] by their index.
+pub(super) struct OnDiskAccounts {
+    // `ondisk::Database::get` requires `&mut self`
+    db: RefCell<ondisk::Database>,
+    directory: PathBuf,
+    /// Accounts modified since the last write, `None` when removed.
+    dirty: BTreeMap<u64, Option<Account>>,
+    write_buffer_len: usize,
+    temporary: bool,
+    /// One past the highest index ever set.
+    len: usize,
+}
+
+impl OnDiskAccounts {
+    /// Opens the accounts in the directory, keeping the ones already
+    /// stored there, if any.
+    pub fn open(directory: impl AsRef<Path>, config: &OnDiskConfig) -> std::io::Result<Self> {
+        let directory = directory.as_ref();
+        let db = ondisk::Database::create(directory)?;
+
+        let len = db
+            .keys()
+            .map(index_from_key)
+            .try_fold(0, |len, index| Ok::<_, std::io::Error>(len.max(index? + 1)))?;
+
+        Ok(Self {
+            db: RefCell::new(db),
+            directory: directory.to_path_buf(),
+            dirty: BTreeMap::new(),
+            write_buffer_len: config.write_buffer_len,
+            temporary: config.temporary,
+            len,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn get(&self, index: usize) -> std::io::Result<Option<Cow<'_, Account>>> {
+        match self.dirty.get(&(index as u64)) {
+            Some(account) => Ok(account.as_ref().map(Cow::Borrowed)),
+            None => Ok(self.read(index as u64)?.map(Cow::Owned)),
+        }
+    }
+
+    fn read(&self, index: u64) -> std::io::Result<Option<Account>> {
+        let bytes = self.db.borrow_mut().get(&index_to_key(index))?;
+        Ok(bytes.map(|bytes| Account::deserialize(&bytes)))
+    }
+
+    /// Sets the account at `index`, `None` removes it. Once the write
+    /// buffer is full the accounts are written to disk. If that fails,
+    /// they are kept in memory and written by the next [`Self::flush`].
+    pub fn set(&mut self, index: usize, account: Option<Account>) {
+        self.len = self.len.max(index + 1);
+        self.dirty.insert(index as u64, account);
+
+        if self.dirty.len() >= self.write_buffer_len {
+            if let Err(e) = self.flush() {
+                openmina_core::warn!(
+                    openmina_core::log::system_time();
+                    message = "Failed to write accounts to disk, keeping them in memory",
+                    directory = format!("{:?}", self.directory),
+                    error = format!("{e:?}")
+                );
+            }
+        }
+    }
+
+    pub fn take(&mut self, index: usize) -> std::io::Result<Option<Account>> {
+        let Some(account) = self.get(index)?.map(Cow::into_owned) else {
+            return Ok(None);
+        };
+        self.set(index, None);
+        Ok(Some(account))
+    }
+
+    pub fn for_each<F>(&self, mut fun: F) -> std::io::Result<()>
+    where
+        F: FnMut(usize, &Account) -> ControlFlow<()>,
+    {
+        for index in 0..self.len {
+            if let Some(account) = self.get(index)? {
+                if fun(index, &account).is_break() {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the modified accounts to disk. On failure, they are kept in
+    /// memory so that the write can be retried.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        if self.dirty.is_empty() {
+            return Ok(());
+        }
+
+        let mut set = Vec::with_capacity(self.dirty.len());
+        let mut remove = Vec::new();
+        for (index, account) in &self.dirty {
+            match account {
+                Some(account) => set.push((index_to_key(*index), account.serialize().into())),
+                None => remove.push(index_to_key(*index)),
+            }
+        }
+
+        self.db.get_mut().set_batch(set, remove)?;
+        self.dirty.clear();
+        Ok(())
+    }
+
+    /// Copies the accounts into a new directory.
+    pub fn copy_to(&self, directory: impl AsRef<Path>) -> std::io::Result<Self> {
+        let directory = directory.as_ref();
+        let db = self.db.borrow_mut().create_checkpoint(directory)?;
+
+        Ok(Self {
+            db: RefCell::new(db),
+            directory: directory.to_path_buf(),
+            dirty: self.dirty.clone(),
+            write_buffer_len: self.write_buffer_len,
+            temporary: self.temporary,
+            len: self.len,
+        })
+    }
+}
+
+impl Drop for OnDiskAccounts {
+    fn drop(&mut self) {
+        if self.temporary {
+            if let Err(e) = std::fs::remove_dir_all(&self.directory) {
+                openmina_core::warn!(
+                    openmina_core::log::system_time();
+                    message = "Failed to remove accounts directory",
+                    directory = format!("{:?}", self.directory),
+                    error = format!("{e:?}")
+                );
+            }
+            return;
+        }
+        if let Err(e) = self.flush() {
+            openmina_core::error!(
+                openmina_core::log::system_time();
+                message = "Failed to write accounts to disk",
+                directory = format!("{:?}", self.directory),
+                error = format!("{e:?}")
+            );
+        }
+    }
+}
+
+fn index_to_key(index: u64) -> Box<[u8]> {
+    index.to_be_bytes().into()
+}
+
+fn index_from_key(key: &[u8]) -> std::io::Result<usize> {
+    let bytes = key
+        .try_into()
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid account key"))?;
+    Ok(u64::from_be_bytes(bytes) as usize)
+}
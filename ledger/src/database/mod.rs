@@ -2,5 +2,10 @@
 
 mod database;
 mod database_impl;
+mod database_ondisk;
 
 pub use database::*;
+pub use database_ondisk::OnDiskConfig;
+
+#[cfg(test)]
+pub(crate) use database::TestBackend;
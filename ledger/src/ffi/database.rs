@@ -201,7 +201,7 @@ ocaml_export! {
             let directory_name = PathBuf::from(directory_name);
 
             let db: Ref<Option<Database<V2>>> = (*db.0).borrow();
-            let db_clone = db.as_ref().unwrap().clone_db(directory_name);
+            let db_clone = db.as_ref().unwrap().clone_db(directory_name).unwrap();
 
             DatabaseFFI(Rc::new(RefCell::new(Some(db_clone))))
         };
@@ -228,7 +228,7 @@ ocaml_export! {
         let directory_name = PathBuf::from(directory_name);
 
         let db: Ref<Option<Database<V2>>> = (*db.0).borrow();
-        let db_clone = db.as_ref().unwrap().clone_db(directory_name.clone());
+        let db_clone = db.as_ref().unwrap().clone_db(directory_name.clone()).unwrap();
 
         let mut closed_dbs = DB_CLOSED.try_lock().unwrap();
         closed_dbs.insert(directory_name, db_clone);
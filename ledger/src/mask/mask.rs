@@ -133,11 +133,24 @@ impl Mask {
         self.with(|this| this.nmasks_to_root())
     }
 
+    /// Panics when the root database, stored on disk, can't be copied.
+    /// Use [`Self::try_copy`] for masks that can be rooted on disk.
     pub fn copy(&self) -> Mask {
-        let mask = self.with(|this| this.clone());
-        Self {
+        self.try_copy().expect("failed to copy the root database")
+    }
+
+    pub fn try_copy(&self) -> std::io::Result<Mask> {
+        let mask = self.with(|this| this.try_clone())?;
+        Ok(Self {
             inner: Arc::new(Mutex::new(mask)),
-        }
+        })
+    }
+
+    /// Writes the accounts of the root database to disk, and returns the
+    /// first error reading them since the last call, if any. No-op when
+    /// the root database is in memory.
+    pub fn flush(&self) -> std::io::Result<()> {
+        self.with(|this| this.flush())
     }
 
     /// Make `mask` a child of `self`
@@ -489,6 +502,7 @@ impl BaseLedger for Mask {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::database::TestBackend;
     use tests_mask_ocaml::*;
 
     #[cfg(target_family = "wasm")]
@@ -546,149 +560,160 @@ mod tests {
 
     #[test]
     fn test_merkle_path_one_account() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let account = Account::rand();
+            let account = Account::rand();
 
-        let addr = root
-            .get_or_create_account(account.id(), account)
-            .unwrap()
-            .addr();
+            let addr = root
+                .get_or_create_account(account.id(), account)
+                .unwrap()
+                .addr();
 
-        let path = mask.merkle_path(addr);
-        assert_eq!(path.len(), DEPTH);
+            let path = mask.merkle_path(addr);
+            assert_eq!(path.len(), DEPTH);
+        }
     }
 
     #[test]
     fn test_masks() {
-        const DEPTH: usize = 20;
+        for backend in TestBackend::ALL {
+            const DEPTH: usize = 20;
 
-        let root = Mask::new_unattached(DEPTH);
-        let mask = Mask::new_unattached(DEPTH);
+            let root = Mask::new_unattached(DEPTH);
+            let mask = Mask::new_unattached(DEPTH);
 
-        let mut mask = root.register_mask(mask);
+            let mut mask = root.register_mask(mask);
 
-        let accounts: Vec<_> = (0..18).map(|_| Account::rand()).collect();
+            let accounts: Vec<_> = (0..18).map(|_| Account::rand()).collect();
 
-        for account in &accounts {
-            mask.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
+            for account in &accounts {
+                mask.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
 
-        let mask_paths: Vec<_> = (0..18)
-            .map(|index| {
-                let index: AccountIndex = index.into();
-                let addr = Address::from_index(index, DEPTH);
-                mask.merkle_path(addr)
-            })
-            .collect();
+            let mask_paths: Vec<_> = (0..18)
+                .map(|index| {
+                    let index: AccountIndex = index.into();
+                    let addr = Address::from_index(index, DEPTH);
+                    mask.merkle_path(addr)
+                })
+                .collect();
 
-        let mask_root_hash = mask.merkle_root();
+            let mask_root_hash = mask.merkle_root();
 
-        let mut db = Database::create(DEPTH as u8);
-        for account in &accounts {
-            db.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
+            let mut db = backend.create(DEPTH as u8);
+            for account in &accounts {
+                db.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
 
-        let db_paths: Vec<_> = (0..18)
-            .map(|index| {
-                let index: AccountIndex = index.into();
-                let addr = Address::from_index(index, DEPTH);
-                mask.merkle_path(addr)
-            })
-            .collect();
+            let db_paths: Vec<_> = (0..18)
+                .map(|index| {
+                    let index: AccountIndex = index.into();
+                    let addr = Address::from_index(index, DEPTH);
+                    mask.merkle_path(addr)
+                })
+                .collect();
 
-        let db_root_hash = db.merkle_root();
+            let db_root_hash = db.merkle_root();
 
-        assert_eq!(mask_root_hash, db_root_hash);
-        assert_eq!(mask_paths, db_paths);
+            assert_eq!(mask_root_hash, db_root_hash);
+            assert_eq!(mask_paths, db_paths);
+        }
     }
 
     #[test]
     fn test_masks_unregister_recursive() {
-        let (_root, layer1, layer2) = new_chain(DEPTH);
+        for backend in TestBackend::ALL {
+            let (_root, layer1, layer2) = new_chain(backend, DEPTH);
 
-        let layer3 = layer2.make_child();
-        let layer4 = layer2.make_child();
+            let layer3 = layer2.make_child();
+            let layer4 = layer2.make_child();
 
-        for mask in [&layer1, &layer2, &layer3, &layer4] {
-            assert!(mask.get_parent().is_some());
-        }
+            for mask in [&layer1, &layer2, &layer3, &layer4] {
+                assert!(mask.get_parent().is_some());
+            }
 
-        // This should not panic
-        layer1.unregister_mask(UnregisterBehavior::Recursive);
+            // This should not panic
+            layer1.unregister_mask(UnregisterBehavior::Recursive);
 
-        for mask in [&layer1, &layer2, &layer3, &layer4] {
-            assert!(mask.get_parent().is_none());
+            for mask in [&layer1, &layer2, &layer3, &layer4] {
+                assert!(mask.get_parent().is_none());
+            }
         }
     }
 
     // Make sure hashes are correctly invalided in masks (parents/childs)
     #[test]
     fn test_masks_cached_hashes() {
-        for case in 0..2 {
-            let (mut root, mut layer1, mut layer2) = new_chain(DEPTH);
+        for backend in TestBackend::ALL {
+            for case in 0..2 {
+                let (mut root, mut layer1, mut layer2) = new_chain(backend, DEPTH);
 
-            let acc1 = Account::rand();
-            let acc2 = Account::rand();
-            let acc3 = Account::rand();
+                let acc1 = Account::rand();
+                let acc2 = Account::rand();
+                let acc3 = Account::rand();
 
-            let _loc1 = root.get_or_create_account(acc1.id(), acc1).unwrap().addr();
-            let _loc2 = layer1
-                .get_or_create_account(acc2.id(), acc2.clone())
-                .unwrap()
-                .addr();
-            let _loc3 = layer2
-                .get_or_create_account(acc3.id(), acc3)
-                .unwrap()
-                .addr();
+                let _loc1 = root.get_or_create_account(acc1.id(), acc1).unwrap().addr();
+                let _loc2 = layer1
+                    .get_or_create_account(acc2.id(), acc2.clone())
+                    .unwrap()
+                    .addr();
+                let _loc3 = layer2
+                    .get_or_create_account(acc3.id(), acc3)
+                    .unwrap()
+                    .addr();
 
-            let root_hash = layer2.merkle_root();
+                let root_hash = layer2.merkle_root();
 
-            // Different cases where is should result in a different hash for the childs
+                // Different cases where is should result in a different hash for the childs
 
-            if case == 0 {
-                layer1.remove_accounts(&[acc2.id()]);
-            } else if case == 1 {
-                let account_index = AccountIndex::from(1);
-                let addr = Address::from_index(account_index, DEPTH);
-                let new_account = Account::rand();
+                if case == 0 {
+                    layer1.remove_accounts(&[acc2.id()]);
+                } else if case == 1 {
+                    let account_index = AccountIndex::from(1);
+                    let addr = Address::from_index(account_index, DEPTH);
+                    let new_account = Account::rand();
 
-                assert_ne!(*layer1.get(addr.clone()).unwrap(), new_account);
+                    assert_ne!(*layer1.get(addr.clone()).unwrap(), new_account);
 
-                layer1.set(addr, Box::new(new_account));
-            }
+                    layer1.set(addr, Box::new(new_account));
+                }
 
-            assert_ne!(root_hash, layer2.merkle_root(), "case {:?}", case);
+                assert_ne!(root_hash, layer2.merkle_root(), "case {:?}", case);
+            }
         }
     }
 
     #[test]
     fn test_cached_merkle_path() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let account = Account::rand();
-        let addr = Address::first(DEPTH);
+            let account = Account::rand();
+            let addr = Address::first(DEPTH);
 
-        mask.set(addr.clone(), Box::new(account.clone()));
-        mask.merkle_root();
-        let mask_merkle_path = mask.merkle_path(addr.clone());
+            mask.set(addr.clone(), Box::new(account.clone()));
+            mask.merkle_root();
+            let mask_merkle_path = mask.merkle_path(addr.clone());
 
-        root.set(addr.clone(), Box::new(account));
-        root.merkle_root();
-        let root_merkle_path = root.merkle_path(addr);
+            root.set(addr.clone(), Box::new(account));
+            root.merkle_root();
+            let root_merkle_path = root.merkle_path(addr);
 
-        assert!(!mask_merkle_path.is_empty());
-        assert_eq!(mask_merkle_path, root_merkle_path);
-        elog!("path={:?}", mask_merkle_path);
+            assert!(!mask_merkle_path.is_empty());
+            assert_eq!(mask_merkle_path, root_merkle_path);
+            elog!("path={:?}", mask_merkle_path);
+        }
     }
 }
 
 #[cfg(test)]
 mod tests_mask_ocaml {
+    use crate::database::TestBackend;
     use crate::scan_state::currency::{Balance, Magnitude};
 
     use super::*;
@@ -701,13 +726,13 @@ mod tests_mask_ocaml {
     pub const DEPTH: usize = 4;
     pub const FIRST_LOC: Address = Address::first(DEPTH);
 
-    pub fn new_instances(depth: usize) -> (Mask, Mask) {
-        let db = Database::<V2>::create(depth as u8);
+    pub fn new_instances(backend: TestBackend, depth: usize) -> (Mask, Mask) {
+        let db = backend.create(depth as u8);
         (Mask::new_root(db), Mask::new_unattached(depth))
     }
 
-    pub fn new_chain(depth: usize) -> (Mask, Mask, Mask) {
-        let db = Database::<V2>::create(depth as u8);
+    pub fn new_chain(backend: TestBackend, depth: usize) -> (Mask, Mask, Mask) {
+        let db = backend.create(depth as u8);
         let layer1 = Mask::new_unattached(depth);
         let layer2 = Mask::new_unattached(depth);
 
@@ -727,329 +752,365 @@ mod tests_mask_ocaml {
     // "parent, mask agree on set"
     #[test]
     fn test_parent_mask_agree_on_set() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mask = root.register_mask(mask);
 
-        root.set(FIRST_LOC, Box::new(Account::rand()));
+            root.set(FIRST_LOC, Box::new(Account::rand()));
 
-        let root_account = root.get(FIRST_LOC).unwrap();
-        let mask_account = mask.get(FIRST_LOC).unwrap();
+            let root_account = root.get(FIRST_LOC).unwrap();
+            let mask_account = mask.get(FIRST_LOC).unwrap();
 
-        assert_eq!(root_account, mask_account);
+            assert_eq!(root_account, mask_account);
+        }
     }
 
     // "parent, mask agree on set"
     #[test]
     fn test_parent_mask_agree_on_set2() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let account = Box::new(Account::rand());
-        root.set(FIRST_LOC, account.clone());
-        mask.set(FIRST_LOC, account);
+            let account = Box::new(Account::rand());
+            root.set(FIRST_LOC, account.clone());
+            mask.set(FIRST_LOC, account);
 
-        let root_account = root.get(FIRST_LOC).unwrap();
-        let mask_account = mask.get(FIRST_LOC).unwrap();
+            let root_account = root.get(FIRST_LOC).unwrap();
+            let mask_account = mask.get(FIRST_LOC).unwrap();
 
-        assert_eq!(root_account, mask_account);
+            assert_eq!(root_account, mask_account);
+        }
     }
 
     // "parent, mask agree on hashes; set in both mask and parent"
     #[test]
     fn test_parent_mask_agree_on_hashes() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let account = Box::new(Account::rand());
-        root.set(FIRST_LOC, account.clone());
-        mask.set(FIRST_LOC, account);
+            let account = Box::new(Account::rand());
+            root.set(FIRST_LOC, account.clone());
+            mask.set(FIRST_LOC, account);
 
-        assert_eq!(root.merkle_root(), mask.merkle_root());
+            assert_eq!(root.merkle_root(), mask.merkle_root());
+        }
     }
 
     // "parent, mask agree on hashes; set only in parent"
     #[test]
     fn test_parent_mask_agree_on_hashes_set_parent_only() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let account = Box::new(Account::rand());
-        root.set(FIRST_LOC, account);
+            let account = Box::new(Account::rand());
+            root.set(FIRST_LOC, account);
 
-        assert_eq!(root.merkle_root(), mask.merkle_root());
+            assert_eq!(root.merkle_root(), mask.merkle_root());
+        }
     }
 
     // "mask delegates to parent"
     #[test]
     fn test_mask_delegate_to_parent() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mask = root.register_mask(mask);
 
-        let account = Box::new(Account::rand());
-        root.set(FIRST_LOC, account.clone());
+            let account = Box::new(Account::rand());
+            root.set(FIRST_LOC, account.clone());
 
-        let child_account = mask.get(FIRST_LOC).unwrap();
+            let child_account = mask.get(FIRST_LOC).unwrap();
 
-        assert_eq!(account, child_account);
+            assert_eq!(account, child_account);
+        }
     }
 
     // "mask prune after parent notification"
     #[test]
     fn test_mask_prune_after_parent_notif() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        // Set in mask
-        let account = Box::new(Account::rand());
-        mask.set(FIRST_LOC, account.clone());
+            // Set in mask
+            let account = Box::new(Account::rand());
+            mask.set(FIRST_LOC, account.clone());
 
-        assert!(mask.test_is_in_mask(&FIRST_LOC));
+            assert!(mask.test_is_in_mask(&FIRST_LOC));
 
-        root.set(FIRST_LOC, account);
+            root.set(FIRST_LOC, account);
 
-        // The address is no more in the mask
-        assert!(!mask.test_is_in_mask(&FIRST_LOC));
+            // The address is no more in the mask
+            assert!(!mask.test_is_in_mask(&FIRST_LOC));
+        }
     }
 
     // "commit puts mask contents in parent, flushes mask"
     #[test]
     fn test_commit_puts_mask_in_parent_and_flush_mask() {
-        let (root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let account = Box::new(Account::rand());
-        mask.set(FIRST_LOC, account);
+            let account = Box::new(Account::rand());
+            mask.set(FIRST_LOC, account);
 
-        assert!(mask.test_is_in_mask(&FIRST_LOC));
+            assert!(mask.test_is_in_mask(&FIRST_LOC));
 
-        mask.commit();
+            mask.commit();
 
-        // No more in mask
-        assert!(!mask.test_is_in_mask(&FIRST_LOC));
-        // The parent get the account
-        assert!(root.get(FIRST_LOC).is_some());
+            // No more in mask
+            assert!(!mask.test_is_in_mask(&FIRST_LOC));
+            // The parent get the account
+            assert!(root.get(FIRST_LOC).is_some());
+        }
     }
 
     // "commit at layer2, dumps to layer1, not in base"
     #[test]
     fn test_commit_layer2_dumps_to_layer1_not_in_base() {
-        let (root, layer1, mut layer2) = new_chain(DEPTH);
+        for backend in TestBackend::ALL {
+            let (root, layer1, mut layer2) = new_chain(backend, DEPTH);
 
-        let account = Box::new(Account::rand());
+            let account = Box::new(Account::rand());
 
-        layer2.set(FIRST_LOC, account);
-        assert!(layer2.test_is_in_mask(&FIRST_LOC));
-        assert!(!layer1.test_is_in_mask(&FIRST_LOC));
+            layer2.set(FIRST_LOC, account);
+            assert!(layer2.test_is_in_mask(&FIRST_LOC));
+            assert!(!layer1.test_is_in_mask(&FIRST_LOC));
 
-        layer2.commit();
-        assert!(!layer2.test_is_in_mask(&FIRST_LOC));
-        assert!(layer1.test_is_in_mask(&FIRST_LOC));
-        assert!(!root.test_is_in_mask(&FIRST_LOC));
+            layer2.commit();
+            assert!(!layer2.test_is_in_mask(&FIRST_LOC));
+            assert!(layer1.test_is_in_mask(&FIRST_LOC));
+            assert!(!root.test_is_in_mask(&FIRST_LOC));
+        }
     }
 
     // "commit at layer2, dumps to layer1, not in base"
     #[test]
     fn test_commit_layer2_to_root_dumps_to_base_not_in_layer1() {
-        let (root, mut layer1, mut layer2) = new_chain(DEPTH);
-
-        let (addr1, addr2) = (FIRST_LOC, FIRST_LOC.next().unwrap());
-        let (account1, account2) = (Box::new(Account::rand()), Box::new(Account::rand()));
-
-        layer1.set(addr1.clone(), account1);
-        layer2.set(addr2.clone(), account2);
-
-        assert!(layer1.test_is_in_mask(&addr1));
-        assert!(!layer1.test_is_in_mask(&addr2));
-        assert!(layer2.test_is_in_mask(&addr2));
-        assert!(!layer2.test_is_in_mask(&addr1));
-
-        layer2.commit_and_reparent_to_root();
-        assert!(!layer1.test_is_in_mask(&addr1));
-        assert!(!layer1.test_is_in_mask(&addr2));
-        assert!(!layer2.test_is_in_mask(&addr1));
-        assert!(!layer2.test_is_in_mask(&addr2));
-
-        assert!(!layer1.is_attached());
-        assert!(!layer2.is_attached());
-        assert!(root.test_is_in_mask(&addr1));
-        assert!(root.test_is_in_mask(&addr2));
+        for backend in TestBackend::ALL {
+            let (root, mut layer1, mut layer2) = new_chain(backend, DEPTH);
+
+            let (addr1, addr2) = (FIRST_LOC, FIRST_LOC.next().unwrap());
+            let (account1, account2) = (Box::new(Account::rand()), Box::new(Account::rand()));
+
+            layer1.set(addr1.clone(), account1);
+            layer2.set(addr2.clone(), account2);
+
+            assert!(layer1.test_is_in_mask(&addr1));
+            assert!(!layer1.test_is_in_mask(&addr2));
+            assert!(layer2.test_is_in_mask(&addr2));
+            assert!(!layer2.test_is_in_mask(&addr1));
+
+            layer2.commit_and_reparent_to_root();
+            assert!(!layer1.test_is_in_mask(&addr1));
+            assert!(!layer1.test_is_in_mask(&addr2));
+            assert!(!layer2.test_is_in_mask(&addr1));
+            assert!(!layer2.test_is_in_mask(&addr2));
+
+            assert!(!layer1.is_attached());
+            assert!(!layer2.is_attached());
+            assert!(root.test_is_in_mask(&addr1));
+            assert!(root.test_is_in_mask(&addr2));
+        }
     }
 
     // "register and unregister mask"
     #[test]
     fn test_register_unregister_mask() {
-        let (root, mask) = new_instances(DEPTH);
-        let mask = root.register_mask(mask);
-        mask.unregister_mask(UnregisterBehavior::Recursive);
+        for backend in TestBackend::ALL {
+            let (root, mask) = new_instances(backend, DEPTH);
+            let mask = root.register_mask(mask);
+            mask.unregister_mask(UnregisterBehavior::Recursive);
+        }
     }
 
     // "mask and parent agree on Merkle path"
     #[test]
     fn test_mask_and_parent_agree_on_merkle_path() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let account = Box::new(Account::rand());
-        let addr = Address::first(DEPTH);
+            let account = Box::new(Account::rand());
+            let addr = Address::first(DEPTH);
 
-        mask.set(addr.clone(), account.clone());
-        let mask_merkle_path = mask.merkle_path(addr.clone());
+            mask.set(addr.clone(), account.clone());
+            let mask_merkle_path = mask.merkle_path(addr.clone());
 
-        root.set(addr.clone(), account);
-        let root_merkle_path = root.merkle_path(addr);
+            root.set(addr.clone(), account);
+            let root_merkle_path = root.merkle_path(addr);
 
-        assert!(!mask_merkle_path.is_empty());
-        assert_eq!(mask_merkle_path, root_merkle_path);
-        elog!("path={:?}", mask_merkle_path);
+            assert!(!mask_merkle_path.is_empty());
+            assert_eq!(mask_merkle_path, root_merkle_path);
+            elog!("path={:?}", mask_merkle_path);
+        }
     }
 
     // "mask and parent agree on Merkle root before set"
     #[test]
     fn test_agree_on_root_hash_before_set() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        assert_eq!(root.merkle_root(), mask.merkle_root());
+            assert_eq!(root.merkle_root(), mask.merkle_root());
+        }
     }
 
     // "mask and parent agree on Merkle root after set"
     #[test]
     fn test_agree_on_root_hash_after_set() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let account = Box::new(Account::rand());
+            let account = Box::new(Account::rand());
 
-        // the order of sets matters here; if we set in the mask first,
-        // the set in the maskable notifies the mask, which then removes
-        // the account, changing the Merkle root to what it was before the set
+            // the order of sets matters here; if we set in the mask first,
+            // the set in the maskable notifies the mask, which then removes
+            // the account, changing the Merkle root to what it was before the set
 
-        root.set(FIRST_LOC, account.clone());
-        mask.set(FIRST_LOC, account);
+            root.set(FIRST_LOC, account.clone());
+            mask.set(FIRST_LOC, account);
 
-        assert!(root.test_is_in_mask(&FIRST_LOC));
-        assert!(mask.test_is_in_mask(&FIRST_LOC));
-        assert_eq!(root.merkle_root(), mask.merkle_root());
+            assert!(root.test_is_in_mask(&FIRST_LOC));
+            assert!(mask.test_is_in_mask(&FIRST_LOC));
+            assert_eq!(root.merkle_root(), mask.merkle_root());
+        }
     }
 
     // "add and retrieve a block of accounts"
     #[test]
     fn test_add_retrieve_block_of_accounts() {
-        let (root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let accounts = make_full_accounts(DEPTH);
+            let accounts = make_full_accounts(DEPTH);
 
-        for account in &accounts {
-            let account_id = account.id();
-            let res = mask
-                .get_or_create_account(account_id, account.clone())
-                .unwrap();
-            assert!(matches!(res, GetOrCreated::Added(_)));
-        }
+            for account in &accounts {
+                let account_id = account.id();
+                let res = mask
+                    .get_or_create_account(account_id, account.clone())
+                    .unwrap();
+                assert!(matches!(res, GetOrCreated::Added(_)));
+            }
 
-        let retrieved_accounts = mask
-            .get_all_accounts_rooted_at(Address::root())
-            .unwrap()
-            .into_iter()
-            .map(|(_, acc)| acc)
-            .collect::<Vec<_>>();
+            let retrieved_accounts = mask
+                .get_all_accounts_rooted_at(Address::root())
+                .unwrap()
+                .into_iter()
+                .map(|(_, acc)| acc)
+                .collect::<Vec<_>>();
 
-        assert_eq!(
-            accounts.into_iter().map(Box::new).collect::<Vec<_>>(),
-            retrieved_accounts
-        );
+            assert_eq!(
+                accounts.into_iter().map(Box::new).collect::<Vec<_>>(),
+                retrieved_accounts
+            );
+        }
     }
 
     // "removing accounts from mask restores Merkle root"
     #[test]
     fn test_removing_accounts_from_mask_restore_root_hash() {
-        let (root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let accounts = (0..5).map(|_| Account::rand()).collect::<Vec<_>>();
-        let accounts_ids = accounts.iter().map(Account::id).collect::<Vec<_>>();
-        let root_hash0 = mask.merkle_root();
+            let accounts = (0..5).map(|_| Account::rand()).collect::<Vec<_>>();
+            let accounts_ids = accounts.iter().map(Account::id).collect::<Vec<_>>();
+            let root_hash0 = mask.merkle_root();
 
-        for account in accounts {
-            mask.get_or_create_account(account.id(), account).unwrap();
-        }
-        assert_ne!(root_hash0, mask.merkle_root());
+            for account in accounts {
+                mask.get_or_create_account(account.id(), account).unwrap();
+            }
+            assert_ne!(root_hash0, mask.merkle_root());
 
-        mask.remove_accounts(&accounts_ids);
-        assert_eq!(root_hash0, mask.merkle_root());
+            mask.remove_accounts(&accounts_ids);
+            assert_eq!(root_hash0, mask.merkle_root());
+        }
     }
 
     // "removing accounts from parent restores Merkle root"
     #[test]
     fn test_removing_accounts_from_parent_restore_root_hash() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let accounts = (0..5).map(|_| Account::rand()).collect::<Vec<_>>();
-        let accounts_ids = accounts.iter().map(Account::id).collect::<Vec<_>>();
-        let root_hash0 = mask.merkle_root();
+            let accounts = (0..5).map(|_| Account::rand()).collect::<Vec<_>>();
+            let accounts_ids = accounts.iter().map(Account::id).collect::<Vec<_>>();
+            let root_hash0 = mask.merkle_root();
 
-        for account in accounts {
-            root.get_or_create_account(account.id(), account).unwrap();
-        }
-        assert_ne!(root_hash0, mask.merkle_root());
+            for account in accounts {
+                root.get_or_create_account(account.id(), account).unwrap();
+            }
+            assert_ne!(root_hash0, mask.merkle_root());
 
-        mask.remove_accounts(&accounts_ids);
-        assert_eq!(root_hash0, mask.merkle_root());
+            mask.remove_accounts(&accounts_ids);
+            assert_eq!(root_hash0, mask.merkle_root());
+        }
     }
 
     // "removing accounts from parent and mask restores Merkle root"
     #[test]
     fn test_removing_accounts_from_parent_and_mask_restore_root_hash() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let accounts = (0..10).map(|_| Account::rand()).collect::<Vec<_>>();
-        let (accounts_parent, accounts_mask) = accounts.split_at(5);
-        let accounts_ids = accounts.iter().map(Account::id).collect::<Vec<_>>();
+            let accounts = (0..10).map(|_| Account::rand()).collect::<Vec<_>>();
+            let (accounts_parent, accounts_mask) = accounts.split_at(5);
+            let accounts_ids = accounts.iter().map(Account::id).collect::<Vec<_>>();
 
-        let root_hash0 = mask.merkle_root();
+            let root_hash0 = mask.merkle_root();
 
-        for account in accounts_parent {
-            root.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
-        for account in accounts_mask {
-            mask.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
-        assert_ne!(root_hash0, mask.merkle_root());
+            for account in accounts_parent {
+                root.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
+            for account in accounts_mask {
+                mask.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
+            assert_ne!(root_hash0, mask.merkle_root());
 
-        mask.remove_accounts(&accounts_ids);
-        assert_eq!(root_hash0, mask.merkle_root());
+            mask.remove_accounts(&accounts_ids);
+            assert_eq!(root_hash0, mask.merkle_root());
+        }
     }
 
     // "fold of addition over account balances in parent and mask"
     #[test]
     fn test_fold_of_addition_over_account_balance_in_parent_and_mask() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let accounts = (0..10).map(|_| Account::rand()).collect::<Vec<_>>();
-        let balance = accounts
-            .iter()
-            .fold(0u128, |acc, account| acc + account.balance.as_u64() as u128);
+            let accounts = (0..10).map(|_| Account::rand()).collect::<Vec<_>>();
+            let balance = accounts
+                .iter()
+                .fold(0u128, |acc, account| acc + account.balance.as_u64() as u128);
 
-        let (accounts_parent, accounts_mask) = accounts.split_at(5);
+            let (accounts_parent, accounts_mask) = accounts.split_at(5);
 
-        for account in accounts_parent {
-            root.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
-        for account in accounts_mask {
-            mask.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
+            for account in accounts_parent {
+                root.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
+            for account in accounts_mask {
+                mask.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
 
-        let retrieved_balance =
-            mask.fold(0u128, |acc, account| acc + account.balance.as_u64() as u128);
-        assert_eq!(balance, retrieved_balance);
+            let retrieved_balance =
+                mask.fold(0u128, |acc, account| acc + account.balance.as_u64() as u128);
+            assert_eq!(balance, retrieved_balance);
+        }
     }
 
     fn create_existing_account(mask: &mut Mask, account: Account) {
@@ -1067,301 +1128,321 @@ mod tests_mask_ocaml {
     // "masking in to_list"
     #[test]
     fn test_masking_in_to_list() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
-
-        let mut accounts = (0..10).map(|_| Account::rand()).collect::<Vec<_>>();
-        // Make balances non-zero
-        let one = Balance::from_u64(1);
-        accounts
-            .iter_mut()
-            .for_each(|account| account.balance = account.balance.checked_add(&one).unwrap_or(one));
-
-        for account in &accounts {
-            root.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
+
+            let mut accounts = (0..10).map(|_| Account::rand()).collect::<Vec<_>>();
+            // Make balances non-zero
+            let one = Balance::from_u64(1);
+            accounts.iter_mut().for_each(|account| {
+                account.balance = account.balance.checked_add(&one).unwrap_or(one)
+            });
+
+            for account in &accounts {
+                root.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
 
-        let parent_list = root.to_list();
+            let parent_list = root.to_list();
 
-        // Make balances to zero for those same account
-        accounts
-            .iter_mut()
-            .for_each(|account| account.balance = Balance::zero());
+            // Make balances to zero for those same account
+            accounts
+                .iter_mut()
+                .for_each(|account| account.balance = Balance::zero());
 
-        for account in accounts {
-            create_existing_account(&mut mask, account);
-        }
+            for account in accounts {
+                create_existing_account(&mut mask, account);
+            }
 
-        let mask_list = mask.to_list();
-
-        assert_eq!(parent_list.len(), mask_list.len());
-        // Same accounts and order
-        assert_eq!(
-            parent_list.iter().map(Account::id).collect::<Vec<_>>(),
-            mask_list.iter().map(Account::id).collect::<Vec<_>>(),
-        );
-        // Balances of mask are zero
-        assert_eq!(
-            mask_list
-                .iter()
-                .fold(0u128, |acc, account| acc + account.balance.as_u64() as u128),
-            0
-        );
+            let mask_list = mask.to_list();
+
+            assert_eq!(parent_list.len(), mask_list.len());
+            // Same accounts and order
+            assert_eq!(
+                parent_list.iter().map(Account::id).collect::<Vec<_>>(),
+                mask_list.iter().map(Account::id).collect::<Vec<_>>(),
+            );
+            // Balances of mask are zero
+            assert_eq!(
+                mask_list
+                    .iter()
+                    .fold(0u128, |acc, account| acc + account.balance.as_u64() as u128),
+                0
+            );
+        }
     }
 
     // "masking in foldi"
     #[test]
     fn test_masking_in_to_foldi() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
-
-        let mut accounts = (0..10).map(|_| Account::rand()).collect::<Vec<_>>();
-        // Make balances non-zero
-        let one = Balance::from_u64(1);
-        accounts
-            .iter_mut()
-            .for_each(|account| account.balance = account.balance.checked_add(&one).unwrap_or(one));
-
-        for account in &accounts {
-            root.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
+
+            let mut accounts = (0..10).map(|_| Account::rand()).collect::<Vec<_>>();
+            // Make balances non-zero
+            let one = Balance::from_u64(1);
+            accounts.iter_mut().for_each(|account| {
+                account.balance = account.balance.checked_add(&one).unwrap_or(one)
+            });
+
+            for account in &accounts {
+                root.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
 
-        let parent_sum_balance =
-            root.fold(0u128, |acc, account| acc + account.balance.as_u64() as u128);
-        assert_ne!(parent_sum_balance, 0);
+            let parent_sum_balance =
+                root.fold(0u128, |acc, account| acc + account.balance.as_u64() as u128);
+            assert_ne!(parent_sum_balance, 0);
 
-        // Make balances to zero for those same account
-        accounts
-            .iter_mut()
-            .for_each(|account| account.balance = Balance::zero());
+            // Make balances to zero for those same account
+            accounts
+                .iter_mut()
+                .for_each(|account| account.balance = Balance::zero());
 
-        for account in accounts {
-            create_existing_account(&mut mask, account);
-        }
+            for account in accounts {
+                create_existing_account(&mut mask, account);
+            }
 
-        let mask_sum_balance =
-            mask.fold(0u128, |acc, account| acc + account.balance.as_u64() as u128);
-        assert_eq!(mask_sum_balance, 0);
+            let mask_sum_balance =
+                mask.fold(0u128, |acc, account| acc + account.balance.as_u64() as u128);
+            assert_eq!(mask_sum_balance, 0);
+        }
     }
 
     // "create_empty doesn't modify the hash"
     #[test]
     fn test_create_empty_doesnt_modify_the_hash() {
-        let (root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let start_hash = mask.merkle_root();
+            let start_hash = mask.merkle_root();
 
-        let account = Account::empty();
-        mask.get_or_create_account(account.id(), account).unwrap();
+            let account = Account::empty();
+            mask.get_or_create_account(account.id(), account).unwrap();
 
-        assert_eq!(mask.num_accounts(), 1);
-        assert_eq!(start_hash, mask.merkle_root());
+            assert_eq!(mask.num_accounts(), 1);
+            assert_eq!(start_hash, mask.merkle_root());
+        }
     }
 
     // "reuse of locations for removed accounts"
     #[test]
     fn test_reuse_of_locations_for_removed_accounts() {
-        let (root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let accounts = (0..10).map(|_| Account::rand()).collect::<Vec<_>>();
-        let accounts_ids = accounts.iter().map(Account::id).collect::<Vec<_>>();
+            let accounts = (0..10).map(|_| Account::rand()).collect::<Vec<_>>();
+            let accounts_ids = accounts.iter().map(Account::id).collect::<Vec<_>>();
 
-        assert!(mask.last_filled().is_none());
-        for account in accounts {
-            mask.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
-        assert!(mask.last_filled().is_some());
+            assert!(mask.last_filled().is_none());
+            for account in accounts {
+                mask.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
+            assert!(mask.last_filled().is_some());
 
-        mask.remove_accounts(&accounts_ids);
-        assert!(mask.last_filled().is_none());
+            mask.remove_accounts(&accounts_ids);
+            assert!(mask.last_filled().is_none());
+        }
     }
 
     // "num_accounts for unique keys in mask and parent"
     #[test]
     fn test_num_accounts_for_unique_keys_in_mask_and_parent() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let accounts = (0..10).map(|_| Account::rand()).collect::<Vec<_>>();
+            let accounts = (0..10).map(|_| Account::rand()).collect::<Vec<_>>();
 
-        for account in &accounts {
-            mask.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
+            for account in &accounts {
+                mask.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
 
-        let mask_num_accounts_before = mask.num_accounts();
+            let mask_num_accounts_before = mask.num_accounts();
 
-        // Add same accounts to parent
-        for account in &accounts {
-            root.get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
+            // Add same accounts to parent
+            for account in &accounts {
+                root.get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
 
-        let parent_num_accounts = root.num_accounts();
-        let mask_num_accounts_after = mask.num_accounts();
+            let parent_num_accounts = root.num_accounts();
+            let mask_num_accounts_after = mask.num_accounts();
 
-        assert_eq!(accounts.len(), parent_num_accounts);
-        assert_eq!(parent_num_accounts, mask_num_accounts_before);
-        assert_eq!(parent_num_accounts, mask_num_accounts_after);
+            assert_eq!(accounts.len(), parent_num_accounts);
+            assert_eq!(parent_num_accounts, mask_num_accounts_before);
+            assert_eq!(parent_num_accounts, mask_num_accounts_after);
+        }
     }
 
     // "Mask reparenting works"
     #[test]
     fn test_mask_reparenting_works() {
-        let (mut root, mut layer1, mut layer2) = new_chain(DEPTH);
+        for backend in TestBackend::ALL {
+            let (mut root, mut layer1, mut layer2) = new_chain(backend, DEPTH);
 
-        let acc1 = Account::rand();
-        let acc2 = Account::rand();
-        let acc3 = Account::rand();
+            let acc1 = Account::rand();
+            let acc2 = Account::rand();
+            let acc3 = Account::rand();
 
-        let loc1 = root.get_or_create_account(acc1.id(), acc1).unwrap().addr();
-        let loc2 = layer1
-            .get_or_create_account(acc2.id(), acc2)
-            .unwrap()
-            .addr();
-        let loc3 = layer2
-            .get_or_create_account(acc3.id(), acc3)
-            .unwrap()
-            .addr();
+            let loc1 = root.get_or_create_account(acc1.id(), acc1).unwrap().addr();
+            let loc2 = layer1
+                .get_or_create_account(acc2.id(), acc2)
+                .unwrap()
+                .addr();
+            let loc3 = layer2
+                .get_or_create_account(acc3.id(), acc3)
+                .unwrap()
+                .addr();
 
-        // All accounts are accessible from layer2
-        assert!(layer2.get(loc1.clone()).is_some());
-        assert!(layer2.get(loc2.clone()).is_some());
-        assert!(layer2.get(loc3.clone()).is_some());
+            // All accounts are accessible from layer2
+            assert!(layer2.get(loc1.clone()).is_some());
+            assert!(layer2.get(loc2.clone()).is_some());
+            assert!(layer2.get(loc3.clone()).is_some());
 
-        // acc1 is in root
-        assert!(root.get(loc1.clone()).is_some());
+            // acc1 is in root
+            assert!(root.get(loc1.clone()).is_some());
 
-        layer1.commit();
+            layer1.commit();
 
-        // acc2 is in root
-        assert!(root.get(loc2.clone()).is_some());
+            // acc2 is in root
+            assert!(root.get(loc2.clone()).is_some());
 
-        layer1.remove_and_reparent();
+            layer1.remove_and_reparent();
 
-        // acc1, acc2 are in root
-        assert!(root.get(loc1.clone()).is_some());
-        assert!(root.get(loc2.clone()).is_some());
+            // acc1, acc2 are in root
+            assert!(root.get(loc1.clone()).is_some());
+            assert!(root.get(loc2.clone()).is_some());
 
-        // acc3 not in root
-        assert!(root.get(loc3.clone()).is_none());
+            // acc3 not in root
+            assert!(root.get(loc3.clone()).is_none());
 
-        // All accounts are accessible from layer2
-        assert!(layer2.get(loc1).is_some());
-        assert!(layer2.get(loc2).is_some());
-        assert!(layer2.get(loc3).is_some());
+            // All accounts are accessible from layer2
+            assert!(layer2.get(loc1).is_some());
+            assert!(layer2.get(loc2).is_some());
+            assert!(layer2.get(loc3).is_some());
+        }
     }
 
     // "setting an account in the parent doesn't remove the masked
     // copy if the mask is still dirty for that account"
     #[test]
     fn test_set_account_in_parent_doesnt_remove_if_mask_is_dirty() {
-        let (mut root, mask) = new_instances(DEPTH);
-        let mut mask = root.register_mask(mask);
+        for backend in TestBackend::ALL {
+            let (mut root, mask) = new_instances(backend, DEPTH);
+            let mut mask = root.register_mask(mask);
 
-        let mut account = Box::new(Account::rand());
-        let mut account2 = account.clone();
+            let mut account = Box::new(Account::rand());
+            let mut account2 = account.clone();
 
-        account.balance = Balance::from_u64(10);
-        account2.balance = Balance::from_u64(5);
+            account.balance = Balance::from_u64(10);
+            account2.balance = Balance::from_u64(5);
 
-        let loc = mask
-            .get_or_create_account(account.id(), *account.clone())
-            .unwrap()
-            .addr();
+            let loc = mask
+                .get_or_create_account(account.id(), *account.clone())
+                .unwrap()
+                .addr();
 
-        root.set(loc.clone(), account2);
+            root.set(loc.clone(), account2);
 
-        assert_eq!(mask.get(loc).unwrap(), account);
+            assert_eq!(mask.get(loc).unwrap(), account);
+        }
     }
 
     // "get_all_accounts should preserve the ordering of accounts by
     // location with noncontiguous updates of accounts on the mask"
     #[test]
     fn test_get_all_accounts_should_preserve_ordering() {
-        let (_root, mut layer1, mut layer2) = new_chain(DEPTH);
+        for backend in TestBackend::ALL {
+            let (_root, mut layer1, mut layer2) = new_chain(backend, DEPTH);
 
-        let accounts = make_full_accounts(DEPTH);
+            let accounts = make_full_accounts(DEPTH);
 
-        for account in &accounts {
-            layer1
-                .get_or_create_account(account.id(), account.clone())
-                .unwrap();
-        }
+            for account in &accounts {
+                layer1
+                    .get_or_create_account(account.id(), account.clone())
+                    .unwrap();
+            }
+
+            let mut updated_accounts = accounts.clone();
+            let mut rng = thread_rng();
+            let mut nmodified = 0;
 
-        let mut updated_accounts = accounts.clone();
-        let mut rng = thread_rng();
-        let mut nmodified = 0;
+            for account in updated_accounts.iter_mut() {
+                if rng.gen::<u8>() >= 150 {
+                    continue;
+                }
+                account.balance = rng.gen();
 
-        for account in updated_accounts.iter_mut() {
-            if rng.gen::<u8>() >= 150 {
-                continue;
+                create_existing_account(&mut layer2, account.clone());
+                nmodified += 1;
             }
-            account.balance = rng.gen();
 
-            create_existing_account(&mut layer2, account.clone());
-            nmodified += 1;
+            assert!(nmodified > 0);
+            assert_eq!(
+                updated_accounts
+                    .into_iter()
+                    .map(Box::new)
+                    .collect::<Vec<_>>(),
+                layer2
+                    .get_all_accounts_rooted_at(Address::root())
+                    .unwrap()
+                    .into_iter()
+                    .map(|(_, account)| account)
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                accounts.into_iter().map(Box::new).collect::<Vec<_>>(),
+                layer1
+                    .get_all_accounts_rooted_at(Address::root())
+                    .unwrap()
+                    .into_iter()
+                    .map(|(_, account)| account)
+                    .collect::<Vec<_>>()
+            );
         }
-
-        assert!(nmodified > 0);
-        assert_eq!(
-            updated_accounts
-                .into_iter()
-                .map(Box::new)
-                .collect::<Vec<_>>(),
-            layer2
-                .get_all_accounts_rooted_at(Address::root())
-                .unwrap()
-                .into_iter()
-                .map(|(_, account)| account)
-                .collect::<Vec<_>>()
-        );
-        assert_eq!(
-            accounts.into_iter().map(Box::new).collect::<Vec<_>>(),
-            layer1
-                .get_all_accounts_rooted_at(Address::root())
-                .unwrap()
-                .into_iter()
-                .map(|(_, account)| account)
-                .collect::<Vec<_>>()
-        );
     }
 
     #[test]
     fn test_validate_inner_hashes() {
-        let l = Address::first(1);
-        assert_eq!(l.parent().unwrap(), Address::root());
-        assert_eq!(l.parent(), l.next().unwrap().parent());
-        let (root, layer1, layer2) = new_chain(DEPTH);
-
-        let accounts = make_full_accounts(DEPTH);
-
-        for (i, mut mask) in [root, layer1, layer2].into_iter().enumerate() {
-            for account in accounts.iter().skip(i) {
-                mask.get_or_create_account(account.id(), account.clone())
-                    .unwrap();
+        for backend in TestBackend::ALL {
+            let l = Address::first(1);
+            assert_eq!(l.parent().unwrap(), Address::root());
+            assert_eq!(l.parent(), l.next().unwrap().parent());
+            let (root, layer1, layer2) = new_chain(backend, DEPTH);
+
+            let accounts = make_full_accounts(DEPTH);
+
+            for (i, mut mask) in [root, layer1, layer2].into_iter().enumerate() {
+                for account in accounts.iter().skip(i) {
+                    mask.get_or_create_account(account.id(), account.clone())
+                        .unwrap();
+                }
+                dbg!(mask.merkle_root());
+                mask.validate_inner_hashes().unwrap();
             }
-            dbg!(mask.merkle_root());
-            mask.validate_inner_hashes().unwrap();
         }
     }
 
     #[test]
     fn test_nmasks_to_root() {
-        let (root, layer1, layer2) = new_chain(DEPTH);
-        assert_eq!(root.nmasks_to_root(), 0);
-        assert_eq!(layer1.nmasks_to_root(), 1);
-        assert_eq!(layer2.nmasks_to_root(), 2);
-
-        let mut mask = layer2;
-        for index in 0..300 {
-            assert_eq!(mask.nmasks_to_root(), 2 + index);
-            mask = mask.make_child();
+        for backend in TestBackend::ALL {
+            let (root, layer1, layer2) = new_chain(backend, DEPTH);
+            assert_eq!(root.nmasks_to_root(), 0);
+            assert_eq!(layer1.nmasks_to_root(), 1);
+            assert_eq!(layer2.nmasks_to_root(), 2);
+
+            let mut mask = layer2;
+            for index in 0..300 {
+                assert_eq!(mask.nmasks_to_root(), 2 + index);
+                mask = mask.make_child();
+            }
         }
     }
 }
@@ -56,11 +56,13 @@ impl Drop for MaskImpl {
     }
 }
 
-impl Clone for MaskImpl {
-    fn clone(&self) -> Self {
-        match self {
+impl MaskImpl {
+    /// Copies the mask, a root database stored on disk is copied into a
+    /// new directory, which can fail.
+    pub(super) fn try_clone(&self) -> std::io::Result<Self> {
+        Ok(match self {
             Self::Root { database, childs } => Self::Root {
-                database: database.clone_db(database.get_directory().unwrap()),
+                database: database.clone_db(database.get_directory().unwrap())?,
                 childs: childs.clone(),
             },
             Self::Attached {
@@ -99,6 +101,15 @@ impl Clone for MaskImpl {
                 hashes: hashes.clone(),
                 uuid: next_uuid(),
             },
+        })
+    }
+
+    /// See [`super::Mask::flush`]
+    pub(super) fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Root { database, .. } => database.flush(),
+            Self::Attached { parent, .. } => parent.flush(),
+            Self::Unattached { .. } => Ok(()),
         }
     }
 }
@@ -28,12 +28,18 @@ const BUFFER_DEFAULT_CAPACITY: usize = 4096;
 const DATABASE_VERSION: u64 = 1;
 const DATABASE_VERSION_NBYTES: usize = 8;
 
+const FILE_BUFFER_CAPACITY: usize = 4 * 1024 * 1024; // 4 MB
+
 pub struct Database {
     uuid: Uuid,
-    /// Index of keys to their values offset
-    index: HashMap<Key, Offset>,
+    /// Index of keys to their entries
+    index: HashMap<Key, IndexEntry>,
+    /// Sum of the length of the entries in the index
+    live_bytes: u64,
     /// Points to end of file
     current_file_offset: Offset,
+    compaction_config: Option<CompactionConfig>,
+    compaction: Option<RunningCompaction>,
     file: BufWriter<LockedFile>,
     /// Read buffer
     buffer: Vec<u8>,
@@ -41,6 +47,72 @@ pub struct Database {
     filename: PathBuf,
 }
 
+#[derive(Debug, Clone, Copy)]
+struct IndexEntry {
+    header_offset: Offset,
+    /// Length of the entry, including its header
+    length: u64,
+}
+
+/// Settings of the automatic compaction, see [`Database::set_compaction`].
+#[derive(Debug, Clone, Copy)]
+pub struct CompactionConfig {
+    /// Ratio of the file occupied by overwritten or removed entries above
+    /// which the file gets compacted.
+    pub dead_space_ratio: f64,
+    /// Files smaller than this are never compacted.
+    pub min_file_size: u64,
+}
+
+impl Default for CompactionConfig {
+    fn default() -> Self {
+        Self {
+            dead_space_ratio: 0.5,
+            min_file_size: 64 * 1024 * 1024, // 64 MB
+        }
+    }
+}
+
+/// Compaction running in a background thread.
+///
+/// The thread copies the live entries, as of `end_offset`, into a new file.
+/// Once it's done, the entries written since then are appended to the new
+/// file, which then replaces the current one.
+struct RunningCompaction {
+    handle: std::thread::JoinHandle<std::io::Result<HashMap<Key, IndexEntry>>>,
+    end_offset: Offset,
+    filename: PathBuf,
+}
+
+/// Summary of a database file, see [`Database::verify`].
+#[derive(Debug, Clone)]
+pub struct VerifyReport {
+    pub file_size: u64,
+    /// Number of valid entries, including the overwritten and removed ones
+    pub entries: u64,
+    /// Number of keys having a value
+    pub keys: usize,
+    /// Length of the entries which aren't overwritten or removed
+    pub live_bytes: u64,
+    /// Offset of the first corrupted or truncated entry, and why it is invalid.
+    pub corruption: Option<(u64, String)>,
+    /// Whether the invalid entry is the last one, as left by a torn write.
+    /// Opening the database truncates the file before it, any other
+    /// corruption makes opening fail.
+    pub torn_tail: bool,
+}
+
+impl VerifyReport {
+    /// Length of the entries which are overwritten or removed
+    pub fn dead_bytes(&self) -> u64 {
+        let end = match &self.corruption {
+            Some((offset, _)) => *offset,
+            None => self.file_size,
+        };
+        end.saturating_sub(DATABASE_VERSION_NBYTES as u64 + self.live_bytes)
+    }
+}
+
 /// Compute crc32 of an entry
 ///
 /// This is used to verify data corruption
@@ -184,7 +256,7 @@ impl EntryHeader {
         let crc32 = compute_crc32(self, key_bytes, value_bytes);
 
         if crc32 != self.crc32 {
-            return Err(InvalidData.into());
+            return Err(std::io::Error::new(InvalidData, "checksum mismatch"));
         }
 
         Ok(())
@@ -225,6 +297,188 @@ fn ensure_buffer_length(buffer: &mut Vec<u8>, length: usize) {
     }
 }
 
+/// Reads and checks the version at the beginning of the file
+fn read_version(reader: &mut impl std::io::Read) -> std::io::Result<()> {
+    let mut bytes = [0; DATABASE_VERSION_NBYTES];
+    reader.read_exact(&mut bytes)?;
+
+    if read_u64(&bytes)? != DATABASE_VERSION {
+        return Err(std::io::Error::new(Other, "Incompatible database"));
+    }
+    Ok(())
+}
+
+/// Why reading the entries stopped before the end of the file
+#[derive(Debug)]
+enum ReadEntriesError {
+    /// The last entry is incomplete or its checksum doesn't match, as left
+    /// by a torn write. Discarding it recovers the database.
+    TornTail(std::io::Error),
+    /// An entry followed by other entries is invalid.
+    Corrupted(std::io::Error),
+    /// Reading the file failed.
+    Io(std::io::Error),
+}
+
+impl ReadEntriesError {
+    /// Errors of the reader, only a premature end of file means that the
+    /// last entry is torn.
+    fn read(error: std::io::Error) -> Self {
+        match error.kind() {
+            UnexpectedEof => Self::TornTail(error),
+            _ => Self::Io(error),
+        }
+    }
+}
+
+impl std::fmt::Display for ReadEntriesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TornTail(e) => write!(f, "torn last entry: {e}"),
+            Self::Corrupted(e) => write!(f, "corrupted entry: {e}"),
+            Self::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+/// Reads the entries following the version, up to `eof`, and calls `on_entry`
+/// for each of them, with its key and whether it's a removal.
+///
+/// Returns the offset where reading stopped, along with the error which
+/// stopped it before `eof`.
+fn read_entries<F>(
+    reader: &mut impl std::io::Read,
+    eof: Offset,
+    mut on_entry: F,
+) -> (Offset, Option<ReadEntriesError>)
+where
+    F: FnMut(Key, IndexEntry, bool),
+{
+    use ReadEntriesError::{Corrupted, TornTail};
+
+    let mut current_offset = DATABASE_VERSION_NBYTES as u64;
+    let mut bytes = vec![0; BUFFER_DEFAULT_CAPACITY];
+
+    let mut read_entry = |current_offset: Offset| -> Result<u64, ReadEntriesError> {
+        ensure_buffer_length(&mut bytes, EntryHeader::NBYTES);
+        reader
+            .read_exact(&mut bytes[..EntryHeader::NBYTES])
+            .map_err(ReadEntriesError::read)?;
+
+        let header = EntryHeader::read(&bytes).map_err(Corrupted)?;
+        let entry_length = header.entry_length().map_err(Corrupted)?;
+        let length = EntryHeader::NBYTES as u64 + entry_length;
+        let end_offset = current_offset.saturating_add(length);
+        if end_offset > eof {
+            return Err(TornTail(UnexpectedEof.into()));
+        }
+        let entry_length = entry_length as usize;
+        let key_length = header.key_length as usize;
+
+        ensure_buffer_length(&mut bytes, entry_length);
+        reader
+            .read_exact(&mut bytes[..entry_length])
+            .map_err(ReadEntriesError::read)?;
+
+        let (key_bytes, value_bytes) = bytes[..entry_length].split_at(key_length);
+
+        header
+            .verify_checksum(key_bytes, value_bytes)
+            .map_err(|e| {
+                if end_offset == eof {
+                    TornTail(e)
+                } else {
+                    Corrupted(e)
+                }
+            })?;
+
+        let key = decompress(key_bytes, header.key_is_compressed).map_err(Corrupted)?;
+        let entry = IndexEntry {
+            header_offset: current_offset,
+            length,
+        };
+        on_entry(key, entry, header.is_removed);
+
+        Ok(length)
+    };
+
+    while current_offset < eof {
+        match read_entry(current_offset) {
+            Ok(length) => current_offset += length,
+            Err(e) => return (current_offset, Some(e)),
+        }
+    }
+
+    (current_offset, None)
+}
+
+/// Updates the index with an entry, and the length of the live entries
+fn index_entry(
+    index: &mut HashMap<Key, IndexEntry>,
+    live_bytes: &mut u64,
+    key: Key,
+    entry: IndexEntry,
+    is_removed: bool,
+) {
+    let previous = if is_removed {
+        index.remove(&key)
+    } else {
+        *live_bytes += entry.length;
+        index.insert(key, entry)
+    };
+
+    if let Some(previous) = previous {
+        *live_bytes -= previous.length;
+    }
+}
+
+/// Copies the entries from the `source` file into a new `target` file,
+/// returning their index in it
+#[cfg(not(target_family = "wasm"))]
+fn compact_into(
+    source: &Path,
+    target: &Path,
+    mut entries: Vec<(Key, IndexEntry)>,
+) -> std::io::Result<HashMap<Key, IndexEntry>> {
+    // Read the source sequentially
+    entries.sort_unstable_by_key(|(_, entry)| entry.header_offset);
+
+    let mut source = File::open(source)?;
+    let target = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(target)?;
+    let mut target = BufWriter::with_capacity(FILE_BUFFER_CAPACITY, target);
+
+    target.write_all(&DATABASE_VERSION.to_le_bytes())?;
+
+    let mut offset = DATABASE_VERSION_NBYTES as u64;
+    let mut index = HashMap::with_capacity(entries.len());
+    let mut buffer = Vec::with_capacity(BUFFER_DEFAULT_CAPACITY);
+
+    for (key, entry) in entries {
+        let length = entry.length as usize;
+        ensure_buffer_length(&mut buffer, length);
+        read_exact_at(&mut source, &mut buffer[..length], entry.header_offset)?;
+        target.write_all(&buffer[..length])?;
+
+        index.insert(
+            key,
+            IndexEntry {
+                header_offset: offset,
+                length: entry.length,
+            },
+        );
+        offset += entry.length;
+    }
+
+    target.flush()?;
+    target.get_ref().sync_all()?;
+
+    Ok(index)
+}
+
 #[cfg(unix)]
 fn read_exact_at(file: &mut File, buffer: &mut [u8], offset: Offset) -> std::io::Result<()> {
     use std::os::unix::prelude::FileExt;
@@ -264,9 +518,12 @@ impl Database {
     ///
     ///   * Unable to open or create the directory.
     ///   * Another process is already using the database.
-    ///   * The database is corrupted (when the path contains an existing database).
     ///   * The database version is incompatible
     ///
+    /// A torn last entry in an existing database isn't an error: the file is
+    /// truncated before it, see [`Database::verify`]. Any other invalid entry
+    /// is an error.
+    ///
     pub fn create(directory: impl AsRef<Path>) -> std::io::Result<Self> {
         Self::create_impl(directory, CreateMode::Regular)
     }
@@ -305,17 +562,22 @@ impl Database {
         Ok(Self {
             uuid: next_uuid(),
             index: HashMap::with_capacity(128),
+            live_bytes: 0,
             current_file_offset: DATABASE_VERSION_NBYTES as u64,
-            file: BufWriter::with_capacity(4 * 1024 * 1024, file), // 4 MB
+            compaction_config: Some(CompactionConfig::default()),
+            compaction: None,
+            file: BufWriter::with_capacity(FILE_BUFFER_CAPACITY, file),
             buffer: Vec::with_capacity(BUFFER_DEFAULT_CAPACITY),
             filename,
         })
     }
 
     /// Reload the database at the specified path
+    ///
+    /// When the file ends with an incomplete entry, or one whose checksum
+    /// doesn't match, as left by a torn write, the file is truncated before
+    /// it. Any other invalid entry or read error is returned.
     fn reload(filename: PathBuf) -> std::io::Result<Self> {
-        use std::io::Read;
-
         let mut file = LockedFile::try_open_exclusively(
             &filename,
             OpenOptions::new()
@@ -325,69 +587,105 @@ impl Database {
                 .create_new(false),
         )?;
 
-        let mut current_offset = 0;
         let eof = file.seek(SeekFrom::End(0))?;
 
         file.seek(SeekFrom::Start(0))?;
 
-        let mut reader = BufReader::with_capacity(4 * 1024 * 1024, file); // 4 MB
-        let mut bytes = vec![0; BUFFER_DEFAULT_CAPACITY];
+        let mut reader = BufReader::with_capacity(FILE_BUFFER_CAPACITY, file);
 
-        // Check if the database is the same version
-        {
-            reader.read_exact(&mut bytes[..DATABASE_VERSION_NBYTES])?;
-            let database_version = read_u64(&bytes)?;
-            if database_version != DATABASE_VERSION {
-                return Err(std::io::Error::new(Other, "Incompatible database"));
-            }
-            current_offset += DATABASE_VERSION_NBYTES as u64;
-        }
+        read_version(&mut reader)?;
 
         let mut index = HashMap::with_capacity(256);
-
-        while current_offset < eof {
-            let header_offset = current_offset;
-
-            ensure_buffer_length(&mut bytes, EntryHeader::NBYTES);
-            reader.read_exact(&mut bytes[..EntryHeader::NBYTES])?;
-
-            let header = EntryHeader::read(&bytes)?;
-            let entry_length = header.entry_length()? as usize;
-            let key_length = header.key_length as usize;
-
-            ensure_buffer_length(&mut bytes, entry_length);
-            reader.read_exact(&mut bytes[..entry_length])?;
-
-            ensure_buffer_length(&mut bytes, entry_length);
-            let (key_bytes, value_bytes) = bytes[..entry_length].split_at(key_length);
-
-            header.verify_checksum(key_bytes, value_bytes)?;
-
-            let key = decompress(key_bytes, header.key_is_compressed)?;
-
-            if header.is_removed {
-                index.remove(&key);
-            } else {
-                index.insert(key, header_offset);
+        let mut live_bytes = 0;
+
+        let (current_file_offset, error) =
+            read_entries(&mut reader, eof, |key, entry, is_removed| {
+                index_entry(&mut index, &mut live_bytes, key, entry, is_removed)
+            });
+
+        let file = reader.into_inner();
+
+        if let Some(error) = error {
+            match error {
+                ReadEntriesError::TornTail(_) => {}
+                ReadEntriesError::Corrupted(_) => {
+                    return Err(std::io::Error::new(
+                        InvalidData,
+                        format!("{filename:?} at offset {current_file_offset}: {error}"),
+                    ));
+                }
+                ReadEntriesError::Io(e) => return Err(e),
             }
-
-            current_offset += (EntryHeader::NBYTES + entry_length) as u64;
-        }
-
-        if eof != current_offset {
-            return Err(UnexpectedEof.into());
+            openmina_core::warn!(
+                openmina_core::log::system_time();
+                message = "Truncating database at its torn last entry",
+                filename = format!("{filename:?}"),
+                offset = current_file_offset,
+                truncated_bytes = eof - current_file_offset,
+                error = error.to_string()
+            );
+            file.set_len(current_file_offset)?;
+            file.sync_all()?;
         }
 
         Ok(Self {
             uuid: next_uuid(),
             index,
-            current_file_offset: eof,
-            file: BufWriter::with_capacity(4 * 1024 * 1024, reader.into_inner()), // 4 MB
+            live_bytes,
+            current_file_offset,
+            compaction_config: Some(CompactionConfig::default()),
+            compaction: None,
+            file: BufWriter::with_capacity(FILE_BUFFER_CAPACITY, file),
             buffer: Vec::with_capacity(BUFFER_DEFAULT_CAPACITY),
             filename,
         })
     }
 
+    /// Checks the integrity of the database in the specified directory,
+    /// without modifying it.
+    pub fn verify(directory: impl AsRef<Path>) -> std::io::Result<VerifyReport> {
+        let mut file = File::open(directory.as_ref().join("db"))?;
+        let file_size = file.seek(SeekFrom::End(0))?;
+        file.seek(SeekFrom::Start(0))?;
+
+        let mut reader = BufReader::with_capacity(FILE_BUFFER_CAPACITY, file);
+
+        read_version(&mut reader)?;
+
+        let mut index = HashMap::with_capacity(256);
+        let mut live_bytes = 0;
+        let mut entries = 0;
+
+        let (offset, error) = read_entries(&mut reader, file_size, |key, entry, is_removed| {
+            entries += 1;
+            index_entry(&mut index, &mut live_bytes, key, entry, is_removed)
+        });
+        let torn_tail = matches!(error, Some(ReadEntriesError::TornTail(_)));
+        if let Some(ReadEntriesError::Io(e)) = error {
+            return Err(e);
+        }
+
+        Ok(VerifyReport {
+            file_size,
+            entries,
+            keys: index.len(),
+            live_bytes,
+            corruption: error.map(|error| (offset, error.to_string())),
+            torn_tail,
+        })
+    }
+
+    /// Size of the file
+    pub fn file_size(&self) -> u64 {
+        self.current_file_offset
+    }
+
+    /// Length of the entries which are overwritten or removed, and get
+    /// discarded by a compaction
+    pub fn dead_bytes(&self) -> u64 {
+        self.current_file_offset - DATABASE_VERSION_NBYTES as u64 - self.live_bytes
+    }
+
     /// Retrieves the UUID of the current database instance.
     ///
     /// # Returns
@@ -404,6 +702,11 @@ impl Database {
         // NOTE: `close` is actually implemented at the ffi level, where `Self` is dropped
     }
 
+    /// Returns an iterator over the keys of all the entries in the database.
+    pub fn keys(&self) -> impl Iterator<Item = &[u8]> {
+        self.index.keys().map(|key| &**key)
+    }
+
     fn read_header(&mut self, header_offset: Offset) -> std::io::Result<EntryHeader> {
         ensure_buffer_length(&mut self.buffer, EntryHeader::NBYTES);
         read_exact_at(
@@ -435,8 +738,8 @@ impl Database {
     pub fn get(&mut self, key: &[u8]) -> std::io::Result<Option<Value>> {
         // Note: `&mut self` is required for `File::seek`
 
-        let header_offset = match self.index.get(key).copied() {
-            Some(header_offset) => header_offset,
+        let header_offset = match self.index.get(key) {
+            Some(entry) => entry.header_offset,
             None => return Ok(None),
         };
 
@@ -474,11 +777,17 @@ impl Database {
         self.current_file_offset += buffer_len;
 
         // Update index
-        if is_removed {
-            self.index.remove(&key);
-        } else {
-            self.index.insert(key, header_offset);
-        }
+        let entry = IndexEntry {
+            header_offset,
+            length: buffer_len,
+        };
+        index_entry(
+            &mut self.index,
+            &mut self.live_bytes,
+            key,
+            entry,
+            is_removed,
+        );
 
         Ok(())
     }
@@ -496,7 +805,7 @@ impl Database {
     pub fn set(&mut self, key: Key, value: Value) -> std::io::Result<()> {
         self.set_impl(key, Some(value))?;
         self.flush()?;
-        Ok(())
+        self.compact_if_needed()
     }
 
     /// Processes multiple entries (key-value pairs) to set and keys to remove in
@@ -524,8 +833,7 @@ impl Database {
         }
 
         self.flush()?;
-
-        Ok(())
+        self.compact_if_needed()
     }
 
     /// Fetches a batch of values for the given keys.
@@ -608,7 +916,8 @@ impl Database {
     ///   otherwise returns an error.
     pub fn remove(&mut self, key: Key) -> std::io::Result<()> {
         self.remove_impl(key)?;
-        self.flush()
+        self.flush()?;
+        self.compact_if_needed()
     }
 
     /// Retrieves all entries (key-value pairs) from the database.
@@ -651,7 +960,8 @@ impl Database {
             }
         }
 
-        self.flush()
+        self.flush()?;
+        self.compact_if_needed()
     }
 
     /// Triggers garbage collection for the database, cleaning up obsolete
@@ -662,6 +972,8 @@ impl Database {
     /// * `Result<()>` - Returns () if garbage collection is successful,
     ///   otherwise returns an error.
     pub fn gc(&mut self) -> std::io::Result<()> {
+        self.compaction_wait()?;
+
         let directory = self.filename.parent().unwrap();
         let mut new_db = Self::create_impl(directory, CreateMode::Temporary)?;
 
@@ -678,11 +990,158 @@ impl Database {
 
         new_db.filename.clone_from(&self.filename);
         new_db.uuid.clone_from(&self.uuid);
+        new_db.compaction_config = self.compaction_config;
 
         *self = new_db;
 
         Ok(())
     }
+
+    /// Sets the settings of the automatic compaction, `None` disables it.
+    ///
+    /// The compaction runs in a background thread once overwritten and
+    /// removed entries occupy too much of the file, the database remains
+    /// usable meanwhile.
+    pub fn set_compaction(&mut self, config: Option<CompactionConfig>) {
+        self.compaction_config = config;
+    }
+
+    fn compaction_needed(&self) -> bool {
+        let Some(config) = self.compaction_config.as_ref() else {
+            return false;
+        };
+        let file_size = self.file_size();
+
+        file_size >= config.min_file_size
+            && self.dead_bytes() as f64 >= file_size as f64 * config.dead_space_ratio
+    }
+
+    /// Completes the running compaction when it's done, otherwise starts
+    /// one if needed.
+    fn compact_if_needed(&mut self) -> std::io::Result<()> {
+        match self.compaction.as_ref() {
+            Some(compaction) if compaction.handle.is_finished() => self.compaction_wait(),
+            Some(_) => Ok(()),
+            None if self.compaction_needed() => self.compaction_start(),
+            None => Ok(()),
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn compaction_start(&mut self) -> std::io::Result<()> {
+        let source = self.filename.clone();
+        let filename = self.filename.with_file_name("db_compaction");
+        let target = filename.clone();
+        let entries = self
+            .index
+            .iter()
+            .map(|(key, entry)| (key.clone(), *entry))
+            .collect();
+
+        let handle = std::thread::Builder::new()
+            .name("ondisk-compaction".to_string())
+            .spawn(move || compact_into(&source, &target, entries))?;
+
+        self.compaction = Some(RunningCompaction {
+            handle,
+            end_offset: self.current_file_offset,
+            filename,
+        });
+        Ok(())
+    }
+
+    #[cfg(target_family = "wasm")]
+    fn compaction_start(&mut self) -> std::io::Result<()> {
+        // No threads
+        self.gc()
+    }
+
+    /// Waits for the running compaction, if any, and replaces the file
+    /// with the compacted one.
+    ///
+    /// A failed compaction is logged and discarded, the current file stays
+    /// intact.
+    fn compaction_wait(&mut self) -> std::io::Result<()> {
+        let Some(compaction) = self.compaction.take() else {
+            return Ok(());
+        };
+
+        let result = compaction
+            .handle
+            .join()
+            .unwrap_or_else(|_| Err(std::io::Error::new(Other, "compaction thread panicked")))
+            .and_then(|index| {
+                self.compaction_finish(index, compaction.end_offset, &compaction.filename)
+            });
+
+        if let Err(error) = result {
+            openmina_core::error!(
+                openmina_core::log::system_time();
+                message = "Database compaction failed",
+                filename = format!("{:?}", self.filename),
+                error = error.to_string()
+            );
+            let _ = std::fs::remove_file(&compaction.filename);
+        }
+
+        Ok(())
+    }
+
+    fn compaction_finish(
+        &mut self,
+        compacted_index: HashMap<Key, IndexEntry>,
+        end_offset: Offset,
+        filename: &Path,
+    ) -> std::io::Result<()> {
+        let mut file = LockedFile::try_open_exclusively(
+            filename,
+            OpenOptions::new()
+                .read(true)
+                .write(true)
+                .append(true)
+                .create_new(false),
+        )?;
+        let compacted_end = file.seek(SeekFrom::End(0))?;
+
+        // Append the entries written since the compaction started
+        self.file.flush()?;
+        let mut offset = end_offset;
+        while offset < self.current_file_offset {
+            let length = (self.current_file_offset - offset).min(FILE_BUFFER_CAPACITY as u64);
+            let bytes = self.read_value(offset, length as usize)?;
+            file.write_all(bytes)?;
+            offset += length;
+        }
+        file.sync_all()?;
+
+        let index = self
+            .index
+            .iter()
+            .map(|(key, entry)| {
+                let header_offset = if entry.header_offset >= end_offset {
+                    entry.header_offset - end_offset + compacted_end
+                } else {
+                    compacted_index
+                        .get(key)
+                        .ok_or_else(|| std::io::Error::new(InvalidData, "missing compacted entry"))?
+                        .header_offset
+                };
+                let entry = IndexEntry {
+                    header_offset,
+                    length: entry.length,
+                };
+                Ok((key.clone(), entry))
+            })
+            .collect::<std::io::Result<_>>()?;
+
+        exchange_file_atomically(&self.filename, filename)?;
+
+        self.index = index;
+        self.current_file_offset = compacted_end + (self.current_file_offset - end_offset);
+        self.file = BufWriter::with_capacity(FILE_BUFFER_CAPACITY, file);
+
+        Ok(())
+    }
 }
 
 #[cfg(not(target_os = "linux"))]
@@ -939,6 +1398,145 @@ mod tests {
         assert_eq!(db.get(&key("a")).unwrap().unwrap(), value("b"));
     }
 
+    #[test]
+    fn test_truncate_invalid_tail() {
+        let db_dir = TempDir::new();
+        let filename = db_dir.as_path().join("db");
+
+        let valid_size = {
+            let mut db = Database::create(db_dir.as_path()).unwrap();
+            db.set(key("a"), value("abc")).unwrap();
+            db.set(key("b"), value("def")).unwrap();
+            db.file_size()
+        };
+
+        // Torn write: the last entry is incomplete
+        {
+            let mut db = Database::create(db_dir.as_path()).unwrap();
+            db.set(key("c"), value("ghi")).unwrap();
+        }
+        let file = OpenOptions::new().write(true).open(&filename).unwrap();
+        file.set_len(std::fs::metadata(&filename).unwrap().len() - 2)
+            .unwrap();
+
+        let report = Database::verify(db_dir.as_path()).unwrap();
+        assert_eq!(report.keys, 2);
+        assert_eq!(report.corruption.as_ref().unwrap().0, valid_size);
+        assert!(report.torn_tail);
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        assert_eq!(db.file_size(), valid_size);
+        assert_eq!(db.get(&key("b")).unwrap().unwrap(), value("def"));
+        assert!(db.get(&key("c")).unwrap().is_none());
+        drop(db);
+
+        // Checksum mismatch of the last entry
+        let mut bytes = std::fs::read(&filename).unwrap();
+        *bytes.last_mut().unwrap() ^= 0xff;
+        std::fs::write(&filename, bytes).unwrap();
+
+        let report = Database::verify(db_dir.as_path()).unwrap();
+        assert_eq!(report.keys, 1);
+        assert!(report.corruption.is_some());
+        assert!(report.torn_tail);
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        assert_eq!(db.get(&key("a")).unwrap().unwrap(), value("abc"));
+        assert!(db.get(&key("b")).unwrap().is_none());
+
+        db.set(key("b"), value("def")).unwrap();
+        drop(db);
+        assert!(Database::verify(db_dir.as_path())
+            .unwrap()
+            .corruption
+            .is_none());
+    }
+
+    #[test]
+    fn test_corrupted_entry_is_not_truncated() {
+        let db_dir = TempDir::new();
+        let filename = db_dir.as_path().join("db");
+
+        let offset = {
+            let mut db = Database::create(db_dir.as_path()).unwrap();
+            db.set(key("a"), value("abc")).unwrap();
+            let offset = db.file_size();
+            db.set(key("b"), value("def")).unwrap();
+            db.set(key("c"), value("ghi")).unwrap();
+            offset
+        };
+
+        // Checksum mismatch of an entry followed by a valid one
+        let mut bytes = std::fs::read(&filename).unwrap();
+        bytes[offset as usize + EntryHeader::NBYTES] ^= 0xff;
+        std::fs::write(&filename, &bytes).unwrap();
+
+        let report = Database::verify(db_dir.as_path()).unwrap();
+        assert_eq!(report.corruption.as_ref().unwrap().0, offset);
+        assert!(!report.torn_tail);
+
+        let error = Database::create(db_dir.as_path()).unwrap_err();
+        assert_eq!(error.kind(), InvalidData);
+        // Nothing is discarded
+        assert_eq!(std::fs::read(&filename).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_read_error_is_not_torn_tail() {
+        struct FailingReader;
+
+        impl std::io::Read for FailingReader {
+            fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::new(Other, "input/output error"))
+            }
+        }
+
+        let (offset, error) = read_entries(&mut FailingReader, 1024, |_, _, _| {});
+        assert_eq!(offset, DATABASE_VERSION_NBYTES as u64);
+        assert!(matches!(error, Some(ReadEntriesError::Io(_))));
+    }
+
+    #[test]
+    fn test_compaction() {
+        let db_dir = TempDir::new();
+
+        let sorted = make_random_key_values(1000);
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        db.set_compaction(Some(CompactionConfig {
+            dead_space_ratio: 0.5,
+            min_file_size: 0,
+        }));
+        db.set_batch(sorted.clone(), []).unwrap();
+        assert_eq!(db.dead_bytes(), 0);
+
+        // Overwrite the entries until a compaction starts
+        while db.compaction.is_none() {
+            db.set_batch(sorted.clone(), []).unwrap();
+        }
+        let size_before = db.file_size();
+
+        // Entries written while it runs are kept
+        db.set(key("a"), value("b")).unwrap();
+        db.remove(sorted[0].0.clone()).unwrap();
+        db.compaction_wait().unwrap();
+        assert!(db.compaction.is_none());
+        assert!(db.file_size() < size_before);
+
+        let mut expected = sorted[1..].to_vec();
+        expected.push((key("a"), value("b")));
+        let expected = sorted_vec(expected);
+        assert_eq!(sorted_vec(db.to_alist().unwrap()), expected);
+
+        drop(db);
+        let report = Database::verify(db_dir.as_path()).unwrap();
+        assert!(report.corruption.is_none());
+        assert_eq!(report.keys, expected.len());
+
+        let mut db = Database::create(db_dir.as_path()).unwrap();
+        assert_eq!(sorted_vec(db.to_alist().unwrap()), expected);
+    }
+
     #[test]
     fn test_to_alist() {
         let db_dir = TempDir::new();
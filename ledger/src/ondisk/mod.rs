@@ -33,6 +33,15 @@
 //! - `KEY`: The key data
 //! - `VALUE`: The value data
 //!
+//! ## Compaction and Recovery
+//!
+//! Overwritten and removed entries stay in the file until it's compacted. This
+//! happens in a background thread once they occupy a given ratio of the file,
+//! see [`CompactionConfig`].
+//!
+//! On opening, the file is truncated at the first entry whose CRC32 doesn't
+//! match, or which is incomplete, as left by a torn write.
+//!
 //! ## Example Usage
 //!
 //! Create an instance of MyDatabase:
@@ -602,7 +602,7 @@ impl Memo {
     /// +2 for tag and length bytes
     const MEMO_LENGTH: usize = Self::DIGEST_LENGTH + 2;
 
-    const MAX_INPUT_LENGTH: usize = Self::DIGEST_LENGTH;
+    pub const MAX_INPUT_LENGTH: usize = Self::DIGEST_LENGTH;
 
     const MAX_DIGESTIBLE_STRING_LENGTH: usize = 1000;
 
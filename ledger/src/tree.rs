@@ -143,6 +143,20 @@ impl HashesMatrix {
         }
     }
 
+    /// Removes the hashes of the nodes below `height`
+    pub fn remove_below_height(&mut self, height: usize) {
+        if height == 0 {
+            return;
+        }
+
+        let ledger_depth = self.ledger_depth;
+        self.matrix.retain(|linear, _| {
+            let length = (linear + 1).ilog2() as usize;
+            ledger_depth - length >= height
+        });
+        self.nhashes = self.matrix.len();
+    }
+
     pub fn invalidate_hashes(&mut self, account_index: AccountIndex) {
         let mut addr = Address::from_index(account_index, self.ledger_depth);
 
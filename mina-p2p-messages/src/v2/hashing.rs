@@ -208,6 +208,39 @@ impl generated::MinaBaseSignedCommandStableV2 {
     }
 }
 
+impl generated::MinaBaseFeeTransferSingleStableV2 {
+    /// Hash of a single fee transfer, as used by the archive for internal
+    /// commands.
+    pub fn hash(&self) -> io::Result<TransactionHash> {
+        binprot_blake2b_hash(self)
+    }
+}
+
+impl generated::MinaBaseCoinbaseStableV1 {
+    /// Hash of a coinbase, as used by the archive for internal commands.
+    pub fn hash(&self) -> io::Result<TransactionHash> {
+        binprot_blake2b_hash(self)
+    }
+}
+
+fn binprot_blake2b_hash<T: BinProtWrite>(value: &T) -> io::Result<TransactionHash> {
+    use blake2::{
+        digest::{Update, VariableOutput},
+        Blake2bVar,
+    };
+    let mut encoded = vec![];
+    value.binprot_write(&mut encoded)?;
+
+    let mut hasher = Blake2bVar::new(32).expect("Invalid Blake2bVar output size");
+    hasher.update(&encoded);
+    let mut hash = [0; 32];
+    hasher
+        .finalize_variable(&mut hash)
+        .expect("Invalid buffer size"); // Never occur
+
+    Ok(TransactionHash(hash.into()))
+}
+
 // TODO(adonagy): reduce duplication
 impl generated::MinaBaseZkappCommandTStableV1WireStableV1 {
     fn binprot_write_with_default(&self) -> io::Result<Vec<u8>> {
@@ -0,0 +1,151 @@
+use std::{collections::BTreeMap, io::Write, path::PathBuf, sync::Arc};
+
+use mina_p2p_messages::v2::StateHash;
+use node::{
+    core::{
+        block::{AppliedBlock, ArcBlockWithHash},
+        channels::mpsc,
+        thread,
+    },
+    ledger::write::BlockApplyResultArchive,
+    transition_frontier::archive::PrecomputedBlock,
+};
+
+use crate::NodeService;
+
+/// Backend the archive service writes applied blocks to.
+pub trait ArchiveStorage: Send + 'static {
+    fn store_block(
+        &mut self,
+        block: &ArcBlockWithHash,
+        data: &BlockApplyResultArchive,
+    ) -> Result<(), String>;
+}
+
+pub struct ArchiveService {
+    sender: mpsc::UnboundedSender<(ArcBlockWithHash, Arc<BlockApplyResultArchive>)>,
+}
+
+impl ArchiveService {
+    /// Spawns a thread which writes the blocks sent to the service into
+    /// `storage`, in the order they were applied.
+    pub fn start<S: ArchiveStorage>(mut storage: S) -> Self {
+        let (sender, mut receiver) =
+            mpsc::unbounded_channel::<(ArcBlockWithHash, Arc<BlockApplyResultArchive>)>();
+        thread::Builder::new()
+            .name("archive".to_owned())
+            .spawn(move || {
+                while let Some((block, data)) = receiver.blocking_recv() {
+                    if let Err(error) = storage.store_block(&block, &data) {
+                        openmina_core::error!(
+                            openmina_core::log::system_time();
+                            summary = "failed to archive block",
+                            block_hash = block.hash().to_string(),
+                            error = error
+                        );
+                    }
+                }
+            })
+            .expect("failed to spawn archive thread");
+
+        Self { sender }
+    }
+}
+
+/// Writes the blocks added to the best chain into a directory, as
+/// precomputed block JSON files.
+pub struct PrecomputedBlocksExporter {
+    sender: mpsc::UnboundedSender<(String, PrecomputedBlock)>,
+    network_name: &'static str,
+    /// Archive data of the applied blocks, until they are exported or
+    /// fall behind the root.
+    archive_data: BTreeMap<(u32, StateHash), Arc<BlockApplyResultArchive>>,
+    /// Best chain at the time of the last export.
+    exported: BTreeMap<u32, StateHash>,
+}
+
+impl PrecomputedBlocksExporter {
+    /// Spawns a thread which writes the exported blocks into `dir`, which
+    /// is created if missing.
+    pub fn start(dir: PathBuf, network_name: &'static str) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<(String, PrecomputedBlock)>();
+        thread::Builder::new()
+            .name("precomputed-blocks".to_owned())
+            .spawn(move || {
+                while let Some((file_name, block)) = receiver.blocking_recv() {
+                    let path = dir.join(file_name);
+                    let result = std::fs::create_dir_all(&dir)
+                        .and_then(|_| std::fs::File::create(&path))
+                        .map_err(|err| err.to_string())
+                        .and_then(|file| {
+                            let mut writer = std::io::BufWriter::new(file);
+                            serde_json::to_writer(&mut writer, &block)
+                                .map_err(|err| err.to_string())?;
+                            writer.flush().map_err(|err| err.to_string())
+                        });
+                    if let Err(error) = result {
+                        openmina_core::error!(
+                            openmina_core::log::system_time();
+                            summary = "failed to export precomputed block",
+                            path = path.display().to_string(),
+                            error = error
+                        );
+                    }
+                }
+            })
+            .expect("failed to spawn precomputed blocks thread");
+
+        Self {
+            sender,
+            network_name,
+            archive_data: Default::default(),
+            exported: Default::default(),
+        }
+    }
+
+    fn add_archive_data(&mut self, block: &ArcBlockWithHash, data: Arc<BlockApplyResultArchive>) {
+        self.archive_data
+            .insert((block.height(), block.hash().clone()), data);
+    }
+
+    fn export(&mut self, best_chain: &[AppliedBlock]) {
+        let Some(root) = best_chain.first() else {
+            return;
+        };
+        for block in best_chain {
+            let height = block.height();
+            if self.exported.get(&height) == Some(block.hash()) {
+                continue;
+            }
+            let data = self.archive_data.remove(&(height, block.hash().clone()));
+            let file_name = PrecomputedBlock::file_name(self.network_name, block);
+            let block = PrecomputedBlock::new(block, data.as_deref());
+            let _ = self.sender.send((file_name, block));
+        }
+
+        let root_height = root.height();
+        self.archive_data
+            .retain(|(height, _), _| *height > root_height);
+        self.exported = best_chain
+            .iter()
+            .map(|block| (block.height(), block.hash().clone()))
+            .collect();
+    }
+}
+
+impl node::service::ArchiveService for NodeService {
+    fn send_to_archive(&mut self, block: ArcBlockWithHash, data: Arc<BlockApplyResultArchive>) {
+        if let Some(exporter) = &mut self.precomputed_blocks {
+            exporter.add_archive_data(&block, data.clone());
+        }
+        if let Some(archive) = &self.archive {
+            let _ = archive.sender.send((block, data));
+        }
+    }
+
+    fn export_precomputed_blocks(&mut self, best_chain: &[AppliedBlock]) {
+        if let Some(exporter) = &mut self.precomputed_blocks {
+            exporter.export(best_chain);
+        }
+    }
+}
@@ -7,7 +7,9 @@ use ledger::proofs::{
 };
 use mina_p2p_messages::{
     binprot::BinProtWrite,
-    v2::{MinaBaseProofStableV2, ProverExtendBlockchainInputStableV2, StateHash},
+    v2::{
+        MinaBaseProofStableV2, NonZeroCurvePoint, ProverExtendBlockchainInputStableV2, StateHash,
+    },
 };
 use node::{
     account::AccountSecretKey,
@@ -15,85 +17,98 @@ use node::{
     core::{channels::mpsc, constants::constraint_constants, thread},
 };
 
-use crate::EventSender;
+use crate::{
+    service::signer::{Signer, SignerError},
+    EventSender,
+};
 
 pub struct BlockProducerService {
     provers: Option<BlockProver>,
-    keypair: AccountSecretKey,
+    keypairs: Vec<AccountSecretKey>,
     vrf_evaluation_sender: mpsc::UnboundedSender<VrfEvaluatorInput>,
-    prove_sender: mpsc::UnboundedSender<(
-        BlockProver,
-        StateHash,
-        Box<ProverExtendBlockchainInputStableV2>,
-    )>,
+    prove_sender: mpsc::UnboundedSender<(StateHash, Box<ProverExtendBlockchainInputStableV2>)>,
 }
 
 impl BlockProducerService {
     pub fn new(
-        keypair: AccountSecretKey,
+        keypairs: Vec<AccountSecretKey>,
         vrf_evaluation_sender: mpsc::UnboundedSender<VrfEvaluatorInput>,
-        prove_sender: mpsc::UnboundedSender<(
-            BlockProver,
-            StateHash,
-            Box<ProverExtendBlockchainInputStableV2>,
-        )>,
+        prove_sender: mpsc::UnboundedSender<(StateHash, Box<ProverExtendBlockchainInputStableV2>)>,
         provers: Option<BlockProver>,
     ) -> Self {
         Self {
             provers,
-            keypair,
+            keypairs,
             vrf_evaluation_sender,
             prove_sender,
         }
     }
 
+    /// `signer` evaluates the vrf and proves the blocks. `keypairs` are
+    /// the keys held by the node itself, empty if `signer` is an external
+    /// process.
     pub fn start(
         event_sender: EventSender,
-        keypair: AccountSecretKey,
+        signer: Box<dyn Signer>,
+        keypairs: Vec<AccountSecretKey>,
         provers: Option<BlockProver>,
-    ) -> Self {
+    ) -> Result<Self, SignerError> {
         let (vrf_evaluation_sender, vrf_evaluation_receiver) = mpsc::unbounded_channel();
         let (prove_sender, prove_receiver) = mpsc::unbounded_channel();
 
+        let prove_signer = signer.try_clone()?;
         let event_sender_clone = event_sender.clone();
-        let producer_keypair = keypair.clone();
         thread::Builder::new()
             .name("openmina_vrf_evaluator".to_owned())
             .spawn(move || {
-                vrf_evaluator::vrf_evaluator(
-                    event_sender_clone,
-                    vrf_evaluation_receiver,
-                    producer_keypair.into(),
-                );
+                vrf_evaluator::vrf_evaluator(event_sender_clone, vrf_evaluation_receiver, signer);
             })
             .unwrap();
 
-        let producer_keypair = keypair.clone();
         thread::Builder::new()
             .name("openmina_block_prover".to_owned())
-            .spawn(move || prover_loop(producer_keypair, event_sender, prove_receiver))
+            .spawn(move || prover_loop(prove_signer, event_sender, prove_receiver))
             .unwrap();
 
-        BlockProducerService::new(keypair, vrf_evaluation_sender, prove_sender, provers)
+        Ok(BlockProducerService::new(
+            keypairs,
+            vrf_evaluation_sender,
+            prove_sender,
+            provers,
+        ))
+    }
+
+    pub fn keypairs(&self) -> &[AccountSecretKey] {
+        &self.keypairs
     }
 
-    pub fn keypair(&self) -> AccountSecretKey {
-        self.keypair.clone()
+    /// Key of the block creator in the prover `input`, if it is held by
+    /// the node itself.
+    pub fn block_creator_keypair(
+        &self,
+        input: &ProverExtendBlockchainInputStableV2,
+    ) -> Option<AccountSecretKey> {
+        // Genesis block is created by the genesis producer, which isn't
+        // one of our keys.
+        let block_creator = &input.next_state.body.consensus_state.block_creator;
+        let genesis_producer = AccountSecretKey::genesis_producer();
+        self.keypairs
+            .iter()
+            .chain(std::iter::once(&genesis_producer))
+            .find(|keypair| &NonZeroCurvePoint::from(keypair.public_key()) == block_creator)
+            .cloned()
     }
 }
 
 fn prover_loop(
-    keypair: AccountSecretKey,
+    mut signer: Box<dyn Signer>,
     event_sender: EventSender,
-    mut rx: mpsc::UnboundedReceiver<(
-        BlockProver,
-        StateHash,
-        Box<ProverExtendBlockchainInputStableV2>,
-    )>,
+    mut rx: mpsc::UnboundedReceiver<(StateHash, Box<ProverExtendBlockchainInputStableV2>)>,
 ) {
-    while let Some((provers, block_hash, input)) = rx.blocking_recv() {
-        let res =
-            prove(provers, input.clone(), keypair.clone(), false).map_err(|err| format!("{err:?}"));
+    while let Some((block_hash, input)) = rx.blocking_recv() {
+        let res = signer
+            .prove_block(input.clone())
+            .map_err(|err| err.to_string());
         if res.is_err() {
             // IMPORTANT: Make sure that `input` here is a copy from before `prove` is called, we don't
             // want to leak the private key.
@@ -155,13 +170,12 @@ impl node::service::BlockProducerService for crate::NodeService {
         if self.replayer.is_some() {
             return;
         }
-        let provers = self.provers();
         let _ = self
             .block_producer
             .as_ref()
             .expect("prove shouldn't be requested if block producer isn't initialized")
             .prove_sender
-            .send((provers, block_hash, input));
+            .send((block_hash, input));
     }
 }
 
@@ -1,4 +1,3 @@
-use mina_signer::Keypair;
 use node::{
     block_producer::BlockProducerVrfEvaluatorEvent,
     block_producer::{
@@ -8,48 +7,52 @@ use node::{
     core::channels::mpsc::{UnboundedReceiver, UnboundedSender},
     event_source::Event,
 };
-use vrf::{VrfEvaluationInput, VrfEvaluationOutput};
+use vrf::VrfEvaluationOutput;
 
+use crate::service::signer::{Signer, SignerVrfRequest};
 use crate::NodeService;
 
 pub fn vrf_evaluator(
     event_sender: UnboundedSender<Event>,
     mut vrf_evaluation_receiver: UnboundedReceiver<VrfEvaluatorInput>,
-    keypair: Keypair,
+    mut signer: Box<dyn Signer>,
 ) {
     while let Some(vrf_evaluator_input) = vrf_evaluation_receiver.blocking_recv() {
         // let bytes = serde_json::to_string(&vrf_evaluator_input).unwrap();
         // openmina_core::http::download("vrf.json".to_string(), bytes.as_bytes().to_vec()).unwrap();
 
-        let keypair = &keypair;
         let VrfEvaluatorInput {
             epoch_seed,
-            delegator_table,
+            delegator_tables,
             global_slot,
             total_currency,
             staking_ledger_hash: _,
         } = &vrf_evaluator_input;
 
-        let vrf_result = delegator_table
+        let vrf_result = delegator_tables
             .iter()
-            .find_map(|(index, (pub_key, stake))| {
-                let vrf_input = VrfEvaluationInput {
-                    producer_key: keypair.clone(),
-                    global_slot: *global_slot,
+            .find_map(|(producer, delegator_table)| {
+                let request = SignerVrfRequest {
+                    producer: producer.clone(),
                     epoch_seed: epoch_seed.clone(),
-                    account_pub_key: pub_key.clone(),
-                    delegator_index: *index,
-                    delegated_stake: (*stake).into(),
-                    total_currency: (*total_currency).into(),
+                    global_slot: *global_slot,
+                    total_currency: *total_currency,
+                    delegator_table: delegator_table.clone(),
                 };
-
-                let vrf_result = vrf::evaluate_vrf(vrf_input).unwrap();
-
-                // the first delegate that won the slot
-                if let VrfEvaluationOutput::SlotWon(_) = vrf_result {
-                    return Some(vrf_result);
+                match signer.evaluate_vrf(&request) {
+                    Ok(vrf_result @ VrfEvaluationOutput::SlotWon(_)) => Some(vrf_result),
+                    Ok(VrfEvaluationOutput::SlotLost(_)) => None,
+                    Err(error) => {
+                        openmina_core::error!(
+                            openmina_core::log::system_time();
+                            message = "vrf evaluation failed",
+                            producer = producer.to_string(),
+                            global_slot = global_slot,
+                            error = error.to_string()
+                        );
+                        None
+                    }
                 }
-                None
             })
             .unwrap_or(VrfEvaluationOutput::SlotLost(*global_slot));
 
@@ -80,13 +83,86 @@ impl node::block_producer_effectful::vrf_evaluator_effectful::BlockProducerVrfEv
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use std::{collections::BTreeMap, str::FromStr, sync::Arc};
 
+    use ledger::AccountIndex;
+    use mina_p2p_messages::v2;
     // use mina_signer::keypair;
-    use node::account::AccountSecretKey;
+    use mina_signer::Keypair;
+    use node::{account::AccountSecretKey, core::channels::mpsc};
+    use vrf::VrfEvaluationInput;
+
+    use crate::service::signer::LocalSigner;
 
     use super::*;
 
+    const TOTAL_CURRENCY: u64 = 1_000_000_000_000;
+
+    /// Evaluates `slots` with a signer holding the keys of both producers,
+    /// only the `staked` producer has a delegator with stake.
+    fn won_slots(staked: u64, unstaked: u64, slots: u32) -> Vec<vrf::VrfWonSlot> {
+        let staked = AccountSecretKey::deterministic(staked);
+        let unstaked = AccountSecretKey::deterministic(unstaked);
+        let delegator_tables = Arc::new(BTreeMap::from([
+            (
+                staked.public_key(),
+                BTreeMap::from([(AccountIndex(0), (staked.public_key(), TOTAL_CURRENCY))]),
+            ),
+            (
+                unstaked.public_key(),
+                BTreeMap::from([(AccountIndex(1), (unstaked.public_key(), 0))]),
+            ),
+        ]));
+        let signer = LocalSigner::new([staked, unstaked]);
+
+        let (event_sender, mut event_receiver) = mpsc::unbounded_channel();
+        let (input_sender, input_receiver) = mpsc::unbounded_channel();
+        for global_slot in 0..slots {
+            input_sender
+                .send(VrfEvaluatorInput::new(
+                    v2::EpochSeed::zero(),
+                    delegator_tables.clone(),
+                    global_slot,
+                    TOTAL_CURRENCY,
+                    v2::LedgerHash::zero(),
+                ))
+                .unwrap();
+        }
+        drop(input_sender);
+        vrf_evaluator(event_sender, input_receiver, Box::new(signer));
+
+        let mut won_slots = Vec::new();
+        while let Ok(event) = event_receiver.try_recv() {
+            let Event::BlockProducerEvent(BlockProducerEvent::VrfEvaluator(
+                BlockProducerVrfEvaluatorEvent::Evaluated(output),
+            )) = event
+            else {
+                panic!("unexpected event: {event}");
+            };
+            if let VrfEvaluationOutput::SlotWon(won_slot) = output.evaluation_result {
+                won_slots.push(won_slot);
+            }
+        }
+        won_slots
+    }
+
+    #[test]
+    fn slots_are_won_by_the_staked_producer() {
+        for (staked, unstaked) in [(0, 1), (1, 0)] {
+            let won_slots = won_slots(staked, unstaked, 20);
+            assert!(
+                !won_slots.is_empty(),
+                "with all the stake some slots must be won"
+            );
+            let producer = AccountSecretKey::deterministic(staked).public_key();
+            for won_slot in won_slots {
+                assert_eq!(won_slot.producer, producer);
+                assert_eq!(won_slot.winner_account, producer);
+                assert_eq!(won_slot.account_index, AccountIndex(0));
+            }
+        }
+    }
+
     #[test]
     #[ignore]
     fn test_vrf() {
@@ -99,7 +175,7 @@ mod tests {
 
         let VrfEvaluatorInput {
             epoch_seed,
-            delegator_table,
+            delegator_tables,
             global_slot,
             total_currency,
             staking_ledger_hash: _,
@@ -107,8 +183,9 @@ mod tests {
 
         let now = std::time::Instant::now();
 
-        let vrf_result = delegator_table
-            .iter()
+        let vrf_result = delegator_tables
+            .values()
+            .flatten()
             .map(|(index, (pub_key, stake))| {
                 let vrf_input = VrfEvaluationInput {
                     producer_key: keypair.clone(),
@@ -138,7 +215,11 @@ mod tests {
 
         let elapsed = now.elapsed();
         let slot = vrf_evaluator_input.global_slot;
-        let ndelegator = vrf_evaluator_input.delegator_table.len();
+        let ndelegator: usize = vrf_evaluator_input
+            .delegator_tables
+            .values()
+            .map(|table| table.len())
+            .sum();
         // let nevaluated = nevaluated.load(std::sync::atomic::Ordering::Relaxed);
         eprintln!("TOTAL vrf::evaluate_vrf: {elapsed:?} slot:{slot:?} ndelegators:{ndelegator:?}");
         dbg!(vrf_result);
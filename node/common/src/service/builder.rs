@@ -1,8 +1,10 @@
+use std::path::PathBuf;
+
 use ledger::proofs::provers::BlockProver;
 use node::{
     account::AccountSecretKey,
     core::channels::mpsc,
-    ledger::{LedgerCtx, LedgerManager},
+    ledger::{LedgerAccountsOnDisk, LedgerCtx, LedgerManager, LedgerStorage},
     p2p::{
         identity::SecretKey as P2pSecretKey,
         service_impl::{
@@ -23,7 +25,10 @@ use crate::{
     EventReceiver, EventSender, NodeService,
 };
 
+use super::archive::{ArchiveService, ArchiveStorage, PrecomputedBlocksExporter};
 use super::block_producer::BlockProducerService;
+use super::event_sink::{EventSink, EventSinkService};
+use super::signer::{LocalSigner, Signer, SignerError};
 
 pub struct NodeServiceCommonBuilder {
     rng_seed: [u8; 32],
@@ -32,8 +37,15 @@ pub struct NodeServiceCommonBuilder {
     /// `event_source` state machine defined in the `openmina-node` crate.
     event_sender: EventSender,
     event_receiver: EventReceiver,
+    ledger_storage: Option<LedgerStorage>,
+    ledger_accounts_on_disk: Option<LedgerAccountsOnDisk>,
     ledger_manager: Option<LedgerManager>,
-    block_producer: Option<BlockProducerService>,
+    block_producer_keys: Vec<AccountSecretKey>,
+    block_producer_provers: Option<BlockProver>,
+    block_producer_signer: Option<Box<dyn Signer>>,
+    archive: Option<ArchiveService>,
+    precomputed_blocks: Option<PrecomputedBlocksExporter>,
+    event_sink: EventSinkService,
     p2p: Option<P2pServiceCtx>,
     gather_stats: bool,
     rpc: RpcService,
@@ -45,6 +57,8 @@ pub enum NodeServiceCommonBuildError {
     LedgerNotInit,
     #[error("p2p was never initialized! Please call: NodeServiceBuilder::p2p_init")]
     P2pNotInit,
+    #[error("failed to start the block producer signer: {0}")]
+    Signer(#[from] SignerError),
 }
 
 impl NodeServiceCommonBuilder {
@@ -55,8 +69,15 @@ impl NodeServiceCommonBuilder {
             rng: StdRng::from_seed(rng_seed),
             event_sender,
             event_receiver: event_receiver.into(),
+            ledger_storage: None,
+            ledger_accounts_on_disk: None,
             ledger_manager: None,
-            block_producer: None,
+            block_producer_keys: Vec::new(),
+            block_producer_provers: None,
+            block_producer_signer: None,
+            archive: None,
+            precomputed_blocks: None,
+            event_sink: Default::default(),
             p2p: None,
             rpc: RpcService::new(),
             gather_stats: false,
@@ -71,23 +92,69 @@ impl NodeServiceCommonBuilder {
         self.rpc.req_sender()
     }
 
+    /// Must be called before [`Self::ledger_init`].
+    pub fn ledger_storage(&mut self, storage: LedgerStorage) -> &mut Self {
+        self.ledger_storage = Some(storage);
+        self
+    }
+
+    /// Stores the accounts of snarked ledgers on disk instead of memory.
+    /// Must be called before [`Self::ledger_init`].
+    pub fn ledger_accounts_on_disk(&mut self, on_disk: LedgerAccountsOnDisk) -> &mut Self {
+        self.ledger_accounts_on_disk = Some(on_disk);
+        self
+    }
+
     pub fn ledger_init(&mut self) -> &mut Self {
         let mut ctx = LedgerCtx::default();
         ctx.set_event_sender(self.event_sender.clone());
+        if let Some(storage) = self.ledger_storage.take() {
+            ctx.set_storage(storage);
+        }
+        if let Some(on_disk) = self.ledger_accounts_on_disk.take() {
+            ctx.set_accounts_on_disk(on_disk);
+        }
         self.ledger_manager = Some(LedgerManager::spawn(ctx));
         self
     }
 
+    /// Adds a block producer key. Can be called multiple times to produce
+    /// blocks with multiple keys.
     pub fn block_producer_init(
         &mut self,
         keypair: AccountSecretKey,
         provers: Option<BlockProver>,
     ) -> &mut Self {
-        self.block_producer = Some(BlockProducerService::start(
-            self.event_sender.clone(),
-            keypair,
-            provers,
-        ));
+        self.block_producer_keys.push(keypair);
+        if provers.is_some() {
+            self.block_producer_provers = provers;
+        }
+        self
+    }
+
+    /// Uses `signer` for the vrf evaluation and the block proofs, instead
+    /// of the keys added with [Self::block_producer_init].
+    pub fn block_producer_signer_init(&mut self, signer: Box<dyn Signer>) -> &mut Self {
+        self.block_producer_signer = Some(signer);
+        self
+    }
+
+    pub fn archive_init<S: ArchiveStorage>(&mut self, storage: S) -> &mut Self {
+        self.archive = Some(ArchiveService::start(storage));
+        self
+    }
+
+    pub fn precomputed_blocks_init(
+        &mut self,
+        dir: PathBuf,
+        network_name: &'static str,
+    ) -> &mut Self {
+        self.precomputed_blocks = Some(PrecomputedBlocksExporter::start(dir, network_name));
+        self
+    }
+
+    pub fn event_sink_add<S: EventSink>(&mut self, name: &str, sink: S) -> &mut Self {
+        self.event_sink.add(name, sink);
         self
     }
 
@@ -113,6 +180,23 @@ impl NodeServiceCommonBuilder {
             .ledger_manager
             .ok_or(NodeServiceCommonBuildError::LedgerNotInit)?;
         let p2p = self.p2p.ok_or(NodeServiceCommonBuildError::P2pNotInit)?;
+        let block_producer = match self.block_producer_signer {
+            Some(signer) => Some(signer),
+            None if self.block_producer_keys.is_empty() => None,
+            None => Some(Box::new(
+                LocalSigner::new(self.block_producer_keys.clone())
+                    .with_provers(self.block_producer_provers.clone()),
+            ) as _),
+        }
+        .map(|signer| {
+            BlockProducerService::start(
+                self.event_sender.clone(),
+                signer,
+                self.block_producer_keys,
+                self.block_producer_provers,
+            )
+        })
+        .transpose()?;
 
         Ok(NodeService {
             rng_seed: self.rng_seed,
@@ -131,13 +215,18 @@ impl NodeServiceCommonBuilder {
                 self.event_sender,
             ),
             ledger_manager,
-            block_producer: self.block_producer,
+            block_producer,
+            archive: self.archive,
+            precomputed_blocks: self.precomputed_blocks,
+            event_sink: self.event_sink,
             p2p,
             stats: self.gather_stats.then(Stats::new),
             rpc: self.rpc,
             recorder: Default::default(),
             replayer: None,
             invariants_state: Default::default(),
+            p2p_ban_list_path: None,
+            p2p_peer_store_path: None,
         })
     }
 }
@@ -0,0 +1,58 @@
+use node::{
+    core::{channels::mpsc, thread},
+    event_sink::NodeEvent,
+};
+
+use crate::NodeService;
+
+/// Destination the event sink service publishes node events to.
+pub trait EventSink: Send + 'static {
+    fn publish(&mut self, event: &NodeEvent) -> Result<(), String>;
+}
+
+#[derive(Default)]
+pub struct EventSinkService {
+    senders: Vec<mpsc::UnboundedSender<NodeEvent>>,
+}
+
+impl EventSinkService {
+    /// Spawns a thread which publishes the events sent to the service into
+    /// `sink`, so that a slow sink doesn't hold back the others.
+    pub fn add<S: EventSink>(&mut self, name: &str, mut sink: S) {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<NodeEvent>();
+        let name = name.to_owned();
+        thread::Builder::new()
+            .name(format!("event_sink_{name}"))
+            .spawn(move || {
+                while let Some(event) = receiver.blocking_recv() {
+                    if let Err(error) = sink.publish(&event) {
+                        openmina_core::warn!(
+                            openmina_core::log::system_time();
+                            summary = "failed to publish node event",
+                            sink = name,
+                            error = error
+                        );
+                    }
+                }
+            })
+            .expect("failed to spawn event sink thread");
+
+        self.senders.push(sender);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.senders.is_empty()
+    }
+}
+
+impl node::service::EventSinkService for NodeService {
+    fn event_sink_enabled(&self) -> bool {
+        !self.event_sink.is_empty()
+    }
+
+    fn event_sink_publish(&mut self, event: NodeEvent) {
+        for sender in &self.event_sink.senders {
+            let _ = sender.send(event.clone());
+        }
+    }
+}
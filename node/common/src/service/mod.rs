@@ -1,11 +1,14 @@
 mod event_receiver;
 pub use event_receiver::*;
 
+pub mod archive;
 pub mod block_producer;
+pub mod event_sink;
 pub mod p2p;
 pub mod record;
 pub mod replay;
 pub mod rpc;
+pub mod signer;
 pub mod snark_worker;
 mod snarks;
 
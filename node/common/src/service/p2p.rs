@@ -1,11 +1,13 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, path::Path};
 
 use node::{
     core::channels::mpsc,
     event_source::Event,
     p2p::{
+        ban::P2pBan,
         connection::outgoing::P2pConnectionOutgoingInitOpts,
         identity::{EncryptableType, PublicKey},
+        peer_store::P2pPeerStoreEntry,
         webrtc::ConnectionAuth,
         PeerId,
     },
@@ -100,6 +102,58 @@ impl webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p for NodeService {
     fn connections(&self) -> std::collections::BTreeSet<PeerId> {
         self.p2p.webrtc.peers.keys().copied().collect()
     }
+
+    fn bandwidth(&self) -> &bandwidth::P2pBandwidth {
+        &self.p2p.bandwidth
+    }
+}
+
+impl P2pBanService for NodeService {
+    fn ban_list_persist(&mut self, bans: &BTreeMap<PeerId, P2pBan>) {
+        if self.replayer.is_some() {
+            return;
+        }
+        let Some(path) = self.p2p_ban_list_path.as_ref() else {
+            return;
+        };
+        if let Err(error) = json_write_atomic(path, bans) {
+            openmina_core::error!(
+                openmina_core::log::system_time();
+                summary = "failed to persist p2p ban list",
+                path = path.display().to_string(),
+                error = error.to_string()
+            );
+        }
+    }
+}
+
+impl P2pPeerStoreService for NodeService {
+    fn peer_store_persist(&mut self, peers: &BTreeMap<PeerId, P2pPeerStoreEntry>) {
+        if self.replayer.is_some() {
+            return;
+        }
+        let Some(path) = self.p2p_peer_store_path.as_ref() else {
+            return;
+        };
+        if let Err(error) = json_write_atomic(path, peers) {
+            openmina_core::error!(
+                openmina_core::log::system_time();
+                summary = "failed to persist p2p peer store",
+                path = path.display().to_string(),
+                error = error.to_string()
+            );
+        }
+    }
+}
+
+/// Writes the value into a temporary file first, so that the existing
+/// file isn't lost if the node is killed while writing.
+fn json_write_atomic<T: serde::Serialize>(path: &Path, value: &T) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    let mut file = std::fs::File::create(&tmp_path)?;
+    serde_json::to_writer_pretty(&mut file, value)?;
+    file.sync_all()?;
+    std::fs::rename(tmp_path, path)
 }
 
 #[cfg(feature = "p2p-libp2p")]
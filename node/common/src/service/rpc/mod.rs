@@ -8,12 +8,14 @@ pub mod transaction_pool;
 pub mod transition_frontier;
 
 use node::rpc::{
-    RpcBestChainResponse, RpcBlockProducerStatsGetResponse, RpcConsensusConstantsGetResponse,
-    RpcDiscoveryBoostrapStatsResponse, RpcDiscoveryRoutingTableResponse, RpcHealthCheckResponse,
-    RpcLedgerAccountsResponse, RpcLedgerSlimAccountsResponse, RpcMessageProgressResponse,
+    RpcBestChainResponse, RpcBlockProducerScheduleGetResponse, RpcBlockProducerStatsGetResponse,
+    RpcConsensusConstantsGetResponse, RpcDiscoveryBoostrapStatsResponse,
+    RpcDiscoveryRoutingTableResponse, RpcHealthCheckResponse, RpcLedgerAccountsResponse,
+    RpcLedgerSlimAccountsResponse, RpcMessageProgressResponse, RpcMetricsGetResponse,
     RpcPeersGetResponse, RpcReadinessCheckResponse, RpcRequest, RpcStateGetError,
     RpcStatusGetResponse, RpcTransactionInjectResponse, RpcTransactionPoolResponse,
-    RpcTransactionStatusGetResponse, RpcTransitionFrontierUserCommandsResponse,
+    RpcTransactionStatusGetResponse, RpcTransitionFrontierSubscribeResponse,
+    RpcTransitionFrontierUserCommandsResponse,
 };
 use serde::{Deserialize, Serialize};
 
@@ -24,10 +26,13 @@ use node::State;
 use node::{event_source::Event, rpc::RpcSnarkPoolJobGetResponse};
 pub use node::{
     rpc::{
-        ActionStatsResponse, RpcActionStatsGetResponse, RpcId, RpcIdType,
-        RpcP2pConnectionOutgoingResponse, RpcScanStateSummaryGetResponse, RpcSnarkPoolGetResponse,
-        RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcStateGetResponse,
-        RpcSyncStatsGetResponse, RpcTransactionInjectSuccess,
+        ActionStatsResponse, RpcActionStatsGetResponse, RpcId, RpcIdType, RpcP2pBanAddResponse,
+        RpcP2pBanRemoveResponse, RpcP2pBandwidthGetResponse, RpcP2pBansGetResponse,
+        RpcP2pBitswapResourceAddResponse, RpcP2pBitswapResourceGetResponse,
+        RpcP2pConnectionOutgoingResponse, RpcP2pNodeStatusGetResponse, RpcP2pYamuxStatsGetResponse,
+        RpcScanStateSummaryGetResponse, RpcSnarkPoolGetResponse, RpcSnarkerJobCommitResponse,
+        RpcSnarkerJobSpecResponse, RpcStateGetResponse, RpcSyncStatsGetResponse,
+        RpcTransactionInjectSuccess,
     },
     rpc_effectful::RespondError,
 };
@@ -222,6 +227,11 @@ impl node::rpc_effectful::RpcService for NodeService {
         respond_block_producer_stats_get,
         RpcBlockProducerStatsGetResponse
     );
+    rpc_service_impl!(
+        respond_block_producer_schedule_get,
+        RpcBlockProducerScheduleGetResponse
+    );
+    rpc_service_impl!(respond_metrics_get, RpcMetricsGetResponse);
     rpc_service_impl!(
         respond_message_progress_stats_get,
         RpcMessageProgressResponse
@@ -231,6 +241,20 @@ impl node::rpc_effectful::RpcService for NodeService {
         respond_p2p_connection_outgoing,
         RpcP2pConnectionOutgoingResponse
     );
+    rpc_service_impl!(respond_p2p_node_status_get, RpcP2pNodeStatusGetResponse);
+    rpc_service_impl!(
+        respond_p2p_bitswap_resource_add,
+        RpcP2pBitswapResourceAddResponse
+    );
+    rpc_service_impl!(
+        respond_p2p_bitswap_resource_get,
+        RpcP2pBitswapResourceGetResponse
+    );
+    rpc_service_impl!(respond_p2p_bans_get, RpcP2pBansGetResponse);
+    rpc_service_impl!(respond_p2p_ban_add, RpcP2pBanAddResponse);
+    rpc_service_impl!(respond_p2p_ban_remove, RpcP2pBanRemoveResponse);
+    rpc_service_impl!(respond_p2p_bandwidth_get, RpcP2pBandwidthGetResponse);
+    rpc_service_impl!(respond_p2p_yamux_stats_get, RpcP2pYamuxStatsGetResponse);
 
     fn respond_p2p_connection_incoming_answer(
         &mut self,
@@ -306,6 +330,33 @@ impl node::rpc_effectful::RpcService for NodeService {
         RpcConsensusConstantsGetResponse
     );
     rpc_service_impl!(respond_transaction_status, RpcTransactionStatusGetResponse);
+
+    fn respond_transition_frontier_event(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTransitionFrontierSubscribeResponse,
+    ) -> Result<(), RespondError> {
+        let entry = self.rpc.pending.get(rpc_id);
+        let chan = entry.ok_or(RespondError::UnknownRpcId)?;
+        let chan = chan
+            .downcast_ref::<mpsc::Sender<RpcTransitionFrontierSubscribeResponse>>()
+            .ok_or(RespondError::UnexpectedResponseType)?;
+        match chan.try_send(response) {
+            Ok(()) => Ok(()),
+            // Close the subscription rather than silently skip events, the
+            // subscriber sees its stream end and can resubscribe.
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                self.rpc.pending.remove(rpc_id);
+                Err(RespondError::Custom(
+                    "subscriber is lagging behind, subscription closed".to_owned(),
+                ))
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                self.rpc.pending.remove(rpc_id);
+                Err(RespondError::RespondingFailed)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -59,4 +59,12 @@ impl Stats {
             .flatten();
         JsValue::from_serde(&res).unwrap_or_default()
     }
+
+    pub async fn bandwidth(&self) -> JsValue {
+        let res = self
+            .sender
+            .oneshot_request::<RpcP2pBandwidthGetResponse>(RpcRequest::P2pBandwidthGet)
+            .await;
+        JsValue::from_serde(&res).unwrap_or_default()
+    }
 }
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use node::{
     core::{channels::mpsc, invariants::InvariantsState},
@@ -18,7 +18,9 @@ use sha3::{
 use crate::rpc::RpcReceiver;
 
 use super::{
+    archive::{ArchiveService, PrecomputedBlocksExporter},
     block_producer::BlockProducerService,
+    event_sink::EventSinkService,
     p2p::webrtc_with_libp2p::P2pServiceCtx,
     replay::ReplayerState,
     rpc::{RpcSender, RpcService},
@@ -41,6 +43,9 @@ pub struct NodeService {
 
     pub ledger_manager: LedgerManager,
     pub block_producer: Option<BlockProducerService>,
+    pub archive: Option<ArchiveService>,
+    pub precomputed_blocks: Option<PrecomputedBlocksExporter>,
+    pub event_sink: EventSinkService,
     pub p2p: P2pServiceCtx,
 
     pub stats: Option<Stats>,
@@ -48,6 +53,10 @@ pub struct NodeService {
     pub recorder: Recorder,
     pub replayer: Option<ReplayerState>,
     pub invariants_state: InvariantsState,
+    /// Where the p2p ban list is persisted, if anywhere.
+    pub p2p_ban_list_path: Option<PathBuf>,
+    /// Where known good peers are persisted, if anywhere.
+    pub p2p_peer_store_path: Option<PathBuf>,
 }
 
 impl NodeService {
@@ -111,6 +120,9 @@ impl NodeService {
             snark_block_proof_verify: mpsc::unbounded_channel().0,
             ledger_manager: LedgerManager::spawn(Default::default()),
             block_producer: None,
+            archive: None,
+            precomputed_blocks: None,
+            event_sink: Default::default(),
             p2p: P2pServiceCtx::mocked(p2p_sec_key),
             stats: Some(Stats::new()),
             rpc: RpcService::new(),
@@ -122,6 +134,8 @@ impl NodeService {
                 replay_dynamic_effects_lib: dynamic_effects_lib.unwrap_or_default(),
             }),
             invariants_state: Default::default(),
+            p2p_ban_list_path: None,
+            p2p_peer_store_path: None,
         }
     }
 }
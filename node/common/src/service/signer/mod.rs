@@ -0,0 +1,165 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use ledger::proofs::provers::BlockProver;
+use mina_p2p_messages::v2::{
+    EpochSeed, MinaBaseProofStableV2, NonZeroCurvePoint, ProverExtendBlockchainInputStableV2,
+};
+use node::{
+    account::{AccountPublicKey, AccountSecretKey},
+    block_producer::vrf_evaluator::DelegatorTable,
+};
+use serde::{Deserialize, Serialize};
+use vrf::{VrfEvaluationInput, VrfEvaluationOutput};
+
+use super::block_producer::prove;
+
+/// Holds the block producer keys and does everything that needs the
+/// private keys: the vrf evaluation and the block proof, which has the
+/// block creator's private key as a witness. With a signer outside of the
+/// node process, the keys never enter the node's memory.
+///
+/// Mina blocks, coinbases and fee transfers carry no signatures of their
+/// own, the block proof is what binds a block to its producer key.
+pub trait Signer: Send + 'static {
+    /// Public keys of the keys held by the signer.
+    fn public_keys(&mut self) -> Result<Vec<AccountPublicKey>, SignerError>;
+
+    /// Evaluates the vrf of each delegator in the table, returning the
+    /// first won slot.
+    fn evaluate_vrf(
+        &mut self,
+        request: &SignerVrfRequest,
+    ) -> Result<VrfEvaluationOutput, SignerError>;
+
+    /// Proves the block, using the private key of its block creator as a
+    /// witness.
+    fn prove_block(
+        &mut self,
+        input: Box<ProverExtendBlockchainInputStableV2>,
+    ) -> Result<Arc<MinaBaseProofStableV2>, SignerError>;
+
+    /// Signer backed by the same keys, so that proving a block doesn't
+    /// hold back the vrf evaluation.
+    fn try_clone(&self) -> Result<Box<dyn Signer>, SignerError>;
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignerVrfRequest {
+    pub producer: AccountPublicKey,
+    pub epoch_seed: EpochSeed,
+    pub global_slot: u32,
+    pub total_currency: u64,
+    pub delegator_table: DelegatorTable,
+}
+
+#[derive(thiserror::Error, Serialize, Deserialize, Debug, Clone)]
+pub enum SignerError {
+    #[error("signer doesn't hold the key: {0}")]
+    UnknownKey(AccountPublicKey),
+    #[error("signer doesn't hold the key of the block creator: {0}")]
+    UnknownBlockCreator(NonZeroCurvePoint),
+    #[error("block proof failed: {0}")]
+    Proof(String),
+    #[error("invalid signer input: {0}")]
+    InvalidInput(String),
+    #[error("signer failure: {0}")]
+    Other(String),
+}
+
+/// Signer which holds the keys in the process memory.
+#[derive(Clone)]
+pub struct LocalSigner {
+    keys: BTreeMap<AccountPublicKey, AccountSecretKey>,
+    provers: Option<BlockProver>,
+}
+
+impl LocalSigner {
+    pub fn new(keys: impl IntoIterator<Item = AccountSecretKey>) -> Self {
+        let keys = keys
+            .into_iter()
+            .map(|key| (key.public_key(), key))
+            .collect();
+        Self {
+            keys,
+            provers: None,
+        }
+    }
+
+    /// Provers for the block proofs. Without them, the signer waits for
+    /// the provers made with [BlockProver::make].
+    pub fn with_provers(mut self, provers: Option<BlockProver>) -> Self {
+        self.provers = provers;
+        self
+    }
+
+    fn key(&self, public_key: &AccountPublicKey) -> Result<&AccountSecretKey, SignerError> {
+        self.keys
+            .get(public_key)
+            .ok_or_else(|| SignerError::UnknownKey(public_key.clone()))
+    }
+
+    /// Genesis block is created by the genesis producer, which isn't one
+    /// of our keys.
+    fn block_creator_key(
+        &self,
+        input: &ProverExtendBlockchainInputStableV2,
+    ) -> Result<AccountSecretKey, SignerError> {
+        let block_creator = &input.next_state.body.consensus_state.block_creator;
+        let genesis_producer = AccountSecretKey::genesis_producer();
+        self.keys
+            .values()
+            .chain(std::iter::once(&genesis_producer))
+            .find(|key| &NonZeroCurvePoint::from(key.public_key()) == block_creator)
+            .cloned()
+            .ok_or_else(|| SignerError::UnknownBlockCreator(block_creator.clone()))
+    }
+}
+
+impl Signer for LocalSigner {
+    fn public_keys(&mut self) -> Result<Vec<AccountPublicKey>, SignerError> {
+        Ok(self.keys.keys().cloned().collect())
+    }
+
+    fn evaluate_vrf(
+        &mut self,
+        request: &SignerVrfRequest,
+    ) -> Result<VrfEvaluationOutput, SignerError> {
+        let keypair: mina_signer::Keypair = self.key(&request.producer)?.clone().into();
+        for (index, (pub_key, stake)) in &request.delegator_table {
+            let vrf_input = VrfEvaluationInput {
+                producer_key: keypair.clone(),
+                global_slot: request.global_slot,
+                epoch_seed: request.epoch_seed.clone(),
+                account_pub_key: pub_key.clone(),
+                delegator_index: *index,
+                delegated_stake: (*stake).into(),
+                total_currency: request.total_currency.into(),
+            };
+
+            let vrf_result = vrf::evaluate_vrf(vrf_input)
+                .map_err(|err| SignerError::InvalidInput(err.to_string()))?;
+
+            // the first delegate that won the slot
+            if let VrfEvaluationOutput::SlotWon(_) = vrf_result {
+                return Ok(vrf_result);
+            }
+        }
+        Ok(VrfEvaluationOutput::SlotLost(request.global_slot))
+    }
+
+    fn prove_block(
+        &mut self,
+        input: Box<ProverExtendBlockchainInputStableV2>,
+    ) -> Result<Arc<MinaBaseProofStableV2>, SignerError> {
+        let key = self.block_creator_key(&input)?;
+        let provers = self
+            .provers
+            .clone()
+            .unwrap_or_else(BlockProver::get_once_made);
+        prove(provers, input, key, false).map_err(|err| SignerError::Proof(format!("{err:?}")))
+    }
+
+    fn try_clone(&self) -> Result<Box<dyn Signer>, SignerError> {
+        Ok(Box::new(self.clone()))
+    }
+}
@@ -0,0 +1,1109 @@
+//! Archive storage writing applied blocks into an SQL database, using the
+//! Mina archive schema. Both SQLite and Postgres are supported, the
+//! backend is picked based on the database url.
+
+mod schema;
+pub use schema::SqlDialect;
+
+use ledger::scan_state::transaction_logic::TransactionFailure;
+use mina_p2p_messages::v2::{
+    self, MinaBaseAccountTimingStableV2, MinaBaseAccountUpdateAuthorizationKindStableV1,
+    MinaBaseAccountUpdateMayUseTokenStableV1, MinaBaseSignedCommandPayloadBodyStableV2,
+    MinaBaseStakeDelegationStableV2, MinaBaseTransactionStatusStableV2,
+    MinaBaseUserCommandStableV2, MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesAA,
+    MinaTransactionTransactionStableV2, NonZeroCurvePoint, SgnStableV1, TokenIdKeyHash,
+};
+use node::{core::block::ArcBlockWithHash, ledger::write::BlockApplyResultArchive};
+use openmina_node_common::archive::ArchiveStorage;
+use sqlx::{any::AnyPoolOptions, AnyConnection, AnyPool, Row};
+
+pub struct SqlArchiveStorage {
+    runtime: tokio::runtime::Runtime,
+    pool: AnyPool,
+    dialect: SqlDialect,
+}
+
+impl SqlArchiveStorage {
+    /// Connects to the database at `url` (e.g. `sqlite://archive.db?mode=rwc`
+    /// or `postgres://user@localhost/archive`) and creates the archive
+    /// schema, if it doesn't exist yet.
+    pub fn connect(url: &str) -> Result<Self, sqlx::Error> {
+        let dialect = SqlDialect::from_url(url).ok_or_else(|| {
+            sqlx::Error::Configuration(format!("unsupported archive database: {url}").into())
+        })?;
+        sqlx::any::install_default_drivers();
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let pool = runtime.block_on(async {
+            // Blocks are written one at a time, and an in-memory SQLite
+            // database only lives as long as its connection.
+            let pool = AnyPoolOptions::new()
+                .max_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+                .connect(url)
+                .await?;
+            for statement in dialect.schema() {
+                sqlx::query(&statement).execute(&pool).await?;
+            }
+            Ok::<_, sqlx::Error>(pool)
+        })?;
+
+        Ok(Self {
+            runtime,
+            pool,
+            dialect,
+        })
+    }
+}
+
+impl ArchiveStorage for SqlArchiveStorage {
+    fn store_block(
+        &mut self,
+        block: &ArcBlockWithHash,
+        data: &BlockApplyResultArchive,
+    ) -> Result<(), String> {
+        self.runtime
+            .block_on(async {
+                let mut tx = self.pool.begin().await?;
+                let mut writer = Writer {
+                    conn: &mut *tx,
+                    dialect: self.dialect,
+                };
+                writer.block(block, data).await?;
+                tx.commit().await
+            })
+            .map_err(|err| err.to_string())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum SqlValue {
+    Int(Option<i64>),
+    Text(Option<String>),
+    Bool(bool),
+    /// Value of the given Postgres enum type, stored as text in SQLite.
+    Enum(&'static str, &'static str),
+    /// Stored as a Postgres array literal in SQLite.
+    IntArray(Vec<i64>),
+}
+
+impl SqlValue {
+    fn int(value: impl Into<i64>) -> Self {
+        Self::Int(Some(value.into()))
+    }
+
+    fn text(value: impl ToString) -> Self {
+        Self::Text(Some(value.to_string()))
+    }
+
+    fn is_null(&self) -> bool {
+        matches!(self, Self::Int(None) | Self::Text(None))
+    }
+
+    fn pg_type(&self) -> Option<&'static str> {
+        match self {
+            Self::Enum(pg_type, _) => Some(pg_type),
+            Self::IntArray(_) => Some("int[]"),
+            _ => None,
+        }
+    }
+}
+
+type SqlQuery<'q> = sqlx::query::Query<'q, sqlx::Any, sqlx::any::AnyArguments<'q>>;
+
+struct Writer<'a> {
+    conn: &'a mut AnyConnection,
+    dialect: SqlDialect,
+}
+
+impl Writer<'_> {
+    /// Placeholders for the values of `columns`, which are collected into
+    /// `values` for binding. Nulls are inlined instead.
+    fn placeholders(
+        &self,
+        columns: &[(&str, SqlValue)],
+        values: &mut Vec<SqlValue>,
+    ) -> Vec<String> {
+        columns
+            .iter()
+            .map(|(_, value)| {
+                if value.is_null() {
+                    return "NULL".to_owned();
+                }
+                values.push(value.clone());
+                self.dialect.param(values.len(), value.pg_type())
+            })
+            .collect()
+    }
+
+    fn query<'q>(sql: &'q str, values: Vec<SqlValue>) -> SqlQuery<'q> {
+        values
+            .into_iter()
+            .fold(sqlx::query(sql), |query, value| match value {
+                SqlValue::Int(value) => query.bind(value.unwrap_or_default()),
+                SqlValue::Text(value) => query.bind(value.unwrap_or_default()),
+                SqlValue::Bool(value) => query.bind(value),
+                SqlValue::Enum(_, value) => query.bind(value),
+                SqlValue::IntArray(value) => {
+                    let value = value.iter().map(i64::to_string).collect::<Vec<_>>();
+                    query.bind(format!("{{{}}}", value.join(",")))
+                }
+            })
+    }
+
+    async fn execute(&mut self, sql: &str, values: Vec<SqlValue>) -> Result<(), sqlx::Error> {
+        Self::query(sql, values).execute(&mut *self.conn).await?;
+        Ok(())
+    }
+
+    async fn insert_row(
+        &mut self,
+        table: &str,
+        columns: &[(&str, SqlValue)],
+    ) -> Result<(), sqlx::Error> {
+        let mut values = Vec::new();
+        let names = columns.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        let placeholders = self.placeholders(columns, &mut values);
+        let sql = format!(
+            "INSERT INTO {table} ({}) VALUES ({}) ON CONFLICT DO NOTHING",
+            names.join(", "),
+            placeholders.join(", ")
+        );
+        self.execute(&sql, values).await
+    }
+
+    async fn insert(
+        &mut self,
+        table: &str,
+        columns: &[(&str, SqlValue)],
+    ) -> Result<i64, sqlx::Error> {
+        let mut values = Vec::new();
+        let names = columns.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        let placeholders = self.placeholders(columns, &mut values);
+        let sql = format!(
+            "INSERT INTO {table} ({}) VALUES ({}) RETURNING id",
+            names.join(", "),
+            placeholders.join(", ")
+        );
+        Self::query(&sql, values)
+            .fetch_one(&mut *self.conn)
+            .await?
+            .try_get(0)
+    }
+
+    async fn find(
+        &mut self,
+        table: &str,
+        columns: &[(&str, SqlValue)],
+    ) -> Result<Option<i64>, sqlx::Error> {
+        let mut values = Vec::new();
+        let placeholders = self.placeholders(columns, &mut values);
+        let conditions = columns
+            .iter()
+            .zip(placeholders)
+            .map(|((name, value), placeholder)| {
+                if value.is_null() {
+                    format!("{name} IS NULL")
+                } else {
+                    format!("{name} = {placeholder}")
+                }
+            })
+            .collect::<Vec<_>>();
+        let sql = format!("SELECT id FROM {table} WHERE {}", conditions.join(" AND "));
+        Self::query(&sql, values)
+            .fetch_optional(&mut *self.conn)
+            .await?
+            .map(|row| row.try_get(0))
+            .transpose()
+    }
+
+    /// Id of the row with the given values, which is inserted if missing.
+    async fn find_or_insert(
+        &mut self,
+        table: &str,
+        columns: &[(&str, SqlValue)],
+    ) -> Result<i64, sqlx::Error> {
+        match self.find(table, columns).await? {
+            Some(id) => Ok(id),
+            None => self.insert(table, columns).await,
+        }
+    }
+
+    async fn public_key(&mut self, public_key: &NonZeroCurvePoint) -> Result<i64, sqlx::Error> {
+        self.find_or_insert("public_keys", &[("value", SqlValue::text(public_key))])
+            .await
+    }
+
+    async fn account_identifier(
+        &mut self,
+        public_key: &NonZeroCurvePoint,
+        token_id: &TokenIdKeyHash,
+    ) -> Result<i64, sqlx::Error> {
+        let public_key_id = self.public_key(public_key).await?;
+        let token_id = self
+            .find_or_insert("tokens", &[("value", SqlValue::text(token_id))])
+            .await?;
+        self.find_or_insert(
+            "account_identifiers",
+            &[
+                ("public_key_id", SqlValue::int(public_key_id)),
+                ("token_id", SqlValue::int(token_id)),
+            ],
+        )
+        .await
+    }
+
+    async fn snarked_ledger_hash(&mut self, hash: &v2::LedgerHash) -> Result<i64, sqlx::Error> {
+        self.find_or_insert("snarked_ledger_hashes", &[("value", SqlValue::text(hash))])
+            .await
+    }
+
+    async fn protocol_version(
+        &mut self,
+        version: &v2::ProtocolVersionStableV2,
+    ) -> Result<i64, sqlx::Error> {
+        self.find_or_insert(
+            "protocol_versions",
+            &[
+                (
+                    "\"transaction\"",
+                    SqlValue::int(version.transaction.as_u64() as i64),
+                ),
+                ("network", SqlValue::int(version.network.as_u64() as i64)),
+                ("patch", SqlValue::int(version.patch.as_u64() as i64)),
+            ],
+        )
+        .await
+    }
+
+    async fn epoch_data(
+        &mut self,
+        seed: &v2::EpochSeed,
+        ledger: &v2::MinaBaseEpochLedgerValueStableV1,
+        start_checkpoint: &v2::StateHash,
+        lock_checkpoint: &v2::StateHash,
+        epoch_length: u32,
+    ) -> Result<i64, sqlx::Error> {
+        let ledger_hash_id = self.snarked_ledger_hash(&ledger.hash).await?;
+        self.find_or_insert(
+            "epoch_data",
+            &[
+                ("seed", SqlValue::text(seed)),
+                ("ledger_hash_id", SqlValue::int(ledger_hash_id)),
+                (
+                    "total_currency",
+                    SqlValue::text(ledger.total_currency.as_u64()),
+                ),
+                ("start_checkpoint", SqlValue::text(start_checkpoint)),
+                ("lock_checkpoint", SqlValue::text(lock_checkpoint)),
+                ("epoch_length", SqlValue::int(epoch_length)),
+            ],
+        )
+        .await
+    }
+
+    async fn block(
+        &mut self,
+        block: &ArcBlockWithHash,
+        data: &BlockApplyResultArchive,
+    ) -> Result<(), sqlx::Error> {
+        let state_hash = block.hash().to_string();
+        if self
+            .find("blocks", &[("state_hash", SqlValue::text(&state_hash))])
+            .await?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let header = block.header();
+        let consensus_state = block.consensus_state();
+        let staking = &consensus_state.staking_epoch_data;
+        let next = &consensus_state.next_epoch_data;
+
+        let parent_id = self
+            .find(
+                "blocks",
+                &[("state_hash", SqlValue::text(block.pred_hash()))],
+            )
+            .await?;
+        let creator_id = self.public_key(block.producer()).await?;
+        let block_winner_id = self.public_key(&consensus_state.block_stake_winner).await?;
+        let snarked_ledger_hash_id = self
+            .snarked_ledger_hash(block.snarked_ledger_hash())
+            .await?;
+        let staking_epoch_data_id = self
+            .epoch_data(
+                &staking.seed,
+                &staking.ledger,
+                &staking.start_checkpoint,
+                &staking.lock_checkpoint,
+                staking.epoch_length.as_u32(),
+            )
+            .await?;
+        let next_epoch_data_id = self
+            .epoch_data(
+                &next.seed,
+                &next.ledger,
+                &next.start_checkpoint,
+                &next.lock_checkpoint,
+                next.epoch_length.as_u32(),
+            )
+            .await?;
+        let protocol_version_id = self
+            .protocol_version(&header.current_protocol_version)
+            .await?;
+        let proposed_protocol_version_id = match &header.proposed_protocol_version_opt {
+            Some(version) => Some(self.protocol_version(version).await?),
+            None => None,
+        };
+        // Same base64 encoding as the one used by the Mina archive.
+        let last_vrf_output = serde_json::to_value(&consensus_state.last_vrf_output)
+            .ok()
+            .and_then(|value| value.as_str().map(str::to_owned));
+
+        let block_id = self
+            .insert(
+                "blocks",
+                &[
+                    ("state_hash", SqlValue::text(&state_hash)),
+                    ("parent_id", SqlValue::Int(parent_id)),
+                    ("parent_hash", SqlValue::text(block.pred_hash())),
+                    ("creator_id", SqlValue::int(creator_id)),
+                    ("block_winner_id", SqlValue::int(block_winner_id)),
+                    ("last_vrf_output", SqlValue::Text(last_vrf_output)),
+                    (
+                        "snarked_ledger_hash_id",
+                        SqlValue::int(snarked_ledger_hash_id),
+                    ),
+                    (
+                        "staking_epoch_data_id",
+                        SqlValue::int(staking_epoch_data_id),
+                    ),
+                    ("next_epoch_data_id", SqlValue::int(next_epoch_data_id)),
+                    (
+                        "min_window_density",
+                        SqlValue::int(consensus_state.min_window_density.as_u32()),
+                    ),
+                    (
+                        "sub_window_densities",
+                        SqlValue::IntArray(
+                            consensus_state
+                                .sub_window_densities
+                                .iter()
+                                .map(|density| density.as_u32().into())
+                                .collect(),
+                        ),
+                    ),
+                    (
+                        "total_currency",
+                        SqlValue::text(consensus_state.total_currency.as_u64()),
+                    ),
+                    ("ledger_hash", SqlValue::text(block.merkle_root_hash())),
+                    ("height", SqlValue::int(block.height())),
+                    (
+                        "global_slot_since_hard_fork",
+                        SqlValue::int(block.global_slot()),
+                    ),
+                    (
+                        "global_slot_since_genesis",
+                        SqlValue::int(block.global_slot_since_genesis()),
+                    ),
+                    ("protocol_version_id", SqlValue::int(protocol_version_id)),
+                    (
+                        "proposed_protocol_version_id",
+                        SqlValue::Int(proposed_protocol_version_id),
+                    ),
+                    (
+                        "timestamp",
+                        SqlValue::text(
+                            header
+                                .protocol_state
+                                .body
+                                .blockchain_state
+                                .timestamp
+                                .as_u64(),
+                        ),
+                    ),
+                    (
+                        "chain_status",
+                        SqlValue::Enum("chain_status_type", "pending"),
+                    ),
+                ],
+            )
+            .await?;
+
+        for (sequence_no, (transaction, status)) in data.transactions.iter().enumerate() {
+            let sequence_no = sequence_no as i64;
+            match transaction {
+                MinaTransactionTransactionStableV2::Command(command) => {
+                    self.user_command(block_id, sequence_no, command, status)
+                        .await?;
+                }
+                MinaTransactionTransactionStableV2::FeeTransfer(fee_transfer) => {
+                    let fee_transfers = match fee_transfer {
+                        v2::MinaBaseFeeTransferStableV2::One(a) => vec![a],
+                        v2::MinaBaseFeeTransferStableV2::Two((a, b)) => vec![a, b],
+                    };
+                    for (secondary_sequence_no, fee_transfer) in
+                        fee_transfers.into_iter().enumerate()
+                    {
+                        let hash = fee_transfer.hash()?;
+                        self.internal_command(
+                            block_id,
+                            (sequence_no, secondary_sequence_no as i64),
+                            "fee_transfer",
+                            &fee_transfer.receiver_pk,
+                            fee_transfer.fee.as_u64(),
+                            hash,
+                            status,
+                        )
+                        .await?;
+                    }
+                }
+                MinaTransactionTransactionStableV2::Coinbase(coinbase) => {
+                    if let Some(fee_transfer) = &coinbase.fee_transfer {
+                        let single = v2::MinaBaseFeeTransferSingleStableV2 {
+                            receiver_pk: fee_transfer.receiver_pk.clone(),
+                            fee: fee_transfer.fee.clone(),
+                            fee_token: ledger::TokenId::default().into(),
+                        };
+                        self.internal_command(
+                            block_id,
+                            (sequence_no, 0),
+                            "fee_transfer_via_coinbase",
+                            &fee_transfer.receiver_pk,
+                            fee_transfer.fee.as_u64(),
+                            single.hash()?,
+                            status,
+                        )
+                        .await?;
+                    }
+                    self.internal_command(
+                        block_id,
+                        (sequence_no, 0),
+                        "coinbase",
+                        &coinbase.receiver,
+                        coinbase.amount.as_u64(),
+                        coinbase.hash()?,
+                        status,
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        for (ledger_index, account) in &data.accounts_accessed {
+            self.account_accessed(block_id, *ledger_index, account)
+                .await?;
+        }
+
+        for (account_id, creation_fee) in &data.accounts_created {
+            let account_identifier_id = self
+                .account_identifier(&account_id.0, &account_id.1.clone().into())
+                .await?;
+            self.insert_row(
+                "accounts_created",
+                &[
+                    ("block_id", SqlValue::int(block_id)),
+                    (
+                        "account_identifier_id",
+                        SqlValue::int(account_identifier_id),
+                    ),
+                    ("creation_fee", SqlValue::text(creation_fee.as_u64())),
+                ],
+            )
+            .await?;
+        }
+
+        self.update_chain_status(block_id, block.height(), block.constants().k.as_u32())
+            .await
+    }
+
+    async fn user_command(
+        &mut self,
+        block_id: i64,
+        sequence_no: i64,
+        command: &MinaBaseUserCommandStableV2,
+        status: &MinaBaseTransactionStatusStableV2,
+    ) -> Result<(), sqlx::Error> {
+        let hash = command.hash()?.to_string();
+        let (status, failures) = match status {
+            MinaBaseTransactionStatusStableV2::Applied => ("applied", Vec::new()),
+            MinaBaseTransactionStatusStableV2::Failed(failures) => (
+                "failed",
+                failures
+                    .0
+                    .iter()
+                    .flat_map(|failures| failures.iter())
+                    .map(|failure| TransactionFailure::from(failure).to_string())
+                    .collect(),
+            ),
+        };
+
+        match command {
+            MinaBaseUserCommandStableV2::SignedCommand(command) => {
+                let common = &command.payload.common;
+                let (command_type, receiver, amount) = match &command.payload.body {
+                    MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => (
+                        "payment",
+                        &payment.receiver_pk,
+                        Some(payment.amount.as_u64().to_string()),
+                    ),
+                    MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+                        MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+                    ) => ("delegation", new_delegate, None),
+                };
+
+                let user_command_id = match self
+                    .find("user_commands", &[("hash", SqlValue::text(&hash))])
+                    .await?
+                {
+                    Some(id) => id,
+                    None => {
+                        let fee_payer_id = self.public_key(&common.fee_payer_pk).await?;
+                        let receiver_id = self.public_key(receiver).await?;
+                        self.insert(
+                            "user_commands",
+                            &[
+                                (
+                                    "command_type",
+                                    SqlValue::Enum("user_command_type", command_type),
+                                ),
+                                ("fee_payer_id", SqlValue::int(fee_payer_id)),
+                                ("source_id", SqlValue::int(fee_payer_id)),
+                                ("receiver_id", SqlValue::int(receiver_id)),
+                                ("nonce", SqlValue::int(common.nonce.as_u32())),
+                                ("amount", SqlValue::Text(amount)),
+                                ("fee", SqlValue::text(common.fee.as_u64())),
+                                ("valid_until", SqlValue::int(common.valid_until.as_u32())),
+                                ("memo", SqlValue::text(common.memo.to_base58check())),
+                                ("hash", SqlValue::text(&hash)),
+                            ],
+                        )
+                        .await?
+                    }
+                };
+
+                self.insert_row(
+                    "blocks_user_commands",
+                    &[
+                        ("block_id", SqlValue::int(block_id)),
+                        ("user_command_id", SqlValue::int(user_command_id)),
+                        ("sequence_no", SqlValue::int(sequence_no)),
+                        ("status", SqlValue::Enum("transaction_status", status)),
+                        ("failure_reason", SqlValue::Text(failures.first().cloned())),
+                    ],
+                )
+                .await
+            }
+            MinaBaseUserCommandStableV2::ZkappCommand(command) => {
+                let zkapp_command_id = match self
+                    .find("zkapp_commands", &[("hash", SqlValue::text(&hash))])
+                    .await?
+                {
+                    Some(id) => id,
+                    None => {
+                        let fee_payer = &command.fee_payer.body;
+                        let public_key_id = self.public_key(&fee_payer.public_key).await?;
+                        let fee_payer_body_id = self
+                            .insert(
+                                "zkapp_fee_payer_body",
+                                &[
+                                    ("public_key_id", SqlValue::int(public_key_id)),
+                                    ("fee", SqlValue::text(fee_payer.fee.as_u64())),
+                                    (
+                                        "valid_until",
+                                        SqlValue::Int(
+                                            fee_payer
+                                                .valid_until
+                                                .as_ref()
+                                                .map(|slot| slot.as_u32().into()),
+                                        ),
+                                    ),
+                                    ("nonce", SqlValue::int(fee_payer.nonce.as_u32())),
+                                ],
+                            )
+                            .await?;
+
+                        let mut account_updates = Vec::new();
+                        for update in command.account_updates.iter() {
+                            flatten_account_updates(&update.elt, 0, &mut account_updates);
+                        }
+                        let mut account_update_ids = Vec::new();
+                        for (account_update, call_depth) in account_updates {
+                            account_update_ids
+                                .push(self.account_update(account_update, call_depth).await?);
+                        }
+
+                        self.insert(
+                            "zkapp_commands",
+                            &[
+                                ("zkapp_fee_payer_body_id", SqlValue::int(fee_payer_body_id)),
+                                (
+                                    "zkapp_account_updates_ids",
+                                    SqlValue::IntArray(account_update_ids),
+                                ),
+                                ("memo", SqlValue::text(command.memo.to_base58check())),
+                                ("hash", SqlValue::text(&hash)),
+                            ],
+                        )
+                        .await?
+                    }
+                };
+
+                self.insert_row(
+                    "blocks_zkapp_commands",
+                    &[
+                        ("block_id", SqlValue::int(block_id)),
+                        ("zkapp_command_id", SqlValue::int(zkapp_command_id)),
+                        ("sequence_no", SqlValue::int(sequence_no)),
+                        ("status", SqlValue::Enum("transaction_status", status)),
+                    ],
+                )
+                .await
+            }
+        }
+    }
+
+    async fn account_update(
+        &mut self,
+        account_update: &v2::MinaBaseAccountUpdateTStableV1,
+        call_depth: u32,
+    ) -> Result<i64, sqlx::Error> {
+        let body = &account_update.body;
+        let account_identifier_id = self
+            .account_identifier(&body.public_key, &body.token_id)
+            .await?;
+        let balance_change = match body.balance_change.sgn {
+            SgnStableV1::Pos => body.balance_change.magnitude.as_u64().to_string(),
+            SgnStableV1::Neg => format!("-{}", body.balance_change.magnitude.as_u64()),
+        };
+        let may_use_token = match body.may_use_token {
+            MinaBaseAccountUpdateMayUseTokenStableV1::No => "no",
+            MinaBaseAccountUpdateMayUseTokenStableV1::ParentsOwnToken => "parents_own_token",
+            MinaBaseAccountUpdateMayUseTokenStableV1::InheritFromParent => "inherit_from_parent",
+        };
+        let authorization_kind = match body.authorization_kind {
+            MinaBaseAccountUpdateAuthorizationKindStableV1::NoneGiven => "none_given",
+            MinaBaseAccountUpdateAuthorizationKindStableV1::Signature => "signature",
+            MinaBaseAccountUpdateAuthorizationKindStableV1::Proof(_) => "proof",
+        };
+
+        let body_id = self
+            .insert(
+                "zkapp_account_update_body",
+                &[
+                    (
+                        "account_identifier_id",
+                        SqlValue::int(account_identifier_id),
+                    ),
+                    ("balance_change", SqlValue::Text(Some(balance_change))),
+                    ("increment_nonce", SqlValue::Bool(body.increment_nonce)),
+                    ("call_depth", SqlValue::int(call_depth)),
+                    (
+                        "use_full_commitment",
+                        SqlValue::Bool(body.use_full_commitment),
+                    ),
+                    (
+                        "implicit_account_creation_fee",
+                        SqlValue::Bool(body.implicit_account_creation_fee),
+                    ),
+                    (
+                        "may_use_token",
+                        SqlValue::Enum("may_use_token", may_use_token),
+                    ),
+                    (
+                        "authorization_kind",
+                        SqlValue::Enum("authorization_kind_type", authorization_kind),
+                    ),
+                ],
+            )
+            .await?;
+        self.insert(
+            "zkapp_account_update",
+            &[("body_id", SqlValue::int(body_id))],
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn internal_command(
+        &mut self,
+        block_id: i64,
+        (sequence_no, secondary_sequence_no): (i64, i64),
+        command_type: &'static str,
+        receiver: &NonZeroCurvePoint,
+        fee: u64,
+        hash: v2::TransactionHash,
+        status: &MinaBaseTransactionStatusStableV2,
+    ) -> Result<(), sqlx::Error> {
+        let command_type = SqlValue::Enum("internal_command_type", command_type);
+        let hash = SqlValue::text(hash);
+        let internal_command_id = match self
+            .find(
+                "internal_commands",
+                &[
+                    ("hash", hash.clone()),
+                    ("command_type", command_type.clone()),
+                ],
+            )
+            .await?
+        {
+            Some(id) => id,
+            None => {
+                let receiver_id = self.public_key(receiver).await?;
+                self.insert(
+                    "internal_commands",
+                    &[
+                        ("command_type", command_type),
+                        ("receiver_id", SqlValue::int(receiver_id)),
+                        ("fee", SqlValue::text(fee)),
+                        ("hash", hash),
+                    ],
+                )
+                .await?
+            }
+        };
+
+        let (status, failure_reason) = match status {
+            MinaBaseTransactionStatusStableV2::Applied => ("applied", None),
+            MinaBaseTransactionStatusStableV2::Failed(failures) => (
+                "failed",
+                failures
+                    .0
+                    .iter()
+                    .flat_map(|failures| failures.iter())
+                    .next()
+                    .map(|failure| TransactionFailure::from(failure).to_string()),
+            ),
+        };
+        self.insert_row(
+            "blocks_internal_commands",
+            &[
+                ("block_id", SqlValue::int(block_id)),
+                ("internal_command_id", SqlValue::int(internal_command_id)),
+                ("sequence_no", SqlValue::int(sequence_no)),
+                (
+                    "secondary_sequence_no",
+                    SqlValue::int(secondary_sequence_no),
+                ),
+                ("status", SqlValue::Enum("transaction_status", status)),
+                ("failure_reason", SqlValue::Text(failure_reason)),
+            ],
+        )
+        .await
+    }
+
+    async fn account_accessed(
+        &mut self,
+        block_id: i64,
+        ledger_index: u64,
+        account: &v2::MinaBaseAccountBinableArgStableV2,
+    ) -> Result<(), sqlx::Error> {
+        let account_identifier_id = self
+            .account_identifier(&account.public_key, &account.token_id)
+            .await?;
+        let token_symbol = String::from_utf8_lossy(&account.token_symbol).into_owned();
+        let token_symbol_id = self
+            .find_or_insert("token_symbols", &[("value", SqlValue::text(token_symbol))])
+            .await?;
+        let delegate_id = match &account.delegate {
+            Some(delegate) => Some(self.public_key(delegate).await?),
+            None => None,
+        };
+        let voting_for_id = self
+            .find_or_insert(
+                "voting_for",
+                &[("value", SqlValue::text(&account.voting_for))],
+            )
+            .await?;
+        let timing_id = match &account.timing {
+            MinaBaseAccountTimingStableV2::Untimed => None,
+            MinaBaseAccountTimingStableV2::Timed {
+                initial_minimum_balance,
+                cliff_time,
+                cliff_amount,
+                vesting_period,
+                vesting_increment,
+            } => Some(
+                self.find_or_insert(
+                    "timing_info",
+                    &[
+                        (
+                            "account_identifier_id",
+                            SqlValue::int(account_identifier_id),
+                        ),
+                        (
+                            "initial_minimum_balance",
+                            SqlValue::text(initial_minimum_balance.as_u64()),
+                        ),
+                        ("cliff_time", SqlValue::int(cliff_time.as_u32())),
+                        ("cliff_amount", SqlValue::text(cliff_amount.as_u64())),
+                        ("vesting_period", SqlValue::int(vesting_period.as_u32())),
+                        (
+                            "vesting_increment",
+                            SqlValue::text(vesting_increment.as_u64()),
+                        ),
+                    ],
+                )
+                .await?,
+            ),
+        };
+        let receipt_chain_hash = v2::ReceiptChainHash::from(account.receipt_chain_hash.clone());
+
+        self.insert_row(
+            "accounts_accessed",
+            &[
+                ("ledger_index", SqlValue::int(ledger_index as i64)),
+                ("block_id", SqlValue::int(block_id)),
+                (
+                    "account_identifier_id",
+                    SqlValue::int(account_identifier_id),
+                ),
+                ("token_symbol_id", SqlValue::int(token_symbol_id)),
+                ("balance", SqlValue::text(account.balance.as_u64())),
+                ("nonce", SqlValue::int(account.nonce.as_u32())),
+                ("receipt_chain_hash", SqlValue::text(receipt_chain_hash)),
+                ("delegate_id", SqlValue::Int(delegate_id)),
+                ("voting_for_id", SqlValue::int(voting_for_id)),
+                ("timing_id", SqlValue::Int(timing_id)),
+            ],
+        )
+        .await
+    }
+
+    /// When the block is the new highest one, its ancestors that are at
+    /// least `k` blocks deep become canonical, and all other pending
+    /// blocks at those heights are orphaned.
+    async fn update_chain_status(
+        &mut self,
+        block_id: i64,
+        height: u32,
+        k: u32,
+    ) -> Result<(), sqlx::Error> {
+        let max_height: i64 = sqlx::query("SELECT MAX(height) FROM blocks")
+            .fetch_one(&mut *self.conn)
+            .await?
+            .try_get(0)?;
+        let Some(final_height) = height.checked_sub(k) else {
+            return Ok(());
+        };
+        if max_height > height as i64 {
+            return Ok(());
+        }
+
+        let sql = "WITH RECURSIVE chain (id, parent_id) AS ( \
+                SELECT id, parent_id FROM blocks WHERE id = $1 \
+                UNION ALL \
+                SELECT b.id, b.parent_id FROM blocks b \
+                INNER JOIN chain c ON b.id = c.parent_id \
+                WHERE b.chain_status <> 'canonical' \
+            ) \
+            UPDATE blocks SET chain_status = 'canonical' \
+            WHERE height <= $2 AND id IN (SELECT id FROM chain)";
+        self.execute(
+            sql,
+            vec![SqlValue::int(block_id), SqlValue::int(final_height)],
+        )
+        .await?;
+
+        let sql = "UPDATE blocks SET chain_status = 'orphaned' \
+            WHERE height <= $1 AND chain_status = 'pending'";
+        self.execute(sql, vec![SqlValue::int(final_height)]).await
+    }
+}
+
+/// Account updates of the call forest in pre-order, with their call depth.
+fn flatten_account_updates<'a>(
+    update: &'a MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesAA,
+    call_depth: u32,
+    account_updates: &mut Vec<(&'a v2::MinaBaseAccountUpdateTStableV1, u32)>,
+) {
+    account_updates.push((&update.account_update, call_depth));
+    for call in update.calls.iter() {
+        flatten_account_updates(&call.elt, call_depth + 1, account_updates);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ledger::scan_state::{
+        currency::{Amount, Fee, Nonce},
+        transaction_logic::{
+            signed_command::{Body, PaymentPayload, SignedCommand, SignedCommandPayload},
+            Memo,
+        },
+    };
+    use node::{
+        account::AccountSecretKey,
+        core::block::BlockWithHash,
+        testing::{child_block, genesis_block},
+    };
+
+    use super::*;
+
+    fn genesis() -> ArcBlockWithHash {
+        let mut block = (*genesis_block().block).clone();
+        // Small `k`, so that blocks become final within a short chain.
+        block.header.protocol_state.body.constants.k = 2.into();
+        BlockWithHash::try_new(Arc::new(block)).unwrap()
+    }
+
+    fn payment() -> MinaTransactionTransactionStableV2 {
+        let fee_payer = AccountSecretKey::deterministic(0).public_key_compressed();
+        let payload = SignedCommandPayload::create(
+            Fee::from_u64(10_000_000),
+            fee_payer.clone(),
+            Nonce::from_u32(0),
+            None,
+            Memo::empty(),
+            Body::Payment(PaymentPayload {
+                receiver_pk: AccountSecretKey::deterministic(1).public_key_compressed(),
+                amount: Amount::from_u64(1_000_000_000),
+            }),
+        );
+        let command = SignedCommand {
+            payload,
+            signer: fee_payer,
+            signature: mina_signer::Signature::dummy(),
+        };
+        MinaTransactionTransactionStableV2::Command(Box::new(
+            MinaBaseUserCommandStableV2::SignedCommand((&command).into()),
+        ))
+    }
+
+    fn coinbase() -> MinaTransactionTransactionStableV2 {
+        MinaTransactionTransactionStableV2::Coinbase(v2::MinaBaseCoinbaseStableV1 {
+            receiver: AccountSecretKey::deterministic(2).public_key().into(),
+            amount: v2::CurrencyAmountStableV1(720_000_000_000u64.into()),
+            fee_transfer: None,
+        })
+    }
+
+    fn archive(transactions: Vec<MinaTransactionTransactionStableV2>) -> BlockApplyResultArchive {
+        BlockApplyResultArchive {
+            transactions: transactions
+                .into_iter()
+                .map(|transaction| (transaction, MinaBaseTransactionStatusStableV2::Applied))
+                .collect(),
+            accounts_accessed: Vec::new(),
+            accounts_created: Vec::new(),
+        }
+    }
+
+    fn count(storage: &SqlArchiveStorage, sql: &str) -> i64 {
+        storage
+            .runtime
+            .block_on(sqlx::query(sql).fetch_one(&storage.pool))
+            .unwrap()
+            .get(0)
+    }
+
+    fn chain_status(storage: &SqlArchiveStorage, block: &ArcBlockWithHash) -> String {
+        storage
+            .runtime
+            .block_on(
+                sqlx::query("SELECT chain_status FROM blocks WHERE state_hash = $1")
+                    .bind(block.hash().to_string())
+                    .fetch_one(&storage.pool),
+            )
+            .unwrap()
+            .get(0)
+    }
+
+    #[test]
+    fn sqlite_schema_is_idempotent() {
+        let storage = SqlArchiveStorage::connect("sqlite::memory:").unwrap();
+        storage
+            .runtime
+            .block_on(async {
+                for statement in storage.dialect.schema() {
+                    sqlx::query(&statement).execute(&storage.pool).await?;
+                }
+                sqlx::query("SELECT COUNT(*) FROM blocks")
+                    .fetch_one(&storage.pool)
+                    .await
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn sqlite_chain_with_fork() {
+        let mut storage = SqlArchiveStorage::connect("sqlite::memory:").unwrap();
+
+        // g <- a <- b <- c <- d
+        //         \- f
+        let g = genesis();
+        let a = child_block(&g, 1);
+        let b = child_block(&a, 2);
+        let f = child_block(&a, 3);
+        let c = child_block(&b, 4);
+        let d = child_block(&c, 5);
+
+        let empty = archive(vec![]);
+        let with_commands = archive(vec![payment(), coinbase()]);
+
+        storage.store_block(&g, &empty).unwrap();
+        storage.store_block(&a, &empty).unwrap();
+        storage.store_block(&b, &with_commands).unwrap();
+        storage.store_block(&f, &with_commands).unwrap();
+        storage.store_block(&c, &empty).unwrap();
+        // Already stored, ignored.
+        storage.store_block(&c, &with_commands).unwrap();
+
+        assert_eq!(count(&storage, "SELECT COUNT(*) FROM blocks"), 5);
+        assert_eq!(chain_status(&storage, &g), "canonical");
+        assert_eq!(chain_status(&storage, &a), "canonical");
+        assert_eq!(chain_status(&storage, &b), "pending");
+        assert_eq!(chain_status(&storage, &f), "pending");
+        assert_eq!(chain_status(&storage, &c), "pending");
+
+        storage.store_block(&d, &empty).unwrap();
+        assert_eq!(chain_status(&storage, &b), "canonical");
+        assert_eq!(chain_status(&storage, &f), "orphaned");
+        assert_eq!(chain_status(&storage, &c), "pending");
+        assert_eq!(chain_status(&storage, &d), "pending");
+
+        let parent_hash: String = storage
+            .runtime
+            .block_on(
+                sqlx::query(
+                    "SELECT p.state_hash FROM blocks b \
+                    INNER JOIN blocks p ON p.id = b.parent_id WHERE b.state_hash = $1",
+                )
+                .bind(f.hash().to_string())
+                .fetch_one(&storage.pool),
+            )
+            .unwrap()
+            .get(0);
+        assert_eq!(parent_hash, a.hash().to_string());
+
+        // The same commands included in both forks are stored once, and
+        // linked to each of the blocks.
+        assert_eq!(count(&storage, "SELECT COUNT(*) FROM user_commands"), 1);
+        assert_eq!(
+            count(&storage, "SELECT COUNT(*) FROM blocks_user_commands"),
+            2
+        );
+        assert_eq!(
+            count(
+                &storage,
+                "SELECT COUNT(*) FROM internal_commands WHERE command_type = 'coinbase'"
+            ),
+            1
+        );
+        assert_eq!(
+            count(
+                &storage,
+                "SELECT COUNT(*) FROM blocks_internal_commands WHERE sequence_no = 1"
+            ),
+            2
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! Subset of the [Mina archive schema](https://github.com/MinaProtocol/mina/blob/compatible/src/app/archive/create_schema.sql)
+//! which is written by the archive.
+//!
+//! Table and column names match the upstream schema, so that existing
+//! queries keep working. zkApp commands are stored in reduced form: only
+//! the fee payer and the account update bodies' account, balance change and
+//! authorization are kept, while account updates' `update`, preconditions,
+//! events and actions, as well as accounts' permissions and zkApp state,
+//! are not archived.
+
+/// SQL dialect of the archive database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    Sqlite,
+    Postgres,
+}
+
+const ENUM_TYPES: &[(&str, &[&str])] = &[
+    ("chain_status_type", &["canonical", "orphaned", "pending"]),
+    ("user_command_type", &["payment", "delegation"]),
+    (
+        "internal_command_type",
+        &["fee_transfer_via_coinbase", "fee_transfer", "coinbase"],
+    ),
+    ("transaction_status", &["applied", "failed"]),
+    (
+        "authorization_kind_type",
+        &["none_given", "signature", "proof"],
+    ),
+    (
+        "may_use_token",
+        &["no", "parents_own_token", "inherit_from_parent"],
+    ),
+];
+
+const TABLES: &str = "
+CREATE TABLE IF NOT EXISTS public_keys
+( id {serial}
+, value text NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS tokens
+( id {serial}
+, value text NOT NULL UNIQUE
+, owner_public_key_id int REFERENCES public_keys(id) ON DELETE CASCADE
+, owner_token_id int REFERENCES tokens(id)
+);
+
+CREATE TABLE IF NOT EXISTS token_symbols
+( id {serial}
+, value text NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS account_identifiers
+( id {serial}
+, public_key_id int NOT NULL REFERENCES public_keys(id) ON DELETE CASCADE
+, token_id int NOT NULL REFERENCES tokens(id) ON DELETE CASCADE
+, UNIQUE (public_key_id, token_id)
+);
+
+CREATE TABLE IF NOT EXISTS timing_info
+( id {serial}
+, account_identifier_id int NOT NULL REFERENCES account_identifiers(id)
+, initial_minimum_balance text NOT NULL
+, cliff_time bigint NOT NULL
+, cliff_amount text NOT NULL
+, vesting_period bigint NOT NULL
+, vesting_increment text NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS snarked_ledger_hashes
+( id {serial}
+, value text NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS voting_for
+( id {serial}
+, value text NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS protocol_versions
+( id {serial}
+, \"transaction\" int NOT NULL
+, network int NOT NULL
+, patch int NOT NULL
+, UNIQUE (\"transaction\", network, patch)
+);
+
+CREATE TABLE IF NOT EXISTS epoch_data
+( id {serial}
+, seed text NOT NULL
+, ledger_hash_id int NOT NULL REFERENCES snarked_ledger_hashes(id)
+, total_currency text NOT NULL
+, start_checkpoint text NOT NULL
+, lock_checkpoint text NOT NULL
+, epoch_length bigint NOT NULL
+, UNIQUE (seed, ledger_hash_id, total_currency, start_checkpoint, lock_checkpoint, epoch_length)
+);
+
+CREATE TABLE IF NOT EXISTS blocks
+( id {serial}
+, state_hash text NOT NULL UNIQUE
+, parent_id int REFERENCES blocks(id)
+, parent_hash text NOT NULL
+, creator_id int NOT NULL REFERENCES public_keys(id)
+, block_winner_id int NOT NULL REFERENCES public_keys(id)
+, last_vrf_output text NOT NULL
+, snarked_ledger_hash_id int NOT NULL REFERENCES snarked_ledger_hashes(id)
+, staking_epoch_data_id int NOT NULL REFERENCES epoch_data(id)
+, next_epoch_data_id int NOT NULL REFERENCES epoch_data(id)
+, min_window_density bigint NOT NULL
+, sub_window_densities {int_array} NOT NULL
+, total_currency text NOT NULL
+, ledger_hash text NOT NULL
+, height bigint NOT NULL
+, global_slot_since_hard_fork bigint NOT NULL
+, global_slot_since_genesis bigint NOT NULL
+, protocol_version_id int NOT NULL REFERENCES protocol_versions(id)
+, proposed_protocol_version_id int REFERENCES protocol_versions(id)
+, timestamp text NOT NULL
+, chain_status {chain_status_type} NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_blocks_parent_id ON blocks(parent_id);
+CREATE INDEX IF NOT EXISTS idx_blocks_creator_id ON blocks(creator_id);
+CREATE INDEX IF NOT EXISTS idx_blocks_height ON blocks(height);
+CREATE INDEX IF NOT EXISTS idx_chain_status ON blocks(chain_status);
+
+CREATE TABLE IF NOT EXISTS user_commands
+( id {serial}
+, command_type {user_command_type} NOT NULL
+, fee_payer_id int NOT NULL REFERENCES public_keys(id)
+, source_id int NOT NULL REFERENCES public_keys(id)
+, receiver_id int NOT NULL REFERENCES public_keys(id)
+, nonce bigint NOT NULL
+, amount text
+, fee text NOT NULL
+, valid_until bigint
+, memo text NOT NULL
+, hash text NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS internal_commands
+( id {serial}
+, command_type {internal_command_type} NOT NULL
+, receiver_id int NOT NULL REFERENCES public_keys(id)
+, fee text NOT NULL
+, hash text NOT NULL
+, UNIQUE (hash, command_type)
+);
+
+CREATE TABLE IF NOT EXISTS zkapp_fee_payer_body
+( id {serial}
+, public_key_id int NOT NULL REFERENCES public_keys(id)
+, fee text NOT NULL
+, valid_until bigint
+, nonce bigint NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS zkapp_account_update_body
+( id {serial}
+, account_identifier_id int NOT NULL REFERENCES account_identifiers(id)
+, balance_change text NOT NULL
+, increment_nonce boolean NOT NULL
+, call_depth int NOT NULL
+, use_full_commitment boolean NOT NULL
+, implicit_account_creation_fee boolean NOT NULL
+, may_use_token {may_use_token} NOT NULL
+, authorization_kind {authorization_kind_type} NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS zkapp_account_update
+( id {serial}
+, body_id int NOT NULL REFERENCES zkapp_account_update_body(id)
+);
+
+CREATE TABLE IF NOT EXISTS zkapp_commands
+( id {serial}
+, zkapp_fee_payer_body_id int NOT NULL REFERENCES zkapp_fee_payer_body(id)
+, zkapp_account_updates_ids {int_array} NOT NULL
+, memo text NOT NULL
+, hash text NOT NULL UNIQUE
+);
+
+CREATE TABLE IF NOT EXISTS blocks_user_commands
+( block_id int NOT NULL REFERENCES blocks(id) ON DELETE CASCADE
+, user_command_id int NOT NULL REFERENCES user_commands(id) ON DELETE CASCADE
+, sequence_no int NOT NULL
+, status {transaction_status} NOT NULL
+, failure_reason text
+, PRIMARY KEY (block_id, user_command_id, sequence_no)
+);
+
+CREATE TABLE IF NOT EXISTS blocks_internal_commands
+( block_id int NOT NULL REFERENCES blocks(id) ON DELETE CASCADE
+, internal_command_id int NOT NULL REFERENCES internal_commands(id) ON DELETE CASCADE
+, sequence_no int NOT NULL
+, secondary_sequence_no int NOT NULL
+, status {transaction_status} NOT NULL
+, failure_reason text
+, PRIMARY KEY (block_id, internal_command_id, sequence_no, secondary_sequence_no)
+);
+
+CREATE TABLE IF NOT EXISTS blocks_zkapp_commands
+( block_id int NOT NULL REFERENCES blocks(id) ON DELETE CASCADE
+, zkapp_command_id int NOT NULL REFERENCES zkapp_commands(id) ON DELETE CASCADE
+, sequence_no int NOT NULL
+, status {transaction_status} NOT NULL
+, failure_reasons_ids {int_array}
+, PRIMARY KEY (block_id, zkapp_command_id, sequence_no)
+);
+
+CREATE TABLE IF NOT EXISTS accounts_accessed
+( ledger_index int NOT NULL
+, block_id int NOT NULL REFERENCES blocks(id)
+, account_identifier_id int NOT NULL REFERENCES account_identifiers(id)
+, token_symbol_id int NOT NULL REFERENCES token_symbols(id)
+, balance text NOT NULL
+, nonce bigint NOT NULL
+, receipt_chain_hash text NOT NULL
+, delegate_id int REFERENCES public_keys(id)
+, voting_for_id int NOT NULL REFERENCES voting_for(id)
+, timing_id int REFERENCES timing_info(id)
+, permissions_id int
+, zkapp_id int
+, PRIMARY KEY (block_id, account_identifier_id)
+);
+
+CREATE TABLE IF NOT EXISTS accounts_created
+( block_id int NOT NULL REFERENCES blocks(id)
+, account_identifier_id int NOT NULL REFERENCES account_identifiers(id)
+, creation_fee text NOT NULL
+, PRIMARY KEY (block_id, account_identifier_id)
+);
+";
+
+impl SqlDialect {
+    pub fn from_url(url: &str) -> Option<Self> {
+        if url.starts_with("sqlite:") {
+            Some(Self::Sqlite)
+        } else if url.starts_with("postgres:") || url.starts_with("postgresql:") {
+            Some(Self::Postgres)
+        } else {
+            None
+        }
+    }
+
+    /// Statements creating the schema, if it doesn't exist yet.
+    pub fn schema(self) -> Vec<String> {
+        let (serial, int_array) = match self {
+            Self::Sqlite => ("INTEGER PRIMARY KEY AUTOINCREMENT", "text"),
+            Self::Postgres => ("serial PRIMARY KEY", "int[]"),
+        };
+
+        let mut statements = Vec::new();
+        let mut tables = TABLES
+            .replace("{serial}", serial)
+            .replace("{int_array}", int_array);
+        for (name, values) in ENUM_TYPES {
+            let column_type = match self {
+                Self::Sqlite => "text",
+                Self::Postgres => {
+                    let values = values
+                        .iter()
+                        .map(|value| format!("'{value}'"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    // Postgres has no `CREATE TYPE IF NOT EXISTS`.
+                    statements.push(format!(
+                        "DO $$ BEGIN CREATE TYPE {name} AS ENUM ({values}); \
+                         EXCEPTION WHEN duplicate_object THEN NULL; END $$"
+                    ));
+                    name
+                }
+            };
+            tables = tables.replace(&format!("{{{name}}}"), column_type);
+        }
+        statements.extend(
+            tables
+                .split(';')
+                .map(str::trim)
+                .filter(|statement| !statement.is_empty())
+                .map(str::to_owned),
+        );
+        statements
+    }
+
+    /// Placeholder for the `index`th parameter, cast to `pg_type` where
+    /// Postgres can't infer it from a text value.
+    pub fn param(self, index: usize, pg_type: Option<&str>) -> String {
+        match (self, pg_type) {
+            (Self::Postgres, Some(pg_type)) => format!("CAST(${index} AS {pg_type})"),
+            _ => format!("${index}"),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use node::{core::channels::mpsc, event_sink::NodeEvent};
+use openmina_node_common::event_sink::EventSink;
+
+/// Appends the events to a file, one JSON object per line (NDJSON).
+pub struct NdjsonFileEventSink {
+    file: BufWriter<File>,
+}
+
+impl NdjsonFileEventSink {
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: BufWriter::new(file),
+        })
+    }
+}
+
+impl EventSink for NdjsonFileEventSink {
+    fn publish(&mut self, event: &NodeEvent) -> Result<(), String> {
+        serde_json::to_writer(&mut self.file, event).map_err(|e| e.to_string())?;
+        self.file.write_all(b"\n").map_err(|e| e.to_string())?;
+        self.file.flush().map_err(|e| e.to_string())
+    }
+}
+
+/// POSTs each event as JSON to the url.
+pub struct WebhookEventSink {
+    client: reqwest::blocking::Client,
+    url: String,
+}
+
+impl WebhookEventSink {
+    pub fn new(url: String) -> Self {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("failed to build http client");
+        Self { client, url }
+    }
+}
+
+impl EventSink for WebhookEventSink {
+    fn publish(&mut self, event: &NodeEvent) -> Result<(), String> {
+        self.client
+            .post(&self.url)
+            .json(event)
+            .send()
+            .and_then(|res| res.error_for_status())
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Number of events buffered for an SSE client, clients that fall further
+/// behind are disconnected.
+const SSE_EVENTS_BUFFER: usize = 1024;
+
+/// Forwards the events to the clients connected to the `/events`
+/// Server-Sent Events endpoint of the http server.
+#[derive(Clone, Default)]
+pub struct SseEventSink {
+    subscribers: Arc<Mutex<Vec<mpsc::Sender<NodeEvent>>>>,
+}
+
+impl SseEventSink {
+    pub fn subscribe(&self) -> mpsc::Receiver<NodeEvent> {
+        let (tx, rx) = mpsc::channel(SSE_EVENTS_BUFFER);
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+}
+
+impl EventSink for SseEventSink {
+    fn publish(&mut self, event: &NodeEvent) -> Result<(), String> {
+        // Drop the subscribers whose client disconnected or lags behind,
+        // the stream of a dropped subscriber ends once it drains the buffer.
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|tx| tx.try_send(event.clone()).is_ok());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use node::event_sink::NodeEventKind;
+
+    use super::*;
+
+    fn event(global_slot: u32) -> NodeEvent {
+        NodeEvent::new(
+            redux::Timestamp::ZERO,
+            NodeEventKind::SlotMissed {
+                global_slot,
+                reason: String::new(),
+            },
+        )
+    }
+
+    fn global_slot(event: NodeEvent) -> u32 {
+        match event.kind {
+            NodeEventKind::SlotMissed { global_slot, .. } => global_slot,
+            kind => panic!("unexpected event: {kind:?}"),
+        }
+    }
+
+    #[test]
+    fn sse_drops_lagging_subscribers() {
+        let mut sink = SseEventSink::default();
+        let mut lagging = sink.subscribe();
+        let mut reading = sink.subscribe();
+
+        for slot in 0..=SSE_EVENTS_BUFFER as u32 {
+            sink.publish(&event(slot)).unwrap();
+            assert_eq!(global_slot(reading.try_recv().unwrap()), slot);
+        }
+        assert_eq!(sink.subscribers.lock().unwrap().len(), 1);
+
+        // The lagging subscriber still gets the buffered events.
+        for slot in 0..SSE_EVENTS_BUFFER as u32 {
+            assert_eq!(global_slot(lagging.try_recv().unwrap()), slot);
+        }
+        assert!(matches!(
+            lagging.try_recv(),
+            Err(mpsc::error::TryRecvError::Disconnected)
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 use juniper::GraphQLObject;
 use openmina_core::block::AppliedBlock;
 
+use crate::graphql::user_command::GraphQLUserCommand;
 use crate::graphql::zkapp::{GraphQLFailureReason, GraphQLFeePayer, GraphQLZkappCommand};
 
 use super::{zkapp::GraphQLZkapp, ConversionError};
@@ -15,6 +16,7 @@ pub struct GraphQLBestChainBlock {
 
 #[derive(GraphQLObject, Debug)]
 pub struct GraphQLTransactions {
+    pub user_commands: Vec<GraphQLUserCommand>,
     pub zkapp_commands: Vec<GraphQLZkapp>,
 }
 
@@ -123,12 +125,38 @@ impl TryFrom<mina_p2p_messages::v2::StagedLedgerDiffDiffDiffStableV2> for GraphQ
             .1
             .map_or_else(Vec::new, |v| v.commands.into_iter().collect::<Vec<_>>());
 
-        let zkapp_commands = value
+        let commands = value
             .0
             .commands
             .into_iter()
             .chain(also_zkapp_commands)
             .rev()
+            .collect::<Vec<_>>();
+
+        let user_commands = commands
+            .iter()
+            .filter_map(|cmd| {
+                let MinaBaseUserCommandStableV2::SignedCommand(signed_cmd) = &cmd.data else {
+                    return None;
+                };
+                Some(
+                    GraphQLUserCommand::try_from(signed_cmd).map(|mut user_command| {
+                        if let MinaBaseTransactionStatusStableV2::Failed(failures) = &cmd.status {
+                            user_command.failure_reason = failures
+                                .0
+                                .iter()
+                                .flat_map(|failure_list| failure_list.iter())
+                                .next()
+                                .map(|failure| failure.to_string());
+                        }
+                        user_command
+                    }),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let zkapp_commands = commands
+            .into_iter()
             .map(|cmd| {
                 // std::fs::create_dir_all("zkapps").unwrap();
                 // let zkapp_path = format!("zkapps/{}", zkapp.hash().unwrap());
@@ -186,7 +214,10 @@ impl TryFrom<mina_p2p_messages::v2::StagedLedgerDiffDiffDiffStableV2> for GraphQ
             .into_iter()
             .flatten()
             .collect::<Vec<_>>();
-        Ok(Self { zkapp_commands })
+        Ok(Self {
+            user_commands,
+            zkapp_commands,
+        })
     }
 }
 
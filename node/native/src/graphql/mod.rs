@@ -1,14 +1,22 @@
 use std::str::FromStr;
+use std::sync::Arc;
 
 use juniper::{graphql_value, FieldError};
-use juniper::{EmptySubscription, GraphQLEnum, RootNode};
+use juniper::{GraphQLEnum, RootNode};
+use juniper_graphql_ws::ConnectionConfig;
+use ledger::scan_state::currency::Nonce;
 use ledger::Account;
 use mina_p2p_messages::v2::MinaBaseSignedCommandStableV2;
 use mina_p2p_messages::v2::MinaBaseUserCommandStableV2;
 use mina_p2p_messages::v2::MinaBaseZkappCommandTStableV1WireStableV1;
 use mina_p2p_messages::v2::TokenIdKeyHash;
+use mina_p2p_messages::v2::{NonZeroCurvePoint, StateHash};
+use node::rpc::RpcSnarkPoolGetResponse;
+use node::rpc::RpcStatusGetResponse;
 use node::rpc::RpcTransactionInjectResponse;
+use node::rpc::RpcTransactionInjectSuccess;
 use node::rpc::RpcTransactionInjectedCommand;
+use node::rpc::RpcTransactionPoolResponse;
 use node::rpc::RpcTransactionStatusGetResponse;
 use node::{
     account::AccountPublicKey,
@@ -24,6 +32,9 @@ use warp::{Filter, Rejection, Reply};
 pub mod account;
 pub mod block;
 pub mod constants;
+pub mod snark_pool;
+pub mod subscription;
+pub mod user_command;
 pub mod zkapp;
 
 #[derive(Debug, thiserror::Error)]
@@ -32,6 +43,8 @@ pub enum Error {
     Conversion(ConversionError),
     #[error("State machine empty response")]
     StateMachineEmptyResponse,
+    #[error("Signature is required, the node doesn't hold private keys")]
+    SignatureRequired,
     #[error("Custom: {0}")]
     Custom(String),
 }
@@ -66,11 +79,12 @@ pub enum ConversionError {
     Custom(String),
 }
 
+#[derive(Clone)]
 struct Context(RpcSender);
 
 impl juniper::Context for Context {}
 
-#[derive(Clone, Copy, Debug, GraphQLEnum)]
+#[derive(Clone, Copy, Debug, PartialEq, GraphQLEnum)]
 #[allow(clippy::upper_case_acronyms)]
 enum SyncStatus {
     CONNECTING,
@@ -167,24 +181,7 @@ impl Query {
     }
 
     async fn sync_status(context: &Context) -> juniper::FieldResult<SyncStatus> {
-        let state: RpcSyncStatsGetResponse = context
-            .0
-            .oneshot_request(RpcRequest::SyncStatsGet(SyncStatsQuery { limit: Some(1) }))
-            .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
-
-        if let Some(state) = state.as_ref().and_then(|s| s.first()) {
-            if state.synced.is_some() {
-                Ok(SyncStatus::SYNCED)
-            } else {
-                match &state.kind {
-                    SyncKind::Bootstrap => Ok(SyncStatus::BOOTSTRAP),
-                    SyncKind::Catchup => Ok(SyncStatus::CATCHUP),
-                }
-            }
-        } else {
-            Ok(SyncStatus::LISTENING)
-        }
+        Ok(sync_status(context).await?)
     }
     async fn best_chain(
         max_length: i32,
@@ -264,6 +261,206 @@ impl Query {
             .ok_or(Error::StateMachineEmptyResponse)?;
         Ok(res.to_string())
     }
+
+    /// Retrieve all the scheduled user commands for a specified sender that
+    /// the current daemon sees in its transaction pool. All scheduled
+    /// commands are queried if no sender is specified
+    async fn pooled_user_commands(
+        public_key: Option<user_command::GraphQLPublicKey>,
+        hashes: Option<Vec<String>>,
+        ids: Option<Vec<String>>,
+        context: &Context,
+    ) -> juniper::FieldResult<Vec<user_command::GraphQLUserCommand>> {
+        let filter = PooledCommandsFilter::new(public_key, hashes, ids)?;
+        let mut res = Vec::new();
+        for command in pooled_commands(context).await? {
+            let MinaBaseUserCommandStableV2::SignedCommand(command) = command else {
+                continue;
+            };
+            let user_command = user_command::GraphQLUserCommand::try_from(&command)?;
+            if filter.matches(
+                &command.payload.common.fee_payer_pk,
+                &user_command.hash,
+                &user_command.id,
+            ) {
+                res.push(user_command);
+            }
+        }
+        Ok(res)
+    }
+
+    /// Retrieve all the scheduled zkApp commands for a specified sender that
+    /// the current daemon sees in its transaction pool. All scheduled
+    /// commands are queried if no sender is specified
+    async fn pooled_zkapp_commands(
+        public_key: Option<user_command::GraphQLPublicKey>,
+        hashes: Option<Vec<String>>,
+        ids: Option<Vec<String>>,
+        context: &Context,
+    ) -> juniper::FieldResult<Vec<zkapp::GraphQLZkapp>> {
+        let filter = PooledCommandsFilter::new(public_key, hashes, ids)?;
+        let mut res = Vec::new();
+        for command in pooled_commands(context).await? {
+            let MinaBaseUserCommandStableV2::ZkappCommand(zkapp) = &command else {
+                continue;
+            };
+            let fee_payer = zkapp.fee_payer.body.public_key.clone();
+            let zkapp::GraphQLSendZkappResponse { zkapp } = command.try_into()?;
+            if filter.matches(&fee_payer, &zkapp.hash, &zkapp.id) {
+                res.push(zkapp);
+            }
+        }
+        Ok(res)
+    }
+
+    /// Retrieve a block in the transition frontier with the given state
+    /// hash or height
+    async fn block(
+        state_hash: Option<String>,
+        height: Option<i32>,
+        context: &Context,
+    ) -> juniper::FieldResult<block::GraphQLBestChainBlock> {
+        let state_hash = state_hash.as_deref().map(StateHash::from_str).transpose()?;
+        if state_hash.is_some() == height.is_some() {
+            return Err(Error::Custom(
+                "Must provide exactly one of stateHash or height".to_string(),
+            )
+            .into());
+        }
+
+        let best_chain: Vec<AppliedBlock> = context
+            .0
+            .oneshot_request(RpcRequest::BestChain(u32::MAX))
+            .await
+            .ok_or(Error::StateMachineEmptyResponse)?;
+        let block = best_chain
+            .into_iter()
+            .find(|block| match (&state_hash, height) {
+                (Some(state_hash), _) => block.hash() == state_hash,
+                (_, Some(height)) => u32::try_from(height).ok() == Some(block.height()),
+                _ => false,
+            })
+            .ok_or_else(|| {
+                Error::Custom("Block not found in the transition frontier".to_string())
+            })?;
+
+        Ok(block.try_into()?)
+    }
+
+    /// List of completed snark works that have the lowest fee so far
+    async fn snark_pool(
+        context: &Context,
+    ) -> juniper::FieldResult<Vec<snark_pool::GraphQLCompletedWork>> {
+        let jobs: RpcSnarkPoolGetResponse = context
+            .0
+            .oneshot_request(RpcRequest::SnarkPoolGet)
+            .await
+            .ok_or(Error::StateMachineEmptyResponse)?;
+
+        Ok(jobs
+            .into_iter()
+            .filter_map(|job| Some(snark_pool::GraphQLCompletedWork::new(job.id, job.snark?)))
+            .collect())
+    }
+
+    /// Accounts for which the daemon tracks the private key, the accounts of
+    /// the block producer keys
+    async fn tracked_accounts(
+        context: &Context,
+    ) -> juniper::FieldResult<Vec<account::GraphQLAccount>> {
+        let status: RpcStatusGetResponse = context
+            .0
+            .oneshot_request(RpcRequest::StatusGet)
+            .await
+            .ok_or(Error::StateMachineEmptyResponse)?;
+        let public_keys = status.map_or(vec![], |status| status.block_producers);
+
+        let mut accounts = vec![];
+        for public_key in public_keys {
+            let found: Vec<Account> = context
+                .0
+                .oneshot_request(RpcRequest::LedgerAccountsGet(
+                    AccountQuery::PubKeyWithTokenId(public_key, ledger::TokenId::default().into()),
+                ))
+                .await
+                .ok_or(Error::StateMachineEmptyResponse)?;
+            accounts.extend(found);
+        }
+
+        Ok(accounts
+            .into_iter()
+            .map(|account| account.try_into())
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+async fn sync_status(context: &Context) -> Result<SyncStatus, Error> {
+    let state: RpcSyncStatsGetResponse = context
+        .0
+        .oneshot_request(RpcRequest::SyncStatsGet(SyncStatsQuery { limit: Some(1) }))
+        .await
+        .ok_or(Error::StateMachineEmptyResponse)?;
+
+    if let Some(state) = state.as_ref().and_then(|s| s.first()) {
+        if state.synced.is_some() {
+            Ok(SyncStatus::SYNCED)
+        } else {
+            match &state.kind {
+                SyncKind::Bootstrap => Ok(SyncStatus::BOOTSTRAP),
+                SyncKind::Catchup => Ok(SyncStatus::CATCHUP),
+            }
+        }
+    } else {
+        Ok(SyncStatus::LISTENING)
+    }
+}
+
+/// Filters pooled commands by fee payer, hash, or id, every given filter
+/// must match.
+struct PooledCommandsFilter {
+    public_key: Option<NonZeroCurvePoint>,
+    hashes: Option<Vec<String>>,
+    ids: Option<Vec<String>>,
+}
+
+impl PooledCommandsFilter {
+    fn new(
+        public_key: Option<user_command::GraphQLPublicKey>,
+        hashes: Option<Vec<String>>,
+        ids: Option<Vec<String>>,
+    ) -> Result<Self, ConversionError> {
+        Ok(Self {
+            public_key: public_key.map(|pk| pk.0.parse()).transpose()?,
+            hashes,
+            ids,
+        })
+    }
+
+    fn matches(&self, fee_payer: &NonZeroCurvePoint, hash: &str, id: &str) -> bool {
+        let contains = |values: &Option<Vec<String>>, value: &str| {
+            values
+                .as_ref()
+                .map_or(true, |values| values.iter().any(|v| v == value))
+        };
+        self.public_key.as_ref().map_or(true, |pk| pk == fee_payer)
+            && contains(&self.hashes, hash)
+            && contains(&self.ids, id)
+    }
+}
+
+async fn pooled_commands(
+    context: &Context,
+) -> juniper::FieldResult<Vec<MinaBaseUserCommandStableV2>> {
+    let pool: RpcTransactionPoolResponse = context
+        .0
+        .oneshot_request(RpcRequest::TransactionPoolGet)
+        .await
+        .ok_or(Error::StateMachineEmptyResponse)?;
+
+    Ok(pool
+        .into_iter()
+        .map(|command| command.data.into())
+        .collect())
 }
 
 #[derive(Clone, Debug)]
@@ -275,42 +472,133 @@ impl Mutation {
         input: zkapp::SendZkappInput,
         context: &Context,
     ) -> juniper::FieldResult<zkapp::GraphQLSendZkappResponse> {
-        let res: RpcTransactionInjectResponse = context
-            .0
-            .oneshot_request(RpcRequest::TransactionInject(vec![input.try_into()?]))
-            .await
-            .ok_or(Error::StateMachineEmptyResponse)?;
+        let res = inject_transactions(context, vec![input.try_into()?]).await?;
+        let zkapp_cmd: MinaBaseUserCommandStableV2 = match res.first().cloned() {
+            Some(RpcTransactionInjectedCommand::Zkapp(zkapp_cmd)) => zkapp_cmd.into(),
+            _ => unreachable!(),
+        };
+        Ok(zkapp_cmd.try_into()?)
+    }
+
+    async fn send_payment(
+        input: user_command::InputGraphQLSendPayment,
+        signature: Option<user_command::InputGraphQLSignature>,
+        context: &Context,
+    ) -> juniper::FieldResult<user_command::GraphQLSendPaymentResponse> {
+        let signature = signature.ok_or(Error::SignatureRequired)?;
+        let nonce = nonce_or_inferred(context, input.nonce.as_ref(), &input.from).await?;
+        let command = input.try_into_signed_command(nonce, signature)?;
+        let command = MinaBaseSignedCommandStableV2::from(&command);
+        let payment = user_command::GraphQLUserCommand::try_from(&command)?;
 
-        match res {
-            RpcTransactionInjectResponse::Success(res) => {
-                let zkapp_cmd: MinaBaseUserCommandStableV2 = match res.first().cloned() {
-                    Some(RpcTransactionInjectedCommand::Zkapp(zkapp_cmd)) => zkapp_cmd.into(),
-                    _ => unreachable!(),
-                };
-                Ok(zkapp_cmd.try_into()?)
+        inject_transactions(
+            context,
+            vec![MinaBaseUserCommandStableV2::SignedCommand(command)],
+        )
+        .await?;
+        Ok(user_command::GraphQLSendPaymentResponse { payment })
+    }
+
+    async fn send_delegation(
+        input: user_command::InputGraphQLSendDelegation,
+        signature: Option<user_command::InputGraphQLSignature>,
+        context: &Context,
+    ) -> juniper::FieldResult<user_command::GraphQLSendDelegationResponse> {
+        let signature = signature.ok_or(Error::SignatureRequired)?;
+        let nonce = nonce_or_inferred(context, input.nonce.as_ref(), &input.from).await?;
+        let command = input.try_into_signed_command(nonce, signature)?;
+        let command = MinaBaseSignedCommandStableV2::from(&command);
+        let delegation = user_command::GraphQLUserCommand::try_from(&command)?;
+
+        inject_transactions(
+            context,
+            vec![MinaBaseUserCommandStableV2::SignedCommand(command)],
+        )
+        .await?;
+        Ok(user_command::GraphQLSendDelegationResponse { delegation })
+    }
+}
+
+/// The nonce of the input if any, otherwise the nonce following the
+/// sender's commands waiting in the transaction pool, or the nonce of the
+/// sender's account in the best tip ledger when there are none, like the
+/// Mina daemon.
+async fn nonce_or_inferred(
+    context: &Context,
+    nonce: Option<&user_command::GraphQLUInt32>,
+    from: &user_command::GraphQLPublicKey,
+) -> juniper::FieldResult<Nonce> {
+    if let Some(nonce) = nonce {
+        return Ok(nonce.to_nonce()?);
+    }
+
+    let fee_payer: NonZeroCurvePoint = from.0.parse().map_err(ConversionError::from)?;
+    let pooled_nonce = pooled_commands(context)
+        .await?
+        .into_iter()
+        .filter_map(|command| match command {
+            MinaBaseUserCommandStableV2::SignedCommand(command) => {
+                let common = command.payload.common;
+                (common.fee_payer_pk == fee_payer).then(|| common.nonce.as_u32())
             }
-            RpcTransactionInjectResponse::Rejected(rejected) => {
-                let error_list = rejected
-                    .into_iter()
-                    .map(|(_, err)| graphql_value!({ "message": err.to_string() }))
-                    .collect::<Vec<_>>();
-
-                Err(FieldError::new(
-                    "Transaction rejected",
-                    graphql_value!(juniper::Value::List(error_list)),
-                ))
-            }
-            RpcTransactionInjectResponse::Failure(failure) => {
-                let error_list = failure
-                    .into_iter()
-                    .map(|err| graphql_value!({ "message": err.to_string() }))
-                    .collect::<Vec<_>>();
-
-                Err(FieldError::new(
-                    "Transaction failed",
-                    graphql_value!(juniper::Value::List(error_list)),
-                ))
+            MinaBaseUserCommandStableV2::ZkappCommand(zkapp) => {
+                let body = zkapp.fee_payer.body;
+                (body.public_key == fee_payer).then(|| body.nonce.as_u32())
             }
+        })
+        .max();
+    if let Some(nonce) = pooled_nonce {
+        return Ok(Nonce::from_u32(nonce).incr());
+    }
+
+    let public_key = AccountPublicKey::from_str(&from.0)?;
+    let accounts: Vec<Account> = context
+        .0
+        .oneshot_request(RpcRequest::LedgerAccountsGet(
+            AccountQuery::PubKeyWithTokenId(public_key, ledger::TokenId::default().into()),
+        ))
+        .await
+        .ok_or(Error::StateMachineEmptyResponse)?;
+
+    Ok(accounts
+        .first()
+        .ok_or_else(|| Error::Custom(format!("Couldn't find an account for {}", from.0)))?
+        .nonce)
+}
+
+async fn inject_transactions(
+    context: &Context,
+    transactions: Vec<MinaBaseUserCommandStableV2>,
+) -> juniper::FieldResult<RpcTransactionInjectSuccess> {
+    let res: RpcTransactionInjectResponse = context
+        .0
+        .oneshot_request(RpcRequest::TransactionInject(transactions))
+        .await
+        .ok_or(Error::StateMachineEmptyResponse)?;
+
+    match res {
+        RpcTransactionInjectResponse::Success(res) => Ok(res),
+        RpcTransactionInjectResponse::Rejected(rejected) => {
+            let error_list = rejected
+                .into_iter()
+                .map(|(_, err)| graphql_value!({ "message": err.to_string() }))
+                .collect::<Vec<_>>();
+
+            Err(FieldError::new(
+                "Transaction rejected",
+                graphql_value!(juniper::Value::List(error_list)),
+            ))
+        }
+        RpcTransactionInjectResponse::Failure(failure) => {
+            let error_list = failure
+                .into_iter()
+                .map(|err| graphql_value!({ "message": err.to_string() }))
+                .collect::<Vec<_>>();
+
+            Err(FieldError::new(
+                "Transaction failed",
+                graphql_value!(juniper::Value::List(error_list)),
+            ))
         }
     }
 }
@@ -318,47 +606,423 @@ impl Mutation {
 pub fn routes(
     rpc_sernder: RpcSender,
 ) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
-    let state = warp::any().map(move || Context(rpc_sernder.clone()));
-    let schema = RootNode::new(Query, Mutation, EmptySubscription::<Context>::new());
-    let graphql_filter = juniper_warp::make_graphql_filter(schema, state.boxed());
-    let graphiql_filter = juniper_warp::graphiql_filter("/graphql", None);
-    let playground_filter = juniper_warp::playground_filter("/graphql", None);
+    let context = Context(rpc_sernder);
+    let state = {
+        let context = context.clone();
+        warp::any().map(move || context.clone())
+    };
+    let schema = Arc::new(RootNode::new(Query, Mutation, subscription::Subscription));
+    let graphql_filter = juniper_warp::make_graphql_filter(schema.clone(), state.boxed());
+    let subscriptions_filter =
+        juniper_warp::subscriptions::make_ws_filter(schema, ConnectionConfig::new(context));
+    let graphiql_filter = juniper_warp::graphiql_filter("/graphql", Some("/subscriptions"));
+    let playground_filter = juniper_warp::playground_filter("/graphql", Some("/subscriptions"));
 
     (warp::post().and(warp::path("graphql")).and(graphql_filter))
+        .or(warp::path("subscriptions").and(subscriptions_filter))
         .or(warp::get()
             .and(warp::path("playground"))
             .and(playground_filter))
         .or(warp::get().and(warp::path("graphiql")).and(graphiql_filter))
-
-    // warp::get()
-    //     .and(warp::path("graphiql"))
-    //     .and(juniper_warp::graphiql_filter("/graphql", None))
-    //     .or(warp::path("graphql").and(graphql_filter))
 }
 
-// let routes = (warp::post()
-//         .and(warp::path("graphql"))
-//         .and(juniper_warp::make_graphql_filter(
-//             schema.clone(),
-//             warp::any().map(|| Context),
-//         )))
-//     .or(
-//         warp::path("subscriptions").and(juniper_warp::subscriptions::make_ws_filter(
-//             schema,
-//             ConnectionConfig::new(Context),
-//         )),
-//     )
-//     .or(warp::get()
-//         .and(warp::path("playground"))
-//         .and(juniper_warp::playground_filter(
-//             "/graphql",
-//             Some("/subscriptions"),
-//         )))
-//     .or(warp::get()
-//         .and(warp::path("graphiql"))
-//         .and(juniper_warp::graphiql_filter(
-//             "/graphql",
-//             Some("/subscriptions"),
-//         )))
-//     .or(homepage)
-//     .with(log);
+#[cfg(test)]
+mod tests {
+    use juniper::Variables;
+    use ledger::{
+        scan_state::{
+            currency::{Amount, Balance, Fee},
+            transaction_logic::{
+                signed_command::{Body, PaymentPayload, SignedCommand, SignedCommandPayload},
+                valid, Memo,
+            },
+        },
+        transaction_pool::{transaction_hash::hash_command, ValidCommandWithHash},
+        AccountId,
+    };
+    use mina_p2p_messages::{
+        bigint::BigInt,
+        v2::{MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseStakeDelegationStableV2},
+    };
+    use node::{
+        account::AccountSecretKey,
+        core::channels::{mpsc, oneshot},
+    };
+    use openmina_node_common::rpc::NodeRpcRequest;
+    use serde_json::json;
+
+    use super::*;
+
+    fn sender(i: u64) -> String {
+        AccountSecretKey::deterministic(i).public_key().to_string()
+    }
+
+    fn payment(sender: u64, nonce: u32) -> ValidCommandWithHash {
+        let fee_payer = AccountSecretKey::deterministic(sender).public_key_compressed();
+        let payload = SignedCommandPayload::create(
+            Fee::from_u64(10_000_000),
+            fee_payer.clone(),
+            Nonce::from_u32(nonce),
+            None,
+            Memo::empty(),
+            Body::Payment(PaymentPayload {
+                receiver_pk: fee_payer.clone(),
+                amount: Amount::from_u64(1),
+            }),
+        );
+        let command = SignedCommand {
+            payload,
+            signer: fee_payer,
+            signature: mina_signer::Signature::dummy(),
+        };
+        hash_command(valid::UserCommand::SignedCommand(Box::new(command)))
+    }
+
+    /// Context of a node that only answers transaction pool requests.
+    fn context(pool: RpcTransactionPoolResponse) -> Context {
+        node_context(pool, vec![]).0
+    }
+
+    /// Context of a node that answers transaction pool, ledger account and
+    /// transaction inject requests. Injected commands are accepted and sent
+    /// to the returned receiver.
+    fn node_context(
+        pool: RpcTransactionPoolResponse,
+        accounts: Vec<Account>,
+    ) -> (
+        Context,
+        mpsc::UnboundedReceiver<Vec<MinaBaseUserCommandStableV2>>,
+    ) {
+        let (tx, mut rx) = mpsc::channel::<NodeRpcRequest>(1);
+        let (injected_tx, injected_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(NodeRpcRequest { req, responder }) = rx.recv().await {
+                match req {
+                    RpcRequest::TransactionPoolGet => {
+                        let responder = responder
+                            .downcast::<oneshot::Sender<RpcTransactionPoolResponse>>()
+                            .unwrap();
+                        let _ = responder.send(pool.clone());
+                    }
+                    RpcRequest::LedgerAccountsGet(_) => {
+                        let responder = responder
+                            .downcast::<oneshot::Sender<Vec<Account>>>()
+                            .unwrap();
+                        let _ = responder.send(accounts.clone());
+                    }
+                    RpcRequest::TransactionInject(commands) => {
+                        let responder = responder
+                            .downcast::<oneshot::Sender<RpcTransactionInjectResponse>>()
+                            .unwrap();
+                        let _ = injected_tx.send(commands);
+                        let _ = responder.send(RpcTransactionInjectResponse::Success(vec![]));
+                    }
+                    _ => {}
+                }
+            }
+        });
+        (Context(RpcSender::new(tx)), injected_rx)
+    }
+
+    fn account(sender: u64, nonce: u32) -> Account {
+        let public_key = AccountSecretKey::deterministic(sender).public_key_compressed();
+        let mut account = Account::create_with(
+            AccountId::new(public_key, ledger::TokenId::default()),
+            Balance::from_u64(1_000_000_000_000),
+        );
+        account.nonce = Nonce::from_u32(nonce);
+        account
+    }
+
+    /// `SignatureInput` fields of the same signature, as field and scalar,
+    /// and as raw signature.
+    fn signature_inputs() -> [String; 2] {
+        let signature = mina_signer::Signature::dummy();
+        let field = BigInt::from(signature.rx);
+        let scalar = BigInt::from(signature.s);
+        let raw = field
+            .to_bytes()
+            .iter()
+            .chain(&scalar.to_bytes())
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        [
+            format!(
+                r#"field: "{}", scalar: "{}""#,
+                field.to_decimal(),
+                scalar.to_decimal()
+            ),
+            format!(r#"rawSignature: "{raw}""#),
+        ]
+    }
+
+    fn send_payment(input: &str, signature: &str) -> String {
+        format!(
+            r#"mutation {{ sendPayment(input: {{ {input} }}, signature: {{ {signature} }}) {{
+                payment {{ id kind nonce from to amount fee isDelegation }}
+            }} }}"#
+        )
+    }
+
+    fn payment_input(from: u64, to: u64) -> String {
+        format!(
+            r#"from: "{}", to: "{}", fee: "10000000", amount: "1500000000""#,
+            sender(from),
+            sender(to)
+        )
+    }
+
+    async fn execute(query: &str, context: &Context) -> Result<serde_json::Value, String> {
+        let schema = RootNode::new(Query, Mutation, subscription::Subscription);
+        let (value, errors) = juniper::execute(query, None, &schema, &Variables::new(), context)
+            .await
+            .map_err(|err| err.to_string())?;
+        if let Some(error) = errors.first() {
+            return Err(error.error().message().to_owned());
+        }
+        Ok(serde_json::to_value(value).unwrap())
+    }
+
+    #[tokio::test]
+    async fn pooled_user_commands_filters() {
+        let pool = vec![payment(0, 0), payment(1, 0), payment(1, 1)];
+        let hash = pool[1].hash.to_string();
+        let context = context(pool);
+
+        let all = execute("{ pooledUserCommands { nonce } }", &context)
+            .await
+            .unwrap();
+        assert_eq!(
+            all,
+            json!({ "pooledUserCommands": [{ "nonce": 0 }, { "nonce": 0 }, { "nonce": 1 }] })
+        );
+
+        let query = format!(
+            r#"{{ pooledUserCommands(publicKey: "{}") {{ from nonce }} }}"#,
+            sender(1)
+        );
+        let by_sender = execute(&query, &context).await.unwrap();
+        assert_eq!(
+            by_sender,
+            json!({ "pooledUserCommands": [
+                { "from": sender(1), "nonce": 0 },
+                { "from": sender(1), "nonce": 1 },
+            ] })
+        );
+
+        let query = format!(
+            r#"{{ pooledUserCommands(publicKey: "{}", hashes: ["{hash}"]) {{ hash }} }}"#,
+            sender(1)
+        );
+        let by_hash = execute(&query, &context).await.unwrap();
+        assert_eq!(by_hash, json!({ "pooledUserCommands": [{ "hash": hash }] }));
+
+        let query = format!(
+            r#"{{ pooledUserCommands(publicKey: "{}", hashes: ["{hash}"]) {{ hash }} }}"#,
+            sender(0)
+        );
+        let none = execute(&query, &context).await.unwrap();
+        assert_eq!(none, json!({ "pooledUserCommands": [] }));
+
+        let zkapps = execute("{ pooledZkappCommands { hash } }", &context)
+            .await
+            .unwrap();
+        assert_eq!(zkapps, json!({ "pooledZkappCommands": [] }));
+    }
+
+    #[tokio::test]
+    async fn block_requires_state_hash_or_height() {
+        let context = context(vec![]);
+
+        for query in [
+            "{ block { stateHash } }",
+            r#"{ block(stateHash: "3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ", height: 1) { stateHash } }"#,
+        ] {
+            let error = execute(query, &context).await.unwrap_err();
+            assert!(
+                error.contains("exactly one of stateHash or height"),
+                "{query}: {error}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn send_payment_signature_inputs() {
+        let (context, _injected) = node_context(vec![], vec![account(0, 0)]);
+        let [field_scalar, raw] = signature_inputs();
+
+        let mut ids = vec![];
+        for signature in [field_scalar, raw] {
+            let res = execute(&send_payment(&payment_input(0, 1), &signature), &context)
+                .await
+                .unwrap();
+            ids.push(res["sendPayment"]["payment"]["id"].clone());
+        }
+        assert_eq!(ids[0], ids[1], "both inputs should give the same command");
+
+        let error = execute(
+            &send_payment(&payment_input(0, 1), r#"field: "1""#),
+            &context,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            error.contains("rawSignature or field and scalar"),
+            "{error}"
+        );
+
+        let error = execute(
+            &send_payment(&payment_input(0, 1), r#"rawSignature: "abcd""#),
+            &context,
+        )
+        .await
+        .unwrap_err();
+        assert!(error.contains("Invalid length"), "{error}");
+
+        let query = format!(
+            r#"mutation {{ sendPayment(input: {{ {} }}) {{ payment {{ id }} }} }}"#,
+            payment_input(0, 1)
+        );
+        let error = execute(&query, &context).await.unwrap_err();
+        assert!(error.contains("Signature is required"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn send_payment_rejects_long_memo() {
+        let (context, mut injected) = node_context(vec![], vec![account(0, 0)]);
+        let [signature, _] = signature_inputs();
+
+        let memo = "a".repeat(Memo::MAX_INPUT_LENGTH);
+        let input = format!(r#"{}, memo: "{memo}""#, payment_input(0, 1));
+        execute(&send_payment(&input, &signature), &context)
+            .await
+            .unwrap();
+
+        let memo = "a".repeat(Memo::MAX_INPUT_LENGTH + 1);
+        let input = format!(r#"{}, memo: "{memo}""#, payment_input(0, 1));
+        let error = execute(&send_payment(&input, &signature), &context)
+            .await
+            .unwrap_err();
+        assert!(
+            error.contains(&format!(
+                "memo is longer than {} bytes",
+                Memo::MAX_INPUT_LENGTH
+            )),
+            "{error}"
+        );
+
+        assert!(injected.try_recv().is_ok());
+        assert!(injected.try_recv().is_err(), "rejected memo was injected");
+    }
+
+    #[tokio::test]
+    async fn send_payment_infers_nonce() {
+        let pool = vec![payment(0, 3), payment(1, 4), payment(1, 5)];
+        let (context, _injected) = node_context(pool, vec![account(2, 7)]);
+        let [signature, _] = signature_inputs();
+
+        let nonce = |res: serde_json::Value| res["sendPayment"]["payment"]["nonce"].clone();
+
+        // after the sender's commands in the pool
+        let res = execute(&send_payment(&payment_input(1, 0), &signature), &context)
+            .await
+            .unwrap();
+        assert_eq!(nonce(res), json!(6));
+
+        // the one of the sender's account without pooled commands
+        let res = execute(&send_payment(&payment_input(2, 0), &signature), &context)
+            .await
+            .unwrap();
+        assert_eq!(nonce(res), json!(7));
+
+        // the given one
+        let input = format!(r#"{}, nonce: "2""#, payment_input(1, 0));
+        let res = execute(&send_payment(&input, &signature), &context)
+            .await
+            .unwrap();
+        assert_eq!(nonce(res), json!(2));
+    }
+
+    #[tokio::test]
+    async fn send_payment_converts_fee_and_amount() {
+        let (context, mut injected) = node_context(vec![], vec![account(0, 0)]);
+        let [signature, _] = signature_inputs();
+
+        let res = execute(&send_payment(&payment_input(0, 1), &signature), &context)
+            .await
+            .unwrap();
+        assert_eq!(
+            res["sendPayment"]["payment"],
+            json!({
+                "id": res["sendPayment"]["payment"]["id"],
+                "kind": "PAYMENT",
+                "nonce": 0,
+                "from": sender(0),
+                "to": sender(1),
+                "amount": "1500000000",
+                "fee": "10000000",
+                "isDelegation": false,
+            })
+        );
+
+        let commands = injected.try_recv().unwrap();
+        let [MinaBaseUserCommandStableV2::SignedCommand(command)] = &commands[..] else {
+            panic!("expected one signed command: {commands:?}");
+        };
+        assert_eq!(command.payload.common.fee.as_u64(), 10_000_000);
+        let MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) = &command.payload.body
+        else {
+            panic!("expected a payment: {command:?}");
+        };
+        assert_eq!(payment.amount.as_u64(), 1_500_000_000);
+
+        for (fee, amount) in [("-1", "1"), ("1", "1.5"), ("18446744073709551616", "1")] {
+            let input = format!(
+                r#"from: "{}", to: "{}", fee: "{fee}", amount: "{amount}""#,
+                sender(0),
+                sender(1)
+            );
+            execute(&send_payment(&input, &signature), &context)
+                .await
+                .unwrap_err();
+        }
+    }
+
+    #[tokio::test]
+    async fn send_delegation_injects_delegation() {
+        let (context, mut injected) = node_context(vec![], vec![account(0, 3)]);
+        let [signature, _] = signature_inputs();
+
+        let query = format!(
+            r#"mutation {{ sendDelegation(input: {{ from: "{}", to: "{}", fee: "10000000" }}, signature: {{ {signature} }}) {{
+                delegation {{ kind nonce from to amount fee isDelegation }}
+            }} }}"#,
+            sender(0),
+            sender(1)
+        );
+        let res = execute(&query, &context).await.unwrap();
+        assert_eq!(
+            res,
+            json!({ "sendDelegation": { "delegation": {
+                "kind": "STAKE_DELEGATION",
+                "nonce": 3,
+                "from": sender(0),
+                "to": sender(1),
+                "amount": "0",
+                "fee": "10000000",
+                "isDelegation": true,
+            } } })
+        );
+
+        let commands = injected.try_recv().unwrap();
+        let [MinaBaseUserCommandStableV2::SignedCommand(command)] = &commands[..] else {
+            panic!("expected one signed command: {commands:?}");
+        };
+        assert!(matches!(
+            &command.payload.body,
+            MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+                MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+            ) if new_delegate.to_string() == sender(1)
+        ));
+    }
+}
@@ -0,0 +1,26 @@
+use juniper::GraphQLObject;
+use node::rpc::RpcSnarkPoolJobSnarkWork;
+use openmina_core::snark::SnarkJobId;
+
+#[derive(GraphQLObject, Debug)]
+#[graphql(name = "CompletedWork", description = "Completed snark works")]
+pub struct GraphQLCompletedWork {
+    /// Public key of the prover
+    pub prover: String,
+    /// Amount the prover is paid for the snark work
+    pub fee: String,
+    /// Unique identifier for the snark work purchased. Unlike the Mina
+    /// daemon, which uses integers, these are the ids of the snark jobs,
+    /// as used by the snark pool RPCs.
+    pub work_ids: Vec<String>,
+}
+
+impl GraphQLCompletedWork {
+    pub fn new(id: SnarkJobId, snark: RpcSnarkPoolJobSnarkWork) -> Self {
+        Self {
+            prover: snark.snarker.to_string(),
+            fee: snark.fee.as_u64().to_string(),
+            work_ids: vec![id.to_string()],
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeSet;
+use std::pin::Pin;
+
+use juniper::futures::{stream, Stream, StreamExt};
+use juniper::{FieldError, GraphQLEnum};
+use mina_p2p_messages::v2::{
+    MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseStakeDelegationStableV2,
+    MinaBaseUserCommandStableV2, NonZeroCurvePoint, StateHash,
+};
+use node::rpc::{RpcBestChainResponse, RpcRequest, RpcTransitionFrontierEvent};
+use openmina_core::block::AppliedBlock;
+
+use super::block::GraphQLBestChainBlock;
+use super::user_command::GraphQLPublicKey;
+use super::{sync_status, Context, Error, SyncStatus};
+
+/// Number of transition frontier events buffered for a subscriber, the node
+/// closes the subscription when the buffer is full.
+const EVENTS_BUFFER: usize = 64;
+
+type GraphQLStream<T> = Pin<Box<dyn Stream<Item = Result<T, FieldError>> + Send>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, GraphQLEnum)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum ChainReorganizationStatus {
+    CHANGED,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Subscription;
+
+#[juniper::graphql_subscription(context = Context)]
+impl Subscription {
+    /// Event that triggers when the network sync status changes
+    async fn new_sync_update(context: &Context) -> GraphQLStream<SyncStatus> {
+        let events = transition_frontier_events(context).await;
+        let context = context.clone();
+        Box::pin(
+            events
+                .then(move |_| {
+                    let context = context.clone();
+                    async move { sync_status(&context).await }
+                })
+                .scan(None, |last, status| {
+                    let update = match status {
+                        Ok(status) if *last == Some(status) => None,
+                        Ok(status) => {
+                            *last = Some(status);
+                            Some(Ok(status))
+                        }
+                        Err(err) => Some(Err(err.into())),
+                    };
+                    async move { Some(update) }
+                })
+                .filter_map(|update| async move { update }),
+        )
+    }
+
+    /// Event that triggers when a new block is added to the best chain. If
+    /// `publicKey` is provided, only blocks produced by it, or containing
+    /// a transaction with it as the sender or the receiver, are reported
+    async fn new_block(
+        public_key: Option<GraphQLPublicKey>,
+        context: &Context,
+    ) -> GraphQLStream<GraphQLBestChainBlock> {
+        let public_key = match public_key
+            .map(|pk| pk.0.parse::<NonZeroCurvePoint>())
+            .transpose()
+        {
+            Ok(public_key) => public_key,
+            Err(err) => {
+                let err = FieldError::from(Error::Conversion(err.into()));
+                return Box::pin(stream::once(async move { Err(err) }));
+            }
+        };
+
+        let best_chain: Option<RpcBestChainResponse> = context
+            .0
+            .oneshot_request(RpcRequest::BestChain(u32::MAX))
+            .await;
+        let Some(best_chain) = best_chain else {
+            let err = FieldError::from(Error::StateMachineEmptyResponse);
+            return Box::pin(stream::once(async move { Err(err) }));
+        };
+        let mut known = block_hashes(&best_chain);
+
+        let events = transition_frontier_events(context).await;
+        Box::pin(events.flat_map(move |event| {
+            let new_blocks = event
+                .best_chain
+                .iter()
+                .filter(|block| !known.contains(block.hash()))
+                .filter(|block| {
+                    public_key
+                        .as_ref()
+                        .map_or(true, |pk| block_involves(block, pk))
+                })
+                .cloned()
+                .map(|block| -> Result<GraphQLBestChainBlock, FieldError> { Ok(block.try_into()?) })
+                .collect::<Vec<_>>();
+            known = block_hashes(&event.best_chain);
+            stream::iter(new_blocks)
+        }))
+    }
+
+    /// Event that triggers when the best tip changes in a way that is not
+    /// a trivial extension of the existing one
+    async fn chain_reorganization(context: &Context) -> GraphQLStream<ChainReorganizationStatus> {
+        let events = transition_frontier_events(context).await;
+        Box::pin(events.filter_map(|event| async move {
+            event
+                .reorg
+                .then_some(Ok(ChainReorganizationStatus::CHANGED))
+        }))
+    }
+}
+
+/// Subscribes to the transition frontier events of the node. The stream
+/// ends when the node shuts down or when the subscriber lags behind by
+/// more than [`EVENTS_BUFFER`] events.
+async fn transition_frontier_events(
+    context: &Context,
+) -> impl Stream<Item = RpcTransitionFrontierEvent> + Send {
+    let events = context
+        .0
+        .multishot_request(EVENTS_BUFFER, RpcRequest::TransitionFrontierSubscribe)
+        .await;
+    stream::unfold(events, |mut events| async move {
+        let event = events.recv().await?;
+        Some((event, events))
+    })
+}
+
+fn block_hashes(best_chain: &[AppliedBlock]) -> BTreeSet<StateHash> {
+    best_chain
+        .iter()
+        .map(|block| block.hash().clone())
+        .collect()
+}
+
+/// Whether the block is produced by `public_key`, or contains a signed
+/// command or zkApp command involving it.
+fn block_involves(block: &AppliedBlock, public_key: &NonZeroCurvePoint) -> bool {
+    if block.producer() == public_key || &block.consensus_state().coinbase_receiver == public_key {
+        return true;
+    }
+    block.commands_iter().any(|command| match &command.data {
+        MinaBaseUserCommandStableV2::SignedCommand(command) => {
+            let receiver = match &command.payload.body {
+                MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => &payment.receiver_pk,
+                MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+                    MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+                ) => new_delegate,
+            };
+            &command.payload.common.fee_payer_pk == public_key || receiver == public_key
+        }
+        MinaBaseUserCommandStableV2::ZkappCommand(command) => {
+            &command.fee_payer.body.public_key == public_key
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use juniper::{RootNode, Variables};
+    use node::{
+        account::AccountSecretKey,
+        core::{
+            block::ArcBlockWithHash,
+            channels::{mpsc, oneshot},
+        },
+        testing::{child_block, genesis_block},
+    };
+    use openmina_node_common::rpc::{NodeRpcRequest, RpcSender};
+    use serde_json::json;
+
+    use super::super::{Mutation, Query};
+    use super::*;
+
+    fn chain(blocks: &[&ArcBlockWithHash]) -> Vec<AppliedBlock> {
+        blocks
+            .iter()
+            .map(|block| AppliedBlock {
+                block: (*block).clone(),
+                just_emitted_a_proof: false,
+            })
+            .collect()
+    }
+
+    /// Context of a node with the `best_chain`, that sends `events` to every
+    /// transition frontier subscriber.
+    fn context(
+        best_chain: RpcBestChainResponse,
+        events: Vec<RpcTransitionFrontierEvent>,
+    ) -> Context {
+        let (tx, mut rx) = mpsc::channel::<NodeRpcRequest>(1);
+        tokio::spawn(async move {
+            while let Some(NodeRpcRequest { req, responder }) = rx.recv().await {
+                match req {
+                    RpcRequest::BestChain(_) => {
+                        let responder = responder
+                            .downcast::<oneshot::Sender<RpcBestChainResponse>>()
+                            .unwrap();
+                        let _ = responder.send(best_chain.clone());
+                    }
+                    RpcRequest::TransitionFrontierSubscribe => {
+                        let responder = responder
+                            .downcast::<mpsc::Sender<RpcTransitionFrontierEvent>>()
+                            .unwrap();
+                        for event in events.clone() {
+                            responder.send(event).await.unwrap();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        });
+        Context(RpcSender::new(tx))
+    }
+
+    /// Values emitted by the subscription until the events run out.
+    async fn subscribe(query: &str, context: &Context) -> Vec<serde_json::Value> {
+        let schema = RootNode::new(Query, Mutation, Subscription);
+        let (value, errors) =
+            juniper::resolve_into_stream(query, None, &schema, &Variables::new(), context)
+                .await
+                .unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        let juniper::Value::Object(object) = value else {
+            panic!("unexpected subscription value");
+        };
+        let Some((_, juniper::Value::Scalar(stream))) = object.into_iter().next() else {
+            panic!("unexpected subscription field");
+        };
+        stream
+            .map(|value| serde_json::to_value(value.unwrap()).unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn new_blocks_and_reorganizations() {
+        // g <- a <- b
+        //       \- c
+        let g = genesis_block();
+        let a = child_block(&g, 1);
+        let b = child_block(&a, 2);
+        let c = child_block(&a, 3);
+        let events = vec![
+            RpcTransitionFrontierEvent {
+                synced: true,
+                best_chain: chain(&[&g, &a, &b]),
+                reorg: false,
+            },
+            RpcTransitionFrontierEvent {
+                synced: true,
+                best_chain: chain(&[&g, &a, &c]),
+                reorg: true,
+            },
+        ];
+        let context = context(chain(&[&g, &a]), events);
+
+        let blocks = subscribe("subscription { newBlock { stateHash } }", &context).await;
+        assert_eq!(
+            blocks,
+            vec![
+                json!({ "stateHash": b.hash().to_string() }),
+                json!({ "stateHash": c.hash().to_string() }),
+            ]
+        );
+
+        let uninvolved = AccountSecretKey::deterministic(100).public_key();
+        let query =
+            format!(r#"subscription {{ newBlock(publicKey: "{uninvolved}") {{ stateHash }} }}"#);
+        assert_eq!(
+            subscribe(&query, &context).await,
+            Vec::<serde_json::Value>::new()
+        );
+
+        let reorgs = subscribe("subscription { chainReorganization }", &context).await;
+        assert_eq!(reorgs, vec![json!("CHANGED")]);
+    }
+}
@@ -0,0 +1,325 @@
+use std::str::FromStr;
+
+use juniper::{GraphQLInputObject, GraphQLObject, GraphQLScalar};
+use ledger::scan_state::currency::{Amount, Fee, Nonce, Slot};
+use ledger::scan_state::transaction_logic::signed_command::{
+    self, PaymentPayload, SignedCommand, SignedCommandPayload, StakeDelegationPayload,
+};
+use ledger::scan_state::transaction_logic::Memo;
+use mina_p2p_messages::bigint::BigInt;
+use mina_p2p_messages::v2::{
+    MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseSignedCommandStableV2,
+    MinaBaseStakeDelegationStableV2, TokenIdKeyHash,
+};
+use mina_signer::CompressedPubKey;
+use node::account::AccountPublicKey;
+
+use super::ConversionError;
+
+/// Unsigned 64-bit integer, serialized as a string, like the `UInt64`
+/// scalar of the Mina daemon.
+#[derive(GraphQLScalar, Debug, Clone)]
+#[graphql(name = "UInt64", transparent)]
+pub struct GraphQLUInt64(pub String);
+
+/// Unsigned 32-bit integer, serialized as a string, like the `UInt32`
+/// scalar of the Mina daemon.
+#[derive(GraphQLScalar, Debug, Clone)]
+#[graphql(name = "UInt32", transparent)]
+pub struct GraphQLUInt32(pub String);
+
+impl GraphQLUInt32 {
+    pub fn to_nonce(&self) -> Result<Nonce, ConversionError> {
+        Ok(Nonce::from_u32(self.0.parse()?))
+    }
+}
+
+/// Base58Check encoded public key, like the `PublicKey` scalar of the Mina
+/// daemon.
+#[derive(GraphQLScalar, Debug, Clone)]
+#[graphql(name = "PublicKey", transparent)]
+pub struct GraphQLPublicKey(pub String);
+
+impl TryFrom<&GraphQLPublicKey> for CompressedPubKey {
+    type Error = ConversionError;
+    fn try_from(value: &GraphQLPublicKey) -> Result<Self, Self::Error> {
+        AccountPublicKey::from_str(&value.0)?
+            .try_into()
+            .map_err(|_| ConversionError::Custom(format!("invalid public key: {}", value.0)))
+    }
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(name = "SendPaymentInput")]
+pub struct InputGraphQLSendPayment {
+    /// Should only be set when cancelling transactions, otherwise the
+    /// nonce of the sender's account is used.
+    pub nonce: Option<GraphQLUInt32>,
+    /// Short arbitrary message provided by the sender.
+    pub memo: Option<String>,
+    /// The global slot since genesis after which this transaction cannot
+    /// be applied.
+    pub valid_until: Option<GraphQLUInt32>,
+    pub fee: GraphQLUInt64,
+    pub amount: GraphQLUInt64,
+    pub to: GraphQLPublicKey,
+    pub from: GraphQLPublicKey,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(name = "SendDelegationInput")]
+pub struct InputGraphQLSendDelegation {
+    /// Should only be set when cancelling transactions, otherwise the
+    /// nonce of the sender's account is used.
+    pub nonce: Option<GraphQLUInt32>,
+    /// Short arbitrary message provided by the sender.
+    pub memo: Option<String>,
+    /// The global slot since genesis after which this transaction cannot
+    /// be applied.
+    pub valid_until: Option<GraphQLUInt32>,
+    pub fee: GraphQLUInt64,
+    /// Public key of the account being delegated to.
+    pub to: GraphQLPublicKey,
+    pub from: GraphQLPublicKey,
+}
+
+/// Either `rawSignature`, or both `field` and `scalar`.
+#[derive(GraphQLInputObject, Debug)]
+#[graphql(name = "SignatureInput")]
+pub struct InputGraphQLSignature {
+    /// Field component of the signature, as a decimal string.
+    pub field: Option<String>,
+    /// Scalar component of the signature, as a decimal string.
+    pub scalar: Option<String>,
+    /// Hex encoded field and scalar, in little-endian.
+    pub raw_signature: Option<String>,
+}
+
+impl TryFrom<InputGraphQLSignature> for mina_signer::Signature {
+    type Error = ConversionError;
+    fn try_from(value: InputGraphQLSignature) -> Result<Self, Self::Error> {
+        let (field, scalar) = match value {
+            InputGraphQLSignature {
+                raw_signature: Some(raw),
+                ..
+            } => {
+                let bytes = decode_hex(&raw)?;
+                if bytes.len() != 64 {
+                    return Err(ConversionError::InvalidLength);
+                }
+                let (field, scalar) = bytes.split_at(32);
+                let to_bigint = |bytes: &[u8]| {
+                    bytes
+                        .try_into()
+                        .map(BigInt::from_bytes)
+                        .map_err(|_| ConversionError::InvalidLength)
+                };
+                (to_bigint(field)?, to_bigint(scalar)?)
+            }
+            InputGraphQLSignature {
+                field: Some(field),
+                scalar: Some(scalar),
+                ..
+            } => (
+                BigInt::from_decimal(&field)?,
+                BigInt::from_decimal(&scalar)?,
+            ),
+            _ => {
+                return Err(ConversionError::MissingField(
+                    "rawSignature or field and scalar".to_owned(),
+                ))
+            }
+        };
+
+        Ok(Self {
+            rx: field
+                .try_into()
+                .map_err(|_| ConversionError::InvalidBigInt)?,
+            s: scalar
+                .try_into()
+                .map_err(|_| ConversionError::InvalidBigInt)?,
+        })
+    }
+}
+
+impl InputGraphQLSendPayment {
+    /// Builds the signed payment, `nonce` must be the one of the input when
+    /// it has one.
+    pub fn try_into_signed_command(
+        self,
+        nonce: Nonce,
+        signature: InputGraphQLSignature,
+    ) -> Result<SignedCommand, ConversionError> {
+        let body = signed_command::Body::Payment(PaymentPayload {
+            receiver_pk: (&self.to).try_into()?,
+            amount: Amount::from_u64(self.amount.0.parse()?),
+        });
+        create_signed_command(
+            &self.from,
+            &self.fee,
+            nonce,
+            self.valid_until.as_ref(),
+            self.memo.as_deref(),
+            body,
+            signature,
+        )
+    }
+}
+
+impl InputGraphQLSendDelegation {
+    /// Builds the signed delegation, `nonce` must be the one of the input when
+    /// it has one.
+    pub fn try_into_signed_command(
+        self,
+        nonce: Nonce,
+        signature: InputGraphQLSignature,
+    ) -> Result<SignedCommand, ConversionError> {
+        let body = signed_command::Body::StakeDelegation(StakeDelegationPayload::SetDelegate {
+            new_delegate: (&self.to).try_into()?,
+        });
+        create_signed_command(
+            &self.from,
+            &self.fee,
+            nonce,
+            self.valid_until.as_ref(),
+            self.memo.as_deref(),
+            body,
+            signature,
+        )
+    }
+}
+
+fn create_signed_command(
+    from: &GraphQLPublicKey,
+    fee: &GraphQLUInt64,
+    nonce: Nonce,
+    valid_until: Option<&GraphQLUInt32>,
+    memo: Option<&str>,
+    body: signed_command::Body,
+    signature: InputGraphQLSignature,
+) -> Result<SignedCommand, ConversionError> {
+    let memo = match memo {
+        None => Memo::empty(),
+        Some(memo) if memo.len() > Memo::MAX_INPUT_LENGTH => {
+            return Err(ConversionError::Custom(format!(
+                "memo is longer than {} bytes",
+                Memo::MAX_INPUT_LENGTH
+            )))
+        }
+        Some(memo) => {
+            Memo::from_str(memo).map_err(|_| ConversionError::Custom("invalid memo".to_owned()))?
+        }
+    };
+    let valid_until = valid_until
+        .map(|slot| Ok::<_, ConversionError>(Slot::from_u32(slot.0.parse()?)))
+        .transpose()?;
+    let from: CompressedPubKey = from.try_into()?;
+
+    Ok(SignedCommand {
+        payload: SignedCommandPayload::create(
+            Fee::from_u64(fee.0.parse()?),
+            from.clone(),
+            nonce,
+            valid_until,
+            memo,
+            body,
+        ),
+        signer: from,
+        signature: signature.try_into()?,
+    })
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, ConversionError> {
+    if s.len() % 2 != 0 || !s.is_ascii() {
+        return Err(ConversionError::InvalidLength);
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| Ok(u8::from_str_radix(&s[i..i + 2], 16)?))
+        .collect()
+}
+
+#[derive(GraphQLObject, Debug)]
+#[graphql(name = "SendPaymentPayload")]
+pub struct GraphQLSendPaymentResponse {
+    pub payment: GraphQLUserCommand,
+}
+
+#[derive(GraphQLObject, Debug)]
+#[graphql(name = "SendDelegationPayload")]
+pub struct GraphQLSendDelegationResponse {
+    pub delegation: GraphQLUserCommand,
+}
+
+#[derive(GraphQLObject, Debug)]
+#[graphql(
+    name = "UserCommand",
+    description = "Common interface for user commands"
+)]
+pub struct GraphQLUserCommand {
+    /// Signed command represented as base64 string
+    pub id: String,
+    pub hash: String,
+    /// String describing the kind of user command
+    pub kind: String,
+    /// Sequence number of command for the fee-payer's account
+    pub nonce: i32,
+    /// Public key of the sender
+    pub from: String,
+    /// Public key of the receiver
+    pub to: String,
+    /// The global slot number after which this transaction cannot be applied
+    pub valid_until: String,
+    /// Token used by the command
+    pub token: String,
+    /// Amount that the source is sending to receiver; 0 for commands
+    /// without an associated amount
+    pub amount: String,
+    /// Token used to pay the fee
+    pub fee_token: String,
+    /// Fee that the fee-payer is willing to pay for making the transaction
+    pub fee: String,
+    /// A short message from the sender, encoded with Base58Check, version
+    /// byte=0x14; byte 2 of the decoding is the message length
+    pub memo: String,
+    /// If true, this command represents a delegation of stake
+    pub is_delegation: bool,
+    /// null is no failure or status unknown, reason for failure otherwise.
+    pub failure_reason: Option<String>,
+}
+
+impl TryFrom<&MinaBaseSignedCommandStableV2> for GraphQLUserCommand {
+    type Error = ConversionError;
+    fn try_from(value: &MinaBaseSignedCommandStableV2) -> Result<Self, Self::Error> {
+        let common = &value.payload.common;
+        let (kind, to, amount, is_delegation) = match &value.payload.body {
+            MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => (
+                "PAYMENT",
+                &payment.receiver_pk,
+                payment.amount.as_u64(),
+                false,
+            ),
+            MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+                MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+            ) => ("STAKE_DELEGATION", new_delegate, 0, true),
+        };
+        let token = TokenIdKeyHash::from(ledger::TokenId::default()).to_string();
+
+        Ok(Self {
+            id: value.to_base64()?,
+            hash: value.hash()?.to_string(),
+            kind: kind.to_owned(),
+            nonce: common.nonce.as_u32() as i32,
+            from: common.fee_payer_pk.to_string(),
+            to: to.to_string(),
+            valid_until: common.valid_until.as_u32().to_string(),
+            token: token.clone(),
+            amount: amount.to_string(),
+            fee_token: token,
+            fee: common.fee.as_u64().to_string(),
+            memo: common.memo.to_base58check(),
+            is_delegation,
+            failure_reason: None,
+        })
+    }
+}
@@ -1,5 +1,6 @@
 use std::{convert::Infallible, mem::size_of, str::FromStr};
 
+use juniper::futures::stream;
 use mina_p2p_messages::binprot::BinProtWrite;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use warp::{
@@ -10,8 +11,11 @@ use warp::{
 };
 
 use node::core::snark::SnarkJobId;
+use node::p2p::{BitswapCid, PeerId};
 use node::rpc::*;
 
+use crate::event_sink::SseEventSink;
+
 use openmina_node_common::rpc::{
     RpcActionStatsGetResponse, RpcSender, RpcSnarkPoolGetResponse, RpcSnarkerJobCommitResponse,
     RpcSnarkerJobSpecResponse, RpcStateGetResponse, RpcSyncStatsGetResponse,
@@ -26,7 +30,7 @@ macro_rules! compose_route {
     );
 }
 
-pub async fn run(port: u16, rpc_sender: RpcSender) {
+pub async fn run(port: u16, rpc_sender: RpcSender, events: Option<SseEventSink>) {
     let build_env_get = warp::path!("build_env")
         .and(warp::get())
         .then(move || async { with_json_reply(&node::BuildEnv::get(), StatusCode::OK) });
@@ -38,7 +42,7 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
                 incoming::{IncomingSignalingMethod, P2pConnectionIncomingInitOpts},
                 P2pConnectionResponse,
             },
-            webrtc, PeerId,
+            webrtc,
         };
 
         use super::rpc::RpcP2pConnectionIncomingResponse;
@@ -180,6 +184,121 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
             }
         });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    let peer_node_status_get = warp::path!("state" / "peers" / PeerId / "node-status")
+        .and(warp::get())
+        .then(move |peer_id: PeerId| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let res: Option<RpcP2pNodeStatusGetResponse> = rpc_sender_clone
+                    .oneshot_request(RpcRequest::P2pNodeStatusGet(peer_id))
+                    .await;
+                match res {
+                    None => with_json_reply(
+                        &"response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(Err(err)) => with_json_reply(&err, StatusCode::BAD_GATEWAY),
+                    Some(Ok(status)) => with_json_reply(&status, StatusCode::OK),
+                }
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let p2p_bans_get = warp::path!("p2p" / "bans").and(warp::get()).then(move || {
+        let rpc_sender_clone = rpc_sender_clone.clone();
+        async move {
+            let result = rpc_sender_clone
+                .oneshot_request::<RpcP2pBansGetResponse>(RpcRequest::P2pBansGet)
+                .await;
+
+            with_json_reply(&result, StatusCode::OK)
+        }
+    });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let p2p_ban_add = warp::path!("p2p" / "bans")
+        .and(warp::post())
+        .and(warp::filters::body::json())
+        .then(move |request: RpcP2pBanAddRequest| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let res: Option<RpcP2pBanAddResponse> = rpc_sender_clone
+                    .oneshot_request(RpcRequest::P2pBanAdd(request))
+                    .await;
+                match res {
+                    None => with_json_reply(
+                        &"response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(Err(err)) => with_json_reply(&err, StatusCode::BAD_REQUEST),
+                    Some(Ok(())) => with_json_reply(&(), StatusCode::OK),
+                }
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let p2p_ban_remove = warp::path!("p2p" / "bans" / PeerId)
+        .and(warp::delete())
+        .then(move |peer_id: PeerId| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let res: Option<RpcP2pBanRemoveResponse> = rpc_sender_clone
+                    .oneshot_request(RpcRequest::P2pBanRemove(peer_id))
+                    .await;
+                match res {
+                    None => with_json_reply(
+                        &"response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(Err(err)) => with_json_reply(&err, StatusCode::NOT_FOUND),
+                    Some(Ok(())) => with_json_reply(&(), StatusCode::OK),
+                }
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let p2p_bitswap_resource_add = warp::path!("p2p" / "bitswap" / "resources")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(BITSWAP_RESOURCE_MAX_SIZE))
+        .and(warp::body::bytes())
+        .then(move |body: bytes::Bytes| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let res: Option<RpcP2pBitswapResourceAddResponse> = rpc_sender_clone
+                    .oneshot_request(RpcRequest::P2pBitswapResourceAdd(body.to_vec().into()))
+                    .await;
+                match res {
+                    None => with_json_reply(
+                        &"response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(Err(err)) => with_json_reply(&err, StatusCode::SERVICE_UNAVAILABLE),
+                    Some(Ok(root)) => with_json_reply(&root, StatusCode::OK),
+                }
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let p2p_bitswap_resource_get = warp::path!("p2p" / "bitswap" / "resources" / BitswapCid)
+        .and(warp::get())
+        .then(move |root: BitswapCid| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let res: Option<RpcP2pBitswapResourceGetResponse> = rpc_sender_clone
+                    .oneshot_request(RpcRequest::P2pBitswapResourceGet(root))
+                    .await;
+                match res {
+                    None => JsonOrBinary::error(
+                        "response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(Err(err)) => JsonOrBinary::error(err, StatusCode::BAD_GATEWAY),
+                    Some(Ok(data)) => JsonOrBinary::Binary(data.to_vec()),
+                }
+            }
+        });
+
     let rpc_sender_clone = rpc_sender.clone();
     let message_progress_get = warp::path!("state" / "message-progress")
         .and(warp::get())
@@ -271,7 +390,39 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
                 }
             });
 
-        action_stats.or(sync_stats).or(block_producer_stats)
+        let rpc_sender_clone = rpc_sender.clone();
+        let bandwidth_stats = warp::path!("stats" / "bandwidth")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: Option<RpcP2pBandwidthGetResponse> = rpc_sender_clone
+                        .oneshot_request(RpcRequest::P2pBandwidthGet)
+                        .await;
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
+        let rpc_sender_clone = rpc_sender.clone();
+        let yamux_stats = warp::path!("stats" / "yamux")
+            .and(warp::get())
+            .then(move || {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let result: Option<RpcP2pYamuxStatsGetResponse> = rpc_sender_clone
+                        .oneshot_request(RpcRequest::P2pYamuxStatsGet)
+                        .await;
+
+                    with_json_reply(&result, StatusCode::OK)
+                }
+            });
+
+        action_stats
+            .or(sync_stats)
+            .or(block_producer_stats)
+            .or(bandwidth_stats)
+            .or(yamux_stats)
     };
 
     let rpc_sender_clone = rpc_sender.clone();
@@ -551,6 +702,21 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
             }
         });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    let block_producer_schedule = warp::path!("block-producer" / "schedule")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let result: RpcBlockProducerScheduleGetResponse = rpc_sender_clone
+                    .oneshot_request(RpcRequest::BlockProducerScheduleGet)
+                    .await
+                    .flatten();
+
+                with_json_reply(&result, StatusCode::OK)
+            }
+        });
+
     let cors = warp::cors()
         .allow_any_origin()
         .allow_methods(["GET", "POST", "PUT", "DELETE", "OPTIONS"])
@@ -572,6 +738,12 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
         routes,
         status,
         peers_get,
+        peer_node_status_get,
+        p2p_bans_get,
+        p2p_ban_add,
+        p2p_ban_remove,
+        p2p_bitswap_resource_add,
+        p2p_bitswap_resource_get,
         message_progress_get,
         stats,
         scan_state_summary_get,
@@ -585,10 +757,13 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
         accounts,
         transaction_post,
         transition_frontier_user_commands,
+        block_producer_schedule,
         healthcheck(rpc_sender.clone()),
         readiness(rpc_sender.clone()),
+        metrics(rpc_sender.clone()),
         discovery::routing_table(rpc_sender.clone()),
         discovery::bootstrap_stats(rpc_sender.clone()),
+        node_events(events),
         super::graphql::routes(rpc_sender),
     );
 
@@ -597,6 +772,29 @@ pub async fn run(port: u16, rpc_sender: RpcSender) {
     warp::serve(routes).run(([0, 0, 0, 0], port)).await;
 }
 
+/// Server-Sent Events stream of the node events, available when the SSE
+/// event sink is enabled.
+fn node_events(
+    events: Option<SseEventSink>,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("events").and(warp::get()).and_then(move || {
+        let events = events.clone();
+        async move {
+            let Some(events) = events else {
+                return Err(warp::reject::not_found());
+            };
+            let stream = stream::unfold(events.subscribe(), |mut rx| async move {
+                let event = rx.recv().await?;
+                let data = warp::sse::Event::default()
+                    .event(event.kind.name())
+                    .json_data(&event);
+                Some((data, rx))
+            });
+            Ok(warp::sse::reply(warp::sse::keep_alive().stream(stream)))
+        }
+    })
+}
+
 fn healthcheck(
     rpc_sender: RpcSender,
 ) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
@@ -647,6 +845,35 @@ fn readiness(
     })
 }
 
+/// Maximum size of a resource published with `POST /p2p/bitswap/resources`.
+const BITSWAP_RESOURCE_MAX_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Prometheus text exposition format.
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+fn metrics(rpc_sender: RpcSender) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("metrics").and(warp::get()).then(move || {
+        let rpc_sender = rpc_sender.clone();
+        async move {
+            let reply = rpc_sender
+                .oneshot_request(RpcRequest::MetricsGet)
+                .await
+                .map_or_else(
+                    || {
+                        with_status(
+                            String::from(DROPPED_CHANNEL),
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        )
+                    },
+                    |reply: RpcMetricsGetResponse| {
+                        with_status(reply.to_prometheus(), StatusCode::OK)
+                    },
+                );
+            warp::reply::with_header(reply, CONTENT_TYPE, METRICS_CONTENT_TYPE)
+        }
+    })
+}
+
 mod discovery {
     use node::rpc::{
         RpcDiscoveryBoostrapStatsResponse, RpcDiscoveryRoutingTableResponse, RpcRequest,
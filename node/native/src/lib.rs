@@ -1,7 +1,11 @@
 pub use openmina_node_common::*;
 
+pub mod archive;
+pub mod event_sink;
 pub mod graphql;
 pub mod http_server;
+pub mod precomputed_blocks;
+pub mod signer;
 
 mod service;
 pub use service::{NodeService, *};
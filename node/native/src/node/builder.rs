@@ -2,7 +2,7 @@ use std::{
     fs::File,
     io::{BufRead, BufReader, Read},
     net::IpAddr,
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
@@ -12,10 +12,13 @@ use ledger::proofs::provers::BlockProver;
 use mina_p2p_messages::v2::{self, NonZeroCurvePoint};
 use node::{
     account::AccountSecretKey,
+    block_producer::TransactionSelectionPolicyConfig,
     daemon_json::Daemon,
+    ledger::{LedgerAccountsOnDisk, LedgerStorage},
     p2p::{
         channels::ChannelId, connection::outgoing::P2pConnectionOutgoingInitOpts,
-        identity::SecretKey as P2pSecretKey, P2pLimits, P2pMeshsubConfig, P2pTimeouts,
+        identity::SecretKey as P2pSecretKey, service_impl::bandwidth::P2pBandwidthLimits,
+        P2pLimits, P2pMeshsubConfig, P2pTimeouts,
     },
     service::Recorder,
     snark::{get_srs, BlockVerifier, TransactionVerifier, VerifierSRS},
@@ -24,10 +27,10 @@ use node::{
     SnarkerStrategy, TransitionFrontierConfig,
 };
 use openmina_core::{consensus::ConsensusConstants, constants::constraint_constants};
-use openmina_node_common::p2p::TaskSpawner;
+use openmina_node_common::{p2p::TaskSpawner, signer::Signer};
 use rand::Rng;
 
-use crate::NodeServiceBuilder;
+use crate::{archive::SqlArchiveStorage, signer::UnixSocketSigner, NodeServiceBuilder};
 
 use super::Node;
 
@@ -39,7 +42,7 @@ pub struct NodeBuilder {
     p2p_sec_key: Option<P2pSecretKey>,
     p2p_is_seed: bool,
     p2p_is_started: bool,
-    block_producer: Option<BlockProducerConfig>,
+    block_producers: Vec<BlockProducerConfig>,
     snarker: Option<SnarkerConfig>,
     service: NodeServiceBuilder,
     verifier_srs: Option<Arc<VerifierSRS>>,
@@ -47,6 +50,8 @@ pub struct NodeBuilder {
     work_verifier_index: Option<TransactionVerifier>,
     http_port: Option<u16>,
     daemon_conf: Daemon,
+    transition_frontier_persistent: bool,
+    archive: bool,
 }
 
 impl NodeBuilder {
@@ -68,6 +73,7 @@ impl NodeBuilder {
             genesis_config,
             p2p: P2pConfig {
                 libp2p_port: None,
+                libp2p_quic_port: None,
                 listen_port: None,
                 // Must be replaced with builder api.
                 identity_pub_key: P2pSecretKey::deterministic(0).public_key(),
@@ -81,11 +87,13 @@ impl NodeBuilder {
                 },
                 timeouts: P2pTimeouts::default(),
                 limits: P2pLimits::default().with_max_peers(Some(100)),
+                ban: Default::default(),
+                peer_store: Default::default(),
             },
             p2p_sec_key: None,
             p2p_is_seed: false,
             p2p_is_started: false,
-            block_producer: None,
+            block_producers: Vec::new(),
             snarker: None,
             service: NodeServiceBuilder::new(rng_seed),
             verifier_srs: None,
@@ -93,6 +101,8 @@ impl NodeBuilder {
             work_verifier_index: None,
             http_port: None,
             daemon_conf,
+            transition_frontier_persistent: false,
+            archive: false,
         }
     }
 
@@ -114,6 +124,11 @@ impl NodeBuilder {
         self
     }
 
+    pub fn p2p_libp2p_quic_port(&mut self, port: u16) -> &mut Self {
+        self.p2p.libp2p_quic_port = Some(port);
+        self
+    }
+
     /// Set up node as a seed node.
     pub fn p2p_seed_node(&mut self) -> &mut Self {
         self.p2p_is_seed = true;
@@ -171,6 +186,40 @@ impl NodeBuilder {
         Ok(self)
     }
 
+    /// Load the p2p ban list from the file, if it exists, and persist
+    /// ban list updates into it.
+    pub fn p2p_ban_list_file(&mut self, path: impl Into<PathBuf>) -> anyhow::Result<&mut Self> {
+        let path = path.into();
+        if path.exists() {
+            let file =
+                File::open(&path).context(anyhow::anyhow!("opening p2p ban list file {path:?}"))?;
+            self.p2p.ban.initial_bans = serde_json::from_reader(BufReader::new(file))
+                .context(anyhow::anyhow!("reading p2p ban list file {path:?}"))?;
+        }
+        self.service.p2p_ban_list_path(path);
+        Ok(self)
+    }
+
+    /// Load known good peers from the file, if it exists, so that they are
+    /// dialed before seeds, and periodically persist them into it.
+    pub fn p2p_peer_store_file(&mut self, path: impl Into<PathBuf>) -> anyhow::Result<&mut Self> {
+        let path = path.into();
+        if path.exists() {
+            let file = File::open(&path)
+                .context(anyhow::anyhow!("opening p2p peer store file {path:?}"))?;
+            self.p2p.peer_store.initial_peers = serde_json::from_reader(BufReader::new(file))
+                .context(anyhow::anyhow!("reading p2p peer store file {path:?}"))?;
+        }
+        self.service.p2p_peer_store_path(path);
+        Ok(self)
+    }
+
+    /// Limit p2p bandwidth, per peer and for the whole node.
+    pub fn p2p_bandwidth_limits(&mut self, limits: P2pBandwidthLimits) -> &mut Self {
+        self.service.p2p_bandwidth_limits(limits);
+        self
+    }
+
     pub fn p2p_max_peers(&mut self, limit: usize) -> &mut Self {
         self.p2p.limits = self.p2p.limits.with_max_peers(Some(limit));
         self
@@ -188,7 +237,8 @@ impl NodeBuilder {
         Ok(self)
     }
 
-    /// Set up block producer.
+    /// Set up block producer. Can be called multiple times to produce blocks
+    /// with multiple keys.
     pub fn block_producer(
         &mut self,
         key: AccountSecretKey,
@@ -198,8 +248,12 @@ impl NodeBuilder {
             pub_key: key.public_key().into(),
             custom_coinbase_receiver: None,
             proposed_protocol_version: None,
+            transaction_selection: Vec::new(),
         };
-        self.block_producer = Some(config);
+        // The key might already be added by `block_producer_signer`.
+        self.block_producers
+            .retain(|bp| bp.pub_key != config.pub_key);
+        self.block_producers.push(config);
         self.service.block_producer_init(key, provers);
         self
     }
@@ -216,12 +270,33 @@ impl NodeBuilder {
         Ok(self.block_producer(key, provers))
     }
 
-    /// Receive block producer's coinbase reward to another account.
+    /// Set up block producer with the keys held by the signer listening on
+    /// the Unix socket at `path`. The signer evaluates the vrf and proves
+    /// the blocks, so the private keys stay in the signer process.
+    pub fn block_producer_signer(&mut self, path: impl AsRef<Path>) -> anyhow::Result<&mut Self> {
+        let mut signer = UnixSocketSigner::connect(path).context("Failed to connect to signer")?;
+        let public_keys = signer
+            .public_keys()
+            .context("Failed to get public keys from signer")?;
+        for public_key in public_keys {
+            self.block_producers.push(BlockProducerConfig {
+                pub_key: public_key.into(),
+                custom_coinbase_receiver: None,
+                proposed_protocol_version: None,
+                transaction_selection: Vec::new(),
+            });
+        }
+        self.service.block_producer_signer_init(Box::new(signer));
+        Ok(self)
+    }
+
+    /// Receive the last added block producer's coinbase reward to another
+    /// account.
     pub fn custom_coinbase_receiver(
         &mut self,
         addr: NonZeroCurvePoint,
     ) -> anyhow::Result<&mut Self> {
-        let bp = self.block_producer.as_mut().ok_or_else(|| {
+        let bp = self.block_producers.last_mut().ok_or_else(|| {
             anyhow::anyhow!(
                 "can't set custom_coinbase_receiver when block producer is not initialized."
             )
@@ -230,11 +305,28 @@ impl NodeBuilder {
         Ok(self)
     }
 
+    /// Set the transaction selection policies of all the block producers
+    /// added so far.
+    pub fn block_producer_transaction_selection(
+        &mut self,
+        policies: Vec<TransactionSelectionPolicyConfig>,
+    ) -> anyhow::Result<&mut Self> {
+        if self.block_producers.is_empty() {
+            anyhow::bail!(
+                "can't set transaction selection when block producer is not initialized."
+            );
+        }
+        for bp in &mut self.block_producers {
+            bp.transaction_selection = policies.clone();
+        }
+        Ok(self)
+    }
+
     pub fn custom_block_producer_config(
         &mut self,
         config: BlockProducerConfig,
     ) -> anyhow::Result<&mut Self> {
-        *self.block_producer.as_mut().ok_or_else(|| {
+        *self.block_producers.last_mut().ok_or_else(|| {
             anyhow::anyhow!("block producer not initialized! Call `block_producer` function first.")
         })? = config;
         Ok(self)
@@ -274,6 +366,75 @@ impl NodeBuilder {
         self
     }
 
+    /// Persist the transition frontier and its ledgers into the directory
+    /// and resume from them on startup, instead of syncing from scratch.
+    pub fn transition_frontier_storage_dir(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> anyhow::Result<&mut Self> {
+        let path = path.as_ref();
+        let storage = LedgerStorage::open(path).context(anyhow::anyhow!(
+            "opening transition frontier storage {path:?}"
+        ))?;
+        self.service.ledger_storage(storage);
+        self.transition_frontier_persistent = true;
+        Ok(self)
+    }
+
+    /// Store the accounts of snarked ledgers in `path` instead of memory,
+    /// using less memory at the cost of slower ledger access. Anything
+    /// already in `path` is removed.
+    pub fn ledger_accounts_dir(&mut self, path: impl AsRef<Path>) -> anyhow::Result<&mut Self> {
+        let path = path.as_ref();
+        let on_disk = LedgerAccountsOnDisk::open(path.to_path_buf()).context(anyhow::anyhow!(
+            "opening ledger accounts directory {path:?}"
+        ))?;
+        self.service.ledger_accounts_on_disk(on_disk);
+        Ok(self)
+    }
+
+    /// Write every applied block into the archive database at `url`,
+    /// which can be either SQLite or Postgres.
+    pub fn archive(&mut self, url: &str) -> anyhow::Result<&mut Self> {
+        let storage = SqlArchiveStorage::connect(url)
+            .context(anyhow::anyhow!("connecting to archive database {url}"))?;
+        self.service.archive_init(storage);
+        self.archive = true;
+        Ok(self)
+    }
+
+    /// Write the blocks added to the best chain into `dir`, as precomputed
+    /// block JSON files.
+    pub fn precomputed_blocks_dir(&mut self, dir: impl AsRef<Path>) -> &mut Self {
+        self.service
+            .precomputed_blocks_init(dir.as_ref().to_path_buf());
+        self.archive = true;
+        self
+    }
+
+    /// Append node events (applied blocks, best tip changes, included
+    /// transactions, ...) to the file at `path`, one JSON object per line.
+    pub fn event_log(&mut self, path: impl AsRef<Path>) -> anyhow::Result<&mut Self> {
+        let path = path.as_ref();
+        self.service
+            .event_sink_ndjson(path)
+            .context(anyhow::anyhow!("opening event log {path:?}"))?;
+        Ok(self)
+    }
+
+    /// POST node events as JSON to `url`.
+    pub fn event_webhook(&mut self, url: String) -> &mut Self {
+        self.service.event_sink_webhook(url);
+        self
+    }
+
+    /// Stream node events from the `/events` Server-Sent Events endpoint of
+    /// the http server.
+    pub fn event_sse(&mut self) -> &mut Self {
+        self.service.event_sink_sse();
+        self
+    }
+
     pub fn gather_stats(&mut self) -> &mut Self {
         self.service.gather_stats();
         self
@@ -345,8 +506,12 @@ impl NodeBuilder {
                 work_verifier_index,
                 work_verifier_srs: srs,
             },
-            transition_frontier: TransitionFrontierConfig::new(self.genesis_config),
-            block_producer: self.block_producer,
+            transition_frontier: TransitionFrontierConfig {
+                persistent: self.transition_frontier_persistent,
+                archive: self.archive,
+                ..TransitionFrontierConfig::new(self.genesis_config)
+            },
+            block_producers: self.block_producers,
             tx_pool: ledger::transaction_pool::Config {
                 trust_system: (),
                 pool_max_size: self.daemon_conf.tx_pool_max_size(),
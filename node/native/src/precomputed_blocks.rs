@@ -0,0 +1,342 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use ledger::staged_ledger::staged_ledger::SkipVerification;
+use mina_p2p_messages::v2::{self, StateHash};
+use node::{
+    core::block::AppliedBlock,
+    ledger::{write::LedgerWriteRequest, LedgerCtx, LedgerStorage},
+    transition_frontier::{
+        archive::PrecomputedBlock, genesis::GenesisConfig, sync::frontier_commit_request,
+    },
+};
+use serde::Deserialize;
+
+/// Applies the precomputed blocks found in `blocks_dir` and persists the
+/// resulting transition frontier into `frontier_dir`, so that a node using
+/// it resumes from the last imported block instead of syncing from scratch.
+///
+/// Blocks are applied on top of the frontier already persisted in
+/// `frontier_dir`, or on top of the genesis block if there isn't one. Only
+/// the chain ending at the highest block is imported, other forks are
+/// ignored.
+///
+/// Returns the new best tip, or `None` if there was nothing to import.
+pub fn import_precomputed_blocks(
+    genesis_config: &GenesisConfig,
+    blocks_dir: &Path,
+    frontier_dir: &Path,
+    skip_verification: bool,
+) -> anyhow::Result<Option<AppliedBlock>> {
+    let mut ledger = LedgerCtx::default();
+    let storage = LedgerStorage::open(frontier_dir)
+        .context(anyhow::anyhow!("opening frontier storage {frontier_dir:?}"))?;
+    ledger.set_storage(storage);
+
+    let (mut best_chain, mut needed_protocol_states) = match ledger
+        .frontier_restore()
+        .map_err(anyhow::Error::msg)
+        .context("restoring persisted frontier")?
+    {
+        Some(restored) => (
+            restored.best_chain.clone(),
+            restored.needed_protocol_states.clone(),
+        ),
+        None => {
+            let (masks, data) = genesis_config.load().context("loading genesis ledger")?;
+            masks
+                .into_iter()
+                .for_each(|mask| ledger.insert_genesis_ledger(mask));
+            let genesis = data
+                .block_with_dummy_proof()
+                .map_err(|_| anyhow::anyhow!("invalid genesis block"))?;
+            let genesis = AppliedBlock {
+                block: genesis,
+                just_emitted_a_proof: false,
+            };
+            (vec![genesis], BTreeMap::new())
+        }
+    };
+
+    let files = chain_files(blocks_dir, &best_chain)?;
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let skip_verification = skip_verification.then_some(SkipVerification::All);
+    for path in files {
+        let block = read_precomputed_block(&path)?
+            .into_block()
+            .map_err(|_| anyhow::anyhow!("invalid block {path:?}"))?;
+        let pred_block = best_chain
+            .last()
+            .cloned()
+            .expect("best chain is never empty");
+        let result = ledger
+            .block_apply(block.clone(), pred_block, skip_verification, false)
+            .map_err(anyhow::Error::msg)
+            .context(anyhow::anyhow!("applying block {path:?}"))?;
+
+        let old_chain = best_chain.clone();
+        best_chain.push(AppliedBlock {
+            block,
+            just_emitted_a_proof: result.just_emitted_a_proof,
+        });
+        let k = best_chain[0].constants().k.as_u32() as usize;
+        let new_root_index = best_chain.len().saturating_sub(k.saturating_add(1));
+        best_chain.drain(..new_root_index);
+        commit(
+            &mut ledger,
+            &old_chain,
+            &best_chain,
+            &mut needed_protocol_states,
+        );
+    }
+
+    ledger
+        .frontier_persist(&best_chain, &needed_protocol_states)
+        .map_err(anyhow::Error::msg)
+        .context("persisting frontier")?;
+    Ok(best_chain.last().cloned())
+}
+
+/// Commits the ledgers the same way the transition frontier sync does, and
+/// keeps the protocol states still needed by the new frontier.
+fn commit(
+    ledger: &mut LedgerCtx,
+    old_chain: &[AppliedBlock],
+    new_chain: &[AppliedBlock],
+    needed_protocol_states: &mut BTreeMap<StateHash, v2::MinaStateProtocolStateValueStableV2>,
+) {
+    // Blocks dropped from the old chain may be needed to recreate the
+    // root snarked ledger.
+    let old_protocol_states = old_chain
+        .iter()
+        .map(|b| (b.hash().clone(), b.header().protocol_state.clone()))
+        .collect();
+    let Some(LedgerWriteRequest::Commit {
+        ledgers_to_keep,
+        root_snarked_ledger_updates,
+        needed_protocol_states: protocol_states,
+        new_root,
+        new_best_tip,
+    }) = frontier_commit_request(
+        old_chain,
+        needed_protocol_states,
+        new_chain,
+        Default::default(),
+        &old_protocol_states,
+    )
+    else {
+        return;
+    };
+
+    let result = ledger.commit(
+        ledgers_to_keep,
+        root_snarked_ledger_updates,
+        protocol_states,
+        new_root.block_with_hash(),
+        new_best_tip.block_with_hash(),
+    );
+
+    let protocol_state = |hash: &StateHash| {
+        needed_protocol_states.get(hash).cloned().or_else(|| {
+            old_chain
+                .iter()
+                .chain(new_chain)
+                .find(|b| b.hash() == hash)
+                .map(|b| b.header().protocol_state.clone())
+        })
+    };
+    let new_needed_protocol_states = result
+        .needed_protocol_states
+        .into_iter()
+        .filter_map(|hash| Some((hash.clone(), protocol_state(&hash)?)))
+        .collect();
+    *needed_protocol_states = new_needed_protocol_states;
+}
+
+/// Files of the blocks to apply on top of `best_chain`, in order.
+fn chain_files(blocks_dir: &Path, best_chain: &[AppliedBlock]) -> anyhow::Result<Vec<PathBuf>> {
+    #[derive(Deserialize)]
+    struct Header {
+        data: HeaderData,
+    }
+    #[derive(Deserialize)]
+    struct HeaderData {
+        protocol_state: v2::MinaStateProtocolStateValueStableV2,
+    }
+
+    let root = best_chain.first().expect("best chain is never empty");
+    let best_tip = best_chain.last().expect("best chain is never empty");
+    let known = best_chain
+        .iter()
+        .map(|b| b.hash().clone())
+        .collect::<BTreeSet<_>>();
+
+    // Hash -> (height, parent hash, file).
+    let mut blocks = BTreeMap::new();
+    let entries =
+        std::fs::read_dir(blocks_dir).context(anyhow::anyhow!("reading {blocks_dir:?}"))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().map_or(true, |ext| ext != "json") {
+            continue;
+        }
+        let file = File::open(&path).context(anyhow::anyhow!("opening {path:?}"))?;
+        let header: Header = serde_json::from_reader(BufReader::new(file))
+            .context(anyhow::anyhow!("parsing {path:?}"))?;
+        let protocol_state = header.data.protocol_state;
+        let hash = protocol_state
+            .try_hash()
+            .map_err(|_| anyhow::anyhow!("invalid block {path:?}"))?;
+        let height = protocol_state
+            .body
+            .consensus_state
+            .blockchain_length
+            .as_u32();
+        // Blocks at or below the best tip are kept, so that a fork of
+        // the best chain can be told apart from missing blocks.
+        if height > root.height() {
+            blocks.insert(hash, (height, protocol_state.previous_state_hash, path));
+        }
+    }
+
+    let Some(mut hash) = blocks
+        .iter()
+        .max_by_key(|(_, (height, ..))| *height)
+        .filter(|(_, (height, ..))| *height > best_tip.height())
+        .map(|(hash, _)| hash.clone())
+    else {
+        return Ok(vec![]);
+    };
+    let mut files = vec![];
+    while !known.contains(&hash) {
+        let Some((_, pred_hash, path)) = blocks.remove(&hash) else {
+            anyhow::bail!(
+                "missing block {hash}, the blocks don't connect to the best tip {} ({})",
+                best_tip.hash(),
+                best_tip.height()
+            );
+        };
+        files.push(path);
+        hash = pred_hash;
+    }
+    if &hash != best_tip.hash() {
+        anyhow::bail!("the blocks fork off the best chain at {hash}, which isn't the best tip");
+    }
+    files.reverse();
+    Ok(files)
+}
+
+fn read_precomputed_block(path: &Path) -> anyhow::Result<PrecomputedBlock> {
+    let file = File::open(path).context(anyhow::anyhow!("opening {path:?}"))?;
+    serde_json::from_reader(BufReader::new(file)).context(anyhow::anyhow!("parsing {path:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use node::{
+        core::block::ArcBlockWithHash,
+        testing::{child_block, genesis_block},
+    };
+
+    use super::*;
+
+    fn genesis() -> AppliedBlock {
+        AppliedBlock {
+            block: genesis_block(),
+            just_emitted_a_proof: false,
+        }
+    }
+
+    fn child(parent: &AppliedBlock, timestamp: u64) -> AppliedBlock {
+        AppliedBlock {
+            block: child_block(&parent.block, timestamp),
+            just_emitted_a_proof: false,
+        }
+    }
+
+    /// Writes the blocks into a fresh directory named after `name`.
+    fn blocks_dir(name: &str, blocks: &[&AppliedBlock]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "openmina-precomputed-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        for block in blocks {
+            let block: &ArcBlockWithHash = &block.block;
+            let path = dir.join(PrecomputedBlock::file_name("testnet", block));
+            let file = File::create(path).unwrap();
+            serde_json::to_writer(file, &PrecomputedBlock::new(block, None)).unwrap();
+        }
+        dir
+    }
+
+    fn file_names(dir: &Path, blocks: &[&AppliedBlock]) -> Vec<PathBuf> {
+        blocks
+            .iter()
+            .map(|block| dir.join(PrecomputedBlock::file_name("testnet", &block.block)))
+            .collect()
+    }
+
+    #[test]
+    fn chain_files_follows_highest_chain() {
+        // g <- a <- b <- c
+        //         \- f
+        let g = genesis();
+        let a = child(&g, 1);
+        let b = child(&a, 2);
+        let f = child(&a, 3);
+        let c = child(&b, 4);
+
+        let dir = blocks_dir("chain", &[&a, &b, &f, &c]);
+        let files = chain_files(&dir, &[g.clone()]).unwrap();
+        assert_eq!(files, file_names(&dir, &[&a, &b, &c]));
+
+        // Already imported blocks are skipped.
+        let files = chain_files(&dir, &[g, a.clone()]).unwrap();
+        assert_eq!(files, file_names(&dir, &[&b, &c]));
+        let files = chain_files(&dir, &[a, b, c]).unwrap();
+        assert!(files.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn chain_files_detects_gap() {
+        let g = genesis();
+        let a = child(&g, 1);
+        let b = child(&a, 2);
+        let c = child(&b, 3);
+
+        let dir = blocks_dir("gap", &[&a, &c]);
+        let err = chain_files(&dir, &[g]).unwrap_err();
+        assert!(err.to_string().contains("missing block"), "{err}");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn chain_files_detects_fork() {
+        // g <- a <- b
+        //         \- f <- f2
+        let g = genesis();
+        let a = child(&g, 1);
+        let b = child(&a, 2);
+        let f = child(&a, 3);
+        let f2 = child(&f, 4);
+
+        let dir = blocks_dir("fork", &[&f, &f2]);
+        let err = chain_files(&dir, &[g, a, b]).unwrap_err();
+        assert!(err.to_string().contains("fork off"), "{err}");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,19 +1,32 @@
+use std::path::{Path, PathBuf};
+
 use ledger::proofs::provers::BlockProver;
 use node::{
-    account::AccountSecretKey, core::thread, p2p::identity::SecretKey as P2pSecretKey,
+    account::AccountSecretKey,
+    core::thread,
+    ledger::{LedgerAccountsOnDisk, LedgerStorage},
+    p2p::{identity::SecretKey as P2pSecretKey, service_impl::bandwidth::P2pBandwidthLimits},
     service::Recorder,
 };
 pub use openmina_node_common::NodeServiceCommonBuildError;
 use openmina_node_common::{
-    p2p::TaskSpawner, rpc::RpcSender, EventSender, NodeServiceCommonBuilder,
+    p2p::TaskSpawner, rpc::RpcSender, signer::Signer, EventSender, NodeServiceCommonBuilder,
 };
 
-use crate::{http_server, NodeService, P2pTaskSpawner};
+use crate::{
+    archive::SqlArchiveStorage,
+    event_sink::{NdjsonFileEventSink, SseEventSink, WebhookEventSink},
+    http_server, NodeService, P2pTaskSpawner,
+};
 
 pub struct NodeServiceBuilder {
     common: NodeServiceCommonBuilder,
     pub(super) recorder: Recorder,
+    p2p_ban_list_path: Option<PathBuf>,
+    p2p_peer_store_path: Option<PathBuf>,
+    p2p_bandwidth_limits: P2pBandwidthLimits,
     http_server_port: Option<u16>,
+    sse_event_sink: Option<SseEventSink>,
 }
 
 #[derive(thiserror::Error, derive_more::From, Debug, Clone)]
@@ -27,7 +40,11 @@ impl NodeServiceBuilder {
         Self {
             common: NodeServiceCommonBuilder::new(rng_seed),
             recorder: Default::default(),
+            p2p_ban_list_path: None,
+            p2p_peer_store_path: None,
+            p2p_bandwidth_limits: Default::default(),
             http_server_port: None,
+            sse_event_sink: None,
         }
     }
 
@@ -39,11 +56,32 @@ impl NodeServiceBuilder {
         self.common.rpc_sender()
     }
 
+    pub fn ledger_storage(&mut self, storage: LedgerStorage) -> &mut Self {
+        self.common.ledger_storage(storage);
+        self
+    }
+
+    pub fn ledger_accounts_on_disk(&mut self, on_disk: LedgerAccountsOnDisk) -> &mut Self {
+        self.common.ledger_accounts_on_disk(on_disk);
+        self
+    }
+
     pub fn ledger_init(&mut self) -> &mut Self {
         self.common.ledger_init();
         self
     }
 
+    pub fn archive_init(&mut self, storage: SqlArchiveStorage) -> &mut Self {
+        self.common.archive_init(storage);
+        self
+    }
+
+    pub fn precomputed_blocks_init(&mut self, dir: PathBuf) -> &mut Self {
+        self.common
+            .precomputed_blocks_init(dir, openmina_core::NetworkConfig::global().name);
+        self
+    }
+
     pub fn block_producer_init(
         &mut self,
         keypair: AccountSecretKey,
@@ -53,6 +91,11 @@ impl NodeServiceBuilder {
         self
     }
 
+    pub fn block_producer_signer_init(&mut self, signer: Box<dyn Signer>) -> &mut Self {
+        self.common.block_producer_signer_init(signer);
+        self
+    }
+
     pub fn p2p_init(&mut self, secret_key: P2pSecretKey) -> &mut Self {
         self.common.p2p_init(secret_key, P2pTaskSpawner {});
         self
@@ -77,26 +120,74 @@ impl NodeServiceBuilder {
         self
     }
 
+    pub fn p2p_ban_list_path(&mut self, path: PathBuf) -> &mut Self {
+        self.p2p_ban_list_path = Some(path);
+        self
+    }
+
+    pub fn p2p_peer_store_path(&mut self, path: PathBuf) -> &mut Self {
+        self.p2p_peer_store_path = Some(path);
+        self
+    }
+
+    pub fn p2p_bandwidth_limits(&mut self, limits: P2pBandwidthLimits) -> &mut Self {
+        self.p2p_bandwidth_limits = limits;
+        self
+    }
+
+    /// Append node events to the file at `path`, one JSON object per line.
+    pub fn event_sink_ndjson(&mut self, path: impl AsRef<Path>) -> std::io::Result<&mut Self> {
+        let sink = NdjsonFileEventSink::open(path)?;
+        self.common.event_sink_add("ndjson", sink);
+        Ok(self)
+    }
+
+    /// POST node events as JSON to `url`.
+    pub fn event_sink_webhook(&mut self, url: String) -> &mut Self {
+        self.common
+            .event_sink_add("webhook", WebhookEventSink::new(url));
+        self
+    }
+
+    /// Stream node events from the `/events` endpoint of the http server.
+    pub fn event_sink_sse(&mut self) -> &mut Self {
+        let sink = SseEventSink::default();
+        self.sse_event_sink = Some(sink.clone());
+        self.common.event_sink_add("sse", sink);
+        self
+    }
+
+    /// Http server is started when the service is built.
     pub fn http_server_init(&mut self, port: u16) -> &mut Self {
         if let Some(cur_port) = self.http_server_port {
             panic!("trying to start http server on port `{port}`, when it's already running on port `{cur_port}`");
         }
         self.http_server_port = Some(port);
+        self
+    }
+
+    fn http_server_start(&self, port: u16) {
         let rpc_sender = self.rpc_sender();
+        let events = self.sse_event_sink.clone();
         let runtime = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .unwrap();
         thread::Builder::new()
             .name("openmina_http_server".to_owned())
-            .spawn(move || runtime.block_on(http_server::run(port, rpc_sender)))
+            .spawn(move || runtime.block_on(http_server::run(port, rpc_sender, events)))
             .unwrap();
-        self
     }
 
     pub fn build(self) -> Result<NodeService, NodeServiceBuildError> {
+        if let Some(port) = self.http_server_port {
+            self.http_server_start(port);
+        }
         let mut service = self.common.build()?;
         service.recorder = self.recorder;
+        service.p2p_ban_list_path = self.p2p_ban_list_path;
+        service.p2p_peer_store_path = self.p2p_peer_store_path;
+        service.p2p.bandwidth.set_limits(self.p2p_bandwidth_limits);
         Ok(service)
     }
 }
@@ -0,0 +1,233 @@
+//! Signer which talks to an external signing process over a Unix socket.
+//!
+//! Requests and responses are JSON objects, one per line.
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use mina_p2p_messages::v2::{MinaBaseProofStableV2, ProverExtendBlockchainInputStableV2};
+use node::account::AccountPublicKey;
+pub use openmina_node_common::signer::*;
+use serde::{Deserialize, Serialize};
+use vrf::VrfEvaluationOutput;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SignerRequest {
+    PublicKeys,
+    EvaluateVrf(SignerVrfRequest),
+    /// Block prover input, without the block creator's private key.
+    ProveBlock(Box<ProverExtendBlockchainInputStableV2>),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SignerResponse {
+    PublicKeys(Vec<AccountPublicKey>),
+    VrfEvaluated(VrfEvaluationOutput),
+    BlockProved(MinaBaseProofStableV2),
+    Error(SignerError),
+}
+
+pub struct UnixSocketSigner {
+    path: PathBuf,
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+}
+
+impl UnixSocketSigner {
+    pub fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_owned();
+        let writer = UnixStream::connect(&path)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(Self {
+            path,
+            reader,
+            writer,
+        })
+    }
+
+    fn request(&mut self, request: &SignerRequest) -> Result<SignerResponse, SignerError> {
+        let io_error = |err: io::Error| SignerError::Other(err.to_string());
+        let mut line =
+            serde_json::to_vec(request).map_err(|e| SignerError::Other(e.to_string()))?;
+        line.push(b'\n');
+        self.writer.write_all(&line).map_err(io_error)?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line).map_err(io_error)? == 0 {
+            return Err(SignerError::Other(
+                "signer closed the connection".to_owned(),
+            ));
+        }
+        match serde_json::from_str(&line).map_err(|e| SignerError::Other(e.to_string()))? {
+            SignerResponse::Error(error) => Err(error),
+            response => Ok(response),
+        }
+    }
+}
+
+fn unexpected_response(response: SignerResponse) -> SignerError {
+    SignerError::Other(format!("unexpected signer response: {response:?}"))
+}
+
+impl Signer for UnixSocketSigner {
+    fn public_keys(&mut self) -> Result<Vec<AccountPublicKey>, SignerError> {
+        match self.request(&SignerRequest::PublicKeys)? {
+            SignerResponse::PublicKeys(keys) => Ok(keys),
+            response => Err(unexpected_response(response)),
+        }
+    }
+
+    fn evaluate_vrf(
+        &mut self,
+        request: &SignerVrfRequest,
+    ) -> Result<VrfEvaluationOutput, SignerError> {
+        match self.request(&SignerRequest::EvaluateVrf(request.clone()))? {
+            SignerResponse::VrfEvaluated(output) => Ok(output),
+            response => Err(unexpected_response(response)),
+        }
+    }
+
+    fn prove_block(
+        &mut self,
+        input: Box<ProverExtendBlockchainInputStableV2>,
+    ) -> Result<Arc<MinaBaseProofStableV2>, SignerError> {
+        match self.request(&SignerRequest::ProveBlock(input))? {
+            SignerResponse::BlockProved(proof) => Ok(proof.into()),
+            response => Err(unexpected_response(response)),
+        }
+    }
+
+    /// Opens another connection to the signer.
+    fn try_clone(&self) -> Result<Box<dyn Signer>, SignerError> {
+        Self::connect(&self.path)
+            .map(|signer| Box::new(signer) as _)
+            .map_err(|err| SignerError::Other(err.to_string()))
+    }
+}
+
+/// Serves the requests of the [UnixSocketSigner]s connecting to `path`
+/// with `signer`, each connection on its own thread, so that a block proof
+/// doesn't hold back the vrf evaluation.
+pub fn serve(path: impl AsRef<Path>, signer: impl Signer) -> io::Result<()> {
+    let listener = UnixListener::bind(path)?;
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let mut signer = signer
+            .try_clone()
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        std::thread::spawn(move || {
+            if let Err(error) = serve_connection(stream, &mut signer) {
+                openmina_core::warn!(
+                    openmina_core::log::system_time();
+                    summary = "signer connection failed",
+                    error = error.to_string()
+                );
+            }
+        });
+    }
+    Ok(())
+}
+
+fn serve_connection(stream: UnixStream, signer: &mut Box<dyn Signer>) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let response = match serde_json::from_str(&line?) {
+            Ok(SignerRequest::PublicKeys) => signer.public_keys().map(SignerResponse::PublicKeys),
+            Ok(SignerRequest::EvaluateVrf(request)) => signer
+                .evaluate_vrf(&request)
+                .map(SignerResponse::VrfEvaluated),
+            Ok(SignerRequest::ProveBlock(input)) => signer
+                .prove_block(input)
+                .map(|proof| SignerResponse::BlockProved((*proof).clone())),
+            Err(err) => Err(SignerError::InvalidInput(err.to_string())),
+        }
+        .unwrap_or_else(SignerResponse::Error);
+
+        let mut line = serde_json::to_vec(&response)?;
+        line.push(b'\n');
+        writer.write_all(&line)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::v2::EpochSeed;
+    use node::account::AccountSecretKey;
+
+    use super::*;
+
+    fn socket_path(name: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("openmina-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("signer.sock");
+        let _ = std::fs::remove_file(&path);
+        (dir, path)
+    }
+
+    #[test]
+    fn unix_socket_signer_forwards_to_served_signer() {
+        let (dir, path) = socket_path("signer");
+
+        let key = AccountSecretKey::deterministic(0);
+        let public_key = key.public_key();
+        let producer = public_key.clone();
+        let listener = UnixListener::bind(&path).unwrap();
+        std::thread::spawn(move || {
+            let mut signer: Box<dyn Signer> = Box::new(LocalSigner::new([key]));
+            let (stream, _) = listener.accept().unwrap();
+            serve_connection(stream, &mut signer).unwrap();
+        });
+
+        let mut signer = UnixSocketSigner::connect(&path).unwrap();
+        assert_eq!(signer.public_keys().unwrap(), vec![public_key]);
+
+        let mut request = SignerVrfRequest {
+            producer,
+            epoch_seed: EpochSeed::zero(),
+            global_slot: 7,
+            total_currency: 1,
+            delegator_table: Default::default(),
+        };
+        assert!(matches!(
+            signer.evaluate_vrf(&request),
+            Ok(VrfEvaluationOutput::SlotLost(7))
+        ));
+
+        let unknown = AccountSecretKey::deterministic(1).public_key();
+        request.producer = unknown.clone();
+        assert!(matches!(
+            signer.evaluate_vrf(&request),
+            Err(SignerError::UnknownKey(key)) if key == unknown
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn served_signer_accepts_a_connection_per_clone() {
+        let (dir, path) = socket_path("signer-clone");
+
+        let key = AccountSecretKey::deterministic(0);
+        let public_key = key.public_key();
+        let server_path = path.clone();
+        std::thread::spawn(move || serve(server_path, LocalSigner::new([key])));
+        let mut signer = loop {
+            match UnixSocketSigner::connect(&path) {
+                Ok(signer) => break signer,
+                Err(_) => std::thread::sleep(std::time::Duration::from_millis(10)),
+            }
+        };
+
+        // the first connection stays open while the clone is served
+        let mut clone = signer.try_clone().unwrap();
+        assert_eq!(clone.public_keys().unwrap(), vec![public_key.clone()]);
+        assert_eq!(signer.public_keys().unwrap(), vec![public_key]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
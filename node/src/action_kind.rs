@@ -27,6 +27,8 @@ use crate::ledger::read::LedgerReadAction;
 use crate::ledger::write::LedgerWriteAction;
 use crate::ledger::LedgerAction;
 use crate::ledger_effectful::LedgerEffectfulAction;
+use crate::p2p::ban::P2pBanAction;
+use crate::p2p::ban_effectful::P2pBanEffectfulAction;
 use crate::p2p::callbacks::P2pCallbacksAction;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
 use crate::p2p::channels::rpc::P2pChannelsRpcAction;
@@ -47,6 +49,7 @@ use crate::p2p::connection::{P2pConnectionAction, P2pConnectionEffectfulAction};
 use crate::p2p::disconnection::P2pDisconnectionAction;
 use crate::p2p::disconnection_effectful::P2pDisconnectionEffectfulAction;
 use crate::p2p::identify::P2pIdentifyAction;
+use crate::p2p::network::bitswap::P2pNetworkBitswapAction;
 use crate::p2p::network::identify::stream::P2pNetworkIdentifyStreamAction;
 use crate::p2p::network::identify::stream_effectful::P2pNetworkIdentifyStreamEffectfulAction;
 use crate::p2p::network::identify::{P2pNetworkIdentifyAction, P2pNetworkIdentifyEffectfulAction};
@@ -55,7 +58,9 @@ use crate::p2p::network::kad::kad_effectful::P2pNetworkKadEffectfulAction;
 use crate::p2p::network::kad::request::P2pNetworkKadRequestAction;
 use crate::p2p::network::kad::stream::P2pNetworkKademliaStreamAction;
 use crate::p2p::network::kad::{P2pNetworkKadAction, P2pNetworkKademliaAction};
+use crate::p2p::network::node_status::P2pNetworkNodeStatusAction;
 use crate::p2p::network::noise::P2pNetworkNoiseAction;
+use crate::p2p::network::ping::P2pNetworkPingAction;
 use crate::p2p::network::pnet::P2pNetworkPnetAction;
 use crate::p2p::network::pnet_effectful::P2pNetworkPnetEffectfulAction;
 use crate::p2p::network::pubsub::pubsub_effectful::P2pNetworkPubsubEffectfulAction;
@@ -67,6 +72,8 @@ use crate::p2p::network::select::P2pNetworkSelectAction;
 use crate::p2p::network::yamux::P2pNetworkYamuxAction;
 use crate::p2p::network::{P2pNetworkAction, P2pNetworkEffectfulAction};
 use crate::p2p::peer::P2pPeerAction;
+use crate::p2p::peer_store::P2pPeerStoreAction;
+use crate::p2p::peer_store_effectful::P2pPeerStoreEffectfulAction;
 use crate::p2p::{P2pAction, P2pEffectfulAction, P2pInitializeAction};
 use crate::rpc::RpcAction;
 use crate::rpc_effectful::RpcEffectfulAction;
@@ -188,6 +195,7 @@ pub enum ActionKind {
     ExternalSnarkWorkerEffectfulKill,
     ExternalSnarkWorkerEffectfulStart,
     ExternalSnarkWorkerEffectfulSubmitWork,
+    LedgerEffectfulFrontierPersist,
     LedgerEffectfulReadInit,
     LedgerEffectfulWriteInit,
     LedgerReadFindTodos,
@@ -198,6 +206,10 @@ pub enum ActionKind {
     LedgerWriteInit,
     LedgerWritePending,
     LedgerWriteSuccess,
+    P2pBanAdd,
+    P2pBanPruneExpired,
+    P2pBanRemove,
+    P2pBanEffectfulPersist,
     P2pCallbacksP2pChannelsRpcReady,
     P2pCallbacksP2pChannelsRpcRequestReceived,
     P2pCallbacksP2pChannelsRpcResponseReceived,
@@ -206,6 +218,9 @@ pub enum ActionKind {
     P2pCallbacksP2pChannelsStreamingRpcResponseReceived,
     P2pCallbacksP2pChannelsStreamingRpcTimeout,
     P2pCallbacksP2pDisconnection,
+    P2pCallbacksP2pNetworkBitswapResourceError,
+    P2pCallbacksP2pNetworkBitswapResourceReady,
+    P2pCallbacksP2pNetworkNodeStatusRequest,
     P2pCallbacksRpcRespondBestTip,
     P2pChannelsBestTipInit,
     P2pChannelsBestTipPending,
@@ -349,6 +364,14 @@ pub enum ActionKind {
     P2pIdentifyNewRequest,
     P2pIdentifyUpdatePeerInformation,
     P2pInitializeInitialize,
+    P2pNetworkBitswapAddResource,
+    P2pNetworkBitswapIncomingData,
+    P2pNetworkBitswapNewStream,
+    P2pNetworkBitswapOutgoingData,
+    P2pNetworkBitswapOutgoingMessage,
+    P2pNetworkBitswapRemoteClose,
+    P2pNetworkBitswapRemoveResource,
+    P2pNetworkBitswapWant,
     P2pNetworkIdentifyStreamClose,
     P2pNetworkIdentifyStreamIncomingData,
     P2pNetworkIdentifyStreamNew,
@@ -387,6 +410,13 @@ pub enum ActionKind {
     P2pNetworkKademliaStreamSendResponse,
     P2pNetworkKademliaStreamWaitIncoming,
     P2pNetworkKademliaStreamWaitOutgoing,
+    P2pNetworkNodeStatusIncomingData,
+    P2pNetworkNodeStatusNewStream,
+    P2pNetworkNodeStatusQuery,
+    P2pNetworkNodeStatusQueryError,
+    P2pNetworkNodeStatusQuerySuccess,
+    P2pNetworkNodeStatusRemoteClose,
+    P2pNetworkNodeStatusRespond,
     P2pNetworkNoiseDecryptedData,
     P2pNetworkNoiseHandshakeDone,
     P2pNetworkNoiseIncomingChunk,
@@ -396,6 +426,11 @@ pub enum ActionKind {
     P2pNetworkNoiseOutgoingChunkSelectMux,
     P2pNetworkNoiseOutgoingData,
     P2pNetworkNoiseOutgoingDataSelectMux,
+    P2pNetworkPingIncomingData,
+    P2pNetworkPingNewStream,
+    P2pNetworkPingPing,
+    P2pNetworkPingRemoteClose,
+    P2pNetworkPingTimeout,
     P2pNetworkPnetIncomingData,
     P2pNetworkPnetOutgoingData,
     P2pNetworkPnetSetupNonce,
@@ -405,6 +440,7 @@ pub enum ActionKind {
     P2pNetworkPubsubBroadcast,
     P2pNetworkPubsubBroadcastSigned,
     P2pNetworkPubsubGraft,
+    P2pNetworkPubsubHeartbeat,
     P2pNetworkPubsubIncomingData,
     P2pNetworkPubsubIncomingMessage,
     P2pNetworkPubsubIncomingMessageCleanup,
@@ -414,6 +450,7 @@ pub enum ActionKind {
     P2pNetworkPubsubOutgoingMessageClear,
     P2pNetworkPubsubOutgoingMessageError,
     P2pNetworkPubsubPrune,
+    P2pNetworkPubsubRejectMessage,
     P2pNetworkPubsubSign,
     P2pNetworkPubsubSignError,
     P2pNetworkPubsubValidateIncomingMessages,
@@ -442,6 +479,8 @@ pub enum ActionKind {
     P2pNetworkSchedulerOutgoingDidConnect,
     P2pNetworkSchedulerPrune,
     P2pNetworkSchedulerPruneStream,
+    P2pNetworkSchedulerQuicDidConnect,
+    P2pNetworkSchedulerQuicIncomingData,
     P2pNetworkSchedulerSelectDone,
     P2pNetworkSchedulerSelectError,
     P2pNetworkSchedulerYamuxDidInit,
@@ -453,6 +492,8 @@ pub enum ActionKind {
     P2pNetworkSchedulerEffectfulNoiseSelectDone,
     P2pNetworkSchedulerEffectfulOutgoingConnect,
     P2pNetworkSchedulerEffectfulOutgoingDidConnect,
+    P2pNetworkSchedulerEffectfulQuicInterfaceDetected,
+    P2pNetworkSchedulerEffectfulQuicSend,
     P2pNetworkSelectIncomingData,
     P2pNetworkSelectIncomingDataAuth,
     P2pNetworkSelectIncomingDataMux,
@@ -473,8 +514,12 @@ pub enum ActionKind {
     P2pPeerDiscovered,
     P2pPeerReady,
     P2pPeerRemove,
+    P2pPeerRttUpdate,
+    P2pPeerStoreSave,
+    P2pPeerStoreEffectfulPersist,
     RpcActionStatsGet,
     RpcBestChain,
+    RpcBlockProducerScheduleGet,
     RpcBlockProducerStatsGet,
     RpcConsensusConstantsGet,
     RpcDiscoveryBoostrapStats,
@@ -486,6 +531,15 @@ pub enum ActionKind {
     RpcLedgerAccountsGetPending,
     RpcLedgerAccountsGetSuccess,
     RpcMessageProgressGet,
+    RpcMetricsGet,
+    RpcP2pBanAdd,
+    RpcP2pBanRemove,
+    RpcP2pBandwidthGet,
+    RpcP2pBansGet,
+    RpcP2pBitswapResourceAdd,
+    RpcP2pBitswapResourceGetError,
+    RpcP2pBitswapResourceGetInit,
+    RpcP2pBitswapResourceGetSuccess,
     RpcP2pConnectionIncomingAnswerReady,
     RpcP2pConnectionIncomingError,
     RpcP2pConnectionIncomingInit,
@@ -496,6 +550,10 @@ pub enum ActionKind {
     RpcP2pConnectionOutgoingInit,
     RpcP2pConnectionOutgoingPending,
     RpcP2pConnectionOutgoingSuccess,
+    RpcP2pNodeStatusGetError,
+    RpcP2pNodeStatusGetInit,
+    RpcP2pNodeStatusGetSuccess,
+    RpcP2pYamuxStatsGet,
     RpcPeersGet,
     RpcReadinessCheck,
     RpcScanStateSummaryGetInit,
@@ -517,9 +575,13 @@ pub enum ActionKind {
     RpcTransactionInjectSuccess,
     RpcTransactionPool,
     RpcTransactionStatusGet,
+    RpcTransitionFrontierNotify,
+    RpcTransitionFrontierSubscribe,
+    RpcTransitionFrontierUnsubscribe,
     RpcTransitionFrontierUserCommandsGet,
     RpcEffectfulActionStatsGet,
     RpcEffectfulBestChain,
+    RpcEffectfulBlockProducerScheduleGet,
     RpcEffectfulBlockProducerStatsGet,
     RpcEffectfulConsensusConstantsGet,
     RpcEffectfulDiscoveryBoostrapStats,
@@ -528,11 +590,20 @@ pub enum ActionKind {
     RpcEffectfulHealthCheck,
     RpcEffectfulLedgerAccountsGetSuccess,
     RpcEffectfulMessageProgressGet,
+    RpcEffectfulMetricsGet,
+    RpcEffectfulP2pBanAdd,
+    RpcEffectfulP2pBanRemove,
+    RpcEffectfulP2pBandwidthGet,
+    RpcEffectfulP2pBansGet,
+    RpcEffectfulP2pBitswapResourceAdd,
+    RpcEffectfulP2pBitswapResourceGet,
     RpcEffectfulP2pConnectionIncomingError,
     RpcEffectfulP2pConnectionIncomingRespond,
     RpcEffectfulP2pConnectionIncomingSuccess,
     RpcEffectfulP2pConnectionOutgoingError,
     RpcEffectfulP2pConnectionOutgoingSuccess,
+    RpcEffectfulP2pNodeStatusGet,
+    RpcEffectfulP2pYamuxStatsGet,
     RpcEffectfulPeersGet,
     RpcEffectfulReadinessCheck,
     RpcEffectfulScanStateSummaryGetSuccess,
@@ -549,6 +620,7 @@ pub enum ActionKind {
     RpcEffectfulTransactionInjectSuccess,
     RpcEffectfulTransactionPool,
     RpcEffectfulTransactionStatusGet,
+    RpcEffectfulTransitionFrontierNotify,
     RpcEffectfulTransitionFrontierUserCommandsGet,
     SnarkBlockVerifyError,
     SnarkBlockVerifyFinish,
@@ -618,6 +690,10 @@ pub enum ActionKind {
     TransactionPoolEffectfulFetchAccounts,
     TransitionFrontierGenesisInject,
     TransitionFrontierGenesisProvenInject,
+    TransitionFrontierRestoreError,
+    TransitionFrontierRestoreInit,
+    TransitionFrontierRestorePending,
+    TransitionFrontierRestoreSuccess,
     TransitionFrontierSyncFailed,
     TransitionFrontierSynced,
     TransitionFrontierGenesisLedgerLoadInit,
@@ -707,7 +783,7 @@ pub enum ActionKind {
 }
 
 impl ActionKind {
-    pub const COUNT: u16 = 597;
+    pub const COUNT: u16 = 666;
 }
 
 impl std::fmt::Display for ActionKind {
@@ -767,6 +843,8 @@ impl ActionKindGet for P2pAction {
             Self::Initialization(a) => a.kind(),
             Self::Connection(a) => a.kind(),
             Self::Disconnection(a) => a.kind(),
+            Self::Ban(a) => a.kind(),
+            Self::PeerStore(a) => a.kind(),
             Self::Identify(a) => a.kind(),
             Self::Channels(a) => a.kind(),
             Self::Peer(a) => a.kind(),
@@ -781,6 +859,8 @@ impl ActionKindGet for P2pEffectfulAction {
             Self::Channels(a) => a.kind(),
             Self::Connection(a) => a.kind(),
             Self::Disconnection(a) => a.kind(),
+            Self::Ban(a) => a.kind(),
+            Self::PeerStore(a) => a.kind(),
             Self::Network(a) => a.kind(),
             Self::Initialize => ActionKind::P2pEffectfulInitialize,
         }
@@ -809,6 +889,15 @@ impl ActionKindGet for P2pCallbacksAction {
             }
             Self::P2pDisconnection { .. } => ActionKind::P2pCallbacksP2pDisconnection,
             Self::RpcRespondBestTip { .. } => ActionKind::P2pCallbacksRpcRespondBestTip,
+            Self::P2pNetworkNodeStatusRequest { .. } => {
+                ActionKind::P2pCallbacksP2pNetworkNodeStatusRequest
+            }
+            Self::P2pNetworkBitswapResourceReady { .. } => {
+                ActionKind::P2pCallbacksP2pNetworkBitswapResourceReady
+            }
+            Self::P2pNetworkBitswapResourceError { .. } => {
+                ActionKind::P2pCallbacksP2pNetworkBitswapResourceError
+            }
         }
     }
 }
@@ -827,6 +916,7 @@ impl ActionKindGet for LedgerEffectfulAction {
         match self {
             Self::WriteInit { .. } => ActionKind::LedgerEffectfulWriteInit,
             Self::ReadInit { .. } => ActionKind::LedgerEffectfulReadInit,
+            Self::FrontierPersist { .. } => ActionKind::LedgerEffectfulFrontierPersist,
         }
     }
 }
@@ -875,6 +965,10 @@ impl ActionKindGet for TransitionFrontierAction {
             Self::Sync(a) => a.kind(),
             Self::GenesisInject => ActionKind::TransitionFrontierGenesisInject,
             Self::GenesisProvenInject => ActionKind::TransitionFrontierGenesisProvenInject,
+            Self::RestoreInit => ActionKind::TransitionFrontierRestoreInit,
+            Self::RestorePending => ActionKind::TransitionFrontierRestorePending,
+            Self::RestoreSuccess { .. } => ActionKind::TransitionFrontierRestoreSuccess,
+            Self::RestoreError { .. } => ActionKind::TransitionFrontierRestoreError,
             Self::Synced { .. } => ActionKind::TransitionFrontierSynced,
             Self::SyncFailed { .. } => ActionKind::TransitionFrontierSyncFailed,
         }
@@ -1039,6 +1133,8 @@ impl ActionKindGet for RpcAction {
             Self::ActionStatsGet { .. } => ActionKind::RpcActionStatsGet,
             Self::SyncStatsGet { .. } => ActionKind::RpcSyncStatsGet,
             Self::BlockProducerStatsGet { .. } => ActionKind::RpcBlockProducerStatsGet,
+            Self::BlockProducerScheduleGet { .. } => ActionKind::RpcBlockProducerScheduleGet,
+            Self::MetricsGet { .. } => ActionKind::RpcMetricsGet,
             Self::MessageProgressGet { .. } => ActionKind::RpcMessageProgressGet,
             Self::PeersGet { .. } => ActionKind::RpcPeersGet,
             Self::P2pConnectionOutgoingInit { .. } => ActionKind::RpcP2pConnectionOutgoingInit,
@@ -1063,6 +1159,20 @@ impl ActionKindGet for RpcAction {
             Self::P2pConnectionIncomingSuccess { .. } => {
                 ActionKind::RpcP2pConnectionIncomingSuccess
             }
+            Self::P2pBansGet { .. } => ActionKind::RpcP2pBansGet,
+            Self::P2pBanAdd { .. } => ActionKind::RpcP2pBanAdd,
+            Self::P2pBanRemove { .. } => ActionKind::RpcP2pBanRemove,
+            Self::P2pBandwidthGet { .. } => ActionKind::RpcP2pBandwidthGet,
+            Self::P2pYamuxStatsGet { .. } => ActionKind::RpcP2pYamuxStatsGet,
+            Self::P2pBitswapResourceAdd { .. } => ActionKind::RpcP2pBitswapResourceAdd,
+            Self::P2pBitswapResourceGetInit { .. } => ActionKind::RpcP2pBitswapResourceGetInit,
+            Self::P2pBitswapResourceGetSuccess { .. } => {
+                ActionKind::RpcP2pBitswapResourceGetSuccess
+            }
+            Self::P2pBitswapResourceGetError { .. } => ActionKind::RpcP2pBitswapResourceGetError,
+            Self::P2pNodeStatusGetInit { .. } => ActionKind::RpcP2pNodeStatusGetInit,
+            Self::P2pNodeStatusGetSuccess { .. } => ActionKind::RpcP2pNodeStatusGetSuccess,
+            Self::P2pNodeStatusGetError { .. } => ActionKind::RpcP2pNodeStatusGetError,
             Self::ScanStateSummaryGetInit { .. } => ActionKind::RpcScanStateSummaryGetInit,
             Self::ScanStateSummaryLedgerGetInit { .. } => {
                 ActionKind::RpcScanStateSummaryLedgerGetInit
@@ -1094,6 +1204,11 @@ impl ActionKindGet for RpcAction {
             Self::BestChain { .. } => ActionKind::RpcBestChain,
             Self::ConsensusConstantsGet { .. } => ActionKind::RpcConsensusConstantsGet,
             Self::TransactionStatusGet { .. } => ActionKind::RpcTransactionStatusGet,
+            Self::TransitionFrontierSubscribe { .. } => ActionKind::RpcTransitionFrontierSubscribe,
+            Self::TransitionFrontierNotify => ActionKind::RpcTransitionFrontierNotify,
+            Self::TransitionFrontierUnsubscribe { .. } => {
+                ActionKind::RpcTransitionFrontierUnsubscribe
+            }
             Self::Finish { .. } => ActionKind::RpcFinish,
         }
     }
@@ -1107,6 +1222,10 @@ impl ActionKindGet for RpcEffectfulAction {
             Self::ActionStatsGet { .. } => ActionKind::RpcEffectfulActionStatsGet,
             Self::SyncStatsGet { .. } => ActionKind::RpcEffectfulSyncStatsGet,
             Self::BlockProducerStatsGet { .. } => ActionKind::RpcEffectfulBlockProducerStatsGet,
+            Self::BlockProducerScheduleGet { .. } => {
+                ActionKind::RpcEffectfulBlockProducerScheduleGet
+            }
+            Self::MetricsGet { .. } => ActionKind::RpcEffectfulMetricsGet,
             Self::MessageProgressGet { .. } => ActionKind::RpcEffectfulMessageProgressGet,
             Self::PeersGet { .. } => ActionKind::RpcEffectfulPeersGet,
             Self::P2pConnectionOutgoingError { .. } => {
@@ -1124,6 +1243,14 @@ impl ActionKindGet for RpcEffectfulAction {
             Self::P2pConnectionIncomingSuccess { .. } => {
                 ActionKind::RpcEffectfulP2pConnectionIncomingSuccess
             }
+            Self::P2pNodeStatusGet { .. } => ActionKind::RpcEffectfulP2pNodeStatusGet,
+            Self::P2pBitswapResourceAdd { .. } => ActionKind::RpcEffectfulP2pBitswapResourceAdd,
+            Self::P2pBitswapResourceGet { .. } => ActionKind::RpcEffectfulP2pBitswapResourceGet,
+            Self::P2pBansGet { .. } => ActionKind::RpcEffectfulP2pBansGet,
+            Self::P2pBanAdd { .. } => ActionKind::RpcEffectfulP2pBanAdd,
+            Self::P2pBanRemove { .. } => ActionKind::RpcEffectfulP2pBanRemove,
+            Self::P2pBandwidthGet { .. } => ActionKind::RpcEffectfulP2pBandwidthGet,
+            Self::P2pYamuxStatsGet { .. } => ActionKind::RpcEffectfulP2pYamuxStatsGet,
             Self::ScanStateSummaryGetSuccess { .. } => {
                 ActionKind::RpcEffectfulScanStateSummaryGetSuccess
             }
@@ -1158,6 +1285,9 @@ impl ActionKindGet for RpcEffectfulAction {
             Self::BestChain { .. } => ActionKind::RpcEffectfulBestChain,
             Self::ConsensusConstantsGet { .. } => ActionKind::RpcEffectfulConsensusConstantsGet,
             Self::TransactionStatusGet { .. } => ActionKind::RpcEffectfulTransactionStatusGet,
+            Self::TransitionFrontierNotify { .. } => {
+                ActionKind::RpcEffectfulTransitionFrontierNotify
+            }
         }
     }
 }
@@ -1224,6 +1354,24 @@ impl ActionKindGet for P2pDisconnectionAction {
     }
 }
 
+impl ActionKindGet for P2pBanAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Add { .. } => ActionKind::P2pBanAdd,
+            Self::Remove { .. } => ActionKind::P2pBanRemove,
+            Self::PruneExpired => ActionKind::P2pBanPruneExpired,
+        }
+    }
+}
+
+impl ActionKindGet for P2pPeerStoreAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Save => ActionKind::P2pPeerStoreSave,
+        }
+    }
+}
+
 impl ActionKindGet for P2pIdentifyAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -1255,6 +1403,7 @@ impl ActionKindGet for P2pPeerAction {
             Self::Discovered { .. } => ActionKind::P2pPeerDiscovered,
             Self::Ready { .. } => ActionKind::P2pPeerReady,
             Self::BestTipUpdate { .. } => ActionKind::P2pPeerBestTipUpdate,
+            Self::RttUpdate { .. } => ActionKind::P2pPeerRttUpdate,
             Self::Remove { .. } => ActionKind::P2pPeerRemove,
         }
     }
@@ -1272,6 +1421,9 @@ impl ActionKindGet for P2pNetworkAction {
             Self::Kad(a) => a.kind(),
             Self::Pubsub(a) => a.kind(),
             Self::Rpc(a) => a.kind(),
+            Self::Bitswap(a) => a.kind(),
+            Self::NodeStatus(a) => a.kind(),
+            Self::Ping(a) => a.kind(),
         }
     }
 }
@@ -1314,6 +1466,22 @@ impl ActionKindGet for P2pDisconnectionEffectfulAction {
     }
 }
 
+impl ActionKindGet for P2pBanEffectfulAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Persist { .. } => ActionKind::P2pBanEffectfulPersist,
+        }
+    }
+}
+
+impl ActionKindGet for P2pPeerStoreEffectfulAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::Persist { .. } => ActionKind::P2pPeerStoreEffectfulPersist,
+        }
+    }
+}
+
 impl ActionKindGet for P2pNetworkEffectfulAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -1844,6 +2012,8 @@ impl ActionKindGet for P2pNetworkSchedulerAction {
             Self::SelectDone { .. } => ActionKind::P2pNetworkSchedulerSelectDone,
             Self::SelectError { .. } => ActionKind::P2pNetworkSchedulerSelectError,
             Self::YamuxDidInit { .. } => ActionKind::P2pNetworkSchedulerYamuxDidInit,
+            Self::QuicDidConnect { .. } => ActionKind::P2pNetworkSchedulerQuicDidConnect,
+            Self::QuicIncomingData { .. } => ActionKind::P2pNetworkSchedulerQuicIncomingData,
             Self::Disconnect { .. } => ActionKind::P2pNetworkSchedulerDisconnect,
             Self::Error { .. } => ActionKind::P2pNetworkSchedulerError,
             Self::Disconnected { .. } => ActionKind::P2pNetworkSchedulerDisconnected,
@@ -1940,11 +2110,13 @@ impl ActionKindGet for P2pNetworkPubsubAction {
                 ActionKind::P2pNetworkPubsubValidateIncomingMessages
             }
             Self::IncomingMessage { .. } => ActionKind::P2pNetworkPubsubIncomingMessage,
+            Self::RejectMessage { .. } => ActionKind::P2pNetworkPubsubRejectMessage,
             Self::IncomingMessageCleanup { .. } => {
                 ActionKind::P2pNetworkPubsubIncomingMessageCleanup
             }
             Self::Graft { .. } => ActionKind::P2pNetworkPubsubGraft,
             Self::Prune { .. } => ActionKind::P2pNetworkPubsubPrune,
+            Self::Heartbeat => ActionKind::P2pNetworkPubsubHeartbeat,
             Self::Broadcast { .. } => ActionKind::P2pNetworkPubsubBroadcast,
             Self::Sign { .. } => ActionKind::P2pNetworkPubsubSign,
             Self::SignError { .. } => ActionKind::P2pNetworkPubsubSignError,
@@ -1972,6 +2144,47 @@ impl ActionKindGet for P2pNetworkRpcAction {
     }
 }
 
+impl ActionKindGet for P2pNetworkBitswapAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::NewStream { .. } => ActionKind::P2pNetworkBitswapNewStream,
+            Self::IncomingData { .. } => ActionKind::P2pNetworkBitswapIncomingData,
+            Self::RemoteClose { .. } => ActionKind::P2pNetworkBitswapRemoteClose,
+            Self::AddResource { .. } => ActionKind::P2pNetworkBitswapAddResource,
+            Self::RemoveResource { .. } => ActionKind::P2pNetworkBitswapRemoveResource,
+            Self::Want { .. } => ActionKind::P2pNetworkBitswapWant,
+            Self::OutgoingMessage { .. } => ActionKind::P2pNetworkBitswapOutgoingMessage,
+            Self::OutgoingData { .. } => ActionKind::P2pNetworkBitswapOutgoingData,
+        }
+    }
+}
+
+impl ActionKindGet for P2pNetworkNodeStatusAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::NewStream { .. } => ActionKind::P2pNetworkNodeStatusNewStream,
+            Self::IncomingData { .. } => ActionKind::P2pNetworkNodeStatusIncomingData,
+            Self::RemoteClose { .. } => ActionKind::P2pNetworkNodeStatusRemoteClose,
+            Self::Respond { .. } => ActionKind::P2pNetworkNodeStatusRespond,
+            Self::Query { .. } => ActionKind::P2pNetworkNodeStatusQuery,
+            Self::QuerySuccess { .. } => ActionKind::P2pNetworkNodeStatusQuerySuccess,
+            Self::QueryError { .. } => ActionKind::P2pNetworkNodeStatusQueryError,
+        }
+    }
+}
+
+impl ActionKindGet for P2pNetworkPingAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::NewStream { .. } => ActionKind::P2pNetworkPingNewStream,
+            Self::IncomingData { .. } => ActionKind::P2pNetworkPingIncomingData,
+            Self::RemoteClose { .. } => ActionKind::P2pNetworkPingRemoteClose,
+            Self::Ping { .. } => ActionKind::P2pNetworkPingPing,
+            Self::Timeout { .. } => ActionKind::P2pNetworkPingTimeout,
+        }
+    }
+}
+
 impl ActionKindGet for P2pConnectionOutgoingEffectfulAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -2009,6 +2222,9 @@ impl ActionKindGet for P2pNetworkSchedulerEffectfulAction {
             Self::InterfaceDetected { .. } => {
                 ActionKind::P2pNetworkSchedulerEffectfulInterfaceDetected
             }
+            Self::QuicInterfaceDetected { .. } => {
+                ActionKind::P2pNetworkSchedulerEffectfulQuicInterfaceDetected
+            }
             Self::IncomingConnectionIsReady { .. } => {
                 ActionKind::P2pNetworkSchedulerEffectfulIncomingConnectionIsReady
             }
@@ -2023,6 +2239,7 @@ impl ActionKindGet for P2pNetworkSchedulerEffectfulAction {
                 ActionKind::P2pNetworkSchedulerEffectfulIncomingDataIsReady
             }
             Self::NoiseSelectDone { .. } => ActionKind::P2pNetworkSchedulerEffectfulNoiseSelectDone,
+            Self::QuicSend { .. } => ActionKind::P2pNetworkSchedulerEffectfulQuicSend,
             Self::Disconnect { .. } => ActionKind::P2pNetworkSchedulerEffectfulDisconnect,
         }
     }
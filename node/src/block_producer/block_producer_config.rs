@@ -1,11 +1,17 @@
 use mina_p2p_messages::v2::{NonZeroCurvePoint, ProtocolVersionStableV2};
 use serde::{Deserialize, Serialize};
 
+use super::TransactionSelectionPolicyConfig;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BlockProducerConfig {
     pub pub_key: NonZeroCurvePoint,
     pub custom_coinbase_receiver: Option<NonZeroCurvePoint>,
     pub proposed_protocol_version: Option<ProtocolVersionStableV2>,
+    /// Policies applied, in order, when picking transactions for the block.
+    /// Highest fee first if empty.
+    #[serde(default)]
+    pub transaction_selection: Vec<TransactionSelectionPolicyConfig>,
 }
 
 impl BlockProducerConfig {
@@ -14,6 +20,7 @@ impl BlockProducerConfig {
             pub_key,
             custom_coinbase_receiver: None,
             proposed_protocol_version: None,
+            transaction_selection: Vec::new(),
         }
     }
 
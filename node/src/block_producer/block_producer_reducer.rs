@@ -417,9 +417,16 @@ impl BlockProducerEnabled {
         let vrf_truncated_output: v2::ConsensusVrfOutputTruncatedStableV1 =
             (*won_slot.vrf_output).clone().into();
         let vrf_hash = won_slot.vrf_output.hash();
-        let block_creator = self.config.pub_key.clone();
-        let coinbase_receiver = self.config.coinbase_receiver().clone();
-        let proposed_protocol_version_opt = self.config.proposed_protocol_version.clone();
+        let Some(config) = self.config(&won_slot.producer) else {
+            bug_condition!(
+                "Invalid state for `BlockProducerAction::BlockUnprovenBuild`: no config for the won slot producer {}",
+                won_slot.producer
+            );
+            return;
+        };
+        let block_creator = config.pub_key.clone();
+        let coinbase_receiver = config.coinbase_receiver().clone();
+        let proposed_protocol_version_opt = config.proposed_protocol_version.clone();
 
         let ledger_proof_statement = ledger_proof_statement_from_emitted_proof(
             emitted_ledger_proof.as_deref(),
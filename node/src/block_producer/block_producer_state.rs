@@ -20,7 +20,8 @@ pub struct BlockProducerState(Option<BlockProducerEnabled>);
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BlockProducerEnabled {
-    pub config: BlockProducerConfig,
+    /// Configs of the keys we produce blocks with.
+    pub configs: Vec<BlockProducerConfig>,
     pub vrf_evaluator: BlockProducerVrfEvaluatorState,
     pub current: BlockProducerCurrentState,
     /// Blocks that were injected into transition frontier, but hasn't
@@ -143,9 +144,12 @@ pub enum BlockProducerWonSlotDiscardReason {
 }
 
 impl BlockProducerState {
-    pub fn new(now: redux::Timestamp, config: Option<BlockProducerConfig>) -> Self {
-        Self(config.map(|config| BlockProducerEnabled {
-            config: config.clone(),
+    pub fn new(now: redux::Timestamp, configs: Vec<BlockProducerConfig>) -> Self {
+        if configs.is_empty() {
+            return Self(None);
+        }
+        Self(Some(BlockProducerEnabled {
+            configs,
             vrf_evaluator: BlockProducerVrfEvaluatorState::new(now),
             current: BlockProducerCurrentState::Idle { time: now },
             injected_blocks: Default::default(),
@@ -171,18 +175,23 @@ impl BlockProducerState {
         self.0.is_some()
     }
 
-    pub fn config(&self) -> Option<&BlockProducerConfig> {
-        self.with(None, |this| Some(&this.config))
+    pub fn configs(&self) -> &[BlockProducerConfig] {
+        self.with(&[], |this| &this.configs)
+    }
+
+    /// Config of the block producer key `producer`, if it's ours.
+    pub fn config(&self, producer: &v2::NonZeroCurvePoint) -> Option<&BlockProducerConfig> {
+        self.with(None, |this| this.config(producer))
     }
 
     pub fn is_me(&self, producer: &v2::NonZeroCurvePoint) -> bool {
-        self.with(false, |this| producer == &this.config.pub_key)
+        self.config(producer).is_some()
     }
 
     /// Checks if the block was produced by us recently.
     pub fn is_produced_by_me(&self, block: &ArcBlockWithHash) -> bool {
         self.with(false, |this| {
-            block.producer() == &this.config.pub_key && this.injected_blocks.contains(block.hash())
+            this.config(block.producer()).is_some() && this.injected_blocks.contains(block.hash())
         })
     }
 
@@ -215,14 +224,24 @@ impl BlockProducerState {
         self.with(None, |this| Some(&this.vrf_evaluator))
     }
 
-    pub fn vrf_evaluator_with_config(
+    /// Vrf evaluator along with the keys it evaluates slots for.
+    pub fn vrf_evaluator_with_producers(
         &self,
-    ) -> Option<(&BlockProducerVrfEvaluatorState, &BlockProducerConfig)> {
-        self.with(None, |this| Some((&this.vrf_evaluator, &this.config)))
+    ) -> Option<(&BlockProducerVrfEvaluatorState, BTreeSet<AccountPublicKey>)> {
+        self.with(None, |this| {
+            let producers = this
+                .configs
+                .iter()
+                .map(|config| config.pub_key.clone().into())
+                .collect();
+            Some((&this.vrf_evaluator, producers))
+        })
     }
 
-    /// If we need to construct delegator table, get it's inputs.
-    pub fn vrf_delegator_table_inputs(&self) -> Option<(&v2::LedgerHash, &AccountPublicKey)> {
+    /// If we need to construct delegator tables, get their inputs.
+    pub fn vrf_delegator_table_inputs(
+        &self,
+    ) -> Option<(&v2::LedgerHash, &BTreeSet<AccountPublicKey>)> {
         self.vrf_evaluator()?.vrf_delegator_table_inputs()
     }
 
@@ -231,6 +250,14 @@ impl BlockProducerState {
     }
 }
 
+impl BlockProducerEnabled {
+    pub fn config(&self, producer: &v2::NonZeroCurvePoint) -> Option<&BlockProducerConfig> {
+        self.configs
+            .iter()
+            .find(|config| &config.pub_key == producer)
+    }
+}
+
 impl BlockProducerCurrentState {
     pub fn won_slot_should_search(&self) -> bool {
         match self {
@@ -0,0 +1,379 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    str::FromStr,
+};
+
+use ledger::scan_state::transaction_logic::{valid, GenericCommand};
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+
+/// Decides which of the includable pool transactions go into the produced
+/// block, and in which order.
+pub trait TransactionSelectionPolicy {
+    /// Filters and reorders `candidates`. They are given ordered by fee,
+    /// with the transactions of each sender in nonce order, which must be
+    /// kept in the result.
+    fn select(&self, candidates: Vec<TransactionCandidate>) -> Vec<TransactionCandidate>;
+}
+
+#[derive(Debug, Clone)]
+pub struct TransactionCandidate {
+    pub command: valid::UserCommand,
+    pub fee_payer: AccountPublicKey,
+    pub fee: u64,
+    pub is_zkapp_command: bool,
+    /// Time when the transaction was received, if it's known.
+    pub received_at: Option<redux::Timestamp>,
+}
+
+impl TransactionCandidate {
+    pub fn new(command: valid::UserCommand, received_at: Option<redux::Timestamp>) -> Self {
+        Self {
+            fee_payer: command.fee_payer().public_key.into(),
+            fee: command.fee().as_u64(),
+            is_zkapp_command: matches!(command, valid::UserCommand::ZkAppCommand(_)),
+            command,
+            received_at,
+        }
+    }
+}
+
+/// Built-in transaction selection policies. Policies are applied one after
+/// another, starting from the pool's max fee order.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum TransactionSelectionPolicyConfig {
+    /// Highest fee first. This is the order of the pool, so it's only
+    /// needed to undo a reordering by an earlier policy.
+    MaxFee,
+    /// Earliest received first.
+    Fifo,
+    /// Only include transactions of these fee payers.
+    SenderAllowlist(BTreeSet<AccountPublicKey>),
+    /// Never include transactions of these fee payers.
+    SenderDenylist(BTreeSet<AccountPublicKey>),
+    /// Include at most this many zkapp commands.
+    MaxZkappCommands(usize),
+    /// Skip transactions with a fee lower than this, in nanomina.
+    MinFee(u64),
+}
+
+impl TransactionSelectionPolicy for TransactionSelectionPolicyConfig {
+    fn select(&self, candidates: Vec<TransactionCandidate>) -> Vec<TransactionCandidate> {
+        match self {
+            Self::MaxFee => sort_in_nonce_order(candidates, |c| Reverse(c.fee)),
+            // Unknown time sorts last.
+            Self::Fifo => {
+                sort_in_nonce_order(candidates, |c| (c.received_at.is_none(), c.received_at))
+            }
+            Self::SenderAllowlist(senders) => {
+                retain_in_nonce_order(candidates, |c| senders.contains(&c.fee_payer))
+            }
+            Self::SenderDenylist(senders) => {
+                retain_in_nonce_order(candidates, |c| !senders.contains(&c.fee_payer))
+            }
+            Self::MaxZkappCommands(max) => {
+                let mut count = 0;
+                retain_in_nonce_order(candidates, |c| {
+                    if !c.is_zkapp_command {
+                        return true;
+                    }
+                    count += 1;
+                    count <= *max
+                })
+            }
+            Self::MinFee(min_fee) => retain_in_nonce_order(candidates, |c| c.fee >= *min_fee),
+        }
+    }
+}
+
+impl TransactionSelectionPolicy for [TransactionSelectionPolicyConfig] {
+    fn select(&self, candidates: Vec<TransactionCandidate>) -> Vec<TransactionCandidate> {
+        self.iter()
+            .fold(candidates, |candidates, policy| policy.select(candidates))
+    }
+}
+
+/// Stable sorts the candidates by `key`. A sender's transaction is never
+/// ordered before its earlier ones, it gets at least the key of those.
+fn sort_in_nonce_order<K: Ord + Copy>(
+    candidates: Vec<TransactionCandidate>,
+    mut key: impl FnMut(&TransactionCandidate) -> K,
+) -> Vec<TransactionCandidate> {
+    let mut latest_by_sender = BTreeMap::new();
+    let mut candidates = candidates
+        .into_iter()
+        .map(|candidate| {
+            let key = key(&candidate);
+            let key = *latest_by_sender
+                .entry(candidate.fee_payer.clone())
+                .and_modify(|latest: &mut K| *latest = key.max(*latest))
+                .or_insert(key);
+            (key, candidate)
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|(key, _)| *key);
+    candidates
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Keeps the candidates for which `f` returns true. Once a transaction of
+/// a sender is skipped, its later transactions are skipped too, as they
+/// would have a nonce gap.
+fn retain_in_nonce_order(
+    candidates: Vec<TransactionCandidate>,
+    mut f: impl FnMut(&TransactionCandidate) -> bool,
+) -> Vec<TransactionCandidate> {
+    let mut skipped_senders = BTreeSet::new();
+    candidates
+        .into_iter()
+        .filter(|candidate| {
+            if skipped_senders.contains(&candidate.fee_payer) {
+                return false;
+            }
+            if f(candidate) {
+                return true;
+            }
+            skipped_senders.insert(candidate.fee_payer.clone());
+            false
+        })
+        .collect()
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("invalid transaction selection policy `{0}`, expected one of: max-fee, fifo, allow=<PK>[,<PK>..], deny=<PK>[,<PK>..], max-zkapp-commands=<N>, min-fee=<NANOMINA>")]
+pub struct TransactionSelectionPolicyParseError(String);
+
+impl FromStr for TransactionSelectionPolicyConfig {
+    type Err = TransactionSelectionPolicyParseError;
+
+    /// Parses `max-fee`, `fifo`, `allow=<PK>[,<PK>..]`, `deny=<PK>[,<PK>..]`,
+    /// `max-zkapp-commands=<N>` or `min-fee=<NANOMINA>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || TransactionSelectionPolicyParseError(s.to_owned());
+        let senders = |v: &str| {
+            v.split(',')
+                .map(|key| key.trim().parse().map_err(|_| err()))
+                .collect::<Result<BTreeSet<_>, _>>()
+        };
+        let (name, value) = match s.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (s, None),
+        };
+        Ok(match (name.trim(), value) {
+            ("max-fee", None) => Self::MaxFee,
+            ("fifo", None) => Self::Fifo,
+            ("allow", Some(v)) => Self::SenderAllowlist(senders(v)?),
+            ("deny", Some(v)) => Self::SenderDenylist(senders(v)?),
+            ("max-zkapp-commands", Some(v)) => {
+                Self::MaxZkappCommands(v.trim().parse().map_err(|_| err())?)
+            }
+            ("min-fee", Some(v)) => Self::MinFee(v.trim().parse().map_err(|_| err())?),
+            _ => return Err(err()),
+        })
+    }
+}
+
+impl fmt::Display for TransactionSelectionPolicyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |senders: &BTreeSet<AccountPublicKey>| {
+            senders
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        match self {
+            Self::MaxFee => write!(f, "max-fee"),
+            Self::Fifo => write!(f, "fifo"),
+            Self::SenderAllowlist(senders) => write!(f, "allow={}", join(senders)),
+            Self::SenderDenylist(senders) => write!(f, "deny={}", join(senders)),
+            Self::MaxZkappCommands(max) => write!(f, "max-zkapp-commands={max}"),
+            Self::MinFee(fee) => write!(f, "min-fee={fee}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::scan_state::{
+        currency::{Amount, Fee, Nonce},
+        transaction_logic::{
+            signed_command::{Body, PaymentPayload, SignedCommand, SignedCommandPayload},
+            Memo,
+        },
+    };
+
+    use crate::account::AccountSecretKey;
+
+    use super::*;
+
+    fn candidate(sender: u64, nonce: u32, fee: u64, received_at: u64) -> TransactionCandidate {
+        let fee_payer = AccountSecretKey::deterministic(sender).public_key_compressed();
+        let payload = SignedCommandPayload::create(
+            Fee::from_u64(fee),
+            fee_payer.clone(),
+            Nonce::from_u32(nonce),
+            None,
+            Memo::empty(),
+            Body::Payment(PaymentPayload {
+                receiver_pk: fee_payer.clone(),
+                amount: Amount::from_u64(1),
+            }),
+        );
+        let command = SignedCommand {
+            payload,
+            signer: fee_payer,
+            signature: mina_signer::Signature::dummy(),
+        };
+        TransactionCandidate::new(
+            valid::UserCommand::SignedCommand(Box::new(command)),
+            Some(redux::Timestamp::new(received_at)),
+        )
+    }
+
+    fn zkapp(mut candidate: TransactionCandidate) -> TransactionCandidate {
+        candidate.is_zkapp_command = true;
+        candidate
+    }
+
+    /// `(sender, nonce)` of the candidates.
+    fn ids(candidates: &[TransactionCandidate]) -> Vec<(u64, u32)> {
+        candidates
+            .iter()
+            .map(|c| {
+                let sender = (0..3)
+                    .find(|i| AccountSecretKey::deterministic(*i).public_key() == c.fee_payer)
+                    .unwrap();
+                (sender, c.command.nonce().unwrap().as_u32())
+            })
+            .collect()
+    }
+
+    fn sender(i: u64) -> AccountPublicKey {
+        AccountSecretKey::deterministic(i).public_key()
+    }
+
+    /// Pool order, by fee, with each sender's transactions in nonce order.
+    fn pool() -> Vec<TransactionCandidate> {
+        vec![
+            candidate(0, 0, 50, 30),
+            candidate(1, 0, 40, 10),
+            candidate(0, 1, 30, 5),
+            candidate(2, 0, 20, 20),
+            candidate(1, 1, 10, 40),
+        ]
+    }
+
+    #[test]
+    fn fifo_keeps_sender_nonce_order() {
+        let selected = TransactionSelectionPolicyConfig::Fifo.select(pool());
+        // (0, 1) was received first, but (0, 0) must precede it.
+        assert_eq!(ids(&selected), [(1, 0), (2, 0), (0, 0), (0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn fifo_sorts_unknown_receive_time_last() {
+        let mut candidates = pool();
+        candidates[1].received_at = None;
+        let selected = TransactionSelectionPolicyConfig::Fifo.select(candidates);
+        assert_eq!(ids(&selected), [(2, 0), (0, 0), (0, 1), (1, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn max_fee_restores_fee_order() {
+        let policies = [
+            TransactionSelectionPolicyConfig::Fifo,
+            TransactionSelectionPolicyConfig::MaxFee,
+        ];
+        assert_eq!(ids(&policies[..].select(pool())), ids(&pool()));
+
+        // Sender's later transaction with a higher fee stays after the
+        // earlier one.
+        let candidates = vec![candidate(0, 0, 10, 0), candidate(0, 1, 50, 0)];
+        let selected = TransactionSelectionPolicyConfig::MaxFee.select(candidates);
+        assert_eq!(ids(&selected), [(0, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn min_fee_skips_later_nonces_of_sender() {
+        let candidates = vec![
+            candidate(0, 0, 50, 0),
+            candidate(1, 0, 5, 0),
+            candidate(0, 1, 30, 0),
+            candidate(1, 1, 40, 0),
+        ];
+        let selected = TransactionSelectionPolicyConfig::MinFee(10).select(candidates);
+        assert_eq!(ids(&selected), [(0, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn sender_allowlist_and_denylist() {
+        let allow = TransactionSelectionPolicyConfig::SenderAllowlist([sender(0)].into());
+        assert_eq!(ids(&allow.select(pool())), [(0, 0), (0, 1)]);
+
+        let deny = TransactionSelectionPolicyConfig::SenderDenylist([sender(0)].into());
+        assert_eq!(ids(&deny.select(pool())), [(1, 0), (2, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn max_zkapp_commands_counts_only_zkapp_commands() {
+        let candidates = vec![
+            zkapp(candidate(0, 0, 50, 0)),
+            candidate(1, 0, 40, 0),
+            zkapp(candidate(2, 0, 30, 0)),
+            candidate(0, 1, 20, 0),
+            zkapp(candidate(1, 1, 10, 0)),
+        ];
+        let selected = TransactionSelectionPolicyConfig::MaxZkappCommands(1).select(candidates);
+        assert_eq!(ids(&selected), [(0, 0), (1, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn policies_are_applied_in_order() {
+        let policies = [
+            TransactionSelectionPolicyConfig::MinFee(20),
+            TransactionSelectionPolicyConfig::Fifo,
+            TransactionSelectionPolicyConfig::SenderDenylist([sender(2)].into()),
+        ];
+        assert_eq!(ids(&policies[..].select(pool())), [(1, 0), (0, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn transaction_selection_policy_parse_display_roundtrip() {
+        let senders = (0..2)
+            .map(|i| AccountSecretKey::deterministic(i).public_key())
+            .collect::<BTreeSet<_>>();
+        let policies = [
+            TransactionSelectionPolicyConfig::MaxFee,
+            TransactionSelectionPolicyConfig::Fifo,
+            TransactionSelectionPolicyConfig::SenderAllowlist(senders.clone()),
+            TransactionSelectionPolicyConfig::SenderDenylist(senders),
+            TransactionSelectionPolicyConfig::MaxZkappCommands(8),
+            TransactionSelectionPolicyConfig::MinFee(10_000_000),
+        ];
+        for policy in policies {
+            assert_eq!(
+                policy
+                    .to_string()
+                    .parse::<TransactionSelectionPolicyConfig>()
+                    .unwrap(),
+                policy
+            );
+        }
+
+        assert!("min-fee"
+            .parse::<TransactionSelectionPolicyConfig>()
+            .is_err());
+        assert!("fifo=1"
+            .parse::<TransactionSelectionPolicyConfig>()
+            .is_err());
+        assert!("allow=foo"
+            .parse::<TransactionSelectionPolicyConfig>()
+            .is_err());
+    }
+}
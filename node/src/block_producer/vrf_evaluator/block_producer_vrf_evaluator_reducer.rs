@@ -204,14 +204,14 @@ impl BlockProducerVrfEvaluatorState {
                 state.set_epoch_context();
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
-                let vrf_evaluator_state = state.block_producer.vrf_evaluator_with_config();
+                let vrf_evaluator_state = state.block_producer.vrf_evaluator_with_producers();
 
-                if let Some((vrf_evaluator_state, config)) = vrf_evaluator_state {
+                if let Some((vrf_evaluator_state, producers)) = vrf_evaluator_state {
                     if let Some(epoch_data) = vrf_evaluator_state.epoch_context().get_epoch_data() {
                         dispatcher.push(
                             BlockProducerVrfEvaluatorAction::InitializeEpochEvaluation {
                                 staking_epoch_data: epoch_data,
-                                producer: config.pub_key.clone().into(),
+                                producers,
                                 best_tip_global_slot: *best_tip_global_slot,
                                 best_tip_epoch,
                                 best_tip_slot: *best_tip_slot,
@@ -233,7 +233,7 @@ impl BlockProducerVrfEvaluatorState {
                 best_tip_global_slot,
                 next_epoch_first_slot,
                 staking_epoch_data,
-                producer,
+                producers,
             } => {
                 state.status = BlockProducerVrfEvaluatorStatus::ReadyToEvaluate {
                     time: meta.time(),
@@ -245,7 +245,7 @@ impl BlockProducerVrfEvaluatorState {
                     best_tip_global_slot: *best_tip_global_slot,
                     next_epoch_first_slot: *next_epoch_first_slot,
                     staking_epoch_data: staking_epoch_data.clone(),
-                    producer: producer.clone(),
+                    producers: producers.clone(),
                 };
 
                 let dispatcher = state_context.into_dispatcher();
@@ -258,7 +258,7 @@ impl BlockProducerVrfEvaluatorState {
                     best_tip_global_slot,
                     next_epoch_first_slot,
                     staking_epoch_data,
-                    producer,
+                    producers,
                     time: _,
                     is_current_epoch_evaluated: _,
                     is_next_epoch_evaluated: _,
@@ -274,23 +274,23 @@ impl BlockProducerVrfEvaluatorState {
                     best_tip_global_slot: *best_tip_global_slot,
                     next_epoch_first_slot: *next_epoch_first_slot,
                     staking_epoch_data: staking_epoch_data.clone(),
-                    producer: producer.clone(),
+                    producers: producers.clone(),
                 };
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
-                let (staking_ledger_hash, producer) =
+                let (staking_ledger_hash, producers) =
                     match state.block_producer.vrf_delegator_table_inputs() {
                         Some((v1, v2)) => (v1.clone(), v2.clone()),
                         None => return,
                     };
 
                 dispatcher.push(LedgerReadAction::Init {
-                    request: LedgerReadRequest::DelegatorTable(staking_ledger_hash, producer),
+                    request: LedgerReadRequest::DelegatorTable(staking_ledger_hash, producers),
                     callback: LedgerReadInitCallback::None,
                 })
             }
             BlockProducerVrfEvaluatorAction::FinalizeDelegatorTableConstruction {
-                delegator_table,
+                delegator_tables,
             } => {
                 let BlockProducerVrfEvaluatorStatus::EpochDelegatorTablePending {
                     best_tip_epoch,
@@ -298,7 +298,7 @@ impl BlockProducerVrfEvaluatorState {
                     best_tip_global_slot,
                     next_epoch_first_slot,
                     staking_epoch_data,
-                    producer,
+                    producers,
                     time: _,
                     staking_epoch_ledger_hash: _,
                 } = &state.status
@@ -307,14 +307,20 @@ impl BlockProducerVrfEvaluatorState {
                     return;
                 };
 
-                openmina_core::log::warn!(
-                    meta.time();
-                    kind = "BlockProducerVrfEvaluatorAction::FinalizeDelegatorTableConstruction",
-                    message = "Empty delegator table, account may not exist yet in the staking ledger"
-                );
+                for producer in producers
+                    .iter()
+                    .filter(|producer| !delegator_tables.contains_key(*producer))
+                {
+                    openmina_core::log::warn!(
+                        meta.time();
+                        kind = "BlockProducerVrfEvaluatorAction::FinalizeDelegatorTableConstruction",
+                        message = "Empty delegator table, account may not exist yet in the staking ledger",
+                        producer = producer.to_string()
+                    );
+                }
 
                 let mut staking_epoch_data = staking_epoch_data.clone();
-                staking_epoch_data.delegator_table = delegator_table.clone();
+                staking_epoch_data.delegator_tables = delegator_tables.clone();
 
                 state.status = BlockProducerVrfEvaluatorStatus::EpochDelegatorTableSuccess {
                     time: meta.time(),
@@ -324,7 +330,7 @@ impl BlockProducerVrfEvaluatorState {
                     best_tip_global_slot: *best_tip_global_slot,
                     next_epoch_first_slot: *next_epoch_first_slot,
                     staking_epoch_data: staking_epoch_data.clone(),
-                    producer: producer.clone(),
+                    producers: producers.clone(),
                 };
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
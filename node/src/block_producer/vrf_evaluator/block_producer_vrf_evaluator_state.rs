@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
 use mina_p2p_messages::v2;
@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{account::AccountPublicKey, block_producer::BlockProducerWonSlot};
 
-use super::{DelegatorTable, VrfEvaluatorInput, VrfWonSlotWithHash};
+use super::{DelegatorTables, VrfEvaluatorInput, VrfWonSlotWithHash};
 
 pub const SLOTS_PER_EPOCH: u32 = 7140;
 /// Vrf evaluator sub-state
@@ -333,7 +333,7 @@ impl BlockProducerVrfEvaluatorState {
         if let Some(pending_evaluation) = self.current_evaluation() {
             Some(VrfEvaluatorInput::new(
                 pending_evaluation.epoch_data.seed,
-                pending_evaluation.epoch_data.delegator_table,
+                pending_evaluation.epoch_data.delegator_tables,
                 pending_evaluation
                     .latest_evaluated_slot
                     .checked_add(1)
@@ -358,14 +358,32 @@ impl BlockProducerVrfEvaluatorState {
             .retain(|global_slot, _| cutoff_slot < *global_slot);
     }
 
-    /// If we need to construct delegator table, get it's inputs.
-    pub fn vrf_delegator_table_inputs(&self) -> Option<(&v2::LedgerHash, &AccountPublicKey)> {
+    /// Won slots of the `epoch` and of the next one, in slot order.
+    pub fn scheduled_won_slots(
+        &self,
+        epoch: u32,
+        genesis_timestamp: redux::Timestamp,
+    ) -> impl Iterator<Item = BlockProducerWonSlot> + '_ {
+        self.won_slots
+            .values()
+            .map(move |won_slot| {
+                BlockProducerWonSlot::from_vrf_won_slot(won_slot, genesis_timestamp)
+            })
+            .filter(move |won_slot| {
+                won_slot.epoch() == epoch || Some(won_slot.epoch()) == epoch.checked_add(1)
+            })
+    }
+
+    /// If we need to construct delegator tables, get their inputs.
+    pub fn vrf_delegator_table_inputs(
+        &self,
+    ) -> Option<(&v2::LedgerHash, &BTreeSet<AccountPublicKey>)> {
         match &self.status {
             BlockProducerVrfEvaluatorStatus::EpochDelegatorTablePending {
                 staking_epoch_ledger_hash,
-                producer,
+                producers,
                 ..
-            } => Some((staking_epoch_ledger_hash, producer)),
+            } => Some((staking_epoch_ledger_hash, producers)),
             _ => None,
         }
     }
@@ -375,7 +393,7 @@ impl BlockProducerVrfEvaluatorState {
 pub struct EpochData {
     pub seed: v2::EpochSeed,
     pub ledger: v2::LedgerHash,
-    pub delegator_table: Arc<DelegatorTable>,
+    pub delegator_tables: Arc<DelegatorTables>,
     pub total_currency: u64,
 }
 
@@ -385,7 +403,7 @@ impl EpochData {
             seed,
             ledger,
             total_currency,
-            delegator_table: Default::default(),
+            delegator_tables: Default::default(),
         }
     }
 }
@@ -397,7 +415,7 @@ impl From<v2::ConsensusProofOfStakeDataEpochDataStakingValueVersionedValueStable
         Self {
             seed: value.seed,
             ledger: value.ledger.hash,
-            delegator_table: Default::default(),
+            delegator_tables: Default::default(),
             total_currency: value.ledger.total_currency.as_u64(),
         }
     }
@@ -408,7 +426,7 @@ impl From<v2::ConsensusProofOfStakeDataEpochDataNextValueVersionedValueStableV1>
         Self {
             seed: value.seed,
             ledger: value.ledger.hash,
-            delegator_table: Default::default(),
+            delegator_tables: Default::default(),
             total_currency: value.ledger.total_currency.as_u64(),
         }
     }
@@ -440,7 +458,7 @@ pub enum BlockProducerVrfEvaluatorStatus {
         best_tip_global_slot: u32,
         next_epoch_first_slot: u32,
         staking_epoch_data: EpochData,
-        producer: AccountPublicKey,
+        producers: BTreeSet<AccountPublicKey>,
     },
     /// Waiting for delegator table building
     EpochDelegatorTablePending {
@@ -452,7 +470,7 @@ pub enum BlockProducerVrfEvaluatorStatus {
         best_tip_global_slot: u32,
         next_epoch_first_slot: u32,
         staking_epoch_data: EpochData,
-        producer: AccountPublicKey,
+        producers: BTreeSet<AccountPublicKey>,
     },
     /// Delegator table built successfully
     EpochDelegatorTableSuccess {
@@ -464,7 +482,7 @@ pub enum BlockProducerVrfEvaluatorStatus {
         best_tip_global_slot: u32,
         next_epoch_first_slot: u32,
         staking_epoch_data: EpochData,
-        producer: AccountPublicKey,
+        producers: BTreeSet<AccountPublicKey>,
     },
     InitialSlotSelection {
         time: redux::Timestamp,
@@ -943,6 +961,26 @@ mod test {
         })
     }
 
+    #[test]
+    fn test_scheduled_won_slots() {
+        let mut vrf_evaluator_state = GENESIS_EPOCH_FIRST_SLOT.lock().unwrap().clone();
+        vrf_evaluator_state.won_slots = generate_slots(7139, 7141)
+            .chain(generate_slots(14279, 14281))
+            .chain(generate_slots(21419, 21421))
+            .collect();
+
+        let scheduled = |epoch| {
+            vrf_evaluator_state
+                .scheduled_won_slots(epoch, redux::Timestamp::ZERO)
+                .map(|won_slot| won_slot.global_slot())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(scheduled(0), vec![7139, 7140, 7141, 14279]);
+        assert_eq!(scheduled(1), vec![7140, 7141, 14279, 14280, 14281, 21419]);
+        assert_eq!(scheduled(3), vec![21420, 21421]);
+        assert_eq!(scheduled(4), Vec::<u32>::new());
+    }
+
     #[test]
     fn test_cleanup_old_won_slots() {
         // arbitrary, need it just to fill it with some slots
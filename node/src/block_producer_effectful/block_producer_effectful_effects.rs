@@ -40,7 +40,7 @@ pub fn block_producer_effects<S: crate::Service>(
             let Some((won_slot, pred_block, producer, coinbase_receiver)) = None.or_else(|| {
                 let pred_block = state.block_producer.current_parent_chain()?.last()?;
                 let won_slot = state.block_producer.current_won_slot()?;
-                let config = state.block_producer.config()?;
+                let config = state.block_producer.config(&won_slot.producer)?;
                 Some((
                     won_slot,
                     pred_block,
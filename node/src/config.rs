@@ -23,7 +23,7 @@ pub struct Config {
     pub snark: SnarkConfig,
     pub p2p: P2pConfig,
     pub transition_frontier: TransitionFrontierConfig,
-    pub block_producer: Option<BlockProducerConfig>,
+    pub block_producers: Vec<BlockProducerConfig>,
     pub global: GlobalConfig,
     pub tx_pool: ledger::transaction_pool::Config,
 }
@@ -3,6 +3,7 @@ use rand::prelude::*;
 
 use crate::block_producer::BlockProducerAction;
 use crate::block_producer_effectful::block_producer_effects;
+use crate::event_sink::event_sink_effects;
 use crate::event_source::event_source_effects;
 use crate::external_snark_worker_effectful::external_snark_worker_effectful_effects;
 use crate::ledger::read::LedgerReadAction;
@@ -15,7 +16,7 @@ use crate::snark_pool::candidate::SnarkPoolCandidateAction;
 use crate::snark_pool::{snark_pool_effects, SnarkPoolAction};
 use crate::transaction_pool::candidate::TransactionPoolCandidateAction;
 use crate::transition_frontier::genesis::TransitionFrontierGenesisAction;
-use crate::transition_frontier::transition_frontier_effects;
+use crate::transition_frontier::{transition_frontier_effects, TransitionFrontierAction};
 use crate::{
     p2p_ready, Action, ActionWithMeta, ExternalSnarkWorkerAction, Service, Store,
     TransactionPoolAction,
@@ -33,11 +34,13 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
     }
 
     logger_effects(store, meta.clone().with_action(&action));
+    event_sink_effects(store, meta.clone().with_action(&action));
     match action {
         // Following action gets dispatched very often, so ideally this
         // effect execution should be as light as possible.
         Action::CheckTimeouts(_) => {
             // TODO(binier): create init action and dispatch these there.
+            store.dispatch(TransitionFrontierAction::RestoreInit);
             store.dispatch(TransitionFrontierGenesisAction::LedgerLoadInit);
             store.dispatch(ExternalSnarkWorkerAction::Start);
 
@@ -0,0 +1,133 @@
+use std::collections::BTreeSet;
+
+use mina_p2p_messages::v2;
+
+use crate::block_producer::BlockProducerAction;
+use crate::snark_pool::SnarkPoolAction;
+use crate::transition_frontier::sync::TransitionFrontierSyncAction;
+use crate::{Action, ActionWithMetaRef, Service, State, Store, TransitionFrontierAction};
+
+use super::{NodeEvent, NodeEventKind, NodeEventTransactionStatus};
+
+pub fn event_sink_effects<S: Service>(store: &mut Store<S>, action: ActionWithMetaRef<'_>) {
+    if !store.service.event_sink_enabled() {
+        return;
+    }
+    let (action, meta) = action.split();
+    let state = store.state.get();
+
+    let events = match action {
+        Action::TransitionFrontier(TransitionFrontierAction::Sync(
+            TransitionFrontierSyncAction::BlocksNextApplySuccess { hash, .. },
+        )) => state
+            .transition_frontier
+            .sync
+            .block_state(hash)
+            .and_then(|s| s.block())
+            .map(NodeEventKind::block_applied)
+            .into_iter()
+            .collect(),
+        Action::TransitionFrontier(TransitionFrontierAction::Synced { .. }) => {
+            best_tip_changed_events(state)
+        }
+        Action::SnarkPool(SnarkPoolAction::WorkAdd { snark, .. }) => {
+            vec![NodeEventKind::SnarkWorkAdded {
+                job_id: snark.job_id(),
+                snarker: snark.snarker.clone(),
+                fee: snark.fee.as_u64(),
+            }]
+        }
+        Action::BlockProducer(BlockProducerAction::BlockProduced) => state
+            .block_producer
+            .produced_block()
+            .map(NodeEventKind::block_produced)
+            .into_iter()
+            .collect(),
+        Action::BlockProducer(BlockProducerAction::WonSlotDiscard { reason }) => state
+            .block_producer
+            .current_won_slot()
+            .map(|won_slot| NodeEventKind::SlotMissed {
+                global_slot: won_slot.global_slot.slot_number.as_u32(),
+                reason: format!("{reason:?}"),
+            })
+            .into_iter()
+            .collect(),
+        _ => return,
+    };
+
+    for kind in events {
+        store
+            .service
+            .event_sink_publish(NodeEvent::new(meta.time(), kind));
+    }
+}
+
+/// Best tip change, followed by the user commands which became part of the
+/// best chain with it.
+fn best_tip_changed_events(state: &State) -> Vec<NodeEventKind> {
+    let transition_frontier = &state.transition_frontier;
+    let Some(best_tip) = transition_frontier.best_tip() else {
+        return vec![];
+    };
+    let mut events = vec![NodeEventKind::BestTipChanged {
+        hash: best_tip.hash().clone(),
+        height: best_tip.height(),
+        global_slot: best_tip.global_slot(),
+        reorg: transition_frontier
+            .chain_diff
+            .as_ref()
+            .map_or(false, |diff| diff.reorg_best_tip),
+    }];
+
+    let Some(diff) = &transition_frontier.chain_diff else {
+        return events;
+    };
+    let mut new_commands = diff
+        .new_commands
+        .iter()
+        .filter_map(|cmd| {
+            v2::MinaBaseUserCommandStableV2::from(&cmd.data.forget_check())
+                .hash()
+                .ok()
+        })
+        .collect::<BTreeSet<_>>();
+
+    // New commands are in the blocks which were added on top of the common
+    // ancestor of the old and new best chains, so only the last few blocks
+    // need to be looked at.
+    let mut included = vec![];
+    for block in transition_frontier.best_chain.iter().rev() {
+        if new_commands.is_empty() {
+            break;
+        }
+        for cmd in block.commands_iter() {
+            let Ok(hash) = cmd.data.hash() else {
+                continue;
+            };
+            if !new_commands.remove(&hash) {
+                continue;
+            }
+            let status = match cmd.status {
+                v2::MinaBaseTransactionStatusStableV2::Applied => {
+                    NodeEventTransactionStatus::Applied
+                }
+                v2::MinaBaseTransactionStatusStableV2::Failed(_) => {
+                    NodeEventTransactionStatus::Failed
+                }
+            };
+            included.push(NodeEventKind::TransactionIncluded {
+                hash,
+                block_hash: block.hash().clone(),
+                block_height: block.height(),
+                status,
+            });
+        }
+    }
+    // Oldest blocks first.
+    included.sort_by_key(|event| match event {
+        NodeEventKind::TransactionIncluded { block_height, .. } => *block_height,
+        _ => 0,
+    });
+    events.extend(included);
+    events
+}
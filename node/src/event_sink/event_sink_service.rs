@@ -0,0 +1,11 @@
+use super::NodeEvent;
+
+pub trait EventSinkService: redux::Service {
+    /// Whether any event sink is configured. Events aren't built
+    /// otherwise.
+    fn event_sink_enabled(&self) -> bool;
+
+    /// Publish the event to the configured sinks. Delivery happens in the
+    /// background, failures are reported by the service itself.
+    fn event_sink_publish(&mut self, event: NodeEvent);
+}
@@ -0,0 +1,11 @@
+//! Events published to external tools, derived from the actions of the
+//! state machine.
+
+mod node_event;
+pub use node_event::*;
+
+mod event_sink_effects;
+pub use event_sink_effects::*;
+
+mod event_sink_service;
+pub use event_sink_service::*;
@@ -0,0 +1,192 @@
+use mina_p2p_messages::v2::{NonZeroCurvePoint, StateHash, TransactionHash};
+use openmina_core::{block::ArcBlockWithHash, snark::SnarkJobId};
+use serde::{Deserialize, Serialize};
+
+/// Version of the [`NodeEvent`] schema. Bumped on changes which aren't
+/// backwards compatible, new event kinds and new fields are not such.
+pub const NODE_EVENT_VERSION: u32 = 1;
+
+/// Event published to the event sinks.
+///
+/// Serialized as a flat JSON object, with the `kind` field telling which
+/// of the [`NodeEventKind`] variants it is, e.g.:
+///
+/// ```json
+/// {"version":1,"time":"2024-06-01T12:00:00Z","kind":"best_tip_changed","hash":"3N...","height":1234,"global_slot":5678,"reorg":false}
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NodeEvent {
+    pub version: u32,
+    /// Time of the action the event was derived from, in RFC 3339 format.
+    pub time: String,
+    #[serde(flatten)]
+    pub kind: NodeEventKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NodeEventKind {
+    /// Block was applied to the ledger, it's not necessarily part of the
+    /// best chain yet.
+    BlockApplied {
+        hash: StateHash,
+        height: u32,
+        global_slot: u32,
+    },
+    /// Transition frontier was updated with a new best tip.
+    BestTipChanged {
+        hash: StateHash,
+        height: u32,
+        global_slot: u32,
+        /// Whether blocks of the previous best chain were dropped.
+        reorg: bool,
+    },
+    /// User command was included in a block which became a part of the
+    /// best chain.
+    TransactionIncluded {
+        hash: TransactionHash,
+        block_hash: StateHash,
+        block_height: u32,
+        status: NodeEventTransactionStatus,
+    },
+    /// Snark work was added to the snark pool.
+    SnarkWorkAdded {
+        job_id: SnarkJobId,
+        snarker: NonZeroCurvePoint,
+        fee: u64,
+    },
+    /// Block was produced by this node.
+    BlockProduced {
+        hash: StateHash,
+        height: u32,
+        global_slot: u32,
+    },
+    /// Slot won by this node was given up, without producing a block.
+    SlotMissed { global_slot: u32, reason: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeEventTransactionStatus {
+    Applied,
+    Failed,
+}
+
+impl NodeEvent {
+    pub fn new(time: redux::Timestamp, kind: NodeEventKind) -> Self {
+        Self {
+            version: NODE_EVENT_VERSION,
+            time: openmina_core::log::to_rfc_3339(time).unwrap_or_default(),
+            kind,
+        }
+    }
+}
+
+impl NodeEventKind {
+    /// Value of the `kind` field of the serialized event.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::BlockApplied { .. } => "block_applied",
+            Self::BestTipChanged { .. } => "best_tip_changed",
+            Self::TransactionIncluded { .. } => "transaction_included",
+            Self::SnarkWorkAdded { .. } => "snark_work_added",
+            Self::BlockProduced { .. } => "block_produced",
+            Self::SlotMissed { .. } => "slot_missed",
+        }
+    }
+
+    pub fn block_applied(block: &ArcBlockWithHash) -> Self {
+        Self::BlockApplied {
+            hash: block.hash().clone(),
+            height: block.height(),
+            global_slot: block.global_slot(),
+        }
+    }
+
+    pub fn block_produced(block: &ArcBlockWithHash) -> Self {
+        Self::BlockProduced {
+            hash: block.hash().clone(),
+            height: block.height(),
+            global_slot: block.global_slot(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash() -> StateHash {
+        "3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ"
+            .parse()
+            .unwrap()
+    }
+
+    /// 2024-06-01T12:00:00Z
+    fn time() -> redux::Timestamp {
+        redux::Timestamp::new(1_717_243_200_000_000_000)
+    }
+
+    /// Serialized events are consumed by external tools, so their format
+    /// must not change without bumping [`NODE_EVENT_VERSION`].
+    #[test]
+    fn serialized_schema() {
+        let event = NodeEvent::new(
+            time(),
+            NodeEventKind::BestTipChanged {
+                hash: hash(),
+                height: 1234,
+                global_slot: 5678,
+                reorg: false,
+            },
+        );
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"version":1,"time":"2024-06-01T12:00:00Z","kind":"best_tip_changed","hash":"3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ","height":1234,"global_slot":5678,"reorg":false}"#
+        );
+
+        let event = NodeEvent::new(
+            time(),
+            NodeEventKind::SlotMissed {
+                global_slot: 5678,
+                reason: "not synced".to_owned(),
+            },
+        );
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"version":1,"time":"2024-06-01T12:00:00Z","kind":"slot_missed","global_slot":5678,"reason":"not synced"}"#
+        );
+    }
+
+    #[test]
+    fn kind_name_matches_serialized_kind() {
+        let kinds = [
+            NodeEventKind::BlockApplied {
+                hash: hash(),
+                height: 1,
+                global_slot: 1,
+            },
+            NodeEventKind::BestTipChanged {
+                hash: hash(),
+                height: 1,
+                global_slot: 1,
+                reorg: true,
+            },
+            NodeEventKind::BlockProduced {
+                hash: hash(),
+                height: 1,
+                global_slot: 1,
+            },
+            NodeEventKind::SlotMissed {
+                global_slot: 1,
+                reason: String::new(),
+            },
+        ];
+        for kind in kinds {
+            let name = kind.name();
+            let value = serde_json::to_value(NodeEvent::new(time(), kind)).unwrap();
+            assert_eq!(value["kind"], name);
+            assert_eq!(value["version"], NODE_EVENT_VERSION);
+        }
+    }
+}
@@ -35,6 +35,8 @@ impl std::fmt::Display for Event {
                     RpcRequest::ActionStatsGet(query) => write!(f, "ActionStatsGet, {query:?}"),
                     RpcRequest::SyncStatsGet(query) => write!(f, "SyncStatsGet, {query:?}"),
                     RpcRequest::BlockProducerStatsGet => write!(f, "BlockProducerStatsGet"),
+                    RpcRequest::BlockProducerScheduleGet => write!(f, "BlockProducerScheduleGet"),
+                    RpcRequest::MetricsGet => write!(f, "MetricsGet"),
                     RpcRequest::PeersGet => write!(f, "PeersGet"),
                     RpcRequest::MessageProgressGet => write!(f, "MessageProgressGet"),
                     RpcRequest::P2pConnectionOutgoing(opts) => {
@@ -43,6 +45,22 @@ impl std::fmt::Display for Event {
                     RpcRequest::P2pConnectionIncoming(opts) => {
                         write!(f, "P2pConnectionIncoming, {}", opts.peer_id)
                     }
+                    RpcRequest::P2pNodeStatusGet(peer_id) => {
+                        write!(f, "P2pNodeStatusGet, {peer_id}")
+                    }
+                    RpcRequest::P2pBitswapResourceAdd(data) => {
+                        write!(f, "P2pBitswapResourceAdd, {} bytes", data.len())
+                    }
+                    RpcRequest::P2pBitswapResourceGet(root) => {
+                        write!(f, "P2pBitswapResourceGet, {root}")
+                    }
+                    RpcRequest::P2pBansGet => write!(f, "P2pBansGet"),
+                    RpcRequest::P2pBanAdd(request) => {
+                        write!(f, "P2pBanAdd, {}", request.peer_id)
+                    }
+                    RpcRequest::P2pBanRemove(peer_id) => write!(f, "P2pBanRemove, {peer_id}"),
+                    RpcRequest::P2pBandwidthGet => write!(f, "P2pBandwidthGet"),
+                    RpcRequest::P2pYamuxStatsGet => write!(f, "P2pYamuxStatsGet"),
                     RpcRequest::ScanStateSummaryGet(query) => {
                         write!(f, "ScanStateSummaryGet, {query:?}")
                     }
@@ -71,6 +89,9 @@ impl std::fmt::Display for Event {
                     RpcRequest::BestChain(..) => write!(f, "BestChain"),
                     RpcRequest::ConsensusConstantsGet => write!(f, "ConsensusConstantsGet"),
                     RpcRequest::TransactionStatusGet(..) => write!(f, "TransactionStatusGet"),
+                    RpcRequest::TransitionFrontierSubscribe => {
+                        write!(f, "TransitionFrontierSubscribe")
+                    }
                 }
             }
             Self::ExternalSnarkWorker(event) => {
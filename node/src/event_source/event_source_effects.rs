@@ -117,6 +117,17 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                     MioEvent::ConnectionDidCloseOnDemand(addr) => {
                         store.dispatch(P2pNetworkSchedulerAction::Prune { addr });
                     }
+                    MioEvent::QuicConnectionDidConnect(addr, result) => {
+                        store.dispatch(P2pNetworkSchedulerAction::QuicDidConnect { addr, result });
+                    }
+                    MioEvent::QuicStreamDataDidReceive(addr, stream_id, data, fin) => {
+                        store.dispatch(P2pNetworkSchedulerAction::QuicIncomingData {
+                            addr,
+                            stream_id,
+                            data,
+                            fin,
+                        });
+                    }
                 },
                 P2pEvent::Connection(e) => match e {
                     P2pConnectionEvent::OfferSdpReady(peer_id, res) => match res {
@@ -316,6 +327,12 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::BlockProducerStatsGet => {
                     store.dispatch(RpcAction::BlockProducerStatsGet { rpc_id });
                 }
+                RpcRequest::BlockProducerScheduleGet => {
+                    store.dispatch(RpcAction::BlockProducerScheduleGet { rpc_id });
+                }
+                RpcRequest::MetricsGet => {
+                    store.dispatch(RpcAction::MetricsGet { rpc_id });
+                }
                 RpcRequest::PeersGet => {
                     store.dispatch(RpcAction::PeersGet { rpc_id });
                 }
@@ -328,6 +345,30 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::P2pConnectionIncoming(opts) => {
                     store.dispatch(RpcAction::P2pConnectionIncomingInit { rpc_id, opts });
                 }
+                RpcRequest::P2pNodeStatusGet(peer_id) => {
+                    store.dispatch(RpcAction::P2pNodeStatusGetInit { rpc_id, peer_id });
+                }
+                RpcRequest::P2pBitswapResourceAdd(data) => {
+                    store.dispatch(RpcAction::P2pBitswapResourceAdd { rpc_id, data });
+                }
+                RpcRequest::P2pBitswapResourceGet(root) => {
+                    store.dispatch(RpcAction::P2pBitswapResourceGetInit { rpc_id, root });
+                }
+                RpcRequest::P2pBansGet => {
+                    store.dispatch(RpcAction::P2pBansGet { rpc_id });
+                }
+                RpcRequest::P2pBanAdd(request) => {
+                    store.dispatch(RpcAction::P2pBanAdd { rpc_id, request });
+                }
+                RpcRequest::P2pBanRemove(peer_id) => {
+                    store.dispatch(RpcAction::P2pBanRemove { rpc_id, peer_id });
+                }
+                RpcRequest::P2pBandwidthGet => {
+                    store.dispatch(RpcAction::P2pBandwidthGet { rpc_id });
+                }
+                RpcRequest::P2pYamuxStatsGet => {
+                    store.dispatch(RpcAction::P2pYamuxStatsGet { rpc_id });
+                }
                 RpcRequest::ScanStateSummaryGet(query) => {
                     store.dispatch(RpcAction::ScanStateSummaryGetInit { rpc_id, query });
                 }
@@ -385,6 +426,9 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::TransactionStatusGet(tx) => {
                     store.dispatch(RpcAction::TransactionStatusGet { rpc_id, tx });
                 }
+                RpcRequest::TransitionFrontierSubscribe => {
+                    store.dispatch(RpcAction::TransitionFrontierSubscribe { rpc_id });
+                }
             },
             Event::ExternalSnarkWorker(e) => match e {
                 ExternalSnarkWorkerEvent::Started => {
@@ -13,7 +13,7 @@ use ledger::{
 };
 use mina_p2p_messages::v2::{self, LedgerHash, MinaBaseAccountBinableArgStableV2};
 use mina_signer::CompressedPubKey;
-use openmina_core::{channels::mpsc, thread};
+use openmina_core::{block::AppliedBlock, channels::mpsc, thread};
 use std::collections::BTreeMap;
 
 /// The type enumerating different requests that can be made to the
@@ -57,6 +57,10 @@ pub(super) enum LedgerRequest {
     InsertGenesisLedger {
         mask: Mask,
     },
+    FrontierPersist {
+        best_chain: Vec<AppliedBlock>,
+        needed_protocol_states: BTreeMap<v2::StateHash, v2::MinaStateProtocolStateValueStableV2>,
+    },
     StagedLedgerReconstructResult {
         staged_ledger_hash: LedgerHash,
         result: Result<StagedLedger, String>,
@@ -158,6 +162,7 @@ impl LedgerRequest {
                     block,
                     pred_block,
                     skip_verification,
+                    archive,
                 } => {
                     let block_hash = block.hash().clone();
                     let skip_verification = if skip_verification {
@@ -165,7 +170,8 @@ impl LedgerRequest {
                     } else {
                         None
                     };
-                    let result = ledger_ctx.block_apply(block, pred_block, skip_verification);
+                    let result =
+                        ledger_ctx.block_apply(block, pred_block, skip_verification, archive);
                     LedgerWriteResponse::BlockApply { block_hash, result }
                 }
                 LedgerWriteRequest::Commit {
@@ -188,20 +194,29 @@ impl LedgerRequest {
                         result,
                     }
                 }
+                LedgerWriteRequest::FrontierRestore => LedgerWriteResponse::FrontierRestore {
+                    result: ledger_ctx.frontier_restore(),
+                },
             }),
             Self::Read(id, request) => LedgerResponse::Read(
                 id,
                 match request {
-                    LedgerReadRequest::DelegatorTable(ledger_hash, producer) => {
+                    LedgerReadRequest::DelegatorTable(ledger_hash, producers) => {
                         let res = ledger_ctx
                             .producers_with_delegates(&ledger_hash, |pub_key| {
-                                AccountPublicKey::from(pub_key.clone()) == producer
+                                producers.contains(&AccountPublicKey::from(pub_key.clone()))
                             })
-                            .and_then(|list| list.into_iter().next())
-                            .map(|(_, table)| {
-                                table
-                                    .into_iter()
-                                    .map(|(index, pub_key, balance)| (index, (pub_key, balance)))
+                            .map(|list| {
+                                list.into_iter()
+                                    .map(|(producer, table)| {
+                                        let table = table
+                                            .into_iter()
+                                            .map(|(index, pub_key, balance)| {
+                                                (index, (pub_key, balance))
+                                            })
+                                            .collect();
+                                        (producer, table)
+                                    })
                                     .collect()
                             });
 
@@ -312,6 +327,21 @@ impl LedgerRequest {
                 ledger_ctx.insert_genesis_ledger(mask);
                 LedgerResponse::Success
             }
+            LedgerRequest::FrontierPersist {
+                best_chain,
+                needed_protocol_states,
+            } => {
+                if let Err(error) =
+                    ledger_ctx.frontier_persist(&best_chain, &needed_protocol_states)
+                {
+                    openmina_core::error!(
+                        openmina_core::log::system_time();
+                        kind = "LedgerService::frontier_persist",
+                        summary = format!("Failed to persist transition frontier: {error}")
+                    );
+                }
+                LedgerResponse::Success
+            }
             LedgerRequest::StagedLedgerReconstructResult {
                 staged_ledger_hash,
                 result,
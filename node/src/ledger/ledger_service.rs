@@ -1,18 +1,20 @@
 use super::{
     ledger_empty_hash_at_depth,
+    ledger_storage::{LedgerStorageFrontier, LedgerStorageRoot},
     read::LedgerReadResponse,
     read::{LedgerReadId, LedgerReadRequest},
     write::CommitResult,
     write::LedgerWriteRequest,
     write::LedgerWriteResponse,
-    LedgerAddress, LedgerEvent, LEDGER_DEPTH,
+    write::RestoredFrontier,
+    LedgerAddress, LedgerEvent, LedgerStorage, LEDGER_DEPTH,
 };
 use crate::{
     account::AccountPublicKey,
     block_producer_effectful::StagedLedgerDiffCreateOutput,
     ledger::{
         ledger_manager::{LedgerManager, LedgerRequest},
-        write::BlockApplyResult,
+        write::{BlockApplyResult, BlockApplyResultArchive},
     },
     p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases,
     rpc::{
@@ -28,7 +30,7 @@ use crate::{
 use ark_ff::fields::arithmetic::InvalidBigInt;
 use ledger::{
     scan_state::{
-        currency::Slot,
+        currency::{Fee, Slot},
         scan_state::{AvailableJobMessage, JobValueBase, JobValueMerge, JobValueWithIndex, Pass},
         transaction_logic::{
             local_state::LocalState,
@@ -44,7 +46,7 @@ use ledger::{
         validate_block::block_body_hash,
     },
     verifier::Verifier,
-    Account, AccountId, BaseLedger, Database, Mask, UnregisterBehavior,
+    Account, AccountId, BaseLedger, Database, Mask, OnDiskConfig, UnregisterBehavior,
 };
 use mina_hasher::Fp;
 use mina_p2p_messages::{
@@ -69,7 +71,7 @@ use openmina_core::{
 };
 use std::{
     collections::{BTreeMap, BTreeSet},
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
@@ -81,6 +83,48 @@ fn error_to_string(e: InvalidBigInt) -> String {
     format!("{:?}", e)
 }
 
+fn ondisk_error_to_string(e: std::io::Error) -> String {
+    format!("failed to access ledger accounts on disk: {e}")
+}
+
+/// Directory where the accounts of snarked ledgers are stored, to keep
+/// them out of memory.
+#[derive(Debug, Clone)]
+pub struct LedgerAccountsOnDisk {
+    directory: PathBuf,
+    config: OnDiskConfig,
+}
+
+impl LedgerAccountsOnDisk {
+    /// Ledgers aren't reused between runs, so the `directory` is emptied
+    /// first, then created if needed.
+    pub fn open(directory: PathBuf) -> std::io::Result<Self> {
+        match std::fs::remove_dir_all(&directory) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        std::fs::create_dir_all(&directory)?;
+        Ok(Self {
+            directory,
+            config: OnDiskConfig {
+                temporary: true,
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Creates an empty snarked ledger, in memory if `on_disk` is `None`.
+    fn create(on_disk: Option<&Self>) -> Result<Mask, String> {
+        let Some(on_disk) = on_disk else {
+            return Ok(Mask::create(LEDGER_DEPTH));
+        };
+        let directory = on_disk.directory.join(ledger::next_uuid());
+        let db = Database::create_ondisk(LEDGER_DEPTH as u8, directory, &on_disk.config)
+            .map_err(ondisk_error_to_string)?;
+        Ok(Mask::new_root(db))
+    }
+}
+
 /// Indexing `StagedLedger` both by their "merkle root hash" and their "staged ledger hash"
 #[derive(Default)]
 struct StagedLedgersStorage {
@@ -174,6 +218,10 @@ pub struct LedgerCtx {
     additional_snarked_ledgers: BTreeMap<LedgerHash, Mask>,
     staged_ledgers: StagedLedgersStorage,
     sync: LedgerSyncState,
+    /// Where the transition frontier and its ledgers are persisted, if anywhere.
+    storage: Option<LedgerStorage>,
+    /// Where the accounts of snarked ledgers are stored, in memory if `None`.
+    accounts_on_disk: Option<LedgerAccountsOnDisk>,
     event_sender:
         Option<openmina_core::channels::mpsc::UnboundedSender<crate::event_source::Event>>,
 }
@@ -228,6 +276,16 @@ impl LedgerCtx {
         self.event_sender = Some(event_sender);
     }
 
+    pub fn set_storage(&mut self, storage: LedgerStorage) {
+        self.storage = Some(storage);
+    }
+
+    /// Stores the accounts of snarked ledgers on disk instead of memory.
+    /// Must be set before the genesis ledger is inserted.
+    pub fn set_accounts_on_disk(&mut self, on_disk: LedgerAccountsOnDisk) {
+        self.accounts_on_disk = Some(on_disk);
+    }
+
     pub(super) fn send_event(&self, event: LedgerEvent) {
         if let Some(tx) = self.event_sender.as_ref() {
             let _ = tx.send(event.into());
@@ -244,8 +302,25 @@ impl LedgerCtx {
 
     pub fn insert_genesis_ledger(&mut self, mut mask: Mask) {
         let merkle_root_hash = merkle_root(&mut mask);
-        let staged_ledger =
-            StagedLedger::create_exn(constraint_constants().clone(), mask.copy()).unwrap();
+        if self.accounts_on_disk.is_some() {
+            match self.genesis_ledger_to_disk(&mask, &merkle_root_hash) {
+                Ok(on_disk) => mask = on_disk,
+                Err(error) => openmina_core::error!(openmina_core::log::system_time();
+                    kind = "LedgerService::insert_genesis_ledger",
+                    summary = "failed to move the genesis ledger to disk, keeping it in memory",
+                    error = error),
+            }
+        }
+        let staged_ledger = match mask.try_copy() {
+            Ok(copy) => StagedLedger::create_exn(constraint_constants().clone(), copy).unwrap(),
+            Err(e) => {
+                openmina_core::error!(openmina_core::log::system_time();
+                    kind = "LedgerService::insert_genesis_ledger",
+                    summary = "failed to copy the genesis ledger",
+                    error = ondisk_error_to_string(e));
+                return;
+            }
+        };
         self.snarked_ledgers.insert(merkle_root_hash.clone(), mask);
         // The genesis ledger is a specific case, some of its hashes are zero
         let staged_ledger_hash =
@@ -254,6 +329,28 @@ impl LedgerCtx {
             .insert(Arc::new(staged_ledger_hash), staged_ledger);
     }
 
+    /// Copies the accounts of the genesis ledger into a ledger stored on disk.
+    fn genesis_ledger_to_disk(
+        &self,
+        mask: &Mask,
+        merkle_root_hash: &LedgerHash,
+    ) -> Result<Mask, String> {
+        let mut on_disk = LedgerAccountsOnDisk::create(self.accounts_on_disk.as_ref())?;
+        for account in mask.to_list() {
+            on_disk
+                .get_or_create_account(account.id(), account)
+                .map_err(|e| format!("{e:?}"))?;
+        }
+        let calculated = merkle_root(&mut on_disk);
+        on_disk.flush().map_err(ondisk_error_to_string)?;
+        if &calculated != merkle_root_hash {
+            return Err(format!(
+                "genesis ledger hash mismatch, expected: {merkle_root_hash}, found: {calculated}"
+            ));
+        }
+        Ok(on_disk)
+    }
+
     pub fn staged_ledger_reconstruct_result_store(&mut self, ledger: StagedLedger) {
         self.staged_ledgers.insert_by_recomputing_hash(ledger);
     }
@@ -342,7 +439,7 @@ impl LedgerCtx {
                 origin_snarked_ledger_hash
             ))?;
 
-        let target = origin.copy();
+        let target = origin.try_copy().map_err(ondisk_error_to_string)?;
         self.sync
             .snarked_ledgers
             .insert(target_snarked_ledger_hash, target);
@@ -367,7 +464,7 @@ impl LedgerCtx {
         // merkle root hash forces all pending hashes to be computed.
         let _force_hashing = origin.merkle_root();
 
-        Ok(())
+        origin.flush().map_err(ondisk_error_to_string)
     }
 
     /// Returns a mutable reference to the [StagedLedger] with the specified `hash` if it exists or `None` otherwise.
@@ -580,6 +677,7 @@ impl LedgerCtx {
             .map_err(|_| "Failed when setting accounts".to_owned())?;
 
         let computed_hash = LedgerHash::from_fp(mask.get_inner_hash_at_addr(parent.clone())?);
+        mask.flush().map_err(ondisk_error_to_string)?;
 
         Ok(computed_hash)
     }
@@ -589,14 +687,15 @@ impl LedgerCtx {
         snarked_ledger_hash: LedgerHash,
         parts: Option<Arc<StagedLedgerAuxAndPendingCoinbasesValid>>,
         callback: F,
-    ) -> Result<(), InvalidBigInt>
+    ) -> Result<(), String>
     where
         F: 'static + FnOnce(v2::LedgerHash, Result<StagedLedger, String>) + Send,
     {
         let snarked_ledger = self
             .sync
-            .snarked_ledger_mut(snarked_ledger_hash.clone())?
-            .copy();
+            .snarked_ledger_mut(snarked_ledger_hash.clone(), self.accounts_on_disk.as_ref())?
+            .try_copy()
+            .map_err(ondisk_error_to_string)?;
 
         thread::Builder::new()
             .name("staged-ledger-reconstruct".into())
@@ -617,13 +716,15 @@ impl LedgerCtx {
         &mut self,
         snarked_ledger_hash: LedgerHash,
         parts: Option<Arc<StagedLedgerAuxAndPendingCoinbasesValid>>,
-    ) -> Result<(v2::LedgerHash, Result<(), String>), InvalidBigInt> {
+    ) -> Result<(v2::LedgerHash, Result<(), String>), String> {
         let snarked_ledger = self
             .sync
-            .snarked_ledger_mut(snarked_ledger_hash.clone())?
-            .copy();
+            .snarked_ledger_mut(snarked_ledger_hash.clone(), self.accounts_on_disk.as_ref())?
+            .try_copy()
+            .map_err(ondisk_error_to_string)?;
         let (staged_ledger_hash, result) =
-            staged_ledger_reconstruct(snarked_ledger, snarked_ledger_hash, parts)?;
+            staged_ledger_reconstruct(snarked_ledger, snarked_ledger_hash, parts)
+                .map_err(error_to_string)?;
         let result = match result {
             Err(err) => Err(err),
             Ok(staged_ledger) => {
@@ -640,6 +741,7 @@ impl LedgerCtx {
         block: ArcBlockWithHash,
         pred_block: AppliedBlock,
         skip_verification: Option<SkipVerification>,
+        archive: bool,
     ) -> Result<BlockApplyResult, String> {
         openmina_core::info!(openmina_core::log::system_time();
             kind = "LedgerService::block_apply",
@@ -674,6 +776,11 @@ impl LedgerCtx {
         let prev_protocol_state: ledger::proofs::block::ProtocolState =
             prev_protocol_state.try_into()?;
 
+        // Applying the diff creates a child mask, so this one keeps the
+        // accounts as they were before the block.
+        let pred_ledger = staged_ledger.ledger();
+        let archive_diff = archive.then(|| diff.clone());
+
         let result = staged_ledger
             .apply(
                 skip_verification,
@@ -684,7 +791,7 @@ impl LedgerCtx {
                 &Verifier,
                 &prev_state_view,
                 prev_protocol_state.hashes(),
-                coinbase_receiver,
+                coinbase_receiver.clone(),
                 supercharge_coinbase,
             )
             .map_err(|err| format!("{err:?}"))?;
@@ -714,12 +821,26 @@ impl LedgerCtx {
             panic!("staged ledger hash mismatch. found: {ledger_hashes:#?}, expected: {expected_ledger_hashes:#?}");
         }
 
+        let archive_data = archive_diff
+            .map(|diff| {
+                block_apply_archive_data(
+                    diff,
+                    coinbase_receiver,
+                    supercharge_coinbase,
+                    &pred_ledger,
+                    &staged_ledger.ledger(),
+                )
+            })
+            .transpose()?
+            .map(Arc::new);
+
         self.sync
             .staged_ledgers
             .insert(Arc::new(ledger_hashes), staged_ledger);
 
         Ok(BlockApplyResult {
             just_emitted_a_proof,
+            archive_data,
         })
     }
 
@@ -847,6 +968,162 @@ impl LedgerCtx {
         }
     }
 
+    /// Persists the transition frontier along with the snarked ledgers
+    /// and the scan state of its root, so that it can be restored later.
+    pub fn frontier_persist(
+        &mut self,
+        best_chain: &[AppliedBlock],
+        needed_protocol_states: &BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    ) -> Result<(), String> {
+        let Some(storage) = self.storage.as_ref() else {
+            return Ok(());
+        };
+        let (Some(root), Some(best_tip)) = (best_chain.first(), best_chain.last()) else {
+            return Ok(());
+        };
+
+        let snarked_ledger_hashes = [
+            root.snarked_ledger_hash(),
+            root.staking_epoch_ledger_hash(),
+            root.next_epoch_ledger_hash(),
+            best_tip.staking_epoch_ledger_hash(),
+            best_tip.next_epoch_ledger_hash(),
+        ]
+        .into_iter()
+        .filter(|hash| {
+            self.snarked_ledgers.contains_key(*hash)
+                || self.additional_snarked_ledgers.contains_key(*hash)
+        })
+        .cloned()
+        .collect::<BTreeSet<_>>();
+        if !snarked_ledger_hashes.contains(root.snarked_ledger_hash()) {
+            return Err(format!(
+                "root snarked ledger missing: {}",
+                root.snarked_ledger_hash()
+            ));
+        }
+
+        // The root's scan state and ledgers are costly to encode, so they
+        // are only written when the root changes.
+        if storage.is_root_stored(root.hash(), &snarked_ledger_hashes) {
+            return self.frontier_store(best_chain, None);
+        }
+
+        let snarked_ledgers = snarked_ledger_hashes
+            .into_iter()
+            .filter_map(|hash| {
+                let mask = self
+                    .snarked_ledgers
+                    .get(&hash)
+                    .or_else(|| self.additional_snarked_ledgers.get(&hash))?
+                    .clone();
+                Some((hash, mask))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let protocol_states = best_chain
+            .iter()
+            .map(|b| (b.hash().clone(), b.header().protocol_state.clone()))
+            .chain(needed_protocol_states.clone())
+            .collect();
+        let staged_ledger_parts = self
+            .staged_ledger_aux_and_pending_coinbase(root.staged_ledger_hashes(), protocol_states)
+            .ok_or_else(|| format!("root staged ledger missing: {}", root.merkle_root_hash()))?;
+
+        let root = LedgerStorageRoot {
+            needed_protocol_states,
+            snarked_ledgers: &snarked_ledgers,
+            staged_ledger_parts: &staged_ledger_parts,
+        };
+        self.frontier_store(best_chain, Some(root))?;
+        snarked_ledgers
+            .values()
+            .try_for_each(Mask::flush)
+            .map_err(ondisk_error_to_string)
+    }
+
+    fn frontier_store(
+        &mut self,
+        best_chain: &[AppliedBlock],
+        root: Option<LedgerStorageRoot<'_>>,
+    ) -> Result<(), String> {
+        let Some(storage) = self.storage.as_mut() else {
+            return Ok(());
+        };
+        storage.store(best_chain, root).map_err(|e| e.to_string())
+    }
+
+    /// Restores the transition frontier persisted by [`Self::frontier_persist`].
+    ///
+    /// Root staged ledger is reconstructed from the persisted scan state
+    /// and the rest of the blocks are applied on top of it again.
+    pub fn frontier_restore(&mut self) -> Result<Option<Arc<RestoredFrontier>>, String> {
+        let Some(storage) = self.storage.as_mut() else {
+            return Ok(None);
+        };
+        let Some(LedgerStorageFrontier {
+            best_chain,
+            needed_protocol_states,
+            snarked_ledgers,
+            staged_ledger_parts,
+        }) = storage.load().map_err(|e| e.to_string())?
+        else {
+            return Ok(None);
+        };
+        let Some(root) = best_chain.first() else {
+            return Ok(None);
+        };
+
+        for (hash, accounts) in snarked_ledgers {
+            let mut mask = LedgerAccountsOnDisk::create(self.accounts_on_disk.as_ref())?;
+            for account in accounts {
+                mask.get_or_create_account(account.id(), account)
+                    .map_err(|e| format!("{e:?}"))?;
+            }
+            let calculated = merkle_root(&mut mask);
+            if calculated != hash {
+                return Err(format!(
+                    "snarked ledger hash mismatch, expected: {hash}, found: {calculated}"
+                ));
+            }
+            self.snarked_ledgers.insert(hash, mask);
+        }
+
+        let snarked_ledger_hash = root.snarked_ledger_hash();
+        let snarked_ledger = self
+            .snarked_ledgers
+            .get(snarked_ledger_hash)
+            .ok_or_else(|| format!("root snarked ledger missing: {snarked_ledger_hash}"))?
+            .try_copy()
+            .map_err(ondisk_error_to_string)?;
+        let (_, staged_ledger) = staged_ledger_reconstruct(
+            snarked_ledger,
+            snarked_ledger_hash.clone(),
+            Some(staged_ledger_parts),
+        )
+        .map_err(error_to_string)?;
+        self.staged_ledgers.insert(
+            Arc::new(root.staged_ledger_hashes().clone()),
+            staged_ledger?,
+        );
+
+        for (pred_block, block) in best_chain.iter().zip(best_chain.iter().skip(1)) {
+            self.block_apply(
+                block.block.clone(),
+                pred_block.clone(),
+                Some(SkipVerification::All),
+                false,
+            )?;
+        }
+        let applied = self.sync.staged_ledgers.take();
+        self.staged_ledgers.extend(applied);
+
+        Ok(Some(Arc::new(RestoredFrontier {
+            best_chain,
+            needed_protocol_states,
+        })))
+    }
+
     pub fn get_num_accounts(
         &mut self,
         ledger_hash: v2::LedgerHash,
@@ -1233,14 +1510,21 @@ impl LedgerSyncState {
     }
 
     /// Returns a [Mask] instance for the snarked ledger with [hash]. If it doesn't
-    /// exist a new instance is created.
-    fn snarked_ledger_mut(&mut self, hash: LedgerHash) -> Result<&mut Mask, InvalidBigInt> {
-        let hash_fp = hash.to_field()?;
-        Ok(self.snarked_ledgers.entry(hash.clone()).or_insert_with(|| {
-            let mut ledger = Mask::create(LEDGER_DEPTH);
-            ledger.set_cached_hash_unchecked(&LedgerAddress::root(), hash_fp);
-            ledger
-        }))
+    /// exist a new instance is created, stored in `on_disk` if set.
+    fn snarked_ledger_mut(
+        &mut self,
+        hash: LedgerHash,
+        on_disk: Option<&LedgerAccountsOnDisk>,
+    ) -> Result<&mut Mask, String> {
+        let hash_fp = hash.to_field().map_err(error_to_string)?;
+        match self.snarked_ledgers.entry(hash) {
+            std::collections::btree_map::Entry::Occupied(entry) => Ok(entry.into_mut()),
+            std::collections::btree_map::Entry::Vacant(entry) => {
+                let mut ledger = LedgerAccountsOnDisk::create(on_disk)?;
+                ledger.set_cached_hash_unchecked(&LedgerAddress::root(), hash_fp);
+                Ok(entry.insert(ledger))
+            }
+        }
     }
 
     fn staged_ledger_mut(
@@ -1251,6 +1535,57 @@ impl LedgerSyncState {
     }
 }
 
+fn block_apply_archive_data(
+    diff: Diff,
+    coinbase_receiver: CompressedPubKey,
+    supercharge_coinbase: bool,
+    pred_ledger: &Mask,
+    ledger: &Mask,
+) -> Result<BlockApplyResultArchive, String> {
+    let transactions = diff
+        .get_transactions(
+            constraint_constants(),
+            coinbase_receiver,
+            supercharge_coinbase,
+        )
+        .map_err(|err| format!("{err:?}"))?;
+
+    let account_ids = transactions
+        .iter()
+        .flat_map(|tx| tx.data.accounts_referenced())
+        .collect::<BTreeSet<_>>();
+
+    let accounts_accessed = account_ids
+        .iter()
+        .filter_map(|id| {
+            let index = ledger.index_of_account(id.clone())?;
+            let account = ledger.get_at_index(index)?;
+            Some((index.0, (&*account).into()))
+        })
+        .collect();
+
+    let creation_fee = Fee::from_u64(constraint_constants().account_creation_fee);
+    let accounts_created = account_ids
+        .into_iter()
+        .filter(|id| {
+            pred_ledger.location_of_account(id).is_none()
+                && ledger.location_of_account(id).is_some()
+        })
+        .map(|id| (id.into(), (&creation_fee).into()))
+        .collect();
+
+    let transactions = transactions
+        .iter()
+        .map(|tx| ((&tx.data).into(), (&tx.status).into()))
+        .collect();
+
+    Ok(BlockApplyResultArchive {
+        transactions,
+        accounts_accessed,
+        accounts_created,
+    })
+}
+
 fn staged_ledger_reconstruct(
     snarked_ledger: Mask,
     snarked_ledger_hash: LedgerHash,
@@ -1326,6 +1661,22 @@ pub trait LedgerService: redux::Service {
             self.ledger_manager().call(request);
         }
     }
+
+    fn frontier_persist(
+        &mut self,
+        best_chain: Vec<AppliedBlock>,
+        needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    ) {
+        let request = LedgerRequest::FrontierPersist {
+            best_chain,
+            needed_protocol_states,
+        };
+        if self.force_sync_calls() {
+            let _ = self.ledger_manager().call_sync(request);
+        } else {
+            self.ledger_manager().call(request);
+        }
+    }
 }
 
 /// Save reconstruction to file, when it fails.
@@ -1451,10 +1802,71 @@ fn dump_application_to_file(
 mod tests {
     use mina_p2p_messages::v2::MinaBaseLedgerHash0StableV1;
 
-    use crate::ledger::hash_node_at_depth;
+    use crate::ledger::{hash_node_at_depth, LedgerStorage};
+    use crate::testing::genesis_config;
 
     use super::*;
 
+    #[test]
+    fn frontier_persist_and_restore() {
+        for accounts_on_disk in [false, true] {
+            let dir =
+                std::env::temp_dir().join(format!("openmina-frontier-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            let ledgers_dir =
+                std::env::temp_dir().join(format!("openmina-ledgers-{}", std::process::id()));
+            let new_ledger_ctx = || {
+                let mut ledger_ctx = LedgerCtx::default();
+                ledger_ctx.set_storage(LedgerStorage::open(&dir).unwrap());
+                if accounts_on_disk {
+                    ledger_ctx.set_accounts_on_disk(
+                        LedgerAccountsOnDisk::open(ledgers_dir.clone()).unwrap(),
+                    );
+                }
+                ledger_ctx
+            };
+
+            let (masks, loaded) = genesis_config().load().unwrap();
+            let best_chain = vec![AppliedBlock {
+                block: loaded.block_with_dummy_proof().unwrap(),
+                just_emitted_a_proof: false,
+            }];
+
+            let mut ledger_ctx = new_ledger_ctx();
+            for mask in masks {
+                ledger_ctx.insert_genesis_ledger(mask);
+            }
+            if accounts_on_disk {
+                assert!(std::fs::read_dir(&ledgers_dir).unwrap().next().is_some());
+            }
+            ledger_ctx
+                .frontier_persist(&best_chain, &Default::default())
+                .unwrap();
+            // Same root, only the best chain gets written.
+            ledger_ctx
+                .frontier_persist(&best_chain, &Default::default())
+                .unwrap();
+            drop(ledger_ctx);
+
+            let mut ledger_ctx = new_ledger_ctx();
+            let restored = ledger_ctx.frontier_restore().unwrap().unwrap();
+            let hashes = |chain: &[AppliedBlock]| {
+                chain
+                    .iter()
+                    .map(|block| block.hash().clone())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(hashes(&restored.best_chain), hashes(&best_chain));
+            assert!(ledger_ctx
+                .staged_ledger_mut(best_chain[0].staged_ledger_hashes())
+                .is_some());
+
+            drop(ledger_ctx);
+            let _ = std::fs::remove_dir_all(&dir);
+            let _ = std::fs::remove_dir_all(&ledgers_dir);
+        }
+    }
+
     #[test]
     fn test_ledger_hash() {
         IntoIterator::into_iter([(
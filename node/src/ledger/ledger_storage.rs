@@ -0,0 +1,299 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+    sync::Arc,
+};
+
+use ledger::{ondisk::Database, Account, BaseLedger, Mask};
+use mina_p2p_messages::{
+    binprot::{
+        self,
+        macros::{BinProtRead, BinProtWrite},
+        BinProtRead, BinProtWrite,
+    },
+    v2::{self, LedgerHash, MinaStateProtocolStateValueStableV2, StateHash},
+};
+use openmina_core::block::{AppliedBlock, BlockWithHash};
+
+use crate::p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases;
+
+const FRONTIER_KEY: &[u8] = b"frontier";
+const FRONTIER_ROOT_KEY: &[u8] = b"frontier_root";
+const BLOCK_KEY_PREFIX: &str = "block/";
+const SNARKED_LEDGER_KEY_PREFIX: &str = "snarked_ledger/";
+
+/// Persists the transition frontier together with the ledgers needed to
+/// resume from it, so that a restarted node doesn't have to sync again.
+///
+/// Blocks and snarked ledgers are stored under their own keys, so that
+/// only the ones which weren't persisted before get written. The root's
+/// scan state and ledgers are only written when the root changes.
+pub struct LedgerStorage {
+    db: Database,
+    root: Option<StateHash>,
+    blocks: BTreeSet<StateHash>,
+    snarked_ledgers: BTreeSet<LedgerHash>,
+}
+
+/// Transition frontier loaded from the [`LedgerStorage`].
+pub(super) struct LedgerStorageFrontier {
+    pub best_chain: Vec<AppliedBlock>,
+    pub needed_protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    pub snarked_ledgers: Vec<(LedgerHash, Vec<Account>)>,
+    pub staged_ledger_parts: Arc<StagedLedgerAuxAndPendingCoinbases>,
+}
+
+/// Parts of the transition frontier root which are needed to reconstruct
+/// its staged ledger.
+pub(super) struct LedgerStorageRoot<'a> {
+    pub needed_protocol_states: &'a BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    pub snarked_ledgers: &'a BTreeMap<LedgerHash, Mask>,
+    pub staged_ledger_parts: &'a StagedLedgerAuxAndPendingCoinbases,
+}
+
+#[derive(BinProtRead, BinProtWrite)]
+struct StoredFrontier {
+    best_chain: Vec<StoredBlock>,
+}
+
+#[derive(BinProtRead, BinProtWrite)]
+struct StoredFrontierRoot {
+    hash: StateHash,
+    needed_protocol_states: Vec<StoredProtocolState>,
+    snarked_ledgers: Vec<LedgerHash>,
+    staged_ledger_parts: StagedLedgerAuxAndPendingCoinbases,
+}
+
+#[derive(BinProtRead, BinProtWrite)]
+struct StoredBlock {
+    hash: StateHash,
+    just_emitted_a_proof: bool,
+}
+
+#[derive(BinProtRead, BinProtWrite)]
+struct StoredProtocolState {
+    hash: StateHash,
+    state: MinaStateProtocolStateValueStableV2,
+}
+
+impl LedgerStorage {
+    /// Opens the storage in the `directory`, creating it if needed.
+    pub fn open(directory: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut storage = Self {
+            db: Database::create(directory)?,
+            root: None,
+            blocks: Default::default(),
+            snarked_ledgers: Default::default(),
+        };
+        if let Some(frontier) = storage.option_get::<StoredFrontier>(FRONTIER_KEY)? {
+            storage.blocks = frontier.best_chain.into_iter().map(|b| b.hash).collect();
+        }
+        if let Some(root) = storage.option_get::<StoredFrontierRoot>(FRONTIER_ROOT_KEY)? {
+            storage.root = Some(root.hash);
+            storage.snarked_ledgers = root.snarked_ledgers.into_iter().collect();
+        }
+        Ok(storage)
+    }
+
+    fn option_get<T: BinProtRead>(&mut self, key: &[u8]) -> std::io::Result<Option<T>> {
+        self.db.get(key)?.map(|bytes| decode(&bytes)).transpose()
+    }
+
+    /// Loads the persisted frontier, if there is one.
+    pub(super) fn load(&mut self) -> std::io::Result<Option<LedgerStorageFrontier>> {
+        let Some(frontier) = self.option_get::<StoredFrontier>(FRONTIER_KEY)? else {
+            return Ok(None);
+        };
+        let Some(root) = self.option_get::<StoredFrontierRoot>(FRONTIER_ROOT_KEY)? else {
+            return Ok(None);
+        };
+        if frontier.best_chain.first().map(|b| &b.hash) != Some(&root.hash) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("frontier doesn't start at the stored root: {}", root.hash),
+            ));
+        }
+
+        let best_chain = frontier
+            .best_chain
+            .into_iter()
+            .map(
+                |StoredBlock {
+                     hash,
+                     just_emitted_a_proof,
+                 }| {
+                    let block: v2::MinaBlockBlockStableV2 = self.value_get(&block_key(&hash))?;
+                    let block = BlockWithHash::try_new(Arc::new(block)).map_err(invalid_data)?;
+                    if block.hash() != &hash {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!(
+                                "block hash mismatch, expected: {hash}, found: {}",
+                                block.hash()
+                            ),
+                        ));
+                    }
+                    Ok(AppliedBlock {
+                        block,
+                        just_emitted_a_proof,
+                    })
+                },
+            )
+            .collect::<std::io::Result<_>>()?;
+        let snarked_ledgers = root
+            .snarked_ledgers
+            .into_iter()
+            .map(|hash| {
+                let accounts = self.value_get(&snarked_ledger_key(&hash))?;
+                Ok((hash, accounts))
+            })
+            .collect::<std::io::Result<_>>()?;
+
+        Ok(Some(LedgerStorageFrontier {
+            best_chain,
+            needed_protocol_states: root
+                .needed_protocol_states
+                .into_iter()
+                .map(|StoredProtocolState { hash, state }| (hash, state))
+                .collect(),
+            snarked_ledgers,
+            staged_ledger_parts: root.staged_ledger_parts.into(),
+        }))
+    }
+
+    fn value_get<T: BinProtRead>(&mut self, key: &[u8]) -> std::io::Result<T> {
+        let bytes = self.db.get(key)?.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("missing key: {}", String::from_utf8_lossy(key)),
+            )
+        })?;
+        decode(&bytes)
+    }
+
+    /// Whether the root of the frontier is already persisted for the block
+    /// `root` with the `snarked_ledgers`, so that it needn't be written again.
+    pub(super) fn is_root_stored(
+        &self,
+        root: &StateHash,
+        snarked_ledgers: &BTreeSet<LedgerHash>,
+    ) -> bool {
+        self.root.as_ref() == Some(root) && &self.snarked_ledgers == snarked_ledgers
+    }
+
+    /// Replaces the persisted frontier with the new one, writing only
+    /// blocks and ledgers which aren't persisted yet and removing the
+    /// ones that aren't needed anymore.
+    ///
+    /// `root` can only be omitted if it [is stored](Self::is_root_stored)
+    /// already.
+    pub(super) fn store(
+        &mut self,
+        best_chain: &[AppliedBlock],
+        root: Option<LedgerStorageRoot<'_>>,
+    ) -> std::io::Result<()> {
+        let Some(root_block) = best_chain.first() else {
+            return Ok(());
+        };
+        let mut to_set = Vec::new();
+        let mut to_remove = Vec::new();
+
+        for block in best_chain {
+            if !self.blocks.contains(block.hash()) {
+                to_set.push((block_key(block.hash()), encode(&**block.block())?));
+            }
+        }
+        let blocks = best_chain
+            .iter()
+            .map(|b| b.hash().clone())
+            .collect::<BTreeSet<_>>();
+        to_remove.extend(self.blocks.difference(&blocks).map(block_key));
+
+        let ledgers = match root {
+            Some(root) => {
+                for (hash, mask) in root.snarked_ledgers {
+                    if !self.snarked_ledgers.contains(hash) {
+                        to_set.push((snarked_ledger_key(hash), encode(&mask.to_list())?));
+                    }
+                }
+                let ledgers = root
+                    .snarked_ledgers
+                    .keys()
+                    .cloned()
+                    .collect::<BTreeSet<_>>();
+                to_remove.extend(
+                    self.snarked_ledgers
+                        .difference(&ledgers)
+                        .map(snarked_ledger_key),
+                );
+
+                let stored_root = StoredFrontierRoot {
+                    hash: root_block.hash().clone(),
+                    needed_protocol_states: root
+                        .needed_protocol_states
+                        .iter()
+                        .map(|(hash, state)| StoredProtocolState {
+                            hash: hash.clone(),
+                            state: state.clone(),
+                        })
+                        .collect(),
+                    snarked_ledgers: ledgers.iter().cloned().collect(),
+                    staged_ledger_parts: root.staged_ledger_parts.clone(),
+                };
+                to_set.push((FRONTIER_ROOT_KEY.into(), encode(&stored_root)?));
+                Some(ledgers)
+            }
+            None if self.root.as_ref() == Some(root_block.hash()) => None,
+            None => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("frontier root isn't stored: {}", root_block.hash()),
+                ))
+            }
+        };
+
+        let frontier = StoredFrontier {
+            best_chain: best_chain
+                .iter()
+                .map(|b| StoredBlock {
+                    hash: b.hash().clone(),
+                    just_emitted_a_proof: b.just_emitted_a_proof,
+                })
+                .collect(),
+        };
+        // Written last, so that it never references missing entries.
+        to_set.push((FRONTIER_KEY.into(), encode(&frontier)?));
+
+        self.db.set_batch(to_set, to_remove)?;
+        self.blocks = blocks;
+        if let Some(ledgers) = ledgers {
+            self.root = Some(root_block.hash().clone());
+            self.snarked_ledgers = ledgers;
+        }
+        Ok(())
+    }
+}
+
+fn block_key(hash: &StateHash) -> Box<[u8]> {
+    format!("{BLOCK_KEY_PREFIX}{hash}").into_bytes().into()
+}
+
+fn snarked_ledger_key(hash: &LedgerHash) -> Box<[u8]> {
+    format!("{SNARKED_LEDGER_KEY_PREFIX}{hash}")
+        .into_bytes()
+        .into()
+}
+
+fn encode<T: BinProtWrite>(value: &T) -> std::io::Result<Box<[u8]>> {
+    let mut bytes = Vec::new();
+    value.binprot_write(&mut bytes)?;
+    Ok(bytes.into())
+}
+
+fn decode<T: BinProtRead>(mut bytes: &[u8]) -> std::io::Result<T> {
+    T::binprot_read(&mut bytes).map_err(invalid_data)
+}
+
+fn invalid_data(error: impl std::fmt::Debug) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{error:?}"))
+}
@@ -74,31 +74,31 @@ impl LedgerReadState {
 
         match (request.request(), response) {
             (
-                LedgerReadRequest::DelegatorTable(ledger_hash, pub_key),
-                LedgerReadResponse::DelegatorTable(table),
+                LedgerReadRequest::DelegatorTable(ledger_hash, producers),
+                LedgerReadResponse::DelegatorTable(tables),
             ) => {
                 let expected = state.block_producer.vrf_delegator_table_inputs();
-                if !expected.map_or(false, |(expected_hash, producer)| {
-                    ledger_hash == expected_hash && pub_key == producer
+                if !expected.map_or(false, |(expected_hash, expected_producers)| {
+                    ledger_hash == expected_hash && producers == expected_producers
                 }) {
                     bug_condition!("delegator table unexpected");
                     return;
                 }
-                match table {
+                match tables {
                     None => {
                         // TODO(tizoc): Revise this, may be better to dispatch a different action here
                         // and avoid running the VRF evaluator altogether when we know that the
                         // table is empty.
                         dispatcher.push(
                             BlockProducerVrfEvaluatorAction::FinalizeDelegatorTableConstruction {
-                                delegator_table: Default::default(),
+                                delegator_tables: Default::default(),
                             },
                         );
                     }
-                    Some(table) => {
+                    Some(tables) => {
                         dispatcher.push(
                             BlockProducerVrfEvaluatorAction::FinalizeDelegatorTableConstruction {
-                                delegator_table: table.into(),
+                                delegator_tables: tables.into(),
                             },
                         );
                     }
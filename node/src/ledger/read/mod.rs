@@ -12,14 +12,14 @@ use redux::Callback;
 
 mod ledger_read_reducer;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
 use mina_p2p_messages::v2;
 use serde::{Deserialize, Serialize};
 
 use crate::account::AccountPublicKey;
-use crate::block_producer::vrf_evaluator::DelegatorTable;
+use crate::block_producer::vrf_evaluator::DelegatorTables;
 use crate::ledger::LedgerAddress;
 use crate::p2p::channels::rpc::StagedLedgerAuxAndPendingCoinbases;
 use crate::rpc::{AccountQuery, RpcScanStateSummaryScanStateJob};
@@ -38,8 +38,8 @@ pub enum LedgerReadKind {
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub enum LedgerReadRequest {
-    /// Delegator table requested by vrf state machine.
-    DelegatorTable(v2::LedgerHash, AccountPublicKey),
+    /// Delegator tables of the producers, requested by vrf state machine.
+    DelegatorTable(v2::LedgerHash, BTreeSet<AccountPublicKey>),
     // p2p rpcs
     GetNumAccounts(v2::LedgerHash),
     GetAccounts(v2::LedgerHash, Vec<AccountId>, Option<RpcId>),
@@ -53,8 +53,9 @@ pub enum LedgerReadRequest {
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum LedgerReadResponse {
-    /// Delegator table requested by vrf state machine.
-    DelegatorTable(Option<DelegatorTable>),
+    /// Delegator tables requested by vrf state machine. Producers
+    /// without delegators are missing from it.
+    DelegatorTable(Option<DelegatorTables>),
     // p2p rpcs
     GetNumAccounts(Option<(u64, v2::LedgerHash)>),
     GetAccounts(Vec<Account>, Option<RpcId>),
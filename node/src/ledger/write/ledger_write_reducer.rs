@@ -2,8 +2,11 @@ use redux::Dispatcher;
 
 use crate::{
     ledger_effectful::LedgerEffectfulAction,
-    transition_frontier::sync::{
-        ledger::staged::TransitionFrontierSyncLedgerStagedAction, TransitionFrontierSyncAction,
+    transition_frontier::{
+        sync::{
+            ledger::staged::TransitionFrontierSyncLedgerStagedAction, TransitionFrontierSyncAction,
+        },
+        TransitionFrontierAction,
     },
     Action, BlockProducerAction, State, Substate,
 };
@@ -144,6 +147,7 @@ impl LedgerWriteState {
                     dispatcher.push(TransitionFrontierSyncAction::BlocksNextApplySuccess {
                         hash,
                         just_emitted_a_proof: result.just_emitted_a_proof,
+                        archive_data: result.archive_data,
                     });
                 }
             },
@@ -159,6 +163,14 @@ impl LedgerWriteState {
                     dispatcher.push(TransitionFrontierSyncAction::CommitSuccess { result });
                 }
             }
+            (_, LedgerWriteResponse::FrontierRestore { result }) => match result {
+                Err(error) => {
+                    dispatcher.push(TransitionFrontierAction::RestoreError { error });
+                }
+                Ok(restored) => {
+                    dispatcher.push(TransitionFrontierAction::RestoreSuccess { restored });
+                }
+            },
         }
     }
 }
@@ -28,6 +28,7 @@ pub enum LedgerWriteKind {
     StagedLedgerDiffCreate,
     BlockApply,
     Commit,
+    FrontierRestore,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -51,6 +52,8 @@ pub enum LedgerWriteRequest {
         block: ArcBlockWithHash,
         pred_block: AppliedBlock,
         skip_verification: bool,
+        /// Whether to collect the data needed to archive the block.
+        archive: bool,
     },
     Commit {
         ledgers_to_keep: BTreeSet<v2::LedgerHash>,
@@ -59,6 +62,8 @@ pub enum LedgerWriteRequest {
         new_root: AppliedBlock,
         new_best_tip: AppliedBlock,
     },
+    /// Restore the transition frontier and its ledgers from the storage.
+    FrontierRestore,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -80,11 +85,31 @@ pub enum LedgerWriteResponse {
         best_tip_hash: v2::StateHash,
         result: CommitResult,
     },
+    FrontierRestore {
+        result: Result<Option<Arc<RestoredFrontier>>, String>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BlockApplyResult {
     pub just_emitted_a_proof: bool,
+    pub archive_data: Option<Arc<BlockApplyResultArchive>>,
+}
+
+/// Data needed to archive a block, which is only known after applying it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockApplyResultArchive {
+    /// Transactions of the block, including internal commands, in the
+    /// order they were applied.
+    pub transactions: Vec<(
+        v2::MinaTransactionTransactionStableV2,
+        v2::MinaBaseTransactionStatusStableV2,
+    )>,
+    /// Accounts referenced by the transactions, after applying the block,
+    /// together with their index in the ledger.
+    pub accounts_accessed: Vec<(u64, v2::MinaBaseAccountBinableArgStableV2)>,
+    /// Accounts created by the block, with the creation fee paid for them.
+    pub accounts_created: Vec<(v2::MinaBaseAccountIdStableV2, v2::CurrencyFeeStableV1)>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -93,6 +118,14 @@ pub struct CommitResult {
     pub needed_protocol_states: BTreeSet<v2::StateHash>,
 }
 
+/// Transition frontier restored from the storage, with all of its
+/// ledgers already present in the ledger service.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RestoredFrontier {
+    pub best_chain: Vec<AppliedBlock>,
+    pub needed_protocol_states: BTreeMap<v2::StateHash, v2::MinaStateProtocolStateValueStableV2>,
+}
+
 impl LedgerWriteRequest {
     pub fn kind(&self) -> LedgerWriteKind {
         match self {
@@ -100,6 +133,7 @@ impl LedgerWriteRequest {
             Self::StagedLedgerDiffCreate { .. } => LedgerWriteKind::StagedLedgerDiffCreate,
             Self::BlockApply { .. } => LedgerWriteKind::BlockApply,
             Self::Commit { .. } => LedgerWriteKind::Commit,
+            Self::FrontierRestore => LedgerWriteKind::FrontierRestore,
         }
     }
 }
@@ -111,6 +145,7 @@ impl LedgerWriteResponse {
             Self::StagedLedgerDiffCreate { .. } => LedgerWriteKind::StagedLedgerDiffCreate,
             Self::BlockApply { .. } => LedgerWriteKind::BlockApply,
             Self::Commit { .. } => LedgerWriteKind::Commit,
+            Self::FrontierRestore { .. } => LedgerWriteKind::FrontierRestore,
         }
     }
 }
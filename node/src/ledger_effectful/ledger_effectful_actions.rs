@@ -2,7 +2,10 @@ use crate::ledger::{
     read::{LedgerReadIdType, LedgerReadInitCallback, LedgerReadRequest},
     write::LedgerWriteRequest,
 };
-use openmina_core::requests::RequestId;
+use std::collections::BTreeMap;
+
+use mina_p2p_messages::v2;
+use openmina_core::{block::AppliedBlock, requests::RequestId};
 use redux::Callback;
 use serde::{Deserialize, Serialize};
 
@@ -17,6 +20,12 @@ pub enum LedgerEffectfulAction {
         callback: LedgerReadInitCallback,
         id: RequestId<LedgerReadIdType>,
     },
+    /// Persist the transition frontier, so that the node can resume from
+    /// it after restart.
+    FrontierPersist {
+        best_chain: Vec<AppliedBlock>,
+        needed_protocol_states: BTreeMap<v2::StateHash, v2::MinaStateProtocolStateValueStableV2>,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for LedgerEffectfulAction {
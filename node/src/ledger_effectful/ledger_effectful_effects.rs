@@ -46,5 +46,13 @@ pub fn ledger_effectful_effects<S>(
                 LedgerReadInitCallback::None => {}
             }
         }
+        LedgerEffectfulAction::FrontierPersist {
+            best_chain,
+            needed_protocol_states,
+        } => {
+            store
+                .service
+                .frontier_persist(best_chain, needed_protocol_states);
+        }
     }
 }
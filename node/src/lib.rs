@@ -33,6 +33,7 @@ pub mod block_producer;
 pub mod block_producer_effectful;
 pub mod consensus;
 pub mod daemon_json;
+pub mod event_sink;
 pub mod event_source;
 pub mod external_snark_worker;
 pub mod external_snark_worker_effectful;
@@ -48,5 +49,8 @@ pub mod transaction_pool;
 pub mod transition_frontier;
 pub mod watched_accounts;
 
+#[cfg(any(test, feature = "test-helpers"))]
+pub mod testing;
+
 pub type Store<S> = redux::Store<State, S, Action>;
 pub type Effects<S> = redux::Effects<State, S, Action>;
@@ -65,6 +65,8 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 P2pConnectionAction::Incoming(action) => action.action_event(&context),
             },
             P2pAction::Disconnection(action) => action.action_event(&context),
+            P2pAction::Ban(action) => action.action_event(&context),
+            P2pAction::PeerStore(action) => action.action_event(&context),
             P2pAction::Identify(action) => action.action_event(&context),
             P2pAction::Channels(action) => match action {
                 P2pChannelsAction::MessageReceived(action) => action.action_event(&context),
@@ -102,6 +104,9 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 P2pNetworkAction::Kad(action) => action.action_event(&context),
                 P2pNetworkAction::Pubsub(action) => action.action_event(&context),
                 P2pNetworkAction::Identify(action) => action.action_event(&context),
+                P2pNetworkAction::Bitswap(action) => action.action_event(&context),
+                P2pNetworkAction::NodeStatus(action) => action.action_event(&context),
+                P2pNetworkAction::Ping(action) => action.action_event(&context),
             },
         },
         Action::P2pEffectful(action) => match action {
@@ -111,6 +116,8 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                 P2pConnectionEffectfulAction::Incoming(action) => action.action_event(&context),
             },
             p2p::P2pEffectfulAction::Disconnection(action) => action.action_event(&context),
+            p2p::P2pEffectfulAction::Ban(action) => action.action_event(&context),
+            p2p::P2pEffectfulAction::PeerStore(action) => action.action_event(&context),
             p2p::P2pEffectfulAction::Network(action) => action.action_event(&context),
             p2p::P2pEffectfulAction::Initialize => {}
         },
@@ -0,0 +1,3 @@
+pub use ::p2p::ban::*;
+
+mod p2p_ban_actions;
@@ -4,7 +4,7 @@ use p2p::{
         rpc::{P2pRpcId, P2pRpcRequest, P2pRpcResponse},
         streaming_rpc::P2pStreamingRpcResponseFull,
     },
-    PeerId,
+    BitswapBlockError, BitswapCid, PeerId, StreamId,
 };
 use serde::{Deserialize, Serialize};
 
@@ -46,6 +46,17 @@ pub enum P2pCallbacksAction {
     RpcRespondBestTip {
         peer_id: PeerId,
     },
+    P2pNetworkNodeStatusRequest {
+        peer_id: PeerId,
+        stream_id: StreamId,
+    },
+    P2pNetworkBitswapResourceReady {
+        root: BitswapCid,
+    },
+    P2pNetworkBitswapResourceError {
+        root: BitswapCid,
+        error: BitswapBlockError,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for P2pCallbacksAction {
@@ -63,6 +74,9 @@ impl redux::EnablingCondition<crate::State> for P2pCallbacksAction {
             P2pCallbacksAction::RpcRespondBestTip { .. } => {
                 state.transition_frontier.best_tip().is_some()
             }
+            P2pCallbacksAction::P2pNetworkNodeStatusRequest { .. } => true,
+            P2pCallbacksAction::P2pNetworkBitswapResourceReady { .. } => true,
+            P2pCallbacksAction::P2pNetworkBitswapResourceError { .. } => true,
         }
     }
 }
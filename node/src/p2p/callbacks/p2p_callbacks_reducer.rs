@@ -7,8 +7,10 @@ use p2p::{
         rpc::{BestTipWithProof, P2pChannelsRpcAction, P2pRpcRequest, P2pRpcResponse},
         streaming_rpc::P2pStreamingRpcResponseFull,
     },
+    connection::outgoing::P2pConnectionOutgoingInitOpts,
     disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
-    PeerId,
+    Data, P2pNetworkNodeStatus, P2pNetworkNodeStatusAction, P2pNetworkNodeStatusBanned,
+    P2pNetworkNodeStatusPeer, P2pNetworkNodeStatusTrust, P2pNetworkNodeSyncStatus, PeerId,
 };
 use redux::{ActionMeta, ActionWithMeta, Dispatcher};
 
@@ -24,12 +26,12 @@ use crate::{
             },
             staged::{PeerStagedLedgerPartsFetchError, TransitionFrontierSyncLedgerStagedAction},
         },
-        PeerBlockFetchError, TransitionFrontierSyncAction,
+        PeerBlockFetchError, SyncPhase, TransitionFrontierSyncAction, TransitionFrontierSyncState,
     },
     watched_accounts::{
         WatchedAccountLedgerInitialState, WatchedAccountsLedgerInitialStateGetError,
     },
-    Action, ConsensusAction, State, WatchedAccountsAction,
+    Action, ConsensusAction, RpcAction, State, WatchedAccountsAction,
 };
 
 use super::P2pCallbacksAction;
@@ -290,9 +292,137 @@ impl crate::State {
                     best_tip: best_tip.clone(),
                 });
             }
+            P2pCallbacksAction::P2pNetworkNodeStatusRequest { peer_id, stream_id } => {
+                // without the genesis block there is nothing to report, the stream is just closed
+                let status = Self::p2p_network_node_status(state);
+                dispatcher.push(P2pNetworkNodeStatusAction::Respond {
+                    peer_id: *peer_id,
+                    stream_id: *stream_id,
+                    status: status.map(Box::new),
+                });
+            }
+            P2pCallbacksAction::P2pNetworkBitswapResourceReady { root } => {
+                let p2p = p2p_ready!(state.p2p, meta.time());
+                let limit = Option::<usize>::from(p2p.config.limits.bitswap_resource())
+                    .unwrap_or(usize::MAX);
+                let data = p2p
+                    .network
+                    .scheduler
+                    .bitswap_state
+                    .resource_data(root, limit)
+                    .map(Data::from);
+
+                for rpc_id in state.rpc.pending_bitswap_resource_gets(root) {
+                    match &data {
+                        Some(data) => dispatcher.push(RpcAction::P2pBitswapResourceGetSuccess {
+                            rpc_id,
+                            data: data.clone(),
+                        }),
+                        None => dispatcher.push(RpcAction::P2pBitswapResourceGetError {
+                            rpc_id,
+                            error: format!("resource {root} is no longer available"),
+                        }),
+                    }
+                }
+            }
+            P2pCallbacksAction::P2pNetworkBitswapResourceError { root, error } => {
+                for rpc_id in state.rpc.pending_bitswap_resource_gets(root) {
+                    dispatcher.push(RpcAction::P2pBitswapResourceGetError {
+                        rpc_id,
+                        error: error.to_string(),
+                    });
+                }
+            }
         }
     }
 
+    /// Node status in the format used by the OCaml node for `/mina/node-status`.
+    ///
+    /// Before the node has a best tip, the status reports the genesis block
+    /// as the protocol state and no block height.
+    fn p2p_network_node_status(state: &State) -> Option<P2pNetworkNodeStatus> {
+        let p2p = state.p2p.ready()?;
+        let best_tip = state.transition_frontier.best_tip();
+        let protocol_state_hash = match best_tip {
+            Some(best_tip) => best_tip.hash().clone(),
+            None => state.genesis_block()?.hash().clone(),
+        };
+
+        let sync = &state.transition_frontier.sync;
+        let sync_status = match sync {
+            TransitionFrontierSyncState::Idle if p2p.ready_peers_iter().next().is_none() => {
+                P2pNetworkNodeSyncStatus::Connecting
+            }
+            TransitionFrontierSyncState::Idle => P2pNetworkNodeSyncStatus::Listening,
+            _ => match sync.sync_phase() {
+                SyncPhase::Bootstrap => P2pNetworkNodeSyncStatus::Bootstrap,
+                SyncPhase::Catchup => P2pNetworkNodeSyncStatus::Catchup,
+                SyncPhase::Synced => P2pNetworkNodeSyncStatus::Synced,
+            },
+        };
+
+        let peers = p2p
+            .ready_peers_iter()
+            .filter_map(|(peer_id, _)| {
+                let P2pConnectionOutgoingInitOpts::LibP2P(opts) =
+                    p2p.peers.get(peer_id)?.dial_opts.as_ref()?
+                else {
+                    return None;
+                };
+                // node status only advertises TCP ports
+                if opts.transport.is_quic() {
+                    return None;
+                }
+                Some(P2pNetworkNodeStatusPeer {
+                    host: opts.host.to_string(),
+                    libp2p_port: opts.port,
+                    peer_id: peer_id.to_libp2p_string(),
+                })
+            })
+            .collect::<Vec<_>>();
+        let ban_statuses = peers
+            .iter()
+            .map(|peer| {
+                let trust = P2pNetworkNodeStatusTrust {
+                    trust: 0.0,
+                    banned: P2pNetworkNodeStatusBanned::Unbanned,
+                };
+                (peer.clone(), trust)
+            })
+            .collect();
+
+        Some(P2pNetworkNodeStatus {
+            node_ip_addr: p2p
+                .config
+                .external_addrs
+                .first()
+                .map_or_else(|| "0.0.0.0".to_owned(), ToString::to_string),
+            node_peer_id: p2p.my_id().to_libp2p_string(),
+            sync_status,
+            peers,
+            block_producers: state
+                .block_producer
+                .config()
+                .map(|config| config.pub_key.clone())
+                .into_iter()
+                .collect(),
+            protocol_state_hash,
+            ban_statuses,
+            k_block_hashes_and_timestamps: state
+                .transition_frontier
+                .best_chain
+                .iter()
+                .map(|block| {
+                    let timestamp_ms = u64::from(block.timestamp()) / 1_000_000;
+                    (block.hash().clone(), timestamp_ms.to_string())
+                })
+                .collect(),
+            git_commit: state.config.build.git.commit_hash.clone(),
+            uptime_minutes: state.uptime().as_secs() / 60,
+            block_height_opt: best_tip.map(|best_tip| best_tip.height()),
+        })
+    }
+
     fn handle_rpc_channels_request(
         dispatcher: &mut Dispatcher<Action, State>,
         state: &State,
@@ -4,11 +4,13 @@ use p2p::{
     network::identify::stream_effectful::P2pNetworkIdentifyStreamEffectfulAction,
 };
 
+pub mod ban;
 pub mod channels;
 pub mod connection;
 pub mod disconnection;
 pub mod network;
 pub mod peer;
+pub mod peer_store;
 
 pub mod callbacks;
 
@@ -95,9 +97,15 @@ impl_into_global_action!(connection::incoming::P2pConnectionIncomingAction);
 
 impl_into_global_action!(disconnection::P2pDisconnectionAction);
 
+impl_into_global_action!(ban::P2pBanAction);
+impl_into_global_action!(peer_store::P2pPeerStoreAction);
+
 impl_into_global_action!(network::P2pNetworkSchedulerAction);
 impl_into_global_action!(network::kad::P2pNetworkKademliaAction);
 impl_into_global_action!(network::pubsub::P2pNetworkPubsubAction);
+impl_into_global_action!(network::bitswap::P2pNetworkBitswapAction);
+impl_into_global_action!(network::node_status::P2pNetworkNodeStatusAction);
+impl_into_global_action!(network::ping::P2pNetworkPingAction);
 
 impl_into_global_action!(channels::P2pChannelsMessageReceivedAction);
 impl_into_global_action!(channels::signaling::discovery::P2pChannelsSignalingDiscoveryAction);
@@ -127,6 +135,8 @@ impl_into_global_action!(effectful p2p::P2pNetworkPnetEffectfulAction);
 impl_into_global_action!(effectful connection::incoming_effectful::P2pConnectionIncomingEffectfulAction);
 impl_into_global_action!(effectful connection::outgoing_effectful::P2pConnectionOutgoingEffectfulAction);
 impl_into_global_action!(effectful p2p::disconnection_effectful::P2pDisconnectionEffectfulAction);
+impl_into_global_action!(effectful p2p::ban_effectful::P2pBanEffectfulAction);
+impl_into_global_action!(effectful p2p::peer_store_effectful::P2pPeerStoreEffectfulAction);
 impl_into_global_action!(effectful network::pubsub::P2pNetworkPubsubEffectfulAction);
 impl_into_global_action!(effectful P2pNetworkIdentifyStreamEffectfulAction);
 impl_into_global_action!(effectful P2pChannelsEffectfulAction);
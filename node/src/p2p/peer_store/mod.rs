@@ -0,0 +1,3 @@
+pub use ::p2p::peer_store::*;
+
+mod p2p_peer_store_actions;
@@ -3,14 +3,14 @@ use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use ark_ff::fields::arithmetic::InvalidBigInt;
-use ledger::scan_state::currency::{Amount, Balance, Fee, Nonce, Slot};
+use ledger::scan_state::currency::{Amount, Balance, Fee, Magnitude, Nonce, Slot};
 use ledger::scan_state::transaction_logic::signed_command::SignedCommandPayload;
 use ledger::scan_state::transaction_logic::{self, signed_command, valid, Memo};
 use ledger::transaction_pool::{diff, ValidCommandWithHash};
-use ledger::Account;
+use ledger::{Account, AccountIndex};
 use mina_p2p_messages::bigint::BigInt;
 use mina_p2p_messages::v2::{
-    MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseTransactionStatusStableV2,
+    LedgerHash, MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseTransactionStatusStableV2,
     MinaBaseUserCommandStableV2, MinaTransactionTransactionStableV2,
     SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse, StateHash, TransactionHash,
 };
@@ -18,6 +18,8 @@ use openmina_core::block::AppliedBlock;
 use openmina_core::consensus::ConsensusConstants;
 use openmina_node_account::AccountPublicKey;
 use p2p::bootstrap::P2pNetworkKadBootstrapStats;
+use p2p::service_impl::bandwidth::P2pBandwidthStats;
+use p2p::{BitswapCid, ConnectionAddr, Data, P2pNetworkYamuxStats};
 pub use rpc_state::*;
 
 mod rpc_actions;
@@ -28,6 +30,9 @@ pub use rpc_reducer::collect_rpc_peers_info;
 
 mod rpc_impls;
 
+mod rpc_metrics;
+pub use rpc_metrics::*;
+
 pub use openmina_core::requests::{RpcId, RpcIdType};
 
 use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
@@ -37,16 +42,17 @@ use openmina_core::snark::SnarkJobId;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
+use crate::block_producer::BlockProducerWonSlot;
 use crate::external_snark_worker::{
     ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkSpecError,
 };
 use crate::p2p::connection::incoming::P2pConnectionIncomingInitOpts;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
-use crate::p2p::PeerId;
+use crate::p2p::{P2pNetworkNodeStatus, PeerId};
 use crate::snark_pool::{JobCommitment, JobSummary};
 use crate::stats::actions::{ActionStatsForBlock, ActionStatsSnapshot};
 use crate::stats::block_producer::{
-    BlockProductionAttempt, BlockProductionAttemptWonSlot, VrfEvaluatorStats,
+    BlockProducerKeyStats, BlockProductionAttempt, BlockProductionAttemptWonSlot, VrfEvaluatorStats,
 };
 use crate::stats::sync::SyncStatsSnapshot;
 
@@ -57,16 +63,34 @@ pub enum RpcRequest {
     ActionStatsGet(ActionStatsQuery),
     SyncStatsGet(SyncStatsQuery),
     BlockProducerStatsGet,
+    BlockProducerScheduleGet,
+    MetricsGet,
     MessageProgressGet,
     PeersGet,
     P2pConnectionOutgoing(P2pConnectionOutgoingInitOpts),
     P2pConnectionIncoming(P2pConnectionIncomingInitOpts),
+    P2pNodeStatusGet(PeerId),
+    P2pBansGet,
+    P2pBanAdd(RpcP2pBanAddRequest),
+    P2pBanRemove(PeerId),
+    P2pBandwidthGet,
+    P2pYamuxStatsGet,
+    /// Makes the data available to other peers over bitswap.
+    P2pBitswapResourceAdd(Data),
+    /// Fetches the resource with the given root from peers over bitswap.
+    P2pBitswapResourceGet(BitswapCid),
     ScanStateSummaryGet(RpcScanStateSummaryGetQuery),
     SnarkPoolGet,
-    SnarkPoolJobGet { job_id: SnarkJobId },
+    SnarkPoolJobGet {
+        job_id: SnarkJobId,
+    },
     SnarkerConfig,
-    SnarkerJobCommit { job_id: SnarkJobId },
-    SnarkerJobSpec { job_id: SnarkJobId },
+    SnarkerJobCommit {
+        job_id: SnarkJobId,
+    },
+    SnarkerJobSpec {
+        job_id: SnarkJobId,
+    },
     SnarkerWorkers,
     HealthCheck,
     ReadinessCheck,
@@ -79,6 +103,9 @@ pub enum RpcRequest {
     BestChain(MaxLength),
     ConsensusConstantsGet,
     TransactionStatusGet(MinaBaseUserCommandStableV2),
+    /// Multishot request, answered with a [`RpcTransitionFrontierEvent`]
+    /// whenever the node starts syncing or syncs to a new best chain.
+    TransitionFrontierSubscribe,
 }
 
 pub type MaxLength = u32;
@@ -169,6 +196,35 @@ pub struct RpcPeerInfo {
     pub address: Option<String>,
     pub incoming: bool,
     pub time: u64,
+    /// Round trip time of the last ping, in milliseconds.
+    #[serde(default)]
+    pub rtt_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcP2pBan {
+    pub peer_id: PeerId,
+    pub reason: String,
+    pub since: u64,
+    /// `None` if the ban is permanent.
+    pub until: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcP2pYamuxConnectionStats {
+    pub addr: ConnectionAddr,
+    pub peer_id: Option<PeerId>,
+    pub yamux: P2pNetworkYamuxStats,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcP2pBanAddRequest {
+    pub peer_id: PeerId,
+    /// Ban duration in seconds, the ban is permanent if `None`.
+    #[serde(default)]
+    pub duration_secs: Option<u64>,
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -342,8 +398,18 @@ pub type RpcStatusGetResponse = Option<RpcNodeStatus>;
 pub type RpcActionStatsGetResponse = Option<ActionStatsResponse>;
 pub type RpcSyncStatsGetResponse = Option<Vec<SyncStatsSnapshot>>;
 pub type RpcBlockProducerStatsGetResponse = Option<RpcBlockProducerStats>;
+pub type RpcBlockProducerScheduleGetResponse = Option<RpcBlockProducerSchedule>;
+pub type RpcMetricsGetResponse = RpcMetrics;
 pub type RpcPeersGetResponse = Vec<RpcPeerInfo>;
 pub type RpcP2pConnectionOutgoingResponse = Result<(), String>;
+pub type RpcP2pNodeStatusGetResponse = Result<P2pNetworkNodeStatus, String>;
+pub type RpcP2pBansGetResponse = Vec<RpcP2pBan>;
+pub type RpcP2pBanAddResponse = Result<(), String>;
+pub type RpcP2pBanRemoveResponse = Result<(), String>;
+pub type RpcP2pBandwidthGetResponse = P2pBandwidthStats;
+pub type RpcP2pYamuxStatsGetResponse = Vec<RpcP2pYamuxConnectionStats>;
+pub type RpcP2pBitswapResourceAddResponse = Result<BitswapCid, String>;
+pub type RpcP2pBitswapResourceGetResponse = Result<Data, String>;
 pub type RpcScanStateSummaryGetResponse = Result<RpcScanStateSummary, String>;
 pub type RpcSnarkPoolGetResponse = Vec<RpcSnarkPoolJobSummary>;
 pub type RpcSnarkPoolJobGetResponse = Option<RpcSnarkPoolJobFull>;
@@ -355,6 +421,20 @@ pub type RpcTransitionFrontierUserCommandsResponse = Vec<MinaBaseUserCommandStab
 pub type RpcBestChainResponse = Vec<AppliedBlock>;
 pub type RpcConsensusConstantsGetResponse = ConsensusConstants;
 pub type RpcTransactionStatusGetResponse = TransactionStatus;
+pub type RpcTransitionFrontierSubscribeResponse = RpcTransitionFrontierEvent;
+
+/// Snapshot of the transition frontier, pushed to the subscribers of
+/// [`RpcRequest::TransitionFrontierSubscribe`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcTransitionFrontierEvent {
+    /// Whether the transition frontier is synced, `false` while it syncs
+    /// towards a new best tip.
+    pub synced: bool,
+    pub best_chain: Vec<AppliedBlock>,
+    /// Whether the best tip changed to one that doesn't extend the previous
+    /// best tip.
+    pub reorg: bool,
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone, strum_macros::Display)]
 #[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
@@ -383,7 +463,8 @@ pub struct RpcTransactionInjectedPayment {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RpcTransactionInjectedCommand {
     Payment(RpcTransactionInjectedPayment),
-    Delegation,
+    /// Same as a payment, with `is_delegation` set and a zero amount.
+    Delegation(RpcTransactionInjectedPayment),
     Zkapp(valid::UserCommand),
 }
 
@@ -419,8 +500,17 @@ impl From<ValidCommandWithHash> for RpcTransactionInjectedCommand {
                             nonce: signedcmd.nonce(),
                         })
                     }
-                    transaction_logic::signed_command::Body::StakeDelegation(_) => {
-                        todo!("inject stake delegation")
+                    transaction_logic::signed_command::Body::StakeDelegation(ref delegation) => {
+                        Self::Delegation(RpcTransactionInjectedPayment {
+                            amount: Amount::zero(),
+                            fee: signedcmd.fee(),
+                            from: signedcmd.fee_payer_pk().clone().into(),
+                            to: delegation.receiver_pk().clone().into(),
+                            hash: value.hash.to_string(),
+                            is_delegation: true,
+                            memo: signedcmd.payload.common.memo.to_string(),
+                            nonce: signedcmd.nonce(),
+                        })
                     }
                 }
             }
@@ -456,6 +546,8 @@ pub struct RpcNodeStatus {
     pub snark_pool: RpcNodeStatusSnarkPool,
     pub transaction_pool: RpcNodeStatusTransactionPool,
     pub current_block_production_attempt: Option<BlockProductionAttempt>,
+    /// Public keys the node produces blocks with.
+    pub block_producers: Vec<AccountPublicKey>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -499,13 +591,60 @@ pub struct RpcBlockProducerStats {
     pub current_epoch: Option<u32>,
     pub epoch_start: Option<u32>,
     pub epoch_end: Option<u32>,
+    /// First of the block producer keys.
     pub public_key: AccountPublicKey,
+    /// Stats of each block producer key.
+    pub producers: BTreeMap<NonZeroCurvePoint, BlockProducerKeyStats>,
     pub attempts: Vec<BlockProductionAttempt>,
     pub future_won_slots: Vec<BlockProductionAttemptWonSlot>,
     pub current_epoch_vrf_stats: Option<VrfEvaluatorStats>,
     pub vrf_stats: BTreeMap<u32, VrfEvaluatorStats>,
 }
 
+/// Won slots of the block producer keys in the current and next epoch.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcBlockProducerSchedule {
+    pub current_time: redux::Timestamp,
+    pub current_global_slot: Option<u32>,
+    pub current_epoch: Option<u32>,
+    /// Slots up to this one have been evaluated, so the won slots after it
+    /// are still unknown.
+    pub latest_evaluated_global_slot: u32,
+    pub won_slots: Vec<RpcBlockProducerScheduledSlot>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcBlockProducerScheduledSlot {
+    pub slot_time: redux::Timestamp,
+    pub global_slot: u32,
+    pub epoch: u32,
+    pub producer: NonZeroCurvePoint,
+    /// Account whose delegated stake won the slot.
+    pub delegator: NonZeroCurvePoint,
+    pub delegator_index: AccountIndex,
+    pub vrf_output: String,
+    /// Fractional vrf output and the threshold it had to be below.
+    pub value_with_threshold: Option<(f64, f64)>,
+    pub staking_ledger_hash: LedgerHash,
+}
+
+impl From<&BlockProducerWonSlot> for RpcBlockProducerScheduledSlot {
+    fn from(won_slot: &BlockProducerWonSlot) -> Self {
+        let (delegator, delegator_index) = won_slot.delegator.clone();
+        Self {
+            slot_time: won_slot.slot_time,
+            global_slot: won_slot.global_slot(),
+            epoch: won_slot.epoch(),
+            producer: won_slot.producer.clone(),
+            delegator,
+            delegator_index,
+            vrf_output: won_slot.vrf_output.to_string(),
+            value_with_threshold: won_slot.value_with_threshold,
+            staking_ledger_hash: won_slot.staking_ledger_hash.clone(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcSnarkerConfig {
     pub public_key: NonZeroCurvePoint,
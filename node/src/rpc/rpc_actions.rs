@@ -6,17 +6,18 @@ use openmina_core::block::AppliedBlock;
 use openmina_core::snark::SnarkJobId;
 use openmina_core::ActionEvent;
 use openmina_node_account::AccountPublicKey;
-use p2p::PeerId;
+use p2p::{BitswapCid, Data, PeerId};
 use serde::{Deserialize, Serialize};
 
 use crate::external_snark_worker::SnarkWorkId;
 use crate::p2p::connection::incoming::P2pConnectionIncomingInitOpts;
 use crate::p2p::connection::outgoing::{P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts};
 use crate::p2p::connection::P2pConnectionResponse;
+use crate::p2p::P2pNetworkNodeStatus;
 
 use super::{
-    ActionStatsQuery, RpcId, RpcScanStateSummaryGetQuery, RpcScanStateSummaryScanStateJob,
-    SyncStatsQuery,
+    ActionStatsQuery, RpcId, RpcP2pBanAddRequest, RpcScanStateSummaryGetQuery,
+    RpcScanStateSummaryScanStateJob, SyncStatsQuery,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
@@ -41,6 +42,12 @@ pub enum RpcAction {
     BlockProducerStatsGet {
         rpc_id: RpcId,
     },
+    BlockProducerScheduleGet {
+        rpc_id: RpcId,
+    },
+    MetricsGet {
+        rpc_id: RpcId,
+    },
 
     MessageProgressGet {
         rpc_id: RpcId,
@@ -89,6 +96,54 @@ pub enum RpcAction {
         rpc_id: RpcId,
     },
 
+    P2pBansGet {
+        rpc_id: RpcId,
+    },
+    P2pBanAdd {
+        rpc_id: RpcId,
+        request: RpcP2pBanAddRequest,
+    },
+    P2pBanRemove {
+        rpc_id: RpcId,
+        peer_id: PeerId,
+    },
+    P2pBandwidthGet {
+        rpc_id: RpcId,
+    },
+    P2pYamuxStatsGet {
+        rpc_id: RpcId,
+    },
+
+    P2pBitswapResourceAdd {
+        rpc_id: RpcId,
+        data: Data,
+    },
+    P2pBitswapResourceGetInit {
+        rpc_id: RpcId,
+        root: BitswapCid,
+    },
+    P2pBitswapResourceGetSuccess {
+        rpc_id: RpcId,
+        data: Data,
+    },
+    P2pBitswapResourceGetError {
+        rpc_id: RpcId,
+        error: String,
+    },
+
+    P2pNodeStatusGetInit {
+        rpc_id: RpcId,
+        peer_id: PeerId,
+    },
+    P2pNodeStatusGetSuccess {
+        rpc_id: RpcId,
+        status: Box<P2pNetworkNodeStatus>,
+    },
+    P2pNodeStatusGetError {
+        rpc_id: RpcId,
+        error: String,
+    },
+
     ScanStateSummaryGetInit {
         rpc_id: RpcId,
         query: RpcScanStateSummaryGetQuery,
@@ -203,6 +258,15 @@ pub enum RpcAction {
         tx: MinaBaseUserCommandStableV2,
     },
 
+    TransitionFrontierSubscribe {
+        rpc_id: RpcId,
+    },
+    /// Push the current state of the transition frontier to the subscribers.
+    TransitionFrontierNotify,
+    TransitionFrontierUnsubscribe {
+        rpc_id: RpcId,
+    },
+
     Finish {
         rpc_id: RpcId,
     },
@@ -223,6 +287,8 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
             RpcAction::ActionStatsGet { .. } => true,
             RpcAction::SyncStatsGet { .. } => true,
             RpcAction::BlockProducerStatsGet { .. } => true,
+            RpcAction::BlockProducerScheduleGet { .. } => true,
+            RpcAction::MetricsGet { .. } => true,
             RpcAction::MessageProgressGet { .. } => true,
             RpcAction::PeersGet { .. } => true,
             RpcAction::P2pConnectionOutgoingInit { rpc_id, .. } => {
@@ -246,6 +312,38 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
             RpcAction::P2pConnectionIncomingInit { rpc_id, .. } => {
                 !state.rpc.requests.contains_key(rpc_id)
             }
+            RpcAction::P2pBansGet { .. } => true,
+            RpcAction::P2pBanAdd { .. } => true,
+            RpcAction::P2pBanRemove { .. } => true,
+            RpcAction::P2pBandwidthGet { .. } => true,
+            RpcAction::P2pYamuxStatsGet { .. } => true,
+            RpcAction::P2pBitswapResourceAdd { .. } => true,
+            RpcAction::P2pBitswapResourceGetInit { rpc_id, .. } => {
+                !state.rpc.requests.contains_key(rpc_id)
+            }
+            RpcAction::P2pBitswapResourceGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_pending()),
+            RpcAction::P2pBitswapResourceGetError { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_pending()),
+            RpcAction::P2pNodeStatusGetInit { rpc_id, .. } => {
+                !state.rpc.requests.contains_key(rpc_id)
+            }
+            RpcAction::P2pNodeStatusGetSuccess { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_pending()),
+            RpcAction::P2pNodeStatusGetError { rpc_id, .. } => state
+                .rpc
+                .requests
+                .get(rpc_id)
+                .map_or(false, |v| v.status.is_pending()),
             RpcAction::P2pConnectionIncomingPending { rpc_id } => state
                 .rpc
                 .requests
@@ -297,6 +395,15 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
             RpcAction::ConsensusConstantsGet { .. } => true,
             RpcAction::BestChain { .. } => state.transition_frontier.best_tip().is_some(),
             RpcAction::TransactionStatusGet { .. } => true,
+            RpcAction::TransitionFrontierSubscribe { .. } => true,
+            RpcAction::TransitionFrontierNotify => state
+                .rpc
+                .transition_frontier_subscription_rpc_ids()
+                .next()
+                .is_some(),
+            RpcAction::TransitionFrontierUnsubscribe { rpc_id } => {
+                state.rpc.requests.contains_key(rpc_id)
+            }
             RpcAction::LedgerAccountsGetInit { .. } => {
                 state.transition_frontier.best_tip().is_some()
             }
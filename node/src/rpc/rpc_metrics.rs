@@ -0,0 +1,371 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write};
+use std::time::Duration;
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+use strum::VariantNames;
+
+use crate::stats::actions::ActionStatsSnapshot;
+use crate::stats::block_producer::{BlockProductionAttempt, BlockProductionStatus};
+use crate::stats::sync::{SyncLedger, SyncStatsSnapshot};
+use crate::transition_frontier::sync::TransitionFrontierSyncState;
+
+/// Node metrics, rendered in the Prometheus text format by
+/// [`RpcMetrics::to_prometheus`].
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RpcMetrics {
+    pub peers: RpcMetricsPeers,
+    pub best_tip_height: Option<u32>,
+    pub best_tip_global_slot: Option<u32>,
+    pub sync_status: String,
+    /// Latest sync, `None` if stats are disabled or the node never synced.
+    pub sync: Option<SyncStatsSnapshot>,
+    pub transaction_pool_size: usize,
+    pub snark_pool_jobs: usize,
+    pub snark_pool_snarks: usize,
+    /// `None` if stats are disabled.
+    pub actions: Option<ActionStatsSnapshot>,
+    /// Number of block production attempts by status, over the retained
+    /// history of attempts.
+    pub block_production_attempts: BTreeMap<String, usize>,
+    pub p2p_received_bytes: u64,
+    pub p2p_sent_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct RpcMetricsPeers {
+    pub connecting: usize,
+    pub ready: usize,
+    pub disconnecting: usize,
+    pub disconnected: usize,
+}
+
+impl RpcMetrics {
+    pub fn collect_peers(state: &crate::State) -> RpcMetricsPeers {
+        let mut peers = RpcMetricsPeers::default();
+        let Some(p2p) = state.p2p.ready() else {
+            return peers;
+        };
+        for peer in p2p.peers.values() {
+            let count = match &peer.status {
+                p2p::P2pPeerStatus::Connecting(_) => &mut peers.connecting,
+                p2p::P2pPeerStatus::Ready(_) => &mut peers.ready,
+                p2p::P2pPeerStatus::Disconnecting { .. } => &mut peers.disconnecting,
+                p2p::P2pPeerStatus::Disconnected { .. } => &mut peers.disconnected,
+            };
+            *count = count.saturating_add(1);
+        }
+        peers
+    }
+
+    pub fn collect_block_production_attempts(
+        attempts: &[BlockProductionAttempt],
+    ) -> BTreeMap<String, usize> {
+        attempts.iter().fold(BTreeMap::new(), |mut acc, attempt| {
+            let status = match &attempt.status {
+                BlockProductionStatus::Committed => "committed",
+                BlockProductionStatus::Canonical { .. } => "canonical",
+                BlockProductionStatus::Orphaned { .. } => "orphaned",
+                BlockProductionStatus::Discarded { .. } => "discarded",
+                _ => "pending",
+            };
+            let count = acc.entry(status.to_owned()).or_default();
+            *count = count.saturating_add(1);
+            acc
+        })
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format.
+    pub fn to_prometheus(&self) -> String {
+        let mut w = PrometheusWriter::default();
+
+        w.header(
+            "openmina_peers",
+            "gauge",
+            "Number of peers by connection state.",
+        );
+        let peers = &self.peers;
+        w.sample(
+            "openmina_peers",
+            &[("state", "connecting")],
+            peers.connecting,
+        );
+        w.sample("openmina_peers", &[("state", "ready")], peers.ready);
+        w.sample(
+            "openmina_peers",
+            &[("state", "disconnecting")],
+            peers.disconnecting,
+        );
+        w.sample(
+            "openmina_peers",
+            &[("state", "disconnected")],
+            peers.disconnected,
+        );
+
+        if let Some(height) = self.best_tip_height {
+            w.header(
+                "openmina_best_tip_height",
+                "gauge",
+                "Height of the best tip.",
+            );
+            w.sample("openmina_best_tip_height", &[], height);
+        }
+        if let Some(slot) = self.best_tip_global_slot {
+            w.header(
+                "openmina_best_tip_global_slot",
+                "gauge",
+                "Global slot of the best tip.",
+            );
+            w.sample("openmina_best_tip_global_slot", &[], slot);
+        }
+
+        w.header(
+            "openmina_sync_status",
+            "gauge",
+            "Status of the transition frontier sync, 1 for the current one.",
+        );
+        for &status in TransitionFrontierSyncState::VARIANTS {
+            let current = status == self.sync_status;
+            w.sample(
+                "openmina_sync_status",
+                &[("status", status)],
+                u8::from(current),
+            );
+        }
+
+        if let Some(sync) = &self.sync {
+            w.header(
+                "openmina_sync_phase_duration_seconds",
+                "gauge",
+                "Duration of the phases of the latest sync, finished ones only.",
+            );
+            for (ledger, phase, duration) in sync_phases(sync) {
+                let labels = [("ledger", ledger), ("phase", phase)];
+                w.sample(
+                    "openmina_sync_phase_duration_seconds",
+                    &labels,
+                    seconds(duration),
+                );
+            }
+        }
+
+        w.header(
+            "openmina_transaction_pool_size",
+            "gauge",
+            "Number of transactions in the pool.",
+        );
+        w.sample(
+            "openmina_transaction_pool_size",
+            &[],
+            self.transaction_pool_size,
+        );
+        w.header(
+            "openmina_snark_pool_jobs",
+            "gauge",
+            "Number of jobs in the snark pool.",
+        );
+        w.sample("openmina_snark_pool_jobs", &[], self.snark_pool_jobs);
+        w.header(
+            "openmina_snark_pool_snarks",
+            "gauge",
+            "Number of jobs in the snark pool which have a snark.",
+        );
+        w.sample("openmina_snark_pool_snarks", &[], self.snark_pool_snarks);
+
+        if let Some(actions) = &self.actions {
+            let name = "openmina_action_duration_seconds";
+            w.header(
+                name,
+                "histogram",
+                "Time from an action until the next one, by action kind.",
+            );
+            for (kind, ranges) in actions.iter() {
+                let kind = kind.to_string();
+                let (mut count, mut sum) = (0u64, 0u64);
+                for (le, range) in ranges.ranges() {
+                    count = count.saturating_add(range.total_calls);
+                    sum = sum.saturating_add(range.total_duration);
+                    let le = le.map_or_else(|| "+Inf".to_owned(), seconds_from_nanos);
+                    w.sample(
+                        &format!("{name}_bucket"),
+                        &[("kind", &kind), ("le", &le)],
+                        count,
+                    );
+                }
+                w.sample(
+                    &format!("{name}_sum"),
+                    &[("kind", &kind)],
+                    seconds_from_nanos(sum),
+                );
+                w.sample(&format!("{name}_count"), &[("kind", &kind)], count);
+            }
+        }
+
+        w.header(
+            "openmina_block_production_attempts",
+            "gauge",
+            "Number of recent block production attempts by outcome.",
+        );
+        for (status, count) in &self.block_production_attempts {
+            w.sample(
+                "openmina_block_production_attempts",
+                &[("status", status)],
+                count,
+            );
+        }
+
+        w.header(
+            "openmina_p2p_received_bytes_total",
+            "counter",
+            "Bytes received from peers.",
+        );
+        w.sample(
+            "openmina_p2p_received_bytes_total",
+            &[],
+            self.p2p_received_bytes,
+        );
+        w.header(
+            "openmina_p2p_sent_bytes_total",
+            "counter",
+            "Bytes sent to peers.",
+        );
+        w.sample("openmina_p2p_sent_bytes_total", &[], self.p2p_sent_bytes);
+
+        w.0
+    }
+}
+
+/// Durations of the finished phases of a sync, with the ledger they are
+/// about, or `"none"`.
+fn sync_phases(sync: &SyncStatsSnapshot) -> Vec<(&'static str, &'static str, Duration)> {
+    fn elapsed(start: Option<Timestamp>, end: Option<Timestamp>) -> Option<Duration> {
+        end?.checked_sub(start?)
+    }
+
+    let ledgers: [(&'static str, &Option<SyncLedger>); 3] = [
+        ("staking_epoch", &sync.ledgers.staking_epoch),
+        ("next_epoch", &sync.ledgers.next_epoch),
+        ("root", &sync.ledgers.root),
+    ];
+
+    let mut phases = Vec::new();
+    for (name, ledger) in ledgers {
+        let Some(ledger) = ledger else {
+            continue;
+        };
+        let (snarked, staged) = (&ledger.snarked, &ledger.staged);
+        let ledger_phases = [
+            (
+                "snarked_fetch_hashes",
+                elapsed(snarked.fetch_hashes_start, snarked.fetch_hashes_end),
+            ),
+            (
+                "snarked_fetch_accounts",
+                elapsed(snarked.fetch_accounts_start, snarked.fetch_accounts_end),
+            ),
+            (
+                "staged_fetch_parts",
+                elapsed(staged.fetch_parts_start, staged.fetch_parts_end),
+            ),
+            (
+                "staged_reconstruct",
+                elapsed(staged.reconstruct_start, staged.reconstruct_end),
+            ),
+        ];
+        phases.extend(
+            ledger_phases
+                .into_iter()
+                .filter_map(|(phase, duration)| Some((name, phase, duration?))),
+        );
+    }
+
+    let blocks_fetch = elapsed(
+        sync.blocks.iter().filter_map(|b| b.fetch_start).min(),
+        sync.blocks.iter().filter_map(|b| b.fetch_end).max(),
+    );
+    let blocks_apply = elapsed(
+        sync.blocks.iter().filter_map(|b| b.apply_start).min(),
+        sync.blocks.iter().filter_map(|b| b.apply_end).max(),
+    );
+    let total = elapsed(Some(sync.best_tip_received), sync.synced);
+    phases.extend(
+        [
+            ("blocks_fetch", blocks_fetch),
+            ("blocks_apply", blocks_apply),
+            ("total", total),
+        ]
+        .into_iter()
+        .filter_map(|(phase, duration)| Some(("none", phase, duration?))),
+    );
+
+    phases
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{}", duration.as_secs_f64())
+}
+
+fn seconds_from_nanos(nanos: u64) -> String {
+    seconds(Duration::from_nanos(nanos))
+}
+
+#[derive(Default)]
+struct PrometheusWriter(String);
+
+impl PrometheusWriter {
+    fn header(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.0, "# HELP {name} {help}");
+        let _ = writeln!(self.0, "# TYPE {name} {kind}");
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.0.push_str(name);
+        if !labels.is_empty() {
+            self.0.push('{');
+            for (i, (key, value)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.0.push(',');
+                }
+                let value = value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n");
+                let _ = write!(self.0, "{key}=\"{value}\"");
+            }
+            self.0.push('}');
+        }
+        let _ = writeln!(self.0, " {value}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_prometheus() {
+        let metrics = RpcMetrics {
+            peers: RpcMetricsPeers {
+                ready: 3,
+                ..Default::default()
+            },
+            best_tip_height: Some(42),
+            sync_status: "Synced".to_owned(),
+            block_production_attempts: [("canonical".to_owned(), 2)].into(),
+            p2p_received_bytes: 1024,
+            ..Default::default()
+        };
+        let text = metrics.to_prometheus();
+
+        assert!(text.contains("# TYPE openmina_peers gauge\n"));
+        assert!(text.contains("openmina_peers{state=\"ready\"} 3\n"));
+        assert!(text.contains("openmina_best_tip_height 42\n"));
+        assert!(!text.contains("openmina_best_tip_global_slot"));
+        assert!(text.contains("openmina_sync_status{status=\"Synced\"} 1\n"));
+        assert!(text.contains("openmina_sync_status{status=\"Idle\"} 0\n"));
+        assert!(text.contains("openmina_sync_status{status=\"BlocksPending\"} 0\n"));
+        assert!(text.contains("openmina_block_production_attempts{status=\"canonical\"} 2\n"));
+        assert!(text.contains("openmina_p2p_received_bytes_total 1024\n"));
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use openmina_core::{
     block::AppliedBlock,
     bug_condition,
@@ -5,9 +7,12 @@ use openmina_core::{
     transaction::TransactionWithHash,
 };
 use p2p::{
+    ban::{P2pBanAction, P2pBanReason},
+    bitswap_split,
     connection::{incoming::P2pConnectionIncomingAction, outgoing::P2pConnectionOutgoingAction},
     webrtc::P2pConnectionResponse,
-    PeerId,
+    P2pNetworkBitswapAction, P2pNetworkBitswapResourceState, P2pNetworkNodeStatusAction, PeerId,
+    BITSWAP_MAX_BLOCK_SIZE,
 };
 use redux::ActionWithMeta;
 
@@ -19,8 +24,9 @@ use crate::{
 };
 
 use super::{
-    PeerConnectionStatus, RpcAction, RpcPeerInfo, RpcRequest, RpcRequestExtraData, RpcRequestState,
-    RpcRequestStatus, RpcScanStateSummaryGetQuery, RpcSnarkerConfig, RpcState,
+    PeerConnectionStatus, RpcAction, RpcP2pBan, RpcP2pYamuxConnectionStats, RpcPeerInfo,
+    RpcRequest, RpcRequestExtraData, RpcRequestState, RpcRequestStatus,
+    RpcScanStateSummaryGetQuery, RpcSnarkerConfig, RpcState, RpcTransitionFrontierEvent,
 };
 
 impl RpcState {
@@ -60,6 +66,14 @@ impl RpcState {
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(RpcEffectfulAction::BlockProducerStatsGet { rpc_id: *rpc_id });
             }
+            RpcAction::BlockProducerScheduleGet { rpc_id } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::BlockProducerScheduleGet { rpc_id: *rpc_id });
+            }
+            RpcAction::MetricsGet { rpc_id } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::MetricsGet { rpc_id: *rpc_id });
+            }
             RpcAction::MessageProgressGet { rpc_id } => {
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(RpcEffectfulAction::MessageProgressGet { rpc_id: *rpc_id });
@@ -216,6 +230,242 @@ impl RpcState {
                 dispatcher
                     .push(RpcEffectfulAction::P2pConnectionIncomingSuccess { rpc_id: *rpc_id });
             }
+            RpcAction::P2pBansGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let bans = state
+                    .p2p
+                    .ready()
+                    .map(|p2p| {
+                        p2p.ban
+                            .bans
+                            .iter()
+                            .map(|(peer_id, ban)| RpcP2pBan {
+                                peer_id: *peer_id,
+                                reason: ban.reason.to_string(),
+                                since: ban.since.into(),
+                                until: ban.until.map(Into::into),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                dispatcher.push(RpcEffectfulAction::P2pBansGet {
+                    rpc_id: *rpc_id,
+                    bans,
+                });
+            }
+            RpcAction::P2pBanAdd { rpc_id, request } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = match state.p2p.ready() {
+                    None => Err("p2p is not initialized".to_owned()),
+                    Some(p2p) if p2p.my_id() == request.peer_id => {
+                        Err("cannot ban the local node".to_owned())
+                    }
+                    Some(_) => {
+                        dispatcher.push(P2pBanAction::Add {
+                            peer_id: request.peer_id,
+                            reason: P2pBanReason::Manual(
+                                request
+                                    .reason
+                                    .clone()
+                                    .unwrap_or_else(|| "no reason given".to_owned()),
+                            ),
+                            duration: request.duration_secs.map(Duration::from_secs),
+                        });
+                        Ok(())
+                    }
+                };
+                dispatcher.push(RpcEffectfulAction::P2pBanAdd {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::P2pBanRemove { rpc_id, peer_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = match state.p2p.ready() {
+                    None => Err("p2p is not initialized".to_owned()),
+                    Some(p2p) if !p2p.ban.is_banned(peer_id) => {
+                        Err(format!("peer {peer_id} is not banned"))
+                    }
+                    Some(_) => {
+                        dispatcher.push(P2pBanAction::Remove { peer_id: *peer_id });
+                        Ok(())
+                    }
+                };
+                dispatcher.push(RpcEffectfulAction::P2pBanRemove {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::P2pBandwidthGet { rpc_id } => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::P2pBandwidthGet { rpc_id: *rpc_id });
+            }
+            RpcAction::P2pYamuxStatsGet { rpc_id } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let stats = collect_rpc_yamux_stats(state);
+                dispatcher.push(RpcEffectfulAction::P2pYamuxStatsGet {
+                    rpc_id: *rpc_id,
+                    stats,
+                });
+            }
+            RpcAction::P2pBitswapResourceAdd { rpc_id, data } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let response = match state.p2p.ready() {
+                    None => Err("p2p is not initialized".to_owned()),
+                    Some(_) => {
+                        let (root, _) = bitswap_split(data, BITSWAP_MAX_BLOCK_SIZE);
+                        dispatcher
+                            .push(P2pNetworkBitswapAction::AddResource { data: data.clone() });
+                        Ok(root)
+                    }
+                };
+                dispatcher.push(RpcEffectfulAction::P2pBitswapResourceAdd {
+                    rpc_id: *rpc_id,
+                    response,
+                });
+            }
+            RpcAction::P2pBitswapResourceGetInit { rpc_id, root } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::P2pBitswapResourceGet(*root),
+                    status: RpcRequestStatus::Pending { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let Some(p2p) = state.p2p.ready() else {
+                    dispatcher.push(RpcAction::P2pBitswapResourceGetError {
+                        rpc_id: *rpc_id,
+                        error: "p2p is not initialized".to_owned(),
+                    });
+                    return;
+                };
+
+                let bitswap_state = &p2p.network.scheduler.bitswap_state;
+                match bitswap_state.resources.get(root) {
+                    None => dispatcher.push(P2pNetworkBitswapAction::Want { root: *root }),
+                    // the callback will finish the request
+                    Some(P2pNetworkBitswapResourceState::Pending { .. }) => {}
+                    Some(P2pNetworkBitswapResourceState::Ready { .. }) => {
+                        let limit = Option::<usize>::from(p2p.config.limits.bitswap_resource())
+                            .unwrap_or(usize::MAX);
+                        match bitswap_state.resource_data(root, limit) {
+                            Some(data) => {
+                                dispatcher.push(RpcAction::P2pBitswapResourceGetSuccess {
+                                    rpc_id: *rpc_id,
+                                    data: data.into(),
+                                })
+                            }
+                            None => dispatcher.push(RpcAction::P2pBitswapResourceGetError {
+                                rpc_id: *rpc_id,
+                                error: format!("resource {root} is not available"),
+                            }),
+                        }
+                    }
+                    Some(P2pNetworkBitswapResourceState::Error { error, .. }) => {
+                        dispatcher.push(RpcAction::P2pBitswapResourceGetError {
+                            rpc_id: *rpc_id,
+                            error: error.to_string(),
+                        })
+                    }
+                }
+            }
+            RpcAction::P2pBitswapResourceGetSuccess { rpc_id, data } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    bug_condition!(
+                        "Rpc state not found for RpcAction::P2pBitswapResourceGetSuccess({})",
+                        rpc_id
+                    );
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::P2pBitswapResourceGet {
+                    rpc_id: *rpc_id,
+                    response: Ok(data.clone()),
+                });
+            }
+            RpcAction::P2pBitswapResourceGetError { rpc_id, error } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    bug_condition!(
+                        "Rpc state not found for RpcAction::P2pBitswapResourceGetError({})",
+                        rpc_id
+                    );
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Error {
+                    time: meta.time(),
+                    error: error.clone(),
+                };
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::P2pBitswapResourceGet {
+                    rpc_id: *rpc_id,
+                    response: Err(error.clone()),
+                });
+            }
+            RpcAction::P2pNodeStatusGetInit { rpc_id, peer_id } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::P2pNodeStatusGet(*peer_id),
+                    status: RpcRequestStatus::Pending { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p = p2p_ready!(state.p2p, meta.time());
+
+                if p2p
+                    .network
+                    .scheduler
+                    .node_status_state
+                    .outgoing
+                    .contains_key(peer_id)
+                {
+                    dispatcher.push(RpcAction::P2pNodeStatusGetError {
+                        rpc_id: *rpc_id,
+                        error: format!("node status query to {peer_id} is already in progress"),
+                    });
+                    return;
+                }
+
+                dispatcher.push(P2pNetworkNodeStatusAction::Query {
+                    peer_id: *peer_id,
+                    rpc_id: Some(*rpc_id),
+                });
+            }
+            RpcAction::P2pNodeStatusGetSuccess { rpc_id, status } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    bug_condition!(
+                        "Rpc state not found for RpcAction::P2pNodeStatusGetSuccess({})",
+                        rpc_id
+                    );
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Success { time: meta.time() };
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::P2pNodeStatusGet {
+                    rpc_id: *rpc_id,
+                    response: Ok(*status.clone()),
+                });
+            }
+            RpcAction::P2pNodeStatusGetError { rpc_id, error } => {
+                let Some(rpc) = state.requests.get_mut(rpc_id) else {
+                    bug_condition!(
+                        "Rpc state not found for RpcAction::P2pNodeStatusGetError({})",
+                        rpc_id
+                    );
+                    return;
+                };
+                rpc.status = RpcRequestStatus::Error {
+                    time: meta.time(),
+                    error: error.clone(),
+                };
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(RpcEffectfulAction::P2pNodeStatusGet {
+                    rpc_id: *rpc_id,
+                    response: Err(error.clone()),
+                });
+            }
             RpcAction::ScanStateSummaryGetInit { rpc_id, query } => {
                 let rpc_state = RpcRequestState {
                     req: RpcRequest::ScanStateSummaryGet(query.clone()),
@@ -614,6 +864,31 @@ impl RpcState {
                     tx: tx.clone(),
                 });
             }
+            RpcAction::TransitionFrontierSubscribe { rpc_id } => {
+                let rpc_state = RpcRequestState {
+                    req: RpcRequest::TransitionFrontierSubscribe,
+                    status: RpcRequestStatus::Pending { time: meta.time() },
+                    data: Default::default(),
+                };
+                state.requests.insert(*rpc_id, rpc_state);
+            }
+            RpcAction::TransitionFrontierNotify => {
+                let rpc_ids = state.transition_frontier_subscription_rpc_ids().collect();
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let transition_frontier = &state.transition_frontier;
+                let event = RpcTransitionFrontierEvent {
+                    synced: transition_frontier.sync.is_synced(),
+                    best_chain: transition_frontier.best_chain.clone(),
+                    reorg: transition_frontier
+                        .chain_diff
+                        .as_ref()
+                        .map_or(false, |diff| diff.reorg_best_tip),
+                };
+                dispatcher.push(RpcEffectfulAction::TransitionFrontierNotify { rpc_ids, event });
+            }
+            RpcAction::TransitionFrontierUnsubscribe { rpc_id } => {
+                state.requests.remove(rpc_id);
+            }
             RpcAction::P2pConnectionIncomingAnswerReady {
                 rpc_id,
                 answer,
@@ -668,8 +943,30 @@ pub fn collect_rpc_peers_info(state: &crate::State) -> Vec<RpcPeerInfo> {
                     best_tip_global_slot: best_tip.map(|bt| bt.global_slot_since_genesis()),
                     best_tip_timestamp: best_tip.map(|bt| bt.timestamp().into()),
                     time,
+                    rtt_ms: state
+                        .status
+                        .as_ready()
+                        .and_then(|r| r.rtt)
+                        .map(|rtt| rtt.as_millis() as u64),
                 }
             })
             .collect()
     })
 }
+
+fn collect_rpc_yamux_stats(state: &crate::State) -> Vec<RpcP2pYamuxConnectionStats> {
+    state.p2p.ready().map_or_else(Vec::new, |p2p| {
+        p2p.network
+            .scheduler
+            .connections
+            .iter()
+            .filter_map(|(addr, connection)| {
+                Some(RpcP2pYamuxConnectionStats {
+                    addr: *addr,
+                    peer_id: connection.peer_id().copied(),
+                    yamux: connection.yamux_state()?.stats(),
+                })
+            })
+            .collect()
+    })
+}
@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 
 use mina_p2p_messages::v2;
 use openmina_core::block::AppliedBlock;
+use p2p::BitswapCid;
 use serde::{Deserialize, Serialize};
 
 use super::{AccountQuery, RpcId, RpcRequest};
@@ -61,6 +62,18 @@ impl RpcState {
         Self::default()
     }
 
+    /// Pending requests for the bitswap resource with the given root.
+    pub fn pending_bitswap_resource_gets(&self, root: &BitswapCid) -> Vec<RpcId> {
+        self.requests
+            .iter()
+            .filter(|(_, req)| req.status.is_pending())
+            .filter(
+                |(_, req)| matches!(&req.req, RpcRequest::P2pBitswapResourceGet(r) if r == root),
+            )
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
     pub fn scan_state_summary_rpc_ids(
         &self,
     ) -> impl Iterator<
@@ -93,6 +106,13 @@ impl RpcState {
             }
         })
     }
+
+    pub fn transition_frontier_subscription_rpc_ids(&self) -> impl Iterator<Item = RpcId> + '_ {
+        self.requests
+            .iter()
+            .filter(|(_, req)| matches!(req.req, RpcRequest::TransitionFrontierSubscribe))
+            .map(|(id, _)| *id)
+    }
 }
 
 impl Default for RpcRequestExtraData {
@@ -3,9 +3,11 @@ use crate::{
     p2p::connection::P2pConnectionResponse,
     rpc::{
         discovery::RpcDiscoveryRoutingTable, AccountQuery, ActionStatsQuery, RpcBestChainResponse,
+        RpcP2pBan, RpcP2pBanAddResponse, RpcP2pBanRemoveResponse, RpcP2pBitswapResourceAddResponse,
+        RpcP2pBitswapResourceGetResponse, RpcP2pNodeStatusGetResponse, RpcP2pYamuxStatsGetResponse,
         RpcPeerInfo, RpcScanStateSummaryScanStateJob, RpcSnarkerConfig,
         RpcTransactionInjectFailure, RpcTransactionInjectRejected, RpcTransactionInjectSuccess,
-        SyncStatsQuery,
+        RpcTransitionFrontierEvent, SyncStatsQuery,
     },
 };
 use ledger::{
@@ -39,6 +41,12 @@ pub enum RpcEffectfulAction {
     BlockProducerStatsGet {
         rpc_id: RpcId,
     },
+    BlockProducerScheduleGet {
+        rpc_id: RpcId,
+    },
+    MetricsGet {
+        rpc_id: RpcId,
+    },
 
     MessageProgressGet {
         rpc_id: RpcId,
@@ -65,6 +73,37 @@ pub enum RpcEffectfulAction {
     P2pConnectionIncomingSuccess {
         rpc_id: RpcId,
     },
+    P2pNodeStatusGet {
+        rpc_id: RpcId,
+        response: RpcP2pNodeStatusGetResponse,
+    },
+    P2pBitswapResourceAdd {
+        rpc_id: RpcId,
+        response: RpcP2pBitswapResourceAddResponse,
+    },
+    P2pBitswapResourceGet {
+        rpc_id: RpcId,
+        response: RpcP2pBitswapResourceGetResponse,
+    },
+    P2pBansGet {
+        rpc_id: RpcId,
+        bans: Vec<RpcP2pBan>,
+    },
+    P2pBanAdd {
+        rpc_id: RpcId,
+        response: RpcP2pBanAddResponse,
+    },
+    P2pBanRemove {
+        rpc_id: RpcId,
+        response: RpcP2pBanRemoveResponse,
+    },
+    P2pBandwidthGet {
+        rpc_id: RpcId,
+    },
+    P2pYamuxStatsGet {
+        rpc_id: RpcId,
+        stats: RpcP2pYamuxStatsGetResponse,
+    },
     ScanStateSummaryGetSuccess {
         rpc_id: RpcId,
         scan_state: Result<Vec<Vec<RpcScanStateSummaryScanStateJob>>, String>,
@@ -144,6 +183,10 @@ pub enum RpcEffectfulAction {
         rpc_id: RpcId,
         tx: MinaBaseUserCommandStableV2,
     },
+    TransitionFrontierNotify {
+        rpc_ids: Vec<RpcId>,
+        event: RpcTransitionFrontierEvent,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for RpcEffectfulAction {
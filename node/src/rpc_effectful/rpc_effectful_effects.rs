@@ -1,4 +1,4 @@
-use super::{super::rpc, RpcEffectfulAction};
+use super::{super::rpc, RespondError, RpcEffectfulAction};
 use crate::{
     block_producer::BlockProducerWonSlot,
     external_snark_worker::available_job_to_snark_worker_spec,
@@ -7,8 +7,8 @@ use crate::{
     rpc::{
         AccountQuery, AccountSlim, ActionStatsQuery, ActionStatsResponse, CurrentMessageProgress,
         MessagesStats, RootLedgerSyncProgress, RootStagedLedgerSyncProgress, RpcAction,
-        RpcBlockProducerStats, RpcMessageProgressResponse, RpcNodeStatus,
-        RpcNodeStatusTransactionPool, RpcNodeStatusTransitionFrontier,
+        RpcBlockProducerSchedule, RpcBlockProducerStats, RpcMessageProgressResponse, RpcMetrics,
+        RpcNodeStatus, RpcNodeStatusTransactionPool, RpcNodeStatusTransitionFrontier,
         RpcNodeStatusTransitionFrontierBlockSummary, RpcNodeStatusTransitionFrontierSync,
         RpcRequestExtraData, RpcScanStateSummary, RpcScanStateSummaryBlock,
         RpcScanStateSummaryBlockTransaction, RpcScanStateSummaryBlockTransactionKind,
@@ -93,6 +93,12 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                     transaction_candidates: state.transaction_pool.candidates.transactions_count(),
                 },
                 current_block_production_attempt,
+                block_producers: state
+                    .block_producer
+                    .configs()
+                    .iter()
+                    .map(|config| config.pub_key.clone().into())
+                    .collect(),
             };
             let _ = store.service.respond_status_get(rpc_id, Some(status));
         }
@@ -129,15 +135,63 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 .map(|s| s.collect_sync_stats(query.limit));
             let _ = store.service.respond_sync_stats_get(rpc_id, resp);
         }
+        RpcEffectfulAction::MetricsGet { rpc_id } => {
+            let state = store.state.get();
+            let best_tip = state.transition_frontier.best_tip();
+            let mut metrics = RpcMetrics {
+                peers: RpcMetrics::collect_peers(state),
+                best_tip_height: best_tip.map(|b| b.height()),
+                best_tip_global_slot: best_tip.map(|b| b.global_slot()),
+                sync_status: state.transition_frontier.sync.to_string(),
+                transaction_pool_size: state.transaction_pool.size(),
+                snark_pool_jobs: state.snark_pool.jobs_iter().count(),
+                snark_pool_snarks: state
+                    .snark_pool
+                    .jobs_iter()
+                    .filter(|job| job.snark.is_some())
+                    .count(),
+                ..Default::default()
+            };
+
+            let bandwidth = store.service.bandwidth_stats();
+            metrics.p2p_received_bytes = bandwidth.global.inbound.total_bytes;
+            metrics.p2p_sent_bytes = bandwidth.global.outbound.total_bytes;
+
+            if let Some(stats) = store.service.stats() {
+                metrics.sync = stats.collect_sync_stats(Some(1)).pop();
+                metrics.actions = Some(stats.collect_action_stats_since_start());
+                metrics.block_production_attempts = RpcMetrics::collect_block_production_attempts(
+                    &stats.block_producer().collect_attempts(),
+                );
+            }
+
+            respond_or_log!(
+                store.service.respond_metrics_get(rpc_id, metrics),
+                meta.time()
+            );
+        }
         RpcEffectfulAction::BlockProducerStatsGet { rpc_id } => {
             let mut create_response = || {
                 let state = store.state.get();
                 let best_tip = state.transition_frontier.best_tip()?;
-                let public_key = state.block_producer.config()?.pub_key.clone();
+                let configs = state.block_producer.configs();
+                let public_key = configs.first()?.pub_key.clone();
                 let won_slots = &state.block_producer.vrf_evaluator()?.won_slots;
 
                 let stats = store.service.stats()?;
                 let attempts = stats.block_producer().collect_attempts();
+                let producers = configs
+                    .iter()
+                    .map(|config| {
+                        let key_stats = stats
+                            .block_producer()
+                            .producers
+                            .get(&config.pub_key)
+                            .cloned()
+                            .unwrap_or_default();
+                        (config.pub_key.clone(), key_stats)
+                    })
+                    .collect();
                 let future_slot = attempts.last().map_or(0, |v| {
                     v.won_slot.global_slot.checked_add(1).expect("overflow")
                 });
@@ -165,6 +219,7 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                     epoch_end: epoch_start
                         .map(|slot| slot.checked_add(slots_per_epoch).expect("overflow")),
                     public_key: public_key.into(),
+                    producers,
                     attempts,
                     future_won_slots: won_slots
                         .range(future_slot..)
@@ -183,6 +238,32 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 .service
                 .respond_block_producer_stats_get(rpc_id, response);
         }
+        RpcEffectfulAction::BlockProducerScheduleGet { rpc_id } => {
+            let create_response = || {
+                let state = store.state.get();
+                let best_tip = state.transition_frontier.best_tip()?;
+                let vrf_evaluator = state.block_producer.vrf_evaluator()?;
+                let current_epoch = state.current_epoch();
+
+                Some(RpcBlockProducerSchedule {
+                    current_time: meta.time(),
+                    current_global_slot: state.cur_global_slot(),
+                    current_epoch,
+                    latest_evaluated_global_slot: vrf_evaluator.latest_evaluated_global_slot(),
+                    won_slots: current_epoch
+                        .into_iter()
+                        .flat_map(|epoch| {
+                            vrf_evaluator.scheduled_won_slots(epoch, best_tip.genesis_timestamp())
+                        })
+                        .map(|won_slot| (&won_slot).into())
+                        .collect(),
+                })
+            };
+            let response = create_response();
+            let _ = store
+                .service
+                .respond_block_producer_schedule_get(rpc_id, response);
+        }
         RpcEffectfulAction::MessageProgressGet { rpc_id } => {
             // TODO: move to stats
             let p2p = p2p_ready!(store.state().p2p, meta.time());
@@ -326,6 +407,63 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 .respond_p2p_connection_outgoing(rpc_id, Ok(()));
             store.dispatch(RpcAction::Finish { rpc_id });
         }
+        RpcEffectfulAction::P2pNodeStatusGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_p2p_node_status_get(rpc_id, response),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
+        RpcEffectfulAction::P2pBitswapResourceAdd { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_p2p_bitswap_resource_add(rpc_id, response),
+                meta.time()
+            );
+        }
+        RpcEffectfulAction::P2pBitswapResourceGet { rpc_id, response } => {
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_p2p_bitswap_resource_get(rpc_id, response),
+                meta.time()
+            );
+            store.dispatch(RpcAction::Finish { rpc_id });
+        }
+        RpcEffectfulAction::P2pBansGet { rpc_id, bans } => {
+            respond_or_log!(
+                store.service().respond_p2p_bans_get(rpc_id, bans),
+                meta.time()
+            );
+        }
+        RpcEffectfulAction::P2pBanAdd { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_p2p_ban_add(rpc_id, response),
+                meta.time()
+            );
+        }
+        RpcEffectfulAction::P2pBanRemove { rpc_id, response } => {
+            respond_or_log!(
+                store.service().respond_p2p_ban_remove(rpc_id, response),
+                meta.time()
+            );
+        }
+        RpcEffectfulAction::P2pBandwidthGet { rpc_id } => {
+            let stats = store.service().bandwidth_stats();
+            respond_or_log!(
+                store.service().respond_p2p_bandwidth_get(rpc_id, stats),
+                meta.time()
+            );
+        }
+        RpcEffectfulAction::P2pYamuxStatsGet { rpc_id, stats } => {
+            respond_or_log!(
+                store.service().respond_p2p_yamux_stats_get(rpc_id, stats),
+                meta.time()
+            );
+        }
         RpcEffectfulAction::P2pConnectionIncomingRespond { rpc_id, response } => {
             let error = match &response {
                 P2pConnectionResponse::Accepted(_) => None,
@@ -774,5 +912,23 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta<RpcE
                 )
             }
         }
+        RpcEffectfulAction::TransitionFrontierNotify { rpc_ids, event } => {
+            for rpc_id in rpc_ids {
+                match store
+                    .service
+                    .respond_transition_frontier_event(rpc_id, event.clone())
+                {
+                    Ok(()) => {}
+                    // The subscriber is gone.
+                    Err(RespondError::UnknownRpcId | RespondError::RespondingFailed) => {
+                        store.dispatch(RpcAction::TransitionFrontierUnsubscribe { rpc_id });
+                    }
+                    Err(err) => {
+                        openmina_core::log::warn!(meta.time(); "Failed to notify subscriber: {err}");
+                        store.dispatch(RpcAction::TransitionFrontierUnsubscribe { rpc_id });
+                    }
+                }
+            }
+        }
     }
 }
@@ -1,15 +1,19 @@
 use crate::{
     p2p::connection::P2pConnectionResponse,
     rpc::{
-        RpcActionStatsGetResponse, RpcBestChainResponse, RpcBlockProducerStatsGetResponse,
-        RpcDiscoveryBoostrapStatsResponse, RpcDiscoveryRoutingTableResponse,
-        RpcHealthCheckResponse, RpcId, RpcLedgerAccountsResponse, RpcLedgerSlimAccountsResponse,
-        RpcMessageProgressResponse, RpcP2pConnectionOutgoingResponse, RpcPeersGetResponse,
-        RpcReadinessCheckResponse, RpcScanStateSummaryGetResponse, RpcSnarkPoolGetResponse,
-        RpcSnarkPoolJobGetResponse, RpcSnarkerConfigGetResponse, RpcSnarkerJobCommitResponse,
-        RpcSnarkerJobSpecResponse, RpcSnarkerWorkersResponse, RpcStatusGetResponse,
-        RpcSyncStatsGetResponse, RpcTransactionInjectResponse, RpcTransactionPoolResponse,
-        RpcTransactionStatusGetResponse, RpcTransitionFrontierUserCommandsResponse,
+        RpcActionStatsGetResponse, RpcBestChainResponse, RpcBlockProducerScheduleGetResponse,
+        RpcBlockProducerStatsGetResponse, RpcDiscoveryBoostrapStatsResponse,
+        RpcDiscoveryRoutingTableResponse, RpcHealthCheckResponse, RpcId, RpcLedgerAccountsResponse,
+        RpcLedgerSlimAccountsResponse, RpcMessageProgressResponse, RpcMetricsGetResponse,
+        RpcP2pBanAddResponse, RpcP2pBanRemoveResponse, RpcP2pBandwidthGetResponse,
+        RpcP2pBansGetResponse, RpcP2pBitswapResourceAddResponse, RpcP2pBitswapResourceGetResponse,
+        RpcP2pConnectionOutgoingResponse, RpcP2pNodeStatusGetResponse, RpcP2pYamuxStatsGetResponse,
+        RpcPeersGetResponse, RpcReadinessCheckResponse, RpcScanStateSummaryGetResponse,
+        RpcSnarkPoolGetResponse, RpcSnarkPoolJobGetResponse, RpcSnarkerConfigGetResponse,
+        RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcSnarkerWorkersResponse,
+        RpcStatusGetResponse, RpcSyncStatsGetResponse, RpcTransactionInjectResponse,
+        RpcTransactionPoolResponse, RpcTransactionStatusGetResponse,
+        RpcTransitionFrontierSubscribeResponse, RpcTransitionFrontierUserCommandsResponse,
     },
     State,
 };
@@ -67,6 +71,16 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcBlockProducerStatsGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_block_producer_schedule_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockProducerScheduleGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_metrics_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcMetricsGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_message_progress_stats_get(
         &mut self,
         rpc_id: RpcId,
@@ -82,6 +96,46 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcP2pConnectionOutgoingResponse,
     ) -> Result<(), RespondError>;
+    fn respond_p2p_node_status_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcP2pNodeStatusGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_p2p_bitswap_resource_add(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcP2pBitswapResourceAddResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_p2p_bitswap_resource_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcP2pBitswapResourceGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_p2p_bans_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcP2pBansGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_p2p_ban_add(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcP2pBanAddResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_p2p_ban_remove(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcP2pBanRemoveResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_p2p_bandwidth_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcP2pBandwidthGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_p2p_yamux_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcP2pYamuxStatsGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_p2p_connection_incoming_answer(
         &mut self,
         rpc_id: RpcId,
@@ -187,4 +241,11 @@ pub trait RpcService {
         rpc_id: RpcId,
         response: RpcTransactionStatusGetResponse,
     ) -> Result<(), RespondError>;
+    /// Pushes an event to a subscriber, keeping the subscription.
+    /// [`RespondError::RespondingFailed`] means that the subscriber is gone.
+    fn respond_transition_frontier_event(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTransitionFrontierSubscribeResponse,
+    ) -> Result<(), RespondError>;
 }
@@ -1,5 +1,6 @@
 pub use crate::block_producer_effectful::vrf_evaluator_effectful::BlockProducerVrfEvaluatorService;
 pub use crate::block_producer_effectful::BlockProducerService;
+pub use crate::event_sink::EventSinkService;
 pub use crate::event_source::EventSourceService;
 pub use crate::external_snark_worker_effectful::ExternalSnarkWorkerService;
 pub use crate::ledger::LedgerService;
@@ -9,6 +10,7 @@ pub use crate::rpc_effectful::RpcService;
 pub use crate::snark::block_verify_effectful::SnarkBlockVerifyService;
 pub use crate::snark::work_verify_effectful::SnarkWorkVerifyService;
 pub use crate::snark_pool::SnarkPoolService;
+pub use crate::transition_frontier::archive::ArchiveService;
 pub use crate::transition_frontier::genesis_effectful::TransitionFrontierGenesisService;
 pub use crate::transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedService;
 pub use redux::TimeService;
@@ -31,6 +33,8 @@ pub trait Service:
     + BlockProducerService
     + ExternalSnarkWorkerService
     + RpcService
+    + ArchiveService
+    + EventSinkService
 {
     fn stats(&mut self) -> Option<&mut Stats>;
     fn recorder(&mut self) -> &mut Recorder;
@@ -19,7 +19,8 @@ use p2p::connection::outgoing::P2pConnectionOutgoingError;
 use p2p::connection::P2pConnectionResponse;
 use p2p::{
     bootstrap::P2pNetworkKadBootstrapState, network::identify::P2pNetworkIdentifyState,
-    P2pCallbacks, P2pConfig, P2pNetworkSchedulerState, P2pPeerState, P2pPeerStatusReady, PeerId,
+    BitswapBlockError, BitswapCid, P2pCallbacks, P2pConfig, P2pNetworkNodeStatus,
+    P2pNetworkSchedulerState, P2pPeerState, P2pPeerStatusReady, PeerId, StreamId,
 };
 use redux::{ActionMeta, EnablingCondition, Timestamp};
 use serde::{Deserialize, Serialize};
@@ -73,6 +74,8 @@ pub struct State {
 
     pub watched_accounts: WatchedAccountsState,
 
+    /// Time when the state machine was created.
+    start_time: Timestamp,
     // TODO(binier): include action kind in `last_action`.
     last_action: ActionMeta,
     applied_actions_count: u64,
@@ -268,6 +271,9 @@ impl_p2p_state_access!(State, p2p::P2pNetworkKadState);
 impl_p2p_state_access!(State, P2pNetworkSchedulerState);
 impl_p2p_state_access!(State, p2p::P2pLimits);
 impl_p2p_state_access!(State, p2p::P2pNetworkPubsubState);
+impl_p2p_state_access!(State, p2p::P2pNetworkBitswapState);
+impl_p2p_state_access!(State, p2p::P2pNetworkNodeStatusState);
+impl_p2p_state_access!(State, p2p::P2pNetworkPingState);
 impl_p2p_state_access!(State, p2p::P2pConfig);
 
 impl p2p::P2pStateTrait for State {}
@@ -284,13 +290,14 @@ impl State {
             consensus: ConsensusState::new(),
             transition_frontier: TransitionFrontierState::new(config.transition_frontier),
             external_snark_worker: ExternalSnarkWorkers::new(now),
-            block_producer: BlockProducerState::new(now, config.block_producer),
+            block_producer: BlockProducerState::new(now, config.block_producers),
             rpc: RpcState::new(),
             transaction_pool: TransactionPoolState::new(config.tx_pool, constants),
 
             watched_accounts: WatchedAccountsState::new(),
 
             config: config.global,
+            start_time: now,
             last_action: ActionMeta::zero_custom(now),
             applied_actions_count: 0,
         }
@@ -308,6 +315,13 @@ impl State {
         self.last_action.time()
     }
 
+    /// Time passed since the state machine was created.
+    pub fn uptime(&self) -> Duration {
+        self.time()
+            .checked_sub(self.start_time)
+            .unwrap_or(Duration::ZERO)
+    }
+
     pub fn pseudo_rng(&self) -> StdRng {
         crate::core::pseudo_rng(self.time())
     }
@@ -628,6 +642,31 @@ impl P2p {
                     P2pCallbacksAction::P2pChannelsStreamingRpcTimeout { peer_id, id }
                 }
             )),
+            on_p2p_network_node_status_request: Some(redux::callback!(
+                on_p2p_network_node_status_request((peer_id: PeerId, stream_id: StreamId)) -> crate::Action {
+                    P2pCallbacksAction::P2pNetworkNodeStatusRequest { peer_id, stream_id }
+                }
+            )),
+            on_p2p_network_node_status_query_success: Some(redux::callback!(
+                on_p2p_network_node_status_query_success((rpc_id: RpcId, status: Box<P2pNetworkNodeStatus>)) -> crate::Action {
+                    RpcAction::P2pNodeStatusGetSuccess { rpc_id, status }
+                }
+            )),
+            on_p2p_network_node_status_query_error: Some(redux::callback!(
+                on_p2p_network_node_status_query_error((rpc_id: RpcId, error: String)) -> crate::Action {
+                    RpcAction::P2pNodeStatusGetError { rpc_id, error }
+                }
+            )),
+            on_p2p_network_bitswap_resource_ready: Some(redux::callback!(
+                on_p2p_network_bitswap_resource_ready(root: BitswapCid) -> crate::Action {
+                    P2pCallbacksAction::P2pNetworkBitswapResourceReady { root }
+                }
+            )),
+            on_p2p_network_bitswap_resource_error: Some(redux::callback!(
+                on_p2p_network_bitswap_resource_error((root: BitswapCid, error: BitswapBlockError)) -> crate::Action {
+                    P2pCallbacksAction::P2pNetworkBitswapResourceError { root, error }
+                }
+            )),
         }
     }
 
@@ -89,6 +89,16 @@ impl ActionStatsSnapshot {
             .expect("kind_i out of bounds")
             .add(duration);
     }
+
+    /// Stats of the action kinds which were executed at least once.
+    pub fn iter(&self) -> impl Iterator<Item = (ActionKind, &ActionStatsForRanges)> {
+        self.0
+            .iter()
+            .enumerate()
+            .skip(1) // skip `None` action
+            .filter(|(_, v)| v.total_calls() > 0)
+            .filter_map(|(i, v)| Some((ActionKind::try_from(i as u16).ok()?, v)))
+    }
 }
 
 impl Serialize for ActionStatsSnapshot {
@@ -200,4 +210,26 @@ impl ActionStatsForRanges {
         stats.total_duration = stats.total_duration.saturating_add(duration);
         stats.max_duration = std::cmp::max(stats.max_duration, duration);
     }
+
+    /// Upper bound of each range in nanoseconds, `None` for the last one,
+    /// along with its stats.
+    pub fn ranges(&self) -> [(Option<u64>, &ActionStatsForRange); 9] {
+        [
+            (Some(1_000), &self.under_1_us),
+            (Some(10_000), &self.under_10_us),
+            (Some(50_000), &self.under_50_us),
+            (Some(100_000), &self.under_100_us),
+            (Some(500_000), &self.under_500_us),
+            (Some(1_000_000), &self.under_1_ms),
+            (Some(5_000_000), &self.under_5_ms),
+            (Some(50_000_000), &self.under_50_ms),
+            (None, &self.above_50_ms),
+        ]
+    }
+
+    pub fn total_calls(&self) -> u64 {
+        self.ranges()
+            .iter()
+            .fold(0, |acc, (_, v)| acc.saturating_add(v.total_calls))
+    }
 }
@@ -16,6 +16,8 @@ const MAX_HISTORY: usize = 2048;
 pub struct BlockProducerStats {
     pub(super) attempts: VecDeque<BlockProductionAttempt>,
     pub vrf_evaluator: BTreeMap<u32, VrfEvaluatorStats>,
+    /// Stats of each block producer key.
+    pub producers: BTreeMap<v2::NonZeroCurvePoint, BlockProducerKeyStats>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -32,6 +34,7 @@ pub struct BlockProductionAttemptWonSlot {
     pub slot_time: redux::Timestamp,
     pub global_slot: u32,
     pub epoch: u32,
+    pub producer: v2::NonZeroCurvePoint,
     pub delegator: (v2::NonZeroCurvePoint, AccountIndex),
     pub value_with_threshold: Option<(f64, f64)>,
 }
@@ -91,6 +94,13 @@ pub struct ProducedBlockTransactions {
     pub zkapps: u16,
 }
 
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct BlockProducerKeyStats {
+    pub won_slots: u32,
+    pub produced_blocks: u32,
+    pub discarded_slots: u32,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct VrfEvaluatorStats {
     pub total_slots: u32,
@@ -114,6 +124,12 @@ impl BlockProducerStats {
             .map_or(false, |b| &b.hash == hash)
     }
 
+    /// Stats of the key which won the slot of the latest attempt.
+    fn latest_attempt_key_stats(&mut self) -> Option<&mut BlockProducerKeyStats> {
+        let producer = self.attempts.back()?.won_slot.producer.clone();
+        Some(self.producers.entry(producer).or_default())
+    }
+
     pub fn collect_attempts(&self) -> Vec<BlockProductionAttempt> {
         self.attempts.iter().cloned().collect()
     }
@@ -207,6 +223,9 @@ impl BlockProducerStats {
             },
             status: BlockProductionStatus::Scheduled,
         });
+        if let Some(stats) = self.latest_attempt_key_stats() {
+            stats.won_slots = stats.won_slots.saturating_add(1);
+        }
     }
 
     pub fn staged_ledger_diff_create_start(&mut self, time: redux::Timestamp) {
@@ -252,6 +271,12 @@ impl BlockProducerStats {
             }
             _ => false,
         });
+        let is_produced = self.attempts.back().map_or(false, |attempt| {
+            matches!(attempt.status, BlockProductionStatus::Produced)
+        });
+        if let Some(stats) = self.latest_attempt_key_stats().filter(|_| is_produced) {
+            stats.produced_blocks = stats.produced_blocks.saturating_add(1);
+        }
     }
 
     pub fn proof_create_start(&mut self, time: redux::Timestamp) {
@@ -329,6 +354,9 @@ impl BlockProducerStats {
             attempt.times.discarded = Some(time);
             true
         });
+        if let Some(stats) = self.latest_attempt_key_stats() {
+            stats.discarded_slots = stats.discarded_slots.saturating_add(1);
+        }
     }
 
     /// Returns `true` if this is a block we just produced
@@ -375,6 +403,7 @@ impl From<&BlockProducerWonSlot> for BlockProductionAttemptWonSlot {
             slot_time: won_slot.slot_time,
             global_slot: won_slot.global_slot(),
             epoch: won_slot.epoch(),
+            producer: won_slot.producer.clone(),
             delegator: won_slot.delegator.clone(),
             value_with_threshold: won_slot.value_with_threshold,
         }
@@ -0,0 +1,34 @@
+//! Fixtures shared by the tests of the node and of the crates built on it.
+
+use std::sync::Arc;
+
+use mina_p2p_messages::v2;
+use openmina_core::block::{ArcBlockWithHash, BlockWithHash};
+
+use crate::transition_frontier::genesis::{GenesisConfig, NonStakers};
+
+/// Small genesis, with one whale and one fish staking.
+pub fn genesis_config() -> GenesisConfig {
+    GenesisConfig::Counts {
+        whales: 1,
+        fish: 1,
+        non_stakers: NonStakers::None,
+        constants: GenesisConfig::default_constants(0),
+    }
+}
+
+/// Genesis block of [`genesis_config`], with a dummy proof.
+pub fn genesis_block() -> ArcBlockWithHash {
+    let (_, loaded) = genesis_config().load().unwrap();
+    loaded.block_with_dummy_proof().unwrap()
+}
+
+/// Child of `parent`, `timestamp` tells apart siblings.
+pub fn child_block(parent: &ArcBlockWithHash, timestamp: u64) -> ArcBlockWithHash {
+    let mut block = (*parent.block).clone();
+    let protocol_state = &mut block.header.protocol_state;
+    protocol_state.previous_state_hash = parent.hash().clone();
+    protocol_state.body.consensus_state.blockchain_length = (parent.height() + 1).into();
+    protocol_state.body.blockchain_state.timestamp = v2::BlockTimeTimeStableV1(timestamp.into());
+    BlockWithHash::try_new(Arc::new(block)).unwrap()
+}
@@ -17,7 +17,10 @@ use redux::callback;
 use snark::user_command_verify::{SnarkUserCommandVerifyAction, SnarkUserCommandVerifyId};
 use std::collections::{BTreeMap, BTreeSet};
 
-use crate::{BlockProducerAction, RpcAction};
+use crate::{
+    block_producer::{TransactionCandidate, TransactionSelectionPolicy},
+    BlockProducerAction, RpcAction,
+};
 
 use super::{
     PendingId, TransactionPoolAction, TransactionPoolActionWithMetaRef,
@@ -393,14 +396,38 @@ impl TransactionPoolState {
             TransactionPoolAction::CollectTransactionsByFee => {
                 let transaction_capacity =
                     2u64.pow(constraint_constants().transaction_capacity_log_2 as u32);
-                let transactions_by_fee = substate
-                    .pool
-                    .list_includable_transactions(transaction_capacity as usize)
-                    .into_iter()
-                    .map(|cmd| cmd.data)
-                    .collect::<Vec<_>>();
+                let (dispatcher, global_state) = state.into_dispatcher_and_state();
+                let pool = &global_state.transaction_pool;
+                let policies = global_state
+                    .block_producer
+                    .current_won_slot()
+                    .and_then(|won_slot| global_state.block_producer.config(&won_slot.producer))
+                    .map_or(&[][..], |config| &config.transaction_selection[..]);
 
-                let dispatcher = state.into_dispatcher();
+                let transactions_by_fee = if policies.is_empty() {
+                    pool.pool
+                        .list_includable_transactions(transaction_capacity as usize)
+                        .into_iter()
+                        .map(|cmd| cmd.data)
+                        .collect::<Vec<_>>()
+                } else {
+                    // Policies may skip transactions, so consider the whole pool.
+                    let candidates = pool
+                        .pool
+                        .list_includable_transactions(usize::MAX)
+                        .into_iter()
+                        .map(|cmd| {
+                            let received_at = pool.dpool.get(&cmd.hash).map(|tx| tx.time);
+                            TransactionCandidate::new(cmd.data, received_at)
+                        })
+                        .collect();
+                    policies
+                        .select(candidates)
+                        .into_iter()
+                        .take(transaction_capacity as usize)
+                        .map(|candidate| candidate.command)
+                        .collect()
+                };
 
                 dispatcher.push(BlockProducerAction::WonSlotTransactionsSuccess {
                     transactions_by_fee,
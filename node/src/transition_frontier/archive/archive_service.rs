@@ -0,0 +1,15 @@
+use std::sync::Arc;
+
+use openmina_core::block::{AppliedBlock, ArcBlockWithHash};
+
+use crate::ledger::write::BlockApplyResultArchive;
+
+pub trait ArchiveService: redux::Service {
+    /// Send the applied block to the archive. Writing happens in the
+    /// background, failures are reported by the service itself.
+    fn send_to_archive(&mut self, block: ArcBlockWithHash, data: Arc<BlockApplyResultArchive>);
+
+    /// Export the blocks of the new best chain, which weren't exported
+    /// already, as precomputed blocks.
+    fn export_precomputed_blocks(&mut self, best_chain: &[AppliedBlock]);
+}
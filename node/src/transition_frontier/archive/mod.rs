@@ -0,0 +1,5 @@
+mod archive_service;
+pub use archive_service::*;
+
+mod precomputed_block;
+pub use precomputed_block::*;
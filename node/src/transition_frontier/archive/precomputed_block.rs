@@ -0,0 +1,177 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use ark_ff::fields::arithmetic::InvalidBigInt;
+use mina_p2p_messages::{
+    list::List,
+    number::Number,
+    v2::{self, StateBodyHash, StateHash, TokenIdKeyHash},
+};
+use openmina_core::block::{ArcBlockWithHash, BlockWithHash};
+use serde::{Deserialize, Serialize};
+
+use crate::ledger::write::BlockApplyResultArchive;
+
+/// Version of the precomputed block format written by the node.
+pub const PRECOMPUTED_BLOCK_VERSION: u32 = 3;
+
+/// Block in the "precomputed block" JSON format, as written by the Mina
+/// daemon with `--precomputed-blocks-file` and consumed by the archive
+/// tooling.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrecomputedBlock {
+    pub version: u32,
+    pub data: PrecomputedBlockData,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrecomputedBlockData {
+    pub scheduled_time: v2::BlockTimeTimeStableV1,
+    pub protocol_state: v2::MinaStateProtocolStateValueStableV2,
+    pub protocol_state_proof: v2::PicklesProofProofsVerifiedMaxStableV2,
+    pub staged_ledger_diff: v2::StagedLedgerDiffDiffStableV2,
+    pub delta_transition_chain_proof: (StateHash, List<StateBodyHash>),
+    pub protocol_version: PrecomputedProtocolVersion,
+    #[serde(default)]
+    pub proposed_protocol_version: Option<PrecomputedProtocolVersion>,
+    /// Accounts touched by the block, as they are after applying it.
+    #[serde(default)]
+    pub accounts_accessed: Vec<(u64, v2::MinaBaseAccountBinableArgStableV2)>,
+    #[serde(default)]
+    pub accounts_created: Vec<(v2::MinaBaseAccountIdStableV2, v2::CurrencyFeeStableV1)>,
+    /// Tokens of the accessed accounts. Token owners are not tracked, so
+    /// they are always `None`.
+    #[serde(default)]
+    pub tokens_used: Vec<(TokenIdKeyHash, Option<v2::MinaBaseAccountIdStableV2>)>,
+}
+
+/// Protocol version, serialized with plain integers like the daemon does.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrecomputedProtocolVersion {
+    pub transaction: u64,
+    pub network: u64,
+    pub patch: u64,
+}
+
+impl PrecomputedBlock {
+    /// Builds the precomputed block. Without `archive_data`, the accounts
+    /// related fields are left empty.
+    pub fn new(block: &ArcBlockWithHash, archive_data: Option<&BlockApplyResultArchive>) -> Self {
+        let header = block.header();
+        let (accounts_accessed, accounts_created) = archive_data
+            .map(|data| {
+                (
+                    data.accounts_accessed.clone(),
+                    data.accounts_created.clone(),
+                )
+            })
+            .unwrap_or_default();
+        let tokens_used = accounts_accessed
+            .iter()
+            .map(|(_, account)| account.token_id.clone())
+            .collect::<BTreeSet<_>>();
+
+        Self {
+            version: PRECOMPUTED_BLOCK_VERSION,
+            data: PrecomputedBlockData {
+                scheduled_time: header
+                    .protocol_state
+                    .body
+                    .blockchain_state
+                    .timestamp
+                    .clone(),
+                protocol_state: header.protocol_state.clone(),
+                protocol_state_proof: header.protocol_state_proof.0.clone().into(),
+                staged_ledger_diff: block.body().staged_ledger_diff.clone(),
+                delta_transition_chain_proof: header.delta_block_chain_proof.clone(),
+                protocol_version: (&header.current_protocol_version).into(),
+                proposed_protocol_version: header
+                    .proposed_protocol_version_opt
+                    .as_ref()
+                    .map(Into::into),
+                accounts_accessed,
+                accounts_created,
+                tokens_used: tokens_used
+                    .into_iter()
+                    .map(|token_id| (token_id, None))
+                    .collect(),
+            },
+        }
+    }
+
+    /// Name of the file the block is written to, following the daemon's
+    /// `<network>-<height>-<state hash>.json` convention.
+    pub fn file_name(network_name: &str, block: &ArcBlockWithHash) -> String {
+        format!("{network_name}-{}-{}.json", block.height(), block.hash())
+    }
+
+    pub fn height(&self) -> u32 {
+        self.data
+            .protocol_state
+            .body
+            .consensus_state
+            .blockchain_length
+            .as_u32()
+    }
+
+    /// Converts the precomputed block back into a block, computing its hash.
+    pub fn into_block(self) -> Result<ArcBlockWithHash, InvalidBigInt> {
+        let data = self.data;
+        let block = v2::MinaBlockBlockStableV2 {
+            header: v2::MinaBlockHeaderStableV2 {
+                protocol_state: data.protocol_state,
+                protocol_state_proof: Arc::new(v2::MinaBaseProofStableV2(
+                    data.protocol_state_proof.into(),
+                )),
+                delta_block_chain_proof: data.delta_transition_chain_proof,
+                current_protocol_version: data.protocol_version.into(),
+                proposed_protocol_version_opt: data.proposed_protocol_version.map(Into::into),
+            },
+            body: v2::StagedLedgerDiffBodyStableV1 {
+                staged_ledger_diff: data.staged_ledger_diff,
+            },
+        };
+        BlockWithHash::try_new(Arc::new(block))
+    }
+}
+
+impl From<&v2::ProtocolVersionStableV2> for PrecomputedProtocolVersion {
+    fn from(value: &v2::ProtocolVersionStableV2) -> Self {
+        Self {
+            transaction: value.transaction.as_u64(),
+            network: value.network.as_u64(),
+            patch: value.patch.as_u64(),
+        }
+    }
+}
+
+impl From<PrecomputedProtocolVersion> for v2::ProtocolVersionStableV2 {
+    fn from(value: PrecomputedProtocolVersion) -> Self {
+        Self {
+            transaction: Number(value.transaction),
+            network: Number(value.network),
+            patch: Number(value.patch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::genesis_block;
+
+    use super::*;
+
+    #[test]
+    fn json_round_trip_keeps_block_hash() {
+        let block = genesis_block();
+
+        let precomputed = PrecomputedBlock::new(&block, None);
+        assert_eq!(precomputed.version, PRECOMPUTED_BLOCK_VERSION);
+        assert_eq!(precomputed.height(), block.height());
+
+        let json = serde_json::to_string(&precomputed).unwrap();
+        let decoded: PrecomputedBlock = serde_json::from_str(&json).unwrap();
+        let decoded = decoded.into_block().unwrap();
+        assert_eq!(decoded.hash(), block.hash());
+        assert_eq!(decoded.body(), block.body());
+    }
+}
@@ -1,22 +1,15 @@
 use crate::{
-    account::AccountSecretKey, block_producer::calc_epoch_seed,
+    account::AccountSecretKey,
     transition_frontier::genesis_effectful::TransitionFrontierGenesisEffectfulAction,
 };
-use ledger::{
-    dummy::dummy_blockchain_proof, scan_state::transaction_logic::local_state::LocalState,
-};
+use ledger::dummy::dummy_blockchain_proof;
 use mina_p2p_messages::v2;
-use openmina_core::{
-    block::{genesis::genesis_and_negative_one_protocol_states, BlockWithHash},
-    constants::PROTOCOL_VERSION,
-    error,
-};
+use openmina_core::{block::BlockWithHash, constants::PROTOCOL_VERSION, error};
 use p2p::P2pInitializeAction;
 
 use super::{
-    empty_block_body, empty_block_body_hash, empty_pending_coinbase, empty_pending_coinbase_hash,
-    TransitionFrontierGenesisAction, TransitionFrontierGenesisActionWithMetaRef,
-    TransitionFrontierGenesisState,
+    empty_block_body, empty_pending_coinbase, TransitionFrontierGenesisAction,
+    TransitionFrontierGenesisActionWithMetaRef, TransitionFrontierGenesisState,
 };
 
 impl TransitionFrontierGenesisState {
@@ -58,28 +51,7 @@ impl TransitionFrontierGenesisState {
                     return;
                 };
 
-                let genesis_vrf = ::vrf::genesis_vrf(data.staking_epoch_seed.clone()).unwrap();
-                let genesis_vrf_hash = genesis_vrf.hash();
-
-                let Ok((negative_one, genesis, genesis_hash)) =
-                    genesis_and_negative_one_protocol_states(
-                        data.constants.clone(),
-                        data.genesis_ledger_hash.clone(),
-                        data.genesis_total_currency.clone(),
-                        data.staking_epoch_ledger_hash.clone(),
-                        data.staking_epoch_total_currency.clone(),
-                        data.next_epoch_ledger_hash.clone(),
-                        data.next_epoch_total_currency.clone(),
-                        AccountSecretKey::genesis_producer().public_key().into(),
-                        empty_pending_coinbase_hash(),
-                        (&LocalState::dummy()).into(),
-                        empty_block_body_hash(),
-                        genesis_vrf.into(),
-                        data.staking_epoch_seed.clone(),
-                        data.next_epoch_seed.clone(),
-                        calc_epoch_seed(&data.next_epoch_seed, genesis_vrf_hash), //data.next_epoch_seed.clone(),
-                    )
-                else {
+                let Ok((negative_one, genesis, genesis_hash)) = data.protocol_states() else {
                     error!(meta.time(); "invalid negative protocol state");
                     return;
                 };
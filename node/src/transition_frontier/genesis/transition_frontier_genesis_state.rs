@@ -1,9 +1,19 @@
-use ledger::dummy::dummy_blockchain_proof;
+use ark_ff::fields::arithmetic::InvalidBigInt;
+use ledger::{
+    dummy::dummy_blockchain_proof, scan_state::transaction_logic::local_state::LocalState,
+};
 use mina_p2p_messages::v2;
-use openmina_core::{block::ArcBlockWithHash, constants::PROTOCOL_VERSION};
+use openmina_core::{
+    block::{genesis::genesis_and_negative_one_protocol_states, ArcBlockWithHash},
+    constants::PROTOCOL_VERSION,
+};
 use serde::{Deserialize, Serialize};
 
-use super::{empty_block_body, GenesisConfigLoaded};
+use crate::{account::AccountSecretKey, block_producer::calc_epoch_seed};
+
+use super::{
+    empty_block_body, empty_block_body_hash, empty_pending_coinbase_hash, GenesisConfigLoaded,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum TransitionFrontierGenesisState {
@@ -45,22 +55,7 @@ impl TransitionFrontierGenesisState {
         else {
             return None;
         };
-        ArcBlockWithHash::try_new(
-            v2::MinaBlockBlockStableV2 {
-                header: v2::MinaBlockHeaderStableV2 {
-                    protocol_state: genesis.clone(),
-                    protocol_state_proof: dummy_blockchain_proof().clone(),
-                    delta_block_chain_proof: (genesis_hash.clone(), std::iter::empty().collect()),
-                    current_protocol_version: PROTOCOL_VERSION.clone(),
-                    proposed_protocol_version_opt: None,
-                },
-                body: v2::StagedLedgerDiffBodyStableV1 {
-                    staged_ledger_diff: empty_block_body(),
-                },
-            }
-            .into(),
-        )
-        .ok()
+        block_with_dummy_proof(genesis.clone(), genesis_hash.clone()).ok()
     }
 
     pub fn prove_pending_block_hash(&self) -> Option<v2::StateHash> {
@@ -83,3 +78,66 @@ impl TransitionFrontierGenesisState {
             .or_else(|| self.block_with_dummy_proof())
     }
 }
+
+impl GenesisConfigLoaded {
+    /// Computes the "negative one" and genesis protocol states, along with
+    /// the genesis state hash.
+    pub fn protocol_states(
+        &self,
+    ) -> Result<
+        (
+            v2::MinaStateProtocolStateValueStableV2,
+            v2::MinaStateProtocolStateValueStableV2,
+            v2::StateHash,
+        ),
+        InvalidBigInt,
+    > {
+        let genesis_vrf = ::vrf::genesis_vrf(self.staking_epoch_seed.clone()).unwrap();
+        let genesis_vrf_hash = genesis_vrf.hash();
+
+        genesis_and_negative_one_protocol_states(
+            self.constants.clone(),
+            self.genesis_ledger_hash.clone(),
+            self.genesis_total_currency.clone(),
+            self.staking_epoch_ledger_hash.clone(),
+            self.staking_epoch_total_currency.clone(),
+            self.next_epoch_ledger_hash.clone(),
+            self.next_epoch_total_currency.clone(),
+            AccountSecretKey::genesis_producer().public_key().into(),
+            empty_pending_coinbase_hash(),
+            (&LocalState::dummy()).into(),
+            empty_block_body_hash(),
+            genesis_vrf.into(),
+            self.staking_epoch_seed.clone(),
+            self.next_epoch_seed.clone(),
+            calc_epoch_seed(&self.next_epoch_seed, genesis_vrf_hash), //data.next_epoch_seed.clone(),
+        )
+    }
+
+    /// Genesis block, with a dummy proof.
+    pub fn block_with_dummy_proof(&self) -> Result<ArcBlockWithHash, InvalidBigInt> {
+        let (_, genesis, genesis_hash) = self.protocol_states()?;
+        block_with_dummy_proof(genesis, genesis_hash)
+    }
+}
+
+fn block_with_dummy_proof(
+    genesis: v2::MinaStateProtocolStateValueStableV2,
+    genesis_hash: v2::StateHash,
+) -> Result<ArcBlockWithHash, InvalidBigInt> {
+    ArcBlockWithHash::try_new(
+        v2::MinaBlockBlockStableV2 {
+            header: v2::MinaBlockHeaderStableV2 {
+                protocol_state: genesis,
+                protocol_state_proof: dummy_blockchain_proof().clone(),
+                delta_block_chain_proof: (genesis_hash, std::iter::empty().collect()),
+                current_protocol_version: PROTOCOL_VERSION.clone(),
+                proposed_protocol_version_opt: None,
+            },
+            body: v2::StagedLedgerDiffBodyStableV1 {
+                staged_ledger_diff: empty_block_body(),
+            },
+        }
+        .into(),
+    )
+}
@@ -9,6 +9,7 @@ pub use transition_frontier_sync_actions::*;
 mod transition_frontier_sync_reducer;
 
 mod transition_frontier_sync_effects;
+pub use transition_frontier_sync_effects::frontier_commit_request;
 
 use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use mina_p2p_messages::v2::{LedgerHash, StateHash};
 use openmina_core::block::ArcBlockWithHash;
 use openmina_core::consensus::consensus_take;
@@ -5,7 +7,7 @@ use openmina_core::ActionEvent;
 use redux::Callback;
 use serde::{Deserialize, Serialize};
 
-use crate::ledger::write::CommitResult;
+use crate::ledger::write::{BlockApplyResultArchive, CommitResult};
 use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
 use crate::transition_frontier::sync::TransitionFrontierSyncLedgerPending;
@@ -105,6 +107,7 @@ pub enum TransitionFrontierSyncAction {
     BlocksNextApplySuccess {
         hash: StateHash,
         just_emitted_a_proof: bool,
+        archive_data: Option<Arc<BlockApplyResultArchive>>,
     },
     /// Done applying all pending blocks
     BlocksSuccess,
@@ -123,7 +126,8 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncAction {
     fn is_enabled(&self, state: &crate::State, time: redux::Timestamp) -> bool {
         match self {
             TransitionFrontierSyncAction::Init { best_tip, .. } => {
-                !state.transition_frontier.sync.is_pending()
+                state.transition_frontier.restore.is_finished()
+                    && !state.transition_frontier.sync.is_pending()
                     && !state.transition_frontier.sync.is_synced()
                     && state
                         .transition_frontier
@@ -346,6 +350,7 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierSyncAction {
             TransitionFrontierSyncAction::BlocksNextApplySuccess {
                 hash,
                 just_emitted_a_proof: _,
+                archive_data: _,
             } => state
                 .transition_frontier
                 .sync
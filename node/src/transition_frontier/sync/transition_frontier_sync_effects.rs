@@ -1,4 +1,6 @@
-use mina_p2p_messages::v2::LedgerHash;
+use std::collections::BTreeMap;
+
+use mina_p2p_messages::v2::{LedgerHash, MinaStateProtocolStateValueStableV2, StateHash};
 use openmina_core::block::{AppliedBlock, ArcBlockWithHash};
 use p2p::channels::rpc::{P2pChannelsRpcAction, P2pRpcId};
 use p2p::PeerId;
@@ -12,7 +14,10 @@ use crate::{p2p_ready, Service, Store, TransitionFrontierAction};
 use super::ledger::snarked::TransitionFrontierSyncLedgerSnarkedAction;
 use super::ledger::staged::TransitionFrontierSyncLedgerStagedAction;
 use super::ledger::{SyncLedgerTarget, TransitionFrontierSyncLedgerAction};
-use super::{SyncError, TransitionFrontierSyncAction, TransitionFrontierSyncState};
+use super::{
+    SyncError, TransitionFrontierRootSnarkedLedgerUpdates, TransitionFrontierSyncAction,
+    TransitionFrontierSyncState,
+};
 
 impl TransitionFrontierSyncAction {
     pub fn effects<S>(&self, meta: &ActionMeta, store: &mut Store<S>)
@@ -265,11 +270,14 @@ impl TransitionFrontierSyncAction {
                     || super::CATCHUP_BLOCK_VERIFY_TAIL_LENGTH
                         < store.state().transition_frontier.sync.pending_count();
 
+                let archive = store.state().transition_frontier.config.archive;
+
                 store.dispatch(LedgerWriteAction::Init {
                     request: LedgerWriteRequest::BlockApply {
                         block,
                         pred_block,
                         skip_verification,
+                        archive,
                     },
                     on_init: redux::callback!(
                         on_block_next_apply_init(request: LedgerWriteRequest) -> crate::Action {
@@ -277,6 +285,7 @@ impl TransitionFrontierSyncAction {
                                 block,
                                 pred_block: _,
                                 skip_verification: _,
+                                archive: _,
                             } = request
                             else {
                                 unreachable!()
@@ -308,11 +317,25 @@ impl TransitionFrontierSyncAction {
             TransitionFrontierSyncAction::BlocksNextApplySuccess {
                 hash,
                 just_emitted_a_proof: _,
+                archive_data,
             } => {
                 if let Some(stats) = store.service.stats() {
                     stats.block_producer().block_apply_end(meta.time(), hash);
                 }
 
+                if let Some(archive_data) = archive_data {
+                    let block = store
+                        .state()
+                        .transition_frontier
+                        .sync
+                        .block_state(hash)
+                        .and_then(|state| state.block())
+                        .cloned();
+                    if let Some(block) = block {
+                        store.service.send_to_archive(block, archive_data.clone());
+                    }
+                }
+
                 if !store.dispatch(TransitionFrontierSyncAction::BlocksNextApplyInit) {
                     store.dispatch(TransitionFrontierSyncAction::BlocksSuccess);
                 }
@@ -334,58 +357,18 @@ impl TransitionFrontierSyncAction {
                 else {
                     return;
                 };
-                let Some(new_root) = chain.first() else {
-                    return;
-                };
-                let Some(new_best_tip) = chain.last() else {
+                let Some(request) = frontier_commit_request(
+                    &transition_frontier.best_chain,
+                    &transition_frontier.needed_protocol_states,
+                    chain,
+                    root_snarked_ledger_updates.clone(),
+                    needed_protocol_states,
+                ) else {
                     return;
                 };
-                let ledgers_to_keep = chain
-                    .iter()
-                    .flat_map(|b| {
-                        [
-                            b.snarked_ledger_hash(),
-                            b.merkle_root_hash(),
-                            b.staking_epoch_ledger_hash(),
-                            b.next_epoch_ledger_hash(),
-                        ]
-                    })
-                    .cloned()
-                    .collect();
-                let mut root_snarked_ledger_updates = root_snarked_ledger_updates.clone();
-                if transition_frontier
-                    .best_chain
-                    .iter()
-                    .any(|b| b.hash() == new_root.hash())
-                {
-                    let old_chain = transition_frontier
-                        .best_chain
-                        .iter()
-                        .map(AppliedBlock::block_with_hash);
-                    root_snarked_ledger_updates
-                        .extend_with_needed(new_root.block_with_hash(), old_chain);
-                }
-
-                let needed_protocol_states = if root_snarked_ledger_updates.is_empty() {
-                    // We don't need protocol states unless we need to
-                    // recreate some snarked ledgers during `commit`.
-                    Default::default()
-                } else {
-                    needed_protocol_states
-                        .iter()
-                        .chain(&transition_frontier.needed_protocol_states)
-                        .map(|(k, v)| (k.clone(), v.clone()))
-                        .collect()
-                };
 
                 store.dispatch(LedgerWriteAction::Init {
-                    request: LedgerWriteRequest::Commit {
-                        ledgers_to_keep,
-                        root_snarked_ledger_updates,
-                        needed_protocol_states,
-                        new_root: new_root.clone(),
-                        new_best_tip: new_best_tip.clone(),
-                    },
+                    request,
                     on_init: redux::callback!(
                         on_frontier_commit_init(_request: LedgerWriteRequest) -> crate::Action {
                             TransitionFrontierSyncAction::CommitPending
@@ -404,6 +387,56 @@ impl TransitionFrontierSyncAction {
 
 // Helper functions
 
+/// Ledger commit moving the transition frontier from `old_chain` to
+/// `new_chain`, with the root snarked ledger updates and the protocol
+/// states gathered while syncing `new_chain`.
+pub fn frontier_commit_request(
+    old_chain: &[AppliedBlock],
+    old_needed_protocol_states: &BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+    new_chain: &[AppliedBlock],
+    mut root_snarked_ledger_updates: TransitionFrontierRootSnarkedLedgerUpdates,
+    needed_protocol_states: &BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
+) -> Option<LedgerWriteRequest> {
+    let new_root = new_chain.first()?;
+    let new_best_tip = new_chain.last()?;
+    let ledgers_to_keep = new_chain
+        .iter()
+        .flat_map(|b| {
+            [
+                b.snarked_ledger_hash(),
+                b.merkle_root_hash(),
+                b.staking_epoch_ledger_hash(),
+                b.next_epoch_ledger_hash(),
+            ]
+        })
+        .cloned()
+        .collect();
+    if old_chain.iter().any(|b| b.hash() == new_root.hash()) {
+        let old_chain = old_chain.iter().map(AppliedBlock::block_with_hash);
+        root_snarked_ledger_updates.extend_with_needed(new_root.block_with_hash(), old_chain);
+    }
+
+    let needed_protocol_states = if root_snarked_ledger_updates.is_empty() {
+        // We don't need protocol states unless we need to
+        // recreate some snarked ledgers during `commit`.
+        Default::default()
+    } else {
+        needed_protocol_states
+            .iter()
+            .chain(old_needed_protocol_states)
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect()
+    };
+
+    Some(LedgerWriteRequest::Commit {
+        ledgers_to_keep,
+        root_snarked_ledger_updates,
+        needed_protocol_states,
+        new_root: new_root.clone(),
+        new_best_tip: new_best_tip.clone(),
+    })
+}
+
 /// Gets from the current state the best tip sync target
 fn sync_best_tip(state: &crate::State) -> ArcBlockWithHash {
     state.transition_frontier.sync.best_tip().unwrap().clone()
@@ -620,6 +620,7 @@ impl TransitionFrontierSyncState {
             TransitionFrontierSyncAction::BlocksNextApplySuccess {
                 hash,
                 just_emitted_a_proof,
+                archive_data: _,
             } => {
                 let Some(block_state) = state.block_state_mut(hash) else {
                     return;
@@ -4,7 +4,7 @@ use mina_p2p_messages::v2::{self, LedgerHash, MinaStateProtocolStateValueStableV
 use openmina_core::block::{AppliedBlock, ArcBlockWithHash};
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
-use strum_macros::Display;
+use strum_macros::{Display, VariantNames};
 
 use crate::p2p::channels::rpc::P2pRpcId;
 use crate::p2p::PeerId;
@@ -12,7 +12,7 @@ use crate::p2p::PeerId;
 use super::ledger::{SyncLedgerTarget, SyncLedgerTargetKind, TransitionFrontierSyncLedgerState};
 use super::PeerBlockFetchError;
 
-#[derive(Serialize, Deserialize, Display, Debug, Clone)]
+#[derive(Serialize, Deserialize, Display, VariantNames, Debug, Clone)]
 pub enum TransitionFrontierSyncState {
     Idle,
     Init {
@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use super::genesis::TransitionFrontierGenesisAction;
 use super::genesis_effectful::TransitionFrontierGenesisEffectfulAction;
 use super::sync::{SyncError, TransitionFrontierSyncAction, TransitionFrontierSyncState};
+use crate::ledger::write::{LedgerWriteState, RestoredFrontier};
 
 pub type TransitionFrontierActionWithMeta = redux::ActionWithMeta<TransitionFrontierAction>;
 pub type TransitionFrontierActionWithMetaRef<'a> =
@@ -29,6 +30,20 @@ pub enum TransitionFrontierAction {
     #[action_event(level = info)]
     GenesisProvenInject,
 
+    /// Restore the frontier persisted by the previous run, before
+    /// initializing it from genesis or syncing.
+    #[action_event(level = info)]
+    RestoreInit,
+    RestorePending,
+    #[action_event(level = info)]
+    RestoreSuccess {
+        restored: Option<Arc<RestoredFrontier>>,
+    },
+    #[action_event(level = warn, fields(error))]
+    RestoreError {
+        error: String,
+    },
+
     Sync(TransitionFrontierSyncAction),
     /// Transition frontier synced.
     Synced {
@@ -48,6 +63,7 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierAction {
             TransitionFrontierAction::GenesisEffect(a) => a.is_enabled(state, time),
             TransitionFrontierAction::GenesisInject => {
                 state.transition_frontier.root().is_none()
+                    && state.transition_frontier.restore.is_finished()
                     && state
                         .transition_frontier
                         .genesis
@@ -62,6 +78,18 @@ impl redux::EnablingCondition<crate::State> for TransitionFrontierAction {
                     b.is_genesis() && !Arc::ptr_eq(&genesis.block, &b.block)
                 })
             }
+            TransitionFrontierAction::RestoreInit => {
+                state.transition_frontier.restore.is_idle()
+                    && matches!(
+                        state.ledger.write,
+                        LedgerWriteState::Idle | LedgerWriteState::Success { .. }
+                    )
+            }
+            TransitionFrontierAction::RestorePending => state.transition_frontier.restore.is_idle(),
+            TransitionFrontierAction::RestoreSuccess { .. }
+            | TransitionFrontierAction::RestoreError { .. } => {
+                state.transition_frontier.restore.is_pending()
+            }
             TransitionFrontierAction::Sync(a) => a.is_enabled(state, time),
             TransitionFrontierAction::Synced { .. } => matches!(
                 state.transition_frontier.sync,
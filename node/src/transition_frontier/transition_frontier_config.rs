@@ -7,10 +7,21 @@ use super::genesis::TransitionFrontierGenesisConfig;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransitionFrontierConfig {
     pub genesis: Arc<TransitionFrontierGenesisConfig>,
+    /// Whether the frontier is persisted by the ledger service, in which
+    /// case it is restored from there before syncing.
+    #[serde(default)]
+    pub persistent: bool,
+    /// Whether applied blocks are sent to the archive service.
+    #[serde(default)]
+    pub archive: bool,
 }
 
 impl TransitionFrontierConfig {
     pub fn new(genesis: Arc<TransitionFrontierGenesisConfig>) -> Self {
-        TransitionFrontierConfig { genesis }
+        TransitionFrontierConfig {
+            genesis,
+            persistent: false,
+            archive: false,
+        }
     }
 }
@@ -1,9 +1,12 @@
+use mina_p2p_messages::binprot::BinProtWrite;
 use redux::Timestamp;
 
 use crate::block_producer::BlockProducerAction;
 use crate::consensus::ConsensusAction;
 use crate::ledger::LEDGER_DEPTH;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
+use crate::p2p::P2pNetworkBitswapAction;
+use crate::rpc::RpcAction;
 use crate::snark_pool::{SnarkPoolAction, SnarkWork};
 use crate::stats::sync::SyncingLedger;
 use crate::{Store, TransactionPoolAction};
@@ -69,6 +72,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                             stats.syncing_blocks_init(chain);
                         }
                     }
+                    store.dispatch(RpcAction::TransitionFrontierNotify);
                 }
                 TransitionFrontierSyncAction::BestTipUpdate {
                     ref best_tip,
@@ -97,6 +101,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                             stats.syncing_blocks_init(chain);
                         }
                     }
+                    store.dispatch(RpcAction::TransitionFrontierNotify);
                 }
                 TransitionFrontierSyncAction::LedgerStakingPending => {
                     if let Some(stats) = store.service.stats() {
@@ -201,6 +206,7 @@ pub fn transition_frontier_effects<S: crate::Service>(
                 TransitionFrontierSyncAction::BlocksNextApplySuccess {
                     ref hash,
                     just_emitted_a_proof: _,
+                    archive_data: _,
                 } => {
                     if let Some(stats) = store.service.stats() {
                         if let Some(state) =
@@ -269,6 +275,14 @@ pub fn transition_frontier_effects<S: crate::Service>(
             }
             a.effects(&meta, store);
         }
+        TransitionFrontierAction::RestoreInit => {}
+        TransitionFrontierAction::RestorePending => {}
+        TransitionFrontierAction::RestoreSuccess { restored } => {
+            if restored.is_some() {
+                synced_effects(&meta, store);
+            }
+        }
+        TransitionFrontierAction::RestoreError { .. } => {}
         TransitionFrontierAction::Synced { .. } => {
             synced_effects(&meta, store);
         }
@@ -294,6 +308,7 @@ fn synced_effects<S: crate::Service>(
     if let Some(stats) = store.service.stats() {
         stats.new_best_chain(meta.time(), best_chain);
     }
+    store.service.export_precomputed_blocks(best_chain);
 
     let chain_diff = chain_diff.clone();
 
@@ -306,6 +321,12 @@ fn synced_effects<S: crate::Service>(
         });
     }
 
+    // make the new best tip available to peers over bitswap.
+    let mut block = Vec::new();
+    if best_tip.block().binprot_write(&mut block).is_ok() {
+        store.dispatch(P2pNetworkBitswapAction::AddResource { data: block.into() });
+    }
+
     let best_tip_hash = best_tip.merkle_root_hash().clone();
     store.dispatch(ConsensusAction::Prune);
     store.dispatch(BlockProducerAction::BestTipUpdate {
@@ -320,6 +341,7 @@ fn synced_effects<S: crate::Service>(
             diff,
         });
     }
+    store.dispatch(RpcAction::TransitionFrontierNotify);
 }
 
 // Handling of the actions related to the synchronization of a target ledger
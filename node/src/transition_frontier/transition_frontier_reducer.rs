@@ -1,7 +1,10 @@
 use super::sync::{SyncError, TransitionFrontierSyncState};
 use super::{
-    TransitionFrontierAction, TransitionFrontierActionWithMetaRef, TransitionFrontierState,
+    TransitionFrontierAction, TransitionFrontierActionWithMetaRef, TransitionFrontierRestoreState,
+    TransitionFrontierState,
 };
+use crate::ledger::write::{LedgerWriteAction, LedgerWriteRequest};
+use crate::ledger_effectful::LedgerEffectfulAction;
 use openmina_core::block::AppliedBlock;
 
 impl TransitionFrontierState {
@@ -54,6 +57,44 @@ impl TransitionFrontierState {
                     state.sync = TransitionFrontierSyncState::Synced { time: meta.time() };
                 }
             }
+            TransitionFrontierAction::RestoreInit => {
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(LedgerWriteAction::Init {
+                    request: LedgerWriteRequest::FrontierRestore,
+                    on_init: redux::callback!(
+                        on_frontier_restore_init(_request: LedgerWriteRequest) -> crate::Action {
+                            TransitionFrontierAction::RestorePending
+                        }
+                    ),
+                });
+            }
+            TransitionFrontierAction::RestorePending => {
+                state.restore = TransitionFrontierRestoreState::Pending { time: meta.time() };
+            }
+            TransitionFrontierAction::RestoreSuccess { restored } => {
+                state.restore = TransitionFrontierRestoreState::Success {
+                    time: meta.time(),
+                    restored: restored.is_some(),
+                };
+                if let Some(restored) = restored {
+                    state.best_chain.clone_from(&restored.best_chain);
+                    state
+                        .needed_protocol_states
+                        .clone_from(&restored.needed_protocol_states);
+                    state.sync = TransitionFrontierSyncState::Synced { time: meta.time() };
+                } else {
+                    let dispatcher = state_context.into_dispatcher();
+                    dispatcher.push(TransitionFrontierAction::GenesisInject);
+                }
+            }
+            TransitionFrontierAction::RestoreError { error } => {
+                state.restore = TransitionFrontierRestoreState::Error {
+                    time: meta.time(),
+                    error: error.clone(),
+                };
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(TransitionFrontierAction::GenesisInject);
+            }
             TransitionFrontierAction::Sync(a) => {
                 let best_chain = state.best_chain.clone();
                 super::sync::TransitionFrontierSyncState::reducer(
@@ -107,6 +148,16 @@ impl TransitionFrontierState {
                 state.chain_diff = state.maybe_make_chain_diff(&new_chain);
                 state.best_chain = new_chain;
                 state.sync = TransitionFrontierSyncState::Synced { time: meta.time() };
+
+                if state.config.persistent {
+                    let best_chain = state.best_chain.clone();
+                    let needed_protocol_states = state.needed_protocol_states.clone();
+                    let dispatcher = state_context.into_dispatcher();
+                    dispatcher.push(LedgerEffectfulAction::FrontierPersist {
+                        best_chain,
+                        needed_protocol_states,
+                    });
+                }
             }
             TransitionFrontierAction::SyncFailed { error, .. } => {
                 match error {
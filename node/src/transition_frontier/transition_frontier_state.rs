@@ -31,10 +31,53 @@ pub struct TransitionFrontierState {
     pub blacklist: BTreeMap<StateHash, u32>,
     /// The diff of `Self::best_chain` with the previous one
     pub chain_diff: Option<BestTipDiff>,
+    /// Restoring of the frontier persisted by the previous run.
+    pub restore: TransitionFrontierRestoreState,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum TransitionFrontierRestoreState {
+    /// Frontier isn't persisted, so there is nothing to restore.
+    Disabled,
+    Idle,
+    Pending {
+        time: redux::Timestamp,
+    },
+    Success {
+        time: redux::Timestamp,
+        restored: bool,
+    },
+    Error {
+        time: redux::Timestamp,
+        error: String,
+    },
+}
+
+impl TransitionFrontierRestoreState {
+    pub fn is_idle(&self) -> bool {
+        matches!(self, Self::Idle)
+    }
+
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Pending { .. })
+    }
+
+    /// Frontier can't be initialized by other means until restoring is done.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            Self::Disabled | Self::Success { .. } | Self::Error { .. }
+        )
+    }
 }
 
 impl TransitionFrontierState {
     pub fn new(config: TransitionFrontierConfig) -> Self {
+        let restore = if config.persistent {
+            TransitionFrontierRestoreState::Idle
+        } else {
+            TransitionFrontierRestoreState::Disabled
+        };
         Self {
             config,
             genesis: TransitionFrontierGenesisState::Idle,
@@ -43,6 +86,7 @@ impl TransitionFrontierState {
             sync: TransitionFrontierSyncState::Idle,
             blacklist: Default::default(),
             chain_diff: None,
+            restore,
         }
     }
 
@@ -101,7 +145,8 @@ impl TransitionFrontierState {
     }
 
     /// Create a diff between the old best chain and the new one
-    /// This is used to update the transaction pool
+    /// This is used to update the transaction pool and to notify RPC
+    /// subscribers of chain reorganizations
     pub fn maybe_make_chain_diff(&self, new_chain: &[AppliedBlock]) -> Option<BestTipDiff> {
         let old_chain = self.best_chain.as_slice();
         let new_root = new_chain.first();
@@ -185,15 +230,18 @@ impl TransitionFrontierState {
 
         let removed_commands = collect(diff_old_chain);
         let new_commands = collect(diff_new_chain);
+        // Blocks of the old chain were dropped, so the new best tip doesn't
+        // extend the old one.
+        let reorg_best_tip = !diff_old_chain.is_empty();
 
-        if removed_commands.is_empty() && new_commands.is_empty() {
+        if removed_commands.is_empty() && new_commands.is_empty() && !reorg_best_tip {
             return None;
         }
 
         Some(BestTipDiff {
             new_commands,
             removed_commands,
-            reorg_best_tip: false, // TODO: Unused for now
+            reorg_best_tip,
         })
     }
 }
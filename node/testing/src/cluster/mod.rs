@@ -275,6 +275,7 @@ impl Cluster {
             },
             p2p: P2pConfig {
                 libp2p_port: Some(libp2p_port),
+                libp2p_quic_port: None,
                 listen_port: Some(http_port),
                 identity_pub_key: p2p_sec_key.public_key(),
                 initial_peers,
@@ -290,9 +291,11 @@ impl Cluster {
                         .unwrap_or_default(),
                     ..Default::default()
                 },
+                ban: Default::default(),
+                peer_store: Default::default(),
             },
             transition_frontier: TransitionFrontierConfig::new(testing_config.genesis),
-            block_producer: block_producer_config,
+            block_producers: block_producer_config.into_iter().collect(),
             tx_pool: ledger::transaction_pool::Config {
                 trust_system: (),
                 pool_max_size: 3000,
@@ -339,7 +342,7 @@ impl Cluster {
                 let task = async {
                     tokio::select! {
                         _ = shutdown.closed() => {}
-                        _ = http_server::run(http_port, rpc_sender) => {}
+                        _ = http_server::run(http_port, rpc_sender, None) => {}
                     }
                 };
                 local_set.block_on(&runtime, task);
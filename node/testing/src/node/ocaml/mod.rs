@@ -165,6 +165,7 @@ impl OcamlNode {
             peer_id: self.peer_id(),
             host: [127, 0, 0, 1].into(),
             port: self.libp2p_port,
+            transport: Default::default(),
         })
     }
 
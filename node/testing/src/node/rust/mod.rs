@@ -72,6 +72,7 @@ impl Node {
                 peer_id,
                 host: node::p2p::webrtc::Host::Ipv4([127, 0, 0, 1].into()),
                 port: self.store.state().p2p.config().libp2p_port.unwrap(),
+                transport: Default::default(),
             };
             P2pConnectionOutgoingInitOpts::LibP2P(opts)
         }
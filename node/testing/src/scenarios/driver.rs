@@ -475,6 +475,18 @@ fn is_network_connection_finalized(conn_state: &P2pNetworkConnectionState) -> Op
             })),
         ..
     } = conn_state
+    {
+        Some(remote_peer_id)
+    } else if let P2pNetworkConnectionState {
+        auth: Some(P2pNetworkAuthState::Tls(remote_peer_id)),
+        mux:
+            Some(P2pNetworkConnectionMuxState::Yamux(P2pNetworkYamuxState {
+                terminated: None,
+                init: true,
+                ..
+            })),
+        ..
+    } = conn_state
     {
         Some(remote_peer_id)
     } else {
@@ -50,6 +50,7 @@ impl MultiNodeVrfGetCorrectSlots {
                     pub_key: sec_key.public_key().into(),
                     custom_coinbase_receiver: None,
                     proposed_protocol_version: None,
+                    transaction_selection: Vec::new(),
                 },
                 sec_key,
             }),
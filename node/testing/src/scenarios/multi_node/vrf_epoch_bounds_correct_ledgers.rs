@@ -66,6 +66,7 @@ impl MultiNodeVrfEpochBoundsCorrectLedger {
                     pub_key: sec_key.public_key().into(),
                     custom_coinbase_receiver: None,
                     proposed_protocol_version: None,
+                    transaction_selection: Vec::new(),
                 },
                 sec_key: sec_key.clone(),
             }),
@@ -138,8 +139,9 @@ impl MultiNodeVrfEpochBoundsCorrectLedger {
         let initial_balance = if let Some(pending_evaluation) = vrf_evaluator.current_evaluation() {
             let (_, balance) = pending_evaluation
                 .epoch_data
-                .delegator_table
-                .get(&AccountIndex(1))
+                .delegator_tables
+                .values()
+                .find_map(|table| table.get(&AccountIndex(1)))
                 .expect("Account not found");
             eprintln!("Initial balance: {balance}");
             *balance
@@ -234,8 +236,9 @@ impl MultiNodeVrfEpochBoundsCorrectLedger {
         let new_balance = if let Some(pending_evaluation) = vrf_evaluator.current_evaluation() {
             let (_, balance) = pending_evaluation
                 .epoch_data
-                .delegator_table
-                .get(&AccountIndex(1))
+                .delegator_tables
+                .values()
+                .find_map(|table| table.get(&AccountIndex(1)))
                 .expect("Account not found");
             eprintln!("New balance: {balance}");
             *balance
@@ -52,6 +52,7 @@ impl MultiNodeVrfEpochBoundsEvaluation {
                     pub_key: sec_key.public_key().into(),
                     custom_coinbase_receiver: None,
                     proposed_protocol_version: None,
+                    transaction_selection: Vec::new(),
                 },
                 sec_key: sec_key.clone(),
             }),
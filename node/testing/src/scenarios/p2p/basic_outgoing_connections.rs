@@ -25,6 +25,7 @@ fn custom_listener(peer_id: PeerId, port: u16) -> ListenerNode {
         peer_id,
         host: node::p2p::webrtc::Host::Ipv4([127, 0, 0, 1].into()),
         port,
+        transport: Default::default(),
     })
     .into()
 }
@@ -181,6 +182,7 @@ impl DontConnectToSelfInitialPeer {
                 peer_id,
                 host: node::p2p::webrtc::Host::Ipv4([127, 0, 0, 1].into()),
                 port,
+                transport: Default::default(),
             });
         let (node_ut, _) = driver.add_rust_node(
             RustNodeTestingConfig::devnet_default()
@@ -378,6 +380,7 @@ impl ConnectToUnavailableInitialPeers {
                         peer_id,
                         host: [127, 0, 0, 1].into(),
                         port,
+                        transport: Default::default(),
                     }
                     .into(),
                 );
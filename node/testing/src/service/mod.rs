@@ -20,12 +20,17 @@ use mina_p2p_messages::v2::{
 use node::account::AccountPublicKey;
 use node::block_producer::vrf_evaluator::VrfEvaluatorInput;
 use node::block_producer::BlockProducerEvent;
+use node::core::block::{AppliedBlock, ArcBlockWithHash};
 use node::core::channels::mpsc;
 use node::core::invariants::InvariantsState;
 use node::core::snark::{Snark, SnarkJobId};
+use node::event_sink::NodeEvent;
 use node::external_snark_worker_effectful::ExternalSnarkWorkerEvent;
+use node::ledger::write::BlockApplyResultArchive;
+use node::p2p::ban::P2pBan;
+use node::p2p::peer_store::P2pPeerStoreEntry;
 use node::p2p::service_impl::webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p;
-use node::p2p::P2pCryptoService;
+use node::p2p::{P2pBanService, P2pCryptoService, P2pPeerStoreService};
 use node::recorder::Recorder;
 use node::service::{
     BlockProducerService, BlockProducerVrfEvaluatorService, TransitionFrontierGenesisService,
@@ -321,6 +326,18 @@ impl P2pCryptoService for NodeTestingService {
     }
 }
 
+impl P2pBanService for NodeTestingService {
+    fn ban_list_persist(&mut self, bans: &BTreeMap<PeerId, P2pBan>) {
+        self.real.ban_list_persist(bans)
+    }
+}
+
+impl P2pPeerStoreService for NodeTestingService {
+    fn peer_store_persist(&mut self, peers: &BTreeMap<PeerId, P2pPeerStoreEntry>) {
+        self.real.peer_store_persist(peers)
+    }
+}
+
 impl node::ledger::LedgerService for NodeTestingService {
     fn ledger_manager(&self) -> &node::ledger::LedgerManager {
         self.real.ledger_manager()
@@ -345,6 +362,26 @@ impl TransitionFrontierGenesisService for NodeTestingService {
     }
 }
 
+impl node::service::ArchiveService for NodeTestingService {
+    fn send_to_archive(&mut self, block: ArcBlockWithHash, data: Arc<BlockApplyResultArchive>) {
+        node::service::ArchiveService::send_to_archive(&mut self.real, block, data);
+    }
+
+    fn export_precomputed_blocks(&mut self, best_chain: &[AppliedBlock]) {
+        node::service::ArchiveService::export_precomputed_blocks(&mut self.real, best_chain);
+    }
+}
+
+impl node::service::EventSinkService for NodeTestingService {
+    fn event_sink_enabled(&self) -> bool {
+        node::service::EventSinkService::event_sink_enabled(&self.real)
+    }
+
+    fn event_sink_publish(&mut self, event: NodeEvent) {
+        node::service::EventSinkService::event_sink_publish(&mut self.real, event);
+    }
+}
+
 impl P2pServiceWebrtc for NodeTestingService {
     type Event = Event;
 
@@ -419,6 +456,10 @@ impl P2pServiceWebrtcWithLibp2p for NodeTestingService {
     fn connections(&self) -> std::collections::BTreeSet<PeerId> {
         self.real.connections()
     }
+
+    fn bandwidth(&self) -> &node::p2p::service_impl::bandwidth::P2pBandwidth {
+        self.real.bandwidth()
+    }
 }
 
 impl SnarkBlockVerifyService for NodeTestingService {
@@ -514,7 +555,12 @@ impl BlockProducerService for NodeTestingService {
             let dummy_proof = (*ledger::dummy::dummy_blockchain_proof()).clone();
             BlockProducerEvent::BlockProve(block_hash, Ok(dummy_proof.into())).into()
         }
-        let keypair = self.real.block_producer().unwrap().keypair();
+        let keypair = self
+            .real
+            .block_producer()
+            .unwrap()
+            .block_creator_keypair(&input)
+            .expect("block creator must be one of the producer keys");
 
         match self.proof_kind() {
             ProofKind::Dummy => {
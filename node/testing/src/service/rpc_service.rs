@@ -20,6 +20,11 @@ impl RpcService for super::NodeTestingService {
         respond_block_producer_stats_get,
         node::rpc::RpcBlockProducerStatsGetResponse
     );
+    to_real!(
+        respond_block_producer_schedule_get,
+        node::rpc::RpcBlockProducerScheduleGetResponse
+    );
+    to_real!(respond_metrics_get, node::rpc::RpcMetricsGetResponse);
 
     to_real!(
         respond_action_stats_get,
@@ -34,6 +39,29 @@ impl RpcService for super::NodeTestingService {
         respond_p2p_connection_outgoing,
         node::rpc::RpcP2pConnectionOutgoingResponse,
     );
+    to_real!(
+        respond_p2p_node_status_get,
+        node::rpc::RpcP2pNodeStatusGetResponse,
+    );
+    to_real!(
+        respond_p2p_bitswap_resource_add,
+        node::rpc::RpcP2pBitswapResourceAddResponse,
+    );
+    to_real!(
+        respond_p2p_bitswap_resource_get,
+        node::rpc::RpcP2pBitswapResourceGetResponse,
+    );
+    to_real!(respond_p2p_bans_get, node::rpc::RpcP2pBansGetResponse,);
+    to_real!(respond_p2p_ban_add, node::rpc::RpcP2pBanAddResponse,);
+    to_real!(respond_p2p_ban_remove, node::rpc::RpcP2pBanRemoveResponse,);
+    to_real!(
+        respond_p2p_bandwidth_get,
+        node::rpc::RpcP2pBandwidthGetResponse,
+    );
+    to_real!(
+        respond_p2p_yamux_stats_get,
+        node::rpc::RpcP2pYamuxStatsGetResponse,
+    );
     to_real!(
         respond_p2p_connection_incoming_answer,
         P2pConnectionResponse,
@@ -107,4 +135,8 @@ impl RpcService for super::NodeTestingService {
         respond_transaction_status,
         node::rpc::RpcTransactionStatusGetResponse,
     );
+    to_real!(
+        respond_transition_frontier_event,
+        node::rpc::RpcTransitionFrontierSubscribeResponse,
+    );
 }
@@ -209,6 +209,7 @@ impl Simulator {
                         pub_key: sec_key.public_key().into(),
                         custom_coinbase_receiver: None,
                         proposed_protocol_version: None,
+                        transaction_selection: Vec::new(),
                     },
                     sec_key,
                 }),
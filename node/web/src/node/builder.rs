@@ -28,7 +28,7 @@ pub struct NodeBuilder {
     p2p_no_discovery: bool,
     p2p_is_started: bool,
     initial_peers: Vec<P2pConnectionOutgoingInitOpts>,
-    block_producer: Option<BlockProducerConfig>,
+    block_producers: Vec<BlockProducerConfig>,
     snarker: Option<SnarkerConfig>,
     service: NodeServiceCommonBuilder,
     verifier_srs: Option<Arc<VerifierSRS>>,
@@ -48,7 +48,7 @@ impl NodeBuilder {
             p2p_no_discovery: false,
             p2p_is_started: false,
             initial_peers: Vec::new(),
-            block_producer: None,
+            block_producers: Vec::new(),
             snarker: None,
             service: NodeServiceCommonBuilder::new(rng_seed),
             verifier_srs: None,
@@ -116,7 +116,8 @@ impl NodeBuilder {
         Ok(self)
     }
 
-    /// Set up block producer.
+    /// Set up block producer. Can be called multiple times to produce blocks
+    /// with multiple keys.
     pub fn block_producer(
         &mut self,
         key: AccountSecretKey,
@@ -126,18 +127,20 @@ impl NodeBuilder {
             pub_key: key.public_key().into(),
             custom_coinbase_receiver: None,
             proposed_protocol_version: None,
+            transaction_selection: Vec::new(),
         };
-        self.block_producer = Some(config);
+        self.block_producers.push(config);
         self.service.block_producer_init(key, provers);
         self
     }
 
-    /// Receive block producer's coinbase reward to another account.
+    /// Receive the last added block producer's coinbase reward to another
+    /// account.
     pub fn custom_coinbase_receiver(
         &mut self,
         addr: NonZeroCurvePoint,
     ) -> anyhow::Result<&mut Self> {
-        let bp = self.block_producer.as_mut().ok_or_else(|| {
+        let bp = self.block_producers.last_mut().ok_or_else(|| {
             anyhow::anyhow!(
                 "can't set custom_coinbase_receiver when block producer is not initialized."
             )
@@ -150,7 +153,7 @@ impl NodeBuilder {
         &mut self,
         config: BlockProducerConfig,
     ) -> anyhow::Result<&mut Self> {
-        *self.block_producer.as_mut().ok_or_else(|| {
+        *self.block_producers.last_mut().ok_or_else(|| {
             anyhow::anyhow!("block producer not initialized! Call `block_producer` function first.")
         })? = config;
         Ok(self)
@@ -227,6 +230,7 @@ impl NodeBuilder {
             },
             p2p: P2pConfig {
                 libp2p_port: None,
+                libp2p_quic_port: None,
                 listen_port: None,
                 identity_pub_key: p2p_sec_key.public_key(),
                 initial_peers,
@@ -239,6 +243,8 @@ impl NodeBuilder {
                 },
                 timeouts: P2pTimeouts::default(),
                 limits: P2pLimits::default().with_max_peers(Some(100)),
+                ban: Default::default(),
+                peer_store: Default::default(),
             },
             ledger: LedgerConfig {},
             snark: SnarkConfig {
@@ -248,7 +254,7 @@ impl NodeBuilder {
                 work_verifier_srs: srs,
             },
             transition_frontier,
-            block_producer: self.block_producer,
+            block_producers: self.block_producers,
             tx_pool: ledger::transaction_pool::Config {
                 trust_system: (),
                 pool_max_size: node::daemon_json::Daemon::DEFAULT.tx_pool_max_size(),
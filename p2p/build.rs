@@ -5,8 +5,13 @@ fn main() {
         &[
             "src/network/pubsub/message.proto",
             "src/network/identify/p2p_network_identify_message.proto",
+            "src/network/bitswap/message.proto",
+        ],
+        &[
+            "src/network/pubsub",
+            "src/network/identify",
+            "src/network/bitswap",
         ],
-        &["src/network/pubsub", "src/network/identify"],
     )
     .expect("Proto build failed");
 }
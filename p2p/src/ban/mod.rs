@@ -0,0 +1,19 @@
+mod p2p_ban_state;
+pub use p2p_ban_state::*;
+
+mod p2p_ban_actions;
+pub use p2p_ban_actions::*;
+
+mod p2p_ban_reducer;
+
+use serde::{Deserialize, Serialize};
+
+use crate::disconnection::P2pDisconnectionReason;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, thiserror::Error)]
+pub enum P2pBanReason {
+    #[error("disconnected: {0}")]
+    Disconnection(P2pDisconnectionReason),
+    #[error("banned by the operator: {0}")]
+    Manual(String),
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::{P2pState, PeerId};
+
+use super::P2pBanReason;
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(level = info, fields(display(peer_id), display(reason), debug(duration)))]
+pub enum P2pBanAction {
+    /// Ban the peer, disconnecting it if needed.
+    ///
+    /// The ban is permanent if `duration` is `None`.
+    Add {
+        peer_id: PeerId,
+        reason: P2pBanReason,
+        duration: Option<Duration>,
+    },
+    /// Lift the ban.
+    Remove { peer_id: PeerId },
+    /// Lift all bans that expired.
+    #[action_event(level = debug)]
+    PruneExpired,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pBanAction {
+    fn is_enabled(&self, state: &P2pState, time: redux::Timestamp) -> bool {
+        match self {
+            P2pBanAction::Add { peer_id, .. } => peer_id != &state.my_id(),
+            P2pBanAction::Remove { peer_id } => state.ban.is_banned(peer_id),
+            P2pBanAction::PruneExpired => state.ban.expired(time).next().is_some(),
+        }
+    }
+}
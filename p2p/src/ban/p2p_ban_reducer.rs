@@ -0,0 +1,97 @@
+use openmina_core::{info, Substate};
+use redux::ActionWithMeta;
+
+use crate::{
+    ban_effectful::P2pBanEffectfulAction,
+    disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
+    P2pNetworkKadKey, P2pState, PeerId,
+};
+
+use super::{P2pBan, P2pBanAction, P2pBanState};
+
+impl P2pBanState {
+    /// Substate is accessed
+    pub fn reducer<Action, State>(
+        mut state_context: Substate<Action, State, P2pState>,
+        action: ActionWithMeta<P2pBanAction>,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let p2p_state = state_context.get_substate_mut()?;
+        let (action, meta) = action.split();
+
+        match action {
+            P2pBanAction::Add {
+                peer_id,
+                reason,
+                duration,
+            } => {
+                let ban = P2pBan {
+                    reason,
+                    since: meta.time(),
+                    until: duration.map(|duration| meta.time() + duration),
+                };
+                // keep the longest of the bans
+                let ban = match p2p_state.ban.bans.remove(&peer_id) {
+                    Some(existing) if existing.outlasts(&ban) => existing,
+                    _ => ban,
+                };
+                p2p_state.ban.bans.insert(peer_id, ban);
+                Self::kad_ban(p2p_state, peer_id);
+
+                let bans = p2p_state.ban.bans.clone();
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pDisconnectionAction::Init {
+                    peer_id,
+                    reason: P2pDisconnectionReason::Banned,
+                });
+                dispatcher.push(P2pBanEffectfulAction::Persist { bans });
+                Ok(())
+            }
+            P2pBanAction::Remove { peer_id } => {
+                p2p_state.ban.bans.remove(&peer_id);
+                Self::kad_unban(p2p_state, &peer_id);
+
+                let bans = p2p_state.ban.bans.clone();
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pBanEffectfulAction::Persist { bans });
+                Ok(())
+            }
+            P2pBanAction::PruneExpired => {
+                let expired = p2p_state
+                    .ban
+                    .expired(meta.time())
+                    .copied()
+                    .collect::<Vec<_>>();
+                for peer_id in &expired {
+                    info!(meta.time(); summary = "ban expired", peer_id = display(peer_id));
+                    p2p_state.ban.bans.remove(peer_id);
+                    Self::kad_unban(p2p_state, peer_id);
+                }
+
+                let bans = p2p_state.ban.bans.clone();
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pBanEffectfulAction::Persist { bans });
+                Ok(())
+            }
+        }
+    }
+
+    fn kad_ban(p2p_state: &mut P2pState, peer_id: PeerId) {
+        let Some(discovery_state) = p2p_state.network.scheduler.discovery_state.as_mut() else {
+            return;
+        };
+        discovery_state.banned_peers.insert(peer_id);
+        if let Ok(key) = P2pNetworkKadKey::try_from(&peer_id) {
+            discovery_state.routing_table.remove(&key);
+        }
+    }
+
+    fn kad_unban(p2p_state: &mut P2pState, peer_id: &PeerId) {
+        if let Some(discovery_state) = p2p_state.network.scheduler.discovery_state.as_mut() {
+            discovery_state.banned_peers.remove(peer_id);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::PeerId;
+
+use super::P2pBanReason;
+
+/// Peers that are not allowed to connect to us.
+///
+/// Expired bans are removed periodically, so a peer stays banned
+/// until the next cleanup after [`P2pBan::until`].
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct P2pBanState {
+    pub bans: BTreeMap<PeerId, P2pBan>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct P2pBan {
+    pub reason: P2pBanReason,
+    pub since: Timestamp,
+    /// `None` if the ban is permanent.
+    pub until: Option<Timestamp>,
+}
+
+impl P2pBanState {
+    pub fn new(bans: BTreeMap<PeerId, P2pBan>) -> Self {
+        Self { bans }
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.bans.contains_key(peer_id)
+    }
+
+    pub fn expired(&self, now: Timestamp) -> impl Iterator<Item = &PeerId> {
+        self.bans
+            .iter()
+            .filter(move |(_, ban)| ban.is_expired(now))
+            .map(|(peer_id, _)| peer_id)
+    }
+}
+
+impl P2pBan {
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.until.map_or(false, |until| now >= until)
+    }
+
+    /// Whether this ban lasts longer than the `other` one.
+    pub fn outlasts(&self, other: &Self) -> bool {
+        match (self.until, other.until) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(until), Some(other_until)) => until > other_until,
+        }
+    }
+}
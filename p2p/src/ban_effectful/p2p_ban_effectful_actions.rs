@@ -0,0 +1,19 @@
+use std::collections::BTreeMap;
+
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::{ban::P2pBan, P2pState, PeerId};
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(level = debug)]
+pub enum P2pBanEffectfulAction {
+    /// Persist the current ban list.
+    Persist { bans: BTreeMap<PeerId, P2pBan> },
+}
+
+impl redux::EnablingCondition<P2pState> for P2pBanEffectfulAction {
+    fn is_enabled(&self, _state: &P2pState, _time: redux::Timestamp) -> bool {
+        true
+    }
+}
@@ -0,0 +1,17 @@
+use redux::ActionMeta;
+
+use super::{P2pBanEffectfulAction, P2pBanService};
+
+impl P2pBanEffectfulAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pBanService,
+    {
+        match self {
+            P2pBanEffectfulAction::Persist { bans } => {
+                store.service().ban_list_persist(&bans);
+            }
+        }
+    }
+}
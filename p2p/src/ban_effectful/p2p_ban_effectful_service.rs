@@ -0,0 +1,8 @@
+use std::collections::BTreeMap;
+
+use crate::{ban::P2pBan, PeerId};
+
+pub trait P2pBanService: redux::Service {
+    /// Stores the ban list, so that bans survive restarts.
+    fn ban_list_persist(&mut self, bans: &BTreeMap<PeerId, P2pBan>);
+}
@@ -51,6 +51,10 @@ impl P2pState {
             return Err(RejectionReason::ConnectingToSelf);
         }
 
+        if self.ban.is_banned(&peer_id) {
+            return Err(RejectionReason::Banned);
+        }
+
         if self.is_peer_connected_or_connecting(&peer_id) {
             // Both nodes trying to connect to each other at the same time.
             // Choose connection arbitrarily based on peer id.
@@ -72,6 +76,10 @@ impl P2pState {
             return Err(RejectionReason::ConnectingToSelf);
         }
 
+        if self.ban.is_banned(&peer_id) {
+            return Err(RejectionReason::Banned);
+        }
+
         if self.already_has_max_ready_peers() {
             return Err(RejectionReason::PeerCapacityFull);
         }
@@ -424,6 +424,7 @@ impl P2pConnectionIncomingState {
                                     peer_id,
                                     host: Host::from(addr.ip()),
                                     port: addr.port(),
+                                    transport: Default::default(),
                                 },
                             )),
                             status: P2pPeerStatus::Disconnected { time: meta.time() },
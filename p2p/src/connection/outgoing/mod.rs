@@ -44,6 +44,54 @@ pub struct P2pConnectionOutgoingInitLibp2pOpts {
     pub peer_id: PeerId,
     pub host: Host,
     pub port: u16,
+    pub transport: Libp2pTransport,
+}
+
+/// Transport used to reach a libp2p peer.
+///
+/// Selected by the multiaddr: `/tcp/<port>` for TCP with pnet, noise and yamux,
+/// `/udp/<port>/quic-v1` for QUIC.
+#[derive(
+    BinProtWrite,
+    BinProtRead,
+    Serialize,
+    Deserialize,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Debug,
+    Clone,
+    Copy,
+)]
+pub enum Libp2pTransport {
+    #[default]
+    Tcp,
+    Quic,
+}
+
+impl Libp2pTransport {
+    pub fn is_quic(&self) -> bool {
+        matches!(self, Self::Quic)
+    }
+
+    /// Appends transport protocols for the given `port` to the multiaddr.
+    pub fn with_port(self, maddr: Multiaddr, port: u16) -> Multiaddr {
+        match self {
+            Self::Tcp => maddr.with(Protocol::Tcp(port)),
+            Self::Quic => maddr.with(Protocol::Udp(port)).with(Protocol::QuicV1),
+        }
+    }
+}
+
+impl fmt::Display for Libp2pTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp => write!(f, "tcp"),
+            Self::Quic => write!(f, "quic"),
+        }
+    }
 }
 
 impl P2pConnectionOutgoingInitLibp2pOpts {
@@ -93,14 +141,12 @@ pub(crate) mod libp2p_opts {
         fn to_peer_id_multiaddr(&self) -> (PeerId, Multiaddr) {
             (
                 self.peer_id,
-                Multiaddr::from_iter([(&self.host).into(), multiaddr::Protocol::Tcp(self.port)]),
+                self.transport
+                    .with_port(Multiaddr::from_iter([(&self.host).into()]), self.port),
             )
         }
         fn into_peer_id_multiaddr(self) -> (PeerId, Multiaddr) {
-            (
-                self.peer_id,
-                Multiaddr::from_iter([(&self.host).into(), multiaddr::Protocol::Tcp(self.port)]),
-            )
+            self.to_peer_id_multiaddr()
         }
 
         pub fn matches_socket_addr(&self, addr: SocketAddr) -> bool {
@@ -138,6 +184,7 @@ pub(crate) mod libp2p_opts {
                 peer_id,
                 host,
                 port,
+                transport: Default::default(),
             }
         }
     }
@@ -240,6 +287,7 @@ impl P2pConnectionOutgoingInitOpts {
                 peer_id: peer_id.try_into().ok()?,
                 host: host.parse().ok()?,
                 port: msg.libp2p_port.as_u64() as u16,
+                transport: Libp2pTransport::Tcp,
             };
             Self::LibP2P(opts)
         };
@@ -252,6 +300,8 @@ impl P2pConnectionOutgoingInitOpts {
     #[cfg(feature = "p2p-libp2p")]
     pub fn try_into_mina_rpc(&self) -> Option<v2::NetworkPeerPeerStableV1> {
         match self {
+            // OCaml nodes only know about TCP addresses.
+            P2pConnectionOutgoingInitOpts::LibP2P(opts) if opts.transport.is_quic() => None,
             P2pConnectionOutgoingInitOpts::LibP2P(opts) => Some(v2::NetworkPeerPeerStableV1 {
                 host: opts.host.to_string().as_bytes().into(),
                 libp2p_port: (opts.port as u64).into(),
@@ -405,14 +455,15 @@ impl TryFrom<P2pConnectionOutgoingInitLibp2pOpts> for multiaddr::Multiaddr {
     fn try_from(value: P2pConnectionOutgoingInitLibp2pOpts) -> Result<Self, Self::Error> {
         use multiaddr::Protocol;
 
-        Ok(Self::empty()
-            .with(match &value.host {
-                // maybe should be just `Dns`?
-                Host::Domain(v) => Protocol::Dns4(v.into()),
-                Host::Ipv4(v) => Protocol::Ip4(*v),
-                Host::Ipv6(v) => Protocol::Ip6(*v),
-            })
-            .with(Protocol::Tcp(value.port))
+        let maddr = Self::empty().with(match &value.host {
+            // maybe should be just `Dns`?
+            Host::Domain(v) => Protocol::Dns4(v.into()),
+            Host::Ipv4(v) => Protocol::Ip4(*v),
+            Host::Ipv6(v) => Protocol::Ip6(*v),
+        });
+        Ok(value
+            .transport
+            .with_port(maddr, value.port)
             .with(Protocol::P2p(libp2p_identity::PeerId::try_from(
                 value.peer_id,
             )?)))
@@ -464,7 +515,7 @@ impl TryFrom<&multiaddr::Multiaddr> for P2pConnectionOutgoingInitLibp2pOpts {
                 }
             },
             port: match iter.next() {
-                Some(Protocol::Tcp(port)) => port,
+                Some(Protocol::Tcp(port) | Protocol::Udp(port)) => port,
                 Some(_) => {
                     return Err(P2pConnectionOutgoingInitOptsParseError::Other(
                         "unexpected part in multiaddr! expected port".to_string(),
@@ -476,6 +527,18 @@ impl TryFrom<&multiaddr::Multiaddr> for P2pConnectionOutgoingInitLibp2pOpts {
                     ));
                 }
             },
+            transport: match maddr.iter().nth(1) {
+                Some(Protocol::Udp(_)) => match iter.next() {
+                    Some(Protocol::QuicV1) => Libp2pTransport::Quic,
+                    _ => {
+                        return Err(P2pConnectionOutgoingInitOptsParseError::Other(
+                            "unexpected part in multiaddr! expected `quic-v1` after udp port"
+                                .to_string(),
+                        ));
+                    }
+                },
+                _ => Libp2pTransport::Tcp,
+            },
             peer_id: match iter.next() {
                 Some(Protocol::P2p(hash)) => libp2p_identity::PeerId::from_multihash(hash.into())
                     .map_err(|_| {
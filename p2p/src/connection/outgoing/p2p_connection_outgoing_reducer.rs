@@ -75,7 +75,10 @@ impl P2pConnectionOutgoingState {
                 if let P2pConnectionOutgoingInitOpts::LibP2P(libp2p_opts) = &opts {
                     match SocketAddr::try_from(libp2p_opts) {
                         Ok(addr) => {
-                            dispatcher.push(P2pNetworkSchedulerAction::OutgoingConnect { addr });
+                            dispatcher.push(P2pNetworkSchedulerAction::OutgoingConnect {
+                                addr,
+                                transport: libp2p_opts.transport,
+                            });
                         }
                         Err(
                             P2pConnectionOutgoingInitLibp2pOptsTryToSocketAddrError::Unresolved(
@@ -115,7 +118,10 @@ impl P2pConnectionOutgoingState {
                 if let P2pConnectionOutgoingInitOpts::LibP2P(libp2p_opts) = &opts {
                     match SocketAddr::try_from(libp2p_opts) {
                         Ok(addr) => {
-                            dispatcher.push(P2pNetworkSchedulerAction::OutgoingConnect { addr });
+                            dispatcher.push(P2pNetworkSchedulerAction::OutgoingConnect {
+                                addr,
+                                transport: libp2p_opts.transport,
+                            });
                         }
                         Err(
                             P2pConnectionOutgoingInitLibp2pOptsTryToSocketAddrError::Unresolved(
@@ -511,6 +517,7 @@ impl P2pConnectionOutgoingState {
                     error: error.clone(),
                     rpc_id,
                 };
+                p2p_state.peer_store.on_failure(&peer_id);
 
                 let (dispatcher, state) = state_context.into_dispatcher_and_state();
                 let p2p_state: &P2pState = state.substate()?;
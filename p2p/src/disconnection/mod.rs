@@ -41,4 +41,10 @@ pub enum P2pDisconnectionReason {
     Timeout,
     #[error("rpc protocol not supported")]
     Unsupported,
+    #[error("pubsub score is below the graylist threshold")]
+    PubsubGraylisted,
+    #[error("peer stopped answering pings")]
+    PingTimeout,
+    #[error("peer is banned")]
+    Banned,
 }
@@ -5,8 +5,9 @@ use rand::prelude::*;
 use redux::ActionWithMeta;
 
 use crate::{
-    disconnection_effectful::P2pDisconnectionEffectfulAction, P2pNetworkSchedulerAction,
-    P2pPeerAction, P2pPeerStatus, P2pState,
+    ban::{P2pBanAction, P2pBanReason},
+    disconnection_effectful::P2pDisconnectionEffectfulAction,
+    P2pNetworkSchedulerAction, P2pPeerAction, P2pPeerStatus, P2pState,
 };
 
 use super::{P2pDisconnectedState, P2pDisconnectionAction, P2pDisconnectionReason};
@@ -52,6 +53,16 @@ impl P2pDisconnectedState {
                 Ok(())
             }
             P2pDisconnectionAction::Init { peer_id, reason } => {
+                let ban =
+                    p2p_state
+                        .config
+                        .ban
+                        .duration(&reason)
+                        .map(|duration| P2pBanAction::Add {
+                            peer_id,
+                            reason: P2pBanReason::Disconnection(reason.clone()),
+                            duration: Some(duration),
+                        });
                 let Some(peer) = p2p_state.peers.get_mut(&peer_id) else {
                     bug_condition!("Invalid state for: `P2pDisconnectionAction::Init`");
                     return Ok(());
@@ -78,11 +89,17 @@ impl P2pDisconnectedState {
                     }
 
                     dispatcher.push(P2pDisconnectionAction::Finish { peer_id });
+                    if let Some(ban) = ban {
+                        dispatcher.push(ban);
+                    }
                     return Ok(());
                 }
 
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(P2pDisconnectionEffectfulAction::Init { peer_id });
+                if let Some(ban) = ban {
+                    dispatcher.push(ban);
+                }
                 Ok(())
             }
             P2pDisconnectionAction::PeerClosed { peer_id } => {
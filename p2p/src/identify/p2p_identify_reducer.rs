@@ -4,7 +4,10 @@ use redux::ActionWithMeta;
 use crate::{
     connection::outgoing::P2pConnectionOutgoingInitOpts,
     disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
-    token::{BroadcastAlgorithm, DiscoveryAlgorithm, IdentifyAlgorithm, RpcAlgorithm, StreamKind},
+    token::{
+        BitswapAlgorithm, BroadcastAlgorithm, DiscoveryAlgorithm, IdentifyAlgorithm, RpcAlgorithm,
+        StreamKind,
+    },
     P2pNetworkConnectionMuxState, P2pNetworkKadRequestAction, P2pNetworkKadState,
     P2pNetworkKademliaAction, P2pNetworkYamuxAction, P2pState, YamuxStreamKind,
 };
@@ -109,6 +112,24 @@ impl P2pState {
                     });
                 }
 
+                // prefer the newest version of bitswap supported by the peer
+                let bitswap_stream_kind = [
+                    BitswapAlgorithm::MinaBitswap1_2_0,
+                    BitswapAlgorithm::MinaBitswap1_1_0,
+                    BitswapAlgorithm::MinaBitswap1_0_0,
+                    BitswapAlgorithm::MinaBitswap,
+                ]
+                .map(StreamKind::Bitswap)
+                .into_iter()
+                .find(|stream_kind| info.protocols.contains(stream_kind));
+                if let Some(stream_kind) = bitswap_stream_kind {
+                    dispatcher.push(P2pNetworkYamuxAction::OpenStream {
+                        addr,
+                        stream_id: YamuxStreamKind::Bitswap.stream_id(addr.incoming),
+                        stream_kind,
+                    });
+                }
+
                 let kad_state: Option<&P2pNetworkKadState> = state.substate().ok();
                 let protocol = StreamKind::Discovery(DiscoveryAlgorithm::Kademlia1_0_0);
                 if kad_state.map_or(false, |state| state.request(&peer_id).is_some())
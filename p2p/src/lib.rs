@@ -1,9 +1,13 @@
 ///#![feature(trivial_bounds)]
+pub mod ban;
+pub mod ban_effectful;
 pub mod channels;
 pub mod connection;
 pub mod disconnection;
 pub mod disconnection_effectful;
 pub mod identity;
+use ban::P2pBanAction;
+use ban_effectful::P2pBanEffectfulAction;
 use bootstrap::P2pNetworkKadBootstrapState;
 use channels::{
     best_tip::P2pChannelsBestTipAction,
@@ -32,6 +36,8 @@ use network::identify::{
     P2pNetworkIdentifyStreamAction,
 };
 use openmina_core::SubstateAccess;
+use peer_store::P2pPeerStoreAction;
+use peer_store_effectful::P2pPeerStoreEffectfulAction;
 
 pub mod webrtc;
 
@@ -43,6 +49,9 @@ pub use self::network::*;
 pub mod peer;
 pub use peer::*;
 
+pub mod peer_store;
+pub mod peer_store_effectful;
+
 mod p2p_config;
 pub use p2p_config::*;
 
@@ -101,6 +110,9 @@ pub trait P2pStateTrait:
     + SubstateAccess<P2pNetworkSchedulerState>
     + SubstateAccess<P2pLimits>
     + SubstateAccess<P2pNetworkPubsubState>
+    + SubstateAccess<P2pNetworkBitswapState>
+    + SubstateAccess<P2pNetworkNodeStatusState>
+    + SubstateAccess<P2pNetworkPingState>
     + SubstateAccess<P2pConfig>
 {
 }
@@ -128,6 +140,9 @@ pub trait P2pActionTrait<State>:
     + From<P2pConnectionIncomingAction>
     + From<P2pNetworkPubsubAction>
     + From<P2pNetworkPubsubEffectfulAction>
+    + From<P2pNetworkBitswapAction>
+    + From<P2pNetworkNodeStatusAction>
+    + From<P2pNetworkPingAction>
     + From<P2pChannelsSignalingExchangeAction>
     + From<P2pChannelsSignalingDiscoveryAction>
     + From<P2pChannelsTransactionAction>
@@ -142,6 +157,10 @@ pub trait P2pActionTrait<State>:
     + From<P2pConnectionIncomingEffectfulAction>
     + From<P2pConnectionOutgoingEffectfulAction>
     + From<P2pDisconnectionEffectfulAction>
+    + From<P2pBanAction>
+    + From<P2pBanEffectfulAction>
+    + From<P2pPeerStoreAction>
+    + From<P2pPeerStoreEffectfulAction>
     + From<P2pNetworkKadEffectfulAction>
     + From<P2pChannelsEffectfulAction>
 {
@@ -0,0 +1,18 @@
+mod pb {
+    include!(concat!(env!("OUT_DIR"), "/bitswap.rs"));
+}
+
+mod p2p_network_bitswap_protocol;
+pub use self::p2p_network_bitswap_protocol::*;
+
+mod p2p_network_bitswap_actions;
+pub use self::p2p_network_bitswap_actions::P2pNetworkBitswapAction;
+
+mod p2p_network_bitswap_state;
+pub use self::p2p_network_bitswap_state::{
+    P2pNetworkBitswapClientState, P2pNetworkBitswapResourceState, P2pNetworkBitswapState,
+    P2pNetworkBitswapStreamError,
+};
+
+#[cfg(feature = "p2p-libp2p")]
+mod p2p_network_bitswap_reducer;
@@ -0,0 +1,80 @@
+use crate::{token::BitswapAlgorithm, ConnectionAddr, Data, P2pState, PeerId, StreamId};
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use super::BitswapCid;
+
+/// Actions of the bitswap exchange.
+///
+/// Handling bitswap streams, serving blocks we have to other peers
+/// and fetching resources (trees of blocks) from them.
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+pub enum P2pNetworkBitswapAction {
+    /// Create a new stream, either incoming or outgoing.
+    NewStream {
+        incoming: bool,
+        peer_id: PeerId,
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+        protocol: BitswapAlgorithm,
+    },
+
+    /// Process incoming raw data from a peer.
+    IncomingData {
+        peer_id: PeerId,
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+        data: Data,
+    },
+
+    /// Remote peer closed the stream.
+    RemoteClose {
+        peer_id: PeerId,
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+    },
+
+    /// Make the resource available to other peers.
+    ///
+    /// The resource is split into blocks, its root can be obtained with
+    /// [`super::bitswap_split`].
+    AddResource { data: Data },
+
+    /// Stop serving or fetching the resource and remove its blocks.
+    #[action_event(fields(display(root)))]
+    RemoveResource { root: BitswapCid },
+
+    /// Start fetching the resource with the given root from connected peers.
+    #[action_event(fields(display(root)))]
+    Want { root: BitswapCid },
+
+    /// Prepare an outgoing message to send to a specific peer.
+    OutgoingMessage { peer_id: PeerId },
+
+    /// Send encoded data over the outgoing stream to a specific peer.
+    OutgoingData { peer_id: PeerId, data: Data },
+}
+
+impl From<P2pNetworkBitswapAction> for crate::P2pAction {
+    fn from(value: P2pNetworkBitswapAction) -> Self {
+        crate::P2pAction::Network(value.into())
+    }
+}
+
+impl redux::EnablingCondition<P2pState> for P2pNetworkBitswapAction {
+    fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
+        let bitswap_state = &state.network.scheduler.bitswap_state;
+        match self {
+            P2pNetworkBitswapAction::Want { root } => !bitswap_state.resources.contains_key(root),
+            P2pNetworkBitswapAction::RemoveResource { root } => {
+                bitswap_state.resources.contains_key(root)
+            }
+            P2pNetworkBitswapAction::OutgoingMessage { peer_id } => {
+                bitswap_state.clients.get(peer_id).map_or(false, |s| {
+                    s.outgoing_stream_id.is_some() && !s.message_is_empty()
+                })
+            }
+            _ => true,
+        }
+    }
+}
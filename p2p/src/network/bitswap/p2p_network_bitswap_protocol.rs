@@ -0,0 +1,303 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    str::FromStr,
+};
+
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use serde::{Deserialize, Serialize};
+
+use crate::Data;
+
+/// Prefix of a CIDv1 with `raw` codec and `blake2b-256` multihash.
+///
+/// This is the only kind of CID Mina nodes use for bitswap blocks.
+pub const BITSWAP_CID_PREFIX: [u8; 6] = [0x01, 0x55, 0xa0, 0xe4, 0x02, 0x20];
+
+/// Maximum size of a single block, the same value as used by the OCaml node.
+pub const BITSWAP_MAX_BLOCK_SIZE: usize = 262144;
+
+const LINKS_COUNT_SIZE: usize = 2;
+const LINK_SIZE: usize = 32;
+
+/// Content identifier of a bitswap block, i.e. blake2b-256 hash of the block.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitswapCid(pub [u8; LINK_SIZE]);
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error, Serialize, Deserialize)]
+pub enum BitswapCidError {
+    #[error("unsupported CID prefix: {0}")]
+    Prefix(String),
+    #[error("invalid CID length: {0}")]
+    Length(usize),
+    #[error("invalid CID hex: {0}")]
+    Hex(String),
+}
+
+impl BitswapCid {
+    /// Computes the CID of the block with the given content.
+    pub fn of_block(block: &[u8]) -> Self {
+        Self(Blake2b::<U32>::digest(block).into())
+    }
+
+    /// Computes the CID of the block received with the given CID prefix
+    /// (bitswap 1.1.0+ payload).
+    pub fn of_prefixed_block(prefix: &[u8], block: &[u8]) -> Result<Self, BitswapCidError> {
+        if prefix != BITSWAP_CID_PREFIX {
+            return Err(BitswapCidError::Prefix(hex::encode(prefix)));
+        }
+        Ok(Self::of_block(block))
+    }
+
+    /// Parses binary representation of CID (prefix followed by the hash).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BitswapCidError> {
+        let Some(hash) = bytes.strip_prefix(&BITSWAP_CID_PREFIX[..]) else {
+            let len = bytes.len().min(BITSWAP_CID_PREFIX.len());
+            return Err(BitswapCidError::Prefix(hex::encode(&bytes[..len])));
+        };
+        hash.try_into()
+            .map(Self)
+            .map_err(|_| BitswapCidError::Length(bytes.len()))
+    }
+
+    /// Binary representation of CID (prefix followed by the hash).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(BITSWAP_CID_PREFIX.len() + LINK_SIZE);
+        bytes.extend_from_slice(&BITSWAP_CID_PREFIX);
+        bytes.extend_from_slice(&self.0);
+        bytes
+    }
+}
+
+impl fmt::Display for BitswapCid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+impl FromStr for BitswapCid {
+    type Err = BitswapCidError;
+
+    /// Parses the hex encoded hash, as printed by [`fmt::Display`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(s).map_err(|_| BitswapCidError::Hex(s.to_owned()))?;
+        let len = bytes.len();
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| BitswapCidError::Length(len))
+    }
+}
+
+impl fmt::Debug for BitswapCid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BitswapCid")
+            .field(&self.to_string())
+            .finish()
+    }
+}
+
+impl Serialize for BitswapCid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        hex::encode(self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BitswapCid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let hex_str = String::deserialize(deserializer)?;
+        hex::decode(hex_str)
+            .map_err(Error::custom)
+            .and_then(|v| v.try_into().map_err(|_| Error::custom("wrong size")))
+            .map(Self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error, Serialize, Deserialize)]
+pub enum BitswapBlockError {
+    #[error("block is too short: {0} bytes")]
+    TooShort(usize),
+    #[error("block {0} is missing")]
+    Missing(BitswapCid),
+    #[error("resource is too big: exceeds {0} bytes")]
+    TooBig(usize),
+}
+
+/// Decoded bitswap block.
+///
+/// Mina splits large resources into a tree of blocks. Each block is encoded
+/// as a 2-byte little-endian number of links, followed by the links (hashes
+/// of the child blocks), followed by a chunk of the resource data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitswapBlock<'a> {
+    pub links: Vec<BitswapCid>,
+    pub data: &'a [u8],
+}
+
+impl<'a> BitswapBlock<'a> {
+    pub fn decode(block: &'a [u8]) -> Result<Self, BitswapBlockError> {
+        let too_short = || BitswapBlockError::TooShort(block.len());
+
+        let (count, rest) = block
+            .split_first_chunk::<LINKS_COUNT_SIZE>()
+            .ok_or_else(too_short)?;
+        let links_len = (u16::from_le_bytes(*count) as usize) * LINK_SIZE;
+        if rest.len() < links_len {
+            return Err(too_short());
+        }
+        let (links, data) = rest.split_at(links_len);
+        let links = links
+            .chunks_exact(LINK_SIZE)
+            .map(|link| BitswapCid(link.try_into().expect("exact chunk")))
+            .collect();
+
+        Ok(BitswapBlock { links, data })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut block =
+            Vec::with_capacity(LINKS_COUNT_SIZE + self.links.len() * LINK_SIZE + self.data.len());
+        block.extend_from_slice(&(self.links.len() as u16).to_le_bytes());
+        for link in &self.links {
+            block.extend_from_slice(&link.0);
+        }
+        block.extend_from_slice(self.data);
+        block
+    }
+}
+
+/// Splits the resource into blocks of at most `max_block_size` bytes.
+///
+/// Returns the CID of the root block and all the blocks of the resource.
+/// Data is kept in the leaves, and the leaves are all at the same depth,
+/// so the resource can be reassembled by [`bitswap_join`].
+pub fn bitswap_split(
+    data: &[u8],
+    max_block_size: usize,
+) -> (BitswapCid, BTreeMap<BitswapCid, Data>) {
+    let chunk_size = max_block_size.saturating_sub(LINKS_COUNT_SIZE).max(1);
+    let fanout =
+        (max_block_size.saturating_sub(LINKS_COUNT_SIZE) / LINK_SIZE).clamp(2, u16::MAX as usize);
+
+    let mut blocks = BTreeMap::new();
+    let mut add_block = |block: BitswapBlock| {
+        let block = block.encode();
+        let cid = BitswapCid::of_block(&block);
+        blocks.insert(cid, Data::from(block));
+        cid
+    };
+
+    let mut level = if data.is_empty() {
+        vec![add_block(BitswapBlock {
+            links: vec![],
+            data,
+        })]
+    } else {
+        data.chunks(chunk_size)
+            .map(|data| {
+                add_block(BitswapBlock {
+                    links: vec![],
+                    data,
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+
+    while level.len() > 1 {
+        level = level
+            .chunks(fanout)
+            .map(|links| {
+                add_block(BitswapBlock {
+                    links: links.to_vec(),
+                    data: &[],
+                })
+            })
+            .collect();
+    }
+
+    (level[0], blocks)
+}
+
+/// Reassembles the resource from its blocks.
+///
+/// Blocks are traversed breadth-first starting from the root, and the data
+/// chunks are concatenated in that order.
+pub fn bitswap_join(
+    root: &BitswapCid,
+    blocks: &BTreeMap<BitswapCid, Data>,
+    limit: usize,
+) -> Result<Vec<u8>, BitswapBlockError> {
+    let mut data = Vec::new();
+    let mut queue = VecDeque::from([*root]);
+
+    while let Some(cid) = queue.pop_front() {
+        let block = blocks.get(&cid).ok_or(BitswapBlockError::Missing(cid))?;
+        let block = BitswapBlock::decode(block)?;
+        if data.len() + block.data.len() > limit {
+            return Err(BitswapBlockError::TooBig(limit));
+        }
+        data.extend_from_slice(block.data);
+        queue.extend(block.links);
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cid_bytes() {
+        let cid = BitswapCid::of_block(b"block");
+        let bytes = cid.to_bytes();
+        assert_eq!(&bytes[..BITSWAP_CID_PREFIX.len()], &BITSWAP_CID_PREFIX);
+        assert_eq!(BitswapCid::from_bytes(&bytes), Ok(cid));
+        assert!(BitswapCid::from_bytes(&bytes[1..]).is_err());
+        assert!(BitswapCid::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        assert_eq!(cid.to_string().parse(), Ok(cid));
+        assert!("zz".parse::<BitswapCid>().is_err());
+        assert!(hex::encode(&bytes).parse::<BitswapCid>().is_err());
+    }
+
+    #[test]
+    fn block_encoding() {
+        let links = vec![BitswapCid::of_block(b"a"), BitswapCid::of_block(b"b")];
+        let block = BitswapBlock {
+            links,
+            data: b"data",
+        };
+        let encoded = block.encode();
+        assert_eq!(encoded.len(), 2 + 2 * 32 + 4);
+        assert_eq!(BitswapBlock::decode(&encoded), Ok(block));
+        assert!(BitswapBlock::decode(&encoded[..40]).is_err());
+        assert!(BitswapBlock::decode(&[1]).is_err());
+    }
+
+    #[test]
+    fn split_join() {
+        let data = (0..100_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        for max_block_size in [100, 1000, BITSWAP_MAX_BLOCK_SIZE] {
+            let (root, blocks) = bitswap_split(&data, max_block_size);
+            assert!(blocks.values().all(|b| b.len() <= max_block_size));
+            assert_eq!(bitswap_join(&root, &blocks, data.len()), Ok(data.clone()));
+            assert_eq!(
+                bitswap_join(&root, &blocks, data.len() - 1),
+                Err(BitswapBlockError::TooBig(data.len() - 1))
+            );
+        }
+
+        let (root, blocks) = bitswap_split(&[], 100);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(bitswap_join(&root, &blocks, 0), Ok(vec![]));
+    }
+}
@@ -0,0 +1,567 @@
+use std::collections::BTreeSet;
+
+use openmina_core::{bug_condition, fuzzed_maybe, warn, Substate};
+use redux::{Dispatcher, Timestamp};
+
+use crate::{
+    Data, P2pLimits, P2pNetworkConnectionError, P2pNetworkSchedulerAction,
+    P2pNetworkStreamProtobufError, P2pNetworkYamuxAction, P2pState, PeerId, StreamId, YamuxFlags,
+};
+
+use super::{
+    bitswap_join, bitswap_split, pb, BitswapBlock, BitswapCid, BitswapCidError,
+    P2pNetworkBitswapAction, P2pNetworkBitswapClientState, P2pNetworkBitswapResourceState,
+    P2pNetworkBitswapState, BITSWAP_MAX_BLOCK_SIZE,
+};
+
+/// Maximum number of blocks a peer can ask for, that we don't have yet.
+const MAX_WANTS_PER_PEER: usize = 1024;
+
+impl P2pNetworkBitswapState {
+    pub fn reducer<Action, State>(
+        mut state_context: Substate<Action, State, Self>,
+        action: redux::ActionWithMeta<P2pNetworkBitswapAction>,
+        limits: &P2pLimits,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let bitswap_state = state_context.get_substate_mut()?;
+        let (action, meta) = action.split();
+        let resource_limit = Option::<usize>::from(limits.bitswap_resource()).unwrap_or(usize::MAX);
+
+        match action {
+            P2pNetworkBitswapAction::NewStream {
+                incoming: true,
+                peer_id,
+                addr,
+                stream_id,
+                protocol,
+            } => {
+                let client = bitswap_state
+                    .clients
+                    .entry(peer_id)
+                    .or_insert_with(|| P2pNetworkBitswapClientState::new(protocol, addr));
+                client.addr = addr;
+                client.buffers.insert(stream_id, vec![]);
+                Ok(())
+            }
+            P2pNetworkBitswapAction::NewStream {
+                incoming: false,
+                peer_id,
+                addr,
+                stream_id,
+                protocol,
+            } => {
+                let missing = bitswap_state
+                    .missing_blocks()
+                    .copied()
+                    .collect::<BTreeSet<_>>();
+                let client = bitswap_state
+                    .clients
+                    .entry(peer_id)
+                    .or_insert_with(|| P2pNetworkBitswapClientState::new(protocol, addr));
+                client.protocol = protocol;
+                client.addr = addr;
+                client.outgoing_stream_id = Some(stream_id);
+                // let the peer know everything we are looking for
+                for cid in &missing {
+                    client.want(cid, false);
+                }
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pNetworkBitswapAction::OutgoingMessage { peer_id });
+                Ok(())
+            }
+            P2pNetworkBitswapAction::IncomingData {
+                peer_id,
+                addr,
+                stream_id,
+                data,
+            } => {
+                let pending = bitswap_state.pending_resources();
+                let result = bitswap_state
+                    .reduce_incoming_data(&peer_id, stream_id, &data, limits)
+                    .and_then(|messages| {
+                        messages.into_iter().try_for_each(|message| {
+                            bitswap_state
+                                .reduce_incoming_message(
+                                    &peer_id,
+                                    message,
+                                    meta.time(),
+                                    resource_limit,
+                                )
+                                .map_err(P2pNetworkStreamProtobufError::Convert)
+                        })
+                    });
+                bitswap_state.prune_resources(limits);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                if let Err(error) = result {
+                    warn!(meta.time(); summary = "error handling bitswap data", error = display(&error));
+                    dispatcher.push(P2pNetworkSchedulerAction::Error {
+                        addr,
+                        error: P2pNetworkConnectionError::BitswapStreamError(error.into()),
+                    });
+                    return Ok(());
+                }
+
+                Self::notify_completed(dispatcher, state, pending)?;
+                Self::broadcast(dispatcher, state)
+            }
+            P2pNetworkBitswapAction::RemoteClose {
+                peer_id,
+                addr,
+                stream_id,
+            } => {
+                if let Some(client) = bitswap_state.clients.get_mut(&peer_id) {
+                    client.buffers.remove(&stream_id);
+                    if client.outgoing_stream_id == Some(stream_id) {
+                        client.outgoing_stream_id = None;
+                    }
+                }
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pNetworkYamuxAction::OutgoingData {
+                    addr,
+                    stream_id,
+                    data: Data::empty(),
+                    flags: YamuxFlags::FIN,
+                });
+                Ok(())
+            }
+            P2pNetworkBitswapAction::AddResource { data } => {
+                let (root, blocks) = bitswap_split(&data, BITSWAP_MAX_BLOCK_SIZE);
+                for (cid, block) in blocks {
+                    bitswap_state.insert_block(cid, block, meta.time(), resource_limit);
+                }
+                bitswap_state.resources.insert(
+                    root,
+                    P2pNetworkBitswapResourceState::Ready {
+                        time: meta.time(),
+                        size: data.len(),
+                    },
+                );
+                bitswap_state.prune_resources(limits);
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                Self::broadcast(dispatcher, state)
+            }
+            P2pNetworkBitswapAction::RemoveResource { root } => {
+                if bitswap_state.remove_resource(&root).is_none() {
+                    bug_condition!(
+                        "Resource {root} not found for `P2pNetworkBitswapAction::RemoveResource`"
+                    );
+                    return Ok(());
+                }
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                Self::broadcast(dispatcher, state)
+            }
+            P2pNetworkBitswapAction::Want { root } => {
+                let (_, missing) = bitswap_state.traverse(&root);
+                if missing.is_empty() {
+                    let resource = bitswap_state.assemble(&root, meta.time(), resource_limit);
+                    bitswap_state.resources.insert(root, resource);
+                    bitswap_state.prune_resources(limits);
+
+                    let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                    return Self::notify_completed(dispatcher, state, BTreeSet::from([root]));
+                }
+
+                bitswap_state.request_blocks(&missing);
+                bitswap_state.resources.insert(
+                    root,
+                    P2pNetworkBitswapResourceState::Pending {
+                        time: meta.time(),
+                        missing,
+                    },
+                );
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                Self::broadcast(dispatcher, state)
+            }
+            P2pNetworkBitswapAction::OutgoingMessage { peer_id } => {
+                let Some(client) = bitswap_state.clients.get_mut(&peer_id) else {
+                    bug_condition!(
+                        "Invalid state for action: `P2pNetworkBitswapAction::OutgoingMessage`"
+                    );
+                    return Ok(());
+                };
+                let message = std::mem::take(&mut client.message);
+                let max_size =
+                    Option::<usize>::from(limits.bitswap_message()).unwrap_or(usize::MAX);
+
+                let dispatcher = state_context.into_dispatcher();
+                for message in split_message(message, max_size) {
+                    let data = prost::Message::encode_length_delimited_to_vec(&message);
+                    dispatcher.push(P2pNetworkBitswapAction::OutgoingData {
+                        peer_id,
+                        data: Data::from(data),
+                    });
+                }
+                Ok(())
+            }
+            P2pNetworkBitswapAction::OutgoingData { peer_id, data } => {
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let state: &Self = state.substate()?;
+
+                let Some(client) = state.clients.get(&peer_id) else {
+                    bug_condition!(
+                        "Missing state for action: `P2pNetworkBitswapAction::OutgoingData`"
+                    );
+                    return Ok(());
+                };
+                let flags = fuzzed_maybe!(Default::default(), crate::fuzzer::mutate_yamux_flags);
+
+                if let Some(stream_id) = client.outgoing_stream_id {
+                    dispatcher.push(P2pNetworkYamuxAction::OutgoingData {
+                        addr: client.addr,
+                        stream_id,
+                        data,
+                        flags,
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes the resource with its blocks, keeping the ones shared with
+    /// other resources, and cancels the wants for its missing blocks.
+    fn remove_resource(&mut self, root: &BitswapCid) -> Option<P2pNetworkBitswapResourceState> {
+        let resource = self.resources.remove(root)?;
+
+        let mut shared_blocks = BTreeSet::new();
+        let mut shared_missing = BTreeSet::new();
+        for other_root in self.resources.keys() {
+            let (blocks, missing) = self.traverse(other_root);
+            shared_blocks.extend(blocks);
+            shared_missing.extend(missing);
+        }
+
+        let (blocks, _) = self.traverse(root);
+        for cid in blocks.difference(&shared_blocks) {
+            self.blocks.remove(cid);
+        }
+
+        if let P2pNetworkBitswapResourceState::Pending { missing, .. } = &resource {
+            for cid in missing.difference(&shared_missing) {
+                self.clients
+                    .values_mut()
+                    .filter(|client| client.outgoing_stream_id.is_some())
+                    .for_each(|client| client.want(cid, true));
+            }
+        }
+
+        Some(resource)
+    }
+
+    /// Removes the oldest complete resources over the limit. Pending ones
+    /// are kept, they are removed by the one who wants them.
+    fn prune_resources(&mut self, limits: &P2pLimits) {
+        let limit = Option::<usize>::from(limits.bitswap_resources()).unwrap_or(usize::MAX);
+        let mut complete = self
+            .resources
+            .iter()
+            .filter_map(|(root, resource)| match resource {
+                P2pNetworkBitswapResourceState::Pending { .. } => None,
+                P2pNetworkBitswapResourceState::Ready { time, .. }
+                | P2pNetworkBitswapResourceState::Error { time, .. } => Some((*time, *root)),
+            })
+            .collect::<Vec<_>>();
+        if complete.len() <= limit {
+            return;
+        }
+        complete.sort();
+        let excess = complete.len() - limit;
+        for (_, root) in complete.into_iter().take(excess) {
+            self.remove_resource(&root);
+        }
+    }
+
+    /// Calls the callbacks for the resources out of `pending` that are
+    /// complete now.
+    fn notify_completed<Action, State>(
+        dispatcher: &mut Dispatcher<Action, State>,
+        state: &State,
+        pending: BTreeSet<BitswapCid>,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let p2p_state: &P2pState = state.substate()?;
+        let callbacks = &p2p_state.callbacks;
+        let resources = &p2p_state.network.scheduler.bitswap_state.resources;
+
+        for root in pending {
+            match resources.get(&root) {
+                Some(P2pNetworkBitswapResourceState::Ready { .. }) => {
+                    if let Some(callback) = &callbacks.on_p2p_network_bitswap_resource_ready {
+                        dispatcher.push_callback(callback.clone(), root);
+                    }
+                }
+                Some(P2pNetworkBitswapResourceState::Error { error, .. }) => {
+                    if let Some(callback) = &callbacks.on_p2p_network_bitswap_resource_error {
+                        dispatcher.push_callback(callback.clone(), (root, error.clone()));
+                    }
+                }
+                Some(P2pNetworkBitswapResourceState::Pending { .. }) | None => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends the data to the stream buffer and decodes all complete
+    /// length-prefixed messages from it.
+    fn reduce_incoming_data(
+        &mut self,
+        peer_id: &PeerId,
+        stream_id: StreamId,
+        data: &[u8],
+        limits: &P2pLimits,
+    ) -> Result<Vec<pb::Message>, P2pNetworkStreamProtobufError<BitswapCidError>> {
+        let Some(client) = self.clients.get_mut(peer_id) else {
+            bug_condition!("State not found for action: `P2pNetworkBitswapAction::IncomingData`");
+            return Ok(vec![]);
+        };
+        let buffer = client.buffers.entry(stream_id).or_default();
+        buffer.extend_from_slice(data);
+
+        let mut messages = vec![];
+        let mut offset = 0;
+        while offset < buffer.len() {
+            let rest = &buffer[offset..];
+            let (len, tail) = match unsigned_varint::decode::usize(rest) {
+                Ok(v) => v,
+                Err(unsigned_varint::decode::Error::Insufficient) => break,
+                Err(_) => return Err(P2pNetworkStreamProtobufError::MessageLength),
+            };
+            if len > limits.bitswap_message() {
+                return Err(P2pNetworkStreamProtobufError::Limit(
+                    len,
+                    limits.bitswap_message(),
+                ));
+            }
+            if tail.len() < len {
+                break;
+            }
+            let message = <pb::Message as prost::Message>::decode(&tail[..len])
+                .map_err(|err| P2pNetworkStreamProtobufError::Message(err.to_string()))?;
+            messages.push(message);
+            offset += rest.len() - tail.len() + len;
+        }
+        buffer.drain(..offset);
+
+        Ok(messages)
+    }
+
+    fn reduce_incoming_message(
+        &mut self,
+        peer_id: &PeerId,
+        message: pb::Message,
+        time: Timestamp,
+        resource_limit: usize,
+    ) -> Result<(), BitswapCidError> {
+        use pb::message::wantlist::WantType;
+
+        let Some(client) = self.clients.get_mut(peer_id) else {
+            bug_condition!("State not found for action: `P2pNetworkBitswapAction::IncomingData`");
+            return Ok(());
+        };
+
+        if let Some(wantlist) = message.wantlist {
+            if wantlist.full {
+                client.wants.clear();
+            }
+            for entry in wantlist.entries {
+                let cid = BitswapCid::from_bytes(&entry.block)?;
+                if entry.cancel {
+                    client.wants.remove(&cid);
+                    continue;
+                }
+                match self.blocks.get(&cid) {
+                    Some(_) if entry.want_type() == WantType::Have => {
+                        client.send_presence(&cid, true);
+                    }
+                    Some(block) => client.send_block(block),
+                    None => {
+                        if entry.send_dont_have {
+                            client.send_presence(&cid, false);
+                        }
+                        if client.wants.len() < MAX_WANTS_PER_PEER {
+                            client.wants.insert(cid);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut received = message
+            .blocks
+            .into_iter()
+            .map(|data| (BitswapCid::of_block(&data), data))
+            .collect::<Vec<_>>();
+        for block in message.payload {
+            let cid = BitswapCid::of_prefixed_block(&block.prefix, &block.data)?;
+            received.push((cid, block.data));
+        }
+
+        let mut missing = BTreeSet::new();
+        for (cid, data) in received {
+            // ignore blocks we didn't ask for
+            if self.missing_blocks().any(|c| c == &cid) {
+                missing.extend(self.insert_block(cid, data.into(), time, resource_limit));
+            }
+        }
+        self.request_blocks(&missing);
+
+        Ok(())
+    }
+
+    /// Stores the block, sends it to peers that want it, and updates pending
+    /// resources. Returns blocks that became missing, i.e. the links of
+    /// the new block that aren't available locally.
+    fn insert_block(
+        &mut self,
+        cid: BitswapCid,
+        data: Data,
+        time: Timestamp,
+        resource_limit: usize,
+    ) -> BTreeSet<BitswapCid> {
+        for client in self.clients.values_mut() {
+            if client.wants.remove(&cid) {
+                client.send_block(&data);
+            }
+        }
+        self.blocks.insert(cid, data);
+
+        let (_, missing) = self.traverse(&cid);
+        let mut completed = vec![];
+        for (root, resource) in self.resources.iter_mut() {
+            let P2pNetworkBitswapResourceState::Pending {
+                missing: resource_missing,
+                ..
+            } = resource
+            else {
+                continue;
+            };
+            if resource_missing.remove(&cid) {
+                resource_missing.extend(missing.iter().copied());
+                if resource_missing.is_empty() {
+                    completed.push(*root);
+                }
+            }
+        }
+        for root in completed {
+            let resource = self.assemble(&root, time, resource_limit);
+            self.resources.insert(root, resource);
+        }
+
+        missing
+    }
+
+    /// Adds the blocks to the wantlist of each peer we can send it to.
+    fn request_blocks(&mut self, cids: &BTreeSet<BitswapCid>) {
+        self.clients
+            .values_mut()
+            .filter(|client| client.outgoing_stream_id.is_some())
+            .for_each(|client| cids.iter().for_each(|cid| client.want(cid, false)));
+    }
+
+    /// Walks the tree of blocks starting from the `root`.
+    ///
+    /// Returns blocks of the tree that are available locally, and the ones that are missing.
+    fn traverse(&self, root: &BitswapCid) -> (BTreeSet<BitswapCid>, BTreeSet<BitswapCid>) {
+        let mut present = BTreeSet::new();
+        let mut missing = BTreeSet::new();
+        let mut stack = vec![*root];
+
+        while let Some(cid) = stack.pop() {
+            if present.contains(&cid) || missing.contains(&cid) {
+                continue;
+            }
+            let Some(block) = self.blocks.get(&cid) else {
+                missing.insert(cid);
+                continue;
+            };
+            present.insert(cid);
+            if let Ok(block) = BitswapBlock::decode(block) {
+                stack.extend(block.links);
+            }
+        }
+
+        (present, missing)
+    }
+
+    fn assemble(
+        &self,
+        root: &BitswapCid,
+        time: Timestamp,
+        resource_limit: usize,
+    ) -> P2pNetworkBitswapResourceState {
+        match bitswap_join(root, &self.blocks, resource_limit) {
+            Ok(data) => P2pNetworkBitswapResourceState::Ready {
+                time,
+                size: data.len(),
+            },
+            Err(error) => P2pNetworkBitswapResourceState::Error { time, error },
+        }
+    }
+
+    fn broadcast<Action, State>(
+        dispatcher: &mut Dispatcher<Action, State>,
+        state: &State,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let state: &P2pNetworkBitswapState = state.substate()?;
+
+        for peer_id in state
+            .clients
+            .iter()
+            .filter(|(_, s)| s.outgoing_stream_id.is_some() && !s.message_is_empty())
+            .map(|(peer_id, _)| *peer_id)
+        {
+            dispatcher.push(P2pNetworkBitswapAction::OutgoingMessage { peer_id });
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits the message so each part carries at most `max_size` bytes of blocks.
+fn split_message(message: pb::Message, max_size: usize) -> Vec<pb::Message> {
+    let mut messages = vec![];
+    let mut current = pb::Message {
+        wantlist: message.wantlist,
+        block_presences: message.block_presences,
+        ..Default::default()
+    };
+    let mut current_size = 0;
+
+    let mut push = |current: &mut pb::Message, current_size: &mut usize, size: usize| {
+        if *current_size > 0 && *current_size + size > max_size {
+            messages.push(std::mem::take(current));
+            *current_size = 0;
+        }
+        *current_size += size;
+    };
+
+    for block in message.blocks {
+        push(&mut current, &mut current_size, block.len());
+        current.blocks.push(block);
+    }
+    for block in message.payload {
+        push(&mut current, &mut current_size, block.data.len());
+        current.payload.push(block);
+    }
+    messages.push(current);
+
+    messages
+}
@@ -0,0 +1,179 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    token::BitswapAlgorithm, ConnectionAddr, Data, P2pNetworkStreamProtobufError, PeerId, StreamId,
+};
+
+use super::{pb, BitswapBlockError, BitswapCid, BitswapCidError, BITSWAP_CID_PREFIX};
+
+/// State of the bitswap exchange (`/mina/bitswap-exchange`).
+///
+/// Keeps the blocks we are able to serve to other peers, the resources
+/// we are fetching from them and per-peer stream state.
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkBitswapState {
+    /// State of each peer that has a bitswap stream with us.
+    pub clients: BTreeMap<PeerId, P2pNetworkBitswapClientState>,
+
+    /// Blocks that are available locally, either added by us or fetched from peers.
+    pub blocks: BTreeMap<BitswapCid, Data>,
+
+    /// Resources (trees of blocks) indexed by the CID of the root block.
+    pub resources: BTreeMap<BitswapCid, P2pNetworkBitswapResourceState>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkBitswapClientState {
+    pub protocol: BitswapAlgorithm,
+    pub addr: ConnectionAddr,
+    pub outgoing_stream_id: Option<StreamId>,
+    /// Partially received messages for each incoming stream.
+    pub buffers: BTreeMap<StreamId, Vec<u8>>,
+    /// Blocks the peer asked for, that we don't have yet.
+    pub wants: BTreeSet<BitswapCid>,
+    /// Message that will be sent to the peer with the next
+    /// [`super::P2pNetworkBitswapAction::OutgoingMessage`].
+    pub message: pb::Message,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum P2pNetworkBitswapResourceState {
+    /// Resource is being fetched from peers.
+    Pending {
+        time: Timestamp,
+        /// Blocks that are requested from peers but not yet received.
+        missing: BTreeSet<BitswapCid>,
+    },
+    /// All blocks of the resource are available locally.
+    Ready { time: Timestamp, size: usize },
+    /// All blocks are received, but they could not be assembled into the resource.
+    Error {
+        time: Timestamp,
+        error: BitswapBlockError,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error, Serialize, Deserialize)]
+#[error("bitswap stream: {0}")]
+pub struct P2pNetworkBitswapStreamError(#[from] P2pNetworkStreamProtobufError<BitswapCidError>);
+
+impl P2pNetworkBitswapState {
+    pub fn prune_peer_state(&mut self, peer_id: &PeerId) {
+        self.clients.remove(peer_id);
+    }
+
+    /// Returns the resource with the given root, if all of its blocks are available.
+    pub fn resource_data(&self, root: &BitswapCid, limit: usize) -> Option<Vec<u8>> {
+        match self.resources.get(root)? {
+            P2pNetworkBitswapResourceState::Ready { .. } => {
+                super::bitswap_join(root, &self.blocks, limit).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Roots of the resources that are being fetched.
+    pub fn pending_resources(&self) -> BTreeSet<BitswapCid> {
+        self.resources
+            .iter()
+            .filter(|(_, resource)| resource.is_pending())
+            .map(|(root, _)| *root)
+            .collect()
+    }
+
+    /// Blocks that are missing for all pending resources.
+    pub fn missing_blocks(&self) -> impl Iterator<Item = &BitswapCid> {
+        self.resources
+            .values()
+            .filter_map(|resource| match resource {
+                P2pNetworkBitswapResourceState::Pending { missing, .. } => Some(missing),
+                _ => None,
+            })
+            .flatten()
+    }
+}
+
+impl P2pNetworkBitswapResourceState {
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Self::Pending { .. })
+    }
+
+    pub fn is_ready(&self) -> bool {
+        matches!(self, Self::Ready { .. })
+    }
+}
+
+impl P2pNetworkBitswapClientState {
+    pub fn new(protocol: BitswapAlgorithm, addr: ConnectionAddr) -> Self {
+        P2pNetworkBitswapClientState {
+            protocol,
+            addr,
+            outgoing_stream_id: None,
+            buffers: Default::default(),
+            wants: Default::default(),
+            message: Default::default(),
+        }
+    }
+
+    pub fn message_is_empty(&self) -> bool {
+        self.message.wantlist.is_none()
+            && self.message.blocks.is_empty()
+            && self.message.payload.is_empty()
+            && self.message.block_presences.is_empty()
+    }
+
+    /// Bitswap 1.0.0 sends raw blocks, newer versions send blocks with CID prefix
+    /// and support block presences.
+    fn is_legacy(&self) -> bool {
+        matches!(
+            self.protocol,
+            BitswapAlgorithm::MinaBitswap | BitswapAlgorithm::MinaBitswap1_0_0
+        )
+    }
+
+    pub fn want(&mut self, cid: &BitswapCid, cancel: bool) {
+        use pb::message::wantlist::{Entry, WantType};
+
+        let wantlist = self.message.wantlist.get_or_insert_with(Default::default);
+        wantlist.entries.push(Entry {
+            block: cid.to_bytes(),
+            priority: 1,
+            cancel,
+            want_type: WantType::Block.into(),
+            send_dont_have: !cancel && !self.is_legacy(),
+        });
+    }
+
+    pub fn send_block(&mut self, data: &Data) {
+        if self.is_legacy() {
+            self.message.blocks.push(data.to_vec());
+        } else {
+            self.message.payload.push(pb::message::Block {
+                prefix: BITSWAP_CID_PREFIX.to_vec(),
+                data: data.to_vec(),
+            });
+        }
+    }
+
+    pub fn send_presence(&mut self, cid: &BitswapCid, have: bool) {
+        use pb::message::BlockPresenceType;
+
+        if self.is_legacy() {
+            return;
+        }
+        let r#type = if have {
+            BlockPresenceType::Have
+        } else {
+            BlockPresenceType::DontHave
+        };
+        self.message
+            .block_presences
+            .push(pb::message::BlockPresence {
+                cid: cid.to_bytes(),
+                r#type: r#type.into(),
+            });
+    }
+}
@@ -2,6 +2,7 @@ use super::{
     P2pNetworkIdentifyStreamAction, P2pNetworkIdentifyStreamKind, P2pNetworkIdentifyStreamState,
 };
 use crate::{
+    connection::outgoing::Libp2pTransport,
     identify::P2pIdentifyAction,
     network::identify::{
         pb::{self, Identify},
@@ -382,6 +383,12 @@ impl P2pNetworkIdentifyStreamState {
             ips.iter()
                 .map(|ip| Multiaddr::from(*ip).with(multiaddr::Protocol::Tcp(port))),
         );
+        if let Some(quic_port) = config.libp2p_quic_port {
+            listen_addrs.extend(
+                ips.iter()
+                    .map(|ip| Libp2pTransport::Quic.with_port(Multiaddr::from(*ip), quic_port)),
+            );
+        }
 
         let public_key = Some(state.config.identity_pub_key.clone());
 
@@ -389,6 +396,12 @@ impl P2pNetworkIdentifyStreamState {
             token::StreamKind::Identify(token::IdentifyAlgorithm::Identify1_0_0),
             token::StreamKind::Broadcast(token::BroadcastAlgorithm::Meshsub1_1_0),
             token::StreamKind::Rpc(token::RpcAlgorithm::Rpc0_0_1),
+            token::StreamKind::Bitswap(token::BitswapAlgorithm::MinaBitswap1_2_0),
+            token::StreamKind::Bitswap(token::BitswapAlgorithm::MinaBitswap1_1_0),
+            token::StreamKind::Bitswap(token::BitswapAlgorithm::MinaBitswap1_0_0),
+            token::StreamKind::Bitswap(token::BitswapAlgorithm::MinaBitswap),
+            token::StreamKind::Status(token::StatusAlgorithm::MinaNodeStatus),
+            token::StreamKind::Ping(token::PingAlgorithm::Ping1_0_0),
         ];
         if state.network.scheduler.discovery_state.is_some() {
             protocols.push(token::StreamKind::Discovery(
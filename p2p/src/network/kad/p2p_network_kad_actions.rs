@@ -111,7 +111,9 @@ impl EnablingCondition<P2pState> for P2pNetworkKademliaAction {
                     super::P2pNetworkKadStatus::Bootstrapping(_)
                 )
             }
-            P2pNetworkKademliaAction::UpdateRoutingTable { .. } => true,
+            P2pNetworkKademliaAction::UpdateRoutingTable { peer_id, .. } => {
+                !state.ban.is_banned(peer_id)
+            }
         }
     }
 }
@@ -318,6 +318,17 @@ impl<const K: usize> P2pNetworkKadRoutingTable<K> {
         self.buckets[index].iter().find(|e| &e.key == key)
     }
 
+    /// Removes the entry with the specified `key`, returning it if it was present.
+    pub fn remove(&mut self, key: &P2pNetworkKadKey) -> Option<P2pNetworkKadEntry> {
+        // distance to this node
+        let dist = self.this_key - key;
+
+        // index of the closest k-bucket that can contain this node.
+        let index = dist.to_index().min(self.buckets.len() - 1);
+
+        self.buckets[index].remove(key)
+    }
+
     /// FIND_NODE backend. Returns iterator of nodes closest to the specified
     /// `key`, excluding nodes that correspond to the `key` itself and
     /// `self.this_key`.
@@ -597,6 +608,12 @@ impl<const K: usize> P2pNetworkKadBucket<K> {
         }
     }
 
+    /// Removes the entry with the specified `key` from the bucket.
+    fn remove(&mut self, key: &P2pNetworkKadKey) -> Option<P2pNetworkKadEntry> {
+        let pos = self.0.iter().position(|e| &e.key == key)?;
+        Some(self.0.remove(pos))
+    }
+
     /// Splits this bucket into two, keeping entries that are not closer to the
     /// current node than the `dist`.
     fn split<F: Fn(&P2pNetworkKadEntry) -> bool>(self, f: F) -> (Self, Self) {
@@ -765,6 +782,27 @@ mod tests {
         println!("routing table: {rt:+#?}");
     }
 
+    #[test]
+    fn test_remove() {
+        let mut rt: P2pNetworkKadRoutingTable =
+            P2pNetworkKadRoutingTable::new(entry_with_peer_id(peer_id_rand()));
+        let entries = (0..256)
+            .map(|_| entry_with_peer_id(peer_id_rand()))
+            .collect::<Vec<_>>();
+        for entry in &entries {
+            let _ = rt.insert(entry.clone());
+        }
+
+        for entry in &entries {
+            if rt.look_up(&entry.key).is_some() {
+                assert_eq!(rt.remove(&entry.key).as_ref(), Some(entry));
+                assert!(rt.look_up(&entry.key).is_none());
+                rt.assert_k_buckets();
+            }
+            assert!(rt.remove(&entry.key).is_none());
+        }
+    }
+
     #[test]
     fn test_find_node_zero() {
         let this_entry = entry_with_peer_id(peer_id_rand());
@@ -92,6 +92,11 @@ impl super::P2pNetworkKadState {
             ) => {
                 let mut latest_request_peers = Vec::new();
                 for entry in &closest_peers {
+                    if state.banned_peers.contains(&entry.peer_id) {
+                        latest_request_peers
+                            .push((entry.peer_id, P2pNetworkKadLatestRequestPeerKind::Discarded));
+                        continue;
+                    }
                     let kind = match state.routing_table.insert(entry.clone()) {
                         Ok(true) => P2pNetworkKadLatestRequestPeerKind::New,
                         Ok(false) => P2pNetworkKadLatestRequestPeerKind::Existing,
@@ -135,6 +140,9 @@ impl super::P2pNetworkKadState {
                 Ok(())
             }
             (_, P2pNetworkKademliaAction::UpdateRoutingTable { peer_id, addrs }) => {
+                if state.banned_peers.contains(&peer_id) {
+                    return Ok(());
+                }
                 let _ = state.routing_table.insert(
                     P2pNetworkKadEntry::new(peer_id, addrs.clone()).map_err(|e| e.to_string())?,
                 );
@@ -1,4 +1,7 @@
-use std::{collections::BTreeMap, net::SocketAddr};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::SocketAddr,
+};
 
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
@@ -57,6 +60,8 @@ pub struct P2pNetworkKadState {
     pub streams: crate::network::scheduler::StreamState<P2pNetworkKadStreamState>,
     pub status: P2pNetworkKadStatus,
     pub filter_addrs: bool,
+    /// Banned peers, never added to the routing table.
+    pub banned_peers: BTreeSet<PeerId>,
 }
 
 impl Default for P2pNetworkKadState {
@@ -71,6 +76,7 @@ impl Default for P2pNetworkKadState {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(true),
+            banned_peers: Default::default(),
         }
     }
 }
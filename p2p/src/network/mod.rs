@@ -49,6 +49,15 @@ pub use self::kad::*;
 pub mod pubsub;
 pub use self::pubsub::*;
 
+pub mod bitswap;
+pub use self::bitswap::*;
+
+pub mod node_status;
+pub use self::node_status::*;
+
+pub mod ping;
+pub use self::ping::*;
+
 pub mod rpc;
 pub use self::rpc::*;
 
@@ -0,0 +1,13 @@
+mod p2p_network_node_status_message;
+pub use self::p2p_network_node_status_message::*;
+
+mod p2p_network_node_status_actions;
+pub use self::p2p_network_node_status_actions::P2pNetworkNodeStatusAction;
+
+mod p2p_network_node_status_state;
+pub use self::p2p_network_node_status_state::{
+    P2pNetworkNodeStatusQueryState, P2pNetworkNodeStatusState, P2pNetworkNodeStatusStreamState,
+};
+
+#[cfg(feature = "p2p-libp2p")]
+mod p2p_network_node_status_reducer;
@@ -0,0 +1,90 @@
+use openmina_core::{requests::RpcId, ActionEvent};
+use serde::{Deserialize, Serialize};
+
+use crate::{ConnectionAddr, Data, P2pState, PeerId, StreamId};
+
+use super::P2pNetworkNodeStatus;
+
+/// Actions of the node status protocol (`/mina/node-status`).
+///
+/// The peer that opens the stream is asking for the status, the other
+/// side writes its status as JSON and closes the stream.
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+pub enum P2pNetworkNodeStatusAction {
+    /// Create a new stream, either incoming or outgoing.
+    NewStream {
+        incoming: bool,
+        peer_id: PeerId,
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+    },
+
+    /// Process incoming raw data from a peer.
+    IncomingData {
+        peer_id: PeerId,
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+        data: Data,
+    },
+
+    /// Remote peer closed the stream.
+    RemoteClose {
+        peer_id: PeerId,
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+    },
+
+    /// Send our node status to the peer that requested it, or just close
+    /// the stream if the status is not available.
+    #[action_event(fields(display(peer_id), display(stream_id)))]
+    Respond {
+        peer_id: PeerId,
+        stream_id: StreamId,
+        status: Option<Box<P2pNetworkNodeStatus>>,
+    },
+
+    /// Ask the connected peer for its node status.
+    #[action_event(fields(display(peer_id)))]
+    Query {
+        peer_id: PeerId,
+        rpc_id: Option<RpcId>,
+    },
+
+    /// Peer responded with its node status.
+    #[action_event(fields(display(peer_id)))]
+    QuerySuccess {
+        peer_id: PeerId,
+        status: Box<P2pNetworkNodeStatus>,
+    },
+
+    /// Query failed or timed out.
+    #[action_event(fields(display(peer_id), display(error)))]
+    QueryError { peer_id: PeerId, error: String },
+}
+
+impl From<P2pNetworkNodeStatusAction> for crate::P2pAction {
+    fn from(value: P2pNetworkNodeStatusAction) -> Self {
+        crate::P2pAction::Network(value.into())
+    }
+}
+
+impl redux::EnablingCondition<P2pState> for P2pNetworkNodeStatusAction {
+    fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
+        let node_status_state = &state.network.scheduler.node_status_state;
+        match self {
+            P2pNetworkNodeStatusAction::Respond {
+                peer_id, stream_id, ..
+            } => node_status_state
+                .find_request(peer_id, *stream_id)
+                .is_some(),
+            P2pNetworkNodeStatusAction::Query { peer_id, .. } => {
+                !node_status_state.outgoing.contains_key(peer_id)
+            }
+            P2pNetworkNodeStatusAction::QuerySuccess { peer_id, .. }
+            | P2pNetworkNodeStatusAction::QueryError { peer_id, .. } => {
+                node_status_state.outgoing.contains_key(peer_id)
+            }
+            _ => true,
+        }
+    }
+}
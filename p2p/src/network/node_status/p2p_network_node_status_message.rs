@@ -0,0 +1,148 @@
+use mina_p2p_messages::v2::{NonZeroCurvePoint, StateHash};
+use serde::{Deserialize, Serialize};
+
+/// Maximum size of the node status we accept from a peer.
+pub const NODE_STATUS_MAX_SIZE: usize = 1024 * 1024;
+
+/// Node status as served over the `/mina/node-status` protocol.
+///
+/// The JSON representation matches the one produced by the OCaml node,
+/// so it can be consumed by the same tools (e.g. network health crawler).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct P2pNetworkNodeStatus {
+    pub node_ip_addr: String,
+    /// Peer id of the node, in libp2p representation.
+    pub node_peer_id: String,
+    pub sync_status: P2pNetworkNodeSyncStatus,
+    pub peers: Vec<P2pNetworkNodeStatusPeer>,
+    pub block_producers: Vec<NonZeroCurvePoint>,
+    /// Hash of the best tip.
+    pub protocol_state_hash: StateHash,
+    pub ban_statuses: Vec<(P2pNetworkNodeStatusPeer, P2pNetworkNodeStatusTrust)>,
+    /// Hashes of the blocks in the transition frontier with their timestamps
+    /// (milliseconds since unix epoch).
+    pub k_block_hashes_and_timestamps: Vec<(StateHash, String)>,
+    pub git_commit: String,
+    pub uptime_minutes: u64,
+    #[serde(default)]
+    pub block_height_opt: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct P2pNetworkNodeStatusPeer {
+    pub host: String,
+    pub libp2p_port: u16,
+    /// Peer id in libp2p representation.
+    pub peer_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct P2pNetworkNodeStatusTrust {
+    pub trust: f64,
+    pub banned: P2pNetworkNodeStatusBanned,
+}
+
+/// Ban status, encoded as `["Unbanned"]` or `["Banned_until", time]`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "Vec<String>", into = "Vec<String>")]
+pub enum P2pNetworkNodeStatusBanned {
+    Unbanned,
+    BannedUntil(String),
+}
+
+impl TryFrom<Vec<String>> for P2pNetworkNodeStatusBanned {
+    type Error = String;
+
+    fn try_from(value: Vec<String>) -> Result<Self, Self::Error> {
+        match value.as_slice() {
+            [tag] if tag == "Unbanned" => Ok(Self::Unbanned),
+            [tag, time] if tag == "Banned_until" => Ok(Self::BannedUntil(time.clone())),
+            _ => Err(format!("invalid ban status: {value:?}")),
+        }
+    }
+}
+
+impl From<P2pNetworkNodeStatusBanned> for Vec<String> {
+    fn from(value: P2pNetworkNodeStatusBanned) -> Self {
+        match value {
+            P2pNetworkNodeStatusBanned::Unbanned => vec!["Unbanned".to_owned()],
+            P2pNetworkNodeStatusBanned::BannedUntil(time) => {
+                vec!["Banned_until".to_owned(), time]
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum P2pNetworkNodeSyncStatus {
+    /// Node is looking for peers.
+    Connecting,
+    /// Node has peers, but didn't start synchronization yet.
+    Listening,
+    Offline,
+    Bootstrap,
+    Synced,
+    Catchup,
+}
+
+impl P2pNetworkNodeStatus {
+    pub fn from_json(data: &[u8]) -> Result<Self, String> {
+        serde_json::from_slice(data).map_err(|err| err.to_string())
+    }
+
+    pub fn to_json(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("node status is always serializable")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_ocaml_node_status() {
+        let json = r#"{
+            "node_ip_addr": "34.122.14.4",
+            "node_peer_id": "12D3KooWKK3RpV1MWAZk3FJ5xqbVPL2BMDdUEGSfwfQoUprBNZCv",
+            "sync_status": "Synced",
+            "peers": [
+                {
+                    "host": "65.109.110.75",
+                    "libp2p_port": 8302,
+                    "peer_id": "12D3KooWSxxCtzRLfUzoxgRYW9fTKWPUujdvStuwCPSPUN3629mb"
+                }
+            ],
+            "block_producers": ["B62qrPN5Y5yq8kGE3FbVKbGTdTAJNdtNtB5sNVpxyRwWGcDEhpMzc8g"],
+            "protocol_state_hash": "3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ",
+            "ban_statuses": [
+                [
+                    {
+                        "host": "65.109.110.75",
+                        "libp2p_port": 8302,
+                        "peer_id": "12D3KooWSxxCtzRLfUzoxgRYW9fTKWPUujdvStuwCPSPUN3629mb"
+                    },
+                    { "trust": 0.0, "banned": ["Unbanned"] }
+                ]
+            ],
+            "k_block_hashes_and_timestamps": [
+                ["3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ", "1710000000000"]
+            ],
+            "git_commit": "0b63498e271575dbffe2b31f3ab8be293490b1ac",
+            "uptime_minutes": 4221,
+            "block_height_opt": 359604
+        }"#;
+
+        let status = P2pNetworkNodeStatus::from_json(json.as_bytes()).expect("valid status");
+        assert_eq!(status.sync_status, P2pNetworkNodeSyncStatus::Synced);
+        assert_eq!(status.peers.len(), 1);
+        assert_eq!(status.peers[0].libp2p_port, 8302);
+        assert_eq!(status.block_height_opt, Some(359604));
+        assert_eq!(
+            status.ban_statuses[0].1.banned,
+            P2pNetworkNodeStatusBanned::Unbanned
+        );
+
+        let decoded = P2pNetworkNodeStatus::from_json(&status.to_json()).expect("valid status");
+        assert_eq!(decoded, status);
+    }
+}
@@ -0,0 +1,228 @@
+use openmina_core::{bug_condition, fuzzed_maybe, warn, Substate};
+
+use crate::{
+    token::{StatusAlgorithm, StreamKind},
+    Data, P2pNetworkConnectionMuxState, P2pNetworkYamuxAction, P2pState, YamuxFlags,
+    YamuxStreamKind,
+};
+
+use super::{
+    P2pNetworkNodeStatus, P2pNetworkNodeStatusAction, P2pNetworkNodeStatusQueryState,
+    P2pNetworkNodeStatusState, P2pNetworkNodeStatusStreamState, NODE_STATUS_MAX_SIZE,
+};
+
+impl P2pNetworkNodeStatusState {
+    pub fn reducer<Action, State>(
+        mut state_context: Substate<Action, State, Self>,
+        action: redux::ActionWithMeta<P2pNetworkNodeStatusAction>,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let node_status_state = state_context.get_substate_mut()?;
+        let (action, meta) = action.split();
+
+        match action {
+            P2pNetworkNodeStatusAction::NewStream {
+                incoming: true,
+                peer_id,
+                addr,
+                stream_id,
+            } => {
+                node_status_state
+                    .incoming
+                    .entry(peer_id)
+                    .or_default()
+                    .insert(
+                        stream_id,
+                        P2pNetworkNodeStatusStreamState { addr, stream_id },
+                    );
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                if let Some(callback) = &p2p_state.callbacks.on_p2p_network_node_status_request {
+                    dispatcher.push_callback(callback.clone(), (peer_id, stream_id));
+                }
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::NewStream {
+                incoming: false,
+                peer_id,
+                addr,
+                stream_id,
+            } => {
+                let Some(query) = node_status_state.outgoing.get_mut(&peer_id) else {
+                    bug_condition!(
+                        "Unexpected outgoing node status stream {stream_id} with peer {peer_id}"
+                    );
+                    return Ok(());
+                };
+                query.stream = Some(P2pNetworkNodeStatusStreamState { addr, stream_id });
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::IncomingData {
+                peer_id,
+                stream_id,
+                data,
+                ..
+            } => {
+                let Some(query) = node_status_state.find_query_mut(&peer_id, stream_id) else {
+                    // the peer asking for our status is not supposed to send anything
+                    return Ok(());
+                };
+                query.buffer.extend_from_slice(&data);
+
+                if query.buffer.len() > NODE_STATUS_MAX_SIZE {
+                    let dispatcher = state_context.into_dispatcher();
+                    dispatcher.push(P2pNetworkNodeStatusAction::QueryError {
+                        peer_id,
+                        error: format!("node status exceeds {NODE_STATUS_MAX_SIZE} bytes"),
+                    });
+                }
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::RemoteClose {
+                peer_id,
+                addr,
+                stream_id,
+            } => {
+                let result = node_status_state
+                    .find_query(&peer_id, stream_id)
+                    .map(|query| P2pNetworkNodeStatus::from_json(&query.buffer));
+                // the stream is closed with the response
+                let awaits_response = node_status_state
+                    .find_request(&peer_id, stream_id)
+                    .is_some();
+
+                let dispatcher = state_context.into_dispatcher();
+                match result {
+                    Some(Ok(status)) => {
+                        dispatcher.push(P2pNetworkNodeStatusAction::QuerySuccess {
+                            peer_id,
+                            status: Box::new(status),
+                        });
+                    }
+                    Some(Err(error)) => {
+                        warn!(meta.time(); summary = "error decoding node status", peer_id = display(peer_id), error = display(&error));
+                        dispatcher.push(P2pNetworkNodeStatusAction::QueryError { peer_id, error });
+                    }
+                    None => {}
+                }
+
+                if !awaits_response {
+                    dispatcher.push(P2pNetworkYamuxAction::OutgoingData {
+                        addr,
+                        stream_id,
+                        data: Data::empty(),
+                        flags: YamuxFlags::FIN,
+                    });
+                }
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::Respond {
+                peer_id,
+                stream_id,
+                status,
+            } => {
+                let Some(stream) = node_status_state.remove_request(&peer_id, stream_id) else {
+                    bug_condition!(
+                        "Invalid state for action: `P2pNetworkNodeStatusAction::Respond`"
+                    );
+                    return Ok(());
+                };
+
+                let dispatcher = state_context.into_dispatcher();
+                if let Some(status) = status {
+                    let flags =
+                        fuzzed_maybe!(Default::default(), crate::fuzzer::mutate_yamux_flags);
+                    dispatcher.push(P2pNetworkYamuxAction::OutgoingData {
+                        addr: stream.addr,
+                        stream_id: stream.stream_id,
+                        data: Data::from(status.to_json()),
+                        flags,
+                    });
+                }
+                dispatcher.push(P2pNetworkYamuxAction::OutgoingData {
+                    addr: stream.addr,
+                    stream_id: stream.stream_id,
+                    data: Data::empty(),
+                    flags: YamuxFlags::FIN,
+                });
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::Query { peer_id, rpc_id } => {
+                node_status_state.outgoing.insert(
+                    peer_id,
+                    P2pNetworkNodeStatusQueryState {
+                        time: meta.time(),
+                        rpc_id,
+                        stream: None,
+                        buffer: vec![],
+                    },
+                );
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                let stream = p2p_state.network.scheduler.find_peer(&peer_id).and_then(
+                    |(addr, conn_state)| {
+                        let P2pNetworkConnectionMuxState::Yamux(yamux) = conn_state.mux.as_ref()?;
+                        let stream_id =
+                            yamux.next_stream_id(YamuxStreamKind::NodeStatus, addr.incoming)?;
+                        Some((*addr, stream_id))
+                    },
+                );
+
+                if let Some((addr, stream_id)) = stream {
+                    dispatcher.push(P2pNetworkYamuxAction::OpenStream {
+                        addr,
+                        stream_id,
+                        stream_kind: StreamKind::Status(StatusAlgorithm::MinaNodeStatus),
+                    });
+                } else {
+                    dispatcher.push(P2pNetworkNodeStatusAction::QueryError {
+                        peer_id,
+                        error: "peer is not connected over libp2p".to_owned(),
+                    });
+                }
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::QuerySuccess { peer_id, status } => {
+                let Some(query) = node_status_state.outgoing.remove(&peer_id) else {
+                    bug_condition!(
+                        "Invalid state for action: `P2pNetworkNodeStatusAction::QuerySuccess`"
+                    );
+                    return Ok(());
+                };
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                if let (Some(rpc_id), Some(callback)) = (
+                    query.rpc_id,
+                    &p2p_state.callbacks.on_p2p_network_node_status_query_success,
+                ) {
+                    dispatcher.push_callback(callback.clone(), (rpc_id, status));
+                }
+                Ok(())
+            }
+            P2pNetworkNodeStatusAction::QueryError { peer_id, error } => {
+                let Some(query) = node_status_state.outgoing.remove(&peer_id) else {
+                    bug_condition!(
+                        "Invalid state for action: `P2pNetworkNodeStatusAction::QueryError`"
+                    );
+                    return Ok(());
+                };
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                if let (Some(rpc_id), Some(callback)) = (
+                    query.rpc_id,
+                    &p2p_state.callbacks.on_p2p_network_node_status_query_error,
+                ) {
+                    dispatcher.push_callback(callback.clone(), (rpc_id, error));
+                }
+                Ok(())
+            }
+        }
+    }
+}
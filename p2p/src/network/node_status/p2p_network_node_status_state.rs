@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+
+use openmina_core::requests::RpcId;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{network::scheduler::StreamState, ConnectionAddr, P2pTimeouts, PeerId, StreamId};
+
+/// State of the node status protocol (`/mina/node-status`).
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkNodeStatusState {
+    /// Streams opened by peers that requested our node status, waiting for the response.
+    pub incoming: StreamState<P2pNetworkNodeStatusStreamState>,
+    /// Node status queries we sent to other peers.
+    pub outgoing: BTreeMap<PeerId, P2pNetworkNodeStatusQueryState>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkNodeStatusStreamState {
+    pub addr: ConnectionAddr,
+    pub stream_id: StreamId,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkNodeStatusQueryState {
+    pub time: Timestamp,
+    pub rpc_id: Option<RpcId>,
+    /// Stream is set once it is negotiated with the peer.
+    pub stream: Option<P2pNetworkNodeStatusStreamState>,
+    /// Data received so far, the status is complete once the peer closes the stream.
+    pub buffer: Vec<u8>,
+}
+
+impl P2pNetworkNodeStatusState {
+    pub fn prune_peer_state(&mut self, peer_id: &PeerId) {
+        // pending queries are finished by the timeout, so the caller gets notified
+        self.incoming.remove(peer_id);
+    }
+
+    pub fn find_request(
+        &self,
+        peer_id: &PeerId,
+        stream_id: StreamId,
+    ) -> Option<&P2pNetworkNodeStatusStreamState> {
+        self.incoming.get(peer_id)?.get(&stream_id)
+    }
+
+    pub fn remove_request(
+        &mut self,
+        peer_id: &PeerId,
+        stream_id: StreamId,
+    ) -> Option<P2pNetworkNodeStatusStreamState> {
+        let streams = self.incoming.get_mut(peer_id)?;
+        let stream = streams.remove(&stream_id);
+        if streams.is_empty() {
+            self.incoming.remove(peer_id);
+        }
+        stream
+    }
+
+    pub fn find_query(
+        &self,
+        peer_id: &PeerId,
+        stream_id: StreamId,
+    ) -> Option<&P2pNetworkNodeStatusQueryState> {
+        self.outgoing.get(peer_id).filter(|query| {
+            query
+                .stream
+                .as_ref()
+                .map_or(false, |stream| stream.stream_id == stream_id)
+        })
+    }
+
+    pub fn find_query_mut(
+        &mut self,
+        peer_id: &PeerId,
+        stream_id: StreamId,
+    ) -> Option<&mut P2pNetworkNodeStatusQueryState> {
+        self.outgoing.get_mut(peer_id).filter(|query| {
+            query
+                .stream
+                .as_ref()
+                .map_or(false, |stream| stream.stream_id == stream_id)
+        })
+    }
+}
+
+impl P2pNetworkNodeStatusQueryState {
+    pub fn is_timed_out(&self, now: Timestamp, timeouts: &P2pTimeouts) -> bool {
+        now.checked_sub(self.time)
+            .and_then(|dur| timeouts.node_status.map(|to| dur >= to))
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_kept_per_stream() {
+        let peer_id = PeerId::from_bytes([1; 32]);
+        let other_peer_id = PeerId::from_bytes([2; 32]);
+        let addr = ConnectionAddr {
+            sock_addr: "127.0.0.1:8302".parse().unwrap(),
+            incoming: true,
+        };
+
+        let mut state = P2pNetworkNodeStatusState::default();
+        for (peer_id, stream_id) in [(peer_id, 1), (peer_id, 3), (other_peer_id, 1)] {
+            state.incoming.entry(peer_id).or_default().insert(
+                stream_id,
+                P2pNetworkNodeStatusStreamState { addr, stream_id },
+            );
+        }
+        assert!(state.find_request(&peer_id, 1).is_some());
+        assert!(state.find_request(&peer_id, 3).is_some());
+
+        assert!(state.remove_request(&peer_id, 1).is_some());
+        assert!(state.remove_request(&peer_id, 1).is_none());
+        assert!(state.find_request(&peer_id, 3).is_some());
+        assert!(state.remove_request(&peer_id, 3).is_some());
+        assert!(!state.incoming.contains_key(&peer_id));
+
+        state.prune_peer_state(&other_peer_id);
+        assert!(state.incoming.is_empty());
+    }
+}
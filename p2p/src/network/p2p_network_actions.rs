@@ -2,8 +2,8 @@ use openmina_core::ActionEvent;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    identify::*, kad::*, noise::*, pnet::*, pnet_effectful::*, pubsub::*, rpc::*, scheduler::*,
-    select::*, yamux::*, P2pNetworkSchedulerEffectfulAction,
+    bitswap::*, identify::*, kad::*, node_status::*, noise::*, ping::*, pnet::*, pnet_effectful::*,
+    pubsub::*, rpc::*, scheduler::*, select::*, yamux::*, P2pNetworkSchedulerEffectfulAction,
 };
 
 use crate::P2pState;
@@ -19,6 +19,9 @@ pub enum P2pNetworkAction {
     Kad(P2pNetworkKadAction),
     Pubsub(P2pNetworkPubsubAction),
     Rpc(P2pNetworkRpcAction),
+    Bitswap(P2pNetworkBitswapAction),
+    NodeStatus(P2pNetworkNodeStatusAction),
+    Ping(P2pNetworkPingAction),
 }
 
 impl redux::EnablingCondition<P2pState> for P2pNetworkAction {
@@ -33,6 +36,9 @@ impl redux::EnablingCondition<P2pState> for P2pNetworkAction {
             Self::Kad(v) => v.is_enabled(state, time),
             Self::Pubsub(v) => v.is_enabled(state, time),
             Self::Rpc(v) => v.is_enabled(state, time),
+            Self::Bitswap(v) => v.is_enabled(state, time),
+            Self::NodeStatus(v) => v.is_enabled(state, time),
+            Self::Ping(v) => v.is_enabled(state, time),
         }
     }
 }
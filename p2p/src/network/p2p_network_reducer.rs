@@ -55,6 +55,19 @@ impl P2pNetworkState {
                 meta.with_action(a),
                 limits,
             ),
+            P2pNetworkAction::Bitswap(a) => P2pNetworkBitswapState::reducer(
+                Substate::from_compatible_substate(state_context),
+                meta.with_action(a),
+                limits,
+            ),
+            P2pNetworkAction::NodeStatus(a) => P2pNetworkNodeStatusState::reducer(
+                Substate::from_compatible_substate(state_context),
+                meta.with_action(a),
+            ),
+            P2pNetworkAction::Ping(a) => P2pNetworkPingState::reducer(
+                Substate::from_compatible_substate(state_context),
+                meta.with_action(a),
+            ),
         }
     }
 
@@ -1,6 +1,6 @@
 use std::net::{IpAddr, SocketAddr};
 
-use crate::ConnectionAddr;
+use crate::{ConnectionAddr, StreamId};
 
 /// The state machine sends commands to the service.
 pub enum MioCmd {
@@ -20,6 +20,14 @@ pub enum MioCmd {
     Send(ConnectionAddr, Box<[u8]>),
     /// Disconnect the remote peer.
     Disconnect(ConnectionAddr),
+
+    /// Bind a QUIC endpoint to the UDP socket.
+    QuicListenOn(SocketAddr),
+    /// Create a new outgoing QUIC connection to the socket.
+    QuicConnect(SocketAddr),
+    /// Send the data in the QUIC stream, opening the stream if needed.
+    /// If the flag is set, finish the stream after the data.
+    QuicSend(ConnectionAddr, StreamId, Box<[u8]>, bool),
 }
 
 pub trait P2pMioService: redux::Service {
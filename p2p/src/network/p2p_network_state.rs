@@ -2,7 +2,7 @@ use multiaddr::Multiaddr;
 use openmina_core::ChainId;
 use serde::{Deserialize, Serialize};
 
-use crate::{identity::PublicKey, PeerId};
+use crate::{identity::PublicKey, P2pMeshsubConfig, PeerId};
 
 use super::*;
 
@@ -18,6 +18,7 @@ impl P2pNetworkState {
         known_peers: Vec<(PeerId, Multiaddr)>,
         chain_id: &ChainId,
         discovery: bool,
+        meshsub: &P2pMeshsubConfig,
     ) -> Self {
         let peer_id = identity.peer_id();
         let pnet_key = chain_id.preshared_key();
@@ -41,8 +42,11 @@ impl P2pNetworkState {
                 local_pk: identity,
                 pnet_key,
                 connections: Default::default(),
-                broadcast_state: Default::default(),
+                broadcast_state: P2pNetworkPubsubState::new(meshsub.clone()),
                 identify_state: Default::default(),
+                bitswap_state: Default::default(),
+                node_status_state: Default::default(),
+                ping_state: Default::default(),
                 discovery_state,
                 rpc_incoming_streams: Default::default(),
                 rpc_outgoing_streams: Default::default(),
@@ -0,0 +1,11 @@
+mod p2p_network_ping_actions;
+pub use self::p2p_network_ping_actions::P2pNetworkPingAction;
+
+mod p2p_network_ping_state;
+pub use self::p2p_network_ping_state::{
+    P2pNetworkPingOutgoingState, P2pNetworkPingState, P2pNetworkPingStatus,
+    P2pNetworkPingStreamState, PING_INTERVAL, PING_PAYLOAD_SIZE,
+};
+
+#[cfg(feature = "p2p-libp2p")]
+mod p2p_network_ping_reducer;
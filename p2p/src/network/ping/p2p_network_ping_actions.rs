@@ -0,0 +1,67 @@
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::{ConnectionAddr, Data, P2pState, PeerId, StreamId};
+
+/// Actions of the ping protocol (`/ipfs/ping/1.0.0`).
+///
+/// The peer that opens the stream sends 32 byte payloads over it,
+/// the other side echoes every payload back.
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+pub enum P2pNetworkPingAction {
+    /// Create a new stream, either incoming or outgoing.
+    NewStream {
+        incoming: bool,
+        peer_id: PeerId,
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+    },
+
+    /// Process incoming raw data from a peer.
+    IncomingData {
+        peer_id: PeerId,
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+        data: Data,
+    },
+
+    /// Remote peer closed the stream.
+    RemoteClose {
+        peer_id: PeerId,
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+    },
+
+    /// Ping the peer, opening the stream first if needed.
+    #[action_event(level = trace, fields(display(peer_id)))]
+    Ping { peer_id: PeerId },
+
+    /// Peer didn't answer the ping in time.
+    #[action_event(level = warn, fields(display(peer_id)))]
+    Timeout { peer_id: PeerId },
+}
+
+impl From<P2pNetworkPingAction> for crate::P2pAction {
+    fn from(value: P2pNetworkPingAction) -> Self {
+        crate::P2pAction::Network(value.into())
+    }
+}
+
+impl redux::EnablingCondition<P2pState> for P2pNetworkPingAction {
+    fn is_enabled(&self, state: &P2pState, time: redux::Timestamp) -> bool {
+        let ping_state = &state.network.scheduler.ping_state;
+        match self {
+            P2pNetworkPingAction::Ping { peer_id } => {
+                state.get_ready_peer(peer_id).is_some()
+                    && state.network.scheduler.find_peer(peer_id).is_some()
+                    && ping_state.should_ping(peer_id, time)
+            }
+            P2pNetworkPingAction::Timeout { peer_id } => {
+                ping_state.outgoing.get(peer_id).map_or(false, |ping| {
+                    ping.is_timed_out(time, &state.config.timeouts)
+                })
+            }
+            _ => true,
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use openmina_core::{bug_condition, fuzzed_maybe, warn, Substate};
+use redux::Timestamp;
+
+use crate::{
+    disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
+    peer::P2pPeerAction,
+    token::{PingAlgorithm, StreamKind},
+    ConnectionAddr, Data, P2pNetworkConnectionMuxState, P2pNetworkYamuxAction, P2pState, StreamId,
+    YamuxFlags, YamuxStreamKind,
+};
+
+use super::{
+    P2pNetworkPingAction, P2pNetworkPingOutgoingState, P2pNetworkPingState, P2pNetworkPingStatus,
+    P2pNetworkPingStreamState, PING_PAYLOAD_SIZE,
+};
+
+impl P2pNetworkPingState {
+    pub fn reducer<Action, State>(
+        mut state_context: Substate<Action, State, Self>,
+        action: redux::ActionWithMeta<P2pNetworkPingAction>,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let ping_state = state_context.get_substate_mut()?;
+        let (action, meta) = action.split();
+
+        match action {
+            P2pNetworkPingAction::NewStream {
+                incoming: true,
+                peer_id,
+                addr,
+                stream_id,
+            } => {
+                ping_state.incoming.insert(
+                    peer_id,
+                    P2pNetworkPingStreamState {
+                        addr,
+                        stream_id,
+                        buffer: vec![],
+                    },
+                );
+                Ok(())
+            }
+            P2pNetworkPingAction::NewStream {
+                incoming: false,
+                peer_id,
+                addr,
+                stream_id,
+            } => {
+                let Some(ping) = ping_state.outgoing.get_mut(&peer_id) else {
+                    bug_condition!(
+                        "Unexpected outgoing ping stream {stream_id} with peer {peer_id}"
+                    );
+                    return Ok(());
+                };
+                ping.stream = Some(P2pNetworkPingStreamState {
+                    addr,
+                    stream_id,
+                    buffer: vec![],
+                });
+
+                let payload = Self::send_payload(ping, meta.time());
+                let dispatcher = state_context.into_dispatcher();
+                Self::push_payload(dispatcher, addr, stream_id, payload);
+                Ok(())
+            }
+            P2pNetworkPingAction::IncomingData {
+                peer_id,
+                addr,
+                stream_id,
+                data,
+            } => {
+                if let Some(stream) = ping_state.find_incoming_mut(&peer_id, stream_id) {
+                    stream.buffer.extend_from_slice(&data);
+                    let len = stream.buffer.len() - stream.buffer.len() % PING_PAYLOAD_SIZE;
+                    let echo = stream.buffer.drain(..len).collect::<Vec<_>>();
+
+                    if !echo.is_empty() {
+                        let dispatcher = state_context.into_dispatcher();
+                        let flags =
+                            fuzzed_maybe!(Default::default(), crate::fuzzer::mutate_yamux_flags);
+                        dispatcher.push(P2pNetworkYamuxAction::OutgoingData {
+                            addr,
+                            stream_id,
+                            data: Data::from(echo),
+                            flags,
+                        });
+                    }
+                    return Ok(());
+                }
+
+                let Some(ping) = ping_state.find_outgoing_mut(&peer_id, stream_id) else {
+                    return Ok(());
+                };
+                let Some(stream) = ping.stream.as_mut() else {
+                    return Ok(());
+                };
+                stream.buffer.extend_from_slice(&data);
+                if stream.buffer.len() < PING_PAYLOAD_SIZE {
+                    return Ok(());
+                }
+                let echo = stream.buffer.drain(..PING_PAYLOAD_SIZE).collect::<Vec<_>>();
+
+                let P2pNetworkPingStatus::Sent { time, payload } = &ping.status else {
+                    warn!(meta.time(); summary = "unexpected ping response", peer_id = display(peer_id));
+                    return Ok(());
+                };
+                if echo[..] != payload[..] {
+                    // leave the ping pending, the peer gets disconnected on timeout
+                    warn!(meta.time(); summary = "ping response doesn't match the payload", peer_id = display(peer_id));
+                    return Ok(());
+                }
+
+                let rtt = meta.time().checked_sub(*time).unwrap_or_default();
+                ping.status = P2pNetworkPingStatus::Received { time: meta.time() };
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                if p2p_state.get_ready_peer(&peer_id).is_some() {
+                    dispatcher.push(P2pPeerAction::RttUpdate { peer_id, rtt });
+                }
+                Ok(())
+            }
+            P2pNetworkPingAction::RemoteClose {
+                peer_id,
+                addr,
+                stream_id,
+            } => {
+                if ping_state.find_incoming_mut(&peer_id, stream_id).is_some() {
+                    ping_state.incoming.remove(&peer_id);
+                } else if ping_state.find_outgoing_mut(&peer_id, stream_id).is_some() {
+                    // the stream is opened again on the next ping
+                    ping_state.outgoing.remove(&peer_id);
+                }
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pNetworkYamuxAction::OutgoingData {
+                    addr,
+                    stream_id,
+                    data: Data::empty(),
+                    flags: YamuxFlags::FIN,
+                });
+                Ok(())
+            }
+            P2pNetworkPingAction::Ping { peer_id } => {
+                if let Some(ping) = ping_state.outgoing.get_mut(&peer_id) {
+                    let Some((addr, stream_id)) = ping
+                        .stream
+                        .as_ref()
+                        .map(|stream| (stream.addr, stream.stream_id))
+                    else {
+                        bug_condition!("Invalid state for action: `P2pNetworkPingAction::Ping`");
+                        return Ok(());
+                    };
+                    let payload = Self::send_payload(ping, meta.time());
+                    let dispatcher = state_context.into_dispatcher();
+                    Self::push_payload(dispatcher, addr, stream_id, payload);
+                    return Ok(());
+                }
+
+                ping_state.outgoing.insert(
+                    peer_id,
+                    P2pNetworkPingOutgoingState {
+                        stream: None,
+                        status: P2pNetworkPingStatus::Opening { time: meta.time() },
+                    },
+                );
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+                let stream = p2p_state.network.scheduler.find_peer(&peer_id).and_then(
+                    |(addr, conn_state)| {
+                        let P2pNetworkConnectionMuxState::Yamux(yamux) = conn_state.mux.as_ref()?;
+                        let stream_id =
+                            yamux.next_stream_id(YamuxStreamKind::Ping, addr.incoming)?;
+                        Some((*addr, stream_id))
+                    },
+                );
+
+                // if the stream can't be opened, the ping times out
+                if let Some((addr, stream_id)) = stream {
+                    dispatcher.push(P2pNetworkYamuxAction::OpenStream {
+                        addr,
+                        stream_id,
+                        stream_kind: StreamKind::Ping(PingAlgorithm::Ping1_0_0),
+                    });
+                }
+                Ok(())
+            }
+            P2pNetworkPingAction::Timeout { peer_id } => {
+                ping_state.outgoing.remove(&peer_id);
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pDisconnectionAction::Init {
+                    peer_id,
+                    reason: P2pDisconnectionReason::PingTimeout,
+                });
+                Ok(())
+            }
+        }
+    }
+
+    fn send_payload(
+        ping: &mut P2pNetworkPingOutgoingState,
+        time: Timestamp,
+    ) -> [u8; PING_PAYLOAD_SIZE] {
+        let payload = P2pNetworkPingStatus::payload(time);
+        ping.status = P2pNetworkPingStatus::Sent { time, payload };
+        payload
+    }
+
+    fn push_payload<Action, State>(
+        dispatcher: &mut redux::Dispatcher<Action, State>,
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+        payload: [u8; PING_PAYLOAD_SIZE],
+    ) where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let flags = fuzzed_maybe!(Default::default(), crate::fuzzer::mutate_yamux_flags);
+        dispatcher.push(P2pNetworkYamuxAction::OutgoingData {
+            addr,
+            stream_id,
+            data: Data::from(payload.to_vec()),
+            flags,
+        });
+    }
+}
@@ -0,0 +1,125 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{ConnectionAddr, P2pTimeouts, PeerId, StreamId};
+
+/// Size of the ping payload, the peer echoes it back unchanged.
+pub const PING_PAYLOAD_SIZE: usize = 32;
+
+/// Interval between two pings sent to the same peer.
+pub const PING_INTERVAL: Duration = Duration::from_secs(15);
+
+/// State of the ping protocol (`/ipfs/ping/1.0.0`).
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkPingState {
+    /// Streams opened by peers pinging us.
+    pub incoming: BTreeMap<PeerId, P2pNetworkPingStreamState>,
+    /// Our pings to other peers.
+    pub outgoing: BTreeMap<PeerId, P2pNetworkPingOutgoingState>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkPingStreamState {
+    pub addr: ConnectionAddr,
+    pub stream_id: StreamId,
+    /// Data received so far that doesn't make up a whole payload yet.
+    pub buffer: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkPingOutgoingState {
+    /// Stream is set once it is negotiated with the peer, it is reused for subsequent pings.
+    pub stream: Option<P2pNetworkPingStreamState>,
+    pub status: P2pNetworkPingStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum P2pNetworkPingStatus {
+    /// Stream is being opened.
+    Opening { time: Timestamp },
+    /// Payload is sent, waiting for the peer to echo it.
+    Sent {
+        time: Timestamp,
+        payload: [u8; PING_PAYLOAD_SIZE],
+    },
+    /// Peer echoed the last payload.
+    Received { time: Timestamp },
+}
+
+impl P2pNetworkPingState {
+    pub fn prune_peer_state(&mut self, peer_id: &PeerId) {
+        self.incoming.remove(peer_id);
+        self.outgoing.remove(peer_id);
+    }
+
+    pub fn find_incoming_mut(
+        &mut self,
+        peer_id: &PeerId,
+        stream_id: StreamId,
+    ) -> Option<&mut P2pNetworkPingStreamState> {
+        self.incoming
+            .get_mut(peer_id)
+            .filter(|stream| stream.stream_id == stream_id)
+    }
+
+    pub fn find_outgoing_mut(
+        &mut self,
+        peer_id: &PeerId,
+        stream_id: StreamId,
+    ) -> Option<&mut P2pNetworkPingOutgoingState> {
+        self.outgoing.get_mut(peer_id).filter(|ping| {
+            ping.stream
+                .as_ref()
+                .map_or(false, |stream| stream.stream_id == stream_id)
+        })
+    }
+
+    /// Whether it is time to ping the peer again.
+    pub fn should_ping(&self, peer_id: &PeerId, now: Timestamp) -> bool {
+        match self.outgoing.get(peer_id) {
+            None => true,
+            Some(P2pNetworkPingOutgoingState {
+                stream: Some(_),
+                status: P2pNetworkPingStatus::Received { time },
+            }) => now
+                .checked_sub(*time)
+                .map_or(false, |dur| dur >= PING_INTERVAL),
+            Some(_) => false,
+        }
+    }
+}
+
+impl P2pNetworkPingOutgoingState {
+    pub fn is_timed_out(&self, now: Timestamp, timeouts: &P2pTimeouts) -> bool {
+        let time = match &self.status {
+            P2pNetworkPingStatus::Opening { time } | P2pNetworkPingStatus::Sent { time, .. } => {
+                *time
+            }
+            P2pNetworkPingStatus::Received { .. } => return false,
+        };
+        now.checked_sub(time)
+            .and_then(|dur| timeouts.ping.map(|to| dur >= to))
+            .unwrap_or(false)
+    }
+}
+
+impl P2pNetworkPingStatus {
+    /// Payload for a ping sent at `time`.
+    ///
+    /// The peer only has to echo it back, so it doesn't need to be random,
+    /// just different between subsequent pings.
+    pub fn payload(time: Timestamp) -> [u8; PING_PAYLOAD_SIZE] {
+        let mut payload = [0; PING_PAYLOAD_SIZE];
+        let nanos = u64::from(time).to_le_bytes();
+        payload
+            .chunks_mut(nanos.len())
+            .enumerate()
+            .for_each(|(i, chunk)| {
+                chunk.copy_from_slice(&nanos);
+                chunk[0] ^= i as u8;
+            });
+        payload
+    }
+}
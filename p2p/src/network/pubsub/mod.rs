@@ -5,6 +5,11 @@ mod pb {
 mod p2p_network_pubsub_actions;
 pub use self::p2p_network_pubsub_actions::P2pNetworkPubsubAction;
 
+mod p2p_network_pubsub_score;
+pub use self::p2p_network_pubsub_score::{
+    P2pNetworkPubsubPeerScore, P2pNetworkPubsubPeerTopicScore,
+};
+
 mod p2p_network_pubsub_state;
 pub use self::p2p_network_pubsub_state::{
     P2pNetworkPubsubClientState, P2pNetworkPubsubClientTopicState, P2pNetworkPubsubState,
@@ -13,8 +18,8 @@ pub use self::p2p_network_pubsub_state::{
 #[cfg(feature = "p2p-libp2p")]
 mod p2p_network_pubsub_reducer;
 
-#[cfg(feature = "p2p-libp2p")]
-const TOPIC: &str = "coda/consensus-messages/0.0.1";
+/// The only topic Mina nodes gossip on.
+pub const TOPIC: &str = "coda/consensus-messages/0.0.1";
 
 pub mod pubsub_effectful;
 pub use pubsub_effectful::P2pNetworkPubsubEffectfulAction;
@@ -60,6 +60,17 @@ pub enum P2pNetworkPubsubAction {
         seen_limit: usize,
     },
 
+    /// A message from the peer failed validation, penalize the peer.
+    ///
+    /// **Fields:**
+    /// - `reason`: Why the message was rejected.
+    #[action_event(level = warn, fields(display(peer_id), display(topic_id), display(reason)))]
+    RejectMessage {
+        peer_id: PeerId,
+        topic_id: String,
+        reason: String,
+    },
+
     /// Clean up temporary states after processing an incoming message.
     IncomingMessageCleanup { peer_id: PeerId },
 
@@ -69,6 +80,11 @@ pub enum P2pNetworkPubsubAction {
     /// Remove a peer from the mesh network for a specific topic.
     Prune { peer_id: PeerId, topic_id: String },
 
+    /// Periodic maintenance: update peer scores, keep the mesh size between
+    /// the configured degrees and disconnect graylisted peers.
+    #[action_event(level = trace)]
+    Heartbeat,
+
     /// Initiate the broadcasting of a message to all subscribed peers.
     ///
     /// **Fields:**
@@ -126,8 +142,17 @@ impl From<P2pNetworkPubsubAction> for crate::P2pAction {
 }
 
 impl redux::EnablingCondition<P2pState> for P2pNetworkPubsubAction {
-    fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
+    fn is_enabled(&self, state: &P2pState, time: redux::Timestamp) -> bool {
         match self {
+            P2pNetworkPubsubAction::Heartbeat => state
+                .network
+                .scheduler
+                .broadcast_state
+                .last_heartbeat
+                .map_or(true, |last| {
+                    time.checked_sub(last)
+                        .map_or(false, |dur| dur >= state.config.meshsub.heartbeat_interval)
+                }),
             P2pNetworkPubsubAction::OutgoingMessage { peer_id } => state
                 .network
                 .scheduler
@@ -1,4 +1,4 @@
-use std::collections::btree_map::Entry;
+use std::collections::{btree_map::Entry, BTreeMap};
 
 use binprot::BinProtRead;
 use mina_p2p_messages::{gossip, v2};
@@ -7,6 +7,7 @@ use redux::{Dispatcher, Timestamp};
 
 use crate::{
     channels::{snark::P2pChannelsSnarkAction, transaction::P2pChannelsTransactionAction},
+    disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
     peer::P2pPeerAction,
     Data, P2pConfig, P2pNetworkYamuxAction, PeerId,
 };
@@ -14,8 +15,8 @@ use crate::{
 use super::{
     p2p_network_pubsub_state::P2pNetworkPubsubClientMeshAddingState,
     pb::{self, Message},
-    P2pNetworkPubsubAction, P2pNetworkPubsubClientState, P2pNetworkPubsubEffectfulAction,
-    P2pNetworkPubsubState, TOPIC,
+    P2pNetworkPubsubAction, P2pNetworkPubsubClientState, P2pNetworkPubsubClientTopicState,
+    P2pNetworkPubsubEffectfulAction, P2pNetworkPubsubState, TOPIC,
 };
 
 impl P2pNetworkPubsubState {
@@ -56,6 +57,9 @@ impl P2pNetworkPubsubState {
                     cache: Default::default(),
                     buffer: vec![],
                     incoming_messages: vec![],
+                    iwant_served: Default::default(),
+                    ihave_received: 0,
+                    iwant_requested: 0,
                 });
                 state.protocol = protocol;
                 state.addr = addr;
@@ -65,6 +69,7 @@ impl P2pNetworkPubsubState {
                     .entry(super::TOPIC.to_owned())
                     .or_default()
                     .insert(peer_id, Default::default());
+                pubsub_state.scores.entry(peer_id).or_default();
 
                 Ok(())
             }
@@ -88,6 +93,9 @@ impl P2pNetworkPubsubState {
                         cache: Default::default(),
                         buffer: vec![],
                         incoming_messages: vec![],
+                        iwant_served: Default::default(),
+                        ihave_received: 0,
+                        iwant_requested: 0,
                     }
                 });
                 state.outgoing_stream_id = Some(stream_id);
@@ -99,6 +107,7 @@ impl P2pNetworkPubsubState {
                     .entry(TOPIC.to_owned())
                     .or_default()
                     .insert(peer_id, Default::default());
+                pubsub_state.scores.entry(peer_id).or_default();
 
                 if let Some(state) = pubsub_state.clients.get_mut(&peer_id) {
                     state.message.subscriptions.push(pb::rpc::SubOpts {
@@ -117,7 +126,9 @@ impl P2pNetworkPubsubState {
                 };
                 dispatcher.push(P2pNetworkPubsubAction::OutgoingMessage { peer_id });
                 let mesh_size = map.values().filter(|s| s.on_mesh()).count();
-                if mesh_size < config.meshsub.outbound_degree_desired {
+                if mesh_size < config.meshsub.outbound_degree_desired
+                    && state.peer_score(&peer_id) >= 0.0
+                {
                     dispatcher.push(P2pNetworkPubsubAction::Graft {
                         peer_id,
                         topic_id: TOPIC.to_owned(),
@@ -142,6 +153,8 @@ impl P2pNetworkPubsubState {
                     seen_limit,
                     addr,
                 });
+                // flush control messages queued in response, if any
+                dispatcher.push(P2pNetworkPubsubAction::OutgoingMessage { peer_id });
 
                 Ok(())
             }
@@ -226,6 +239,13 @@ impl P2pNetworkPubsubState {
                 }
                 Ok(())
             }
+            P2pNetworkPubsubAction::RejectMessage {
+                peer_id, topic_id, ..
+            } => {
+                pubsub_state
+                    .update_topic_score(&peer_id, &topic_id, |score, _| score.on_invalid_message());
+                Ok(())
+            }
             P2pNetworkPubsubAction::IncomingMessageCleanup { peer_id } => {
                 pubsub_state.clear_incoming();
 
@@ -250,6 +270,9 @@ impl P2pNetworkPubsubState {
                     return Ok(());
                 };
                 state.mesh = P2pNetworkPubsubClientMeshAddingState::Added;
+                pubsub_state.update_topic_score(&peer_id, &topic_id, |score, _| {
+                    score.on_graft(meta.time())
+                });
 
                 if let Some(state) = pubsub_state.clients.get_mut(&peer_id) {
                     let control = state
@@ -280,6 +303,9 @@ impl P2pNetworkPubsubState {
                     return Ok(());
                 };
                 state.mesh = P2pNetworkPubsubClientMeshAddingState::WeRefused;
+                pubsub_state.update_topic_score(&peer_id, &topic_id, |score, config| {
+                    score.on_prune(config, meta.time())
+                });
 
                 if let Some(state) = pubsub_state.clients.get_mut(&peer_id) {
                     let control = state
@@ -305,6 +331,38 @@ impl P2pNetworkPubsubState {
                 dispatcher.push(P2pNetworkPubsubAction::OutgoingMessage { peer_id });
                 Ok(())
             }
+            P2pNetworkPubsubAction::Heartbeat => {
+                pubsub_state.last_heartbeat = Some(meta.time());
+                pubsub_state.refresh_scores(meta.time());
+
+                pubsub_state.emit_gossip(meta.time());
+                pubsub_state
+                    .mcache
+                    .shift(pubsub_state.config.mcache_history_length);
+                let mcache = &pubsub_state.mcache;
+                pubsub_state
+                    .clients
+                    .values_mut()
+                    .for_each(|client| client.reset_gossip_limits(mcache));
+
+                let (dispatcher, global_state) = state_context.into_dispatcher_and_state();
+                let state: &Self = global_state.substate()?;
+
+                for (topic_id, peers) in &state.topics {
+                    state.maintain_mesh(dispatcher, topic_id, peers);
+                }
+
+                for peer_id in state.clients.keys() {
+                    if state.is_graylisted(peer_id) {
+                        dispatcher.push(P2pDisconnectionAction::Init {
+                            peer_id: *peer_id,
+                            reason: P2pDisconnectionReason::PubsubGraylisted,
+                        });
+                    }
+                }
+
+                Self::broadcast(dispatcher, global_state)
+            }
             P2pNetworkPubsubAction::OutgoingMessage { peer_id } => {
                 let msg = if let Some(v) = pubsub_state.clients.get_mut(&peer_id) {
                     &v.message
@@ -408,9 +466,17 @@ impl P2pNetworkPubsubState {
             P2pNetworkPubsubAction::BroadcastSigned { signature } => {
                 if let Some(mut message) = pubsub_state.to_sign.pop_front() {
                     message.signature = Some(signature.0.to_vec());
+                    pubsub_state.mcache.put(message.clone());
+                    let publish_threshold = pubsub_state.config.peer_score.publish_threshold;
+                    let scores = &pubsub_state.scores;
                     pubsub_state
                         .clients
                         .iter_mut()
+                        .filter(|(peer_id, _)| {
+                            scores
+                                .get(peer_id)
+                                .map_or(true, |score| score.score >= publish_threshold)
+                        })
                         .for_each(|(_, state)| state.publish(&message));
                 }
 
@@ -475,8 +541,6 @@ impl P2pNetworkPubsubState {
         message: Message,
         seen_limit: usize,
     ) -> Result<(), String> {
-        let topic = self.topics.entry(message.topic.clone()).or_default();
-
         if let Some(signature) = &message.signature {
             // skip recently seen message
             if !self.seen.contains(signature) {
@@ -510,13 +574,21 @@ impl P2pNetworkPubsubState {
                         }
                     }
                     Err(err) => {
+                        self.update_topic_score(&peer_id, &message.topic, |score, _| {
+                            score.on_invalid_message()
+                        });
                         return Err(err.to_string());
                     }
                 }
             }
         }
 
-        let message_id = self.mcache.put(message.clone());
+        self.update_topic_score(&peer_id, &message.topic, |score, config| {
+            score.on_first_delivery(config)
+        });
+
+        self.mcache.put(message.clone());
+        let topic = self.topics.entry(message.topic.clone()).or_default();
 
         // TODO: this should only happen after the contents have been validated.
         // The only validation that has happened so far is that the message can be parsed.
@@ -527,17 +599,12 @@ impl P2pNetworkPubsubState {
                 *c != &peer_id
             })
             .for_each(|(c, state)| {
-                let Some(topic_state) = topic.get(c) else {
-                    return;
-                };
-                if topic_state.on_mesh() {
+                // peers outside of the mesh learn about the message from IHAVE on heartbeat
+                if topic
+                    .get(c)
+                    .map_or(false, |topic_state| topic_state.on_mesh())
+                {
                     state.publish(&message)
-                } else {
-                    let ctr = state.message.control.get_or_insert_with(Default::default);
-                    ctr.ihave.push(pb::ControlIHave {
-                        topic_id: Some(message.topic.clone()),
-                        message_ids: message_id.clone().into_iter().collect(),
-                    })
                 }
             });
 
@@ -574,13 +641,20 @@ impl P2pNetworkPubsubState {
         match <pb::Rpc as prost::Message>::decode_length_delimited(slice) {
             Ok(decoded) => {
                 client_state.clear_buffer();
+                if self.is_graylisted(peer_id) {
+                    // ignore everything from peers with too low score
+                    return Ok(());
+                }
+                let Some(client_state) = self.clients.get_mut(peer_id) else {
+                    return Ok(());
+                };
                 client_state.incoming_messages.extend(decoded.publish);
 
                 let subscriptions = decoded.subscriptions;
                 let control = decoded.control.unwrap_or_default();
 
                 self.update_subscriptions(peer_id, subscriptions);
-                self.apply_control_commands(peer_id, &control);
+                self.apply_control_commands(peer_id, &control, timestamp);
                 self.respond_to_iwant_requests(peer_id, &control.iwant);
                 self.process_ihave_messages(peer_id, control.ihave, timestamp);
             }
@@ -616,15 +690,39 @@ impl P2pNetworkPubsubState {
     }
 
     /// Applies control commands (`graft` and `prune`) to manage the peer's mesh states within topics.
-    fn apply_control_commands(&mut self, peer_id: &PeerId, control: &pb::ControlMessage) {
-        // Apply graft commands to add the peer to specific topic meshes.
+    fn apply_control_commands(
+        &mut self,
+        peer_id: &PeerId,
+        control: &pb::ControlMessage,
+        timestamp: Timestamp,
+    ) {
+        // Apply graft commands to add the peer to specific topic meshes,
+        // peers with negative score are pruned right away.
+        let accept_graft = self.peer_score(peer_id) >= 0.0;
         for graft in &control.graft {
-            if let Some(mesh_state) = self
+            let Some(mesh_state) = self
                 .topics
                 .get_mut(graft.topic_id())
                 .and_then(|m| m.get_mut(peer_id))
-            {
+            else {
+                continue;
+            };
+
+            if accept_graft {
                 mesh_state.mesh = P2pNetworkPubsubClientMeshAddingState::Added;
+                self.update_topic_score(peer_id, graft.topic_id(), |score, _| {
+                    score.on_graft(timestamp)
+                });
+            } else {
+                mesh_state.mesh = P2pNetworkPubsubClientMeshAddingState::WeRefused;
+                if let Some(client) = self.clients.get_mut(peer_id) {
+                    let ctr = client.message.control.get_or_insert_with(Default::default);
+                    ctr.prune.push(pb::ControlPrune {
+                        topic_id: graft.topic_id.clone(),
+                        peers: vec![],
+                        backoff: None,
+                    });
+                }
             }
         }
 
@@ -636,20 +734,34 @@ impl P2pNetworkPubsubState {
                 .and_then(|m| m.get_mut(peer_id))
             {
                 mesh_state.mesh = P2pNetworkPubsubClientMeshAddingState::TheyRefused;
+                self.update_topic_score(peer_id, prune.topic_id(), |score, config| {
+                    score.on_prune(config, timestamp)
+                });
             }
         }
     }
 
     fn respond_to_iwant_requests(&mut self, peer_id: &PeerId, iwant_requests: &[pb::ControlIWant]) {
-        // Respond to iwant requests by publishing available messages from the cache.
-        for iwant in iwant_requests {
-            for msg_id in &iwant.message_ids {
-                if let Some(msg) = self.mcache.map.get(msg_id) {
-                    if let Some(client) = self.clients.get_mut(peer_id) {
-                        client.publish(msg);
-                    }
-                }
+        if self.peer_score(peer_id) < self.config.peer_score.gossip_threshold {
+            return;
+        }
+        let retransmission = self.config.gossip_retransmission;
+        let Some(client) = self.clients.get_mut(peer_id) else {
+            return;
+        };
+
+        // Respond to iwant requests by publishing available messages from the cache,
+        // the same message is sent to the peer at most `gossip_retransmission` times.
+        for msg_id in iwant_requests.iter().flat_map(|iwant| &iwant.message_ids) {
+            let Some(msg) = self.mcache.map.get(msg_id) else {
+                continue;
+            };
+            let served = client.iwant_served.entry(msg_id.clone()).or_default();
+            if *served >= retransmission {
+                continue;
             }
+            *served += 1;
+            client.publish(msg);
         }
     }
 
@@ -659,24 +771,161 @@ impl P2pNetworkPubsubState {
         ihave_messages: Vec<pb::ControlIHave>,
         timestamp: Timestamp,
     ) {
-        // Process ihave messages by determining which available messages the client wants.
+        if self.peer_score(peer_id) < self.config.peer_score.gossip_threshold {
+            return;
+        }
+
+        // Process ihave messages by determining which available messages the client wants,
+        // limiting how much we process and request from the peer per heartbeat.
         for ihave in ihave_messages {
-            if self.clients.contains_key(peer_id) {
-                let message_ids = ihave
-                    .message_ids
-                    .into_iter()
-                    .filter(|message_id| self.filter_iwant_message_ids(message_id, timestamp))
-                    .collect::<Vec<_>>();
+            let Some(client) = self.clients.get_mut(peer_id) else {
+                bug_condition!("process_ihave_messages: State not found for {}", peer_id);
+                return;
+            };
+            client.ihave_received += 1;
+            if client.ihave_received > self.config.max_ihave_messages {
+                return;
+            }
+            let limit = self
+                .config
+                .max_ihave_length
+                .saturating_sub(client.iwant_requested);
+
+            let message_ids = ihave
+                .message_ids
+                .into_iter()
+                .filter(|message_id| self.filter_iwant_message_ids(message_id, timestamp))
+                .take(limit)
+                .collect::<Vec<_>>();
+            if message_ids.is_empty() {
+                continue;
+            }
+
+            let Some(client) = self.clients.get_mut(peer_id) else {
+                bug_condition!("process_ihave_messages: State not found for {}", peer_id);
+                return;
+            };
+            client.iwant_requested += message_ids.len();
+
+            // Queue the desired message IDs for the client to request.
+            let ctr = client.message.control.get_or_insert_with(Default::default);
+            ctr.iwant.push(pb::ControlIWant { message_ids })
+        }
+    }
 
+    /// Advertises recently cached messages to peers outside of the mesh.
+    fn emit_gossip(&mut self, now: Timestamp) {
+        let gossip_threshold = self.config.peer_score.gossip_threshold;
+
+        for (topic_id, peers) in &self.topics {
+            let message_ids = self
+                .mcache
+                .gossip_ids(topic_id, self.config.mcache_history_gossip)
+                .into_iter()
+                .take(self.config.max_ihave_length)
+                .collect::<Vec<_>>();
+            if message_ids.is_empty() {
+                continue;
+            }
+
+            let candidates = peers
+                .iter()
+                .filter(|(peer_id, topic_state)| {
+                    !topic_state.on_mesh()
+                        && self
+                            .clients
+                            .get(peer_id)
+                            .map_or(false, |client| client.outgoing_stream_id.is_some())
+                        && self
+                            .scores
+                            .get(peer_id)
+                            .map_or(true, |score| score.score >= gossip_threshold)
+                })
+                .map(|(peer_id, _)| *peer_id)
+                .collect::<Vec<_>>();
+            if candidates.is_empty() {
+                continue;
+            }
+
+            // rotate the selected peers, so that all of them get the gossip eventually
+            let offset = now
+                .checked_sub(Timestamp::ZERO)
+                .map_or(0, |dur| dur.as_secs() as usize)
+                % candidates.len();
+            let selected = candidates
+                .iter()
+                .cycle()
+                .skip(offset)
+                .take(self.config.gossip_degree.min(candidates.len()));
+
+            for peer_id in selected {
                 let Some(client) = self.clients.get_mut(peer_id) else {
-                    bug_condition!("process_ihave_messages: State not found for {}", peer_id);
-                    return;
+                    continue;
                 };
-
-                // Queue the desired message IDs for the client to request.
                 let ctr = client.message.control.get_or_insert_with(Default::default);
-                ctr.iwant.push(pb::ControlIWant { message_ids })
+                ctr.ihave.push(pb::ControlIHave {
+                    topic_id: Some(topic_id.clone()),
+                    message_ids: message_ids.clone(),
+                });
+            }
+        }
+    }
+
+    /// Prunes mesh peers with negative score and keeps the mesh size between
+    /// the low and high degree, preferring peers with higher score.
+    fn maintain_mesh<Action, State>(
+        &self,
+        dispatcher: &mut Dispatcher<Action, State>,
+        topic_id: &str,
+        peers: &BTreeMap<PeerId, P2pNetworkPubsubClientTopicState>,
+    ) where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let config = &self.config;
+        let (mut mesh, mut prune): (Vec<_>, Vec<_>) = peers
+            .iter()
+            .filter(|(peer_id, topic_state)| {
+                topic_state.on_mesh() && self.clients.contains_key(peer_id)
+            })
+            .map(|(peer_id, _)| (*peer_id, self.peer_score(peer_id)))
+            .partition(|(_, score)| *score >= 0.0);
+
+        if mesh.len() < config.outbound_degree_low {
+            let mut candidates = peers
+                .iter()
+                .filter(|(peer_id, topic_state)| {
+                    matches!(
+                        topic_state.mesh,
+                        P2pNetworkPubsubClientMeshAddingState::Initial
+                            | P2pNetworkPubsubClientMeshAddingState::WeRefused
+                    ) && self
+                        .clients
+                        .get(peer_id)
+                        .map_or(false, |client| client.outgoing_stream_id.is_some())
+                })
+                .map(|(peer_id, _)| (*peer_id, self.peer_score(peer_id)))
+                .filter(|(_, score)| *score >= 0.0)
+                .collect::<Vec<_>>();
+            candidates.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+            let missing = config.outbound_degree_desired.saturating_sub(mesh.len());
+            for (peer_id, _) in candidates.into_iter().take(missing) {
+                dispatcher.push(P2pNetworkPubsubAction::Graft {
+                    peer_id,
+                    topic_id: topic_id.to_owned(),
+                });
             }
+        } else if mesh.len() > config.outbound_degree_high {
+            mesh.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+            prune.extend(mesh.drain(config.outbound_degree_desired..));
+        }
+
+        for (peer_id, _) in prune {
+            dispatcher.push(P2pNetworkPubsubAction::Prune {
+                peer_id,
+                topic_id: topic_id.to_owned(),
+            });
         }
     }
 
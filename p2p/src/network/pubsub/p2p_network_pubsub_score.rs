@@ -0,0 +1,213 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{P2pMeshsubPeerScoreConfig, P2pMeshsubTopicScoreConfig};
+
+/// Gossipsub v1.1 score of a peer.
+///
+/// Counters are updated as the peer's messages are processed, while the
+/// score itself is recomputed on every heartbeat.
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkPubsubPeerScore {
+    /// Score computed on the last heartbeat.
+    pub score: f64,
+    /// Counters for every scored topic.
+    pub topics: BTreeMap<String, P2pNetworkPubsubPeerTopicScore>,
+    /// Set once the peer is disconnected, the score is kept for
+    /// [`P2pMeshsubPeerScoreConfig::retain_score`] so that reconnecting doesn't reset it.
+    pub disconnected_at: Option<Timestamp>,
+}
+
+#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkPubsubPeerTopicScore {
+    /// When the peer joined the mesh, `None` while it isn't in the mesh.
+    pub grafted_at: Option<Timestamp>,
+    /// P2: messages this peer delivered to us first.
+    pub first_message_deliveries: f64,
+    /// P3: messages this peer delivered to us first while in the mesh.
+    pub mesh_message_deliveries: f64,
+    /// P3b: sticky penalty for leaving the mesh with a delivery deficit.
+    pub mesh_failure_penalty: f64,
+    /// P4: messages that failed validation.
+    pub invalid_message_deliveries: f64,
+}
+
+impl P2pNetworkPubsubPeerScore {
+    /// Recomputes [`Self::score`].
+    pub fn refresh(&mut self, config: &P2pMeshsubPeerScoreConfig, now: Timestamp) {
+        let score = self
+            .topics
+            .iter()
+            .filter_map(|(topic, score)| Some(score.score(config.topics.get(topic)?, now)))
+            .sum::<f64>();
+
+        self.score = if config.topic_score_cap > 0.0 {
+            score.min(config.topic_score_cap)
+        } else {
+            score
+        };
+    }
+
+    pub fn decay(&mut self, config: &P2pMeshsubPeerScoreConfig) {
+        for (topic, score) in &mut self.topics {
+            if let Some(topic_config) = config.topics.get(topic) {
+                score.decay(topic_config, config.decay_to_zero);
+            }
+        }
+    }
+
+    /// Leaves all meshes, applying mesh failure penalties.
+    pub fn on_disconnect(&mut self, config: &P2pMeshsubPeerScoreConfig, now: Timestamp) {
+        self.disconnected_at = Some(now);
+        for (topic, score) in &mut self.topics {
+            if let Some(topic_config) = config.topics.get(topic) {
+                score.on_prune(topic_config, now);
+            }
+        }
+    }
+}
+
+impl P2pNetworkPubsubPeerTopicScore {
+    fn time_in_mesh(&self, now: Timestamp) -> Option<Duration> {
+        now.checked_sub(self.grafted_at?)
+    }
+
+    fn mesh_message_deliveries_deficit(
+        &self,
+        config: &P2pMeshsubTopicScoreConfig,
+        now: Timestamp,
+    ) -> Option<f64> {
+        let time_in_mesh = self.time_in_mesh(now)?;
+        (time_in_mesh >= config.mesh_message_deliveries_activation
+            && self.mesh_message_deliveries < config.mesh_message_deliveries_threshold)
+            .then(|| config.mesh_message_deliveries_threshold - self.mesh_message_deliveries)
+    }
+
+    pub fn score(&self, config: &P2pMeshsubTopicScoreConfig, now: Timestamp) -> f64 {
+        let mut score = 0.0;
+
+        if let Some(time_in_mesh) = self.time_in_mesh(now) {
+            let quantum = config.time_in_mesh_quantum.as_secs_f64().max(f64::EPSILON);
+            let quanta = time_in_mesh.as_secs_f64() / quantum;
+            score += quanta.min(config.time_in_mesh_cap) * config.time_in_mesh_weight;
+        }
+
+        score += self
+            .first_message_deliveries
+            .min(config.first_message_deliveries_cap)
+            * config.first_message_deliveries_weight;
+
+        if let Some(deficit) = self.mesh_message_deliveries_deficit(config, now) {
+            score += deficit * deficit * config.mesh_message_deliveries_weight;
+        }
+
+        score += self.mesh_failure_penalty * config.mesh_failure_penalty_weight;
+
+        score += self.invalid_message_deliveries
+            * self.invalid_message_deliveries
+            * config.invalid_message_deliveries_weight;
+
+        score * config.topic_weight
+    }
+
+    pub fn decay(&mut self, config: &P2pMeshsubTopicScoreConfig, decay_to_zero: f64) {
+        let decay = |value: &mut f64, factor: f64| {
+            *value *= factor;
+            if *value < decay_to_zero {
+                *value = 0.0;
+            }
+        };
+
+        decay(
+            &mut self.first_message_deliveries,
+            config.first_message_deliveries_decay,
+        );
+        decay(
+            &mut self.mesh_message_deliveries,
+            config.mesh_message_deliveries_decay,
+        );
+        decay(
+            &mut self.mesh_failure_penalty,
+            config.mesh_failure_penalty_decay,
+        );
+        decay(
+            &mut self.invalid_message_deliveries,
+            config.invalid_message_deliveries_decay,
+        );
+    }
+
+    pub fn on_graft(&mut self, now: Timestamp) {
+        self.grafted_at.get_or_insert(now);
+    }
+
+    pub fn on_prune(&mut self, config: &P2pMeshsubTopicScoreConfig, now: Timestamp) {
+        if let Some(deficit) = self.mesh_message_deliveries_deficit(config, now) {
+            self.mesh_failure_penalty += deficit * deficit;
+        }
+        self.grafted_at = None;
+    }
+
+    pub fn on_first_delivery(&mut self, config: &P2pMeshsubTopicScoreConfig) {
+        self.first_message_deliveries =
+            (self.first_message_deliveries + 1.0).min(config.first_message_deliveries_cap);
+        if self.grafted_at.is_some() {
+            self.mesh_message_deliveries =
+                (self.mesh_message_deliveries + 1.0).min(config.mesh_message_deliveries_cap);
+        }
+    }
+
+    pub fn on_invalid_message(&mut self) {
+        self.invalid_message_deliveries += 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use redux::Timestamp;
+
+    use super::P2pNetworkPubsubPeerScore;
+    use crate::P2pMeshsubPeerScoreConfig;
+
+    #[test]
+    fn invalid_messages_reach_graylist() {
+        let config = P2pMeshsubPeerScoreConfig::default();
+        let (topic, topic_config) = config.topics.iter().next().unwrap();
+        let now = Timestamp::new(60_000_000_000);
+
+        let mut score = P2pNetworkPubsubPeerScore::default();
+        let topic_score = score.topics.entry(topic.clone()).or_default();
+        topic_score.on_graft(Timestamp::ZERO);
+        topic_score.on_first_delivery(topic_config);
+        score.refresh(&config, now);
+        assert!(score.score > 0.0);
+
+        for _ in 0..10 {
+            score
+                .topics
+                .values_mut()
+                .for_each(|s| s.on_invalid_message());
+        }
+        score.refresh(&config, now);
+        assert!(score.score < config.graylist_threshold);
+    }
+
+    #[test]
+    fn mesh_delivery_deficit_is_penalized_on_prune() {
+        let config = P2pMeshsubPeerScoreConfig::default();
+        let (topic, topic_config) = config.topics.iter().next().unwrap();
+        let activation = topic_config.mesh_message_deliveries_activation;
+        let now = Timestamp::new(activation.as_nanos() as u64);
+
+        let mut score = P2pNetworkPubsubPeerScore::default();
+        let topic_score = score.topics.entry(topic.clone()).or_default();
+        topic_score.on_graft(Timestamp::ZERO);
+        topic_score.on_prune(topic_config, now);
+        assert!(topic_score.grafted_at.is_none());
+        assert!(topic_score.mesh_failure_penalty > 0.0);
+
+        score.refresh(&config, now);
+        assert!(score.score < 0.0);
+    }
+}
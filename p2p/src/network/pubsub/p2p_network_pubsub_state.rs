@@ -1,5 +1,8 @@
-use super::pb;
-use crate::{token::BroadcastAlgorithm, ConnectionAddr, PeerId, StreamId};
+use super::{pb, P2pNetworkPubsubPeerScore, P2pNetworkPubsubPeerTopicScore};
+use crate::{
+    token::BroadcastAlgorithm, ConnectionAddr, P2pMeshsubConfig, P2pMeshsubTopicScoreConfig,
+    PeerId, StreamId,
+};
 
 use std::{
     collections::{BTreeMap, BTreeSet, VecDeque},
@@ -58,6 +61,15 @@ pub struct P2pNetworkPubsubState {
 
     /// `iwant` requests, tracking the number of times peers have expressed interest in specific messages.
     pub iwant: VecDeque<P2pNetworkPubsubIwantRequestCount>,
+
+    /// Meshsub parameters, copied from the config so that the reducer can use them.
+    pub config: P2pMeshsubConfig,
+
+    /// Scores of connected and recently disconnected peers.
+    pub scores: BTreeMap<PeerId, P2pNetworkPubsubPeerScore>,
+
+    /// Time of the last heartbeat.
+    pub last_heartbeat: Option<Timestamp>,
 }
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone)]
@@ -67,8 +79,67 @@ pub struct P2pNetworkPubsubIwantRequestCount {
 }
 
 impl P2pNetworkPubsubState {
+    pub fn new(config: P2pMeshsubConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
     pub fn prune_peer_state(&mut self, peer_id: &PeerId) {
         self.clients.remove(peer_id);
+        for peers in self.topics.values_mut() {
+            peers.remove(peer_id);
+        }
+    }
+
+    /// Score of the peer as of the last heartbeat, unknown peers are neutral.
+    pub fn peer_score(&self, peer_id: &PeerId) -> f64 {
+        self.scores.get(peer_id).map_or(0.0, |score| score.score)
+    }
+
+    pub fn is_graylisted(&self, peer_id: &PeerId) -> bool {
+        self.peer_score(peer_id) < self.config.peer_score.graylist_threshold
+    }
+
+    /// Updates the score counters of the peer for a scored topic.
+    pub fn update_topic_score<F>(&mut self, peer_id: &PeerId, topic_id: &str, f: F)
+    where
+        F: FnOnce(&mut P2pNetworkPubsubPeerTopicScore, &P2pMeshsubTopicScoreConfig),
+    {
+        let Some(config) = self.config.peer_score.topics.get(topic_id) else {
+            return;
+        };
+        let Some(score) = self.scores.get_mut(peer_id) else {
+            return;
+        };
+        f(score.topics.entry(topic_id.to_owned()).or_default(), config);
+    }
+
+    /// Decays and recomputes the scores, forgetting disconnected peers
+    /// once their score is no longer retained.
+    pub fn refresh_scores(&mut self, now: Timestamp) {
+        let config = &self.config.peer_score;
+        let clients = &self.clients;
+
+        self.scores.retain(|peer_id, score| {
+            if clients.contains_key(peer_id) {
+                score.disconnected_at = None;
+            } else if let Some(disconnected_at) = score.disconnected_at {
+                let expired = now
+                    .checked_sub(disconnected_at)
+                    .map_or(false, |dur| dur >= config.retain_score);
+                if expired {
+                    return false;
+                }
+            } else {
+                score.on_disconnect(config, now);
+            }
+
+            score.decay(config);
+            score.refresh(config, now);
+            true
+        });
     }
 
     pub fn filter_iwant_message_ids(&mut self, message_id: &Vec<u8>, timestamp: Timestamp) -> bool {
@@ -168,6 +239,15 @@ pub struct P2pNetworkPubsubClientState {
     /// Holds fully decoded `pb::Message` instances received from the peer,
     /// ready for further handling such as validation, caching, and broadcasting.
     pub incoming_messages: Vec<pb::Message>,
+
+    /// How many times each cached message was sent to the peer in response to IWANT.
+    pub iwant_served: BTreeMap<Vec<u8>, u8>,
+
+    /// Number of IHAVE messages received from the peer since the last heartbeat.
+    pub ihave_received: usize,
+
+    /// Number of message ids requested from the peer since the last heartbeat.
+    pub iwant_requested: usize,
 }
 
 impl P2pNetworkPubsubClientState {
@@ -196,6 +276,14 @@ impl P2pNetworkPubsubClientState {
         self.incoming_messages.clear();
         self.incoming_messages.shrink_to(0x20)
     }
+
+    /// Resets the per heartbeat gossip limits.
+    pub fn reset_gossip_limits(&mut self, mcache: &P2pNetworkPubsubMessageCache) {
+        self.ihave_received = 0;
+        self.iwant_requested = 0;
+        self.iwant_served
+            .retain(|id, _| mcache.map.contains_key(id));
+    }
 }
 
 #[derive(Default, Serialize, Deserialize, Debug, Clone)]
@@ -204,27 +292,55 @@ pub struct P2pNetworkPubsubRecentlyPublishCache {
     pub queue: VecDeque<Vec<u8>>,
 }
 
+/// Message cache, keeps messages for a number of heartbeats (history windows)
+/// so that they can be advertised with IHAVE and served on IWANT.
 // TODO: store blocks, snarks and txs separately
 #[derive(Default, Serialize, Deserialize, Debug, Clone)]
 pub struct P2pNetworkPubsubMessageCache {
     pub map: BTreeMap<Vec<u8>, pb::Message>,
-    pub queue: VecDeque<Vec<u8>>,
+    /// Message ids received during each heartbeat, the most recent first.
+    pub windows: VecDeque<Vec<Vec<u8>>>,
 }
 
 impl P2pNetworkPubsubMessageCache {
-    const CAPACITY: usize = 100;
-
     pub fn put(&mut self, message: pb::Message) -> Option<Vec<u8>> {
         let id = compute_message_id(&message)?;
-        self.map.insert(id.clone(), message);
-        self.queue.push_back(id.clone());
-        if self.queue.len() > Self::CAPACITY {
-            if let Some(id) = self.queue.pop_front() {
-                self.map.remove(&id);
+        if self.map.insert(id.clone(), message).is_none() {
+            if self.windows.is_empty() {
+                self.windows.push_front(vec![]);
+            }
+            if let Some(window) = self.windows.front_mut() {
+                window.push(id.clone());
             }
         }
         Some(id)
     }
+
+    /// Opens a new window, forgetting messages older than `history_length` windows.
+    pub fn shift(&mut self, history_length: usize) {
+        self.windows.push_front(vec![]);
+        while self.windows.len() > history_length.max(1) {
+            for id in self.windows.pop_back().into_iter().flatten() {
+                self.map.remove(&id);
+            }
+        }
+    }
+
+    /// Ids of the messages on the topic from the last `history_gossip` windows,
+    /// the most recent first.
+    pub fn gossip_ids(&self, topic: &str, history_gossip: usize) -> Vec<Vec<u8>> {
+        self.windows
+            .iter()
+            .take(history_gossip)
+            .flat_map(|window| window.iter().rev())
+            .filter(|id| {
+                self.map
+                    .get(*id)
+                    .map_or(false, |message| message.topic == topic)
+            })
+            .cloned()
+            .collect()
+    }
 }
 
 // TODO: what if wasm32?
@@ -277,3 +393,39 @@ impl P2pNetworkPubsubClientTopicState {
         matches!(&self.mesh, P2pNetworkPubsubClientMeshAddingState::Added)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{pb, P2pNetworkPubsubMessageCache};
+
+    fn message(seqno: u64, topic: &str) -> pb::Message {
+        pb::Message {
+            from: None,
+            data: None,
+            seqno: Some(seqno.to_be_bytes().to_vec()),
+            topic: topic.to_owned(),
+            signature: None,
+            key: None,
+        }
+    }
+
+    #[test]
+    fn message_cache_windows() {
+        let mut mcache = P2pNetworkPubsubMessageCache::default();
+        let first = mcache.put(message(1, "a")).unwrap();
+        mcache.shift(3);
+        let second = mcache.put(message(2, "a")).unwrap();
+        mcache.put(message(3, "b")).unwrap();
+
+        assert_eq!(mcache.gossip_ids("a", 1), vec![second.clone()]);
+        assert_eq!(
+            mcache.gossip_ids("a", 2),
+            vec![second.clone(), first.clone()]
+        );
+
+        mcache.shift(3);
+        mcache.shift(3);
+        assert!(!mcache.map.contains_key(&first));
+        assert!(mcache.map.contains_key(&second));
+    }
+}
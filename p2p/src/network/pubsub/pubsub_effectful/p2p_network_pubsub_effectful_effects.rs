@@ -2,9 +2,7 @@ use libp2p_identity::{DecodingError, PublicKey};
 
 use super::super::pb;
 
-use crate::{
-    P2pCryptoService, P2pNetworkConnectionError, P2pNetworkPubsubAction, P2pNetworkSchedulerAction,
-};
+use crate::{P2pCryptoService, P2pNetworkPubsubAction};
 
 use super::P2pNetworkPubsubEffectfulAction;
 
@@ -45,21 +43,22 @@ impl P2pNetworkPubsubEffectfulAction {
             P2pNetworkPubsubEffectfulAction::ValidateIncomingMessages {
                 peer_id,
                 seen_limit,
-                addr,
                 messages,
+                ..
             } => {
                 let mut valid_messages = Vec::with_capacity(messages.len());
 
                 for message in messages {
+                    let topic_id = message.topic.clone();
                     match validate_message(message, store) {
                         Ok(valid_msg) => valid_messages.push(valid_msg),
                         Err(error) => {
-                            store.dispatch(P2pNetworkSchedulerAction::Error {
-                                addr,
-                                error: P2pNetworkConnectionError::PubSubError(error.to_string()),
+                            // the peer score takes care of disconnecting misbehaving peers
+                            store.dispatch(P2pNetworkPubsubAction::RejectMessage {
+                                peer_id,
+                                topic_id,
+                                reason: error.to_string(),
                             });
-
-                            return; // Early exit, no need to process the rest
                         }
                     }
                 }
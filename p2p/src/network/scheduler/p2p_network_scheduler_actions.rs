@@ -11,7 +11,10 @@ use super::{
     p2p_network_scheduler_state::{P2pNetworkConnectionCloseReason, P2pNetworkConnectionError},
 };
 
-use crate::{disconnection::P2pDisconnectionReason, ConnectionAddr, P2pState, PeerId, StreamId};
+use crate::{
+    connection::outgoing::Libp2pTransport, disconnection::P2pDisconnectionReason, ConnectionAddr,
+    P2pLimits, P2pState, PeerId, StreamId,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 #[action_event(fields(display(ip), display(listener), display(addr), debug(result), select_kind = debug(kind), display(error)))]
@@ -43,6 +46,7 @@ pub enum P2pNetworkSchedulerAction {
     /// Initialize outgoing connection.
     OutgoingConnect {
         addr: SocketAddr,
+        transport: Libp2pTransport,
     },
     /// Outgoint TCP stream is established.
     OutgoingDidConnect {
@@ -70,6 +74,22 @@ pub enum P2pNetworkSchedulerAction {
         peer_id: PeerId,
         message_size_limit: Limit<usize>,
         pending_outgoing_limit: Limit<usize>,
+        backpressure_threshold: Limit<usize>,
+        stream_window: u32,
+        max_stream_window: u32,
+    },
+    /// QUIC handshake is finished, the remote peer is authenticated by TLS
+    /// and the connection is ready to open streams.
+    QuicDidConnect {
+        addr: ConnectionAddr,
+        result: Result<PeerId, String>,
+    },
+    /// Data received in the QUIC stream.
+    QuicIncomingData {
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+        data: Data,
+        fin: bool,
     },
 
     /// Action that initiate the specified peer disconnection.
@@ -116,6 +136,25 @@ impl From<P2pNetworkSchedulerAction> for crate::P2pAction {
     }
 }
 
+impl P2pNetworkSchedulerAction {
+    /// Creates [`P2pNetworkSchedulerAction::YamuxDidInit`] with yamux limits taken from the config.
+    pub fn yamux_did_init(limits: &P2pLimits, addr: ConnectionAddr, peer_id: PeerId) -> Self {
+        let window = |limit: Limit<usize>| match limit {
+            Limit::Some(window) => u32::try_from(window).unwrap_or(u32::MAX),
+            Limit::Unlimited => u32::MAX,
+        };
+        P2pNetworkSchedulerAction::YamuxDidInit {
+            addr,
+            peer_id,
+            message_size_limit: limits.yamux_message_size(),
+            pending_outgoing_limit: limits.yamux_pending_outgoing_per_peer(),
+            backpressure_threshold: limits.yamux_backpressure_threshold(),
+            stream_window: window(limits.yamux_stream_window()),
+            max_stream_window: window(limits.yamux_max_stream_window()),
+        }
+    }
+}
+
 impl redux::EnablingCondition<P2pState> for P2pNetworkSchedulerAction {
     fn is_enabled(&self, state: &P2pState, _time: redux::Timestamp) -> bool {
         match self {
@@ -131,7 +170,7 @@ impl redux::EnablingCondition<P2pState> for P2pNetworkSchedulerAction {
                     !state.network.scheduler.connections.contains_key(addr)
                 })
             }
-            P2pNetworkSchedulerAction::OutgoingConnect { addr } => state
+            P2pNetworkSchedulerAction::OutgoingConnect { addr, .. } => state
                 .network
                 .scheduler
                 .connections
@@ -145,12 +184,30 @@ impl redux::EnablingCondition<P2pState> for P2pNetworkSchedulerAction {
                 .scheduler
                 .connections
                 .get(addr)
-                .map_or(false, |conn_state| !conn_state.incoming),
+                .map_or(false, |conn_state| {
+                    !conn_state.incoming && !conn_state.is_quic()
+                }),
             P2pNetworkSchedulerAction::IncomingDataDidReceive { addr, .. }
             | P2pNetworkSchedulerAction::IncomingDataIsReady { addr }
             | P2pNetworkSchedulerAction::YamuxDidInit { addr, .. } => {
                 state.network.scheduler.connections.contains_key(addr)
             }
+            P2pNetworkSchedulerAction::QuicDidConnect { addr, .. } => {
+                match state.network.scheduler.connections.get(addr) {
+                    Some(conn_state) => {
+                        !addr.incoming && conn_state.is_quic() && conn_state.auth.is_none()
+                    }
+                    None => addr.incoming,
+                }
+            }
+            P2pNetworkSchedulerAction::QuicIncomingData { addr, .. } => state
+                .network
+                .scheduler
+                .connections
+                .get(addr)
+                .map_or(false, |conn_state| {
+                    conn_state.is_quic() && conn_state.mux.is_some() && conn_state.closed.is_none()
+                }),
             P2pNetworkSchedulerAction::Disconnect { addr, .. }
             | P2pNetworkSchedulerAction::Error { addr, .. } => state
                 .network
@@ -11,11 +11,13 @@ use token::{
 
 use crate::{
     connection::{
-        incoming::P2pConnectionIncomingAction, outgoing::P2pConnectionOutgoingAction,
+        incoming::P2pConnectionIncomingAction,
+        outgoing::{Libp2pTransport, P2pConnectionOutgoingAction},
         P2pConnectionState,
     },
-    disconnection::P2pDisconnectionAction,
+    disconnection::{P2pDisconnectionAction, P2pDisconnectionReason},
     identify::P2pIdentifyAction,
+    webrtc::RejectionReason,
     P2pConfig, P2pPeerStatus, P2pState, PeerId,
 };
 
@@ -44,6 +46,12 @@ impl P2pNetworkSchedulerState {
                     dispatcher
                         .push(P2pNetworkSchedulerEffectfulAction::InterfaceDetected { ip, port });
                 }
+                if let Some(port) = p2p_config.libp2p_quic_port {
+                    dispatcher.push(P2pNetworkSchedulerEffectfulAction::QuicInterfaceDetected {
+                        ip,
+                        port,
+                    });
+                }
 
                 Ok(())
             }
@@ -85,6 +93,7 @@ impl P2pNetworkSchedulerState {
                         addr,
                         P2pNetworkConnectionState {
                             incoming: true,
+                            transport: Libp2pTransport::Tcp,
                             pnet: P2pNetworkPnetState::new(scheduler_state.pnet_key, meta.time()),
                             select_auth: P2pNetworkSelectState::default(),
                             auth: None,
@@ -107,7 +116,21 @@ impl P2pNetworkSchedulerState {
 
                 Ok(())
             }
-            P2pNetworkSchedulerAction::OutgoingConnect { addr } => {
+            P2pNetworkSchedulerAction::OutgoingConnect { addr, transport } => {
+                let (select_auth, select_mux) = match transport {
+                    Libp2pTransport::Tcp => (
+                        P2pNetworkSelectState::initiator_auth(token::AuthKind::Noise, meta.time()),
+                        P2pNetworkSelectState::initiator_mux(
+                            token::MuxKind::Yamux1_0_0,
+                            meta.time(),
+                        ),
+                    ),
+                    // QUIC negotiates security and multiplexing by itself
+                    Libp2pTransport::Quic => (
+                        P2pNetworkSelectState::default(),
+                        P2pNetworkSelectState::default(),
+                    ),
+                };
                 scheduler_state.connections.insert(
                     ConnectionAddr {
                         sock_addr: addr,
@@ -115,16 +138,11 @@ impl P2pNetworkSchedulerState {
                     },
                     P2pNetworkConnectionState {
                         incoming: false,
+                        transport,
                         pnet: P2pNetworkPnetState::new(scheduler_state.pnet_key, meta.time()),
-                        select_auth: P2pNetworkSelectState::initiator_auth(
-                            token::AuthKind::Noise,
-                            meta.time(),
-                        ),
+                        select_auth,
                         auth: None,
-                        select_mux: P2pNetworkSelectState::initiator_mux(
-                            token::MuxKind::Yamux1_0_0,
-                            meta.time(),
-                        ),
+                        select_mux,
                         mux: None,
                         streams: BTreeMap::default(),
                         closed: None,
@@ -133,7 +151,8 @@ impl P2pNetworkSchedulerState {
                 );
 
                 let dispatcher = state_context.into_dispatcher();
-                dispatcher.push(P2pNetworkSchedulerEffectfulAction::OutgoingConnect { addr });
+                dispatcher
+                    .push(P2pNetworkSchedulerEffectfulAction::OutgoingConnect { addr, transport });
                 Ok(())
             }
             P2pNetworkSchedulerAction::OutgoingDidConnect { addr, result } => {
@@ -253,6 +272,9 @@ impl P2pNetworkSchedulerState {
                 peer_id,
                 message_size_limit,
                 pending_outgoing_limit,
+                backpressure_threshold,
+                stream_window,
+                max_stream_window,
             } => {
                 let Some(cn) = scheduler_state.connections.get_mut(&addr) else {
                     bug_condition!(
@@ -264,6 +286,9 @@ impl P2pNetworkSchedulerState {
                     yamux.init = true;
                     yamux.message_size_limit = message_size_limit;
                     yamux.pending_outgoing_limit = pending_outgoing_limit;
+                    yamux.backpressure_threshold = backpressure_threshold;
+                    yamux.stream_window = stream_window;
+                    yamux.max_stream_window = max_stream_window;
                 }
 
                 let incoming = cn.incoming;
@@ -281,6 +306,148 @@ impl P2pNetworkSchedulerState {
                 dispatcher.push(P2pIdentifyAction::NewRequest { peer_id, addr });
                 Ok(())
             }
+            P2pNetworkSchedulerAction::QuicDidConnect { addr, result } => {
+                let peer_id = match result {
+                    Ok(peer_id) => peer_id,
+                    Err(error) => {
+                        // failed incoming handshakes never reach the state
+                        if !addr.incoming {
+                            let dispatcher = state_context.into_dispatcher();
+                            dispatcher.push(P2pNetworkSchedulerAction::Error {
+                                addr,
+                                error: P2pNetworkConnectionError::Quic(error),
+                            });
+                        }
+                        return Ok(());
+                    }
+                };
+
+                let connection = scheduler_state.connections.entry(addr).or_insert_with(|| {
+                    P2pNetworkConnectionState {
+                        incoming: true,
+                        transport: Libp2pTransport::Quic,
+                        pnet: P2pNetworkPnetState::new(scheduler_state.pnet_key, meta.time()),
+                        select_auth: P2pNetworkSelectState::default(),
+                        auth: None,
+                        select_mux: P2pNetworkSelectState::default(),
+                        mux: None,
+                        streams: BTreeMap::default(),
+                        closed: None,
+                        limit: P2pNetworkConnectionState::INITIAL_LIMIT,
+                    }
+                });
+                connection.auth = Some(P2pNetworkAuthState::Tls(peer_id));
+                connection.mux = Some(P2pNetworkConnectionMuxState::Yamux(
+                    P2pNetworkYamuxState::default(),
+                ));
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+
+                if addr.incoming {
+                    if p2p_state.network.scheduler.connections.len()
+                        > p2p_state.config.limits.max_connections()
+                    {
+                        dispatcher.push(P2pNetworkSchedulerAction::Disconnect {
+                            addr,
+                            reason: P2pDisconnectionReason::Libp2pIncomingRejected(
+                                RejectionReason::PeerCapacityFull,
+                            ),
+                        });
+                        return Ok(());
+                    }
+                    dispatcher.push(P2pConnectionIncomingAction::FinalizePendingLibp2p {
+                        peer_id,
+                        addr: addr.sock_addr,
+                    });
+                } else if let Some((expected_peer_id, _)) = p2p_state.peer_with_connection(addr) {
+                    if expected_peer_id != peer_id {
+                        dispatcher.push(P2pNetworkSchedulerAction::Error {
+                            addr,
+                            error: P2pNetworkConnectionError::Quic(format!(
+                                "expected peer {expected_peer_id}, got {peer_id}"
+                            )),
+                        });
+                        return Ok(());
+                    }
+                }
+
+                dispatcher.push(P2pNetworkSchedulerAction::yamux_did_init(
+                    &p2p_state.config.limits,
+                    addr,
+                    peer_id,
+                ));
+                Ok(())
+            }
+            P2pNetworkSchedulerAction::QuicIncomingData {
+                addr,
+                stream_id,
+                data,
+                fin,
+            } => {
+                let Some(connection) = scheduler_state.connections.get_mut(&addr) else {
+                    bug_condition!(
+                        "Missing connection state for `P2pNetworkSchedulerAction::QuicIncomingData`"
+                    );
+                    return Ok(());
+                };
+                let Some(peer_id) = connection.peer_id().copied() else {
+                    bug_condition!("QUIC connection {addr} is not authenticated");
+                    return Ok(());
+                };
+                let Some(yamux_state) = connection.yamux_state_mut() else {
+                    bug_condition!("QUIC connection {addr} has no stream table");
+                    return Ok(());
+                };
+
+                let new_stream = !yamux_state.streams.contains_key(&stream_id);
+                if new_stream {
+                    let stream = yamux_state.new_stream(true);
+                    yamux_state.streams.insert(stream_id, stream);
+                    connection
+                        .streams
+                        .insert(stream_id, P2pNetworkStreamState::new_incoming(meta.time()));
+                }
+                let incoming_streams = connection
+                    .streams
+                    .values()
+                    .filter(|s| s.select.is_incoming())
+                    .count();
+
+                let (dispatcher, state) = state_context.into_dispatcher_and_state();
+                let p2p_state: &P2pState = state.substate()?;
+
+                if new_stream {
+                    match p2p_state.config.limits.max_streams() {
+                        Limit::Some(limit) if incoming_streams > limit => {
+                            // same as yamux, finish the stream immediately
+                            dispatcher.push(P2pNetworkSchedulerEffectfulAction::QuicSend {
+                                addr,
+                                stream_id,
+                                data: Data::empty(),
+                                fin: true,
+                            });
+                        }
+                        _ => {
+                            dispatcher.push(P2pNetworkSelectAction::Init {
+                                addr,
+                                kind: SelectKind::Stream(peer_id, stream_id),
+                                incoming: true,
+                            });
+                        }
+                    }
+                }
+                if !data.is_empty() || fin {
+                    dispatcher.push(P2pNetworkSelectAction::IncomingData {
+                        addr,
+                        peer_id,
+                        stream_id,
+                        data,
+                        fin,
+                    });
+                }
+                Ok(())
+            }
             P2pNetworkSchedulerAction::Disconnect { addr, reason } => {
                 let Some(conn_state) = scheduler_state.connections.get_mut(&addr) else {
                     bug_condition!(
@@ -519,15 +686,11 @@ impl P2pNetworkSchedulerState {
                     bug_condition!("wrong kind for multiplexing protocol action: {select_kind:?}");
                     return;
                 };
-                let message_size_limit = p2p_state.config.limits.yamux_message_size();
-                let pending_outgoing_limit =
-                    p2p_state.config.limits.yamux_pending_outgoing_per_peer();
-                dispatcher.push(P2pNetworkSchedulerAction::YamuxDidInit {
+                dispatcher.push(P2pNetworkSchedulerAction::yamux_did_init(
+                    &p2p_state.config.limits,
                     addr,
                     peer_id,
-                    message_size_limit,
-                    pending_outgoing_limit,
-                });
+                ));
             }
             Some(Protocol::Stream(kind)) => {
                 let SelectKind::Stream(peer_id, stream_id) = select_kind else {
@@ -535,12 +698,34 @@ impl P2pNetworkSchedulerState {
                     return;
                 };
                 match kind {
-                    StreamKind::Status(_)
-                    | StreamKind::Identify(IdentifyAlgorithm::IdentifyPush1_0_0)
-                    | StreamKind::Bitswap(_)
-                    | StreamKind::Ping(PingAlgorithm::Ping1_0_0) => {
+                    StreamKind::Identify(IdentifyAlgorithm::IdentifyPush1_0_0) => {
                         //unimplemented!()
                     }
+                    StreamKind::Ping(PingAlgorithm::Ping1_0_0) => {
+                        dispatcher.push(P2pNetworkPingAction::NewStream {
+                            incoming,
+                            peer_id,
+                            addr,
+                            stream_id,
+                        });
+                    }
+                    StreamKind::Status(_) => {
+                        dispatcher.push(P2pNetworkNodeStatusAction::NewStream {
+                            incoming,
+                            peer_id,
+                            addr,
+                            stream_id,
+                        });
+                    }
+                    StreamKind::Bitswap(protocol) => {
+                        dispatcher.push(P2pNetworkBitswapAction::NewStream {
+                            incoming,
+                            peer_id,
+                            addr,
+                            stream_id,
+                            protocol,
+                        });
+                    }
                     StreamKind::Identify(IdentifyAlgorithm::Identify1_0_0) => {
                         dispatcher.push(P2pNetworkIdentifyStreamAction::New {
                             addr,
@@ -6,7 +6,10 @@ use std::{
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
-use crate::{disconnection::P2pDisconnectionReason, identity::PublicKey, PeerId};
+use crate::{
+    connection::outgoing::Libp2pTransport, disconnection::P2pDisconnectionReason,
+    identity::PublicKey, PeerId,
+};
 
 use super::super::*;
 
@@ -34,6 +37,9 @@ pub struct P2pNetworkSchedulerState {
     pub connections: BTreeMap<ConnectionAddr, P2pNetworkConnectionState>,
     pub broadcast_state: P2pNetworkPubsubState,
     pub identify_state: identify::P2pNetworkIdentifyState,
+    pub bitswap_state: P2pNetworkBitswapState,
+    pub node_status_state: P2pNetworkNodeStatusState,
+    pub ping_state: P2pNetworkPingState,
     pub discovery_state: Option<P2pNetworkKadState>,
     pub rpc_incoming_streams: StreamState<P2pNetworkRpcState>,
     pub rpc_outgoing_streams: StreamState<P2pNetworkRpcState>,
@@ -56,6 +62,9 @@ impl P2pNetworkSchedulerState {
     pub fn prune_peer_state(&mut self, peer_id: &PeerId) {
         self.broadcast_state.prune_peer_state(peer_id);
         self.identify_state.prune_peer_state(peer_id);
+        self.bitswap_state.prune_peer_state(peer_id);
+        self.node_status_state.prune_peer_state(peer_id);
+        self.ping_state.prune_peer_state(peer_id);
 
         if let Some(discovery_state) = self.discovery_state.as_mut() {
             discovery_state.streams.remove(peer_id);
@@ -80,6 +89,10 @@ impl P2pNetworkSchedulerState {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct P2pNetworkConnectionState {
     pub incoming: bool,
+    /// For QUIC connections pnet, select and noise are not used, the
+    /// yamux state only serves as a stream table.
+    #[serde(default)]
+    pub transport: Libp2pTransport,
     pub pnet: P2pNetworkPnetState,
     pub select_auth: P2pNetworkSelectState,
     pub auth: Option<P2pNetworkAuthState>,
@@ -98,6 +111,10 @@ impl P2pNetworkConnectionState {
         self.auth.as_ref().and_then(P2pNetworkAuthState::peer_id)
     }
 
+    pub fn is_quic(&self) -> bool {
+        self.transport.is_quic()
+    }
+
     pub fn limit(&self) -> usize {
         if let Some(mux) = &self.mux {
             mux.limit()
@@ -115,15 +132,17 @@ impl P2pNetworkConnectionState {
     }
 
     pub fn noise_state(&self) -> Option<&P2pNetworkNoiseState> {
-        self.auth
-            .as_ref()
-            .map(|P2pNetworkAuthState::Noise(state)| state)
+        match self.auth.as_ref()? {
+            P2pNetworkAuthState::Noise(state) => Some(state),
+            P2pNetworkAuthState::Tls(_) => None,
+        }
     }
 
     pub fn noise_state_mut(&mut self) -> Option<&mut P2pNetworkNoiseState> {
-        self.auth
-            .as_mut()
-            .map(|P2pNetworkAuthState::Noise(state)| state)
+        match self.auth.as_mut()? {
+            P2pNetworkAuthState::Noise(state) => Some(state),
+            P2pNetworkAuthState::Tls(_) => None,
+        }
     }
 
     pub fn yamux_state_mut(&mut self) -> Option<&mut P2pNetworkYamuxState> {
@@ -196,21 +215,28 @@ pub enum P2pNetworkConnectionError {
     StreamReset(StreamId),
     #[error("pubsub error: {0}")]
     PubSubError(String),
+    #[error(transparent)]
+    BitswapStreamError(#[from] P2pNetworkBitswapStreamError),
     #[error("peer make us keep too much data at stream {0}")]
     YamuxOverflow(StreamId),
     #[error("peer should not decrease window size at stream {0}")]
     YamuxBadWindowUpdate(StreamId),
+    #[error("quic error: {0}")]
+    Quic(String),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum P2pNetworkAuthState {
     Noise(P2pNetworkNoiseState),
+    /// Peer authenticated by the QUIC TLS handshake.
+    Tls(PeerId),
 }
 
 impl P2pNetworkAuthState {
-    fn peer_id(&self) -> Option<&PeerId> {
+    pub fn peer_id(&self) -> Option<&PeerId> {
         match self {
             P2pNetworkAuthState::Noise(v) => v.peer_id(),
+            P2pNetworkAuthState::Tls(peer_id) => Some(peer_id),
         }
     }
 }
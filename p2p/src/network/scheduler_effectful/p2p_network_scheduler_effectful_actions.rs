@@ -3,7 +3,10 @@ use std::net::{IpAddr, SocketAddr};
 use openmina_core::ActionEvent;
 use serde::{Deserialize, Serialize};
 
-use crate::{ConnectionAddr, P2pNetworkConnectionCloseReason, P2pState};
+use crate::{
+    connection::outgoing::Libp2pTransport, ConnectionAddr, Data, P2pNetworkConnectionCloseReason,
+    P2pState, StreamId,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
 #[action_event(fields(display(ip), display(listener), display(addr), debug(result), select_kind = debug(kind), display(error)))]
@@ -12,6 +15,10 @@ pub enum P2pNetworkSchedulerEffectfulAction {
         ip: IpAddr,
         port: u16,
     },
+    QuicInterfaceDetected {
+        ip: IpAddr,
+        port: u16,
+    },
     IncomingConnectionIsReady {
         listener: SocketAddr,
         should_accept: bool,
@@ -24,6 +31,7 @@ pub enum P2pNetworkSchedulerEffectfulAction {
     /// Initialize outgoing connection.
     OutgoingConnect {
         addr: SocketAddr,
+        transport: Libp2pTransport,
     },
     /// Outgoing TCP stream is established.
     OutgoingDidConnect {
@@ -37,6 +45,13 @@ pub enum P2pNetworkSchedulerEffectfulAction {
         addr: ConnectionAddr,
         incoming: bool,
     },
+    /// Send the data in the QUIC stream.
+    QuicSend {
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+        data: Data,
+        fin: bool,
+    },
     /// Action that initiate the specified peer disconnection.
     Disconnect {
         /// Connection address.
@@ -1,7 +1,7 @@
 use redux::ActionMeta;
 use std::net::SocketAddr;
 
-use crate::{MioCmd, P2pCryptoService, P2pMioService};
+use crate::{connection::outgoing::Libp2pTransport, MioCmd, P2pCryptoService, P2pMioService};
 
 use super::{super::*, *};
 
@@ -17,6 +17,11 @@ impl P2pNetworkSchedulerEffectfulAction {
                     .service()
                     .send_mio_cmd(MioCmd::ListenOn(SocketAddr::new(ip, port)));
             }
+            P2pNetworkSchedulerEffectfulAction::QuicInterfaceDetected { ip, port } => {
+                store
+                    .service()
+                    .send_mio_cmd(MioCmd::QuicListenOn(SocketAddr::new(ip, port)));
+            }
             P2pNetworkSchedulerEffectfulAction::IncomingConnectionIsReady {
                 listener,
                 should_accept,
@@ -35,8 +40,12 @@ impl P2pNetworkSchedulerEffectfulAction {
                     incoming: true,
                 });
             }
-            P2pNetworkSchedulerEffectfulAction::OutgoingConnect { addr } => {
-                store.service().send_mio_cmd(MioCmd::Connect(addr));
+            P2pNetworkSchedulerEffectfulAction::OutgoingConnect { addr, transport } => {
+                let cmd = match transport {
+                    Libp2pTransport::Tcp => MioCmd::Connect(addr),
+                    Libp2pTransport::Quic => MioCmd::QuicConnect(addr),
+                };
+                store.service().send_mio_cmd(cmd);
             }
             P2pNetworkSchedulerEffectfulAction::OutgoingDidConnect { addr } => {
                 let nonce = store.service().generate_random_nonce();
@@ -64,6 +73,16 @@ impl P2pNetworkSchedulerEffectfulAction {
                     signature,
                 });
             }
+            P2pNetworkSchedulerEffectfulAction::QuicSend {
+                addr,
+                stream_id,
+                data,
+                fin,
+            } => {
+                store
+                    .service()
+                    .send_mio_cmd(MioCmd::QuicSend(addr, stream_id, data.0, fin));
+            }
             P2pNetworkSchedulerEffectfulAction::Disconnect { addr, reason } => {
                 store.service().send_mio_cmd(MioCmd::Disconnect(addr));
                 store.dispatch(P2pNetworkSchedulerAction::Disconnected { addr, reason });
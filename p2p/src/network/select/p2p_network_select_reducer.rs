@@ -8,8 +8,9 @@ use token::{
 use crate::{
     fuzzer::{mutate_select_authentication, mutate_select_multiplexing, mutate_select_stream},
     network::identify::P2pNetworkIdentifyStreamAction,
-    ConnectionAddr, Data, P2pNetworkKademliaStreamAction, P2pNetworkNoiseAction,
-    P2pNetworkPnetAction, P2pNetworkPubsubAction, P2pNetworkRpcAction, P2pNetworkSchedulerAction,
+    ConnectionAddr, Data, P2pNetworkBitswapAction, P2pNetworkKademliaStreamAction,
+    P2pNetworkNodeStatusAction, P2pNetworkNoiseAction, P2pNetworkPingAction, P2pNetworkPnetAction,
+    P2pNetworkPubsubAction, P2pNetworkRpcAction, P2pNetworkSchedulerAction,
     P2pNetworkSchedulerState, P2pNetworkYamuxAction, P2pState, YamuxFlags,
 };
 
@@ -490,6 +491,57 @@ impl P2pNetworkSelectState {
                             data,
                         });
                     }
+                    StreamKind::Bitswap(_) => {
+                        if !data.is_empty() {
+                            dispatcher.push(P2pNetworkBitswapAction::IncomingData {
+                                addr,
+                                peer_id,
+                                stream_id,
+                                data,
+                            });
+                        }
+                        if fin {
+                            dispatcher.push(P2pNetworkBitswapAction::RemoteClose {
+                                addr,
+                                peer_id,
+                                stream_id,
+                            });
+                        }
+                    }
+                    StreamKind::Status(_) => {
+                        if !data.is_empty() {
+                            dispatcher.push(P2pNetworkNodeStatusAction::IncomingData {
+                                addr,
+                                peer_id,
+                                stream_id,
+                                data,
+                            });
+                        }
+                        if fin {
+                            dispatcher.push(P2pNetworkNodeStatusAction::RemoteClose {
+                                addr,
+                                peer_id,
+                                stream_id,
+                            });
+                        }
+                    }
+                    StreamKind::Ping(_) => {
+                        if !data.is_empty() {
+                            dispatcher.push(P2pNetworkPingAction::IncomingData {
+                                addr,
+                                peer_id,
+                                stream_id,
+                                data,
+                            });
+                        }
+                        if fin {
+                            dispatcher.push(P2pNetworkPingAction::RemoteClose {
+                                addr,
+                                peer_id,
+                                stream_id,
+                            });
+                        }
+                    }
                     _ => error!(time;
                         "trying to negotiate unimplemented stream kind {kind:?}"
                     ),
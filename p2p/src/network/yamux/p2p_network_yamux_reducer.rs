@@ -35,6 +35,8 @@ impl P2pNetworkYamuxState {
             .connection_state_mut(action.addr())
             .ok_or_else(|| format!("Connection not found for action: {action:?}"))
             .inspect_err(|e| bug_condition!("{}", e))?;
+        // QUIC streams are multiplexed by the transport, yamux state only tracks them
+        let quic = connection_state.is_quic();
 
         let P2pNetworkConnectionMuxState::Yamux(yamux_state) = connection_state
             .mux
@@ -162,6 +164,17 @@ impl P2pNetworkYamuxState {
                     .get(&stream_id)
                     .ok_or_else(|| format!("Stream with id {stream_id} not found for `P2pNetworkYamuxAction::OutgoingData`"))?;
 
+                if quic {
+                    let dispatcher = state_context.into_dispatcher();
+                    dispatcher.push(P2pNetworkSchedulerEffectfulAction::QuicSend {
+                        addr,
+                        stream_id,
+                        data,
+                        fin: flags.intersects(YamuxFlags::FIN | YamuxFlags::RST),
+                    });
+                    return Ok(());
+                }
+
                 if !stream_state.incoming && !stream_state.established && !stream_state.syn_sent {
                     flags.insert(YamuxFlags::SYN);
                 } else if stream_state.incoming && !stream_state.established {
@@ -183,11 +196,12 @@ impl P2pNetworkYamuxState {
             }
             P2pNetworkYamuxAction::IncomingFrame { addr, frame } => {
                 let mut pending_outgoing = VecDeque::default();
+                let mut window_updates = Vec::new();
+                let backpressured = yamux_state.is_backpressured();
                 if let Some(frame) = yamux_state.incoming.pop_front() {
                     if frame.flags.contains(YamuxFlags::SYN) {
-                        yamux_state
-                            .streams
-                            .insert(frame.stream_id, YamuxStreamState::incoming());
+                        let stream = yamux_state.new_stream(true);
+                        yamux_state.streams.insert(frame.stream_id, stream);
 
                         if frame.stream_id != 0 {
                             connection_state.streams.insert(
@@ -197,27 +211,38 @@ impl P2pNetworkYamuxState {
                         }
                     }
                     if frame.flags.contains(YamuxFlags::ACK) {
+                        let stream = yamux_state.new_stream(false);
                         yamux_state
                             .streams
                             .entry(frame.stream_id)
-                            .or_default()
+                            .or_insert(stream)
                             .established = true;
                     }
 
                     match frame.inner {
                         YamuxFrameInner::Data(data) => {
+                            let max_window = yamux_state.max_stream_window;
                             if let Some(stream) = yamux_state.streams.get_mut(&frame.stream_id) {
-                                // must not underflow
-                                // TODO: check it and disconnect peer that violates flow rules
+                                // TODO: disconnect peer that violates flow rules
                                 stream.window_ours =
-                                    stream.window_ours.wrapping_sub(data.len() as u32);
+                                    stream.window_ours.saturating_sub(data.len() as u32);
+                                // while the peer doesn't accept our data,
+                                // it doesn't get more window to send us requests
+                                if !backpressured {
+                                    if let Some(difference) =
+                                        stream.window_update(meta.time(), max_window)
+                                    {
+                                        window_updates.push((frame.stream_id, difference));
+                                    }
+                                }
                             }
                         }
                         YamuxFrameInner::WindowUpdate { difference } => {
+                            let new_stream = yamux_state.new_stream(true);
                             let stream = yamux_state
                                 .streams
                                 .entry(frame.stream_id)
-                                .or_insert_with(YamuxStreamState::incoming);
+                                .or_insert(new_stream);
                             stream.update_window(false, difference);
                             if difference > 0 {
                                 // have some fresh space in the window
@@ -240,6 +265,19 @@ impl P2pNetworkYamuxState {
                                     }
                                 }
                             }
+
+                            // the frames are about to be sent, so if they were
+                            // holding back the peer, resume extending its windows
+                            if backpressured && !yamux_state.is_backpressured() {
+                                let max_window = yamux_state.max_stream_window;
+                                for (stream_id, stream) in &mut yamux_state.streams {
+                                    if let Some(difference) =
+                                        stream.window_update(meta.time(), max_window)
+                                    {
+                                        window_updates.push((*stream_id, difference));
+                                    }
+                                }
+                            }
                         }
                         YamuxFrameInner::Ping { .. } => {}
                         YamuxFrameInner::GoAway(res) => yamux_state.set_res(res),
@@ -254,16 +292,12 @@ impl P2pNetworkYamuxState {
                         .connection_state(&addr)
                         .ok_or_else(|| format!("Connection not found {}", addr))?;
 
-                let stream = connection_state
+                connection_state
                     .yamux_state()
                     .and_then(|yamux_state| yamux_state.streams.get(&frame.stream_id))
                     .ok_or_else(|| format!("Stream with id {} not found for `P2pNetworkYamuxAction::IncomingFrame`", frame.stream_id))?;
 
-                let peer_id = match connection_state
-                    .auth
-                    .as_ref()
-                    .and_then(|P2pNetworkAuthState::Noise(noise)| noise.peer_id())
-                {
+                let peer_id = match connection_state.peer_id() {
                     Some(peer_id) => *peer_id,
                     None => return Ok(()),
                 };
@@ -304,23 +338,21 @@ impl P2pNetworkYamuxState {
                         }
                     }
                 }
+                for (stream_id, difference) in window_updates {
+                    dispatcher.push(P2pNetworkYamuxAction::OutgoingFrame {
+                        addr,
+                        frame: YamuxFrame {
+                            stream_id,
+                            flags: YamuxFlags::empty(),
+                            inner: YamuxFrameInner::WindowUpdate {
+                                difference: difference as i32,
+                            },
+                        },
+                    });
+                }
+
                 match &frame.inner {
                     YamuxFrameInner::Data(data) => {
-                        // here we are very permissive
-                        // always when our window is smaller 64 kb, just increase it by 256 kb
-                        // if we need fine grained back pressure, it should be implemented here
-                        if stream.window_ours < 64 * 1024 {
-                            let difference = 256 * 1024;
-                            dispatcher.push(P2pNetworkYamuxAction::OutgoingFrame {
-                                addr,
-                                frame: YamuxFrame {
-                                    stream_id: frame.stream_id,
-                                    flags: YamuxFlags::empty(),
-                                    inner: YamuxFrameInner::WindowUpdate { difference },
-                                },
-                            });
-                        }
-
                         dispatcher.push(P2pNetworkSelectAction::IncomingData {
                             addr,
                             peer_id,
@@ -388,10 +420,11 @@ impl P2pNetworkYamuxState {
                             // either the window cannot accept any byte,
                             // or the queue is already not empty
                             // in both cases the whole frame goes in the queue and nothing to send
+                            // the peer stops getting window for new requests once
+                            // `backpressure_threshold` is reached, the connection is
+                            // only dropped if it still manages to pile up too much
                             stream.pending.push_back(frame);
-                            if stream.pending.iter().map(YamuxFrame::len).sum::<usize>()
-                                > yamux_state.pending_outgoing_limit
-                            {
+                            if stream.pending_len() > yamux_state.pending_outgoing_limit {
                                 let dispatcher = state_context.into_dispatcher();
                                 let error = P2pNetworkConnectionError::YamuxOverflow(stream_id);
                                 dispatcher.push(P2pNetworkSchedulerAction::Error { addr, error });
@@ -427,6 +460,10 @@ impl P2pNetworkYamuxState {
                 Ok(())
             }
             P2pNetworkYamuxAction::PingStream { addr, ping } => {
+                if quic {
+                    // QUIC keeps the connection alive by itself
+                    return Ok(());
+                }
                 let dispatcher = state_context.into_dispatcher();
                 dispatcher.push(P2pNetworkYamuxAction::OutgoingFrame {
                     addr,
@@ -440,19 +477,14 @@ impl P2pNetworkYamuxState {
                 stream_kind,
                 addr,
             } => {
-                yamux_state
-                    .streams
-                    .insert(stream_id, YamuxStreamState::default());
+                let stream = yamux_state.new_stream(false);
+                yamux_state.streams.insert(stream_id, stream);
                 connection_state.streams.insert(
                     stream_id,
                     P2pNetworkStreamState::new(stream_kind, meta.time()),
                 );
 
-                let peer_id = match connection_state
-                    .auth
-                    .as_ref()
-                    .and_then(|P2pNetworkAuthState::Noise(noise)| noise.peer_id())
-                {
+                let peer_id = match connection_state.peer_id() {
                     Some(peer_id) => *peer_id,
                     None => return Ok(()),
                 };
@@ -485,7 +517,7 @@ impl YamuxStreamState {
             }
         } else {
             let increasing = difference as u32;
-            *window = (*window).wrapping_add(increasing);
+            *window = (*window).saturating_add(increasing);
         }
     }
 }
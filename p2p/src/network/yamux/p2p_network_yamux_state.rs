@@ -1,5 +1,9 @@
-use std::collections::{BTreeMap, VecDeque};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::Duration,
+};
 
+use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use super::super::*;
@@ -8,6 +12,13 @@ use super::super::*;
 pub struct P2pNetworkYamuxState {
     pub message_size_limit: Limit<usize>,
     pub pending_outgoing_limit: Limit<usize>,
+    /// Above this amount of pending outgoing data we stop extending the peer's
+    /// windows, so it can't send us more requests until the data is drained.
+    pub backpressure_threshold: Limit<usize>,
+    /// Receive window a new stream starts growing from.
+    pub stream_window: u32,
+    /// Receive window a stream can grow up to.
+    pub max_stream_window: u32,
     pub buffer: Vec<u8>,
     pub incoming: VecDeque<YamuxFrame>,
     pub streams: BTreeMap<StreamId, YamuxStreamState>,
@@ -26,6 +37,52 @@ impl P2pNetworkYamuxState {
         }
     }
 
+    /// Creates a stream with the configured receive window.
+    pub fn new_stream(&self, incoming: bool) -> YamuxStreamState {
+        YamuxStreamState {
+            incoming,
+            window_target: self.stream_window.max(YamuxStreamState::INITIAL_WINDOW),
+            ..Default::default()
+        }
+    }
+
+    /// Amount of outgoing data waiting for the peer to extend its windows.
+    pub fn pending_outgoing(&self) -> usize {
+        self.streams
+            .values()
+            .map(YamuxStreamState::pending_len)
+            .sum()
+    }
+
+    /// Whether the peer is too slow in accepting our data, so we shouldn't
+    /// accept more data from it.
+    pub fn is_backpressured(&self) -> bool {
+        self.pending_outgoing() > self.backpressure_threshold
+    }
+
+    pub fn stats(&self) -> P2pNetworkYamuxStats {
+        P2pNetworkYamuxStats {
+            buffer_len: self.buffer.len(),
+            incoming_frames: self.incoming.len(),
+            pending_outgoing: self.pending_outgoing(),
+            backpressured: self.is_backpressured(),
+            streams: self
+                .streams
+                .iter()
+                .map(|(stream_id, stream)| YamuxStreamStats {
+                    stream_id: *stream_id,
+                    incoming: stream.incoming,
+                    established: stream.established,
+                    window_ours: stream.window_ours,
+                    window_theirs: stream.window_theirs,
+                    window_target: stream.window_target,
+                    pending_frames: stream.pending.len(),
+                    pending_len: stream.pending_len(),
+                })
+                .collect(),
+        }
+    }
+
     pub fn consume(&mut self, len: usize) {
         // does not need to do anything;
         // we will update the stream window later when we process the `IncomingData' action
@@ -55,6 +112,10 @@ pub struct YamuxStreamState {
     pub writable: bool,
     pub window_theirs: u32,
     pub window_ours: u32,
+    /// Size our receive window is replenished to, grows when the peer
+    /// keeps draining it quickly.
+    pub window_target: u32,
+    pub window_updated_at: Option<Timestamp>,
     pub pending: VecDeque<YamuxFrame>,
 }
 
@@ -66,20 +127,79 @@ impl Default for YamuxStreamState {
             established: false,
             readable: false,
             writable: false,
-            window_theirs: 256 * 1024,
-            window_ours: 256 * 1024,
+            window_theirs: Self::INITIAL_WINDOW,
+            window_ours: Self::INITIAL_WINDOW,
+            window_target: Self::INITIAL_WINDOW,
+            window_updated_at: None,
             pending: VecDeque::default(),
         }
     }
 }
 
 impl YamuxStreamState {
+    /// Window size every stream starts with, as defined by the spec.
+    pub const INITIAL_WINDOW: u32 = 256 * 1024;
+
+    /// If the window is replenished again within this interval, the peer is
+    /// limited by the window rather than by its bandwidth, so it is grown.
+    const WINDOW_GROWTH_INTERVAL: Duration = Duration::from_secs(1);
+
     pub fn incoming() -> Self {
         YamuxStreamState {
             incoming: true,
             ..Default::default()
         }
     }
+
+    pub fn pending_len(&self) -> usize {
+        self.pending.iter().map(YamuxFrame::len).sum()
+    }
+
+    /// Returns the window increment to send to the peer, if our receive
+    /// window has dropped to half of its target size.
+    pub fn window_update(&mut self, now: Timestamp, max_window: u32) -> Option<u32> {
+        if self.window_ours > self.window_target / 2 {
+            return None;
+        }
+        let drained_quickly = self
+            .window_updated_at
+            .and_then(|time| now.checked_sub(time))
+            .is_some_and(|elapsed| elapsed < Self::WINDOW_GROWTH_INTERVAL);
+        if drained_quickly {
+            self.window_target = self
+                .window_target
+                .saturating_mul(2)
+                .min(max_window.max(self.window_target));
+        }
+        self.window_updated_at = Some(now);
+        Some(
+            self.window_target
+                .saturating_sub(self.window_ours)
+                .min(i32::MAX as u32),
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pNetworkYamuxStats {
+    /// Received bytes that don't make a complete frame yet.
+    pub buffer_len: usize,
+    pub incoming_frames: usize,
+    pub pending_outgoing: usize,
+    pub backpressured: bool,
+    pub streams: Vec<YamuxStreamStats>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct YamuxStreamStats {
+    pub stream_id: StreamId,
+    pub incoming: bool,
+    pub established: bool,
+    pub window_ours: u32,
+    pub window_theirs: u32,
+    pub window_target: u32,
+    pub pending_frames: usize,
+    pub pending_len: usize,
 }
 
 bitflags::bitflags! {
@@ -244,6 +364,9 @@ pub enum YamuxStreamKind {
     Gossipsub,
     Kademlia,
     Identify,
+    Bitswap,
+    NodeStatus,
+    Ping,
 }
 
 impl YamuxStreamKind {
@@ -254,6 +377,48 @@ impl YamuxStreamKind {
 
 #[cfg(test)]
 mod tests {
+    use redux::Timestamp;
+
+    use super::YamuxStreamState;
+
+    #[test]
+    fn yamux_window_growth() {
+        const MAX: u32 = 4 * YamuxStreamState::INITIAL_WINDOW;
+        let mut stream = YamuxStreamState::default();
+        let now = Timestamp::new(1_000_000_000);
+
+        assert_eq!(stream.window_update(now, MAX), None);
+
+        stream.window_ours = 1000;
+        let update = stream.window_update(now, MAX).unwrap();
+        assert_eq!(update, YamuxStreamState::INITIAL_WINDOW - 1000);
+        stream.window_ours += update;
+
+        // drained again right away, the window doubles
+        stream.window_ours = 0;
+        let now = Timestamp::new(1_100_000_000);
+        assert_eq!(
+            stream.window_update(now, MAX),
+            Some(2 * YamuxStreamState::INITIAL_WINDOW)
+        );
+
+        // slow peer, the window stays the same
+        stream.window_ours = 0;
+        let now = Timestamp::new(10_000_000_000);
+        assert_eq!(
+            stream.window_update(now, MAX),
+            Some(2 * YamuxStreamState::INITIAL_WINDOW)
+        );
+
+        // never exceeds the maximum
+        for i in 1..10 {
+            stream.window_ours = 0;
+            let now = Timestamp::new(10_000_000_000 + i * 1_000_000);
+            assert!(stream.window_update(now, MAX).unwrap() <= MAX);
+        }
+        assert_eq!(stream.window_target, MAX);
+    }
+
     #[test]
     fn yamux_stream_id() {
         use super::YamuxStreamKind::*;
@@ -261,5 +426,11 @@ mod tests {
         assert_eq!(Rpc.stream_id(true), 2);
         assert_eq!(Kademlia.stream_id(false), 5);
         assert_eq!(Kademlia.stream_id(true), 6);
+        assert_eq!(Bitswap.stream_id(false), 9);
+        assert_eq!(Bitswap.stream_id(true), 10);
+        assert_eq!(NodeStatus.stream_id(false), 11);
+        assert_eq!(NodeStatus.stream_id(true), 12);
+        assert_eq!(Ping.stream_id(false), 13);
+        assert_eq!(Ping.stream_id(true), 14);
     }
 }
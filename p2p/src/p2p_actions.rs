@@ -2,17 +2,21 @@ use openmina_macros::ActionEvent;
 use redux::EnablingCondition;
 use serde::{Deserialize, Serialize};
 
+use crate::ban_effectful::P2pBanEffectfulAction;
 use crate::channels::P2pChannelsEffectfulAction;
 use crate::connection::P2pConnectionEffectfulAction;
 use crate::disconnection_effectful::P2pDisconnectionEffectfulAction;
+use crate::peer_store_effectful::P2pPeerStoreEffectfulAction;
 use crate::P2pNetworkEffectfulAction;
 
+use super::ban::P2pBanAction;
 use super::channels::P2pChannelsAction;
 use super::connection::P2pConnectionAction;
 use super::disconnection::P2pDisconnectionAction;
 use super::identify::P2pIdentifyAction;
 use super::network::P2pNetworkAction;
 use super::peer::P2pPeerAction;
+use super::peer_store::P2pPeerStoreAction;
 use super::P2pState;
 
 #[derive(Serialize, Deserialize, Debug, Clone, derive_more::From, ActionEvent)]
@@ -20,6 +24,8 @@ pub enum P2pAction {
     Initialization(P2pInitializeAction),
     Connection(P2pConnectionAction),
     Disconnection(P2pDisconnectionAction),
+    Ban(P2pBanAction),
+    PeerStore(P2pPeerStoreAction),
     Identify(P2pIdentifyAction),
     Channels(P2pChannelsAction),
     Peer(P2pPeerAction),
@@ -32,6 +38,8 @@ pub enum P2pEffectfulAction {
     Channels(P2pChannelsEffectfulAction),
     Connection(P2pConnectionEffectfulAction),
     Disconnection(P2pDisconnectionEffectfulAction),
+    Ban(P2pBanEffectfulAction),
+    PeerStore(P2pPeerStoreEffectfulAction),
     Network(P2pNetworkEffectfulAction),
 }
 
@@ -56,6 +64,8 @@ impl redux::EnablingCondition<crate::P2pState> for P2pAction {
             P2pAction::Initialization(a) => a.is_enabled(state, time),
             P2pAction::Connection(a) => a.is_enabled(state, time),
             P2pAction::Disconnection(a) => a.is_enabled(state, time),
+            P2pAction::Ban(a) => a.is_enabled(state, time),
+            P2pAction::PeerStore(a) => a.is_enabled(state, time),
             P2pAction::Channels(a) => a.is_enabled(state, time),
             P2pAction::Peer(a) => a.is_enabled(state, time),
             P2pAction::Identify(a) => a.is_enabled(state, time),
@@ -70,6 +80,8 @@ impl redux::EnablingCondition<crate::P2pState> for P2pEffectfulAction {
             P2pEffectfulAction::Channels(a) => a.is_enabled(state, time),
             P2pEffectfulAction::Connection(a) => a.is_enabled(state, time),
             P2pEffectfulAction::Disconnection(a) => a.is_enabled(state, time),
+            P2pEffectfulAction::Ban(a) => a.is_enabled(state, time),
+            P2pEffectfulAction::PeerStore(a) => a.is_enabled(state, time),
             P2pEffectfulAction::Network(a) => a.is_enabled(state, time),
             P2pEffectfulAction::Initialize => true,
         }
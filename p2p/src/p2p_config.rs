@@ -1,9 +1,15 @@
-use std::{collections::BTreeSet, net::IpAddr, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::IpAddr,
+    time::Duration,
+};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    channels::ChannelId, connection::outgoing::P2pConnectionOutgoingInitOpts, identity::PublicKey,
+    ban::P2pBan, channels::ChannelId, connection::outgoing::P2pConnectionOutgoingInitOpts,
+    disconnection::P2pDisconnectionReason, identity::PublicKey, peer_store::P2pPeerStoreEntry,
+    PeerId,
 };
 
 pub const DEVNET_SEEDS: &[&str] = &[
@@ -16,6 +22,8 @@ pub const DEVNET_SEEDS: &[&str] = &[
 pub struct P2pConfig {
     /// TCP port where libp2p is listening incoming connections.
     pub libp2p_port: Option<u16>,
+    /// UDP port where libp2p is listening incoming QUIC connections.
+    pub libp2p_quic_port: Option<u16>,
     /// The HTTP port where signaling server is listening SDP offers and SDP answers.
     pub listen_port: Option<u16>,
     /// The public key used for authentication all p2p communication.
@@ -35,6 +43,10 @@ pub struct P2pConfig {
     pub peer_discovery: bool,
 
     pub meshsub: P2pMeshsubConfig,
+
+    pub ban: P2pBanConfig,
+
+    pub peer_store: P2pPeerStoreConfig,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -45,7 +57,26 @@ pub struct P2pMeshsubConfig {
     pub outbound_degree_desired: usize,
     pub outbound_degree_low: usize,
     pub outbound_degree_high: usize,
+    /// Number of recently seen messages remembered to drop duplicates.
     pub mcache_len: usize,
+
+    /// How often the mesh is maintained, gossip is emitted and peer scores are decayed.
+    pub heartbeat_interval: Duration,
+    /// Number of heartbeats a message is kept in the message cache for IWANT.
+    pub mcache_history_length: usize,
+    /// Number of most recent heartbeats whose messages are advertised with IHAVE.
+    pub mcache_history_gossip: usize,
+    /// Number of peers outside the mesh that receive IHAVE on every heartbeat.
+    pub gossip_degree: usize,
+    /// Maximum number of message ids advertised in an IHAVE,
+    /// and requested from a single peer per heartbeat.
+    pub max_ihave_length: usize,
+    /// Maximum number of IHAVE messages processed from a single peer per heartbeat.
+    pub max_ihave_messages: usize,
+    /// How many times a message is sent to the same peer in response to IWANT.
+    pub gossip_retransmission: u8,
+
+    pub peer_score: P2pMeshsubPeerScoreConfig,
 }
 
 impl Default for P2pMeshsubConfig {
@@ -56,6 +87,111 @@ impl Default for P2pMeshsubConfig {
             outbound_degree_low: 4,
             outbound_degree_high: 12,
             mcache_len: 256,
+            heartbeat_interval: Duration::from_secs(1),
+            mcache_history_length: 5,
+            mcache_history_gossip: 3,
+            gossip_degree: 6,
+            max_ihave_length: 5000,
+            max_ihave_messages: 10,
+            gossip_retransmission: 3,
+            peer_score: Default::default(),
+        }
+    }
+}
+
+/// Gossipsub v1.1 peer scoring parameters.
+///
+/// See <https://github.com/libp2p/specs/blob/master/pubsub/gossipsub/gossipsub-v1.1.md#peer-scoring>.
+/// Counters are decayed once per heartbeat.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pMeshsubPeerScoreConfig {
+    /// Parameters of the scored topics, other topics don't contribute to the score.
+    pub topics: BTreeMap<String, P2pMeshsubTopicScoreConfig>,
+    /// Upper bound for the positive part of the score, `0.0` means no cap.
+    pub topic_score_cap: f64,
+    /// Counters that decay below this value are reset to zero.
+    pub decay_to_zero: f64,
+    /// How long the score of a disconnected peer is kept.
+    pub retain_score: Duration,
+
+    /// Peers below this score don't receive gossip from us.
+    pub gossip_threshold: f64,
+    /// Peers below this score don't receive messages we publish.
+    pub publish_threshold: f64,
+    /// Messages of peers below this score are ignored and the peer gets disconnected.
+    pub graylist_threshold: f64,
+}
+
+impl Default for P2pMeshsubPeerScoreConfig {
+    fn default() -> Self {
+        Self {
+            topics: [(
+                crate::network::pubsub::TOPIC.to_owned(),
+                P2pMeshsubTopicScoreConfig::default(),
+            )]
+            .into(),
+            topic_score_cap: 100.0,
+            decay_to_zero: 0.01,
+            retain_score: Duration::from_secs(5 * 60),
+            gossip_threshold: -2000.0,
+            publish_threshold: -4000.0,
+            graylist_threshold: -8000.0,
+        }
+    }
+}
+
+/// Scoring parameters of a single topic, decays are applied per heartbeat.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct P2pMeshsubTopicScoreConfig {
+    pub topic_weight: f64,
+
+    /// P1: time in mesh.
+    pub time_in_mesh_weight: f64,
+    pub time_in_mesh_quantum: Duration,
+    pub time_in_mesh_cap: f64,
+
+    /// P2: first message deliveries.
+    pub first_message_deliveries_weight: f64,
+    pub first_message_deliveries_decay: f64,
+    pub first_message_deliveries_cap: f64,
+
+    /// P3: mesh message delivery rate, the weight must be negative.
+    pub mesh_message_deliveries_weight: f64,
+    pub mesh_message_deliveries_decay: f64,
+    pub mesh_message_deliveries_threshold: f64,
+    pub mesh_message_deliveries_cap: f64,
+    /// Time in mesh before the delivery rate is taken into account.
+    pub mesh_message_deliveries_activation: Duration,
+
+    /// P3b: penalty for leaving the mesh with a delivery deficit, the weight must be negative.
+    pub mesh_failure_penalty_weight: f64,
+    pub mesh_failure_penalty_decay: f64,
+
+    /// P4: invalid messages, the weight must be negative.
+    pub invalid_message_deliveries_weight: f64,
+    pub invalid_message_deliveries_decay: f64,
+}
+
+impl Default for P2pMeshsubTopicScoreConfig {
+    fn default() -> Self {
+        Self {
+            topic_weight: 1.0,
+            time_in_mesh_weight: 0.01,
+            time_in_mesh_quantum: Duration::from_secs(1),
+            time_in_mesh_cap: 3600.0,
+            first_message_deliveries_weight: 1.0,
+            first_message_deliveries_decay: 0.9,
+            first_message_deliveries_cap: 20.0,
+            // blocks are rare, so the threshold is low and the activation is long
+            mesh_message_deliveries_weight: -1.0,
+            mesh_message_deliveries_decay: 0.99,
+            mesh_message_deliveries_threshold: 1.0,
+            mesh_message_deliveries_cap: 100.0,
+            mesh_message_deliveries_activation: Duration::from_secs(5 * 60),
+            mesh_failure_penalty_weight: -1.0,
+            mesh_failure_penalty_decay: 0.99,
+            invalid_message_deliveries_weight: -100.0,
+            invalid_message_deliveries_decay: 0.99,
         }
     }
 }
@@ -78,6 +214,8 @@ pub struct P2pTimeouts {
     pub kademlia_initial_bootstrap: Option<Duration>,
     pub select: Option<Duration>,
     pub pnet: Option<Duration>,
+    pub node_status: Option<Duration>,
+    pub ping: Option<Duration>,
 }
 
 fn from_env_or(name: &str, default: Option<Duration>) -> Option<Duration> {
@@ -134,6 +272,95 @@ impl Default for P2pTimeouts {
             ),
             select: from_env_or("SELECT_TIMEOUT", Some(Duration::from_secs(5))),
             pnet: from_env_or("PNET_TIMEOUT", Some(Duration::from_secs(2))),
+            node_status: from_env_or("NODE_STATUS_TIMEOUT", Some(Duration::from_secs(10))),
+            ping: from_env_or("PING_TIMEOUT", Some(Duration::from_secs(20))),
+        }
+    }
+}
+
+/// For how long a peer is banned after being disconnected for misbehaving.
+///
+/// `None` means that the peer isn't banned for that reason.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pBanConfig {
+    /// Bans loaded from the persisted ban list.
+    pub initial_bans: BTreeMap<PeerId, P2pBan>,
+    /// Peer sent a message that isn't expected on a channel.
+    pub protocol_violation: Option<Duration>,
+    /// Peer sent data that failed verification.
+    pub invalid_data: Option<Duration>,
+    /// Peer's pubsub score dropped below the graylist threshold.
+    pub pubsub_graylisted: Option<Duration>,
+    /// Peer stopped answering pings.
+    pub unresponsive: Option<Duration>,
+}
+
+impl Default for P2pBanConfig {
+    fn default() -> Self {
+        Self {
+            initial_bans: Default::default(),
+            protocol_violation: from_env_or(
+                "BAN_PROTOCOL_VIOLATION_DURATION",
+                Some(Duration::from_secs(60 * 60)),
+            ),
+            invalid_data: from_env_or(
+                "BAN_INVALID_DATA_DURATION",
+                Some(Duration::from_secs(24 * 60 * 60)),
+            ),
+            pubsub_graylisted: from_env_or(
+                "BAN_PUBSUB_GRAYLISTED_DURATION",
+                Some(Duration::from_secs(60 * 60)),
+            ),
+            unresponsive: from_env_or("BAN_UNRESPONSIVE_DURATION", None),
+        }
+    }
+}
+
+impl P2pBanConfig {
+    /// For how long the peer disconnected with the `reason` should be banned.
+    pub fn duration(&self, reason: &P2pDisconnectionReason) -> Option<Duration> {
+        match reason {
+            P2pDisconnectionReason::P2pChannelMsgUnexpected(_) => self.protocol_violation,
+            P2pDisconnectionReason::SnarkPoolVerifyError
+            | P2pDisconnectionReason::TransitionFrontierSyncLedgerSnarkedNumAccountsRejected => {
+                self.invalid_data
+            }
+            P2pDisconnectionReason::PubsubGraylisted => self.pubsub_graylisted,
+            P2pDisconnectionReason::PingTimeout => self.unresponsive,
+            P2pDisconnectionReason::FreeUpSpace
+            | P2pDisconnectionReason::P2pChannelSendFailed(_)
+            | P2pDisconnectionReason::P2pChannelReceiveFailed(_)
+            | P2pDisconnectionReason::P2pChannelClosed(_)
+            | P2pDisconnectionReason::Libp2pIncomingRejected(_)
+            | P2pDisconnectionReason::TransitionFrontierRpcTimeout(_)
+            | P2pDisconnectionReason::TransitionFrontierStreamingRpcTimeout(_)
+            | P2pDisconnectionReason::DuplicateConnection
+            | P2pDisconnectionReason::Timeout
+            | P2pDisconnectionReason::Unsupported
+            | P2pDisconnectionReason::Banned => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pPeerStoreConfig {
+    /// Peers loaded from the persisted peer store, dialed before seeds.
+    pub initial_peers: BTreeMap<PeerId, P2pPeerStoreEntry>,
+    /// How often known good peers are persisted.
+    pub save_interval: Option<Duration>,
+    /// Maximum number of peers that are persisted.
+    pub max_peers: usize,
+}
+
+impl Default for P2pPeerStoreConfig {
+    fn default() -> Self {
+        Self {
+            initial_peers: Default::default(),
+            save_interval: from_env_or(
+                "PEER_STORE_SAVE_INTERVAL",
+                Some(Duration::from_secs(5 * 60)),
+            ),
+            max_peers: 100,
         }
     }
 }
@@ -253,6 +480,9 @@ pub struct P2pLimits {
     max_streams: Limit<usize>,
     yamux_message_size: Limit<usize>,
     yamux_pending_outgoing_per_peer: Limit<usize>,
+    yamux_backpressure_threshold: Limit<usize>,
+    yamux_stream_window: Limit<usize>,
+    yamux_max_stream_window: Limit<usize>,
 
     identify_message: Limit<usize>,
     kademlia_request: Limit<usize>,
@@ -265,6 +495,10 @@ pub struct P2pLimits {
     rpc_get_staged_ledger: Limit<usize>,
     rpc_get_transition_chain: Limit<usize>,
     rpc_get_some_initial_peers: Limit<usize>,
+
+    bitswap_message: Limit<usize>,
+    bitswap_resource: Limit<usize>,
+    bitswap_resources: Limit<usize>,
 }
 
 macro_rules! limit {
@@ -326,6 +560,25 @@ impl P2pLimits {
         /// Sets the maximum number of streams that a peer is allowed to open simultaneously.
         with_yamux_pending_outgoing_per_peer
     );
+    limit!(
+        /// Amount of pending outgoing data on a connection, above which the peer
+        /// doesn't get more window until it accepts the data.
+        yamux_backpressure_threshold,
+        /// Sets the amount of pending outgoing data that pauses reading from a peer.
+        with_yamux_backpressure_threshold
+    );
+    limit!(
+        /// Initial receive window of a yamux stream.
+        yamux_stream_window,
+        /// Sets the initial receive window of a yamux stream.
+        with_yamux_stream_window
+    );
+    limit!(
+        /// Maximum receive window a yamux stream can grow to.
+        yamux_max_stream_window,
+        /// Sets the maximum receive window a yamux stream can grow to.
+        with_yamux_max_stream_window
+    );
 
     limit!(
         /// Minimum number of peers.
@@ -383,6 +636,23 @@ impl P2pLimits {
         #[doc = "RPC some_initial_peers"]
         rpc_get_some_initial_peers
     );
+
+    limit!(
+        /// Maximum length of bitswap message.
+        bitswap_message
+    );
+    limit!(
+        /// Maximum size of a resource fetched using bitswap.
+        bitswap_resource,
+        /// Sets the maximum size of a resource fetched using bitswap.
+        with_bitswap_resource
+    );
+    limit!(
+        /// Maximum number of complete bitswap resources kept, the oldest ones are removed first.
+        bitswap_resources,
+        /// Sets the maximum number of complete bitswap resources kept.
+        with_bitswap_resources
+    );
 }
 
 impl Default for P2pLimits {
@@ -393,6 +663,11 @@ impl Default for P2pLimits {
         let max_streams = Limit::Some(10);
         // 256 MiB
         let yamux_message_size = Limit::Some(0x10000000);
+        // 16 MiB
+        let yamux_backpressure_threshold = Limit::Some(0x1000000);
+        // 256 KiB, as defined by the spec, and 16 MiB
+        let yamux_stream_window = Limit::Some(0x40000);
+        let yamux_max_stream_window = Limit::Some(0x1000000);
 
         let identify_message = Limit::Some(0x1000);
         let kademlia_request = Limit::Some(50);
@@ -406,6 +681,11 @@ impl Default for P2pLimits {
         let rpc_get_transition_chain = Limit::Some(3_500_000); // 2979112 as observed
         let rpc_get_some_initial_peers = Limit::Some(32_000); // TODO: calculate
 
+        // 4 MiB, enough for a few blocks of the maximal size
+        let bitswap_message = Limit::Some(0x400000);
+        let bitswap_resource = yamux_message_size;
+        let bitswap_resources = Limit::Some(32);
+
         Self {
             max_peers,
             min_peers_in_state,
@@ -413,6 +693,9 @@ impl Default for P2pLimits {
             max_streams,
             yamux_message_size,
             yamux_pending_outgoing_per_peer: rpc_get_staged_ledger,
+            yamux_backpressure_threshold,
+            yamux_stream_window,
+            yamux_max_stream_window,
 
             identify_message,
             kademlia_request,
@@ -425,6 +708,10 @@ impl Default for P2pLimits {
             rpc_get_staged_ledger,
             rpc_get_transition_chain,
             rpc_get_some_initial_peers,
+
+            bitswap_message,
+            bitswap_resource,
+            bitswap_resources,
         }
     }
 }
@@ -15,6 +15,8 @@ impl P2pEffectfulAction {
                 P2pConnectionEffectfulAction::Incoming(action) => action.effects(&meta, store),
             },
             P2pEffectfulAction::Disconnection(action) => action.effects(&meta, store),
+            P2pEffectfulAction::Ban(action) => action.effects(&meta, store),
+            P2pEffectfulAction::PeerStore(action) => action.effects(&meta, store),
             #[cfg(feature = "p2p-libp2p")]
             P2pEffectfulAction::Network(action) => action.effects(&meta, store),
             #[cfg(not(feature = "p2p-libp2p"))]
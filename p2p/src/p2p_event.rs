@@ -8,12 +8,12 @@ use crate::channels::signaling::discovery::SignalingDiscoveryChannelMsg;
 use crate::channels::signaling::exchange::SignalingExchangeChannelMsg;
 use crate::channels::streaming_rpc::StreamingRpcChannelMsg;
 use crate::webrtc::ConnectionAuthEncrypted;
-use crate::ConnectionAddr;
 use crate::{
     channels::{transaction::TransactionPropagationChannelMsg, ChannelId, ChannelMsg, MsgId},
     connection::P2pConnectionResponse,
     PeerId,
 };
+use crate::{ConnectionAddr, StreamId};
 
 #[derive(Serialize, Deserialize, From, Debug, Clone)]
 pub enum P2pEvent {
@@ -54,6 +54,13 @@ pub enum MioEvent {
 
     /// The remote peer is disconnected by our node.
     ConnectionDidCloseOnDemand(ConnectionAddr),
+
+    /// QUIC handshake with the remote peer is finished,
+    /// the remote peer id is taken from its TLS certificate.
+    QuicConnectionDidConnect(ConnectionAddr, Result<PeerId, String>),
+    /// We received the data from the QUIC stream, the flag is set
+    /// if the remote peer finished (or reset) the stream.
+    QuicStreamDataDidReceive(ConnectionAddr, StreamId, crate::Data, bool),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -299,6 +306,16 @@ impl fmt::Display for MioEvent {
             Self::ConnectionDidCloseOnDemand(addr) => {
                 write!(f, "ConnectionDidCloseOnDemand, {addr}")
             }
+            Self::QuicConnectionDidConnect(addr, res) => {
+                write!(f, "QuicConnectionDidConnect, {addr}, {}", res_kind(res))
+            }
+            Self::QuicStreamDataDidReceive(addr, stream_id, data, fin) => {
+                write!(
+                    f,
+                    "QuicStreamDataDidReceive, {addr}, {stream_id}, {}, fin: {fin}",
+                    data.len()
+                )
+            }
         }
     }
 }
@@ -1,4 +1,5 @@
 use crate::{
+    ban::{P2pBanAction, P2pBanState},
     channels::{
         rpc::P2pChannelsRpcAction, signaling::discovery::P2pChannelsSignalingDiscoveryAction,
         streaming_rpc::P2pChannelsStreamingRpcAction, P2pChannelsState,
@@ -8,8 +9,10 @@ use crate::{
         P2pConnectionState,
     },
     disconnection::{P2pDisconnectedState, P2pDisconnectionAction},
-    P2pAction, P2pNetworkKadKey, P2pNetworkKademliaAction, P2pNetworkPnetAction,
-    P2pNetworkRpcAction, P2pNetworkSelectAction, P2pNetworkState, P2pPeerState, P2pState, PeerId,
+    peer_store::{P2pPeerStoreAction, P2pPeerStoreState},
+    P2pAction, P2pNetworkKadKey, P2pNetworkKademliaAction, P2pNetworkNodeStatusAction,
+    P2pNetworkPingAction, P2pNetworkPnetAction, P2pNetworkPubsubAction, P2pNetworkRpcAction,
+    P2pNetworkSelectAction, P2pNetworkState, P2pPeerState, P2pState, PeerId,
 };
 use openmina_core::{bug_condition, Substate};
 use redux::{ActionMeta, ActionWithMeta, Dispatcher, Timestamp};
@@ -40,6 +43,10 @@ impl P2pState {
             P2pAction::Disconnection(action) => {
                 P2pDisconnectedState::reducer(state_context, meta.with_action(action))
             }
+            P2pAction::Ban(action) => P2pBanState::reducer(state_context, meta.with_action(action)),
+            P2pAction::PeerStore(action) => {
+                P2pPeerStoreState::reducer(state_context, meta.with_action(action))
+            }
             P2pAction::Peer(action) => P2pPeerState::reducer(
                 Substate::from_compatible_substate(state_context),
                 meta.with_action(action),
@@ -82,6 +89,8 @@ impl P2pState {
         state.p2p_connection_timeouts_dispatch(dispatcher, time)?;
         dispatcher.push(P2pConnectionOutgoingAction::RandomInit);
         dispatcher.push(P2pDisconnectionAction::RandomTry);
+        dispatcher.push(P2pBanAction::PruneExpired);
+        dispatcher.push(P2pPeerStoreAction::Save);
 
         state.p2p_try_reconnect_disconnected_peers(dispatcher, time)?;
         state.p2p_discovery(dispatcher, time)?;
@@ -90,7 +99,10 @@ impl P2pState {
         {
             state.p2p_pnet_timeouts(dispatcher, time)?;
             state.p2p_select_timeouts(dispatcher, time)?;
+            state.p2p_node_status_timeouts(dispatcher, time)?;
+            state.p2p_ping(dispatcher, time)?;
             state.p2p_rpc_heartbeats(dispatcher, time)?;
+            dispatcher.push(P2pNetworkPubsubAction::Heartbeat);
         }
 
         state.rpc_timeouts(dispatcher, time)?;
@@ -139,15 +151,17 @@ impl P2pState {
 
         let timeouts = &self.config.timeouts;
 
-        self.peers
+        let mut peers = self
+            .peers
             .iter()
-            .filter_map(|(_, peer)| {
-                if peer.can_reconnect(time, timeouts) {
-                    peer.dial_opts.clone()
-                } else {
-                    None
-                }
-            })
+            .filter(|(_, peer)| peer.can_reconnect(time, timeouts))
+            .collect::<Vec<_>>();
+        // previously good peers are dialed before seeds
+        peers.sort_by_key(|(peer_id, _)| self.peer_store.dial_priority(peer_id));
+
+        peers
+            .into_iter()
+            .filter_map(|(_, peer)| peer.dial_opts.clone())
             .map(|opts| P2pConnectionOutgoingAction::Reconnect { opts, rpc_id: None })
             .for_each(|action| dispatcher.push(action));
         Ok(())
@@ -309,6 +323,61 @@ impl P2pState {
         Ok(())
     }
 
+    fn p2p_node_status_timeouts<State, Action>(
+        &self,
+        dispatcher: &mut Dispatcher<Action, State>,
+        time: Timestamp,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let timeouts = &self.config.timeouts;
+
+        self.network
+            .scheduler
+            .node_status_state
+            .outgoing
+            .iter()
+            .filter(|(_, query)| query.is_timed_out(time, timeouts))
+            .map(|(peer_id, _)| P2pNetworkNodeStatusAction::QueryError {
+                peer_id: *peer_id,
+                error: "timeout".to_owned(),
+            })
+            .for_each(|action| dispatcher.push(action));
+
+        Ok(())
+    }
+
+    fn p2p_ping<State, Action>(
+        &self,
+        dispatcher: &mut Dispatcher<Action, State>,
+        time: Timestamp,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let ping_state = &self.network.scheduler.ping_state;
+        let timeouts = &self.config.timeouts;
+
+        ping_state
+            .outgoing
+            .iter()
+            .filter(|(_, ping)| ping.is_timed_out(time, timeouts))
+            .map(|(peer_id, _)| P2pNetworkPingAction::Timeout { peer_id: *peer_id })
+            .for_each(|action| dispatcher.push(action));
+
+        self.peers
+            .iter()
+            .filter(|(_, peer)| peer.is_libp2p && peer.status.as_ready().is_some())
+            .filter(|(peer_id, _)| ping_state.should_ping(peer_id, time))
+            .map(|(peer_id, _)| P2pNetworkPingAction::Ping { peer_id: *peer_id })
+            .for_each(|action| dispatcher.push(action));
+
+        Ok(())
+    }
+
     fn p2p_rpc_heartbeats<State, Action>(
         &self,
         dispatcher: &mut Dispatcher<Action, State>,
@@ -1,8 +1,11 @@
 pub use redux::TimeService;
 
+pub use crate::ban_effectful::P2pBanService;
 pub use crate::channels::P2pChannelsService;
 pub use crate::connection::P2pConnectionService;
 pub use crate::disconnection_effectful::P2pDisconnectionService;
+pub use crate::peer_store_effectful::P2pPeerStoreService;
+pub use crate::service_impl::bandwidth::P2pBandwidthService;
 
 #[cfg(all(not(target_arch = "wasm32"), feature = "p2p-libp2p"))]
 pub use crate::{P2pCryptoService, P2pMioService, P2pNetworkService};
@@ -12,6 +15,9 @@ pub trait P2pService:
     TimeService
     + P2pConnectionService
     + P2pDisconnectionService
+    + P2pBanService
+    + P2pPeerStoreService
+    + P2pBandwidthService
     + P2pChannelsService
     + P2pMioService
     + P2pCryptoService
@@ -24,6 +30,9 @@ impl<T> P2pService for T where
     T: TimeService
         + P2pConnectionService
         + P2pDisconnectionService
+        + P2pBanService
+        + P2pPeerStoreService
+        + P2pBandwidthService
         + P2pChannelsService
         + P2pMioService
         + P2pCryptoService
@@ -33,12 +42,24 @@ impl<T> P2pService for T where
 
 #[cfg(not(all(not(target_arch = "wasm32"), feature = "p2p-libp2p")))]
 pub trait P2pService:
-    TimeService + P2pConnectionService + P2pDisconnectionService + P2pChannelsService
+    TimeService
+    + P2pConnectionService
+    + P2pDisconnectionService
+    + P2pBanService
+    + P2pPeerStoreService
+    + P2pBandwidthService
+    + P2pChannelsService
 {
 }
 
 #[cfg(not(all(not(target_arch = "wasm32"), feature = "p2p-libp2p")))]
 impl<T> P2pService for T where
-    T: TimeService + P2pConnectionService + P2pDisconnectionService + P2pChannelsService
+    T: TimeService
+        + P2pConnectionService
+        + P2pDisconnectionService
+        + P2pBanService
+        + P2pPeerStoreService
+        + P2pBandwidthService
+        + P2pChannelsService
 {
 }
@@ -15,6 +15,7 @@ use std::{
 };
 
 use crate::{
+    ban::P2pBanState,
     bootstrap::P2pNetworkKadBootstrapState,
     channels::{
         rpc::{P2pRpcId, P2pRpcRequest, P2pRpcResponse},
@@ -33,8 +34,10 @@ use crate::{
         identify::{P2pNetworkIdentify, P2pNetworkIdentifyState},
         P2pNetworkState,
     },
-    Limit, P2pConfig, P2pLimits, P2pNetworkKadState, P2pNetworkPubsubState,
-    P2pNetworkSchedulerState, P2pTimeouts, PeerId,
+    peer_store::P2pPeerStoreState,
+    BitswapBlockError, BitswapCid, Limit, P2pConfig, P2pLimits, P2pNetworkBitswapState,
+    P2pNetworkKadState, P2pNetworkNodeStatus, P2pNetworkNodeStatusState, P2pNetworkPingState,
+    P2pNetworkPubsubState, P2pNetworkSchedulerState, P2pTimeouts, PeerId, StreamId,
 };
 use mina_p2p_messages::v2;
 
@@ -44,6 +47,8 @@ pub struct P2pState {
     pub config: P2pConfig,
     pub network: P2pNetworkState,
     pub peers: BTreeMap<PeerId, P2pPeerState>,
+    pub ban: P2pBanState,
+    pub peer_store: P2pPeerStoreState,
 
     pub last_random_disconnection_try: redux::Timestamp,
 
@@ -76,10 +81,18 @@ impl P2pState {
             );
         }
 
-        let initial_peers = config
+        // peers we connected to before the restart go first, so they are
+        // dialed even when seeds are down
+        let stored_peers = config
+            .peer_store
             .initial_peers
-            .iter()
-            .filter(|peer| peer.peer_id() != &my_id);
+            .values()
+            .filter(|entry| entry.is_good())
+            .map(|entry| &entry.dial_opts);
+        let initial_peers = stored_peers
+            .chain(config.initial_peers.iter())
+            .filter(|peer| peer.peer_id() != &my_id)
+            .filter(|peer| !config.ban.initial_bans.contains_key(peer.peer_id()));
 
         let known_peers = if cfg!(feature = "p2p-libp2p") {
             initial_peers
@@ -112,18 +125,29 @@ impl P2pState {
             })
             .collect();
 
-        let network = P2pNetworkState::new(
+        let mut network = P2pNetworkState::new(
             config.identity_pub_key.clone(),
             addrs,
             known_peers,
             chain_id,
             config.peer_discovery,
+            &config.meshsub,
         );
+        if let Some(discovery_state) = network.scheduler.discovery_state.as_mut() {
+            discovery_state
+                .banned_peers
+                .extend(config.ban.initial_bans.keys().copied());
+        }
+        let ban = P2pBanState::new(config.ban.initial_bans.clone());
+        let peer_store = P2pPeerStoreState::new(config.peer_store.initial_peers.clone());
+
         Self {
             chain_id: chain_id.clone(),
             config,
             network,
             peers,
+            ban,
+            peer_store,
 
             last_random_disconnection_try: redux::Timestamp::ZERO,
 
@@ -158,13 +182,16 @@ impl P2pState {
     }
 
     pub fn disconnected_peers(&self) -> impl '_ + Iterator<Item = P2pConnectionOutgoingInitOpts> {
-        self.peers.iter().filter_map(|(_, state)| {
+        self.peers.iter().filter_map(|(peer_id, state)| {
             if let P2pPeerState {
                 status: P2pPeerStatus::Disconnected { .. },
                 dial_opts: Some(opts),
                 ..
             } = state
             {
+                if self.ban.is_banned(peer_id) {
+                    return None;
+                }
                 Some(opts.clone())
             } else {
                 None
@@ -477,6 +504,9 @@ pub struct P2pPeerStatusReady {
     pub connected_since: redux::Timestamp,
     pub channels: P2pChannelsState,
     pub best_tip: Option<ArcBlockWithHash>,
+    /// Round trip time measured by the last ping, `None` until the peer answers one.
+    #[serde(default)]
+    pub rtt: Option<Duration>,
 }
 
 impl P2pPeerStatusReady {
@@ -490,6 +520,7 @@ impl P2pPeerStatusReady {
             connected_since: time,
             channels: P2pChannelsState::new(enabled_channels),
             best_tip: None,
+            rtt: None,
         }
     }
 
@@ -568,6 +599,21 @@ pub struct P2pCallbacks {
     /// Callback for [`P2pChannelsStreamingRpcAction::ResponseReceived`]
     pub on_p2p_channels_streaming_rpc_response_received:
         OptionalCallback<(PeerId, P2pRpcId, Option<P2pStreamingRpcResponseFull>)>,
+
+    /// Callback for [`P2pNetworkNodeStatusAction::NewStream`] opened by the peer
+    pub on_p2p_network_node_status_request: OptionalCallback<(PeerId, StreamId)>,
+    /// Callback for [`P2pNetworkNodeStatusAction::QuerySuccess`]
+    pub on_p2p_network_node_status_query_success:
+        OptionalCallback<(RpcId, Box<P2pNetworkNodeStatus>)>,
+    /// Callback for [`P2pNetworkNodeStatusAction::QueryError`]
+    pub on_p2p_network_node_status_query_error: OptionalCallback<(RpcId, String)>,
+
+    /// Callback for a resource requested with [`P2pNetworkBitswapAction::Want`]
+    /// once all of its blocks are available
+    pub on_p2p_network_bitswap_resource_ready: OptionalCallback<BitswapCid>,
+    /// Callback for a resource requested with [`P2pNetworkBitswapAction::Want`]
+    /// whose blocks can't be assembled
+    pub on_p2p_network_bitswap_resource_error: OptionalCallback<(BitswapCid, BitswapBlockError)>,
 }
 
 impl_substate_access!(P2pState, P2pNetworkState, network);
@@ -617,4 +663,15 @@ impl_substate_access!(
     P2pNetworkPubsubState,
     network.scheduler.broadcast_state
 );
+impl_substate_access!(
+    P2pState,
+    P2pNetworkBitswapState,
+    network.scheduler.bitswap_state
+);
+impl_substate_access!(
+    P2pState,
+    P2pNetworkNodeStatusState,
+    network.scheduler.node_status_state
+);
+impl_substate_access!(P2pState, P2pNetworkPingState, network.scheduler.ping_state);
 impl_substate_access!(P2pState, P2pConfig, config);
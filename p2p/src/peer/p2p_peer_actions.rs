@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use openmina_core::{block::ArcBlockWithHash, ActionEvent};
 use serde::{Deserialize, Serialize};
 
@@ -19,6 +21,9 @@ pub enum P2pPeerAction {
         peer_id: PeerId,
         best_tip: ArcBlockWithHash,
     },
+    /// Peer answered a ping, update its round trip time.
+    #[action_event(level = trace, fields(display(peer_id), debug(rtt)))]
+    RttUpdate { peer_id: PeerId, rtt: Duration },
     /// Remove peer from state
     Remove { peer_id: PeerId },
 }
@@ -29,6 +34,7 @@ impl P2pPeerAction {
             Self::Discovered { peer_id, .. } => peer_id,
             Self::Ready { peer_id, .. } => peer_id,
             Self::BestTipUpdate { peer_id, .. } => peer_id,
+            Self::RttUpdate { peer_id, .. } => peer_id,
             Self::Remove { peer_id } => peer_id,
         }
     }
@@ -39,6 +45,7 @@ impl redux::EnablingCondition<P2pState> for P2pPeerAction {
         match self {
             Self::Discovered { peer_id, .. } => {
                 peer_id != &state.my_id()
+                    && !state.ban.is_banned(peer_id)
                     && state
                         .peers
                         .get(peer_id)
@@ -54,6 +61,7 @@ impl redux::EnablingCondition<P2pState> for P2pPeerAction {
                 // best tip.
                 state.get_ready_peer(peer_id).is_some()
             }
+            Self::RttUpdate { peer_id, .. } => state.get_ready_peer(peer_id).is_some(),
             Self::Remove { peer_id } => {
                 state.peers.len() > state.config.limits.min_peers_in_state()
                     && state.peers.contains_key(peer_id)
@@ -47,6 +47,12 @@ impl P2pPeerState {
                     meta.time(),
                     &p2p_state.config.enabled_channels,
                 ));
+                // only addresses we dialed ourselves are known to be reachable
+                if let Some(dial_opts) = peer.dial_opts.clone().filter(|_| !incoming) {
+                    p2p_state
+                        .peer_store
+                        .on_ready(peer_id, dial_opts, meta.time());
+                }
 
                 if !peer.is_libp2p {
                     let (dispatcher, state) = state_context.into_dispatcher_and_state();
@@ -71,6 +77,14 @@ impl P2pPeerState {
                 }
                 Ok(())
             }
+            P2pPeerAction::RttUpdate { peer_id, rtt } => {
+                let Some(peer) = p2p_state.get_ready_peer_mut(&peer_id) else {
+                    bug_condition!("Peer state not found for `P2pPeerAction::RttUpdate`");
+                    return Ok(());
+                };
+                peer.rtt = Some(rtt);
+                Ok(())
+            }
             P2pPeerAction::Remove { peer_id } => {
                 if p2p_state.peers.remove(&peer_id).is_none() {
                     bug_condition!(
@@ -0,0 +1,7 @@
+mod p2p_peer_store_state;
+pub use p2p_peer_store_state::*;
+
+mod p2p_peer_store_actions;
+pub use p2p_peer_store_actions::*;
+
+mod p2p_peer_store_reducer;
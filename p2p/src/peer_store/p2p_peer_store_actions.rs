@@ -0,0 +1,23 @@
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::P2pState;
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(level = debug)]
+pub enum P2pPeerStoreAction {
+    /// Persist known good peers, so that they are dialed on restart.
+    Save,
+}
+
+impl redux::EnablingCondition<P2pState> for P2pPeerStoreAction {
+    fn is_enabled(&self, state: &P2pState, time: redux::Timestamp) -> bool {
+        match self {
+            P2pPeerStoreAction::Save => crate::is_time_passed(
+                time,
+                state.peer_store.last_saved,
+                state.config.peer_store.save_interval,
+            ),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use openmina_core::Substate;
+use redux::ActionWithMeta;
+
+use crate::{peer_store_effectful::P2pPeerStoreEffectfulAction, P2pState};
+
+use super::{P2pPeerStoreAction, P2pPeerStoreState};
+
+impl P2pPeerStoreState {
+    /// Substate is accessed
+    pub fn reducer<Action, State>(
+        mut state_context: Substate<Action, State, P2pState>,
+        action: ActionWithMeta<P2pPeerStoreAction>,
+    ) -> Result<(), String>
+    where
+        State: crate::P2pStateTrait,
+        Action: crate::P2pActionTrait<State>,
+    {
+        let p2p_state = state_context.get_substate_mut()?;
+        let (action, meta) = action.split();
+
+        match action {
+            P2pPeerStoreAction::Save => {
+                p2p_state.peer_store.last_saved = meta.time();
+                // addresses might have been corrected by identify since the peer got ready
+                for (peer_id, entry) in p2p_state.peer_store.peers.iter_mut() {
+                    if let Some(dial_opts) = p2p_state
+                        .peers
+                        .get(peer_id)
+                        .and_then(|peer| peer.dial_opts.as_ref())
+                    {
+                        entry.dial_opts = dial_opts.clone();
+                    }
+                }
+                let peers = p2p_state
+                    .peer_store
+                    .good_peers(p2p_state.config.peer_store.max_peers);
+
+                let dispatcher = state_context.into_dispatcher();
+                dispatcher.push(P2pPeerStoreEffectfulAction::Persist { peers });
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::{connection::outgoing::P2pConnectionOutgoingInitOpts, PeerId};
+
+/// Peer is no longer considered good if its connection failures exceed
+/// successes by this much.
+const MAX_EXCESS_FAILURES: u32 = 5;
+
+/// Peers we managed to connect to, persisted so that they can be dialed
+/// on restart before the seeds.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pPeerStoreState {
+    pub peers: BTreeMap<PeerId, P2pPeerStoreEntry>,
+    pub last_saved: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct P2pPeerStoreEntry {
+    pub dial_opts: P2pConnectionOutgoingInitOpts,
+    /// Last time the connection with the peer became ready.
+    pub last_seen: Timestamp,
+    /// Number of times the connection with the peer became ready.
+    pub successes: u32,
+    /// Number of failed outgoing connection attempts.
+    pub failures: u32,
+}
+
+impl P2pPeerStoreState {
+    pub fn new(peers: BTreeMap<PeerId, P2pPeerStoreEntry>) -> Self {
+        Self {
+            peers,
+            last_saved: Timestamp::ZERO,
+        }
+    }
+
+    pub fn on_ready(
+        &mut self,
+        peer_id: PeerId,
+        dial_opts: P2pConnectionOutgoingInitOpts,
+        now: Timestamp,
+    ) {
+        let entry = self
+            .peers
+            .entry(peer_id)
+            .or_insert_with(|| P2pPeerStoreEntry {
+                dial_opts: dial_opts.clone(),
+                last_seen: now,
+                successes: 0,
+                failures: 0,
+            });
+        entry.dial_opts = dial_opts;
+        entry.last_seen = now;
+        entry.successes = entry.successes.saturating_add(1);
+    }
+
+    /// Only peers that connected successfully before are tracked.
+    pub fn on_failure(&mut self, peer_id: &PeerId) {
+        if let Some(entry) = self.peers.get_mut(peer_id) {
+            entry.failures = entry.failures.saturating_add(1);
+        }
+    }
+
+    /// Sort key for dialing, stored good peers come first, most recently
+    /// seen ones before others.
+    pub fn dial_priority(&self, peer_id: &PeerId) -> impl Ord {
+        match self.peers.get(peer_id).filter(|entry| entry.is_good()) {
+            Some(entry) => (false, Reverse(entry.last_seen)),
+            None => (true, Reverse(Timestamp::ZERO)),
+        }
+    }
+
+    /// Up to `max` good peers, most recently seen first.
+    pub fn good_peers(&self, max: usize) -> BTreeMap<PeerId, P2pPeerStoreEntry> {
+        let mut peers = self
+            .peers
+            .iter()
+            .filter(|(_, entry)| entry.is_good())
+            .collect::<Vec<_>>();
+        peers.sort_by_key(|(_, entry)| Reverse(entry.last_seen));
+        peers
+            .into_iter()
+            .take(max)
+            .map(|(peer_id, entry)| (*peer_id, entry.clone()))
+            .collect()
+    }
+}
+
+impl P2pPeerStoreEntry {
+    pub fn is_good(&self) -> bool {
+        self.successes > 0 && self.failures.saturating_sub(self.successes) < MAX_EXCESS_FAILURES
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use crate::{
+        connection::outgoing::P2pConnectionOutgoingInitLibp2pOpts, identity::SecretKey,
+        webrtc::Host,
+    };
+
+    use super::*;
+
+    fn peer(i: u8) -> (PeerId, P2pConnectionOutgoingInitOpts) {
+        let peer_id = SecretKey::deterministic(i.into()).public_key().peer_id();
+        let opts = P2pConnectionOutgoingInitOpts::LibP2P(P2pConnectionOutgoingInitLibp2pOpts {
+            peer_id,
+            host: Host::Ipv4(Ipv4Addr::new(10, 0, 0, i)),
+            port: 8302,
+            transport: Default::default(),
+        });
+        (peer_id, opts)
+    }
+
+    fn time(secs: u64) -> Timestamp {
+        Timestamp::new(secs * 1_000_000_000)
+    }
+
+    /// Peers `a` and `b` are good, `b` seen more recently, `c` keeps
+    /// failing and `d` is a seed that never connected.
+    fn store() -> (P2pPeerStoreState, [PeerId; 4]) {
+        let (a, a_opts) = peer(1);
+        let (b, b_opts) = peer(2);
+        let (c, c_opts) = peer(3);
+        let (d, _) = peer(4);
+
+        let mut store = P2pPeerStoreState::new(Default::default());
+        store.on_ready(a, a_opts, time(1));
+        store.on_ready(b, b_opts, time(2));
+        store.on_ready(c, c_opts, time(3));
+        for _ in 0..MAX_EXCESS_FAILURES + 1 {
+            store.on_failure(&c);
+        }
+        store.on_failure(&d);
+
+        (store, [a, b, c, d])
+    }
+
+    #[test]
+    fn dial_priority() {
+        let (store, [a, b, c, d]) = store();
+
+        assert!(
+            !store.peers.contains_key(&d),
+            "unknown peers aren't tracked"
+        );
+        assert!(!store.peers[&c].is_good());
+
+        let mut peers = vec![d, c, a, b];
+        peers.sort_by_key(|peer_id| store.dial_priority(peer_id));
+        assert_eq!(peers, vec![b, a, d, c]);
+    }
+
+    #[test]
+    fn good_peers_save_and_load() {
+        let (store, [a, b, c, _]) = store();
+
+        let saved = store.good_peers(10);
+        assert_eq!(saved.len(), 2);
+        assert!(!saved.contains_key(&c));
+
+        let limited = store.good_peers(1);
+        assert_eq!(limited.keys().copied().collect::<Vec<_>>(), vec![b]);
+
+        let json = serde_json::to_string(&saved).unwrap();
+        let loaded: BTreeMap<PeerId, P2pPeerStoreEntry> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, saved);
+
+        let loaded = P2pPeerStoreState::new(loaded);
+        let mut peers = vec![a, b];
+        peers.sort_by_key(|peer_id| loaded.dial_priority(peer_id));
+        assert_eq!(peers, vec![b, a]);
+    }
+}
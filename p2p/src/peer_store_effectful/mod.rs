@@ -0,0 +1,7 @@
+mod p2p_peer_store_effectful_actions;
+pub use p2p_peer_store_effectful_actions::*;
+
+mod p2p_peer_store_effectful_effects;
+
+mod p2p_peer_store_effectful_service;
+pub use p2p_peer_store_effectful_service::*;
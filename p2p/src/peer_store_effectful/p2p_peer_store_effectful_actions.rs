@@ -0,0 +1,21 @@
+use std::collections::BTreeMap;
+
+use openmina_core::ActionEvent;
+use serde::{Deserialize, Serialize};
+
+use crate::{peer_store::P2pPeerStoreEntry, P2pState, PeerId};
+
+#[derive(Serialize, Deserialize, Debug, Clone, ActionEvent)]
+#[action_event(level = debug)]
+pub enum P2pPeerStoreEffectfulAction {
+    /// Persist known good peers.
+    Persist {
+        peers: BTreeMap<PeerId, P2pPeerStoreEntry>,
+    },
+}
+
+impl redux::EnablingCondition<P2pState> for P2pPeerStoreEffectfulAction {
+    fn is_enabled(&self, _state: &P2pState, _time: redux::Timestamp) -> bool {
+        true
+    }
+}
@@ -0,0 +1,17 @@
+use redux::ActionMeta;
+
+use super::{P2pPeerStoreEffectfulAction, P2pPeerStoreService};
+
+impl P2pPeerStoreEffectfulAction {
+    pub fn effects<Store, S>(self, _: &ActionMeta, store: &mut Store)
+    where
+        Store: crate::P2pStore<S>,
+        Store::Service: P2pPeerStoreService,
+    {
+        match self {
+            P2pPeerStoreEffectfulAction::Persist { peers } => {
+                store.service().peer_store_persist(&peers);
+            }
+        }
+    }
+}
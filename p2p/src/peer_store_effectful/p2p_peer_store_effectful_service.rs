@@ -0,0 +1,8 @@
+use std::collections::BTreeMap;
+
+use crate::{peer_store::P2pPeerStoreEntry, PeerId};
+
+pub trait P2pPeerStoreService: redux::Service {
+    /// Stores known good peers, so that they can be dialed on restart.
+    fn peer_store_persist(&mut self, peers: &BTreeMap<PeerId, P2pPeerStoreEntry>);
+}
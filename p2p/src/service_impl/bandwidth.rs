@@ -0,0 +1,363 @@
+//! Token bucket bandwidth limiting, shared by the transports.
+//!
+//! Every peer has a bucket for each direction, and so does the node as a
+//! whole. Transports ask for an allowance before reading or writing and
+//! record the amount actually transferred. Buckets may go into debt when
+//! a transport can't hold data back (e.g. messages pushed by a WebRTC
+//! data channel), which delays further transfers until the debt is paid.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use redux::Instant;
+use serde::{Deserialize, Serialize};
+
+use crate::PeerId;
+
+/// Amount of bytes transports wait for before transferring, unless less is
+/// requested, so that throttled connections don't trickle tiny chunks.
+const MIN_CHUNK: u64 = 4 * 1024;
+
+/// Usage rate is measured over windows of this length.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Rates are in bytes per second, `None` means unlimited.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct P2pBandwidthLimits {
+    pub peer_inbound: Option<u64>,
+    pub peer_outbound: Option<u64>,
+    pub global_inbound: Option<u64>,
+    pub global_outbound: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum P2pBandwidthDirection {
+    Inbound,
+    Outbound,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum P2pBandwidthPeer {
+    Libp2p(SocketAddr),
+    WebRTC(PeerId),
+}
+
+impl fmt::Display for P2pBandwidthPeer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Libp2p(addr) => write!(f, "{addr}"),
+            Self::WebRTC(peer_id) => write!(f, "{peer_id}"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct P2pBandwidthCounter {
+    pub total_bytes: u64,
+    /// Rate measured over the last complete window.
+    pub bytes_per_sec: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+pub struct P2pBandwidthUsage {
+    pub inbound: P2pBandwidthCounter,
+    pub outbound: P2pBandwidthCounter,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pBandwidthPeerUsage {
+    pub peer: P2pBandwidthPeer,
+    pub usage: P2pBandwidthUsage,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct P2pBandwidthStats {
+    pub limits: P2pBandwidthLimits,
+    pub global: P2pBandwidthUsage,
+    pub peers: Vec<P2pBandwidthPeerUsage>,
+}
+
+pub trait P2pBandwidthService: redux::Service {
+    /// Current bandwidth usage and limits.
+    fn bandwidth_stats(&self) -> P2pBandwidthStats;
+}
+
+/// Handle to the bandwidth limiter, cheap to clone and shared between
+/// the transport threads.
+#[derive(Debug, Clone, Default)]
+pub struct P2pBandwidth(Arc<Mutex<P2pBandwidthInner>>);
+
+#[derive(Debug, Default)]
+struct P2pBandwidthInner {
+    limits: P2pBandwidthLimits,
+    global: Buckets,
+    peers: BTreeMap<P2pBandwidthPeer, Buckets>,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    inbound: Bucket,
+    outbound: Bucket,
+}
+
+#[derive(Debug, Default)]
+struct Bucket {
+    /// Available bytes, negative when in debt.
+    tokens: f64,
+    last_refill: Option<Instant>,
+    counter: P2pBandwidthCounter,
+    window_start: Option<Instant>,
+    window_bytes: u64,
+}
+
+impl P2pBandwidth {
+    pub fn new(limits: P2pBandwidthLimits) -> Self {
+        let bandwidth = Self::default();
+        bandwidth.set_limits(limits);
+        bandwidth
+    }
+
+    pub fn set_limits(&self, limits: P2pBandwidthLimits) {
+        self.lock().limits = limits;
+    }
+
+    pub fn limits(&self) -> P2pBandwidthLimits {
+        self.lock().limits
+    }
+
+    /// How many of `wanted` bytes can be transferred right now. Returns
+    /// zero if the peer or the node as a whole is out of budget.
+    pub fn allowance(
+        &self,
+        peer: P2pBandwidthPeer,
+        direction: P2pBandwidthDirection,
+        wanted: usize,
+    ) -> usize {
+        let now = Instant::now();
+        let mut inner = self.lock();
+        let (peer_rate, global_rate) = inner.limits.rates(direction);
+        let global = inner.global.get(direction).available(global_rate, now);
+        let peer = inner
+            .peers
+            .entry(peer)
+            .or_default()
+            .get(direction)
+            .available(peer_rate, now);
+
+        let available = global.min(peer);
+        let wanted = wanted as u64;
+        let threshold = [Some(wanted), Some(MIN_CHUNK), peer_rate, global_rate]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(0);
+        if available >= threshold {
+            available.min(wanted) as usize
+        } else {
+            0
+        }
+    }
+
+    /// How long to wait until `wanted` bytes (or at least a reasonable
+    /// chunk of them) can be transferred.
+    pub fn delay(
+        &self,
+        peer: P2pBandwidthPeer,
+        direction: P2pBandwidthDirection,
+        wanted: usize,
+    ) -> Duration {
+        let now = Instant::now();
+        let mut inner = self.lock();
+        let (peer_rate, global_rate) = inner.limits.rates(direction);
+        let wanted = wanted as u64;
+        let global = inner.global.get(direction).delay(global_rate, wanted, now);
+        let peer = inner
+            .peers
+            .entry(peer)
+            .or_default()
+            .get(direction)
+            .delay(peer_rate, wanted, now);
+        global.max(peer)
+    }
+
+    /// Records bytes that were transferred.
+    pub fn record(&self, peer: P2pBandwidthPeer, direction: P2pBandwidthDirection, bytes: usize) {
+        let now = Instant::now();
+        let mut inner = self.lock();
+        let (peer_rate, global_rate) = inner.limits.rates(direction);
+        inner
+            .global
+            .get(direction)
+            .consume(global_rate, bytes as u64, now);
+        inner
+            .peers
+            .entry(peer)
+            .or_default()
+            .get(direction)
+            .consume(peer_rate, bytes as u64, now);
+    }
+
+    /// Forgets the peer once its connection is closed.
+    pub fn remove_peer(&self, peer: &P2pBandwidthPeer) {
+        self.lock().peers.remove(peer);
+    }
+
+    pub fn stats(&self) -> P2pBandwidthStats {
+        let now = Instant::now();
+        let mut inner = self.lock();
+        let global = inner.global.usage(now);
+        let peers = inner
+            .peers
+            .iter_mut()
+            .map(|(peer, buckets)| P2pBandwidthPeerUsage {
+                peer: *peer,
+                usage: buckets.usage(now),
+            })
+            .collect();
+        P2pBandwidthStats {
+            limits: inner.limits,
+            global,
+            peers,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, P2pBandwidthInner> {
+        // the state stays consistent even if some thread panicked
+        self.0.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl P2pBandwidthLimits {
+    /// Peer and global rate limits for the direction.
+    fn rates(&self, direction: P2pBandwidthDirection) -> (Option<u64>, Option<u64>) {
+        match direction {
+            P2pBandwidthDirection::Inbound => (self.peer_inbound, self.global_inbound),
+            P2pBandwidthDirection::Outbound => (self.peer_outbound, self.global_outbound),
+        }
+    }
+}
+
+impl Buckets {
+    fn get(&mut self, direction: P2pBandwidthDirection) -> &mut Bucket {
+        match direction {
+            P2pBandwidthDirection::Inbound => &mut self.inbound,
+            P2pBandwidthDirection::Outbound => &mut self.outbound,
+        }
+    }
+
+    fn usage(&mut self, now: Instant) -> P2pBandwidthUsage {
+        P2pBandwidthUsage {
+            inbound: self.inbound.counter(now),
+            outbound: self.outbound.counter(now),
+        }
+    }
+}
+
+impl Bucket {
+    /// Adds tokens accumulated since the last refill, at most one second
+    /// worth of them, so bursts are bounded by the rate.
+    fn refill(&mut self, rate: u64, now: Instant) {
+        let elapsed = self.last_refill.map_or(Duration::from_secs(1), |last| {
+            now.saturating_duration_since(last)
+        });
+        self.last_refill = Some(now);
+        let rate = rate as f64;
+        self.tokens = (self.tokens + rate * elapsed.as_secs_f64()).min(rate);
+    }
+
+    fn available(&mut self, rate: Option<u64>, now: Instant) -> u64 {
+        let Some(rate) = rate else {
+            return u64::MAX;
+        };
+        self.refill(rate, now);
+        self.tokens.max(0.0) as u64
+    }
+
+    fn delay(&mut self, rate: Option<u64>, wanted: u64, now: Instant) -> Duration {
+        let Some(rate) = rate else {
+            return Duration::ZERO;
+        };
+        if rate == 0 {
+            // nothing is ever allowed, just don't spin
+            return Duration::from_secs(1);
+        }
+        self.refill(rate, now);
+        let needed = wanted.min(MIN_CHUNK).min(rate) as f64 - self.tokens;
+        if needed <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(needed / rate as f64)
+        }
+    }
+
+    fn consume(&mut self, rate: Option<u64>, bytes: u64, now: Instant) {
+        if let Some(rate) = rate {
+            self.refill(rate, now);
+            self.tokens -= bytes as f64;
+        }
+        self.counter.total_bytes = self.counter.total_bytes.saturating_add(bytes);
+        self.update_window(now);
+        self.window_bytes = self.window_bytes.saturating_add(bytes);
+    }
+
+    fn counter(&mut self, now: Instant) -> P2pBandwidthCounter {
+        self.update_window(now);
+        self.counter
+    }
+
+    fn update_window(&mut self, now: Instant) {
+        let start = *self.window_start.get_or_insert(now);
+        let elapsed = now.saturating_duration_since(start);
+        if elapsed >= RATE_WINDOW {
+            self.counter.bytes_per_sec = if elapsed >= RATE_WINDOW * 2 {
+                // nothing was transferred during the last window
+                0
+            } else {
+                (self.window_bytes as f64 / elapsed.as_secs_f64()) as u64
+            };
+            self.window_start = Some(now);
+            self.window_bytes = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_limits_rate() {
+        let start = Instant::now();
+        let mut bucket = Bucket::default();
+        let rate = Some(1000);
+
+        assert_eq!(bucket.available(rate, start), 1000);
+        bucket.consume(rate, 1500, start);
+        assert_eq!(bucket.available(rate, start), 0);
+        let delay = bucket.delay(rate, 100, start);
+        assert!((delay.as_secs_f64() - 0.6).abs() < 1e-6, "{delay:?}");
+
+        let later = start + Duration::from_millis(600);
+        assert_eq!(bucket.available(rate, later), 100);
+
+        // no more than a second worth of tokens is accumulated
+        let much_later = later + Duration::from_secs(10);
+        assert_eq!(bucket.available(rate, much_later), 1000);
+    }
+
+    #[test]
+    fn test_unlimited_bucket() {
+        let now = Instant::now();
+        let mut bucket = Bucket::default();
+
+        bucket.consume(None, 1 << 30, now);
+        assert_eq!(bucket.available(None, now), u64::MAX);
+        assert_eq!(bucket.delay(None, 1 << 30, now), Duration::ZERO);
+        assert_eq!(bucket.counter(now).total_bytes, 1 << 30);
+    }
+}
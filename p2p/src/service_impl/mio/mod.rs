@@ -1,12 +1,16 @@
 mod token;
 use self::token::{Token, TokenRegistry};
 
+mod quic;
+use self::quic::Quic;
+
 use std::{
-    collections::{BTreeMap, VecDeque},
+    collections::{BTreeMap, BTreeSet, VecDeque},
     io::{self, Read, Write},
     net::{IpAddr, Ipv4Addr, Shutdown, SocketAddr},
     process,
     sync::mpsc,
+    time::{Duration, Instant},
 };
 
 use libp2p_identity::Keypair;
@@ -17,6 +21,8 @@ use thiserror::Error;
 
 use crate::{ConnectionAddr, MioCmd, MioEvent};
 
+use super::bandwidth::{P2pBandwidth, P2pBandwidthDirection, P2pBandwidthPeer};
+
 #[derive(Debug, Error)]
 enum MioError {
     #[error("mio failed to create poll instance, fatal error: {0}")]
@@ -29,6 +35,8 @@ enum MioError {
     Listen(SocketAddr, io::Error),
     #[error("mio failed to register the socket on {0}, error: {1}")]
     Register(SocketAddr, io::Error),
+    #[error("mio QUIC error: {0}")]
+    Quic(String),
 }
 
 impl MioError {
@@ -47,7 +55,7 @@ const MAX_QUEUED_BYTES: usize = 0x4000000;
 #[derive(Debug)]
 #[allow(clippy::large_enum_variant)]
 pub enum MioService {
-    Pending(Keypair),
+    Pending(Keypair, P2pBandwidth),
     Ready(MioRunningService),
 }
 
@@ -63,8 +71,8 @@ impl redux::TimeService for MioService {}
 impl redux::Service for MioService {}
 
 impl MioService {
-    pub fn pending(keypair: Keypair) -> Self {
-        Self::Pending(keypair)
+    pub fn pending(keypair: Keypair, bandwidth: P2pBandwidth) -> Self {
+        Self::Pending(keypair, bandwidth)
     }
 
     pub fn run<F>(&mut self, event_sender: F)
@@ -72,9 +80,11 @@ impl MioService {
         F: 'static + Send + Sync + Fn(MioEvent),
     {
         *self = match self {
-            Self::Pending(keypair) => {
-                MioService::Ready(MioRunningService::run(event_sender, keypair.clone()))
-            }
+            Self::Pending(keypair, bandwidth) => MioService::Ready(MioRunningService::run(
+                event_sender,
+                keypair.clone(),
+                bandwidth.clone(),
+            )),
             _ => {
                 openmina_core::warn!(openmina_core::log::system_time(); "tried to run already running mio service");
                 return;
@@ -84,7 +94,7 @@ impl MioService {
 
     pub fn keypair(&self) -> &Keypair {
         match self {
-            Self::Pending(keypair) => keypair,
+            Self::Pending(keypair, _) => keypair,
             Self::Ready(s) => &s.keypair,
         }
     }
@@ -114,7 +124,7 @@ impl MioRunningService {
         }
     }
 
-    fn run<F>(event_sender: F, keypair: Keypair) -> Self
+    fn run<F>(event_sender: F, keypair: Keypair, bandwidth: P2pBandwidth) -> Self
     where
         F: 'static + Send + Sync + Fn(MioEvent),
     {
@@ -137,6 +147,7 @@ impl MioRunningService {
 
         let (tx, rx) = mpsc::channel();
 
+        let quic = Quic::new(&keypair, bandwidth.clone());
         let mut inner = MioServiceInner {
             poll,
             event_sender,
@@ -145,6 +156,10 @@ impl MioRunningService {
             listeners: BTreeMap::default(),
             connections: BTreeMap::default(),
             recv_buf: vec![0; 0x8000],
+            bandwidth,
+            throttled_recv: BTreeMap::default(),
+            throttled_send: BTreeSet::default(),
+            quic,
         };
 
         std::thread::Builder::new()
@@ -179,6 +194,12 @@ struct MioServiceInner<F> {
     listeners: BTreeMap<SocketAddr, Listener>,
     connections: BTreeMap<ConnectionAddr, Connection>,
     recv_buf: Vec<u8>,
+    bandwidth: P2pBandwidth,
+    /// Connections that ran out of inbound budget, with the pending read limit.
+    throttled_recv: BTreeMap<ConnectionAddr, usize>,
+    /// Connections that ran out of outbound budget.
+    throttled_send: BTreeSet<ConnectionAddr>,
+    quic: Quic,
 }
 
 struct Listener {
@@ -199,7 +220,11 @@ where
     F: 'static + Send + Sync + Fn(MioEvent),
 {
     fn run(&mut self, events: &mut mio::Events) {
-        if let Err(err) = self.poll.poll(events, None) {
+        let timeout = match (self.throttled_timeout(), self.quic.timeout(Instant::now())) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        if let Err(err) = self.poll.poll(events, timeout) {
             MioError::Poll(err).report();
         }
 
@@ -222,6 +247,13 @@ where
                     }
                     self.listeners.insert(addr, listener);
                 }
+                Some(Token::Quic(local)) => {
+                    if event.is_readable() {
+                        let connections = &self.connections;
+                        self.quic
+                            .recv(local, Instant::now(), |addr| connections.contains_key(addr));
+                    }
+                }
                 Some(Token::Connection(mut addr)) => {
                     let Some(mut connection) = self.connections.remove(&addr) else {
                         continue 'events;
@@ -280,9 +312,21 @@ where
                                 }
                             }
                         } else {
+                            let peer = P2pBandwidthPeer::Libp2p(addr.sock_addr);
                             while let Some((buf, mut offset)) = connection.transmits.pop_front() {
+                                let allowance = self.bandwidth.allowance(
+                                    peer,
+                                    P2pBandwidthDirection::Outbound,
+                                    buf.len() - offset,
+                                );
+                                if allowance == 0 {
+                                    // out of outbound budget, resumed once it's replenished
+                                    connection.transmits.push_front((buf, offset));
+                                    self.throttled_send.insert(addr);
+                                    break;
+                                }
                                 connection.queued_bytes -= buf.len() - offset;
-                                match connection.stream.write(&buf[offset..]) {
+                                match connection.stream.write(&buf[offset..offset + allowance]) {
                                     Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
                                         connection.queued_bytes += buf.len() - offset;
                                         connection.transmits.push_front((buf, offset));
@@ -298,6 +342,11 @@ where
                                         continue 'events;
                                     }
                                     Ok(len) => {
+                                        self.bandwidth.record(
+                                            peer,
+                                            P2pBandwidthDirection::Outbound,
+                                            len,
+                                        );
                                         rereg = true;
                                         offset += len;
                                         if offset == buf.len() {
@@ -331,6 +380,56 @@ where
             }
         }
         events.clear();
+        self.throttled_resume();
+        self.quic.handle_timeouts(Instant::now());
+        for event in self.quic.take_events() {
+            self.send(event);
+        }
+    }
+
+    /// Time until some throttled connection might get budget again.
+    fn throttled_timeout(&self) -> Option<Duration> {
+        let recv = self.throttled_recv.iter().map(|(addr, limit)| {
+            let peer = P2pBandwidthPeer::Libp2p(addr.sock_addr);
+            self.bandwidth
+                .delay(peer, P2pBandwidthDirection::Inbound, *limit)
+        });
+        let send = self.throttled_send.iter().filter_map(|addr| {
+            let (buf, offset) = self.connections.get(addr)?.transmits.front()?;
+            let peer = P2pBandwidthPeer::Libp2p(addr.sock_addr);
+            Some(
+                self.bandwidth
+                    .delay(peer, P2pBandwidthDirection::Outbound, buf.len() - offset),
+            )
+        });
+        recv.chain(send).min()
+    }
+
+    fn throttled_resume(&mut self) {
+        for (addr, limit) in std::mem::take(&mut self.throttled_recv) {
+            // throttles the connection again if there is still no budget
+            self.handle(MioCmd::Recv(addr, limit));
+        }
+        for addr in std::mem::take(&mut self.throttled_send) {
+            let Some(connection) = self.connections.get_mut(&addr) else {
+                continue;
+            };
+            let interests = if connection.incoming_ready {
+                mio::Interest::WRITABLE
+            } else {
+                mio::Interest::READABLE | mio::Interest::WRITABLE
+            };
+            // reregistering makes mio report the socket as writable again
+            let token = self.tokens.register(Token::Connection(addr));
+            if let Err(err) =
+                self.poll
+                    .registry()
+                    .reregister(&mut connection.stream, token, interests)
+            {
+                self.connections.remove(&addr);
+                self.send(MioEvent::ConnectionDidClose(addr, Err(err.to_string())));
+            }
+        }
     }
 
     fn handle(&mut self, cmd: MioCmd) {
@@ -378,7 +477,13 @@ where
                             };
 
                             listener.incomind_ready = false;
-                            if let Err(err) = self.poll.registry().register(
+                            if self.quic.contains(&addr) {
+                                stream.shutdown(Shutdown::Both).unwrap_or_default();
+                                self.send(MioEvent::IncomingConnectionDidAccept(
+                                    Some(addr),
+                                    Err("address is used by a QUIC connection".to_owned()),
+                                ));
+                            } else if let Err(err) = self.poll.registry().register(
                                 &mut stream,
                                 self.tokens.register(Token::Connection(addr)),
                                 mio::Interest::READABLE,
@@ -440,6 +545,17 @@ where
                 }
             }
             Connect(addr) => {
+                let conn_addr = ConnectionAddr {
+                    sock_addr: addr,
+                    incoming: false,
+                };
+                if self.quic.contains(&conn_addr) {
+                    self.send(MioEvent::OutgoingConnectionDidConnect(
+                        conn_addr,
+                        Err("address is used by a QUIC connection".to_owned()),
+                    ));
+                    return;
+                }
                 match TcpStream::connect(addr) {
                     Ok(mut stream) => {
                         let addr = ConnectionAddr {
@@ -480,6 +596,21 @@ where
             }
             Recv(addr, limit) => {
                 if let Some(mut connection) = self.connections.remove(&addr) {
+                    let peer = P2pBandwidthPeer::Libp2p(addr.sock_addr);
+                    let limit =
+                        match self
+                            .bandwidth
+                            .allowance(peer, P2pBandwidthDirection::Inbound, limit)
+                        {
+                            0 if limit > 0 => {
+                                // out of inbound budget, resumed once it's replenished
+                                self.throttled_recv.insert(addr, limit);
+                                self.connections.insert(addr, connection);
+                                return;
+                            }
+                            allowance => allowance,
+                        };
+
                     // Ensure the buffer has enough space for the requested limit
                     if limit > self.recv_buf.len() {
                         // TODO: upper bound? resize to `limit` or try to allocate some extra space too?
@@ -490,6 +621,8 @@ where
                     match connection.stream.read(&mut self.recv_buf[..limit]) {
                         Ok(0) => self.send(MioEvent::ConnectionDidClose(addr, Ok(()))),
                         Ok(read) => {
+                            self.bandwidth
+                                .record(peer, P2pBandwidthDirection::Inbound, read);
                             self.send(MioEvent::IncomingDataDidReceive(
                                 addr,
                                 Ok(self.recv_buf[..read].to_vec().into()),
@@ -578,13 +711,43 @@ where
                         .registry()
                         .deregister(&mut cn.stream)
                         .unwrap_or_default();
+                } else {
+                    self.quic.disconnect(addr, Instant::now());
                 }
                 self.send(MioEvent::ConnectionDidCloseOnDemand(addr));
             }
+            QuicListenOn(addr) => {
+                self.quic
+                    .listen(addr, self.poll.registry(), &mut self.tokens);
+            }
+            QuicConnect(addr) => {
+                let conn_addr = ConnectionAddr {
+                    sock_addr: addr,
+                    incoming: false,
+                };
+                if self.connections.contains_key(&conn_addr) {
+                    self.send(MioEvent::QuicConnectionDidConnect(
+                        conn_addr,
+                        Err("address is used by a TCP connection".to_owned()),
+                    ));
+                    return;
+                }
+                self.quic
+                    .connect(addr, self.poll.registry(), &mut self.tokens, Instant::now());
+            }
+            QuicSend(addr, stream_id, data, fin) => {
+                self.quic.send(addr, stream_id, data, fin, Instant::now());
+            }
         }
     }
 
     pub fn send(&self, event: MioEvent) {
+        if let MioEvent::ConnectionDidClose(addr, _) | MioEvent::ConnectionDidCloseOnDemand(addr) =
+            &event
+        {
+            self.bandwidth
+                .remove_peer(&P2pBandwidthPeer::Libp2p(addr.sock_addr));
+        }
         (self.event_sender)(event);
     }
 }
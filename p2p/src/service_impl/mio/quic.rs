@@ -0,0 +1,669 @@
+//! QUIC transport for libp2p connections.
+//!
+//! Every UDP socket is registered in the mio poll and owns a `quinn_proto`
+//! endpoint. The peer is authenticated by TLS with libp2p certificates and
+//! QUIC streams are used instead of yamux streams, so the state machine
+//! only receives the remote peer id and the stream data.
+//!
+//! Stream ids are translated, the state machine keeps using yamux-like ids:
+//! ids of streams opened by the remote peer have the parity that yamux would
+//! give to the remote side of the connection.
+//!
+//! Connections of both transports are keyed by [`ConnectionAddr`], so a
+//! QUIC connection is rejected when a TCP connection uses the same address,
+//! and the other way around, see `MioServiceInner`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use bytes::{Bytes, BytesMut};
+use libp2p_identity::Keypair;
+use mio::net::UdpSocket;
+use quinn_proto::{
+    ClientConfig, ConnectionHandle, DatagramEvent, Dir, EndpointConfig, Event, IdleTimeout,
+    ReadError, ServerConfig, StreamEvent, TransportConfig, VarInt, WriteError,
+};
+
+use crate::{ConnectionAddr, MioEvent, PeerId, StreamId};
+
+use super::{
+    super::bandwidth::{P2pBandwidth, P2pBandwidthDirection, P2pBandwidthPeer},
+    token::{Token, TokenRegistry},
+    MioError,
+};
+
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5);
+const MAX_DATAGRAM_SIZE: usize = 0x10000;
+
+pub(super) struct Quic {
+    client_config: Option<ClientConfig>,
+    server_config: Option<Arc<ServerConfig>>,
+    endpoints: BTreeMap<SocketAddr, Endpoint>,
+    connections: BTreeMap<ConnectionAddr, Connection>,
+    recv_buf: Vec<u8>,
+    bandwidth: P2pBandwidth,
+    events: Vec<MioEvent>,
+}
+
+struct Endpoint {
+    socket: UdpSocket,
+    inner: quinn_proto::Endpoint,
+    connections: BTreeMap<ConnectionHandle, ConnectionAddr>,
+}
+
+struct Connection {
+    local: SocketAddr,
+    handle: ConnectionHandle,
+    inner: quinn_proto::Connection,
+    connected: bool,
+    /// The connection is closed on demand, the state machine already knows.
+    closed: bool,
+    streams: BTreeMap<StreamId, quinn_proto::StreamId>,
+    reverse: BTreeMap<quinn_proto::StreamId, StreamId>,
+    /// Streams where the remote peer finished sending.
+    recv_finished: BTreeSet<quinn_proto::StreamId>,
+    next_remote_id: StreamId,
+    /// Data waiting for the stream to be opened or to become writable.
+    pending: BTreeMap<StreamId, PendingWrite>,
+}
+
+#[derive(Default)]
+struct PendingWrite {
+    chunks: VecDeque<(Box<[u8]>, usize)>,
+    fin: bool,
+}
+
+enum FlushResult {
+    Blocked,
+    Done,
+    Failed,
+}
+
+fn transport_config() -> Arc<TransportConfig> {
+    let mut config = TransportConfig::default();
+    config
+        .max_idle_timeout(IdleTimeout::try_from(IDLE_TIMEOUT).ok())
+        .keep_alive_interval(Some(KEEP_ALIVE_INTERVAL))
+        .max_concurrent_uni_streams(VarInt::from_u32(0))
+        .allow_spin(false);
+    Arc::new(config)
+}
+
+fn send_to(socket: &UdpSocket, bandwidth: &P2pBandwidth, buf: &[u8], destination: SocketAddr) {
+    match socket.send_to(buf, destination) {
+        Ok(len) => {
+            let peer = P2pBandwidthPeer::Libp2p(destination);
+            bandwidth.record(peer, P2pBandwidthDirection::Outbound, len);
+        }
+        // the datagram is lost, QUIC will retransmit it
+        Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+        Err(err) => {
+            openmina_core::debug!(
+                openmina_core::log::system_time();
+                summary = "failed to send QUIC datagram",
+                addr = openmina_core::log::inner::field::display(destination),
+                error = openmina_core::log::inner::field::display(err),
+            );
+        }
+    }
+}
+
+impl Quic {
+    pub fn new(keypair: &Keypair, bandwidth: P2pBandwidth) -> Self {
+        let transport = transport_config();
+        let client_config = match libp2p_tls::make_client_config(keypair, None) {
+            Ok(tls) => {
+                let mut config = ClientConfig::new(Arc::new(tls));
+                config.transport_config(transport.clone());
+                Some(config)
+            }
+            Err(err) => {
+                MioError::Quic(err.to_string()).report();
+                None
+            }
+        };
+        let server_config = match libp2p_tls::make_server_config(keypair) {
+            Ok(tls) => {
+                let mut config = ServerConfig::with_crypto(Arc::new(tls));
+                config.transport_config(transport);
+                Some(Arc::new(config))
+            }
+            Err(err) => {
+                MioError::Quic(err.to_string()).report();
+                None
+            }
+        };
+
+        Quic {
+            client_config,
+            server_config,
+            endpoints: BTreeMap::default(),
+            connections: BTreeMap::default(),
+            recv_buf: vec![0; MAX_DATAGRAM_SIZE],
+            bandwidth,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn take_events(&mut self) -> Vec<MioEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Time until the earliest connection timer fires.
+    pub fn timeout(&mut self, now: Instant) -> Option<Duration> {
+        self.connections
+            .values_mut()
+            .filter_map(|connection| connection.inner.poll_timeout())
+            .min()
+            .map(|timeout| timeout.saturating_duration_since(now))
+    }
+
+    pub fn handle_timeouts(&mut self, now: Instant) {
+        let expired = self
+            .connections
+            .iter_mut()
+            .filter_map(|(addr, connection)| {
+                let timeout = connection.inner.poll_timeout()?;
+                (timeout <= now).then_some(*addr)
+            })
+            .collect::<Vec<_>>();
+        for addr in expired {
+            if let Some(connection) = self.connections.get_mut(&addr) {
+                connection.inner.handle_timeout(now);
+            }
+            self.drive(addr, now);
+        }
+    }
+
+    pub fn listen(
+        &mut self,
+        addr: SocketAddr,
+        registry: &mio::Registry,
+        tokens: &mut TokenRegistry,
+    ) {
+        let Some(server_config) = self.server_config.clone() else {
+            self.events.push(MioEvent::ListenerError {
+                listener: addr,
+                error: "QUIC is not configured".to_owned(),
+            });
+            return;
+        };
+        match self.bind(addr, Some(server_config), registry, tokens) {
+            Ok(_) => self.events.push(MioEvent::ListenerReady { listener: addr }),
+            Err(err) => {
+                self.events.push(MioEvent::ListenerError {
+                    listener: addr,
+                    error: err.to_string(),
+                });
+                MioError::Listen(addr, err).report();
+            }
+        }
+    }
+
+    fn bind(
+        &mut self,
+        addr: SocketAddr,
+        server_config: Option<Arc<ServerConfig>>,
+        registry: &mio::Registry,
+        tokens: &mut TokenRegistry,
+    ) -> io::Result<SocketAddr> {
+        let mut socket = UdpSocket::bind(addr)?;
+        let local = socket.local_addr()?;
+        registry.register(
+            &mut socket,
+            tokens.register(Token::Quic(local)),
+            mio::Interest::READABLE,
+        )?;
+        let inner =
+            quinn_proto::Endpoint::new(Arc::new(EndpointConfig::default()), server_config, true);
+        self.endpoints.insert(
+            local,
+            Endpoint {
+                socket,
+                inner,
+                connections: BTreeMap::default(),
+            },
+        );
+        Ok(local)
+    }
+
+    pub fn connect(
+        &mut self,
+        remote: SocketAddr,
+        registry: &mio::Registry,
+        tokens: &mut TokenRegistry,
+        now: Instant,
+    ) {
+        let addr = ConnectionAddr {
+            sock_addr: remote,
+            incoming: false,
+        };
+        let Some(client_config) = self.client_config.clone() else {
+            self.events.push(MioEvent::QuicConnectionDidConnect(
+                addr,
+                Err("QUIC is not configured".to_owned()),
+            ));
+            return;
+        };
+
+        // dial from the listening socket if there is one, it helps with NAT
+        let local = self
+            .endpoints
+            .keys()
+            .find(|local| local.is_ipv4() == remote.is_ipv4())
+            .copied();
+        let local = match local {
+            Some(local) => local,
+            None => {
+                let ip = if remote.is_ipv4() {
+                    IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+                } else {
+                    IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+                };
+                match self.bind(SocketAddr::new(ip, 0), None, registry, tokens) {
+                    Ok(local) => local,
+                    Err(err) => {
+                        self.events.push(MioEvent::QuicConnectionDidConnect(
+                            addr,
+                            Err(err.to_string()),
+                        ));
+                        return;
+                    }
+                }
+            }
+        };
+        let Some(endpoint) = self.endpoints.get_mut(&local) else {
+            return;
+        };
+
+        // libp2p doesn't use server names, the peer id is checked by the state machine
+        match endpoint.inner.connect(client_config, remote, "l") {
+            Ok((handle, inner)) => {
+                self.insert(addr, Connection::new(local, handle, inner, false));
+                self.drive(addr, now);
+            }
+            Err(err) => {
+                self.events.push(MioEvent::QuicConnectionDidConnect(
+                    addr,
+                    Err(err.to_string()),
+                ));
+            }
+        }
+    }
+
+    fn insert(&mut self, addr: ConnectionAddr, connection: Connection) {
+        if let Some(old) = self.connections.remove(&addr) {
+            if let Some(endpoint) = self.endpoints.get_mut(&old.local) {
+                endpoint.connections.remove(&old.handle);
+            }
+        }
+        if let Some(endpoint) = self.endpoints.get_mut(&connection.local) {
+            endpoint.connections.insert(connection.handle, addr);
+        }
+        self.connections.insert(addr, connection);
+    }
+
+    pub fn send(
+        &mut self,
+        addr: ConnectionAddr,
+        stream_id: StreamId,
+        data: Box<[u8]>,
+        fin: bool,
+        now: Instant,
+    ) {
+        let Some(connection) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        let pending = connection.pending.entry(stream_id).or_default();
+        if !data.is_empty() {
+            pending.chunks.push_back((data, 0));
+        }
+        pending.fin |= fin;
+        connection.flush(stream_id);
+        self.drive(addr, now);
+    }
+
+    /// Closes the connection, it is kept until drained to answer the peer.
+    pub fn disconnect(&mut self, addr: ConnectionAddr, now: Instant) {
+        let Some(connection) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        connection.closed = true;
+        connection
+            .inner
+            .close(now, VarInt::from_u32(0), Bytes::new());
+        self.drive(addr, now);
+    }
+
+    pub fn contains(&self, addr: &ConnectionAddr) -> bool {
+        self.connections.contains_key(addr)
+    }
+
+    /// Reads all datagrams from the socket. New connections from an address
+    /// for which `is_used` returns true are closed right away, without
+    /// being reported.
+    pub fn recv(
+        &mut self,
+        local: SocketAddr,
+        now: Instant,
+        is_used: impl Fn(&ConnectionAddr) -> bool,
+    ) {
+        let Some(endpoint) = self.endpoints.get_mut(&local) else {
+            return;
+        };
+        let mut touched = BTreeSet::new();
+        let mut new_connections = Vec::new();
+        loop {
+            let (len, remote) = match endpoint.socket.recv_from(&mut self.recv_buf) {
+                Ok(v) => v,
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => break,
+                // ICMP errors of previous datagrams are reported on the socket
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        io::ErrorKind::Interrupted
+                            | io::ErrorKind::ConnectionRefused
+                            | io::ErrorKind::ConnectionReset
+                    ) =>
+                {
+                    continue
+                }
+                Err(err) => {
+                    MioError::Quic(err.to_string()).report();
+                    break;
+                }
+            };
+            let peer = P2pBandwidthPeer::Libp2p(remote);
+            self.bandwidth
+                .record(peer, P2pBandwidthDirection::Inbound, len);
+
+            let data = BytesMut::from(&self.recv_buf[..len]);
+            match endpoint.inner.handle(now, remote, None, None, data) {
+                Some((handle, DatagramEvent::NewConnection(inner))) => {
+                    let addr = ConnectionAddr {
+                        sock_addr: remote,
+                        incoming: true,
+                    };
+                    let mut connection = Connection::new(local, handle, inner, true);
+                    if is_used(&addr) {
+                        connection.closed = true;
+                        connection.inner.close(
+                            now,
+                            VarInt::from_u32(0),
+                            Bytes::from_static(b"address in use"),
+                        );
+                    }
+                    new_connections.push((addr, connection));
+                }
+                Some((handle, DatagramEvent::ConnectionEvent(event))) => {
+                    let connection = endpoint
+                        .connections
+                        .get(&handle)
+                        .and_then(|addr| Some((*addr, self.connections.get_mut(addr)?)))
+                        .filter(|(_, connection)| connection.handle == handle);
+                    if let Some((addr, connection)) = connection {
+                        connection.inner.handle_event(event);
+                        touched.insert(addr);
+                    }
+                }
+                None => {}
+            }
+        }
+        while let Some(transmit) = endpoint.inner.poll_transmit() {
+            send_to(
+                &endpoint.socket,
+                &self.bandwidth,
+                &transmit.contents,
+                transmit.destination,
+            );
+        }
+
+        for (addr, connection) in new_connections {
+            self.insert(addr, connection);
+            touched.insert(addr);
+        }
+        for addr in touched {
+            self.drive(addr, now);
+        }
+    }
+
+    /// Processes the connection events and sends its datagrams.
+    fn drive(&mut self, addr: ConnectionAddr, now: Instant) {
+        let Some(connection) = self.connections.get_mut(&addr) else {
+            return;
+        };
+        let Some(endpoint) = self.endpoints.get_mut(&connection.local) else {
+            return;
+        };
+
+        while let Some(event) = connection.inner.poll_endpoint_events() {
+            if let Some(event) = endpoint.inner.handle_event(connection.handle, event) {
+                connection.inner.handle_event(event);
+            }
+        }
+
+        while let Some(event) = connection.inner.poll() {
+            match event {
+                Event::Connected if connection.closed => {}
+                Event::Connected => {
+                    connection.connected = true;
+                    let result = connection.peer_id();
+                    self.events
+                        .push(MioEvent::QuicConnectionDidConnect(addr, result));
+                }
+                Event::ConnectionLost { reason } => {
+                    if !connection.closed {
+                        connection.closed = true;
+                        let error = Err(reason.to_string());
+                        self.events.push(if connection.connected {
+                            MioEvent::ConnectionDidClose(addr, error)
+                        } else {
+                            MioEvent::QuicConnectionDidConnect(addr, error)
+                        });
+                    }
+                }
+                Event::Stream(StreamEvent::Opened { dir: Dir::Bi }) => {
+                    connection.accept_streams();
+                }
+                Event::Stream(StreamEvent::Readable { id }) => {
+                    connection.read(addr, id, &mut self.events);
+                }
+                Event::Stream(StreamEvent::Writable { id }) => {
+                    if let Some(stream_id) = connection.reverse.get(&id).copied() {
+                        connection.flush(stream_id);
+                    }
+                }
+                Event::Stream(StreamEvent::Available { dir: Dir::Bi }) => {
+                    connection.flush_all();
+                }
+                _ => {}
+            }
+        }
+
+        while let Some(transmit) = connection.inner.poll_transmit(now, 1) {
+            send_to(
+                &endpoint.socket,
+                &self.bandwidth,
+                &transmit.contents,
+                transmit.destination,
+            );
+        }
+
+        if connection.inner.is_drained() {
+            endpoint.connections.remove(&connection.handle);
+            self.connections.remove(&addr);
+        }
+    }
+}
+
+impl Connection {
+    fn new(
+        local: SocketAddr,
+        handle: ConnectionHandle,
+        inner: quinn_proto::Connection,
+        incoming: bool,
+    ) -> Self {
+        Connection {
+            local,
+            handle,
+            inner,
+            connected: false,
+            closed: false,
+            streams: BTreeMap::default(),
+            reverse: BTreeMap::default(),
+            recv_finished: BTreeSet::default(),
+            // the remote dialer takes odd ids, the remote listener even ones
+            next_remote_id: if incoming { 1 } else { 2 },
+            pending: BTreeMap::default(),
+        }
+    }
+
+    fn peer_id(&self) -> Result<PeerId, String> {
+        let identity = self
+            .inner
+            .crypto_session()
+            .peer_identity()
+            .ok_or_else(|| "no peer certificate".to_owned())?;
+        let certificates = identity
+            .downcast::<Vec<rustls::Certificate>>()
+            .map_err(|_| "unexpected peer identity".to_owned())?;
+        let certificate = certificates
+            .first()
+            .ok_or_else(|| "empty peer certificate chain".to_owned())?;
+        let certificate =
+            libp2p_tls::certificate::parse(certificate).map_err(|err| err.to_string())?;
+        PeerId::try_from(certificate.peer_id()).map_err(|err| err.to_string())
+    }
+
+    fn accept_streams(&mut self) {
+        while let Some(stream) = self.inner.streams().accept(Dir::Bi) {
+            let stream_id = self.next_remote_id;
+            self.next_remote_id = self.next_remote_id.wrapping_add(2);
+            self.streams.insert(stream_id, stream);
+            self.reverse.insert(stream, stream_id);
+        }
+    }
+
+    fn read(
+        &mut self,
+        addr: ConnectionAddr,
+        stream: quinn_proto::StreamId,
+        events: &mut Vec<MioEvent>,
+    ) {
+        if !self.reverse.contains_key(&stream) {
+            self.accept_streams();
+        }
+        let Some(stream_id) = self.reverse.get(&stream).copied() else {
+            return;
+        };
+
+        let mut recv = self.inner.recv_stream(stream);
+        let Ok(mut chunks) = recv.read(true) else {
+            return;
+        };
+        let mut data = Vec::new();
+        let mut fin = false;
+        loop {
+            match chunks.next(usize::MAX) {
+                Ok(Some(chunk)) => data.extend_from_slice(&chunk.bytes),
+                Err(ReadError::Blocked) => break,
+                // finished or reset by the peer
+                Ok(None) | Err(_) => {
+                    fin = true;
+                    break;
+                }
+            }
+        }
+        let _ = chunks.finalize();
+
+        if fin {
+            self.recv_finished.insert(stream);
+            self.forget_if_closed(stream_id, stream);
+        }
+        if !data.is_empty() || fin {
+            events.push(MioEvent::QuicStreamDataDidReceive(
+                addr,
+                stream_id,
+                data.into(),
+                fin,
+            ));
+        }
+    }
+
+    fn flush_all(&mut self) {
+        let stream_ids = self.pending.keys().copied().collect::<Vec<_>>();
+        for stream_id in stream_ids {
+            self.flush(stream_id);
+        }
+    }
+
+    fn flush(&mut self, stream_id: StreamId) {
+        let Some(pending) = self.pending.get_mut(&stream_id) else {
+            return;
+        };
+        let stream = match self.streams.get(&stream_id) {
+            Some(stream) => *stream,
+            None => match self.inner.streams().open(Dir::Bi) {
+                Some(stream) => {
+                    self.streams.insert(stream_id, stream);
+                    self.reverse.insert(stream, stream_id);
+                    stream
+                }
+                // the peer doesn't allow more streams yet, retried on `StreamEvent::Available`
+                None => return,
+            },
+        };
+
+        let mut send = self.inner.send_stream(stream);
+        let mut result = FlushResult::Done;
+        while let Some((buf, offset)) = pending.chunks.front_mut() {
+            match send.write(&buf[*offset..]) {
+                Ok(len) => {
+                    *offset += len;
+                    if *offset == buf.len() {
+                        pending.chunks.pop_front();
+                    }
+                }
+                Err(WriteError::Blocked) => {
+                    result = FlushResult::Blocked;
+                    break;
+                }
+                Err(_) => {
+                    result = FlushResult::Failed;
+                    break;
+                }
+            }
+        }
+        let fin = pending.fin;
+        if matches!(result, FlushResult::Done) && fin {
+            let _ = send.finish();
+        }
+
+        match result {
+            FlushResult::Blocked => {}
+            FlushResult::Done if !fin => {
+                self.pending.remove(&stream_id);
+            }
+            FlushResult::Done | FlushResult::Failed => {
+                // our side of the stream is over, the same id may be used for a new stream
+                self.pending.remove(&stream_id);
+                self.streams.remove(&stream_id);
+                self.forget_if_closed(stream_id, stream);
+            }
+        }
+    }
+
+    /// Forgets the stream when both sides are finished.
+    fn forget_if_closed(&mut self, stream_id: StreamId, stream: quinn_proto::StreamId) {
+        let sending = self.streams.get(&stream_id) == Some(&stream);
+        if !sending && self.recv_finished.remove(&stream) {
+            self.reverse.remove(&stream);
+        }
+    }
+}
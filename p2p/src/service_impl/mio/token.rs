@@ -7,6 +7,8 @@ pub enum Token {
     Waker,
     Listener(SocketAddr),
     Connection(ConnectionAddr),
+    /// UDP socket of a QUIC endpoint.
+    Quic(SocketAddr),
 }
 
 #[derive(Default)]
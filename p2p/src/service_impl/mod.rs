@@ -1,3 +1,4 @@
+pub mod bandwidth;
 #[cfg(feature = "p2p-libp2p")]
 pub mod mio;
 #[cfg(feature = "p2p-webrtc")]
@@ -51,7 +52,11 @@ pub mod webrtc {
 
         fn peers(&mut self) -> &mut BTreeMap<PeerId, PeerState>;
 
-        fn init<S: TaskSpawner>(_secret_key: SecretKey, _spawner: S) -> P2pServiceCtx {
+        fn init<S: TaskSpawner>(
+            _secret_key: SecretKey,
+            _spawner: S,
+            _bandwidth: super::bandwidth::P2pBandwidth,
+        ) -> P2pServiceCtx {
             let (cmd_sender, _) = mpsc::unbounded_channel();
             P2pServiceCtx {
                 cmd_sender,
@@ -55,6 +55,7 @@ mod imports {
 use imports::*;
 pub use imports::{webrtc_signal_send, RTCSignalingError};
 
+use super::bandwidth::{P2pBandwidth, P2pBandwidthDirection, P2pBandwidthPeer};
 use super::TaskSpawner;
 
 /// 16KB.
@@ -217,6 +218,15 @@ async fn sleep(dur: Duration) {
     fut.await
 }
 
+/// Waits until the bandwidth limits allow sending some data. WebRTC chunks
+/// are sent whole, so the budget may go into debt by the rest of the chunk.
+async fn bandwidth_wait(bandwidth: &P2pBandwidth, peer: P2pBandwidthPeer, len: usize) {
+    let direction = P2pBandwidthDirection::Outbound;
+    while bandwidth.allowance(peer, direction, len) == 0 {
+        sleep(bandwidth.delay(peer, direction, len)).await;
+    }
+}
+
 async fn wait_for_ice_gathering_complete(pc: &mut RTCConnection) {
     let timeout = sleep(Duration::from_secs(3));
 
@@ -231,6 +241,7 @@ async fn peer_start(
     args: PeerAddArgs,
     abort: broadcast::Receiver<()>,
     closed: broadcast::Sender<()>,
+    bandwidth: P2pBandwidth,
 ) {
     let PeerAddArgs {
         peer_id,
@@ -421,7 +432,7 @@ async fn peer_start(
 
     let _ = main_channel.close().await;
 
-    peer_loop(peer_id, event_sender, cmd_receiver, pc, abort).await
+    peer_loop(peer_id, event_sender, cmd_receiver, pc, abort, bandwidth).await
 }
 
 struct Channel {
@@ -490,7 +501,9 @@ async fn peer_loop(
     mut cmd_receiver: mpsc::UnboundedReceiver<PeerCmd>,
     mut pc: RTCConnection,
     abort: broadcast::Receiver<()>,
+    bandwidth: P2pBandwidth,
 ) {
+    let bandwidth_peer = P2pBandwidthPeer::WebRTC(peer_id);
     // TODO(binier): maybe use small_vec (stack allocated) or something like that.
     let mut channels = Channels::new();
     let mut msg_buf = MsgBuffer::new(64 * 1024);
@@ -649,8 +662,16 @@ async fn peer_loop(
                     let mut len = 0;
                     let mut buf = Vec::new();
                     let event_sender_clone = event_sender.clone();
+                    let bandwidth_clone = bandwidth.clone();
 
                     chan.on_message(move |mut data| {
+                        // data channels can't be paused, so inbound traffic is
+                        // only accounted, delaying reads on other transports
+                        bandwidth_clone.record(
+                            bandwidth_peer,
+                            P2pBandwidthDirection::Inbound,
+                            data.len(),
+                        );
                         while !data.is_empty() {
                             let res = match process_msg(chan_id, &mut buf, &mut len, &mut data) {
                                 Ok(None) => continue,
@@ -665,6 +686,7 @@ async fn peer_loop(
                     });
 
                     let event_sender = event_sender.clone();
+                    let bandwidth = bandwidth.clone();
                     let fut = async move {
                         // Add a delay for sending messages after channel
                         // was opened. Some initial messages get lost otherwise.
@@ -679,6 +701,12 @@ async fn peer_loop(
                                 let Some(chunk) = chunks.next() else {
                                     break Ok(());
                                 };
+                                bandwidth_wait(&bandwidth, bandwidth_peer, chunk.len()).await;
+                                bandwidth.record(
+                                    bandwidth_peer,
+                                    P2pBandwidthDirection::Outbound,
+                                    chunk.len(),
+                                );
                                 if let Err(err) = chan
                                     .send(&chunk)
                                     .await
@@ -731,7 +759,11 @@ pub trait P2pServiceWebrtc: redux::Service {
 
     fn peers(&mut self) -> &mut BTreeMap<PeerId, PeerState>;
 
-    fn init<S: TaskSpawner>(secret_key: SecretKey, spawner: S) -> P2pServiceCtx {
+    fn init<S: TaskSpawner>(
+        secret_key: SecretKey,
+        spawner: S,
+        bandwidth: P2pBandwidth,
+    ) -> P2pServiceCtx {
         const MAX_PEERS: usize = 500;
         let (cmd_sender, mut cmd_receiver) = mpsc::unbounded_channel();
 
@@ -749,6 +781,7 @@ pub trait P2pServiceWebrtc: redux::Service {
                         let conn_permits = conn_permits.clone();
                         let peer_id = args.peer_id;
                         let event_sender = args.event_sender.clone();
+                        let bandwidth = bandwidth.clone();
                         spawn_local(async move {
                             let Ok(_permit) = conn_permits.try_acquire() else {
                                 // state machine shouldn't allow this to happen.
@@ -763,10 +796,11 @@ pub trait P2pServiceWebrtc: redux::Service {
                                 event_sender_clone(P2pConnectionEvent::Closed(peer_id).into());
                             });
                             tokio::select! {
-                                _ = peer_start(api, args, abort.resubscribe(), closed_tx.clone()) => {}
+                                _ = peer_start(api, args, abort.resubscribe(), closed_tx.clone(), bandwidth.clone()) => {}
                                 _ = abort.recv() => {
                                 }
                             }
+                            bandwidth.remove_peer(&P2pBandwidthPeer::WebRTC(peer_id));
 
                             // delay dropping permit to give some time for cleanup.
                             sleep(Duration::from_millis(100)).await;
@@ -14,13 +14,19 @@ use super::mio::MioService;
 #[cfg(feature = "p2p-libp2p")]
 use crate::{P2pMioService, P2pNetworkService, P2pNetworkServiceError};
 
-use super::{webrtc::P2pServiceWebrtc, TaskSpawner};
+use super::{
+    bandwidth::{P2pBandwidth, P2pBandwidthService, P2pBandwidthStats},
+    webrtc::P2pServiceWebrtc,
+    TaskSpawner,
+};
 
 pub struct P2pServiceCtx {
     pub sec_key: SecretKey,
     pub webrtc: super::webrtc::P2pServiceCtx,
     #[cfg(feature = "p2p-libp2p")]
     pub mio: MioService,
+    /// Bandwidth limiter shared by all transports.
+    pub bandwidth: P2pBandwidth,
 }
 
 pub trait P2pServiceWebrtcWithLibp2p: P2pServiceWebrtc {
@@ -29,12 +35,19 @@ pub trait P2pServiceWebrtcWithLibp2p: P2pServiceWebrtc {
 
     fn connections(&self) -> BTreeSet<PeerId>;
 
+    fn bandwidth(&self) -> &P2pBandwidth;
+
     fn init<S: TaskSpawner>(sec_key: SecretKey, spawner: S) -> P2pServiceCtx {
+        let bandwidth = P2pBandwidth::default();
         P2pServiceCtx {
             sec_key: sec_key.clone(),
             #[cfg(feature = "p2p-libp2p")]
-            mio: MioService::pending(sec_key.clone().try_into().expect("valid keypair")),
-            webrtc: <Self as P2pServiceWebrtc>::init(sec_key, spawner),
+            mio: MioService::pending(
+                sec_key.clone().try_into().expect("valid keypair"),
+                bandwidth.clone(),
+            ),
+            webrtc: <Self as P2pServiceWebrtc>::init(sec_key, spawner, bandwidth.clone()),
+            bandwidth,
         }
     }
 
@@ -141,6 +154,12 @@ impl<T: P2pServiceWebrtcWithLibp2p> P2pConnectionService for T {
     }
 }
 
+impl<T: P2pServiceWebrtcWithLibp2p> P2pBandwidthService for T {
+    fn bandwidth_stats(&self) -> P2pBandwidthStats {
+        P2pServiceWebrtcWithLibp2p::bandwidth(self).stats()
+    }
+}
+
 impl<T: P2pServiceWebrtcWithLibp2p> P2pDisconnectionService for T {
     fn disconnect(&mut self, peer_id: PeerId) {
         // By removing the peer, `cmd_sender` gets dropped which will
@@ -225,6 +244,7 @@ impl P2pServiceCtx {
                 cmd_sender: mpsc::unbounded_channel().0,
                 peers: Default::default(),
             },
+            bandwidth: Default::default(),
         }
     }
 }
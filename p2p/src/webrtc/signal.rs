@@ -51,6 +51,8 @@ pub enum RejectionReason {
     AlreadyConnected,
     #[error("self connection detected")]
     ConnectingToSelf,
+    #[error("peer is banned")]
+    Banned,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -93,6 +95,7 @@ impl RejectionReason {
             Self::PeerCapacityFull => false,
             Self::AlreadyConnected => true,
             Self::ConnectingToSelf => false,
+            Self::Banned => false,
         }
     }
 }
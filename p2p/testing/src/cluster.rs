@@ -15,7 +15,7 @@ use p2p::{
         P2pConnectionOutgoingInitOpts, P2pConnectionOutgoingInitOptsParseError,
     },
     identity::SecretKey,
-    P2pCallbacks, P2pConfig, P2pMeshsubConfig, P2pState, PeerId,
+    P2pBanConfig, P2pCallbacks, P2pConfig, P2pMeshsubConfig, P2pState, PeerId,
 };
 use redux::SystemTime;
 use tokio::sync::mpsc;
@@ -41,6 +41,9 @@ pub enum PeerIdConfig {
 #[derive(Debug, Clone, derive_more::From)]
 pub enum Listener {
     Rust(RustNodeId),
+    /// QUIC listener of the Rust node.
+    #[from(ignore)]
+    RustQuic(RustNodeId),
     Libp2p(Libp2pNodeId),
     Multiaddr(Multiaddr),
     SocketPeerId(SocketAddr, PeerId),
@@ -299,6 +302,7 @@ impl Cluster {
                         peer_id,
                         host,
                         port,
+                        transport: Default::default(),
                     },
                 ))
             }
@@ -312,9 +316,11 @@ impl Cluster {
                         peer_id,
                         host,
                         port,
+                        transport: Default::default(),
                     },
                 ))
             }
+            Listener::RustQuic(id) => self.rust_node(id).rust_quic_dial_opts(self.ip),
             Listener::Multiaddr(ref maddr) => {
                 Ok(P2pConnectionOutgoingInitOpts::LibP2P(maddr.try_into()?))
             }
@@ -344,6 +350,11 @@ impl Cluster {
             Self::secret_key(config.peer_id, self.rust_nodes.len(), RUST_NODE_SIG_BYTE);
         let libp2p_port = self.next_port()?;
         let listen_port = self.next_port()?;
+        let quic_port = if config.quic {
+            Some(self.next_port()?)
+        } else {
+            None
+        };
         let initial_peers = config
             .initial_peers
             .into_iter()
@@ -351,6 +362,7 @@ impl Cluster {
             .collect::<Result<_>>()?;
         let config = P2pConfig {
             libp2p_port: Some(libp2p_port),
+            libp2p_quic_port: quic_port,
             listen_port: Some(listen_port),
             identity_pub_key: secret_key.public_key(),
             initial_peers,
@@ -360,6 +372,11 @@ impl Cluster {
             timeouts: config.timeouts,
             limits: config.limits,
             meshsub: P2pMeshsubConfig::default(),
+            ban: P2pBanConfig {
+                initial_bans: config.bans,
+                ..Default::default()
+            },
+            peer_store: Default::default(),
         };
 
         Ok((config, secret_key))
@@ -443,6 +460,7 @@ impl Cluster {
     fn rust_dial_opts(&self, listener: Listener) -> Result<P2pConnectionOutgoingInitOpts> {
         match listener {
             Listener::Rust(id) => Ok(self.rust_node(id).rust_dial_opts(self.ip)),
+            Listener::RustQuic(id) => self.rust_node(id).rust_quic_dial_opts(self.ip),
             Listener::Libp2p(id) => Ok(self.libp2p_node(id).rust_dial_opts(self.ip)),
             Listener::Multiaddr(maddr) => Ok(maddr.try_into().map_err(Error::AddrParse)?),
             Listener::SocketPeerId(socket, peer_id) => Ok(P2pConnectionOutgoingInitOpts::LibP2P(
@@ -454,6 +472,12 @@ impl Cluster {
     fn libp2p_dial_opts(&self, listener: Listener) -> Result<Multiaddr> {
         match listener {
             Listener::Rust(id) => Ok(self.rust_node(id).libp2p_dial_opts(self.ip)),
+            Listener::RustQuic(id) => self
+                .rust_node(id)
+                .rust_quic_dial_opts(self.ip)?
+                .to_string()
+                .parse()
+                .map_err(|err: libp2p::multiaddr::Error| Error::Other(err.to_string())),
             Listener::Libp2p(id) => Ok(self.libp2p_node(id).libp2p_dial_opts(self.ip)),
             Listener::Multiaddr(maddr) => Ok(maddr),
             Listener::SocketPeerId(socket, peer_id) => {
@@ -10,6 +10,8 @@ use openmina_core::{
     ActionEvent,
 };
 use p2p::{
+    ban::P2pBanAction,
+    ban_effectful::P2pBanEffectfulAction,
     bootstrap::P2pNetworkKadBootstrapState,
     channels::{
         best_tip::P2pChannelsBestTipAction,
@@ -37,6 +39,8 @@ use p2p::{
         P2pNetworkIdentifyStreamAction,
     },
     peer::P2pPeerAction,
+    peer_store::P2pPeerStoreAction,
+    peer_store_effectful::P2pPeerStoreEffectfulAction,
     MioEvent, P2pAction, P2pEffectfulAction, P2pEvent, P2pNetworkKadBootstrapAction,
     P2pNetworkKadEffectfulAction, P2pNetworkKadRequestAction, P2pNetworkKademliaAction,
     P2pNetworkKademliaStreamAction, P2pNetworkSchedulerAction, P2pNetworkYamuxAction, P2pState,
@@ -126,6 +130,9 @@ impl_p2p_state_access!(State, p2p::P2pNetworkKadState);
 impl_p2p_state_access!(State, p2p::P2pNetworkSchedulerState);
 impl_p2p_state_access!(State, p2p::P2pLimits);
 impl_p2p_state_access!(State, p2p::P2pNetworkPubsubState);
+impl_p2p_state_access!(State, p2p::P2pNetworkBitswapState);
+impl_p2p_state_access!(State, p2p::P2pNetworkNodeStatusState);
+impl_p2p_state_access!(State, p2p::P2pNetworkPingState);
 impl_p2p_state_access!(State, p2p::P2pConfig);
 
 impl P2pStateTrait for State {}
@@ -257,6 +264,19 @@ pub(super) fn event_effect(store: &mut crate::redux::Store, event: P2pEvent) ->
             MioEvent::ConnectionDidCloseOnDemand(addr) => {
                 SubStore::dispatch(store, P2pNetworkSchedulerAction::Prune { addr })
             }
+            MioEvent::QuicConnectionDidConnect(addr, result) => SubStore::dispatch(
+                store,
+                P2pNetworkSchedulerAction::QuicDidConnect { addr, result },
+            ),
+            MioEvent::QuicStreamDataDidReceive(addr, stream_id, data, fin) => SubStore::dispatch(
+                store,
+                P2pNetworkSchedulerAction::QuicIncomingData {
+                    addr,
+                    stream_id,
+                    data,
+                    fin,
+                },
+            ),
         },
         _ => false,
     }
@@ -294,6 +314,9 @@ impl_from_p2p!(p2p::P2pNetworkPnetAction);
 impl_from_p2p!(p2p::P2pNetworkNoiseAction);
 impl_from_p2p!(p2p::connection::incoming::P2pConnectionIncomingAction);
 impl_from_p2p!(p2p::P2pNetworkPubsubAction);
+impl_from_p2p!(p2p::P2pNetworkBitswapAction);
+impl_from_p2p!(p2p::P2pNetworkNodeStatusAction);
+impl_from_p2p!(p2p::P2pNetworkPingAction);
 impl_from_p2p!(P2pChannelsSignalingDiscoveryAction);
 impl_from_p2p!(P2pChannelsSignalingExchangeAction);
 impl_from_p2p!(P2pChannelsTransactionAction);
@@ -301,6 +324,8 @@ impl_from_p2p!(P2pChannelsSnarkAction);
 impl_from_p2p!(p2p::P2pNetworkRpcAction);
 impl_from_p2p!(P2pChannelsRpcAction);
 impl_from_p2p!(P2pDisconnectionAction);
+impl_from_p2p!(P2pBanAction);
+impl_from_p2p!(P2pPeerStoreAction);
 impl_from_p2p!(P2pChannelsBestTipAction);
 impl_from_p2p!(P2pChannelsSnarkJobCommitmentAction);
 impl_from_p2p!(P2pChannelsStreamingRpcAction);
@@ -313,6 +338,8 @@ impl_from_p2p!(effectful p2p::P2pNetworkPubsubEffectfulAction);
 impl_from_p2p!(effectful P2pNetworkIdentifyStreamEffectfulAction);
 impl_from_p2p!(effectful P2pConnectionOutgoingEffectfulAction);
 impl_from_p2p!(effectful P2pDisconnectionEffectfulAction);
+impl_from_p2p!(effectful P2pBanEffectfulAction);
+impl_from_p2p!(effectful P2pPeerStoreEffectfulAction);
 impl_from_p2p!(effectful P2pChannelsEffectfulAction);
 
 impl p2p::P2pActionTrait<State> for Action {}
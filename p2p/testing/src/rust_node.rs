@@ -1,11 +1,19 @@
 use std::{
+    collections::BTreeMap,
+    net::IpAddr,
     pin::Pin,
     task::{ready, Context, Poll},
     time::Duration,
 };
 
 use futures::Stream;
-use p2p::{P2pAction, P2pEvent, P2pLimits, P2pState, P2pTimeouts, PeerId};
+use p2p::{
+    ban::P2pBan,
+    connection::outgoing::{
+        Libp2pTransport, P2pConnectionOutgoingInitLibp2pOpts, P2pConnectionOutgoingInitOpts,
+    },
+    P2pAction, P2pEvent, P2pLimits, P2pState, P2pTimeouts, PeerId,
+};
 use redux::{Effects, EnablingCondition, Reducer, SubStore};
 use tokio::sync::mpsc;
 
@@ -27,6 +35,10 @@ pub struct RustNodeConfig {
     pub timeouts: P2pTimeouts,
     pub limits: P2pLimits,
     pub discovery: bool,
+    /// Listen for QUIC connections too.
+    pub quic: bool,
+    /// Bans the node starts with, as if loaded from the persisted ban list.
+    pub bans: BTreeMap<PeerId, P2pBan>,
     pub override_fn: Option<Effects<State, ClusterService, Action>>,
     pub override_reducer: Option<Reducer<State, Action>>,
 }
@@ -60,6 +72,16 @@ impl RustNodeConfig {
         self
     }
 
+    pub fn with_quic(mut self, quic: bool) -> Self {
+        self.quic = quic;
+        self
+    }
+
+    pub fn with_bans(mut self, bans: BTreeMap<PeerId, P2pBan>) -> Self {
+        self.bans = bans;
+        self
+    }
+
     pub fn with_override(mut self, override_fn: Effects<State, ClusterService, Action>) -> Self {
         self.override_fn = Some(override_fn);
         self
@@ -104,6 +126,27 @@ impl RustNode {
         &self.store.state().0
     }
 
+    pub fn service(&self) -> &ClusterService {
+        &self.store.service
+    }
+
+    pub fn rust_quic_dial_opts(
+        &self,
+        host: IpAddr,
+    ) -> crate::cluster::Result<P2pConnectionOutgoingInitOpts> {
+        let port = self.state().config.libp2p_quic_port.ok_or_else(|| {
+            crate::cluster::Error::Other("QUIC is not enabled for the node".to_string())
+        })?;
+        Ok(P2pConnectionOutgoingInitOpts::LibP2P(
+            P2pConnectionOutgoingInitLibp2pOpts {
+                peer_id: self.peer_id(),
+                host: host.into(),
+                port,
+                transport: Libp2pTransport::Quic,
+            },
+        ))
+    }
+
     fn next_stored_event(&mut self) -> Option<RustNodeEvent> {
         self.store.service.rust_node_event()
     }
@@ -1,11 +1,14 @@
 use std::{collections::VecDeque, time::Instant};
 
 use p2p::{
+    ban::P2pBan,
     identity::SecretKey,
+    peer_store::P2pPeerStoreEntry,
     service_impl::{
-        mio::MioService, webrtc::P2pServiceWebrtc, webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p,
+        bandwidth::P2pBandwidth, mio::MioService, webrtc::P2pServiceWebrtc,
+        webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p,
     },
-    P2pCryptoService, P2pEvent,
+    P2pBanService, P2pCryptoService, P2pEvent, P2pPeerStoreService,
 };
 use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use redux::{Service, TimeService};
@@ -18,8 +21,11 @@ pub struct ClusterService {
     pub event_sender: mpsc::UnboundedSender<P2pEvent>,
     pub cmd_sender: mpsc::UnboundedSender<p2p::service_impl::webrtc::Cmd>,
     mio: MioService,
+    bandwidth: P2pBandwidth,
     peers: std::collections::BTreeMap<p2p::PeerId, p2p::service_impl::webrtc::PeerState>,
     time: Instant,
+    /// Last persisted ban list, as JSON.
+    pub ban_list: Option<String>,
 
     rust_node_events: VecDeque<RustNodeEvent>,
 }
@@ -32,9 +38,13 @@ impl ClusterService {
         cmd_sender: mpsc::UnboundedSender<p2p::service_impl::webrtc::Cmd>,
         time: Instant,
     ) -> Self {
+        let bandwidth = P2pBandwidth::default();
         let mio = {
             let event_sender = event_sender.clone();
-            let mut mio = MioService::pending(secret_key.try_into().expect("valid keypair"));
+            let mut mio = MioService::pending(
+                secret_key.try_into().expect("valid keypair"),
+                bandwidth.clone(),
+            );
             mio.run(move |mio_event| {
                 let _ = event_sender.send(mio_event.into());
                 //.expect("cannot send mio event")
@@ -46,8 +56,10 @@ impl ClusterService {
             event_sender,
             cmd_sender,
             mio,
+            bandwidth,
             peers: Default::default(),
             time,
+            ban_list: None,
 
             rust_node_events: Default::default(),
         }
@@ -78,6 +90,10 @@ impl P2pServiceWebrtcWithLibp2p for ClusterService {
     fn connections(&self) -> std::collections::BTreeSet<p2p::PeerId> {
         Default::default()
     }
+
+    fn bandwidth(&self) -> &P2pBandwidth {
+        &self.bandwidth
+    }
 }
 
 impl P2pServiceWebrtc for ClusterService {
@@ -187,6 +203,20 @@ impl P2pCryptoService for ClusterService {
     }
 }
 
+impl P2pBanService for ClusterService {
+    fn ban_list_persist(&mut self, bans: &std::collections::BTreeMap<p2p::PeerId, P2pBan>) {
+        self.ban_list = Some(serde_json::to_string(bans).expect("serializable ban list"));
+    }
+}
+
+impl P2pPeerStoreService for ClusterService {
+    fn peer_store_persist(
+        &mut self,
+        _peers: &std::collections::BTreeMap<p2p::PeerId, P2pPeerStoreEntry>,
+    ) {
+    }
+}
+
 impl RustNodeEventStore for ClusterService {
     fn store_event(&mut self, event: RustNodeEvent) {
         self.rust_node_events.push_back(event);
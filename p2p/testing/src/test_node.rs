@@ -17,6 +17,7 @@ pub trait TestNode {
             peer_id: self.peer_id(),
             host: host.into(),
             port: self.libp2p_port(),
+            transport: Default::default(),
         })
     }
 
@@ -0,0 +1,270 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use p2p::{
+    ban::{P2pBan, P2pBanAction, P2pBanReason},
+    disconnection::P2pDisconnectionReason,
+    identity::SecretKey,
+    P2pNetworkKadKey, PeerId,
+};
+use p2p_testing::{
+    cluster::{Cluster, ClusterBuilder, Listener},
+    futures::StreamExt,
+    predicates::kad_finished_bootstrap,
+    rust_node::{RustNodeConfig, RustNodeId},
+    stream::ClusterStreamExt,
+    utils::{run_cluster, wait_for_all_nodes_to_listen, wait_for_nodes_to_connect},
+};
+
+fn peer_id(byte: u8) -> PeerId {
+    SecretKey::from_bytes([byte; 32]).public_key().peer_id()
+}
+
+fn ban(peer_id: PeerId, duration: Option<Duration>) -> P2pBanAction {
+    P2pBanAction::Add {
+        peer_id,
+        reason: P2pBanReason::Manual("test".to_owned()),
+        duration,
+    }
+}
+
+fn is_ready(cluster: &Cluster, node: RustNodeId, peer_id: &PeerId) -> bool {
+    cluster
+        .rust_node(node)
+        .state()
+        .peers
+        .get(peer_id)
+        .map_or(false, |peer| peer.status.as_ready().is_some())
+}
+
+fn in_routing_table(cluster: &Cluster, node: RustNodeId, peer_id: &PeerId) -> bool {
+    let key = P2pNetworkKadKey::try_from(peer_id).expect("valid kad key");
+    cluster
+        .rust_node(node)
+        .state()
+        .network
+        .scheduler
+        .discovery_state()
+        .expect("discovery should be enabled")
+        .routing_table
+        .look_up(&key)
+        .is_some()
+}
+
+/// Bans persisted by the node, parsed back from JSON.
+fn persisted_bans(cluster: &Cluster, node: RustNodeId) -> BTreeMap<PeerId, P2pBan> {
+    let ban_list = cluster
+        .rust_node(node)
+        .service()
+        .ban_list
+        .as_ref()
+        .expect("ban list should be persisted");
+    serde_json::from_str(ban_list).expect("ban list should be valid JSON")
+}
+
+/// Tests that banning a connected peer disconnects it, removes it from the
+/// routing table, and that the longest ban wins.
+#[tokio::test]
+async fn ban_disconnects_peer() -> anyhow::Result<()> {
+    std::env::set_var("OPENMINA_DISCOVERY_FILTER_ADDR", "false");
+
+    let mut cluster = ClusterBuilder::default()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let node1 = cluster.add_rust_node(RustNodeConfig::default().with_discovery(true))?;
+    let node2 = cluster.add_rust_node(
+        RustNodeConfig::default()
+            .with_discovery(true)
+            .with_initial_peers([Listener::Rust(node1)]),
+    )?;
+    let peer_id1 = cluster.peer_id(node1);
+
+    let bootstrap_finished = cluster
+        .stream()
+        .take_during(Duration::from_secs(5))
+        .any(kad_finished_bootstrap(node2))
+        .await;
+    assert!(bootstrap_finished, "bootstrap should have finished");
+    assert!(is_ready(&cluster, node2, &peer_id1));
+
+    assert!(in_routing_table(&cluster, node2, &peer_id1));
+
+    assert!(cluster
+        .rust_node_mut(node2)
+        .dispatch_action(ban(peer_id1, Some(Duration::from_secs(60)))));
+    let until = cluster.rust_node(node2).state().ban.bans[&peer_id1].until;
+    assert!(until.is_some());
+
+    // a shorter ban doesn't shorten the existing one
+    assert!(cluster
+        .rust_node_mut(node2)
+        .dispatch_action(ban(peer_id1, Some(Duration::from_secs(1)))));
+    assert_eq!(
+        cluster.rust_node(node2).state().ban.bans[&peer_id1].until,
+        until
+    );
+
+    // a permanent ban outlasts any other
+    assert!(cluster
+        .rust_node_mut(node2)
+        .dispatch_action(ban(peer_id1, None)));
+    assert_eq!(
+        cluster.rust_node(node2).state().ban.bans[&peer_id1].until,
+        None
+    );
+
+    let discovery_state = cluster
+        .rust_node(node2)
+        .state()
+        .network
+        .scheduler
+        .discovery_state()
+        .expect("discovery should be enabled");
+    assert!(discovery_state.banned_peers.contains(&peer_id1));
+    assert!(!in_routing_table(&cluster, node2, &peer_id1));
+
+    run_cluster(&mut cluster, Duration::from_secs(2)).await;
+    assert!(
+        !is_ready(&cluster, node2, &peer_id1),
+        "peer should be disconnected"
+    );
+    assert!(!in_routing_table(&cluster, node2, &peer_id1));
+    assert!(persisted_bans(&cluster, node2).contains_key(&peer_id1));
+
+    Ok(())
+}
+
+/// Tests that bans are lifted when removed or expired.
+#[tokio::test]
+async fn ban_remove_and_expire() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::default()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let node = cluster.add_rust_node(RustNodeConfig::default())?;
+    let [expiring, permanent, removed] = [peer_id(1), peer_id(2), peer_id(3)];
+
+    assert!(cluster
+        .rust_node_mut(node)
+        .dispatch_action(ban(expiring, Some(Duration::from_millis(200)))));
+    assert!(cluster
+        .rust_node_mut(node)
+        .dispatch_action(ban(permanent, None)));
+    assert!(cluster
+        .rust_node_mut(node)
+        .dispatch_action(ban(removed, Some(Duration::from_secs(60)))));
+
+    assert!(
+        !cluster
+            .rust_node_mut(node)
+            .dispatch_action(P2pBanAction::PruneExpired),
+        "no ban should be expired yet"
+    );
+
+    assert!(cluster
+        .rust_node_mut(node)
+        .dispatch_action(P2pBanAction::Remove { peer_id: removed }));
+    assert!(!cluster.rust_node(node).state().ban.is_banned(&removed));
+    assert!(
+        !cluster
+            .rust_node_mut(node)
+            .dispatch_action(P2pBanAction::Remove { peer_id: removed }),
+        "peer should be unbanned only once"
+    );
+
+    // expired bans are pruned periodically
+    run_cluster(&mut cluster, Duration::from_secs(1)).await;
+
+    let bans = &cluster.rust_node(node).state().ban;
+    assert!(!bans.is_banned(&expiring));
+    assert!(bans.is_banned(&permanent));
+    assert_eq!(
+        persisted_bans(&cluster, node)
+            .into_keys()
+            .collect::<Vec<_>>(),
+        vec![permanent]
+    );
+
+    Ok(())
+}
+
+/// Tests that the persisted ban list is restored by a new node.
+#[tokio::test]
+async fn ban_list_persist_and_reload() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::default()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let node = cluster.add_rust_node(RustNodeConfig::default())?;
+    assert!(cluster
+        .rust_node_mut(node)
+        .dispatch_action(ban(peer_id(1), None)));
+    assert!(cluster
+        .rust_node_mut(node)
+        .dispatch_action(P2pBanAction::Add {
+            peer_id: peer_id(2),
+            reason: P2pBanReason::Disconnection(P2pDisconnectionReason::PingTimeout),
+            duration: Some(Duration::from_secs(60)),
+        }));
+
+    let bans = persisted_bans(&cluster, node);
+    assert_eq!(bans, cluster.rust_node(node).state().ban.bans);
+    assert_eq!(bans.len(), 2);
+
+    let reloaded = cluster.add_rust_node(
+        RustNodeConfig::default()
+            .with_discovery(true)
+            .with_bans(bans.clone()),
+    )?;
+    let state = cluster.rust_node(reloaded).state();
+    assert_eq!(state.ban.bans, bans);
+    let discovery_state = state
+        .network
+        .scheduler
+        .discovery_state()
+        .expect("discovery should be enabled");
+    assert!(bans
+        .keys()
+        .all(|peer_id| discovery_state.banned_peers.contains(peer_id)));
+
+    Ok(())
+}
+
+/// Tests that a node rejects incoming connections from banned peers.
+#[tokio::test]
+async fn banned_peer_is_rejected() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::default()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let node1 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let node2 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let peer_id1 = cluster.peer_id(node1);
+    let peer_id2 = cluster.peer_id(node2);
+
+    assert!(cluster
+        .rust_node_mut(node1)
+        .dispatch_action(ban(peer_id2, None)));
+
+    let listening =
+        wait_for_all_nodes_to_listen(&mut cluster, [node1], Duration::from_secs(2)).await;
+    assert!(listening);
+
+    cluster.connect(node2, node1)?;
+
+    let connected =
+        wait_for_nodes_to_connect(&mut cluster, [(node1, peer_id2)], Duration::from_secs(5)).await;
+    assert!(!connected, "banned peer should not connect");
+    assert!(!is_ready(&cluster, node1, &peer_id2));
+    assert!(!is_ready(&cluster, node2, &peer_id1));
+
+    Ok(())
+}
@@ -0,0 +1,213 @@
+use std::time::Duration;
+
+use p2p::{
+    bitswap_split, BitswapCid, P2pLimits, P2pNetworkBitswapAction, P2pNetworkBitswapResourceState,
+    BITSWAP_MAX_BLOCK_SIZE,
+};
+use p2p_testing::{
+    cluster::{Cluster, ClusterBuilder},
+    rust_node::{RustNodeConfig, RustNodeId},
+    utils::{run_cluster, try_wait_for_nodes_to_connect, wait_for_all_nodes_to_listen},
+};
+
+/// Data that doesn't fit into a single bitswap block.
+fn resource(seed: u8) -> Vec<u8> {
+    (0..600 * 1024)
+        .map(|i| (i % 251) as u8 ^ seed)
+        .collect::<Vec<_>>()
+}
+
+fn resource_state(
+    cluster: &Cluster,
+    node: RustNodeId,
+    root: &BitswapCid,
+) -> Option<P2pNetworkBitswapResourceState> {
+    cluster
+        .rust_node(node)
+        .state()
+        .network
+        .scheduler
+        .bitswap_state
+        .resources
+        .get(root)
+        .cloned()
+}
+
+/// Tests that a resource added by one node can be fetched by another one.
+#[tokio::test]
+async fn want_fetches_resource_from_peer() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::default()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let node1 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let node2 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let peer_id1 = cluster.peer_id(node1);
+
+    let listening =
+        wait_for_all_nodes_to_listen(&mut cluster, [node1], Duration::from_secs(2)).await;
+    assert!(listening);
+
+    cluster.connect(node2, node1)?;
+
+    let connected =
+        try_wait_for_nodes_to_connect(&mut cluster, [(node2, peer_id1)], Duration::from_secs(5))
+            .await?;
+    assert!(connected);
+
+    let data = resource(0);
+    let (root, blocks) = bitswap_split(&data, BITSWAP_MAX_BLOCK_SIZE);
+    assert!(blocks.len() > 1, "resource should span several blocks");
+
+    assert!(cluster
+        .rust_node_mut(node1)
+        .dispatch_action(P2pNetworkBitswapAction::AddResource {
+            data: data.clone().into(),
+        }));
+    assert!(matches!(
+        resource_state(&cluster, node1, &root),
+        Some(P2pNetworkBitswapResourceState::Ready { .. })
+    ));
+
+    // let identify open the bitswap streams
+    run_cluster(&mut cluster, Duration::from_secs(2)).await;
+
+    assert!(cluster
+        .rust_node_mut(node2)
+        .dispatch_action(P2pNetworkBitswapAction::Want { root }));
+
+    for _ in 0..50 {
+        if !matches!(
+            resource_state(&cluster, node2, &root),
+            Some(P2pNetworkBitswapResourceState::Pending { .. })
+        ) {
+            break;
+        }
+        run_cluster(&mut cluster, Duration::from_millis(100)).await;
+    }
+
+    let state = resource_state(&cluster, node2, &root);
+    assert!(
+        matches!(state, Some(P2pNetworkBitswapResourceState::Ready { size, .. }) if size == data.len()),
+        "resource should be fetched: {state:?}"
+    );
+    let fetched = cluster
+        .rust_node(node2)
+        .state()
+        .network
+        .scheduler
+        .bitswap_state
+        .resource_data(&root, usize::MAX);
+    assert_eq!(fetched, Some(data));
+
+    Ok(())
+}
+
+/// Tests that the oldest complete resources are removed over the limit.
+#[tokio::test]
+async fn oldest_resources_are_pruned() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::default()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let node = cluster.add_rust_node(
+        RustNodeConfig::default().with_limits(P2pLimits::default().with_bitswap_resources(2)),
+    )?;
+
+    let mut roots = vec![];
+    for seed in 0..3 {
+        let data = resource(seed);
+        let (root, _) = bitswap_split(&data, BITSWAP_MAX_BLOCK_SIZE);
+        assert!(cluster
+            .rust_node_mut(node)
+            .dispatch_action(P2pNetworkBitswapAction::AddResource { data: data.into() }));
+        roots.push(root);
+        run_cluster(&mut cluster, Duration::from_millis(200)).await;
+    }
+
+    assert!(resource_state(&cluster, node, &roots[0]).is_none());
+    assert!(resource_state(&cluster, node, &roots[1]).is_some());
+    assert!(resource_state(&cluster, node, &roots[2]).is_some());
+
+    let bitswap_state = &cluster
+        .rust_node(node)
+        .state()
+        .network
+        .scheduler
+        .bitswap_state;
+    let (_, blocks) = bitswap_split(&resource(0), BITSWAP_MAX_BLOCK_SIZE);
+    assert!(
+        blocks
+            .keys()
+            .all(|cid| !bitswap_state.blocks.contains_key(cid)),
+        "blocks of the pruned resource should be removed"
+    );
+    assert_eq!(
+        bitswap_state.resource_data(&roots[2], usize::MAX),
+        Some(resource(2))
+    );
+
+    Ok(())
+}
+
+/// Tests that a wanted resource stays pending until it is removed.
+#[tokio::test]
+async fn unknown_resource_stays_pending_until_removed() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::default()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let node = cluster.add_rust_node(
+        RustNodeConfig::default().with_limits(P2pLimits::default().with_bitswap_resources(1)),
+    )?;
+
+    let (root, _) = bitswap_split(&resource(0), BITSWAP_MAX_BLOCK_SIZE);
+    assert!(cluster
+        .rust_node_mut(node)
+        .dispatch_action(P2pNetworkBitswapAction::Want { root }));
+    assert!(
+        !cluster
+            .rust_node_mut(node)
+            .dispatch_action(P2pNetworkBitswapAction::Want { root }),
+        "resource should be wanted only once"
+    );
+
+    // complete resources over the limit don't push out pending ones
+    for seed in 1..3 {
+        assert!(cluster.rust_node_mut(node).dispatch_action(
+            P2pNetworkBitswapAction::AddResource {
+                data: resource(seed).into(),
+            }
+        ));
+        run_cluster(&mut cluster, Duration::from_millis(200)).await;
+    }
+
+    assert!(matches!(
+        resource_state(&cluster, node, &root),
+        Some(P2pNetworkBitswapResourceState::Pending { .. })
+    ));
+
+    assert!(cluster
+        .rust_node_mut(node)
+        .dispatch_action(P2pNetworkBitswapAction::RemoveResource { root }));
+    assert!(resource_state(&cluster, node, &root).is_none());
+    assert_eq!(
+        cluster
+            .rust_node(node)
+            .state()
+            .network
+            .scheduler
+            .bitswap_state
+            .missing_blocks()
+            .count(),
+        0
+    );
+
+    Ok(())
+}
@@ -2,7 +2,7 @@ use std::{future::ready, time::Duration};
 
 use p2p::{P2pNetworkConnectionState, PeerId};
 use p2p_testing::{
-    cluster::{Cluster, ClusterBuilder, ClusterEvent, NodeId},
+    cluster::{Cluster, ClusterBuilder, ClusterEvent, Listener, NodeId},
     event::{allow_disconnections, RustNodeEvent},
     futures::TryStreamExt,
     libp2p::swarm::SwarmEvent,
@@ -74,6 +74,42 @@ async fn rust_to_rust() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests that a Rust node can connect to another Rust node over QUIC.
+#[tokio::test]
+async fn rust_to_rust_quic() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::default()
+        .ports_with_len(10)
+        .total_duration(Duration::from_secs(10))
+        .start()
+        .await?;
+
+    let rust_node = cluster.add_rust_node(RustNodeConfig::default())?;
+    let rust_node1 = cluster.add_rust_node(RustNodeConfig::default().with_quic(true))?;
+    let peer_id = cluster.peer_id(rust_node1);
+
+    let listening =
+        wait_for_all_nodes_to_listen(&mut cluster, [rust_node1], Duration::from_secs(2)).await;
+    assert!(listening);
+
+    cluster.connect(rust_node, Listener::RustQuic(rust_node1))?;
+
+    let connected =
+        try_wait_for_nodes_to_connect(&mut cluster, [(rust_node, peer_id)], Duration::from_secs(5))
+            .await?;
+    assert!(connected);
+
+    assert_peer_is_ready(&cluster, rust_node, peer_id);
+    let state = cluster.rust_node(rust_node).state();
+    let (_, conn_state) = state
+        .network
+        .scheduler
+        .find_peer(&peer_id)
+        .expect("connection should exist");
+    assert!(conn_state.is_quic(), "connection should use QUIC");
+
+    Ok(())
+}
+
 /// Tests that a Rust node can connect to a libp2p client.
 #[tokio::test]
 async fn rust_to_libp2p() -> anyhow::Result<()> {
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use p2p::P2pNetworkNodeStatusAction;
+use p2p_testing::{
+    cluster::ClusterBuilder,
+    rust_node::RustNodeConfig,
+    utils::{run_cluster, try_wait_for_nodes_to_connect, wait_for_all_nodes_to_listen},
+};
+
+/// Tests that a node without a status closes the stream instead of
+/// leaving the peer waiting until the timeout.
+#[tokio::test]
+async fn unavailable_status_closes_stream() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::default()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let node1 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let node2 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let peer_id1 = cluster.peer_id(node1);
+    let peer_id2 = cluster.peer_id(node2);
+
+    let listening =
+        wait_for_all_nodes_to_listen(&mut cluster, [node1], Duration::from_secs(2)).await;
+    assert!(listening);
+
+    cluster.connect(node2, node1)?;
+
+    let connected =
+        try_wait_for_nodes_to_connect(&mut cluster, [(node2, peer_id1)], Duration::from_secs(5))
+            .await?;
+    assert!(connected);
+
+    assert!(cluster
+        .rust_node_mut(node2)
+        .dispatch_action(P2pNetworkNodeStatusAction::Query {
+            peer_id: peer_id1,
+            rpc_id: None,
+        }));
+    run_cluster(&mut cluster, Duration::from_secs(1)).await;
+
+    let stream_id = cluster
+        .rust_node(node1)
+        .state()
+        .network
+        .scheduler
+        .node_status_state
+        .incoming
+        .get(&peer_id2)
+        .and_then(|streams| streams.keys().next().copied())
+        .expect("node should wait with the response");
+
+    assert!(cluster
+        .rust_node_mut(node1)
+        .dispatch_action(P2pNetworkNodeStatusAction::Respond {
+            peer_id: peer_id2,
+            stream_id,
+            status: None,
+        }));
+    run_cluster(&mut cluster, Duration::from_secs(1)).await;
+
+    assert!(cluster
+        .rust_node(node1)
+        .state()
+        .network
+        .scheduler
+        .node_status_state
+        .incoming
+        .is_empty());
+    assert!(
+        !cluster
+            .rust_node(node2)
+            .state()
+            .network
+            .scheduler
+            .node_status_state
+            .outgoing
+            .contains_key(&peer_id1),
+        "query should be finished once the stream is closed"
+    );
+
+    Ok(())
+}
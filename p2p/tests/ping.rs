@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use openmina_core::Substate;
+use p2p::{
+    disconnection::P2pDisconnectionReason, P2pAction, P2pNetworkAction, P2pNetworkPingAction,
+    P2pNetworkPingStatus, P2pState, P2pTimeouts,
+};
+use p2p_testing::{
+    cluster::{ClusterBuilder, ClusterEvent},
+    event::{allow_disconnections, RustNodeEvent},
+    futures::TryStreamExt,
+    predicates::async_fn,
+    redux::{Action, State},
+    rust_node::RustNodeConfig,
+    stream::ClusterStreamExt,
+    utils::{run_cluster, try_wait_for_nodes_to_connect, wait_for_all_nodes_to_listen},
+};
+use redux::{ActionWithMeta, Dispatcher};
+
+/// Tests that a node measures the round trip time to a connected peer.
+#[tokio::test]
+async fn ping_updates_rtt() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::default()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .start()
+        .await?;
+
+    let node1 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let node2 = cluster.add_rust_node(RustNodeConfig::default())?;
+    let peer_id1 = cluster.peer_id(node1);
+
+    let listening =
+        wait_for_all_nodes_to_listen(&mut cluster, [node1], Duration::from_secs(2)).await;
+    assert!(listening);
+
+    cluster.connect(node2, node1)?;
+
+    let connected =
+        try_wait_for_nodes_to_connect(&mut cluster, [(node2, peer_id1)], Duration::from_secs(5))
+            .await?;
+    assert!(connected);
+
+    run_cluster(&mut cluster, Duration::from_secs(2)).await;
+
+    let state = cluster.rust_node(node2).state();
+    let peer = state.peers.get(&peer_id1).expect("peer should exist");
+    assert!(peer.rtt.is_some(), "rtt should be measured: {peer:#?}");
+    let ping = state
+        .network
+        .scheduler
+        .ping_state
+        .outgoing
+        .get(&peer_id1)
+        .expect("peer should be pinged");
+    assert!(
+        matches!(ping.status, P2pNetworkPingStatus::Received { .. }),
+        "ping should be answered: {ping:#?}"
+    );
+
+    Ok(())
+}
+
+/// Tests that a peer that doesn't answer pings gets disconnected.
+#[tokio::test]
+async fn ping_timeout_disconnects() -> anyhow::Result<()> {
+    let mut cluster = ClusterBuilder::default()
+        .ports_with_len(10)
+        .idle_duration(Duration::from_millis(100))
+        .is_error(allow_disconnections)
+        .start()
+        .await?;
+
+    let node1 =
+        cluster.add_rust_node(RustNodeConfig::default().with_override_reducer(mute_reducer))?;
+    let node2 = cluster.add_rust_node(RustNodeConfig::default().with_timeouts(P2pTimeouts {
+        ping: Some(Duration::from_secs(1)),
+        ..Default::default()
+    }))?;
+    let peer_id1 = cluster.peer_id(node1);
+
+    let listening =
+        wait_for_all_nodes_to_listen(&mut cluster, [node1], Duration::from_secs(2)).await;
+    assert!(listening);
+
+    cluster.connect(node2, node1)?;
+
+    let connected =
+        try_wait_for_nodes_to_connect(&mut cluster, [(node2, peer_id1)], Duration::from_secs(5))
+            .await?;
+    assert!(connected);
+
+    let ping_timeout = P2pDisconnectionReason::PingTimeout.to_string();
+    let disconnected = cluster
+        .try_stream()
+        .take_during(Duration::from_secs(5))
+        .try_any(async_fn(|event| {
+            matches!(
+                event,
+                ClusterEvent::Rust {
+                    id,
+                    event: RustNodeEvent::PeerDisconnected { peer_id, reason },
+                } if id == node2 && peer_id == peer_id1 && reason == ping_timeout
+            )
+        }))
+        .await?;
+    assert!(disconnected, "unresponsive peer should be disconnected");
+
+    let state = cluster.rust_node(node2).state();
+    assert!(
+        !state
+            .network
+            .scheduler
+            .ping_state
+            .outgoing
+            .contains_key(&peer_id1),
+        "ping state should be removed"
+    );
+
+    Ok(())
+}
+
+/// Reducer of a node that never echoes pings back.
+fn mute_reducer(
+    state: &mut State,
+    action: &ActionWithMeta<Action>,
+    dispatcher: &mut Dispatcher<Action, State>,
+) {
+    let meta = action.meta().clone();
+    let time = meta.time();
+    match action.action() {
+        Action::P2p(P2pAction::Network(P2pNetworkAction::Ping(
+            P2pNetworkPingAction::IncomingData { .. },
+        ))) => {}
+        Action::P2p(action) => {
+            if let Err(error) = P2pState::reducer(
+                Substate::new(state, dispatcher),
+                meta.with_action(action.clone()),
+            ) {
+                openmina_core::warn!(time; "error = {error}");
+            }
+        }
+        Action::Idle(_) | Action::P2pEffectful(_) => {}
+    }
+}
@@ -3,12 +3,32 @@ use std::{
     path::PathBuf,
 };
 
+use ledger::ondisk::Database;
 use reqwest::Url;
 
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
-struct Args {
+enum Command {
+    /// Converts mina genesis ledger from json to binprot format.
+    Genesis(GenesisArgs),
+    /// Checks the integrity of an on-disk database.
+    Verify(DatabaseArgs),
+    /// Truncates an on-disk database at its first invalid entry, and
+    /// discards its overwritten and removed entries.
+    Compact(DatabaseArgs),
+    /// Prints the entries of an on-disk database, as hex.
+    Dump {
+        #[structopt(flatten)]
+        database: DatabaseArgs,
+        /// Print the values, not only their length.
+        #[structopt(long)]
+        values: bool,
+    },
+}
+
+#[derive(StructOpt)]
+struct GenesisArgs {
     #[structopt(short, long)]
     input: Option<PathBuf>,
     #[structopt(long)]
@@ -17,9 +37,25 @@ struct Args {
     output: PathBuf,
 }
 
+#[derive(StructOpt)]
+struct DatabaseArgs {
+    /// Directory of the database.
+    directory: PathBuf,
+}
+
 fn main() -> anyhow::Result<()> {
-    let Args { input, url, output } = Args::from_args();
+    match Command::from_args() {
+        Command::Genesis(args) => genesis(args),
+        Command::Verify(DatabaseArgs { directory }) => verify(directory),
+        Command::Compact(DatabaseArgs { directory }) => compact(directory),
+        Command::Dump {
+            database: DatabaseArgs { directory },
+            values,
+        } => dump(directory, values),
+    }
+}
 
+fn genesis(GenesisArgs { input, url, output }: GenesisArgs) -> anyhow::Result<()> {
     let data = if let Some(input) = input {
         fs::read(input)?
     } else if let Some(url) = url {
@@ -37,3 +73,70 @@ fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+fn verify(directory: PathBuf) -> anyhow::Result<()> {
+    let report = Database::verify(&directory)?;
+
+    println!("file size:  {}", report.file_size);
+    println!("entries:    {}", report.entries);
+    println!("keys:       {}", report.keys);
+    println!("live bytes: {}", report.live_bytes);
+    println!("dead bytes: {}", report.dead_bytes());
+
+    match report.corruption {
+        None => println!("ok"),
+        Some((offset, error)) if report.torn_tail => anyhow::bail!(
+            "invalid entry at offset {offset}: {error}, `compact` truncates the {} bytes from there",
+            report.file_size - offset
+        ),
+        Some((offset, error)) => anyhow::bail!(
+            "invalid entry at offset {offset}: {error}, the database can't be opened"
+        ),
+    }
+
+    Ok(())
+}
+
+fn compact(directory: PathBuf) -> anyhow::Result<()> {
+    // Fails when there is no database, instead of creating one
+    let report = Database::verify(&directory)?;
+    if let Some((offset, error)) = report.corruption {
+        println!("truncating at offset {offset}: {error}");
+    }
+
+    let mut db = Database::create(&directory)?;
+    let size_before = db.file_size();
+    db.gc()?;
+
+    println!("file size: {size_before} -> {}", db.file_size());
+
+    Ok(())
+}
+
+fn dump(directory: PathBuf, values: bool) -> anyhow::Result<()> {
+    let report = Database::verify(&directory)?;
+    if let Some((offset, error)) = report.corruption {
+        anyhow::bail!("invalid entry at offset {offset}: {error}, run `compact` first");
+    }
+
+    let mut db = Database::create(&directory)?;
+    let mut keys = db.keys().map(Box::<[u8]>::from).collect::<Vec<_>>();
+    keys.sort();
+
+    for key in keys {
+        let Some(value) = db.get(&key)? else {
+            continue;
+        };
+        if values {
+            println!("{}\t{}", to_hex(&key), to_hex(&value));
+        } else {
+            println!("{}\t{}", to_hex(&key), value.len());
+        }
+    }
+
+    Ok(())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}